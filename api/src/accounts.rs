@@ -5,11 +5,12 @@ use crate::{
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
-    param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
+    param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, Param},
 };
 
 use diem_api_types::{
-    AccountData, Address, Error, LedgerInfo, MoveModuleBytecode, Response, TransactionId,
+    AccountData, AccountResourcesWithProof, Address, Error, LedgerInfo, MoveModuleBytecode,
+    Response, TransactionId,
 };
 use diem_types::{
     account_config::AccountResource,
@@ -22,7 +23,8 @@ use move_core_types::{
     identifier::Identifier, language_storage::StructTag, move_resource::MoveStructType,
     value::MoveValue,
 };
-use std::convert::TryInto;
+use serde::Deserialize;
+use std::convert::{TryFrom, TryInto};
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
 // GET /accounts/<address>
@@ -35,24 +37,26 @@ pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
-// GET /accounts/<address>/resources
+// GET /accounts/<address>/resources?with_proof={bool}
 pub fn get_account_resources(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "resources")
         .and(warp::get())
+        .and(warp::query::<ResourcesQuery>())
         .and(context.filter())
-        .map(|address, ctx| (None, address, ctx))
+        .map(|address, query, ctx| (None, address, query, ctx))
         .untuple_one()
         .and_then(handle_get_account_resources)
         .with(metrics("get_account_resources"))
         .boxed()
 }
 
-// GET /ledger/<version>/accounts/<address>/resources
+// GET /ledger/<version>/accounts/<address>/resources?with_proof={bool}
 pub fn get_account_resources_by_ledger_version(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("ledger" / LedgerVersionParam / "accounts" / AddressParam / "resources")
         .and(warp::get())
+        .and(warp::query::<ResourcesQuery>())
         .and(context.filter())
-        .map(|version, address, ctx| (Some(version), address, ctx))
+        .map(|version, address, query, ctx| (Some(version), address, query, ctx))
         .untuple_one()
         .and_then(handle_get_account_resources)
         .with(metrics("get_account_resources_by_ledger_version"))
@@ -94,10 +98,15 @@ async fn handle_get_account(
 async fn handle_get_account_resources(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    query: ResourcesQuery,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_resources")?;
-    Ok(Account::new(ledger_version, address, context)?.resources()?)
+    let with_proof = query
+        .with_proof
+        .map(|v| v.parse("with_proof"))
+        .unwrap_or(Ok(false))?;
+    Ok(Account::new(ledger_version, address, context)?.resources(with_proof)?)
 }
 
 async fn handle_get_account_modules(
@@ -109,6 +118,11 @@ async fn handle_get_account_modules(
     Ok(Account::new(ledger_version, address, context)?.modules()?)
 }
 
+#[derive(Clone, Debug, Deserialize)]
+struct ResourcesQuery {
+    with_proof: Option<Param<bool>>,
+}
+
 pub(crate) struct Account {
     ledger_version: u64,
     address: Address,
@@ -152,7 +166,10 @@ impl Account {
         Response::new(self.latest_ledger_info, &account)
     }
 
-    pub fn resources(self) -> Result<impl Reply, Error> {
+    pub fn resources(self, with_proof: bool) -> Result<impl Reply, Error> {
+        if with_proof {
+            return self.resources_with_proof();
+        }
         let resources = self
             .context
             .move_converter()
@@ -160,6 +177,39 @@ impl Account {
         Response::new(self.latest_ledger_info, &resources)
     }
 
+    /// Returns the resources with the `AccountStateWithProof` they are decoded from. The proof is
+    /// built against the latest ledger info, which is also returned for the client to verify.
+    fn resources_with_proof(self) -> Result<Response, Error> {
+        let ledger_info_with_sigs = self.context.get_latest_ledger_info_with_signatures()?;
+        let state_with_proof = self.context.get_account_state_with_proof(
+            self.address.into(),
+            self.ledger_version,
+            ledger_info_with_sigs.ledger_info().version(),
+        )?;
+        let blob = state_with_proof
+            .blob
+            .as_ref()
+            .ok_or_else(|| self.account_not_found())?;
+        let resources = self
+            .context
+            .move_converter()
+            .try_into_resources(AccountState::try_from(blob)?.get_resources())?;
+
+        let body = AccountResourcesWithProof {
+            resources,
+            ledger_info_with_signatures: bcs::to_bytes(&ledger_info_with_sigs)
+                .map_err(anyhow::Error::from)?
+                .into(),
+            account_state_with_proof: bcs::to_bytes(&state_with_proof)
+                .map_err(anyhow::Error::from)?
+                .into(),
+        };
+        Response::new(
+            LedgerInfo::new(&self.context.chain_id(), &ledger_info_with_sigs),
+            &body,
+        )
+    }
+
     pub fn modules(self) -> Result<impl Reply, Error> {
         let modules = self
             .account_state()?
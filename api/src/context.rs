@@ -4,18 +4,25 @@
 use diem_api_types::{Error, LedgerInfo, MoveConverter, TransactionOnChainData};
 use diem_config::config::{ApiConfig, JsonRpcConfig, RoleType};
 use diem_crypto::HashValue;
-use diem_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use diem_mempool::{
+    AccountPendingTransactions, MempoolClientRequest, MempoolClientSender, MempoolStats,
+    PendingTransaction, SubmissionStatus,
+};
+use diem_state_view::StateView;
 use diem_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
     account_state::AccountState,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{default_protocol::AccountStateWithProof, AccountStateBlob},
     chain_id::ChainId,
     contract_event::ContractEvent,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     protocol_spec::DpnProto,
-    transaction::{SignedTransaction, TransactionInfo},
+    transaction::{SignedTransaction, TransactionInfo, TransactionOutput},
+    vm_status::VMStatus,
 };
+use diem_vm::DiemVM;
 use storage_interface::{MoveDbReader, Order};
 
 use anyhow::{ensure, format_err, Result};
@@ -24,6 +31,7 @@ use std::{
     borrow::Borrow,
     convert::{Infallible, TryFrom},
     sync::Arc,
+    time::Duration,
 };
 use warp::{filters::BoxedFilter, Filter, Reply};
 
@@ -69,6 +77,10 @@ impl Context {
         self.api_config.content_length_limit()
     }
 
+    pub fn stream_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.api_config.stream_poll_interval_ms())
+    }
+
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
         warp::any().map(move || self.clone())
     }
@@ -117,6 +129,29 @@ impl Context {
         Ok(account_state_blob)
     }
 
+    /// Executes the transaction against the state at `version` without committing it.
+    pub fn simulate_transaction(
+        &self,
+        txn: &SignedTransaction,
+        version: u64,
+    ) -> (VMStatus, TransactionOutput) {
+        let state_view = DbStateView {
+            db: self.db.borrow(),
+            version,
+        };
+        DiemVM::simulate_signed_transaction(txn, &state_view)
+    }
+
+    pub fn get_account_state_with_proof(
+        &self,
+        address: AccountAddress,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<AccountStateWithProof> {
+        self.db
+            .get_account_state_with_proof(address, version, ledger_version)
+    }
+
     pub fn get_block_timestamp(&self, version: u64) -> Result<u64> {
         self.db.get_block_timestamp(version)
     }
@@ -204,6 +239,50 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    /// Returns the transaction pending in mempool by hash, along with its mempool state.
+    pub async fn get_mempool_transaction(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<PendingTransaction>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetPendingTransactionByHash(
+                hash, req_sender,
+            ))
+            .await?;
+
+        Ok(callback.await?)
+    }
+
+    pub async fn get_mempool_account_transactions(
+        &self,
+        address: AccountAddress,
+    ) -> Result<AccountPendingTransactions> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetAccountTransactions(
+                address, req_sender,
+            ))
+            .await?;
+
+        callback.await?
+    }
+
+    pub async fn get_mempool_stats(&self) -> Result<MempoolStats> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetStats(req_sender))
+            .await?;
+
+        Ok(callback.await?)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -232,6 +311,39 @@ impl Context {
             .collect::<Vec<_>>())
     }
 
+    /// Returns the sequence number of the latest event under `event_key` as of `ledger_version`,
+    /// or `None` if there is no event.
+    pub fn get_latest_event_sequence_number(
+        &self,
+        event_key: &EventKey,
+        ledger_version: u64,
+    ) -> Result<Option<u64>> {
+        let events = self.db.get_events_with_proofs(
+            event_key,
+            u64::MAX,
+            Order::Descending,
+            1,
+            Some(ledger_version),
+        )?;
+        Ok(events.first().map(|e| e.event.sequence_number()))
+    }
+
+    /// Returns the number of transactions sent by the account as of `ledger_version`, i.e. the
+    /// sequence number of its next transaction.
+    pub fn get_account_sequence_number(
+        &self,
+        address: AccountAddress,
+        ledger_version: u64,
+    ) -> Result<u64> {
+        Ok(match self.get_account_state(address, ledger_version)? {
+            Some(state) => state
+                .get_account_resource()?
+                .map(|r| r.sequence_number())
+                .unwrap_or(0),
+            None => 0,
+        })
+    }
+
     pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
         diem_json_rpc::runtime::health_check_route(self.db.clone())
     }
@@ -246,3 +358,27 @@ impl Context {
         )
     }
 }
+
+/// `DbStateView` is a `StateView` of the committed state at `version`.
+struct DbStateView<'a> {
+    db: &'a dyn MoveDbReader<DpnProto>,
+    version: u64,
+}
+
+impl<'a> StateView for DbStateView<'a> {
+    fn get(&self, access_path: &AccessPath) -> Result<Option<Vec<u8>>> {
+        let (blob, _) = self
+            .db
+            .get_account_state_with_proof_by_version(access_path.address, self.version)?;
+        Ok(match blob {
+            Some(blob) => AccountState::try_from(&blob)?
+                .get(&access_path.path)
+                .cloned(),
+            None => None,
+        })
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
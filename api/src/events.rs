@@ -5,12 +5,13 @@ use crate::{
     accounts::Account,
     context::Context,
     failpoint::fail_point,
+    filter::{EventFilter, MAX_FILTER_SCAN_SIZE},
     metrics::metrics,
-    page::Page,
+    page::{scan, Order, Page},
     param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam},
 };
 
-use diem_api_types::{Error, LedgerInfo, Response};
+use diem_api_types::{Error, Event, LedgerInfo, Response};
 
 use anyhow::Result;
 use diem_types::event::EventKey;
@@ -21,6 +22,7 @@ pub fn get_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("events" / EventKeyParam)
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(warp::query::<EventFilter>())
         .and(context.filter())
         .and_then(handle_get_events_by_event_key)
         .with(metrics("get_events_by_event_key"))
@@ -32,6 +34,7 @@ pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)
     warp::path!("accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam)
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(warp::query::<EventFilter>())
         .and(context.filter())
         .and_then(handle_get_events_by_event_handle)
         .with(metrics("get_events_by_event_handle"))
@@ -41,10 +44,11 @@ pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)
 async fn handle_get_events_by_event_key(
     event_key: EventKeyParam,
     page: Page,
+    filter: EventFilter,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_key")?;
-    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page)?)
+    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page, filter)?)
 }
 
 async fn handle_get_events_by_event_handle(
@@ -52,12 +56,13 @@ async fn handle_get_events_by_event_handle(
     struct_tag: MoveStructTagParam,
     field_name: MoveIdentifierParam,
     page: Page,
+    filter: EventFilter,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_handle")?;
     let key =
         Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
-    Ok(Events::new(key, context)?.list(page)?)
+    Ok(Events::new(key, context)?.list(page, filter)?)
 }
 
 struct Events {
@@ -76,16 +81,49 @@ impl Events {
         })
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
-        let contract_events = self.context.get_events(
-            &self.key,
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
+    pub fn list(self, page: Page, filter: EventFilter) -> Result<impl Reply, Error> {
+        let ledger_version = self.ledger_info.version();
+        let limit = page.limit()?;
+        let order = page.order()?;
+        let matcher = filter.parse()?;
+        let last_sequence_number = match self
+            .context
+            .get_latest_event_sequence_number(&self.key, ledger_version)?
+        {
+            Some(sequence_number) => sequence_number,
+            None => {
+                let events: Vec<Event> = vec![];
+                return Response::new(self.ledger_info, &events);
+            }
+        };
+        let default_start = match order {
+            Order::Asc => 0,
+            Order::Desc => last_sequence_number,
+        };
+        let start = page.start_position(default_start, u64::MAX)?;
+        let max_scan = if matcher.is_empty() {
+            limit as u64
+        } else {
+            MAX_FILTER_SCAN_SIZE
+        };
+
+        let (contract_events, cursor) = scan(
+            start,
+            last_sequence_number,
+            order,
+            limit,
+            max_scan,
+            |first, count| {
+                Ok(self
+                    .context
+                    .get_events(&self.key, first, count, ledger_version)?)
+            },
+            |event| event.sequence_number(),
+            |event| matcher.matches(event),
         )?;
 
         let converter = self.context.move_converter();
         let events = converter.try_into_events(&contract_events)?;
-        Response::new(self.ledger_info, &events)
+        Ok(Response::new(self.ledger_info, &events)?.with_cursor(cursor.map(|c| c.to_string())))
     }
 }
@@ -0,0 +1,204 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::param::{AddressParam, EventKeyParam, Param};
+
+use diem_api_types::{Error, MoveType, ScriptFunctionId, TransactionOnChainData};
+use diem_types::{
+    account_address::AccountAddress,
+    contract_event::ContractEvent,
+    event::EventKey,
+    transaction::{Transaction, TransactionInfo, TransactionInfoTrait, TransactionPayload},
+    vm_status::KeptVMStatus,
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// The maximum number of items scanned to fill a page when filtering.
+pub(crate) const MAX_FILTER_SCAN_SIZE: u64 = 10_000;
+
+/// Query parameters filtering transactions:
+/// * `success`: only (un)successfully executed transactions.
+/// * `script_function`: only user transactions calling the script function, e.g.
+///   `0x1::PaymentScripts::peer_to_peer_with_metadata`.
+/// * `event_type`: only transactions emitting an event of the type, e.g.
+///   `0x1::DiemAccount::SentPaymentEvent`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct TransactionFilter {
+    success: Option<Param<bool>>,
+    script_function: Option<Param<ScriptFunctionId>>,
+    event_type: Option<Param<MoveType>>,
+}
+
+impl TransactionFilter {
+    pub fn parse(self) -> Result<TransactionMatcher, Error> {
+        Ok(TransactionMatcher {
+            success: self.success.map(|v| v.parse("success")).transpose()?,
+            script_function: self
+                .script_function
+                .map(|v| {
+                    v.parse("script_function")
+                        .map(|id| (ModuleId::from(id.module), id.name))
+                })
+                .transpose()?,
+            event_type: parse_event_type(self.event_type)?,
+        })
+    }
+}
+
+pub(crate) struct TransactionMatcher {
+    success: Option<bool>,
+    script_function: Option<(ModuleId, Identifier)>,
+    event_type: Option<TypeTag>,
+}
+
+impl TransactionMatcher {
+    pub fn is_empty(&self) -> bool {
+        self.success.is_none() && self.script_function.is_none() && self.event_type.is_none()
+    }
+
+    pub fn matches(&self, txn: &TransactionOnChainData<TransactionInfo>) -> bool {
+        if let Some(success) = self.success {
+            if (txn.info.status() == &KeptVMStatus::Executed) != success {
+                return false;
+            }
+        }
+        if let Some((module, function)) = &self.script_function {
+            let matched = match &txn.transaction {
+                Transaction::UserTransaction(signed_txn) => match signed_txn.payload() {
+                    TransactionPayload::ScriptFunction(script_function) => {
+                        script_function.module() == module
+                            && script_function.function() == function.as_ident_str()
+                    }
+                    _ => false,
+                },
+                _ => false,
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(event_type) = &self.event_type {
+            if !txn.events.iter().any(|e| e.type_tag() == event_type) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Query parameters filtering events:
+/// * `event_type`: only events of the type, e.g. `0x1::DiemAccount::SentPaymentEvent`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct EventFilter {
+    event_type: Option<Param<MoveType>>,
+}
+
+impl EventFilter {
+    pub fn parse(self) -> Result<EventMatcher, Error> {
+        Ok(EventMatcher {
+            event_type: parse_event_type(self.event_type)?,
+        })
+    }
+}
+
+pub(crate) struct EventMatcher {
+    event_type: Option<TypeTag>,
+}
+
+impl EventMatcher {
+    pub fn is_empty(&self) -> bool {
+        self.event_type.is_none()
+    }
+
+    pub fn matches(&self, event: &ContractEvent) -> bool {
+        match &self.event_type {
+            Some(event_type) => event.type_tag() == event_type,
+            None => true,
+        }
+    }
+}
+
+/// Query parameters filtering streamed transactions and events:
+/// * `address`: only user transactions sent by the account.
+/// * `event_key`: only events with the key.
+/// * `event_type`: only events of the type, e.g. `0x1::DiemAccount::SentPaymentEvent`.
+///
+/// Without any parameter, all transactions are streamed. With only event parameters, transactions
+/// are not streamed, only their matching events.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct StreamFilter {
+    address: Option<AddressParam>,
+    event_key: Option<EventKeyParam>,
+    event_type: Option<Param<MoveType>>,
+}
+
+impl StreamFilter {
+    pub fn parse(self) -> Result<StreamMatcher, Error> {
+        Ok(StreamMatcher {
+            address: self
+                .address
+                .map(|v| v.parse("address").map(|a| a.into()))
+                .transpose()?,
+            event_key: self
+                .event_key
+                .map(|v| v.parse("event_key").map(|k| k.into()))
+                .transpose()?,
+            event_type: parse_event_type(self.event_type)?,
+        })
+    }
+}
+
+pub(crate) struct StreamMatcher {
+    address: Option<AccountAddress>,
+    event_key: Option<EventKey>,
+    event_type: Option<TypeTag>,
+}
+
+impl StreamMatcher {
+    fn has_event_filter(&self) -> bool {
+        self.event_key.is_some() || self.event_type.is_some()
+    }
+
+    pub fn matches_transaction(&self, txn: &TransactionOnChainData<TransactionInfo>) -> bool {
+        match &self.address {
+            Some(address) => match &txn.transaction {
+                Transaction::UserTransaction(signed_txn) => &signed_txn.sender() == address,
+                _ => false,
+            },
+            None => !self.has_event_filter(),
+        }
+    }
+
+    pub fn matches_event(&self, event: &ContractEvent) -> bool {
+        if !self.has_event_filter() {
+            return false;
+        }
+        if let Some(key) = &self.event_key {
+            if event.key() != key {
+                return false;
+            }
+        }
+        if let Some(event_type) = &self.event_type {
+            if event.type_tag() != event_type {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn parse_event_type(param: Option<Param<MoveType>>) -> Result<Option<TypeTag>, Error> {
+    param
+        .map(|v| {
+            let move_type = v.parse("event_type")?;
+            TypeTag::try_from(move_type.clone())
+                .map_err(|_| Error::invalid_param("event_type", move_type))
+        })
+        .transpose()
+}
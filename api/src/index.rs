@@ -6,9 +6,9 @@ use crate::{
     context::Context,
     events,
     failpoint::fail_point,
-    log,
+    log, mempool,
     metrics::{metrics, status_metrics},
-    transactions,
+    stream, tables, transactions,
 };
 use diem_api_types::{Error, Response};
 
@@ -42,9 +42,17 @@ pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Inf
         .or(transactions::get_account_transactions(context.clone()))
         .or(transactions::submit_bcs_transactions(context.clone()))
         .or(transactions::submit_json_transactions(context.clone()))
+        .or(transactions::simulate_bcs_transactions(context.clone()))
+        .or(transactions::simulate_json_transactions(context.clone()))
         .or(transactions::create_signing_message(context.clone()))
         .or(events::get_events_by_event_key(context.clone()))
         .or(events::get_events_by_event_handle(context.clone()))
+        .or(tables::get_table_item(context.clone()))
+        .or(tables::get_table_item_by_ledger_version(context.clone()))
+        .or(mempool::get_account_transactions(context.clone()))
+        .or(mempool::get_transaction(context.clone()))
+        .or(mempool::get_stats(context.clone()))
+        .or(stream::stream(context.clone()))
         .or(context.health_check_route().with(metrics("health_check")))
         // jsonrpc routes must before `recover` and after `index`
         // so that POST '/' can be handled by jsonrpc routes instead of `index` route
@@ -4,12 +4,16 @@
 mod accounts;
 mod context;
 mod events;
+mod filter;
 mod index;
 pub(crate) mod log;
+mod mempool;
 mod metrics;
 mod page;
 pub(crate) mod param;
 pub mod runtime;
+mod stream;
+mod tables;
 mod transactions;
 
 mod failpoint;
@@ -0,0 +1,160 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{AddressParam, HashValueParam},
+};
+
+use diem_api_types::{
+    Address, Error, HashValue, LedgerInfo, MempoolAccountTransactions, MempoolStats,
+    MempoolTransaction, MempoolTransactionState, PendingTransaction, Response,
+};
+
+use anyhow::Result;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+// GET /mempool/accounts/{address}/transactions
+pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("mempool" / "accounts" / AddressParam / "transactions")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_get_account_transactions)
+        .with(metrics("get_mempool_account_transactions"))
+        .boxed()
+}
+
+// GET /mempool/transactions/{txn-hash}
+pub fn get_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("mempool" / "transactions" / HashValueParam)
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_get_transaction)
+        .with(metrics("get_mempool_transaction"))
+        .boxed()
+}
+
+// GET /mempool/stats
+pub fn get_stats(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("mempool" / "stats")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_get_stats)
+        .with(metrics("get_mempool_stats"))
+        .boxed()
+}
+
+async fn handle_get_account_transactions(
+    address: AddressParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_mempool_account_transactions")?;
+    Ok(Mempool::new(context)?
+        .account_transactions(address.parse("account address")?)
+        .await?)
+}
+
+async fn handle_get_transaction(
+    hash: HashValueParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_mempool_transaction")?;
+    Ok(Mempool::new(context)?
+        .transaction(hash.parse("transaction hash")?)
+        .await?)
+}
+
+async fn handle_get_stats(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_mempool_stats")?;
+    Ok(Mempool::new(context)?.stats().await?)
+}
+
+struct Mempool {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Mempool {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    async fn account_transactions(self, address: Address) -> Result<impl Reply, Error> {
+        let account_txns = self
+            .context
+            .get_mempool_account_transactions(address.into())
+            .await?;
+        let missing_sequence_number = account_txns.missing_sequence_number();
+        let transactions = account_txns
+            .transactions
+            .into_iter()
+            .map(|txn| self.render(txn))
+            .collect::<Result<Vec<_>>>()?;
+
+        Response::new(
+            self.ledger_info,
+            &MempoolAccountTransactions {
+                address,
+                sequence_number: account_txns.sequence_number.into(),
+                missing_sequence_number: missing_sequence_number.map(|n| n.into()),
+                transactions,
+            },
+        )
+    }
+
+    async fn transaction(self, hash: HashValue) -> Result<impl Reply, Error> {
+        let txn = self
+            .context
+            .get_mempool_transaction(hash.into())
+            .await?
+            .ok_or_else(|| {
+                Error::not_found("mempool transaction", hash, self.ledger_info.version())
+            })?;
+        let txn = self.render(txn)?;
+
+        Response::new(self.ledger_info, &txn)
+    }
+
+    async fn stats(self) -> Result<impl Reply, Error> {
+        let stats = self.context.get_mempool_stats().await?;
+
+        Response::new(
+            self.ledger_info,
+            &MempoolStats {
+                size: (stats.size as u64).into(),
+                ready: (stats.ready as u64).into(),
+                parked: (stats.parked as u64).into(),
+                accounts: (stats.accounts as u64).into(),
+                capacity: (stats.capacity as u64).into(),
+                capacity_per_user: (stats.capacity_per_user as u64).into(),
+            },
+        )
+    }
+
+    fn render(&self, pending_txn: diem_mempool::PendingTransaction) -> Result<MempoolTransaction> {
+        let txn = pending_txn.txn;
+        let payload = self
+            .context
+            .move_converter()
+            .try_into_transaction_payload(txn.payload().clone())?;
+        let state = match pending_txn.state {
+            diem_mempool::MempoolTransactionState::Ready => MempoolTransactionState::Ready,
+            diem_mempool::MempoolTransactionState::Parked => MempoolTransactionState::Parked,
+        };
+        Ok(MempoolTransaction {
+            state,
+            ranking_score: pending_txn.ranking_score.into(),
+            system_expiration_timestamp_secs: pending_txn.expiration_time.as_secs().into(),
+            transaction: PendingTransaction {
+                request: (&txn, payload).into(),
+                hash: txn.committed_hash().into(),
+            },
+        })
+    }
+}
@@ -5,17 +5,19 @@ use crate::param::{Param, TransactionVersionParam};
 
 use diem_api_types::{Error, TransactionId};
 
-use anyhow::Result;
-use serde::Deserialize;
-use std::num::NonZeroU16;
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+use std::{cmp::min, fmt, num::NonZeroU16, str::FromStr};
 
 const DEFAULT_PAGE_SIZE: u16 = 25;
-const MAX_PAGE_SIZE: u16 = 1000;
+pub(crate) const MAX_PAGE_SIZE: u16 = 1000;
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Page {
     start: Option<TransactionVersionParam>,
     limit: Option<Param<NonZeroU16>>,
+    order: Option<Param<Order>>,
+    cursor: Option<Param<Cursor>>,
 }
 
 impl Page {
@@ -25,14 +27,7 @@ impl Page {
             .clone()
             .map(|v| v.parse("start"))
             .unwrap_or_else(|| Ok(default))?;
-        if version > max {
-            return Err(Error::not_found(
-                "transaction",
-                TransactionId::Version(version),
-                max,
-            ));
-        }
-        Ok(version)
+        check_max_version(version, max)
     }
 
     pub fn limit(&self) -> Result<u16, Error> {
@@ -50,4 +45,257 @@ impl Page {
         }
         Ok(limit)
     }
+
+    /// The order of the page. A cursor carries the order of the page it was issued for, so the
+    /// `order` parameter can be omitted when a cursor is given, but must not contradict it.
+    pub fn order(&self) -> Result<Order, Error> {
+        let order = self.order.clone().map(|v| v.parse("order")).transpose()?;
+        match (self.cursor()?, order) {
+            (Some(cursor), Some(order)) if cursor.order != order => Err(Error::invalid_param(
+                "order",
+                format!("{}, does not match the order of the cursor", order),
+            )),
+            (Some(cursor), _) => Ok(cursor.order),
+            (None, order) => Ok(order.unwrap_or(Order::Asc)),
+        }
+    }
+
+    pub fn cursor(&self) -> Result<Option<Cursor>, Error> {
+        self.cursor.clone().map(|v| v.parse("cursor")).transpose()
+    }
+
+    /// Where the page starts: the position carried by the cursor if given, otherwise the `start`
+    /// parameter, see `Page::start`. The cursor position is checked against `max` the same way.
+    pub fn start_position(&self, default: u64, max: u64) -> Result<u64, Error> {
+        match (self.cursor()?, &self.start) {
+            (Some(_), Some(_)) => Err(Error::invalid_param(
+                "cursor",
+                "can not be used together with start",
+            )),
+            (Some(cursor), None) => check_max_version(cursor.position, max),
+            (None, _) => self.start(default, max),
+        }
+    }
+}
+
+fn check_max_version(version: u64, max: u64) -> Result<u64, Error> {
+    if version > max {
+        return Err(Error::not_found(
+            "transaction",
+            TransactionId::Version(version),
+            max,
+        ));
+    }
+    Ok(version)
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Order {
+    Asc,
+    Desc,
+}
+
+impl FromStr for Order {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "asc" => Ok(Order::Asc),
+            "desc" => Ok(Order::Desc),
+            _ => Err(format_err!("invalid order: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Order::Asc => write!(f, "asc"),
+            Order::Desc => write!(f, "desc"),
+        }
+    }
+}
+
+/// `Cursor` is an opaque continuation token, returned in the `X-Diem-Cursor` response header and
+/// passed back by the client as the `cursor` query parameter to fetch the next page.
+///
+/// The position is a transaction version or a sequence number depending on the endpoint.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Cursor {
+    order: Order,
+    position: u64,
+}
+
+impl Cursor {
+    pub fn new(order: Order, position: u64) -> Self {
+        Self { order, position }
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = bcs::to_bytes(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", hex::encode(bytes))
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(bcs::from_bytes(&hex::decode(s)?)?)
+    }
+}
+
+/// Scans items starting from position `start` in `order`, until `limit` items passing `filter` are
+/// collected, `max_scan` items are scanned or there is no more item. Positions are bounded by
+/// `end` (inclusive): ascending scans stop after it and descending scans start at most from it.
+///
+/// `fetch(first, count)` returns the items at positions `[first, first + count)` in ascending order,
+/// it can return less items when there is no more to fetch. `position` returns the position of an
+/// item.
+///
+/// Returns the collected items in `order`, and the cursor to continue from if there can be more.
+pub(crate) fn scan<T>(
+    start: u64,
+    end: u64,
+    order: Order,
+    limit: u16,
+    max_scan: u64,
+    mut fetch: impl FnMut(u64, u16) -> Result<Vec<T>, Error>,
+    position: impl Fn(&T) -> u64,
+    filter: impl Fn(&T) -> bool,
+) -> Result<(Vec<T>, Option<Cursor>), Error> {
+    let mut items = vec![];
+    let mut scanned = 0;
+    let mut next = match order {
+        Order::Asc if start > end => None,
+        Order::Asc => Some(start),
+        Order::Desc => Some(min(start, end)),
+    };
+
+    while let Some(cursor) = next {
+        if items.len() >= limit as usize || scanned >= max_scan {
+            break;
+        }
+        let batch_size = min(MAX_PAGE_SIZE as u64, max_scan - scanned);
+        let (first, count) = match order {
+            Order::Asc => (cursor, min(batch_size, (end - cursor).saturating_add(1))),
+            Order::Desc => {
+                let first = cursor.saturating_sub(batch_size - 1);
+                (first, cursor - first + 1)
+            }
+        };
+
+        let mut batch = fetch(first, count as u16)?;
+        let exhausted = (batch.len() as u64) < count;
+        if order == Order::Desc {
+            batch.reverse();
+        }
+
+        next = match order {
+            Order::Asc if exhausted => None,
+            Order::Asc => first.checked_add(count).filter(|p| *p <= end),
+            Order::Desc => first.checked_sub(1),
+        };
+        for item in batch {
+            scanned += 1;
+            let item_position = position(&item);
+            if filter(&item) {
+                items.push(item);
+                if items.len() >= limit as usize {
+                    // Continue right after the last returned item.
+                    next = match order {
+                        Order::Asc => item_position.checked_add(1).filter(|p| *p <= end),
+                        Order::Desc => item_position.checked_sub(1),
+                    };
+                    break;
+                }
+            }
+        }
+    }
+
+    Ok((items, next.map(|position| Cursor::new(order, position))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scan, Cursor, Order};
+
+    fn scan_range(
+        start: u64,
+        order: Order,
+        limit: u16,
+        filter: impl Fn(&u64) -> bool,
+    ) -> (Vec<u64>, Option<Cursor>) {
+        // Items are the positions themselves, 0 to 99.
+        scan(
+            start,
+            u64::MAX,
+            order,
+            limit,
+            1000,
+            |first, count| Ok((first..first + count as u64).filter(|p| *p < 100).collect()),
+            |p| *p,
+            filter,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor::new(Order::Desc, 12345);
+        assert_eq!(cursor, cursor.to_string().parse().unwrap());
+        assert!("hello".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn test_scan_ascending() {
+        let (items, cursor) = scan_range(10, Order::Asc, 3, |_| true);
+        assert_eq!(items, vec![10, 11, 12]);
+        assert_eq!(cursor, Some(Cursor::new(Order::Asc, 13)));
+
+        let (items, cursor) = scan_range(98, Order::Asc, 3, |_| true);
+        assert_eq!(items, vec![98, 99]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_scan_descending() {
+        let (items, cursor) = scan_range(10, Order::Desc, 3, |_| true);
+        assert_eq!(items, vec![10, 9, 8]);
+        assert_eq!(cursor, Some(Cursor::new(Order::Desc, 7)));
+
+        let (items, cursor) = scan_range(1, Order::Desc, 3, |_| true);
+        assert_eq!(items, vec![1, 0]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_scan_with_filter() {
+        let (items, cursor) = scan_range(0, Order::Asc, 3, |p| p % 10 == 0);
+        assert_eq!(items, vec![0, 10, 20]);
+        assert_eq!(cursor, Some(Cursor::new(Order::Asc, 21)));
+
+        let (items, cursor) = scan_range(99, Order::Desc, 3, |p| p % 40 == 0);
+        assert_eq!(items, vec![80, 40, 0]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_scan_stops_at_max_scan() {
+        let (items, cursor) = scan(
+            0,
+            u64::MAX,
+            Order::Asc,
+            10,
+            5,
+            |first, count| Ok((first..first + count as u64).collect()),
+            |p| *p,
+            |p| p % 3 == 0,
+        )
+        .unwrap();
+        assert_eq!(items, vec![0, 3]);
+        assert_eq!(cursor, Some(Cursor::new(Order::Asc, 5)));
+    }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use diem_api_types::{Address, Error, EventKey, MoveStructTag, TransactionId};
+use diem_api_types::{Address, Error, EventKey, HashValue, MoveStructTag, TransactionId};
 use move_core_types::identifier::Identifier;
 use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Deserializer};
@@ -15,6 +15,7 @@ pub type LedgerVersionParam = Param<u64>;
 pub type EventKeyParam = Param<EventKey>;
 pub type MoveStructTagParam = Param<MoveStructTag>;
 pub type MoveIdentifierParam = Param<Identifier>;
+pub type HashValueParam = Param<HashValue>;
 
 /// `Param` is designed for parsing `warp` path parameter or query string
 /// into a type specified by the generic type parameter of `Param`.
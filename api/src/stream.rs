@@ -0,0 +1,171 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    filter::{StreamFilter, StreamMatcher},
+    metrics::metrics,
+    param::TransactionVersionParam,
+};
+
+use anyhow::Result;
+use futures::{stream, Stream};
+use serde::Deserialize;
+use std::{collections::VecDeque, convert::Infallible};
+use warp::{filters::BoxedFilter, sse, Filter, Rejection, Reply};
+
+const FETCH_SIZE: u16 = 100;
+
+// GET /stream?start={version}&address={address}&event_key={event_key}&event_type={event_type}
+pub fn stream(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("stream")
+        .and(warp::get())
+        .and(warp::query::<StreamQuery>())
+        .and(warp::query::<StreamFilter>())
+        .and(sse::last_event_id::<u64>())
+        .and(context.filter())
+        .and_then(handle_stream)
+        .with(metrics("stream"))
+        .boxed()
+}
+
+async fn handle_stream(
+    query: StreamQuery,
+    filter: StreamFilter,
+    last_event_id: Option<u64>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream")?;
+    let matcher = filter.parse()?;
+    // A reconnecting client resumes right after the last version it has received.
+    let start = match last_event_id {
+        Some(version) => version.saturating_add(1),
+        None => match query.start {
+            Some(start) => start.parse("start")?,
+            None => context
+                .get_latest_ledger_info()?
+                .version()
+                .saturating_add(1),
+        },
+    };
+    let events = Streamer::new(context, matcher, start).into_stream();
+    Ok(sse::reply(sse::keep_alive().stream(events)))
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct StreamQuery {
+    start: Option<TransactionVersionParam>,
+}
+
+/// `Streamer` polls committed transactions from `next_version` on, and turns the matching
+/// transactions and events into server-sent events:
+/// * `transaction`: a matching transaction.
+/// * `events`: the matching events emitted by a transaction.
+/// * `error`: the stream failed and is closed.
+///
+/// The id of the last message sent for a transaction is its version, so that a client
+/// reconnecting with the `Last-Event-ID` header does not miss any message.
+pub(crate) struct Streamer {
+    context: Context,
+    matcher: StreamMatcher,
+    next_version: u64,
+    pending: VecDeque<sse::Event>,
+    closed: bool,
+}
+
+impl Streamer {
+    pub fn new(context: Context, matcher: StreamMatcher, next_version: u64) -> Self {
+        Self {
+            context,
+            matcher,
+            next_version,
+            pending: VecDeque::new(),
+            closed: false,
+        }
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = Result<sse::Event, Infallible>> + Send {
+        stream::unfold(self, |mut streamer| async move {
+            loop {
+                if let Some(event) = streamer.pending.pop_front() {
+                    return Some((Ok(event), streamer));
+                }
+                if streamer.closed {
+                    return None;
+                }
+                // The DB calls of `fetch` block, so it runs on the blocking thread pool.
+                let fetched = match tokio::task::spawn_blocking(move || {
+                    let fetched = streamer.fetch();
+                    (streamer, fetched)
+                })
+                .await
+                {
+                    Ok((fetching_streamer, fetched)) => {
+                        streamer = fetching_streamer;
+                        fetched
+                    }
+                    // `fetch` panicked and took the streamer with it.
+                    Err(_) => return None,
+                };
+                match fetched {
+                    Ok(true) => continue,
+                    Ok(false) => tokio::time::sleep(streamer.context.stream_poll_interval()).await,
+                    Err(e) => {
+                        streamer.closed = true;
+                        let event = sse::Event::default().event("error").data(e.to_string());
+                        return Some((Ok(event), streamer));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Fetches the next batch of committed transactions, returns false if there is none yet.
+    /// Blocks on DB reads.
+    fn fetch(&mut self) -> Result<bool> {
+        let ledger_version = self
+            .context
+            .get_latest_ledger_info_with_signatures()?
+            .ledger_info()
+            .version();
+        if self.next_version > ledger_version {
+            return Ok(false);
+        }
+        let limit = std::cmp::min(FETCH_SIZE as u64, ledger_version - self.next_version + 1);
+        let txns =
+            self.context
+                .get_transactions(self.next_version, limit as u16, ledger_version)?;
+        if txns.is_empty() {
+            return Ok(false);
+        }
+
+        let converter = self.context.move_converter();
+        for txn in txns {
+            let version = txn.version;
+            let events: Vec<_> = txn
+                .events
+                .iter()
+                .filter(|e| self.matcher.matches_event(e))
+                .cloned()
+                .collect();
+
+            let mut messages = vec![];
+            if self.matcher.matches_transaction(&txn) {
+                let timestamp = self.context.get_block_timestamp(version)?;
+                let txn = converter.try_into_onchain_transaction(timestamp, txn)?;
+                messages.push(sse::Event::default().event("transaction").json_data(&txn)?);
+            }
+            if !events.is_empty() {
+                let events = converter.try_into_events(&events)?;
+                messages.push(sse::Event::default().event("events").json_data(&events)?);
+            }
+            if let Some(last) = messages.pop() {
+                messages.push(last.id(version.to_string()));
+            }
+            self.pending.extend(messages);
+            self.next_version = version + 1;
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{AddressParam, LedgerVersionParam, Param},
+};
+
+use diem_api_types::{
+    Address, Error, LedgerInfo, Response, TableItemRequest, TableItemWithProof, TransactionId,
+};
+use diem_types::{access_path::AccessPath, account_state::AccountState};
+
+use anyhow::Result;
+use move_core_types::language_storage::TableHandle;
+use serde::Deserialize;
+use std::convert::TryFrom;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+// POST /tables/<handle>/item?with_proof={bool}
+pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("tables" / AddressParam / "item")
+        .and(warp::post())
+        .and(warp::query::<TableItemQuery>())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<TableItemRequest>())
+        .and(context.filter())
+        .map(|handle, query, body, ctx| (None, handle, query, body, ctx))
+        .untuple_one()
+        .and_then(handle_get_table_item)
+        .with(metrics("get_table_item"))
+        .boxed()
+}
+
+// POST /ledger/<version>/tables/<handle>/item?with_proof={bool}
+pub fn get_table_item_by_ledger_version(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("ledger" / LedgerVersionParam / "tables" / AddressParam / "item")
+        .and(warp::post())
+        .and(warp::query::<TableItemQuery>())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<TableItemRequest>())
+        .and(context.filter())
+        .map(|version, handle, query, body, ctx| (Some(version), handle, query, body, ctx))
+        .untuple_one()
+        .and_then(handle_get_table_item)
+        .with(metrics("get_table_item_by_ledger_version"))
+        .boxed()
+}
+
+async fn handle_get_table_item(
+    ledger_version: Option<LedgerVersionParam>,
+    handle: AddressParam,
+    query: TableItemQuery,
+    body: TableItemRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_table_item")?;
+    let with_proof = query
+        .with_proof
+        .map(|v| v.parse("with_proof"))
+        .unwrap_or(Ok(false))?;
+    Ok(Table::new(ledger_version, handle, context)?.item(body, with_proof)?)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct TableItemQuery {
+    with_proof: Option<Param<bool>>,
+}
+
+struct Table {
+    ledger_version: u64,
+    handle: Address,
+    latest_ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Table {
+    fn new(
+        ledger_version: Option<LedgerVersionParam>,
+        handle: AddressParam,
+        context: Context,
+    ) -> Result<Self, Error> {
+        let latest_ledger_info = context.get_latest_ledger_info()?;
+        let ledger_version = ledger_version
+            .map(|v| v.parse("ledger version"))
+            .unwrap_or_else(|| Ok(latest_ledger_info.version()))?;
+
+        if ledger_version > latest_ledger_info.version() {
+            return Err(Error::not_found(
+                "ledger",
+                TransactionId::Version(ledger_version),
+                latest_ledger_info.version(),
+            ));
+        }
+
+        Ok(Self {
+            ledger_version,
+            handle: handle.parse("table handle")?,
+            latest_ledger_info,
+            context,
+        })
+    }
+
+    fn item(self, req: TableItemRequest, with_proof: bool) -> Result<Response, Error> {
+        let converter = self.context.move_converter();
+        let key = converter
+            .try_into_table_key(&req.key_type, req.key.clone())
+            .map_err(|e| Error::invalid_request_body(format!("invalid table key: {}", e)))?;
+        let access_path = AccessPath::table_item_access_path(TableHandle(self.handle.into()), key);
+
+        if with_proof {
+            return self.item_with_proof(&req, &access_path);
+        }
+
+        let bytes = self
+            .context
+            .get_account_state(access_path.address, self.ledger_version)?
+            .and_then(|state| state.get(&access_path.path).cloned())
+            .ok_or_else(|| self.item_not_found(&req))?;
+
+        let value = converter.try_into_table_value(&req.value_type, &bytes)?;
+        Response::new(self.latest_ledger_info, &value)
+    }
+
+    /// Returns the item with the `AccountStateWithProof` of the address it is stored under. The
+    /// proof is built against the latest ledger info, which is also returned for the client to
+    /// verify.
+    fn item_with_proof(
+        self,
+        req: &TableItemRequest,
+        access_path: &AccessPath,
+    ) -> Result<Response, Error> {
+        let ledger_info_with_sigs = self.context.get_latest_ledger_info_with_signatures()?;
+        let state_with_proof = self.context.get_account_state_with_proof(
+            access_path.address,
+            self.ledger_version,
+            ledger_info_with_sigs.ledger_info().version(),
+        )?;
+        let bytes = match state_with_proof.blob.as_ref() {
+            Some(blob) => AccountState::try_from(blob)?
+                .get(&access_path.path)
+                .cloned()
+                .ok_or_else(|| self.item_not_found(req))?,
+            None => return Err(self.item_not_found(req)),
+        };
+        let value = self
+            .context
+            .move_converter()
+            .try_into_table_value(&req.value_type, &bytes)?;
+
+        let body = TableItemWithProof {
+            value: value.json()?,
+            ledger_info_with_signatures: bcs::to_bytes(&ledger_info_with_sigs)
+                .map_err(anyhow::Error::from)?
+                .into(),
+            account_state_with_proof: bcs::to_bytes(&state_with_proof)
+                .map_err(anyhow::Error::from)?
+                .into(),
+        };
+        Response::new(
+            LedgerInfo::new(&self.context.chain_id(), &ledger_info_with_sigs),
+            &body,
+        )
+    }
+
+    fn item_not_found(&self, req: &TableItemRequest) -> Error {
+        Error::not_found(
+            "table item",
+            format!(
+                "table handle({}), key({}) and ledger version({})",
+                self.handle, req.key, self.ledger_version,
+            ),
+            self.latest_ledger_info.version(),
+        )
+    }
+}
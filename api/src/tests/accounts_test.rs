@@ -3,6 +3,10 @@
 
 use crate::tests::{assert_json, find_value, new_test_context};
 use diem_api_types::HexEncodedBytes;
+use diem_types::{
+    account_state_blob::default_protocol::AccountStateWithProof,
+    ledger_info::LedgerInfoWithSignatures,
+};
 use serde_json::json;
 
 #[tokio::test]
@@ -499,6 +503,52 @@ async fn test_get_account_resources_by_ledger_version() {
     assert_eq!(tc_account["data"]["sequence_number"], "0");
 }
 
+#[tokio::test]
+async fn test_get_account_resources_by_ledger_version_with_proof() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let tc_address = context.tc_account().address();
+    let resp = context
+        .get(&format!(
+            "{}?with_proof=true",
+            account_resources_with_ledger_version(&tc_address.to_hex_literal(), 0)
+        ))
+        .await;
+    let tc_account = find_value(&resp["resources"], |f| {
+        f["type"] == "0x1::DiemAccount::DiemAccount"
+    });
+    assert_eq!(tc_account["data"]["sequence_number"], "0");
+
+    let ledger_info: LedgerInfoWithSignatures = bcs::from_bytes(
+        resp["ledger_info_with_signatures"]
+            .as_str()
+            .unwrap()
+            .parse::<HexEncodedBytes>()
+            .unwrap()
+            .inner(),
+    )
+    .unwrap();
+    assert_eq!(
+        ledger_info.ledger_info().version(),
+        context.get_latest_ledger_info().version()
+    );
+    let state_with_proof: AccountStateWithProof = bcs::from_bytes(
+        resp["account_state_with_proof"]
+            .as_str()
+            .unwrap()
+            .parse::<HexEncodedBytes>()
+            .unwrap()
+            .inner(),
+    )
+    .unwrap();
+    state_with_proof
+        .verify(ledger_info.ledger_info(), 0, tc_address)
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_get_account_resources_by_ledger_version_is_too_large() {
     let context = new_test_context();
@@ -64,6 +64,39 @@ async fn test_get_events_filter_by_limit_page_size() {
     assert_eq!(resp.as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_events_in_descending_order() {
+    let context = new_test_context();
+
+    let asc = context
+        .get("/events/0x00000000000000000000000000000000000000000a550c18?limit=1000")
+        .await;
+    let desc = context
+        .get("/events/0x00000000000000000000000000000000000000000a550c18?order=desc&limit=1")
+        .await;
+
+    assert_eq!(desc.as_array().unwrap().len(), 1);
+    assert_json(
+        desc[0].clone(),
+        asc.as_array().unwrap().last().unwrap().clone(),
+    );
+}
+
+#[tokio::test]
+async fn test_get_events_filter_by_event_type() {
+    let context = new_test_context();
+
+    let resp = context
+        .get("/events/0x00000000000000000000000000000000000000000a550c18?event_type=0x1::DiemAccount::CreateAccountEvent&limit=1")
+        .await;
+    assert_eq!(resp[0]["type"], "0x1::DiemAccount::CreateAccountEvent");
+
+    let resp = context
+        .get("/events/0x00000000000000000000000000000000000000000a550c18?event_type=0x1::DiemAccount::SentPaymentEvent")
+        .await;
+    assert_json(resp, json!([]));
+}
+
 #[tokio::test]
 async fn test_get_events_by_invalid_key() {
     let context = new_test_context();
@@ -0,0 +1,134 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{assert_json, new_test_context};
+
+use serde_json::json;
+
+#[tokio::test]
+async fn test_get_mempool_account_transactions() {
+    let mut context = new_test_context();
+    let mut tc = context.tc_account();
+    let (first, second) = (context.gen_account(), context.gen_account());
+    let ready_txn = context.create_parent_vasp_by_account(&mut tc, &first);
+    // skip sequence number 1, so that the next transaction is parked
+    *tc.sequence_number_mut() += 1;
+    let parked_txn = context.create_parent_vasp_by_account(&mut tc, &second);
+
+    let mut hashes = vec![];
+    for txn in &[ready_txn, parked_txn] {
+        let pending_txn = context
+            .expect_status_code(202)
+            .post_bcs_txn("/transactions", bcs::to_bytes(txn).unwrap())
+            .await;
+        hashes.push(pending_txn["hash"].clone());
+    }
+
+    let resp = context
+        .get(&format!(
+            "/mempool/accounts/{}/transactions",
+            tc.address().to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["address"], json!(tc.address().to_hex_literal()));
+    assert_eq!(resp["sequence_number"], json!("0"));
+    assert_eq!(resp["missing_sequence_number"], json!("1"));
+    let txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(txns.len(), 2);
+    assert_eq!(txns[0]["state"], json!("ready"));
+    assert_eq!(txns[0]["hash"], hashes[0]);
+    assert_eq!(txns[0]["sequence_number"], json!("0"));
+    assert_eq!(txns[1]["state"], json!("parked"));
+    assert_eq!(txns[1]["hash"], hashes[1]);
+    assert_eq!(txns[1]["sequence_number"], json!("2"));
+}
+
+#[tokio::test]
+async fn test_get_mempool_account_transactions_without_pending_transactions() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+
+    let resp = context
+        .get(&format!(
+            "/mempool/accounts/{}/transactions",
+            account.address().to_hex_literal()
+        ))
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "address": account.address().to_hex_literal(),
+            "sequence_number": "0",
+            "transactions": [],
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_mempool_transaction() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    let txn_hash = pending_txn["hash"].as_str().unwrap();
+
+    let resp = context
+        .get(&format!("/mempool/transactions/{}", txn_hash))
+        .await;
+    assert_eq!(resp["state"], json!("ready"));
+    assert_eq!(resp["hash"], pending_txn["hash"]);
+    assert_eq!(resp["sender"], pending_txn["sender"]);
+    assert!(resp["ranking_score"].is_string());
+
+    let not_found = context
+        .expect_status_code(404)
+        .get("/mempool/transactions/0xdadfeddcca7cb6396c735e9094c76c6e4e9cb3e3ef814730693aed59bd87b31d")
+        .await;
+    assert_json(
+        not_found,
+        json!({
+            "code": 404,
+            "message": "mempool transaction not found by 0xdadfeddcca7cb6396c735e9094c76c6e4e9cb3e3ef814730693aed59bd87b31d",
+            "diem_ledger_version": "0"
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_mempool_transaction_with_invalid_hash() {
+    let context = new_test_context();
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/mempool/transactions/0xinvalid")
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": "invalid parameter transaction hash: 0xinvalid"
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_mempool_stats() {
+    let mut context = new_test_context();
+    let mut tc = context.tc_account();
+    *tc.sequence_number_mut() += 1;
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp_by_account(&mut tc, &account);
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+
+    let resp = context.get("/mempool/stats").await;
+    assert_eq!(resp["size"], json!("1"));
+    assert_eq!(resp["ready"], json!("0"));
+    assert_eq!(resp["parked"], json!("1"));
+    assert_eq!(resp["accounts"], json!("1"));
+}
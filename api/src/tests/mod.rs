@@ -5,7 +5,10 @@ mod accounts_test;
 mod events_test;
 mod index_test;
 mod invalid_post_request_test;
+mod mempool_test;
+mod stream_test;
 mod string_resource_test;
+mod tables_test;
 mod test_context;
 mod transactions_test;
 
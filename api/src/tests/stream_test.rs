@@ -0,0 +1,165 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{new_test_context, TestContext};
+
+use futures::StreamExt;
+use hyper::Body;
+use serde_json::Value;
+use std::time::Duration;
+use warp::Reply;
+
+#[tokio::test]
+async fn test_stream_transactions_from_start() {
+    let context = new_context_with_committed_txn().await;
+
+    let messages = read_messages(stream(&context, "/stream?start=0", None).await, 3).await;
+    assert_eq!(
+        messages
+            .iter()
+            .map(|m| (m.event.as_str(), m.id.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("transaction", Some("0")),
+            ("transaction", Some("1")),
+            ("transaction", Some("2")),
+        ]
+    );
+    assert_eq!(messages[0].data["type"], "genesis_transaction");
+    assert_eq!(messages[1].data["type"], "block_metadata_transaction");
+    assert_eq!(messages[2].data["type"], "user_transaction");
+    assert_eq!(messages[2].data["version"], "2");
+}
+
+#[tokio::test]
+async fn test_stream_filter_by_address() {
+    let context = new_context_with_committed_txn().await;
+    let address = context.tc_account().address();
+
+    let path = format!("/stream?start=0&address={}", address.to_hex_literal());
+    let messages = read_messages(stream(&context, &path, None).await, 1).await;
+    assert_eq!(messages[0].event, "transaction");
+    assert_eq!(messages[0].id.as_deref(), Some("2"));
+    assert_eq!(messages[0].data["sender"], address.to_hex_literal());
+}
+
+#[tokio::test]
+async fn test_stream_filter_by_event_type() {
+    let context = new_context_with_committed_txn().await;
+
+    let messages = read_messages(
+        stream(
+            &context,
+            "/stream?start=1&event_type=0x1::DiemAccount::CreateAccountEvent",
+            None,
+        )
+        .await,
+        1,
+    )
+    .await;
+    assert_eq!(messages[0].event, "events");
+    assert_eq!(messages[0].id.as_deref(), Some("2"));
+    let events = messages[0].data.as_array().unwrap();
+    assert!(!events.is_empty());
+    for event in events {
+        assert_eq!(event["type"], "0x1::DiemAccount::CreateAccountEvent");
+    }
+}
+
+#[tokio::test]
+async fn test_stream_resumes_after_last_event_id() {
+    let context = new_context_with_committed_txn().await;
+
+    // `start` is ignored when resuming.
+    let messages = read_messages(stream(&context, "/stream?start=0", Some(1)).await, 1).await;
+    assert_eq!(messages[0].id.as_deref(), Some("2"));
+}
+
+#[tokio::test]
+async fn test_stream_new_transactions_by_default() {
+    let mut context = new_test_context();
+    let body = stream(&context, "/stream", None).await;
+
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&[txn]).await;
+
+    let messages = read_messages(body, 2).await;
+    assert_eq!(messages[0].id.as_deref(), Some("1"));
+    assert_eq!(messages[1].id.as_deref(), Some("2"));
+}
+
+#[tokio::test]
+async fn test_stream_with_invalid_start() {
+    let context = new_test_context();
+    let resp = context
+        .expect_status_code(400)
+        .get("/stream?start=hello")
+        .await;
+    assert_eq!(resp["message"], "invalid parameter start: hello");
+}
+
+async fn new_context_with_committed_txn() -> TestContext {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&[txn]).await;
+    context
+}
+
+async fn stream(context: &TestContext, path: &str, last_event_id: Option<u64>) -> Body {
+    let mut req = warp::test::request().method("GET").path(path);
+    if let Some(id) = last_event_id {
+        req = req.header("last-event-id", id.to_string());
+    }
+    let reply = req
+        .filter(&crate::stream::stream(context.context.clone()))
+        .await
+        .unwrap();
+    let resp = reply.into_response();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "text/event-stream");
+    resp.into_body()
+}
+
+struct Message {
+    event: String,
+    data: Value,
+    id: Option<String>,
+}
+
+async fn read_messages(mut body: Body, count: usize) -> Vec<Message> {
+    let mut buf = String::new();
+    let mut messages = vec![];
+    while messages.len() < count {
+        let chunk = tokio::time::timeout(Duration::from_secs(10), body.next())
+            .await
+            .expect("timed out waiting for server-sent events")
+            .expect("stream is closed")
+            .unwrap();
+        buf.push_str(std::str::from_utf8(&chunk).unwrap());
+        while let Some(end) = buf.find("\n\n") {
+            let raw: String = buf.drain(..end + 2).collect();
+            let mut message = Message {
+                event: String::new(),
+                data: Value::Null,
+                id: None,
+            };
+            for line in raw.lines() {
+                if let Some(event) = line.strip_prefix("event:") {
+                    message.event = event.to_owned();
+                } else if let Some(data) = line.strip_prefix("data:") {
+                    message.data = serde_json::from_str(data).unwrap();
+                } else if let Some(id) = line.strip_prefix("id:") {
+                    message.id = Some(id.to_owned());
+                }
+            }
+            // Skip keep-alive comments.
+            if !message.event.is_empty() {
+                messages.push(message);
+            }
+        }
+    }
+    messages.truncate(count);
+    messages
+}
@@ -0,0 +1,174 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{assert_json, new_test_context};
+use diem_api_types::{Address, HexEncodedBytes};
+use diem_crypto::ed25519::Ed25519PrivateKey;
+use diem_sdk::types::LocalAccount;
+use diem_types::{
+    account_state_blob::AccountStateWithProof, ledger_info::LedgerInfoWithSignatures,
+};
+use serde_json::json;
+use std::convert::TryInto;
+
+#[tokio::test]
+async fn test_get_table_item() {
+    let context = new_test_context();
+    let mut account = init_test_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&vec![txn]).await;
+
+    // module 0x87342d91af60c3a883a2812c9294c2f8::TableHolder {
+    //     use Std::Table::{Self, Table};
+    //     struct Holder has key {
+    //         items: Table<u64, u64>,
+    //     }
+    //     public(script) fun add(account: signer, key: u64, val: u64) {
+    //         let items = Table::new();
+    //         Table::add(&mut items, key, val);
+    //         move_to(&account, Holder { items });
+    //     }
+    // }
+    let module_code = "0xa11ceb0b040000000901000402040c031013042304052723074a270871200a9101090c9a011b000001010002080001010402030104010003000100010501040203040103050102030401030203030c030300010b01020303020303010b01020900090103070b010209000901090009010b5461626c65486f6c646572055461626c6506486f6c64657203616464056974656d73036e657787342d91af60c3a883a2812c9294c2f800000000000000000000000000000001000201040b0102030300020000020b38000c030d030a010a0238010e000b0312002d000200";
+    context
+        .api_publish_module(&mut account, module_code.parse().unwrap())
+        .await;
+    context
+        .api_execute_script_function(
+            &mut account,
+            "TableHolder::add",
+            json!([]),
+            json!(["7", "42"]),
+        )
+        .await;
+
+    let holder = context
+        .api_get_account_resource(
+            &account,
+            format!(
+                "{}::TableHolder::Holder",
+                account.address().to_hex_literal()
+            ),
+        )
+        .await;
+    let handle = holder["data"]["items"]["handle"].as_str().unwrap();
+    assert_eq!(holder["data"]["items"]["length"], "1");
+
+    let resp = context
+        .post(&format!("/tables/{}/item", handle), u64_item_request("7"))
+        .await;
+    assert_json(resp, json!("42"));
+
+    let version = context.get_latest_ledger_info().version();
+    let resp = context
+        .post(
+            &format!("/ledger/{}/tables/{}/item?with_proof=true", version, handle),
+            u64_item_request("7"),
+        )
+        .await;
+    assert_eq!(resp["value"], json!("42"));
+    let ledger_info_with_sigs: HexEncodedBytes = resp["ledger_info_with_signatures"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let ledger_info_with_sigs: LedgerInfoWithSignatures =
+        bcs::from_bytes(ledger_info_with_sigs.inner()).unwrap();
+    let state_with_proof: HexEncodedBytes = resp["account_state_with_proof"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let state_with_proof: AccountStateWithProof =
+        bcs::from_bytes(state_with_proof.inner()).unwrap();
+    assert_eq!(state_with_proof.version, version);
+    assert!(state_with_proof.blob.is_some());
+    assert_eq!(
+        ledger_info_with_sigs.ledger_info().version(),
+        context.get_latest_ledger_info().version()
+    );
+
+    // other keys of the table have no items
+    let resp = context
+        .expect_status_code(404)
+        .post(&format!("/tables/{}/item", handle), u64_item_request("8"))
+        .await;
+    assert_eq!(resp["code"], 404);
+}
+
+#[tokio::test]
+async fn test_get_table_item_by_ledger_version_is_too_large() {
+    let context = new_test_context();
+    let resp = context
+        .expect_status_code(404)
+        .post(
+            "/ledger/1000000000000000000/tables/0x1/item",
+            u64_item_request("7"),
+        )
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 404,
+            "message": "ledger not found by version(1000000000000000000)",
+            "diem_ledger_version": "0"
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_table_item_by_ledger_version_not_found() {
+    let context = new_test_context();
+    for path in &[
+        "/ledger/0/tables/0x1/item",
+        "/ledger/0/tables/0x1/item?with_proof=true",
+    ] {
+        let resp = context
+            .expect_status_code(404)
+            .post(path, u64_item_request("7"))
+            .await;
+        assert_json(
+            resp,
+            json!({
+                "code": 404,
+                "message": "table item not found by table handle(0x1), key(\"7\") and ledger version(0)",
+                "diem_ledger_version": "0"
+            }),
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_get_table_item_with_invalid_proof_param() {
+    let context = new_test_context();
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/ledger/0/tables/0x1/item?with_proof=maybe",
+            u64_item_request("7"),
+        )
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": "invalid parameter with_proof: maybe"
+        }),
+    );
+}
+
+fn u64_item_request(key: &str) -> serde_json::Value {
+    json!({
+        "key_type": "u64",
+        "value_type": "u64",
+        "key": key,
+    })
+}
+
+fn init_test_account() -> LocalAccount {
+    let key_bytes =
+        hex::decode("a38ba78b1a0fbfc55e2c5dfdedf48d1172283d0f7c59fd64c02d811130a2f4b2").unwrap();
+    let private_key: Ed25519PrivateKey = (&key_bytes[..]).try_into().unwrap();
+    let address: Address = "87342d91af60c3a883a2812c9294c2f8".parse().unwrap();
+    LocalAccount::new(address.into(), private_key, 0)
+}
@@ -31,7 +31,7 @@ use diem_types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     on_chain_config::VMPublishingOption,
     protocol_spec::DpnProto,
-    transaction::{Transaction, TransactionInfo, TransactionStatus},
+    transaction::{RawTransaction, Transaction, TransactionInfo, TransactionStatus},
 };
 use diem_vm::DiemVM;
 use diemdb::DiemDB;
@@ -181,8 +181,17 @@ impl TestContext {
             .sender(tc_account.address())
             .sequence_number(tc_account.sequence_number())
             .build();
+        self.sign_with_invalid_key(txn, &tc_account)
+    }
+
+    /// Signs the transaction with a random key, attached with the public key of the `signer`.
+    pub fn sign_with_invalid_key(
+        &mut self,
+        txn: RawTransaction,
+        signer: &LocalAccount,
+    ) -> SignedTransaction {
         let invalid_key = AccountKey::generate(self.rng());
-        txn.sign(invalid_key.private_key(), tc_account.public_key().clone())
+        txn.sign(invalid_key.private_key(), signer.public_key().clone())
             .unwrap()
             .into_inner()
     }
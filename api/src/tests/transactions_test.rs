@@ -1,9 +1,12 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::tests::{assert_json, find_value, new_test_context, pretty, TestContext};
+use crate::{
+    page::{Cursor, Order},
+    tests::{assert_json, find_value, new_test_context, pretty, TestContext},
+};
 
-use diem_api_types::HexEncodedBytes;
+use diem_api_types::{HexEncodedBytes, X_DIEM_CURSOR};
 use diem_crypto::{
     hash::CryptoHash,
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
@@ -255,6 +258,148 @@ async fn test_get_transactions_param_limit_exceeds_limit() {
     );
 }
 
+#[tokio::test]
+async fn test_get_transactions_in_descending_order() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let asc = context.get("/transactions?start=0&limit=3").await;
+    let mut desc = context.get("/transactions?order=desc&limit=3").await;
+    desc.as_array_mut().unwrap().reverse();
+    assert_json(desc, asc);
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_cursor() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions?order=desc&limit=2"),
+        )
+        .await;
+    let cursor = resp.headers()[X_DIEM_CURSOR].to_str().unwrap().to_owned();
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(versions(&txns), vec!["2", "1"]);
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&format!("/transactions?cursor={}&limit=2", cursor)),
+        )
+        .await;
+    assert!(resp.headers().get(X_DIEM_CURSOR).is_none());
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(versions(&txns), vec!["0"]);
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_invalid_order_param() {
+    let context = new_test_context();
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?order=hello")
+        .await;
+    assert_json(
+        resp,
+        json!({
+          "code": 400,
+          "message": "invalid parameter order: hello"
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_invalid_cursor_param() {
+    let context = new_test_context();
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?cursor=hello")
+        .await;
+    assert_json(
+        resp,
+        json!({
+          "code": 400,
+          "message": "invalid parameter cursor: hello"
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_cursor_and_start_param() {
+    let context = new_test_context();
+    let cursor = Cursor::new(Order::Asc, 1);
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!("/transactions?cursor={}&start=0", cursor))
+        .await;
+    assert_json(
+        resp,
+        json!({
+          "code": 400,
+          "message": "invalid parameter cursor: can not be used together with start"
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_cursor_position_is_too_large() {
+    let context = new_test_context();
+    let ledger_version = context.get_latest_ledger_info().version();
+    let cursor = Cursor::new(Order::Asc, 1000000);
+    let resp = context
+        .expect_status_code(404)
+        .get(&format!("/transactions?cursor={}", cursor))
+        .await;
+    assert_json(
+        resp,
+        json!({
+          "code": 404,
+          "message": "transaction not found by version(1000000)",
+          "diem_ledger_version": ledger_version.to_string()
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_filter_by_script_function() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let txns = context
+        .get(
+            "/transactions?script_function=0x1::AccountCreationScripts::create_parent_vasp_account",
+        )
+        .await;
+    assert_eq!(versions(&txns), vec!["2"]);
+}
+
+#[tokio::test]
+async fn test_get_transactions_filter_by_success_and_event_type() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let txns = context.get("/transactions?success=false").await;
+    assert_json(txns, json!([]));
+
+    let txns = context
+        .get("/transactions?success=true&event_type=0x1::DiemAccount::CreateAccountEvent")
+        .await;
+    assert_eq!(versions(&txns), vec!["0", "2"]);
+}
+
 #[tokio::test]
 async fn test_get_transactions_output_user_transaction_with_script_function_payload() {
     let mut context = new_test_context();
@@ -613,6 +758,97 @@ async fn test_post_invalid_signature_transaction() {
     );
 }
 
+#[tokio::test]
+async fn test_simulate_transaction() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let tc_account = context.tc_account();
+    let txn = context
+        .transaction_factory()
+        .create_parent_vasp_account(Currency::XUS, 0, account.authentication_key(), "vasp", true)
+        .sender(tc_account.address())
+        .sequence_number(tc_account.sequence_number())
+        .build();
+    let txn = context.sign_with_invalid_key(txn, &tc_account);
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["success"], true);
+    assert_eq!(resp["vm_status"], "Executed successfully");
+    assert_eq!(
+        resp["events"][0]["type"],
+        "0x1::DiemAccount::CreateAccountEvent"
+    );
+    assert!(!resp["changes"].as_array().unwrap().is_empty());
+
+    // the simulated transaction is not committed
+    context
+        .expect_status_code(404)
+        .get(&format!("/accounts/{}", account.address()))
+        .await;
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_explains_failure() {
+    let mut context = new_test_context();
+    let txn = context.create_invalid_signature_transaction();
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["success"], false);
+    assert!(resp["vm_status"]
+        .as_str()
+        .unwrap()
+        .starts_with("Move abort"));
+}
+
+#[tokio::test]
+async fn test_simulate_discarded_transaction() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let tc_account = context.tc_account();
+    let txn = context
+        .transaction_factory()
+        .create_parent_vasp_account(Currency::XUS, 0, account.authentication_key(), "vasp", true)
+        .sender(tc_account.address())
+        .sequence_number(tc_account.sequence_number() + 100)
+        .build();
+    let txn = context.sign_with_invalid_key(txn, &tc_account);
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_json(
+        resp,
+        json!({
+          "gas_used": "0",
+          "success": false,
+          "vm_status": "Transaction discarded: SEQUENCE_NUMBER_TOO_NEW",
+          "events": [],
+          "changes": []
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_with_valid_signature() {
+    let mut context = new_test_context();
+    let account = context.gen_account();
+    let txn = context.create_parent_vasp(&account);
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_json(
+        resp,
+        json!({
+          "code": 400,
+          "message": "invalid request body: simulated transaction must not have a valid signature"
+        }),
+    );
+}
+
 #[tokio::test]
 async fn test_post_transaction_rejected_by_mempool() {
     let mut context = new_test_context();
@@ -1180,6 +1416,37 @@ async fn test_get_account_transactions_filter_transactions_by_limit() {
     assert_eq!(txns.as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_account_transactions_in_descending_order() {
+    let mut context = new_test_context();
+    let mut tc_account = context.tc_account();
+    let account1 = context.gen_account();
+    let txn1 = context.create_parent_vasp_by_account(&mut tc_account, &account1);
+    let account2 = context.gen_account();
+    let txn2 = context.create_parent_vasp_by_account(&mut tc_account, &account2);
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let txns = context
+        .get(
+            format!(
+                "/accounts/{}/transactions?order=desc&limit=1",
+                context.tc_account().address()
+            )
+            .as_str(),
+        )
+        .await;
+    assert_eq!(txns.as_array().unwrap().len(), 1);
+    assert_eq!(txns[0]["sequence_number"], "1");
+}
+
+fn versions(txns: &serde_json::Value) -> Vec<&str> {
+    txns.as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["version"].as_str().unwrap())
+        .collect()
+}
+
 const MISC_ERROR: &str = "Move bytecode deserialization / verification failed, including script function not found or invalid arguments";
 
 #[tokio::test]
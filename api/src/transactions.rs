@@ -4,8 +4,9 @@
 use crate::{
     context::Context,
     failpoint::fail_point,
+    filter::{TransactionFilter, MAX_FILTER_SCAN_SIZE},
     metrics::metrics,
-    page::Page,
+    page::{scan, Cursor, Order, Page},
     param::{AddressParam, TransactionIdParam},
 };
 
@@ -15,7 +16,7 @@ use diem_api_types::{
 };
 use diem_types::{
     mempool_status::MempoolStatusCode,
-    transaction::{RawTransaction, SignedTransaction, TransactionInfo},
+    transaction::{RawTransaction, SignedTransaction, TransactionInfo, TransactionStatus},
 };
 
 use anyhow::Result;
@@ -35,22 +36,26 @@ pub fn get_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
-// GET /transactions?start={u64}&limit={u16}
+// GET /transactions?start={u64}&limit={u16}&order={asc|desc}&cursor={string}
+//     &success={bool}&script_function={string}&event_type={string}
 pub fn get_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(warp::query::<TransactionFilter>())
         .and(context.filter())
         .and_then(handle_get_transactions)
         .with(metrics("get_transactions"))
         .boxed()
 }
 
-// GET /accounts/{address}/transactions?start={u64}&limit={u16}
+// GET /accounts/{address}/transactions?start={u64}&limit={u16}&order={asc|desc}&cursor={string}
+//     &success={bool}&script_function={string}&event_type={string}
 pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "transactions")
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(warp::query::<TransactionFilter>())
         .and(context.filter())
         .and_then(handle_get_account_transactions)
         .with(metrics("get_account_transactions"))
@@ -94,6 +99,39 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// POST /transactions/simulate with JSON
+pub fn simulate_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<UserTransactionRequest>())
+        .and(context.filter())
+        .and_then(handle_simulate_json_transactions)
+        .with(metrics("simulate_json_transactions"))
+        .boxed()
+}
+
+// POST /transactions/simulate with BCS
+pub fn simulate_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // See `submit_bcs_transactions` for why the content-type is checked by the header filter.
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION,
+        ))
+        .and(warp::body::bytes())
+        .and(context.filter())
+        .and_then(handle_simulate_bcs_transactions)
+        .with(metrics("simulate_bcs_transactions"))
+        .boxed()
+}
+
 // POST /transactions/signing_message
 pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / "signing_message")
@@ -118,18 +156,23 @@ async fn handle_get_transaction(
         .await?)
 }
 
-async fn handle_get_transactions(page: Page, context: Context) -> Result<impl Reply, Rejection> {
+async fn handle_get_transactions(
+    page: Page,
+    filter: TransactionFilter,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transactions")?;
-    Ok(Transactions::new(context)?.list(page)?)
+    Ok(Transactions::new(context)?.list(page, filter)?)
 }
 
 async fn handle_get_account_transactions(
     address: AddressParam,
     page: Page,
+    filter: TransactionFilter,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_transactions")?;
-    Ok(Transactions::new(context)?.list_by_account(address, page)?)
+    Ok(Transactions::new(context)?.list_by_account(address, page, filter)?)
 }
 
 async fn handle_submit_json_transactions(
@@ -152,6 +195,26 @@ async fn handle_submit_bcs_transactions(
     Ok(Transactions::new(context)?.create(txn).await?)
 }
 
+async fn handle_simulate_json_transactions(
+    body: UserTransactionRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_json_transactions")?;
+    let transactions = Transactions::new(context)?;
+    let txn = transactions.try_into_signed_transaction(body)?;
+    Ok(transactions.simulate(txn)?)
+}
+
+async fn handle_simulate_bcs_transactions(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_bcs_transactions")?;
+    let txn = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.simulate(txn)?)
+}
+
 async fn handle_create_signing_message(
     body: UserTransactionRequest,
     context: Context,
@@ -178,8 +241,15 @@ impl Transactions {
         self,
         req: UserTransactionRequest,
     ) -> Result<impl Reply, Error> {
-        let txn = self
-            .context
+        let txn = self.try_into_signed_transaction(req)?;
+        self.create(txn).await
+    }
+
+    fn try_into_signed_transaction(
+        &self,
+        req: UserTransactionRequest,
+    ) -> Result<SignedTransaction, Error> {
+        self.context
             .move_converter()
             .try_into_signed_transaction(req, self.context.chain_id())
             .map_err(|e| {
@@ -187,14 +257,13 @@ impl Transactions {
                     "failed to create SignedTransaction from UserTransactionRequest: {}",
                     e
                 ))
-            })?;
-        self.create(txn).await
+            })
     }
 
     pub async fn create(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
         let (mempool_status, vm_status_opt) = self.context.submit_transaction(txn.clone()).await?;
         match mempool_status.code {
-            MempoolStatusCode::Accepted => {
+            code if code.is_accepted() => {
                 let converter = self.context.move_converter();
                 let pending_txn = converter.try_into_pending_transaction(txn)?;
                 let resp = Response::new(self.ledger_info, &pending_txn)?;
@@ -213,55 +282,149 @@ impl Transactions {
         }
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
+    /// Executes the transaction against the latest state without committing it.
+    ///
+    /// The transaction must carry the sender's public key for the prologue, but must not have a
+    /// valid signature: nothing that could be submitted by someone else is sent for simulation.
+    pub fn simulate(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
+        if txn.clone().check_signature().is_ok() {
+            return Err(Error::invalid_request_body(
+                "simulated transaction must not have a valid signature",
+            ));
+        }
+        let (vm_status, output) = self
+            .context
+            .simulate_transaction(&txn, self.ledger_info.version());
+        let converter = self.context.move_converter();
+        let output = match output.status() {
+            TransactionStatus::Keep(status) => {
+                converter.try_into_transaction_output(status, &output)?
+            }
+            TransactionStatus::Discard(status) => converter.discarded_transaction_output(*status),
+            TransactionStatus::Retry => {
+                return Err(Error::bad_request(format!(
+                    "invalid transaction: {:?}",
+                    vm_status
+                )))
+            }
+        };
+        Response::new(self.ledger_info, &output)
+    }
+
+    pub fn list(self, page: Page, filter: TransactionFilter) -> Result<impl Reply, Error> {
         let ledger_version = self.ledger_info.version();
         let limit = page.limit()?;
-        let last_page_start = if ledger_version > (limit as u64) {
-            ledger_version - (limit as u64)
+        let order = page.order()?;
+        let matcher = filter.parse()?;
+        let default_start = match order {
+            Order::Asc => ledger_version.saturating_sub(limit as u64),
+            Order::Desc => ledger_version,
+        };
+        let start_version = page.start_position(default_start, ledger_version)?;
+        let max_scan = if matcher.is_empty() {
+            limit as u64
         } else {
-            0
+            MAX_FILTER_SCAN_SIZE
         };
-        let start_version = page.start(last_page_start, ledger_version)?;
 
-        let data = self
-            .context
-            .get_transactions(start_version, limit, ledger_version)?;
+        let (data, cursor) = scan(
+            start_version,
+            ledger_version,
+            order,
+            limit,
+            max_scan,
+            |first, count| {
+                Ok(self
+                    .context
+                    .get_transactions(first, count, ledger_version)?)
+            },
+            |txn| txn.version,
+            |txn| matcher.matches(txn),
+        )?;
 
-        self.render_transactions(data)
+        self.render_transactions(data, order, cursor)
     }
 
-    pub fn list_by_account(self, address: AddressParam, page: Page) -> Result<impl Reply, Error> {
-        let data = self.context.get_account_transactions(
-            address.parse("account address")?.into(),
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
+    pub fn list_by_account(
+        self,
+        address: AddressParam,
+        page: Page,
+        filter: TransactionFilter,
+    ) -> Result<impl Reply, Error> {
+        let address = address.parse("account address")?.into();
+        let ledger_version = self.ledger_info.version();
+        let limit = page.limit()?;
+        let order = page.order()?;
+        let matcher = filter.parse()?;
+        let sequence_number = self
+            .context
+            .get_account_sequence_number(address, ledger_version)?;
+        if sequence_number == 0 {
+            return self.render_transactions(vec![], order, None);
+        }
+        let last_sequence_number = sequence_number - 1;
+        let default_start = match order {
+            Order::Asc => 0,
+            Order::Desc => last_sequence_number,
+        };
+        let start_seq_number = page.start_position(default_start, u64::MAX)?;
+        let max_scan = if matcher.is_empty() {
+            limit as u64
+        } else {
+            MAX_FILTER_SCAN_SIZE
+        };
+
+        let (data, cursor) = scan(
+            start_seq_number,
+            last_sequence_number,
+            order,
+            limit,
+            max_scan,
+            |first, count| {
+                Ok(self
+                    .context
+                    .get_account_transactions(address, first, count, ledger_version)?)
+            },
+            |txn| {
+                txn.transaction
+                    .as_signed_user_txn()
+                    .map(|t| t.sequence_number())
+                    .unwrap_or_default()
+            },
+            |txn| matcher.matches(txn),
         )?;
-        self.render_transactions(data)
+
+        self.render_transactions(data, order, cursor)
     }
 
     fn render_transactions(
         self,
         data: Vec<TransactionOnChainData<TransactionInfo>>,
+        order: Order,
+        cursor: Option<Cursor>,
     ) -> Result<impl Reply, Error> {
-        if data.is_empty() {
-            let txns: Vec<Transaction> = vec![];
-            return Response::new(self.ledger_info, &txns);
-        }
-        let first_version = data[0].version;
-        let mut timestamp = self.context.get_block_timestamp(first_version)?;
         let converter = self.context.move_converter();
-        let txns: Vec<Transaction> = data
-            .into_iter()
-            .map(|t| {
-                let txn = converter.try_into_onchain_transaction(timestamp, t)?;
-                // update timestamp, when txn is metadata block transaction
-                // new timestamp is used for the following transactions
-                timestamp = txn.timestamp();
-                Ok(txn)
-            })
-            .collect::<Result<_>>()?;
-        Response::new(self.ledger_info, &txns)
+        // (version, timestamp, is block metadata) of the last rendered transaction
+        let mut last: Option<(u64, u64, bool)> = None;
+        let mut txns: Vec<Transaction> = Vec::with_capacity(data.len());
+        for t in data {
+            let version = t.version;
+            // A transaction shares the timestamp of an adjacent transaction in the same block,
+            // so only look it up when the page is not contiguous: when the transaction follows a
+            // block metadata transaction, the metadata carries the new timestamp.
+            let timestamp = match last {
+                Some((v, timestamp, _)) if order == Order::Asc && v + 1 == version => timestamp,
+                Some((v, timestamp, false)) if order == Order::Desc && version + 1 == v => {
+                    timestamp
+                }
+                _ => self.context.get_block_timestamp(version)?,
+            };
+            let txn = converter.try_into_onchain_transaction(timestamp, t)?;
+            let is_block_metadata = matches!(txn, Transaction::BlockMetadataTransaction(_));
+            last = Some((version, txn.timestamp(), is_block_metadata));
+            txns.push(txn);
+        }
+        Ok(Response::new(self.ledger_info, &txns)?.with_cursor(cursor.map(|c| c.to_string())))
     }
 
     pub async fn get_transaction(self, id: TransactionId) -> Result<impl Reply, Error> {
@@ -1,7 +1,7 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{HexEncodedBytes, U64};
+use crate::{HexEncodedBytes, MoveResource, U64};
 
 use diem_types::account_config::AccountResource;
 use serde::{Deserialize, Serialize};
@@ -20,3 +20,14 @@ impl From<AccountResource> for AccountData {
         }
     }
 }
+
+/// Account resources at a version, with the proof authenticating the account state they are
+/// decoded from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountResourcesWithProof {
+    pub resources: Vec<MoveResource>,
+    /// BCS serialized `LedgerInfoWithSignatures` the proof is built against.
+    pub ledger_info_with_signatures: HexEncodedBytes,
+    /// BCS serialized `AccountStateWithProof` of the account at the version.
+    pub account_state_with_proof: HexEncodedBytes,
+}
@@ -5,7 +5,8 @@ use crate::{
     Bytecode, DirectWriteSet, Event, HexEncodedBytes, MoveFunction, MoveModuleBytecode,
     MoveResource, MoveScriptBytecode, MoveType, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
     ScriptPayload, ScriptWriteSet, Transaction, TransactionInfo, TransactionOnChainData,
-    TransactionPayload, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    TransactionOutput, TransactionPayload, UserTransactionRequest, WriteSet, WriteSetChange,
+    WriteSetPayload,
 };
 use diem_transaction_builder::error_explain;
 use diem_types::{
@@ -16,13 +17,13 @@ use diem_types::{
         ModuleBundle, RawTransaction, Script, ScriptFunction, SignedTransaction,
         TransactionInfoTrait,
     },
-    vm_status::{AbortLocation, KeptVMStatus},
+    vm_status::{AbortLocation, DiscardedVMStatus, KeptVMStatus},
     write_set::WriteOp,
 };
 use move_binary_format::file_format::FunctionHandleIndex;
 use move_core_types::{
     identifier::Identifier,
-    language_storage::{ModuleId, StructTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
     resolver::MoveResolver,
 };
 use move_resource_viewer::MoveValueAnnotator;
@@ -108,6 +109,37 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
         }
     }
 
+    pub fn try_into_transaction_output(
+        &self,
+        status: &KeptVMStatus,
+        output: &diem_types::transaction::TransactionOutput,
+    ) -> Result<TransactionOutput> {
+        Ok(TransactionOutput {
+            gas_used: output.gas_used().into(),
+            success: status.is_success(),
+            vm_status: self.explain_vm_status(status),
+            events: self.try_into_events(output.events())?,
+            changes: output
+                .write_set()
+                .iter()
+                .map(|(access_path, op)| {
+                    self.try_into_write_set_change(access_path.clone(), op.clone())
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// A discarded transaction uses no gas and changes nothing, only its status is rendered.
+    pub fn discarded_transaction_output(&self, status: DiscardedVMStatus) -> TransactionOutput {
+        TransactionOutput {
+            gas_used: 0.into(),
+            success: false,
+            vm_status: format!("Transaction discarded: {:?}", status),
+            events: vec![],
+            changes: vec![],
+        }
+    }
+
     pub fn try_into_transaction_payload(
         &self,
         payload: diem_types::transaction::TransactionPayload,
@@ -196,6 +228,10 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                     address: access_path.address.into(),
                     resource: typ.into(),
                 },
+                Path::TableItem { handle, key } => WriteSetChange::DeleteTableItem {
+                    handle: handle.0.into(),
+                    key: key.into(),
+                },
             },
             WriteOp::Value(val) => match access_path.get_path() {
                 Path::Code(_) => WriteSetChange::WriteModule {
@@ -206,6 +242,11 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                     address: access_path.address.into(),
                     data: self.try_into_resource(&typ, &val)?,
                 },
+                Path::TableItem { handle, key } => WriteSetChange::WriteTableItem {
+                    handle: handle.0.into(),
+                    key: key.into(),
+                    value: val.into(),
+                },
             },
         };
         Ok(ret)
@@ -415,6 +456,18 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
         })
     }
 
+    /// Serialize the JSON `key` of a table item, whose type is `key_type`.
+    pub fn try_into_table_key(&self, key_type: &MoveType, key: Value) -> Result<Vec<u8>> {
+        let key = self.try_into_move_value(key_type, key)?;
+        key.simple_serialize()
+            .ok_or_else(|| format_err!("failed to serialize table key: {:?}", key))
+    }
+
+    pub fn try_into_table_value(&self, value_type: &MoveType, bytes: &[u8]) -> Result<MoveValue> {
+        let typ: TypeTag = value_type.clone().try_into()?;
+        self.inner.view_value(&typ, bytes)?.try_into()
+    }
+
     pub fn try_into_move_value_vector(
         &self,
         typ: &MoveType,
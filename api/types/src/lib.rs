@@ -9,12 +9,14 @@ mod error;
 mod event_key;
 mod hash;
 mod ledger_info;
+mod mempool;
 pub mod mime_types;
 mod move_types;
 mod response;
+mod table;
 mod transaction;
 
-pub use account::AccountData;
+pub use account::{AccountData, AccountResourcesWithProof};
 pub use address::Address;
 pub use bytecode::Bytecode;
 pub use convert::MoveConverter;
@@ -22,16 +24,22 @@ pub use error::Error;
 pub use event_key::EventKey;
 pub use hash::HashValue;
 pub use ledger_info::LedgerInfo;
+pub use mempool::{
+    MempoolAccountTransactions, MempoolStats, MempoolTransaction, MempoolTransactionState,
+};
 pub use move_types::{
     HexEncodedBytes, MoveFunction, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
     MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType, MoveValue, ScriptFunctionId,
     U128, U64,
 };
-pub use response::{Response, X_DIEM_CHAIN_ID, X_DIEM_LEDGER_TIMESTAMP, X_DIEM_LEDGER_VERSION};
+pub use response::{
+    Response, X_DIEM_CHAIN_ID, X_DIEM_CURSOR, X_DIEM_LEDGER_TIMESTAMP, X_DIEM_LEDGER_VERSION,
+};
+pub use table::{TableItemRequest, TableItemWithProof};
 pub use transaction::{
     BlockMetadataTransaction, DirectWriteSet, Event, GenesisTransaction, PendingTransaction,
     ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction, TransactionData,
-    TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
+    TransactionId, TransactionInfo, TransactionOnChainData, TransactionOutput, TransactionPayload,
     TransactionSigningMessage, UserTransaction, UserTransactionRequest, WriteSet, WriteSetChange,
     WriteSetPayload,
 };
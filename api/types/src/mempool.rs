@@ -0,0 +1,47 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, PendingTransaction, U64};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolTransactionState {
+    /// The transaction can be included in the next block.
+    Ready,
+    /// The transaction waits for a transaction with a lower sequence number.
+    Parked,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MempoolTransaction {
+    pub state: MempoolTransactionState,
+    pub ranking_score: U64,
+    /// Time after which the node drops the transaction from its mempool, in seconds since the
+    /// Unix epoch.
+    pub system_expiration_timestamp_secs: U64,
+    #[serde(flatten)]
+    pub transaction: PendingTransaction,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MempoolAccountTransactions {
+    pub address: Address,
+    /// Sequence number of the account in the latest committed state.
+    pub sequence_number: U64,
+    /// First sequence number missing for the parked transactions to become ready, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_sequence_number: Option<U64>,
+    pub transactions: Vec<MempoolTransaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MempoolStats {
+    pub size: U64,
+    pub ready: U64,
+    pub parked: U64,
+    pub accounts: U64,
+    pub capacity: U64,
+    pub capacity_per_user: U64,
+}
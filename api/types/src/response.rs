@@ -10,10 +10,12 @@ use warp::http::header::{HeaderValue, CONTENT_TYPE};
 pub const X_DIEM_CHAIN_ID: &str = "X-Diem-Chain-Id";
 pub const X_DIEM_LEDGER_VERSION: &str = "X-Diem-Ledger-Version";
 pub const X_DIEM_LEDGER_TIMESTAMP: &str = "X-Diem-Ledger-TimestampUsec";
+pub const X_DIEM_CURSOR: &str = "X-Diem-Cursor";
 
 pub struct Response {
     pub ledger_info: LedgerInfo,
     pub body: Vec<u8>,
+    pub cursor: Option<String>,
 }
 
 impl Response {
@@ -21,8 +23,15 @@ impl Response {
         Ok(Self {
             ledger_info,
             body: serde_json::to_vec(body)?,
+            cursor: None,
         })
     }
+
+    /// Sets the cursor for fetching the next page, returned in the `X-Diem-Cursor` header.
+    pub fn with_cursor(mut self, cursor: Option<String>) -> Self {
+        self.cursor = cursor;
+        self
+    }
 }
 
 impl warp::Reply for Response {
@@ -40,6 +49,11 @@ impl warp::Reply for Response {
             X_DIEM_LEDGER_TIMESTAMP,
             self.ledger_info.ledger_timestamp.into(),
         );
+        if let Some(cursor) = self.cursor {
+            if let Ok(value) = HeaderValue::from_str(&cursor) {
+                headers.insert(X_DIEM_CURSOR, value);
+            }
+        }
 
         res
     }
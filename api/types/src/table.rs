@@ -0,0 +1,26 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{HexEncodedBytes, MoveType};
+
+use serde::{Deserialize, Serialize};
+
+/// Request of a table item. The key is given in JSON, and the key and value types are those of
+/// the table, which the handle doesn't record.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableItemRequest {
+    pub key_type: MoveType,
+    pub value_type: MoveType,
+    pub key: serde_json::Value,
+}
+
+/// A table item at a version, with the proof authenticating the account state it is stored in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableItemWithProof {
+    /// The JSON encoded `MoveValue` of the item.
+    pub value: serde_json::Value,
+    /// BCS serialized `LedgerInfoWithSignatures` the proof is built against.
+    pub ledger_info_with_signatures: HexEncodedBytes,
+    /// BCS serialized `AccountStateWithProof` of the address the item is stored under.
+    pub account_state_with_proof: HexEncodedBytes,
+}
@@ -221,6 +221,16 @@ pub struct UserTransaction {
     pub timestamp: U64,
 }
 
+/// The output of a simulated user transaction, which is not committed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionOutput {
+    pub gas_used: U64,
+    pub success: bool,
+    pub vm_status: String,
+    pub events: Vec<Event>,
+    pub changes: Vec<WriteSetChange>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UserTransactionRequest {
     pub sender: Address,
@@ -368,6 +378,15 @@ pub enum WriteSetChange {
         address: Address,
         data: MoveResource,
     },
+    DeleteTableItem {
+        handle: Address,
+        key: HexEncodedBytes,
+    },
+    WriteTableItem {
+        handle: Address,
+        key: HexEncodedBytes,
+        value: HexEncodedBytes,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -217,6 +217,7 @@ fn compute_genesis(
         db_path,
         false,
         None,
+        None, /* ledger_prune_window */
         RocksdbConfig::default(),
         true, /* account_count_migration */
     )
@@ -44,6 +44,7 @@ pub fn create_genesis_waypoint(genesis: &Transaction) -> Result<Waypoint, Error>
         &path,
         false,
         None,
+        None, /* ledger_prune_window */
         RocksdbConfig::default(),
         true, /* account_count_migration */
     )
@@ -247,6 +247,7 @@ pub fn strip_address(address: &NetworkAddress) -> NetworkAddress {
                     | Protocol::Ip6(_)
                     | Protocol::Memory(_)
                     | Protocol::Tcp(_)
+                    | Protocol::Quic(_)
             )
         })
         .cloned()
@@ -179,7 +179,7 @@ pub fn validate_address(
                 }
                 has_addr = true
             }
-            Protocol::Tcp(_) => has_port = true,
+            Protocol::Tcp(_) | Protocol::Quic(_) => has_port = true,
             Protocol::Dns(_) | Protocol::Ip6(_) | Protocol::Dns6(_) => {
                 return Err(Error::CommandArgumentError(format!(
                     "{}: IPv6 is currently not supported.  Protocol: '{}'",
@@ -18,11 +18,15 @@ pub struct ApiConfig {
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    // interval of polling newly committed transactions for streaming clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_poll_interval_ms: Option<u64>,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 4 * 1024 * 1024; // 4mb
+pub const DEFAULT_STREAM_POLL_INTERVAL_MS: u64 = 100;
 
 fn default_enabled() -> bool {
     true
@@ -38,6 +42,7 @@ impl Default for ApiConfig {
             tls_cert_path: None,
             tls_key_path: None,
             content_length_limit: None,
+            stream_poll_interval_ms: None,
         }
     }
 }
@@ -53,4 +58,9 @@ impl ApiConfig {
             None => DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT,
         }
     }
+
+    pub fn stream_poll_interval_ms(&self) -> u64 {
+        self.stream_poll_interval_ms
+            .unwrap_or(DEFAULT_STREAM_POLL_INTERVAL_MS)
+    }
 }
@@ -11,6 +11,9 @@ pub struct DebugInterfaceConfig {
     pub address: String,
     pub metrics_server_port: u16,
     pub public_metrics_server_port: u16,
+    /// Allow clearing peer bans through `POST /peer-bans/clear`.  Only requests
+    /// from localhost are accepted.
+    pub enable_peer_ban_clearing: bool,
 }
 
 impl Default for DebugInterfaceConfig {
@@ -20,6 +23,7 @@ impl Default for DebugInterfaceConfig {
             address: "0.0.0.0".to_string(),
             metrics_server_port: 9101,
             public_metrics_server_port: 9102,
+            enable_peer_ban_clearing: false,
         }
     }
 }
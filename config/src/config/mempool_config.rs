@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
@@ -10,8 +11,17 @@ pub struct MempoolConfig {
     pub capacity_per_user: usize,
     // number of failovers to broadcast to when the primary network is alive
     pub default_failovers: usize,
+    // if enabled, accepted transactions are journaled to disk and restored on restart
+    pub enable_journal: bool,
+    // path of the journal, relative to the data dir unless absolute
+    pub journal_path: PathBuf,
+    #[serde(skip)]
+    data_dir: PathBuf,
     pub max_broadcasts_per_peer: usize,
     pub mempool_snapshot_interval_secs: u64,
+    // minimum gas price increase, in percent, for a transaction to replace a pending transaction
+    // with the same sender and sequence number
+    pub replace_by_fee_min_gas_price_bump_pct: u64,
     pub shared_mempool_ack_timeout_ms: u64,
     pub shared_mempool_backoff_interval_ms: u64,
     pub shared_mempool_batch_size: usize,
@@ -34,8 +44,26 @@ impl Default for MempoolConfig {
             capacity: 1_000_000,
             capacity_per_user: 100,
             default_failovers: 3,
+            enable_journal: false,
+            journal_path: PathBuf::from("mempool/journal"),
+            data_dir: PathBuf::from("/opt/diem/data"),
+            replace_by_fee_min_gas_price_bump_pct: 10,
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
         }
     }
 }
+
+impl MempoolConfig {
+    pub fn journal_path(&self) -> PathBuf {
+        if self.journal_path.is_relative() {
+            self.data_dir.join(&self.journal_path)
+        } else {
+            self.journal_path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
@@ -220,6 +220,7 @@ impl NodeConfig {
         self.base.data_dir = data_dir.clone();
         self.consensus.set_data_dir(data_dir.clone());
         self.execution.set_data_dir(data_dir.clone());
+        self.mempool.set_data_dir(data_dir.clone());
         self.storage.set_data_dir(data_dir);
     }
 
@@ -231,6 +232,7 @@ impl NodeConfig {
 
         let input_dir = RootPath::new(input_path);
         config.execution.load(&input_dir)?;
+        config.storage.validate()?;
 
         let mut config = config.validate_network_configs()?;
         config.set_data_dir(config.data_dir().to_path_buf());
@@ -11,7 +11,9 @@ use diem_crypto::{x25519, Uniform};
 use diem_network_address_encryption::Encryptor;
 use diem_secure_storage::{CryptoStorage, KVStorage, Storage};
 use diem_types::{
-    network_address::NetworkAddress, transaction::authenticator::AuthenticationKey, PeerId,
+    network_address::{NetworkAddress, Protocol},
+    transaction::authenticator::AuthenticationKey,
+    PeerId,
 };
 use rand::{
     rngs::{OsRng, StdRng},
@@ -209,6 +211,20 @@ impl NetworkConfig {
                 .ok_or_else(|| Error::InvariantViolation("No local IP".to_string()))?;
         }
 
+        // QUIC connections do not go through load balancers that prepend a proxy protocol header
+        let is_quic = self
+            .listen_address
+            .as_slice()
+            .iter()
+            .any(|protocol| matches!(protocol, Protocol::Quic(_)));
+        crate::config::invariant(
+            !(self.enable_proxy_protocol && is_quic),
+            format!(
+                "Proxy protocol is not supported on QUIC listen address: '{}'",
+                self.listen_address
+            ),
+        )?;
+
         self.prepare_identity();
         Ok(())
     }
@@ -304,6 +320,11 @@ impl NetworkConfig {
 pub enum DiscoveryMethod {
     Onchain,
     File(PathBuf, Duration),
+    // Resolve peers from the SRV and TXT records under a DNS name, every interval
+    Dns(String, Duration),
+    // Ask connected peers for the addresses they know about, every interval.
+    // Only supported on networks without mutual authentication.
+    PeerExchange(Duration),
     None,
 }
 
@@ -452,3 +473,22 @@ impl Peer {
         Peer::new(addresses, keys, role)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_proxy_protocol_rejected_on_quic() {
+        let mut config = NetworkConfig::network_with_id(NetworkId::Public);
+        config.enable_proxy_protocol = true;
+        config.listen_address = "/ip4/0.0.0.0/tcp/6180".parse().unwrap();
+        config.load_fullnode_network().unwrap();
+
+        config.listen_address = "/ip4/0.0.0.0/quic/6180".parse().unwrap();
+        config.load_fullnode_network().unwrap_err();
+
+        config.enable_proxy_protocol = false;
+        config.load_fullnode_network().unwrap();
+    }
+}
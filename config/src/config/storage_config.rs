@@ -1,7 +1,10 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::utils;
+use crate::{
+    config::{invariant, Error},
+    utils,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -42,6 +45,10 @@ pub struct StorageConfig {
     /// None disables pruning. The windows is in number of versions, consider system tps
     /// (transaction per second) when calculating proper window.
     pub prune_window: Option<u64>,
+    /// None disables ledger pruning. Transactions, events, write sets and their indices older than
+    /// this many versions are deleted in the background. Proofs can't be served for pruned
+    /// versions, so this should be no smaller than `prune_window`.
+    pub ledger_prune_window: Option<u64>,
     #[serde(skip)]
     data_dir: PathBuf,
     /// Read, Write, Connect timeout for network operations in milliseconds
@@ -71,6 +78,8 @@ impl Default for StorageConfig {
             // conservatively safe minimal prune window. It'll take a few Gigabytes of disk space
             // depending on the size of an average account blob.
             prune_window: Some(1_000_000),
+            // Keep the full ledger history by default.
+            ledger_prune_window: None,
             data_dir: PathBuf::from("/opt/diem/data"),
             // Default read/write/connection timeout, in milliseconds
             timeout_ms: 30_000,
@@ -98,4 +107,41 @@ impl StorageConfig {
         self.backup_service_address
             .set_port(utils::get_available_port());
     }
+
+    /// Checks that the ledger history outlives the state it proves, i.e. that
+    /// `ledger_prune_window` is set only if `prune_window` is, and is no smaller than it.
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some(ledger_prune_window) = self.ledger_prune_window {
+            invariant(
+                matches!(self.prune_window, Some(prune_window) if ledger_prune_window >= prune_window),
+                format!(
+                    "ledger_prune_window ({}) must be no smaller than prune_window ({:?})",
+                    ledger_prune_window, self.prune_window
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_prune_windows() {
+        let mut config = StorageConfig::default();
+        config.validate().unwrap();
+
+        config.prune_window = Some(100);
+        config.ledger_prune_window = Some(100);
+        config.validate().unwrap();
+
+        config.ledger_prune_window = Some(99);
+        config.validate().unwrap_err();
+
+        config.prune_window = None;
+        config.ledger_prune_window = Some(1_000);
+        config.validate().unwrap_err();
+    }
 }
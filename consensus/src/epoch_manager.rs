@@ -52,7 +52,10 @@ use futures::{
     },
     SinkExt, StreamExt,
 };
-use network::protocols::network::{ApplicationNetworkSender, Event};
+use network::{
+    application::reputation::Misbehavior,
+    protocols::network::{ApplicationNetworkSender, Event},
+};
 use safety_rules::SafetyRulesManager;
 use std::{
     cmp::Ordering,
@@ -509,6 +512,8 @@ impl EpochManager {
                         error = ?err,
                         unverified_event = unverified_event
                     );
+                    self.network_sender
+                        .report_misbehavior(peer_id, Misbehavior::InvalidProof);
                     err
                 })?;
 
@@ -19,7 +19,7 @@ use diem_config::network_id::{NetworkId, PeerNetworkId};
 use diem_logger::prelude::*;
 use diem_types::{epoch_change::EpochChangeProof, PeerId};
 use network::{
-    application::storage::PeerMetadataStorage,
+    application::{reputation::Misbehavior, storage::PeerMetadataStorage},
     constants::NETWORK_CHANNEL_SIZE,
     error::NetworkError,
     peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
@@ -139,6 +139,17 @@ impl ConsensusNetworkSender {
         }
     }
 
+    /// Report misbehavior of a validator to the node-wide peer reputation, which counts it but
+    /// never bans validators.
+    pub fn report_misbehavior(&self, peer: PeerId, misbehavior: Misbehavior) {
+        if let Some(peer_metadata_storage) = &self.peer_metadata_storage {
+            let peer_network_id = PeerNetworkId::new(NetworkId::Validator, peer);
+            peer_metadata_storage
+                .reputation()
+                .report(peer_network_id, misbehavior);
+        }
+    }
+
     /// Choose the overlapping protocol for peer. The local protocols are sorted from most to least preferred.
     fn preferred_protocol_for_peer(
         &self,
@@ -3,13 +3,14 @@
 
 //! Debug interface to access information in a specific node.
 
-use diem_config::config::NodeConfig;
+use diem_config::{config::NodeConfig, network_id::PeerNetworkId};
 use diem_logger::{info, json_log, Filter, Logger};
 use diem_metrics::json_metrics::get_git_rev;
+use network::application::storage::PeerMetadataStorage;
 use serde::{Deserialize, Serialize};
 use std::{net::SocketAddr, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
-use warp::Filter as _;
+use warp::{http::StatusCode, Filter as _};
 
 #[derive(Debug)]
 pub struct NodeDebugService {
@@ -25,7 +26,12 @@ struct NodeInfo {
 }
 
 impl NodeDebugService {
-    pub fn new(address: SocketAddr, logger: Option<Arc<Logger>>, node_config: &NodeConfig) -> Self {
+    pub fn new(
+        address: SocketAddr,
+        logger: Option<Arc<Logger>>,
+        node_config: &NodeConfig,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
+    ) -> Self {
         let runtime = Builder::new_multi_thread()
             .thread_name("nodedebug")
             .enable_all()
@@ -83,7 +89,49 @@ impl NodeDebugService {
         };
         let node_info_route = warp::path("node-info").map(move || warp::reply::json(&node_info));
 
-        let routes = log.or(warp::get().and(metrics.or(events).or(node_info_route)));
+        // Get /peer-bans (peers currently banned for misbehavior)
+        let peer_bans = {
+            let peer_metadata_storage = peer_metadata_storage.clone();
+            warp::path("peer-bans")
+                .and(warp::path::end())
+                .map(move || warp::reply::json(&peer_metadata_storage.reputation().bans()))
+        };
+
+        // Post /peer-bans/clear (lifts the ban of the `PeerNetworkId` in the body, or all bans
+        // if the body is empty). Only enabled by config, and only from localhost.
+        let enable_peer_ban_clearing = node_config.debug_interface.enable_peer_ban_clearing;
+        let clear_peer_bans = warp::post()
+            .and(warp::path!("peer-bans" / "clear"))
+            .and(warp::addr::remote())
+            .and(warp::body::content_length_limit(1024))
+            .and(warp::body::bytes())
+            .map(move |remote: Option<SocketAddr>, bytes: bytes::Bytes| {
+                let is_local = remote.map_or(false, |remote| remote.ip().is_loopback());
+                if !enable_peer_ban_clearing || !is_local {
+                    return warp::reply::with_status(warp::reply::reply(), StatusCode::FORBIDDEN);
+                }
+
+                let reputation = peer_metadata_storage.reputation();
+                if bytes.is_empty() {
+                    info!("Clearing all peer bans");
+                    reputation.clear_all_bans();
+                    return warp::reply::with_status(warp::reply::reply(), StatusCode::OK);
+                }
+                match serde_json::from_slice::<PeerNetworkId>(&bytes) {
+                    Ok(peer) => {
+                        info!(peer = %peer, "Clearing peer ban");
+                        reputation.clear_ban(peer);
+                        warp::reply::with_status(warp::reply::reply(), StatusCode::OK)
+                    }
+                    Err(_) => {
+                        warp::reply::with_status(warp::reply::reply(), StatusCode::BAD_REQUEST)
+                    }
+                }
+            });
+
+        let routes = log
+            .or(clear_peer_bans)
+            .or(warp::get().and(metrics.or(events).or(node_info_route).or(peer_bans)));
 
         runtime
             .handle()
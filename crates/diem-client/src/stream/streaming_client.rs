@@ -4,12 +4,15 @@
 use crate::{stream::websocket_transport::WebsocketTransport, StreamError, StreamResult};
 use diem_json_rpc_types::{
     stream::{
-        request::{StreamMethodRequest, SubscribeToEventsParams, SubscribeToTransactionsParams},
+        request::{
+            StreamMethodRequest, SubscribeToAccountParams, SubscribeToBlocksParams,
+            SubscribeToEventsParams, SubscribeToTransactionsParams,
+        },
         response::StreamJsonRpcResponse,
     },
     Id,
 };
-use diem_types::event::EventKey;
+use diem_types::{account_address::AccountAddress, event::EventKey};
 use futures::Stream;
 use std::{
     collections::HashMap,
@@ -159,6 +162,27 @@ impl StreamingClient {
         self.send_subscription(request).await
     }
 
+    pub async fn subscribe_blocks(
+        &mut self,
+        starting_version: u64,
+    ) -> StreamResult<SubscriptionStream> {
+        let request =
+            StreamMethodRequest::SubscribeToBlocks(SubscribeToBlocksParams { starting_version });
+        self.send_subscription(request).await
+    }
+
+    pub async fn subscribe_account(
+        &mut self,
+        address: AccountAddress,
+        starting_version: u64,
+    ) -> StreamResult<SubscriptionStream> {
+        let request = StreamMethodRequest::SubscribeToAccount(SubscribeToAccountParams {
+            address,
+            starting_version,
+        });
+        self.send_subscription(request).await
+    }
+
     pub(crate) async fn send_unsubscribe(&mut self, id: &Id) -> StreamResult<()> {
         debug!("StreamingClient sending unsubscribe for: {:?}", id);
         self.client
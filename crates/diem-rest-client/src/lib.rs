@@ -2,11 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Result};
-use diem_api_types::{MoveModuleBytecode, PendingTransaction, Transaction};
+use diem_api_types::{
+    AccountResourcesWithProof, MoveModuleBytecode, PendingTransaction, TableItemRequest,
+    TableItemWithProof, Transaction,
+};
 use diem_client::{Response, State};
 use diem_crypto::HashValue;
 use diem_types::{account_address::AccountAddress, transaction::SignedTransaction};
-use move_core_types::language_storage::StructTag;
+use move_core_types::language_storage::{StructTag, TableHandle};
 use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient};
 use serde::Deserialize;
 use std::time::Duration;
@@ -15,6 +18,9 @@ use url::Url;
 pub mod types;
 pub use types::{DiemAccount, Resource, RestError};
 
+mod verify;
+pub use verify::{verify_account_resources, verify_table_item};
+
 const BCS_CONTENT_TYPE: &str = "application/x.diem.signed_transaction+bcs";
 const USER_AGENT: &str = concat!("diem-client-sdk-rust / ", env!("CARGO_PKG_VERSION"));
 
@@ -174,6 +180,51 @@ impl Client {
         self.json(response).await
     }
 
+    /// Returns the resources of the account at `version`, with the proof to be verified by
+    /// `verify_account_resources`.
+    pub async fn get_account_resources_with_proof(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Response<AccountResourcesWithProof>> {
+        let url = self.base_url.join(&format!(
+            "ledger/{}/accounts/{}/resources",
+            version, address
+        ))?;
+
+        let response = self
+            .inner
+            .get(url)
+            .query(&[("with_proof", true)])
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    /// Returns the item of the table `handle` at `version`, with the proof to be verified by
+    /// `verify_table_item`.
+    pub async fn get_table_item_with_proof(
+        &self,
+        handle: TableHandle,
+        version: u64,
+        request: &TableItemRequest,
+    ) -> Result<Response<TableItemWithProof>> {
+        let url = self
+            .base_url
+            .join(&format!("ledger/{}/tables/{}/item", version, handle))?;
+
+        let response = self
+            .inner
+            .post(url)
+            .query(&[("with_proof", true)])
+            .json(request)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     pub async fn get_account_resource(
         &self,
         address: AccountAddress,
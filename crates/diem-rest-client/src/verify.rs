@@ -0,0 +1,407 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Context, Result};
+use diem_api_types::{
+    AccountResourcesWithProof, HexEncodedBytes, MoveConverter, TableItemRequest, TableItemWithProof,
+};
+use diem_types::{
+    access_path::AccessPath, account_address::AccountAddress, account_state::AccountState,
+    account_state_blob::default_protocol::AccountStateWithProof, epoch_change::Verifier,
+    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, transaction::Version,
+};
+use move_core_types::{language_storage::TableHandle, resolver::MoveResolver};
+use std::convert::TryFrom;
+
+/// Verifies the `AccountResourcesWithProof` of `address` at `version` returned by the REST API,
+/// and returns the authenticated account state.
+///
+/// The ledger info the proof is built against must be signed by the validators of `trusted`, the
+/// epoch the client trusts, e.g. the epoch state of its `TrustedState`. When the ledger has moved
+/// to a later epoch, the client needs to ratchet its trusted state first, e.g. via a JSON-RPC
+/// state proof.
+///
+/// The decoded resources in the response are checked to be the authenticated ones, decoded with
+/// the modules of `resolver`.
+pub fn verify_account_resources<R: MoveResolver + ?Sized>(
+    response: &AccountResourcesWithProof,
+    trusted: &EpochState,
+    address: AccountAddress,
+    version: Version,
+    resolver: &R,
+) -> Result<AccountState> {
+    let account_state = verify_account_state(
+        &response.ledger_info_with_signatures,
+        &response.account_state_with_proof,
+        trusted,
+        address,
+        version,
+    )?
+    .ok_or_else(|| format_err!("Account {} does not exist at version {}.", address, version))?;
+
+    let resources = MoveConverter::new(resolver)
+        .try_into_resources(account_state.get_resources())
+        .context("Failed to decode the authenticated resources.")?;
+    ensure!(
+        response.resources == resources,
+        "Resources do not match the account state."
+    );
+
+    Ok(account_state)
+}
+
+/// Verifies the `TableItemWithProof` returned by the REST API for `request` on the table
+/// `handle` at `version`, and returns the authenticated BCS serialized value of the item.
+///
+/// The ledger info is checked against `trusted` as in `verify_account_resources`, and the decoded
+/// value in the response is checked to be the authenticated one, decoded with the modules of
+/// `resolver`.
+pub fn verify_table_item<R: MoveResolver + ?Sized>(
+    response: &TableItemWithProof,
+    trusted: &EpochState,
+    handle: TableHandle,
+    request: &TableItemRequest,
+    version: Version,
+    resolver: &R,
+) -> Result<Vec<u8>> {
+    let converter = MoveConverter::new(resolver);
+    let key = converter.try_into_table_key(&request.key_type, request.key.clone())?;
+    let access_path = AccessPath::table_item_access_path(handle, key);
+
+    let bytes = verify_account_state(
+        &response.ledger_info_with_signatures,
+        &response.account_state_with_proof,
+        trusted,
+        access_path.address,
+        version,
+    )?
+    .and_then(|account_state| account_state.get(&access_path.path).cloned())
+    .ok_or_else(|| {
+        format_err!(
+            "Item {} of table {} does not exist at version {}.",
+            request.key,
+            handle,
+            version
+        )
+    })?;
+
+    let value = converter
+        .try_into_table_value(&request.value_type, &bytes)
+        .context("Failed to decode the authenticated table item.")?;
+    ensure!(
+        response.value == value.json()?,
+        "Value does not match the table item."
+    );
+
+    Ok(bytes)
+}
+
+/// Verifies the BCS serialized ledger info and account state proof, and returns the account state
+/// of `address` at `version`, if the account exists.
+fn verify_account_state(
+    ledger_info_with_signatures: &HexEncodedBytes,
+    account_state_with_proof: &HexEncodedBytes,
+    trusted: &EpochState,
+    address: AccountAddress,
+    version: Version,
+) -> Result<Option<AccountState>> {
+    let ledger_info_with_sigs: LedgerInfoWithSignatures =
+        bcs::from_bytes(ledger_info_with_signatures.inner())?;
+    trusted.verify(&ledger_info_with_sigs).with_context(|| {
+        format!(
+            "Ledger info at version {} is not trusted.",
+            ledger_info_with_sigs.ledger_info().version()
+        )
+    })?;
+
+    let state_with_proof: AccountStateWithProof =
+        bcs::from_bytes(account_state_with_proof.inner())?;
+    state_with_proof.verify(ledger_info_with_sigs.ledger_info(), version, address)?;
+    state_with_proof
+        .blob
+        .as_ref()
+        .map(AccountState::try_from)
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diem_api_types::{MoveResource, MoveType};
+    use diem_crypto::{hash::CryptoHash, HashValue};
+    use diem_types::{
+        account_address::HashAccountAddress,
+        account_config::{xdx_type_tag, BalanceResource},
+        account_state_blob::AccountStateBlob,
+        block_info::BlockInfo,
+        ledger_info::LedgerInfo,
+        proof::{
+            SparseMerkleLeafNode, SparseMerkleProof, TransactionAccumulatorProof,
+            TransactionInfoWithProof,
+        },
+        transaction::{TransactionInfo, TransactionInfoTrait},
+        validator_signer::ValidatorSigner,
+        validator_verifier::random_validator_verifier,
+        vm_status::KeptVMStatus,
+    };
+    use move_core_types::{
+        language_storage::{ModuleId, StructTag},
+        resolver::{ModuleResolver, ResourceResolver, TableResolver},
+    };
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    const ADDRESS: AccountAddress = AccountAddress::new([0xdd; AccountAddress::LENGTH]);
+    const VERSION: Version = 0;
+    const EPOCH: u64 = 1;
+
+    /// Resolves the modules of the current Diem Framework release.
+    struct FrameworkModules(BTreeMap<ModuleId, Vec<u8>>);
+
+    impl FrameworkModules {
+        fn new() -> Self {
+            Self(
+                diem_framework_releases::current_modules_with_blobs()
+                    .map(|(blob, module)| (module.self_id(), blob.clone()))
+                    .collect(),
+            )
+        }
+    }
+
+    impl ModuleResolver for FrameworkModules {
+        type Error = anyhow::Error;
+
+        fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>> {
+            Ok(self.0.get(module_id).cloned())
+        }
+    }
+
+    impl ResourceResolver for FrameworkModules {
+        type Error = anyhow::Error;
+
+        fn get_resource(&self, _: &AccountAddress, _: &StructTag) -> Result<Option<Vec<u8>>> {
+            Ok(None)
+        }
+    }
+
+    impl TableResolver for FrameworkModules {
+        type Error = anyhow::Error;
+
+        fn get_table_item(&self, _: &TableHandle, _: &[u8]) -> Result<Option<Vec<u8>>> {
+            Ok(None)
+        }
+    }
+
+    fn balance_tag() -> StructTag {
+        BalanceResource::struct_tag_for_currency(xdx_type_tag())
+    }
+
+    /// Builds the proof of `blob` stored under `address`, in a ledger of a single transaction
+    /// whose state only holds that blob, and the ledger info signed by `signers`.
+    fn prove(
+        address: AccountAddress,
+        blob: AccountStateBlob,
+        signers: &[ValidatorSigner],
+    ) -> (HexEncodedBytes, HexEncodedBytes) {
+        let leaf = SparseMerkleLeafNode::new(address.hash(), blob.hash());
+        let txn_info = TransactionInfo::new(
+            HashValue::zero(),
+            leaf.hash(),
+            HashValue::zero(),
+            0,
+            KeptVMStatus::Executed,
+        );
+        let ledger_info = LedgerInfo::new(
+            BlockInfo::new(
+                EPOCH,
+                0,
+                HashValue::zero(),
+                txn_info.hash(),
+                VERSION,
+                0,
+                None,
+            ),
+            HashValue::zero(),
+        );
+        let signatures = signers
+            .iter()
+            .map(|signer| (signer.author(), signer.sign(&ledger_info)))
+            .collect();
+        let ledger_info_with_sigs = LedgerInfoWithSignatures::new(ledger_info, signatures);
+        let state_with_proof = AccountStateWithProof::new(
+            VERSION,
+            Some(blob),
+            diem_types::proof::AccountStateProof::new(
+                TransactionInfoWithProof::new(TransactionAccumulatorProof::new(vec![]), txn_info),
+                SparseMerkleProof::new(Some(leaf), vec![]),
+            ),
+        );
+        (
+            bcs::to_bytes(&ledger_info_with_sigs).unwrap().into(),
+            bcs::to_bytes(&state_with_proof).unwrap().into(),
+        )
+    }
+
+    fn account_blob(coin: u64) -> AccountStateBlob {
+        let mut state = AccountState::default();
+        state.insert(
+            AccessPath::resource_access_vec(balance_tag()),
+            bcs::to_bytes(&coin).unwrap(),
+        );
+        AccountStateBlob::try_from(&state).unwrap()
+    }
+
+    fn resources_response(coin: u64, signers: &[ValidatorSigner]) -> AccountResourcesWithProof {
+        let resolver = FrameworkModules::new();
+        let blob = account_blob(coin);
+        let resources = MoveConverter::new(&resolver)
+            .try_into_resources(AccountState::try_from(&blob).unwrap().get_resources())
+            .unwrap();
+        let (ledger_info_with_signatures, account_state_with_proof) = prove(ADDRESS, blob, signers);
+        AccountResourcesWithProof {
+            resources,
+            ledger_info_with_signatures,
+            account_state_with_proof,
+        }
+    }
+
+    fn epoch_state() -> (Vec<ValidatorSigner>, EpochState) {
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        (
+            signers,
+            EpochState {
+                epoch: EPOCH,
+                verifier,
+            },
+        )
+    }
+
+    #[test]
+    fn test_verify_account_resources() {
+        let (signers, trusted) = epoch_state();
+        let response = resources_response(42, &signers);
+
+        let account_state = verify_account_resources(
+            &response,
+            &trusted,
+            ADDRESS,
+            VERSION,
+            &FrameworkModules::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            account_state.get_resources().next().unwrap(),
+            (balance_tag(), bcs::to_bytes(&42u64).unwrap().as_slice())
+        );
+    }
+
+    #[test]
+    fn test_verify_account_resources_rejects_tampered_value() {
+        let (signers, trusted) = epoch_state();
+        let mut response = resources_response(42, &signers);
+        let honest: MoveResource = response.resources[0].clone();
+        response.resources[0].data = resources_response(1_000, &signers).resources[0]
+            .data
+            .clone();
+        assert_ne!(response.resources[0], honest);
+
+        verify_account_resources(
+            &response,
+            &trusted,
+            ADDRESS,
+            VERSION,
+            &FrameworkModules::new(),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_account_resources_rejects_other_account() {
+        let (signers, trusted) = epoch_state();
+        let response = resources_response(42, &signers);
+
+        verify_account_resources(
+            &response,
+            &trusted,
+            AccountAddress::new([0xee; AccountAddress::LENGTH]),
+            VERSION,
+            &FrameworkModules::new(),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_account_resources_rejects_untrusted_signers() {
+        let (_, trusted) = epoch_state();
+        let other_signers: Vec<_> = (0..4)
+            .map(|i| ValidatorSigner::random([100 + i; 32]))
+            .collect();
+        let response = resources_response(42, &other_signers);
+
+        verify_account_resources(
+            &response,
+            &trusted,
+            ADDRESS,
+            VERSION,
+            &FrameworkModules::new(),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_account_resources_rejects_other_epoch() {
+        let (signers, mut trusted) = epoch_state();
+        let response = resources_response(42, &signers);
+        trusted.epoch += 1;
+
+        verify_account_resources(
+            &response,
+            &trusted,
+            ADDRESS,
+            VERSION,
+            &FrameworkModules::new(),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_table_item() {
+        let (signers, trusted) = epoch_state();
+        let resolver = FrameworkModules::new();
+        let handle = TableHandle(AccountAddress::new([0x7a; AccountAddress::LENGTH]));
+        let request = TableItemRequest {
+            key_type: MoveType::U64,
+            value_type: MoveType::U64,
+            key: json!("7"),
+        };
+        let key = MoveConverter::new(&resolver)
+            .try_into_table_key(&request.key_type, request.key.clone())
+            .unwrap();
+        let access_path = AccessPath::table_item_access_path(handle, key);
+        let value = bcs::to_bytes(&42u64).unwrap();
+        let mut state = AccountState::default();
+        state.insert(access_path.path, value.clone());
+        let blob = AccountStateBlob::try_from(&state).unwrap();
+        let (ledger_info_with_signatures, account_state_with_proof) =
+            prove(access_path.address, blob, &signers);
+        let mut response = TableItemWithProof {
+            value: json!("42"),
+            ledger_info_with_signatures,
+            account_state_with_proof,
+        };
+
+        assert_eq!(
+            verify_table_item(&response, &trusted, handle, &request, VERSION, &resolver).unwrap(),
+            value
+        );
+
+        let other_key = TableItemRequest {
+            key: json!("8"),
+            ..request.clone()
+        };
+        verify_table_item(&response, &trusted, handle, &other_key, VERSION, &resolver).unwrap_err();
+
+        response.value = json!("43");
+        verify_table_item(&response, &trusted, handle, &request, VERSION, &resolver).unwrap_err();
+    }
+}
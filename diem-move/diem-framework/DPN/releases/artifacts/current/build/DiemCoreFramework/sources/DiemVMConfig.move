@@ -208,6 +208,32 @@ module DiemFramework::DiemVMConfig {
         ensures old(DiemConfig::spec_has_config()) == DiemConfig::spec_has_config();
     }
 
+    /// Replace the gas costs of the natives, e.g. to cover natives that were added after genesis.
+    public fun set_native_schedule(dr_account: &signer, native_schedule: vector<u8>) {
+        DiemTimestamp::assert_operating();
+        Roles::assert_diem_root(dr_account);
+
+        let config = DiemConfig::get<DiemVMConfig>();
+        config.gas_schedule.native_schedule = native_schedule;
+        DiemConfig::set(dr_account, config);
+    }
+    spec set_native_schedule {
+        include DiemTimestamp::AbortsIfNotOperating;
+        /// No one can update DiemVMConfig except for the Diem Root account [[H11]][PERMISSION].
+        include Roles::AbortsIfNotDiemRoot{account: dr_account};
+        include DiemConfig::SetAbortsIf<DiemVMConfig>{account: dr_account };
+        let config = DiemConfig::spec_get_config<DiemVMConfig>();
+        ensures DiemConfig::spec_is_published<DiemVMConfig>();
+        ensures DiemConfig::get<DiemVMConfig>() == DiemVMConfig {
+            gas_schedule: GasSchedule {
+                instruction_schedule: config.gas_schedule.instruction_schedule,
+                native_schedule,
+                gas_constants: config.gas_schedule.gas_constants,
+            }
+        };
+        ensures old(DiemConfig::spec_has_config()) == DiemConfig::spec_has_config();
+    }
+
     spec module { } // Switch documentation context to module level.
 
     /// # Initialization
@@ -237,6 +263,6 @@ module DiemFramework::DiemVMConfig {
                 old(global<DiemConfig<DiemVMConfig>>(@DiemRoot));
     }
     spec module {
-        apply DiemVMConfigRemainsSame to * except set_gas_constants;
+        apply DiemVMConfigRemainsSame to * except set_gas_constants, set_native_schedule;
     }
 }
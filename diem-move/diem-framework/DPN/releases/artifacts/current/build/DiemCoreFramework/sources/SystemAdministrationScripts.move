@@ -104,6 +104,39 @@ module DiemFramework::SystemAdministrationScripts {
         )
     }
 
+    /// # Summary
+    /// Updates the gas costs of the natives stored on chain and used by the VM for gas
+    /// metering, e.g. to charge for natives added after genesis. This transaction can only be
+    /// sent from the Diem Root account.
+    ///
+    /// # Technical Description
+    /// Replaces the native schedule of the `DiemVMConfig` on-chain config and emits a
+    /// `DiemConfig::NewEpochEvent` to trigger a reconfiguration of the system.
+    ///
+    /// # Parameters
+    /// | Name              | Type         | Description                                                                                   |
+    /// | ------            | ------       | -------------                                                                                 |
+    /// | `account`         | `signer`     | Signer of the sending account. Must be the Diem Root account.                                 |
+    /// | `sliding_nonce`   | `u64`        | The `sliding_nonce` (see: `SlidingNonce`) to be used for this transaction.                    |
+    /// | `native_schedule` | `vector<u8>` | The BCS-serialized gas costs of the natives, indexed by the `NativeCostIndex` used by the VM. |
+    ///
+    /// # Common Abort Conditions
+    /// | Error Category             | Error Reason                                  | Description                                                                                |
+    /// | ----------------           | --------------                                | -------------                                                                              |
+    /// | `Errors::NOT_PUBLISHED`    | `SlidingNonce::ESLIDING_NONCE`                | A `SlidingNonce` resource is not published under `account`.                                |
+    /// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_TOO_OLD`                | The `sliding_nonce` is too old and it's impossible to determine if it's duplicated or not. |
+    /// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_TOO_NEW`                | The `sliding_nonce` is too far in the future.                                              |
+    /// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_ALREADY_RECORDED`       | The `sliding_nonce` has been previously recorded.                                          |
+    /// | `Errors::REQUIRES_ADDRESS` | `CoreAddresses::EDIEM_ROOT`                   | `account` is not the Diem Root account.                                                    |
+    public(script) fun set_gas_native_schedule(
+        dr_account: signer,
+        sliding_nonce: u64,
+        native_schedule: vector<u8>,
+    ) {
+        SlidingNonce::record_nonce_or_abort(&dr_account, sliding_nonce);
+        DiemVMConfig::set_native_schedule(&dr_account, native_schedule)
+    }
+
     ///  # Summary
     /// Initializes the Diem consensus config that is stored on-chain.  This
     /// transaction can only be sent from the Diem Root account.
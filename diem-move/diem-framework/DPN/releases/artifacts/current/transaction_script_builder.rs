@@ -2770,6 +2770,35 @@ pub enum ScriptFunctionCall {
         default_account_size: u64,
     },
 
+    /// # Summary
+    /// Updates the gas costs of the natives stored on chain and used by the VM for gas
+    /// metering, e.g. to charge for natives added after genesis. This transaction can only be
+    /// sent from the Diem Root account.
+    ///
+    /// # Technical Description
+    /// Replaces the native schedule of the `DiemVMConfig` on-chain config and emits a
+    /// `DiemConfig::NewEpochEvent` to trigger a reconfiguration of the system.
+    ///
+    /// # Parameters
+    /// | Name              | Type         | Description                                                                                   |
+    /// | ------            | ------       | -------------                                                                                 |
+    /// | `account`         | `signer`     | Signer of the sending account. Must be the Diem Root account.                                 |
+    /// | `sliding_nonce`   | `u64`        | The `sliding_nonce` (see: `SlidingNonce`) to be used for this transaction.                    |
+    /// | `native_schedule` | `vector<u8>` | The BCS-serialized gas costs of the natives, indexed by the `NativeCostIndex` used by the VM. |
+    ///
+    /// # Common Abort Conditions
+    /// | Error Category             | Error Reason                                  | Description                                                                                |
+    /// | ----------------           | --------------                                | -------------                                                                              |
+    /// | `Errors::NOT_PUBLISHED`    | `SlidingNonce::ESLIDING_NONCE`                | A `SlidingNonce` resource is not published under `account`.                                |
+    /// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_TOO_OLD`                | The `sliding_nonce` is too old and it's impossible to determine if it's duplicated or not. |
+    /// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_TOO_NEW`                | The `sliding_nonce` is too far in the future.                                              |
+    /// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_ALREADY_RECORDED`       | The `sliding_nonce` has been previously recorded.                                          |
+    /// | `Errors::REQUIRES_ADDRESS` | `CoreAddresses::EDIEM_ROOT`                   | `account` is not the Diem Root account.                                                    |
+    SetGasNativeSchedule {
+        sliding_nonce: u64,
+        native_schedule: Bytes,
+    },
+
     /// # Summary
     /// Updates a validator's configuration, and triggers a reconfiguration of the system to update the
     /// validator set with this new validator configuration.  Can only be successfully sent by a
@@ -3665,6 +3694,10 @@ impl ScriptFunctionCall {
                 gas_unit_scaling_factor,
                 default_account_size,
             ),
+            SetGasNativeSchedule {
+                sliding_nonce,
+                native_schedule,
+            } => encode_set_gas_native_schedule_script_function(sliding_nonce, native_schedule),
             SetValidatorConfigAndReconfigure {
                 validator_account,
                 consensus_pubkey,
@@ -5512,6 +5545,48 @@ pub fn encode_set_gas_constants_script_function(
     ))
 }
 
+/// # Summary
+/// Updates the gas costs of the natives stored on chain and used by the VM for gas
+/// metering, e.g. to charge for natives added after genesis. This transaction can only be
+/// sent from the Diem Root account.
+///
+/// # Technical Description
+/// Replaces the native schedule of the `DiemVMConfig` on-chain config and emits a
+/// `DiemConfig::NewEpochEvent` to trigger a reconfiguration of the system.
+///
+/// # Parameters
+/// | Name              | Type         | Description                                                                                   |
+/// | ------            | ------       | -------------                                                                                 |
+/// | `account`         | `signer`     | Signer of the sending account. Must be the Diem Root account.                                 |
+/// | `sliding_nonce`   | `u64`        | The `sliding_nonce` (see: `SlidingNonce`) to be used for this transaction.                    |
+/// | `native_schedule` | `vector<u8>` | The BCS-serialized gas costs of the natives, indexed by the `NativeCostIndex` used by the VM. |
+///
+/// # Common Abort Conditions
+/// | Error Category             | Error Reason                                  | Description                                                                                |
+/// | ----------------           | --------------                                | -------------                                                                              |
+/// | `Errors::NOT_PUBLISHED`    | `SlidingNonce::ESLIDING_NONCE`                | A `SlidingNonce` resource is not published under `account`.                                |
+/// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_TOO_OLD`                | The `sliding_nonce` is too old and it's impossible to determine if it's duplicated or not. |
+/// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_TOO_NEW`                | The `sliding_nonce` is too far in the future.                                              |
+/// | `Errors::INVALID_ARGUMENT` | `SlidingNonce::ENONCE_ALREADY_RECORDED`       | The `sliding_nonce` has been previously recorded.                                          |
+/// | `Errors::REQUIRES_ADDRESS` | `CoreAddresses::EDIEM_ROOT`                   | `account` is not the Diem Root account.                                                    |
+pub fn encode_set_gas_native_schedule_script_function(
+    sliding_nonce: u64,
+    native_schedule: Vec<u8>,
+) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(ScriptFunction::new(
+        ModuleId::new(
+            AccountAddress::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+            ident_str!("SystemAdministrationScripts").to_owned(),
+        ),
+        ident_str!("set_gas_native_schedule").to_owned(),
+        vec![],
+        vec![
+            bcs::to_bytes(&sliding_nonce).unwrap(),
+            bcs::to_bytes(&native_schedule).unwrap(),
+        ],
+    ))
+}
+
 /// # Summary
 /// Updates a validator's configuration, and triggers a reconfiguration of the system to update the
 /// validator set with this new validator configuration.  Can only be successfully sent by a
@@ -8161,6 +8236,19 @@ fn decode_set_gas_constants_script_function(
     }
 }
 
+fn decode_set_gas_native_schedule_script_function(
+    payload: &TransactionPayload,
+) -> Option<ScriptFunctionCall> {
+    if let TransactionPayload::ScriptFunction(script) = payload {
+        Some(ScriptFunctionCall::SetGasNativeSchedule {
+            sliding_nonce: bcs::from_bytes(script.args().get(0)?).ok()?,
+            native_schedule: bcs::from_bytes(script.args().get(1)?).ok()?,
+        })
+    } else {
+        None
+    }
+}
+
 fn decode_set_validator_config_and_reconfigure_script_function(
     payload: &TransactionPayload,
 ) -> Option<ScriptFunctionCall> {
@@ -8831,6 +8919,10 @@ static SCRIPT_FUNCTION_DECODER_MAP: once_cell::sync::Lazy<ScriptFunctionDecoderM
             "SystemAdministrationScriptsset_gas_constants".to_string(),
             Box::new(decode_set_gas_constants_script_function),
         );
+        map.insert(
+            "SystemAdministrationScriptsset_gas_native_schedule".to_string(),
+            Box::new(decode_set_gas_native_schedule_script_function),
+        );
         map.insert(
             "ValidatorAdministrationScriptsset_validator_config_and_reconfigure".to_string(),
             Box::new(decode_set_validator_config_and_reconfigure_script_function),
@@ -19,6 +19,7 @@ fn run_tests_for_pkg(path_to_pkg: impl Into<String>) {
         UnitTestingConfig::default_with_bound(Some(100_000)),
         diem_natives(),
         /* compute_coverage */ false,
+        /* debug */ false,
     )
     .unwrap();
 }
@@ -24,6 +24,7 @@ impl DBDebuggerInterface {
             db_root_path,
             true,
             None,
+            None, /* ledger_prune_window */
             RocksdbConfig::default(),
             true, /* account_count_migration, ignored anyway */
         )?)))
@@ -1,7 +1,9 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::*, create_access_path, data_cache::StateViewCache};
+use crate::{
+    counters::*, create_access_path, data_cache::StateViewCache, diem_vm_impl::SessionId,
+};
 use anyhow::Result;
 use diem_state_view::StateView;
 use diem_types::{
@@ -32,10 +34,14 @@ use std::collections::HashSet;
 /// This trait describes the VM adapter's interface.
 /// TODO: bring more of the execution logic in diem_vm into this file.
 pub trait VMAdapter {
-    /// Creates a new Session backed by the given storage.
+    /// Creates a new Session backed by the given storage and identified by `session_id`.
     /// TODO: this doesn't belong in this trait. We should be able to remove
     /// this after redesigning cache ownership model.
-    fn new_session<'r, R: MoveResolver>(&self, remote: &'r R) -> Session<'r, '_, R>;
+    fn new_session<'r, R: MoveResolver>(
+        &self,
+        remote: &'r R,
+        session_id: SessionId,
+    ) -> Session<'r, '_, R>;
 
     /// Checks the signature of the given signed transaction and returns
     /// `Ok(SignatureCheckedTransaction)` if the signature is valid.
@@ -98,7 +104,7 @@ pub fn validate_signed_transaction<A: VMAdapter>(
 
     let remote_cache = StateViewCache::new(state_view);
     let account_role = get_account_role(txn_sender, &remote_cache);
-    let mut session = adapter.new_session(&remote_cache);
+    let mut session = adapter.new_session(&remote_cache, SessionId::txn(&txn));
 
     let (status, gas_price) = match adapter.get_gas_price(&*txn, &remote_cache) {
         Ok(price) => (None, price),
@@ -17,8 +17,8 @@ use fail::fail_point;
 use move_binary_format::errors::*;
 use move_core_types::{
     account_address::AccountAddress,
-    language_storage::{ModuleId, StructTag},
-    resolver::{ModuleResolver, ResourceResolver},
+    language_storage::{ModuleId, StructTag, TableHandle},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
 };
 use std::collections::btree_map::BTreeMap;
 
@@ -127,6 +127,18 @@ impl<'block, S: StateView> ResourceResolver for StateViewCache<'block, S> {
     }
 }
 
+impl<'block, S: StateView> TableResolver for StateViewCache<'block, S> {
+    type Error = VMError;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        RemoteStorage::new(self).get_table_item(handle, key)
+    }
+}
+
 impl<'block, S: StateView> ConfigStorage for StateViewCache<'block, S> {
     fn fetch_config(&self, access_path: AccessPath) -> Option<Vec<u8>> {
         self.get(&access_path).ok()?
@@ -171,6 +183,19 @@ impl<'a, S: StateView> ResourceResolver for RemoteStorage<'a, S> {
     }
 }
 
+impl<'a, S: StateView> TableResolver for RemoteStorage<'a, S> {
+    type Error = VMError;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        let ap = AccessPath::table_item_access_path(*handle, key.to_vec());
+        self.get(&ap).map_err(|e| e.finish(Location::Undefined))
+    }
+}
+
 impl<'a, S: StateView> ConfigStorage for RemoteStorage<'a, S> {
     fn fetch_config(&self, access_path: AccessPath) -> Option<Vec<u8>> {
         self.get(&access_path).ok()?
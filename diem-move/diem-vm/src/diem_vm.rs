@@ -11,7 +11,7 @@ use crate::{
     data_cache::{RemoteStorage, StateViewCache},
     diem_vm_impl::{
         charge_global_write_gas_usage, convert_changeset_and_events, get_currency_info,
-        get_gas_currency_code, get_transaction_output, DiemVMImpl, DiemVMInternals,
+        get_gas_currency_code, get_transaction_output, DiemVMImpl, DiemVMInternals, SessionId,
     },
     errors::expect_only_successful_execution,
     logging::AdapterLogSchema,
@@ -27,8 +27,8 @@ use diem_types::{
     account_config,
     block_metadata::BlockMetadata,
     on_chain_config::{
-        DiemVersion, OnChainConfig, ParallelExecutionConfig, VMConfig, VMPublishingOption,
-        DIEM_VERSION_2, DIEM_VERSION_3,
+        DiemVersion, OnChainConfig, ParallelExecutionConfig, ReadWriteSetAnalysis, VMConfig,
+        VMPublishingOption, DIEM_VERSION_2, DIEM_VERSION_3,
     },
     transaction::{
         ChangeSet, ModuleBundle, SignatureCheckedTransaction, SignedTransaction, Transaction,
@@ -126,7 +126,7 @@ impl DiemVM {
         log_context: &AdapterLogSchema,
     ) -> (VMStatus, TransactionOutput) {
         gas_status.set_metering(false);
-        let mut session = self.0.new_session(storage);
+        let mut session = self.0.new_session(storage, SessionId::epilogue(txn_data));
         match TransactionStatus::from(error_code.clone()) {
             TransactionStatus::Keep(status) => {
                 // The transaction should be charged for gas, so run the epilogue to do that.
@@ -345,7 +345,7 @@ impl DiemVM {
         }
 
         // Revalidate the transaction.
-        let mut session = self.0.new_session(storage);
+        let mut session = self.0.new_session(storage, SessionId::txn(txn));
         if let Err(err) = validate_signature_checked_transaction::<S, Self>(
             self,
             &mut session,
@@ -415,13 +415,14 @@ impl DiemVM {
         storage: &S,
         writeset_payload: &WriteSetPayload,
         txn_sender: Option<AccountAddress>,
+        session_id: SessionId,
     ) -> Result<ChangeSet, Result<(VMStatus, TransactionOutput), VMStatus>> {
         let mut gas_status = GasStatus::new_unmetered();
 
         Ok(match writeset_payload {
             WriteSetPayload::Direct(change_set) => change_set.clone(),
             WriteSetPayload::Script { script, execute_as } => {
-                let mut tmp_session = self.0.new_session(storage);
+                let mut tmp_session = self.0.new_session(storage, session_id);
                 let diem_version = self.0.get_diem_version().map_err(Err)?;
                 let senders = match txn_sender {
                     None => vec![*execute_as],
@@ -488,7 +489,12 @@ impl DiemVM {
         storage: &S,
         writeset_payload: WriteSetPayload,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
-        let change_set = match self.execute_writeset(storage, &writeset_payload, None) {
+        let change_set = match self.execute_writeset(
+            storage,
+            &writeset_payload,
+            None,
+            SessionId::write_set(&writeset_payload),
+        ) {
             Ok(cs) => cs,
             Err(e) => return e,
         };
@@ -518,7 +524,7 @@ impl DiemVM {
             ..Default::default()
         };
         let mut gas_status = GasStatus::new_unmetered();
-        let mut session = self.0.new_session(storage);
+        let mut session = self.0.new_session(storage, SessionId::block_meta(&block_metadata));
 
         let (round, timestamp, previous_vote, proposer) = block_metadata.into_inner();
         let args = serialize_values(&vec![
@@ -576,7 +582,7 @@ impl DiemVM {
         }
 
         // Revalidate the transaction.
-        let mut session = self.0.new_session(storage);
+        let mut session = self.0.new_session(storage, SessionId::txn(txn));
         if let Err(e) = validate_signature_checked_transaction::<S, Self>(
             self,
             &mut session,
@@ -612,14 +618,18 @@ impl DiemVM {
         txn_data: TransactionMetadata,
         log_context: &AdapterLogSchema,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
-        let change_set =
-            match self.execute_writeset(storage, writeset_payload, Some(txn_data.sender())) {
-                Ok(change_set) => change_set,
-                Err(e) => return e,
-            };
+        let change_set = match self.execute_writeset(
+            storage,
+            writeset_payload,
+            Some(txn_data.sender()),
+            SessionId::txn_meta(&txn_data),
+        ) {
+            Ok(change_set) => change_set,
+            Err(e) => return e,
+        };
 
         // Run the epilogue function.
-        let mut session = self.0.new_session(storage);
+        let mut session = self.0.new_session(storage, SessionId::epilogue(&txn_data));
         self.0.run_writeset_epilogue(
             &mut session,
             &txn_data,
@@ -709,6 +719,20 @@ impl DiemVM {
         BLOCK_TRANSACTION_COUNT.observe(count as f64);
         Ok(res)
     }
+
+    /// Executes a user transaction against `state_view` without verifying its signature, so that
+    /// clients can estimate the gas usage and inspect failures before signing the transaction.
+    /// The output must never be committed.
+    pub fn simulate_signed_transaction(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        let data_cache = StateViewCache::new(state_view);
+        let vm = DiemVM::new(&data_cache);
+        let log_context = AdapterLogSchema::new(data_cache.id(), 0);
+        let txn = SignatureCheckedTransaction::new_unchecked_for_simulation(txn.clone());
+        vm.execute_user_transaction(&data_cache, &txn, &log_context)
+    }
 }
 
 // Executor external API
@@ -729,27 +753,34 @@ impl VMExecutor for DiemVM {
         });
 
         // Execute transactions in parallel if on chain config is set and loaded.
-        if let Some(read_write_set_analysis) =
-            ParallelExecutionConfig::fetch_config(&RemoteStorage::new(state_view))
-                .and_then(|config| config.read_write_analysis_result)
-                .map(|config| config.into_inner())
+        match ParallelExecutionConfig::fetch_config(&RemoteStorage::new(state_view))
+            .and_then(|config| config.read_write_analysis_result)
         {
-            let analysis_reuslt = NormalizedReadWriteSetAnalysis::new(read_write_set_analysis);
-
-            // Note that writeset transactions will be executed sequentially as it won't be inferred
-            // by the read write set analysis and thus fall into the sequential path.
-            let (result, _) = crate::parallel_executor::ParallelDiemVM::execute_block(
-                &analysis_reuslt,
-                transactions,
-                state_view,
-            )?;
-            Ok(result)
-        } else {
-            let output = Self::execute_block_and_keep_vm_status(transactions, state_view)?;
-            Ok(output
-                .into_iter()
-                .map(|(_vm_status, txn_output)| txn_output)
-                .collect())
+            Some(ReadWriteSetAnalysis::V1(read_write_set_analysis)) => {
+                let analysis_reuslt = NormalizedReadWriteSetAnalysis::new(read_write_set_analysis);
+
+                // Note that writeset transactions will be executed sequentially as it won't be
+                // inferred by the read write set analysis and thus fall into the sequential path.
+                let (result, _) = crate::parallel_executor::ParallelDiemVM::execute_block(
+                    &analysis_reuslt,
+                    transactions,
+                    state_view,
+                )?;
+                Ok(result)
+            }
+            Some(ReadWriteSetAnalysis::Dynamic) => {
+                crate::parallel_executor::ParallelDiemVM::execute_block_dynamic(
+                    transactions,
+                    state_view,
+                )
+            }
+            None => {
+                let output = Self::execute_block_and_keep_vm_status(transactions, state_view)?;
+                Ok(output
+                    .into_iter()
+                    .map(|(_vm_status, txn_output)| txn_output)
+                    .collect())
+            }
         }
     }
 }
@@ -777,8 +808,12 @@ impl VMValidator for DiemVM {
 }
 
 impl VMAdapter for DiemVM {
-    fn new_session<'r, R: MoveResolver>(&self, remote: &'r R) -> Session<'r, '_, R> {
-        self.0.new_session(remote)
+    fn new_session<'r, R: MoveResolver>(
+        &self,
+        remote: &'r R,
+        session_id: SessionId,
+    ) -> Session<'r, '_, R> {
+        self.0.new_session(remote, session_id)
     }
 
     fn check_signature(txn: SignedTransaction) -> Result<SignatureCheckedTransaction> {
@@ -15,6 +15,7 @@ use diem_crypto::HashValue;
 use diem_logger::prelude::*;
 use diem_state_view::StateView;
 use diem_types::{
+    access_path::AccessPath,
     account_config,
     account_config::CurrencyInfoResource,
     contract_event::ContractEvent,
@@ -22,7 +23,8 @@ use diem_types::{
     on_chain_config::{
         ConfigStorage, DiemVersion, OnChainConfig, VMConfig, VMPublishingOption, DIEM_VERSION_3,
     },
-    transaction::{SignedTransaction, TransactionOutput, TransactionStatus},
+    block_metadata::BlockMetadata,
+    transaction::{SignedTransaction, TransactionOutput, TransactionStatus, WriteSetPayload},
     vm_status::{KeptVMStatus, StatusCode, VMStatus},
     write_set::{WriteOp, WriteSet, WriteSetMut},
 };
@@ -39,8 +41,68 @@ use move_core_types::{
 };
 use move_vm_runtime::{logging::expect_no_verification_errors, move_vm::MoveVM, session::Session};
 use move_vm_types::gas_schedule::{calculate_intrinsic_gas, GasStatus};
+use serde::Serialize;
 use std::{convert::TryFrom, sync::Arc};
 
+/// Identifies the sessions whose effects are committed, so that the tables created in any two of
+/// them get distinct handles.
+#[derive(Serialize)]
+pub enum SessionId {
+    /// The session validating and executing a transaction, or the write set script of a write set
+    /// transaction.
+    Txn {
+        sender: AccountAddress,
+        sequence_number: u64,
+    },
+    /// The session running the epilogue of a transaction after its main session, either to
+    /// charge a failed transaction for gas or to finish a write set transaction.
+    Epilogue {
+        sender: AccountAddress,
+        sequence_number: u64,
+    },
+    /// The session running the prologue of the block with the given id.
+    BlockMeta { id: HashValue },
+    /// The session executing a write set script that isn't sent by any account, e.g. at a
+    /// waypoint, identified by the hash of its payload.
+    WriteSet { id: HashValue },
+}
+
+impl SessionId {
+    pub fn txn(txn: &SignedTransaction) -> Self {
+        Self::Txn {
+            sender: txn.sender(),
+            sequence_number: txn.sequence_number(),
+        }
+    }
+
+    pub fn txn_meta(txn_data: &TransactionMetadata) -> Self {
+        Self::Txn {
+            sender: txn_data.sender(),
+            sequence_number: txn_data.sequence_number(),
+        }
+    }
+
+    pub fn epilogue(txn_data: &TransactionMetadata) -> Self {
+        Self::Epilogue {
+            sender: txn_data.sender(),
+            sequence_number: txn_data.sequence_number(),
+        }
+    }
+
+    pub fn block_meta(block_metadata: &BlockMetadata) -> Self {
+        Self::BlockMeta {
+            id: block_metadata.id(),
+        }
+    }
+
+    pub fn write_set(writeset_payload: &WriteSetPayload) -> Self {
+        let bytes = bcs::to_bytes(writeset_payload).expect("Unexpected serialization error");
+        Self::WriteSet {
+            id: HashValue::sha3_256_of(&bytes),
+        }
+    }
+}
+
 #[derive(Clone)]
 /// A wrapper to make VMRuntime standalone and thread safe.
 pub struct DiemVMImpl {
@@ -454,8 +516,15 @@ impl DiemVMImpl {
             })
     }
 
-    pub fn new_session<'r, R: MoveResolver>(&self, r: &'r R) -> Session<'r, '_, R> {
-        self.move_vm.new_session(r)
+    /// Create a session whose tables get handles derived from `session_id`, which must be unique
+    /// among all the sessions whose effects are committed.
+    pub fn new_session<'r, R: MoveResolver>(
+        &self,
+        r: &'r R,
+        session_id: SessionId,
+    ) -> Session<'r, '_, R> {
+        let session_id = bcs::to_bytes(&session_id).expect("Unexpected serialization error");
+        self.move_vm.new_session_with_id(r, session_id)
     }
 
     pub fn load_module<'r, R: MoveResolver>(
@@ -515,7 +584,8 @@ pub fn convert_changeset_and_events_cached<C: AccessPathCache>(
     // TODO: Cache access path computations if necessary.
     let mut ops = vec![];
 
-    for (addr, account_changeset) in changeset.into_inner() {
+    let (accounts, tables) = changeset.into_inner();
+    for (addr, account_changeset) in accounts {
         let (modules, resources) = account_changeset.into_inner();
         for (struct_tag, blob_opt) in resources {
             let ap = ap_cache.get_resource_path(addr, struct_tag);
@@ -537,6 +607,18 @@ pub fn convert_changeset_and_events_cached<C: AccessPathCache>(
         }
     }
 
+    for (handle, table_changeset) in tables {
+        for (key, blob_opt) in table_changeset {
+            let ap = AccessPath::table_item_access_path(handle, key);
+            let op = match blob_opt {
+                None => WriteOp::Deletion,
+                Some(blob) => WriteOp::Value(blob),
+            };
+
+            ops.push((ap, op))
+        }
+    }
+
     let ws = WriteSetMut::new(ops)
         .freeze()
         .map_err(|_| VMStatus::Error(StatusCode::DATA_FORMAT_ERROR))?;
@@ -14,14 +14,15 @@ use crate::{
     },
 };
 use diem_parallel_executor::{
+    dynamic_executor::DynamicParallelTransactionExecutor,
     errors::Error,
     executor::ParallelTransactionExecutor,
     task::{Transaction as PTransaction, TransactionOutput as PTransactionOutput},
 };
 use diem_state_view::StateView;
 use diem_types::{
-    access_path::AccessPath,
-    transaction::{Transaction, TransactionOutput, TransactionStatus},
+    access_path::{AccessPath, Path},
+    transaction::{Transaction, TransactionOutput, TransactionPayload, TransactionStatus},
     write_set::{WriteOp, WriteSet},
 };
 use move_core_types::vm_status::{StatusCode, VMStatus};
@@ -113,4 +114,75 @@ impl ParallelDiemVM {
             Err(Error::UserError(err)) => Err(err),
         }
     }
+
+    /// Execute a block in parallel without a read write set analysis: conflicts between
+    /// transactions are detected while executing them, so every transaction, including the ones
+    /// the analysis can't cover, is executed in parallel.
+    ///
+    /// Blocks that publish modules are executed sequentially. The VM of every worker caches the
+    /// modules it loads, and loading a cached module is not recorded as a read of it, so a
+    /// transaction could keep using a module published by an execution that was aborted.
+    pub fn execute_block_dynamic<S: StateView>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        if transactions.iter().any(may_publish_modules) {
+            return Self::execute_block_sequentially(transactions, state_view);
+        }
+
+        let signature_verified_block: Vec<PreprocessedTransaction> = transactions
+            .par_iter()
+            .map(|txn| preprocess_transaction::<DiemVM>(txn.clone()))
+            .collect();
+
+        match DynamicParallelTransactionExecutor::<PreprocessedTransaction, DiemVMWrapper<S>>::new()
+            .execute_transactions_parallel(state_view, signature_verified_block)
+        {
+            Ok(results) => {
+                let outputs: Vec<TransactionOutput> = results
+                    .into_iter()
+                    .map(DiemTransactionOutput::into)
+                    .collect();
+                // Only the transactions checked above are expected to publish modules
+                if outputs.iter().any(writes_code) {
+                    return Self::execute_block_sequentially(transactions, state_view);
+                }
+                Ok(outputs)
+            }
+            Err(Error::UserError(err)) => Err(err),
+            Err(_) => Err(VMStatus::Error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+            )),
+        }
+    }
+
+    fn execute_block_sequentially<S: StateView>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let output = DiemVM::execute_block_and_keep_vm_status(transactions, state_view)?;
+        Ok(output
+            .into_iter()
+            .map(|(_vm_status, txn_output)| txn_output)
+            .collect())
+    }
+}
+
+/// Whether `txn` can publish modules: module bundles, and write sets that can write any path.
+fn may_publish_modules(txn: &Transaction) -> bool {
+    match txn {
+        Transaction::UserTransaction(txn) => matches!(
+            txn.payload(),
+            TransactionPayload::ModuleBundle(_) | TransactionPayload::WriteSet(_)
+        ),
+        Transaction::GenesisTransaction(_) => true,
+        Transaction::BlockMetadata(_) => false,
+    }
+}
+
+fn writes_code(output: &TransactionOutput) -> bool {
+    output
+        .write_set()
+        .iter()
+        .any(|(access_path, _)| matches!(access_path.get_path(), Path::Code(_)))
 }
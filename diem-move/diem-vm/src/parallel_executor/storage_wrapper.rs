@@ -8,8 +8,8 @@ use diem_types::{access_path::AccessPath, write_set::WriteOp};
 use move_binary_format::errors::VMError;
 use move_core_types::{
     account_address::AccountAddress,
-    language_storage::{ModuleId, StructTag},
-    resolver::{ModuleResolver, ResourceResolver},
+    language_storage::{ModuleId, StructTag, TableHandle},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
 };
 
 pub(crate) struct VersionedView<'a, S: StateView> {
@@ -37,8 +37,10 @@ impl<'a, S: StateView> StateView for VersionedView<'a, S> {
     // Get some data either through the cache or the `StateView` on a cache miss.
     fn get(&self, access_path: &AccessPath) -> anyhow::Result<Option<Vec<u8>>> {
         match self.hashmap_view.read(access_path) {
-            Ok(Some(WriteOp::Value(v))) => Ok(Some(v.clone())),
-            Ok(Some(WriteOp::Deletion)) => Ok(None),
+            Ok(Some(write_op)) => match write_op.as_ref() {
+                WriteOp::Value(v) => Ok(Some(v.clone())),
+                WriteOp::Deletion => Ok(None),
+            },
             Ok(None) => self.base_view.get(access_path),
             Err(err) => Err(err),
         }
@@ -68,3 +70,15 @@ impl<'a, S: StateView> ResourceResolver for VersionedView<'a, S> {
         RemoteStorage::new(self).get_resource(address, tag)
     }
 }
+
+impl<'a, S: StateView> TableResolver for VersionedView<'a, S> {
+    type Error = VMError;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        RemoteStorage::new(self).get_table_item(handle, key)
+    }
+}
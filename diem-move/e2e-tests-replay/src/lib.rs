@@ -240,6 +240,9 @@ impl<'env> CrossRunner<'env> {
                     let inst = struct_ty.into_struct_inst();
                     stackless_vm_state.put_resource(ap.address, inst, resource);
                 }
+                // NOTE: the stackless VM does not model tables, hence table items are only kept in
+                // the state of the Move VM
+                AP::TableItem { .. } => (),
             }
         }
         for module_env in env.get_modules() {
@@ -1001,7 +1004,7 @@ fn replay_trace<P: AsRef<Path>>(
                                         }
                                         return Ok(());
                                     }
-                                    AP::Resource(_) => (),
+                                    AP::Resource(_) | AP::TableItem { .. } => (),
                                 }
                             }
                             replayer.data_store.add_write_set(res.write_set());
@@ -0,0 +1,107 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::{btree_map::BTreeMap, HashMap},
+    hash::Hash,
+    sync::{Arc, RwLock},
+};
+
+/// Index of a transaction in the block.
+pub type TxnIndex = usize;
+/// How many times a transaction was re-executed before this execution.
+pub type Incarnation = usize;
+
+/// A multi-version map whose entries are created while transactions are executed, for executing
+/// transactions without knowing their write sets upfront.
+//
+//  Unlike `MVHashMap`, an entry can be written several times: every re-execution (incarnation) of
+//  a transaction overwrites the entries of its previous incarnation, and removes the ones it no
+//  longer writes. When a transaction is about to be re-executed, its entries are marked as
+//  estimates, so that transactions reading them wait for the re-execution instead of reading a
+//  value that is likely to change.
+//
+pub struct DynamicMVHashMap<K, V> {
+    data: RwLock<HashMap<K, Arc<RwLock<BTreeMap<TxnIndex, Entry<V>>>>>>,
+}
+
+enum Entry<V> {
+    /// The value written by an incarnation of the transaction.
+    Write(Incarnation, Arc<V>),
+    /// The transaction is being re-executed, and will likely write to the entry again.
+    Estimate,
+}
+
+impl<K: Hash + Clone + Eq, V> DynamicMVHashMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            data: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn versions(&self, key: &K) -> Option<Arc<RwLock<BTreeMap<TxnIndex, Entry<V>>>>> {
+        self.data.read().unwrap().get(key).cloned()
+    }
+
+    /// Write `data` to `key` at `version`, replacing the write of a prior incarnation.
+    pub fn write(&self, key: &K, version: (TxnIndex, Incarnation), data: V) {
+        let versions = match self.versions(key) {
+            Some(versions) => versions,
+            None => self
+                .data
+                .write()
+                .unwrap()
+                .entry(key.clone())
+                .or_default()
+                .clone(),
+        };
+        let (txn_idx, incarnation) = version;
+        versions
+            .write()
+            .unwrap()
+            .insert(txn_idx, Entry::Write(incarnation, Arc::new(data)));
+    }
+
+    /// Mark the write of transaction `txn_idx` to `key` as an estimate of its next incarnation.
+    pub fn mark_estimate(&self, key: &K, txn_idx: TxnIndex) {
+        if let Some(versions) = self.versions(key) {
+            if let Some(entry) = versions.write().unwrap().get_mut(&txn_idx) {
+                *entry = Entry::Estimate;
+            }
+        }
+    }
+
+    /// Remove the write of transaction `txn_idx` to `key`.
+    pub fn delete(&self, key: &K, txn_idx: TxnIndex) {
+        if let Some(versions) = self.versions(key) {
+            versions.write().unwrap().remove(&txn_idx);
+        }
+    }
+
+    /// Get the value of `key` as seen by transaction `txn_idx`, i.e., the last write of a prior
+    /// transaction.
+    /// Returns Ok((version, val)) with the version of the transaction that wrote the value.
+    /// Returns Err(None) if no prior transaction wrote to `key`.
+    /// Returns Err(Some(idx)) if the last write is an estimate of transaction `idx`.
+    pub fn read(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+    ) -> Result<((TxnIndex, Incarnation), Arc<V>), Option<TxnIndex>> {
+        let versions = self.versions(key).ok_or(None)?;
+        let versions = versions.read().unwrap();
+        match versions.range(0..txn_idx).next_back() {
+            Some((idx, Entry::Write(incarnation, data))) => {
+                Ok(((*idx, *incarnation), data.clone()))
+            }
+            Some((idx, Entry::Estimate)) => Err(Some(*idx)),
+            None => Err(None),
+        }
+    }
+}
+
+impl<K: Hash + Clone + Eq, V> Default for DynamicMVHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
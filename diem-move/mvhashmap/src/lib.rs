@@ -9,6 +9,7 @@ use std::{
     hash::Hash,
 };
 
+pub mod dynamic;
 #[cfg(test)]
 mod unit_tests;
 
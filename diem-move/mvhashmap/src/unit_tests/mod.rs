@@ -56,3 +56,38 @@ fn create_write_read_placeholder_struct() {
     let r1 = mvtbl.read(&ap2, 25);
     assert_eq!(Ok(&Some(vec![0, 0, 0])), r1);
 }
+
+#[test]
+fn dynamic_write_read_estimate() {
+    let ap1 = b"/foo/b".to_vec();
+    let ap2 = b"/foo/c".to_vec();
+
+    let mvtbl = dynamic::DynamicMVHashMap::new();
+
+    // Reads of keys no transaction wrote to go to the DB.
+    assert_eq!(Err(None), mvtbl.read(&ap1, 5).map(|(v, _)| v));
+
+    mvtbl.write(&ap1, (10, 0), vec![0, 0, 0]);
+
+    // Reads at a version return the previous versions, not this version.
+    assert_eq!(Err(None), mvtbl.read(&ap1, 10).map(|(v, _)| v));
+    let (version, value) = mvtbl.read(&ap1, 15).unwrap();
+    assert_eq!((10, 0), version);
+    assert_eq!(vec![0, 0, 0], *value);
+
+    // Estimates block the reads of higher transactions until the next incarnation writes.
+    mvtbl.mark_estimate(&ap1, 10);
+    assert_eq!(Err(Some(10)), mvtbl.read(&ap1, 15).map(|(v, _)| v));
+    mvtbl.write(&ap1, (10, 1), vec![1, 1, 1]);
+    let (version, value) = mvtbl.read(&ap1, 15).unwrap();
+    assert_eq!((10, 1), version);
+    assert_eq!(vec![1, 1, 1], *value);
+
+    // Reads skip deleted entries.
+    mvtbl.write(&ap2, (10, 0), vec![0]);
+    mvtbl.write(&ap2, (20, 0), vec![2]);
+    mvtbl.delete(&ap2, 20);
+    let (version, value) = mvtbl.read(&ap2, 25).unwrap();
+    assert_eq!((10, 0), version);
+    assert_eq!(vec![0], *value);
+}
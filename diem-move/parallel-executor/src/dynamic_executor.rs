@@ -0,0 +1,223 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dynamic_scheduler::{DynamicScheduler, SchedulerTask, Version},
+    errors::*,
+    executor::{MVHashMapView, ReadDescriptor},
+    task::{ExecutionStatus, ExecutorTask, Transaction, TransactionOutput},
+};
+use mvhashmap::dynamic::{DynamicMVHashMap, TxnIndex};
+use rayon::scope;
+use std::{
+    cmp::min,
+    collections::HashSet,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+/// The reads, written keys and output of the last execution of every transaction.
+struct TxnLastInputOutput<K, O, E> {
+    reads: Vec<Mutex<Arc<Vec<ReadDescriptor<K>>>>>,
+    written_keys: Vec<Mutex<HashSet<K>>>,
+    outputs: Vec<Mutex<Option<ExecutionStatus<O, E>>>>,
+}
+
+impl<K: Hash + Clone + Eq, O: TransactionOutput, E> TxnLastInputOutput<K, O, E> {
+    fn new(num_txns: usize) -> Self {
+        Self {
+            reads: (0..num_txns)
+                .map(|_| Mutex::new(Arc::new(Vec::new())))
+                .collect(),
+            written_keys: (0..num_txns).map(|_| Mutex::new(HashSet::new())).collect(),
+            outputs: (0..num_txns).map(|_| Mutex::new(None)).collect(),
+        }
+    }
+
+    fn reads(&self, txn_idx: TxnIndex) -> Arc<Vec<ReadDescriptor<K>>> {
+        self.reads[txn_idx].lock().unwrap().clone()
+    }
+
+    fn take_outputs(self) -> Vec<Option<ExecutionStatus<O, E>>> {
+        self.outputs
+            .into_iter()
+            .map(|output| output.into_inner().unwrap())
+            .collect()
+    }
+}
+
+/// Executes the transactions of a block in parallel without knowing their read and write sets
+/// upfront.
+///
+/// Transactions are executed optimistically against the writes of the lower transactions
+/// executed so far, and the reads of every execution are recorded. After its execution, a
+/// transaction is validated by checking that its reads would still return the same values; if
+/// not, the transaction is executed again. The result is the same as executing the transactions
+/// sequentially.
+pub struct DynamicParallelTransactionExecutor<T: Transaction, E: ExecutorTask> {
+    num_cpus: usize,
+    phantom: PhantomData<(T, E)>,
+}
+
+impl<T, E> DynamicParallelTransactionExecutor<T, E>
+where
+    T: Transaction,
+    E: ExecutorTask<T = T>,
+{
+    pub fn new() -> Self {
+        Self {
+            num_cpus: num_cpus::get(),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn execute_transactions_parallel(
+        &self,
+        task_initial_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+    ) -> Result<Vec<E::Output>, E::Error> {
+        if signature_verified_block.is_empty() {
+            return Ok(vec![]);
+        }
+        let num_txns = signature_verified_block.len();
+
+        let versioned_data_cache = DynamicMVHashMap::new();
+        let last_input_output = TxnLastInputOutput::new(num_txns);
+        let scheduler = DynamicScheduler::new(num_txns);
+
+        scope(|s| {
+            for _ in 0..min(self.num_cpus, num_txns) {
+                s.spawn(|_| {
+                    // Make a new executor per thread.
+                    let task = E::init(task_initial_arguments);
+
+                    let mut scheduler_task = None;
+                    while !scheduler.done() {
+                        scheduler_task = match scheduler_task {
+                            Some(SchedulerTask::Execution(version)) => Self::execute(
+                                version,
+                                &signature_verified_block[version.0],
+                                &task,
+                                &versioned_data_cache,
+                                &last_input_output,
+                                &scheduler,
+                            ),
+                            Some(SchedulerTask::Validation(version)) => Self::validate(
+                                version,
+                                &versioned_data_cache,
+                                &last_input_output,
+                                &scheduler,
+                            ),
+                            None => scheduler.next_task(),
+                        };
+                    }
+                });
+            }
+        });
+
+        let outputs = last_input_output.take_outputs();
+
+        // Dropping large structures is expensive -- do this is a separate thread.
+        ::std::thread::spawn(move || {
+            drop(signature_verified_block); // Explicit drops to measure their cost.
+            drop(versioned_data_cache);
+        });
+
+        // Every transaction has been executed and validated, so the outputs are the ones of a
+        // sequential execution, up to the first transaction that aborts or skips the rest.
+        let mut final_results = Vec::with_capacity(num_txns);
+        for output in outputs {
+            match output {
+                Some(ExecutionStatus::Success(t)) => final_results.push(t),
+                Some(ExecutionStatus::SkipRest(t)) => {
+                    final_results.push(t);
+                    break;
+                }
+                Some(ExecutionStatus::Abort(err)) => return Err(Error::UserError(err)),
+                None => return Err(Error::InvariantViolation),
+            }
+        }
+        final_results.resize_with(num_txns, E::Output::skip_output);
+        Ok(final_results)
+    }
+
+    fn execute(
+        version: Version,
+        txn: &T,
+        task: &E,
+        versioned_data_cache: &DynamicMVHashMap<T::Key, T::Value>,
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        scheduler: &DynamicScheduler,
+    ) -> Option<SchedulerTask> {
+        let (idx, incarnation) = version;
+        let view = MVHashMapView::new_dynamic(versioned_data_cache, idx, scheduler);
+        let execute_result = task.execute_transaction(&view, txn);
+        if view.has_unexpected_read() {
+            // The transaction waits on a dependency in the scheduler, which will hand out its
+            // execution again once the dependency is executed.
+            return None;
+        }
+
+        let writes = match &execute_result {
+            ExecutionStatus::Success(output) | ExecutionStatus::SkipRest(output) => {
+                output.get_writes()
+            }
+            ExecutionStatus::Abort(_) => vec![],
+        };
+        let mut written_keys = last_input_output.written_keys[idx].lock().unwrap();
+        let prev_written_keys = std::mem::take(&mut *written_keys);
+        let mut wrote_new_key = false;
+        for (k, v) in writes {
+            wrote_new_key |= !prev_written_keys.contains(&k);
+            written_keys.insert(k.clone());
+            versioned_data_cache.write(&k, version, v);
+        }
+        for k in prev_written_keys.iter() {
+            if !written_keys.contains(k) {
+                versioned_data_cache.delete(k, idx);
+            }
+        }
+        drop(written_keys);
+
+        *last_input_output.reads[idx].lock().unwrap() = Arc::new(view.take_reads());
+        *last_input_output.outputs[idx].lock().unwrap() = Some(execute_result);
+        scheduler.finish_execution(idx, incarnation, wrote_new_key)
+    }
+
+    fn validate(
+        version: Version,
+        versioned_data_cache: &DynamicMVHashMap<T::Key, T::Value>,
+        last_input_output: &TxnLastInputOutput<T::Key, E::Output, E::Error>,
+        scheduler: &DynamicScheduler,
+    ) -> Option<SchedulerTask> {
+        let (idx, incarnation) = version;
+        let read_set_valid = last_input_output.reads(idx).iter().all(|read| {
+            match versioned_data_cache.read(&read.key, idx) {
+                Ok((version, _)) => read.version == Some(version),
+                Err(None) => read.version.is_none(),
+                Err(Some(_)) => false,
+            }
+        });
+
+        let aborted = !read_set_valid && scheduler.try_validation_abort(idx, incarnation);
+        if aborted {
+            // Make the transactions reading the writes of the aborted execution wait for the
+            // re-execution.
+            for k in last_input_output.written_keys[idx].lock().unwrap().iter() {
+                versioned_data_cache.mark_estimate(k, idx);
+            }
+        }
+        scheduler.finish_validation(idx, aborted)
+    }
+}
+
+impl<T, E> Default for DynamicParallelTransactionExecutor<T, E>
+where
+    T: Transaction,
+    E: ExecutorTask<T = T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use mvhashmap::dynamic::{Incarnation, TxnIndex};
+use std::{
+    cmp::min,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+/// A transaction and the incarnation of it to execute or validate.
+pub type Version = (TxnIndex, Incarnation);
+
+pub enum SchedulerTask {
+    Execution(Version),
+    Validation(Version),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TransactionStatus {
+    ReadyToExecute(Incarnation),
+    Executing(Incarnation),
+    Executed(Incarnation),
+    Aborting(Incarnation),
+}
+
+/// Schedules the executions and validations of the transactions of a block, where transactions
+/// are executed optimistically and validated after their execution.
+//
+//  Executions and validations are handed out in the order of the transactions, from two shared
+//  indices. A transaction whose execution reads an estimate of a lower transaction waits on that
+//  transaction, and is resumed once that transaction finishes executing. A transaction whose
+//  reads changed since its execution is aborted and re-executed as a new incarnation, and all the
+//  higher transactions are validated again.
+//
+//  The block is done once both indices reached the end of the block and no task is in flight:
+//  every transaction then has been executed and its last execution validated.
+//
+pub struct DynamicScheduler {
+    num_txns: usize,
+    // Shared index of the next transaction to execute.
+    execution_idx: AtomicUsize,
+    // Shared index of the next transaction to validate.
+    validation_idx: AtomicUsize,
+    // Number of times any of the indices was decreased, to detect a concurrent decrease when
+    // checking whether the block is done.
+    decrease_cnt: AtomicUsize,
+    // Number of tasks handed out and not finished yet.
+    num_active_tasks: AtomicUsize,
+    done_marker: AtomicBool,
+
+    txn_dependency: Vec<Mutex<Vec<TxnIndex>>>, // txn index -> txns waiting on it.
+    txn_status: Vec<Mutex<TransactionStatus>>, // txn index -> status of its last incarnation.
+}
+
+impl DynamicScheduler {
+    pub fn new(num_txns: usize) -> Self {
+        Self {
+            num_txns,
+            execution_idx: AtomicUsize::new(0),
+            validation_idx: AtomicUsize::new(0),
+            decrease_cnt: AtomicUsize::new(0),
+            num_active_tasks: AtomicUsize::new(0),
+            done_marker: AtomicBool::new(false),
+            txn_dependency: (0..num_txns).map(|_| Mutex::new(Vec::new())).collect(),
+            txn_status: (0..num_txns)
+                .map(|_| Mutex::new(TransactionStatus::ReadyToExecute(0)))
+                .collect(),
+        }
+    }
+
+    pub fn done(&self) -> bool {
+        self.done_marker.load(Ordering::Acquire)
+    }
+
+    // Return the next task for the thread: validations take priority over executions of higher
+    // transactions. Return None if there's nothing to do at the moment.
+    pub fn next_task(&self) -> Option<SchedulerTask> {
+        if self.validation_idx.load(Ordering::SeqCst) < self.execution_idx.load(Ordering::SeqCst) {
+            self.next_version_to_validate()
+                .map(SchedulerTask::Validation)
+        } else {
+            self.next_version_to_execute().map(SchedulerTask::Execution)
+        }
+    }
+
+    // Invoked when transaction `txn_idx` read an estimate written by `dep_txn_idx`, which is
+    // being re-executed. Return true if `txn_idx` will be resumed once `dep_txn_idx` finishes
+    // executing, which ends the current execution task; return false if `dep_txn_idx` finished
+    // executing in the meantime, in which case the read can be retried.
+    pub fn add_dependency(&self, txn_idx: TxnIndex, dep_txn_idx: TxnIndex) -> bool {
+        let mut stored_deps = self.txn_dependency[dep_txn_idx].lock().unwrap();
+        if let TransactionStatus::Executed(_) = *self.txn_status[dep_txn_idx].lock().unwrap() {
+            return false;
+        }
+
+        let mut status = self.txn_status[txn_idx].lock().unwrap();
+        if let TransactionStatus::Executing(incarnation) = *status {
+            *status = TransactionStatus::Aborting(incarnation);
+        }
+        stored_deps.push(txn_idx);
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        true
+    }
+
+    // After a transaction is executed, resume the transactions waiting on it and return the
+    // validation of the execution, if the thread should perform it.
+    pub fn finish_execution(
+        &self,
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        wrote_new_key: bool,
+    ) -> Option<SchedulerTask> {
+        *self.txn_status[txn_idx].lock().unwrap() = TransactionStatus::Executed(incarnation);
+
+        let deps = std::mem::take(&mut *self.txn_dependency[txn_idx].lock().unwrap());
+        self.resume_dependencies(deps);
+
+        // Transactions up to `validation_idx` have already been handed out for validation, so
+        // the execution needs to be validated here. Writing to a key the previous incarnation
+        // didn't write to may invalidate the reads of higher transactions, which then need to
+        // be validated again.
+        if self.validation_idx.load(Ordering::SeqCst) > txn_idx {
+            if wrote_new_key {
+                self.decrease_validation_idx(txn_idx);
+            } else {
+                return Some(SchedulerTask::Validation((txn_idx, incarnation)));
+            }
+        }
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        None
+    }
+
+    // Abort the execution `incarnation` of transaction `txn_idx` after it failed validation.
+    // Return false if the execution was already aborted by another validation.
+    pub fn try_validation_abort(&self, txn_idx: TxnIndex, incarnation: Incarnation) -> bool {
+        let mut status = self.txn_status[txn_idx].lock().unwrap();
+        if *status == TransactionStatus::Executed(incarnation) {
+            *status = TransactionStatus::Aborting(incarnation);
+            true
+        } else {
+            false
+        }
+    }
+
+    // After a transaction is validated, return its re-execution if the validation aborted it and
+    // the thread should perform the re-execution.
+    pub fn finish_validation(&self, txn_idx: TxnIndex, aborted: bool) -> Option<SchedulerTask> {
+        if aborted {
+            self.set_ready_status(txn_idx);
+            self.decrease_validation_idx(txn_idx + 1);
+            if self.execution_idx.load(Ordering::SeqCst) > txn_idx {
+                if let Some(incarnation) = self.try_incarnate(txn_idx) {
+                    return Some(SchedulerTask::Execution((txn_idx, incarnation)));
+                }
+            }
+        }
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        None
+    }
+
+    fn next_version_to_execute(&self) -> Option<Version> {
+        if self.execution_idx.load(Ordering::SeqCst) >= self.num_txns {
+            self.check_done();
+            return None;
+        }
+        self.num_active_tasks.fetch_add(1, Ordering::SeqCst);
+        let idx_to_execute = self.execution_idx.fetch_add(1, Ordering::SeqCst);
+        match self.try_incarnate(idx_to_execute) {
+            Some(incarnation) => Some((idx_to_execute, incarnation)),
+            None => {
+                self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+                None
+            }
+        }
+    }
+
+    fn next_version_to_validate(&self) -> Option<Version> {
+        if self.validation_idx.load(Ordering::SeqCst) >= self.num_txns {
+            self.check_done();
+            return None;
+        }
+        self.num_active_tasks.fetch_add(1, Ordering::SeqCst);
+        let idx_to_validate = self.validation_idx.fetch_add(1, Ordering::SeqCst);
+        if idx_to_validate < self.num_txns {
+            if let TransactionStatus::Executed(incarnation) =
+                *self.txn_status[idx_to_validate].lock().unwrap()
+            {
+                return Some((idx_to_validate, incarnation));
+            }
+        }
+        self.num_active_tasks.fetch_sub(1, Ordering::SeqCst);
+        None
+    }
+
+    // Start executing the next incarnation of a transaction, if it's ready to be executed.
+    fn try_incarnate(&self, txn_idx: TxnIndex) -> Option<Incarnation> {
+        if txn_idx >= self.num_txns {
+            return None;
+        }
+        let mut status = self.txn_status[txn_idx].lock().unwrap();
+        if let TransactionStatus::ReadyToExecute(incarnation) = *status {
+            *status = TransactionStatus::Executing(incarnation);
+            Some(incarnation)
+        } else {
+            None
+        }
+    }
+
+    fn set_ready_status(&self, txn_idx: TxnIndex) {
+        let mut status = self.txn_status[txn_idx].lock().unwrap();
+        if let TransactionStatus::Aborting(incarnation) = *status {
+            *status = TransactionStatus::ReadyToExecute(incarnation + 1);
+        }
+    }
+
+    fn resume_dependencies(&self, deps: Vec<TxnIndex>) {
+        if let Some(min_dep) = deps.iter().min() {
+            for dep in deps.iter() {
+                self.set_ready_status(*dep);
+            }
+            self.decrease_execution_idx(*min_dep);
+        }
+    }
+
+    fn decrease_execution_idx(&self, target_idx: TxnIndex) {
+        self.execution_idx.fetch_min(target_idx, Ordering::SeqCst);
+        self.decrease_cnt.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn decrease_validation_idx(&self, target_idx: TxnIndex) {
+        self.validation_idx.fetch_min(target_idx, Ordering::SeqCst);
+        self.decrease_cnt.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn check_done(&self) {
+        let observed_cnt = self.decrease_cnt.load(Ordering::SeqCst);
+        let execution_idx = self.execution_idx.load(Ordering::SeqCst);
+        let validation_idx = self.validation_idx.load(Ordering::SeqCst);
+        if min(execution_idx, validation_idx) >= self.num_txns
+            && self.num_active_tasks.load(Ordering::SeqCst) == 0
+            && observed_cnt == self.decrease_cnt.load(Ordering::SeqCst)
+        {
+            self.done_marker.store(true, Ordering::Release);
+        }
+    }
+}
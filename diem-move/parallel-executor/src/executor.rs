@@ -2,13 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    dynamic_scheduler::DynamicScheduler,
     errors::*,
     outcome_array::OutcomeArray,
     scheduler::Scheduler,
     task::{ExecutionStatus, ExecutorTask, ReadWriteSetInferencer, Transaction, TransactionOutput},
 };
 use anyhow::{bail, Result as AResult};
-use mvhashmap::{MVHashMap, Version};
+use mvhashmap::{
+    dynamic::{DynamicMVHashMap, Incarnation, TxnIndex},
+    MVHashMap, Version,
+};
 use num_cpus;
 use rayon::{prelude::*, scope};
 use std::{
@@ -17,30 +21,119 @@ use std::{
     marker::PhantomData,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
 
+/// A transaction's view of the writes of the transactions before it in the block.
 pub struct MVHashMapView<'a, K, V> {
-    map: &'a MVHashMap<K, V>,
     version: Version,
-    scheduler: &'a Scheduler,
+    inner: ViewInner<'a, K, V>,
     has_unexpected_read: AtomicBool,
 }
 
+enum ViewInner<'a, K, V> {
+    /// Reads of the placeholders created from the inferred write sets of the block.
+    Static {
+        map: &'a MVHashMap<K, Arc<V>>,
+        scheduler: &'a Scheduler,
+    },
+    /// Reads of the writes of the last executions of the transactions, which are captured to
+    /// validate the execution.
+    Dynamic {
+        map: &'a DynamicMVHashMap<K, V>,
+        scheduler: &'a DynamicScheduler,
+        captured_reads: Mutex<Vec<ReadDescriptor<K>>>,
+    },
+}
+
+/// A key a transaction read, and the version of the transaction that wrote the value read, or
+/// None if the value was read from storage.
+pub(crate) struct ReadDescriptor<K> {
+    pub key: K,
+    pub version: Option<(TxnIndex, Incarnation)>,
+}
+
 impl<'a, K: Hash + Clone + Eq, V> MVHashMapView<'a, K, V> {
-    pub fn read(&self, key: &K) -> AResult<Option<&V>> {
-        match self.map.read(key, self.version) {
-            Ok(v) => Ok(Some(v)),
-            Err(None) => Ok(None),
-            Err(Some(dep_idx)) => {
-                // Don't start execution transaction `self.version` until `dep_idx` is computed.
-                if !self.scheduler.add_dependency(self.version, dep_idx) {
-                    // dep_idx is already executed, push `self.version` to ready queue.
-                    self.scheduler.add_transaction(self.version);
+    pub(crate) fn new_static(
+        map: &'a MVHashMap<K, Arc<V>>,
+        version: Version,
+        scheduler: &'a Scheduler,
+    ) -> Self {
+        Self {
+            version,
+            inner: ViewInner::Static { map, scheduler },
+            has_unexpected_read: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn new_dynamic(
+        map: &'a DynamicMVHashMap<K, V>,
+        txn_idx: TxnIndex,
+        scheduler: &'a DynamicScheduler,
+    ) -> Self {
+        Self {
+            version: txn_idx,
+            inner: ViewInner::Dynamic {
+                map,
+                scheduler,
+                captured_reads: Mutex::new(Vec::new()),
+            },
+            has_unexpected_read: AtomicBool::new(false),
+        }
+    }
+
+    pub fn read(&self, key: &K) -> AResult<Option<Arc<V>>> {
+        match &self.inner {
+            ViewInner::Static { map, scheduler } => match map.read(key, self.version) {
+                Ok(v) => Ok(Some(v.clone())),
+                Err(None) => Ok(None),
+                Err(Some(dep_idx)) => {
+                    // Don't start execution transaction `self.version` until `dep_idx` is computed.
+                    if !scheduler.add_dependency(self.version, dep_idx) {
+                        // dep_idx is already executed, push `self.version` to ready queue.
+                        scheduler.add_transaction(self.version);
+                    }
+                    self.has_unexpected_read.fetch_or(true, Ordering::Relaxed);
+                    bail!("Read dependency is not computed, retry later")
+                }
+            },
+            ViewInner::Dynamic {
+                map,
+                scheduler,
+                captured_reads,
+            } => {
+                // The transaction is already waiting on a dependency, don't register it again
+                // for reads the VM performs while failing the execution.
+                if self.has_unexpected_read() {
+                    bail!("Read dependency is not computed, retry later")
+                }
+                loop {
+                    match map.read(key, self.version) {
+                        Ok((version, v)) => {
+                            captured_reads.lock().unwrap().push(ReadDescriptor {
+                                key: key.clone(),
+                                version: Some(version),
+                            });
+                            return Ok(Some(v));
+                        }
+                        Err(None) => {
+                            captured_reads.lock().unwrap().push(ReadDescriptor {
+                                key: key.clone(),
+                                version: None,
+                            });
+                            return Ok(None);
+                        }
+                        Err(Some(dep_idx)) => {
+                            // Resume the transaction once `dep_idx` is re-executed, unless it
+                            // was re-executed in the meantime and the read can be retried.
+                            if scheduler.add_dependency(self.version, dep_idx) {
+                                self.has_unexpected_read.fetch_or(true, Ordering::Relaxed);
+                                bail!("Read dependency is not computed, retry later")
+                            }
+                        }
+                    }
                 }
-                self.has_unexpected_read.fetch_or(true, Ordering::Relaxed);
-                bail!("Read dependency is not computed, retry later")
             }
         }
     }
@@ -52,6 +145,13 @@ impl<'a, K: Hash + Clone + Eq, V> MVHashMapView<'a, K, V> {
     pub fn has_unexpected_read(&self) -> bool {
         self.has_unexpected_read.load(Ordering::Relaxed)
     }
+
+    pub(crate) fn take_reads(self) -> Vec<ReadDescriptor<K>> {
+        match self.inner {
+            ViewInner::Static { .. } => vec![],
+            ViewInner::Dynamic { captured_reads, .. } => captured_reads.into_inner().unwrap(),
+        }
+    }
 }
 
 pub struct ParallelTransactionExecutor<T: Transaction, E: ExecutorTask, I: ReadWriteSetInferencer> {
@@ -118,7 +218,7 @@ where
             .flatten()
             .collect();
 
-        let (versioned_data_cache, max_dependency_level) =
+        let (versioned_data_cache, max_dependency_level): (MVHashMap<_, Arc<T::Value>>, _) =
             MVHashMap::new_from_parallel(path_version_tuples);
 
         if max_dependency_level == 0 {
@@ -158,12 +258,8 @@ where
                         }
 
                         // Process the output of a transaction
-                        let view = MVHashMapView {
-                            map: &versioned_data_cache,
-                            version: idx,
-                            scheduler: &scheduler,
-                            has_unexpected_read: AtomicBool::new(false),
-                        };
+                        let view =
+                            MVHashMapView::new_static(&versioned_data_cache, idx, &scheduler);
                         let execute_result = task.execute_transaction(&view, txn);
                         if view.has_unexpected_read() {
                             // We've already added this transaction back to the scheduler in the
@@ -171,41 +267,40 @@ where
                             // here.
                             continue;
                         }
-                        let commit_result =
-                            match execute_result {
-                                ExecutionStatus::Success(output) => {
-                                    // Commit the side effects to the versioned_data_cache.
-                                    if output.get_writes().into_iter().all(|(k, v)| {
-                                        versioned_data_cache.write(&k, idx, v).is_ok()
-                                    }) {
-                                        ExecutionStatus::Success(output)
-                                    } else {
-                                        // Failed to write to the versioned data cache as
-                                        // transaction write to a key that wasn't estimated by the
-                                        // inferencer, aborting the entire execution.
-                                        ExecutionStatus::Abort(Error::UnestimatedWrite)
-                                    }
-                                }
-                                ExecutionStatus::SkipRest(output) => {
-                                    // Commit and skip the rest of the transactions.
-                                    if output.get_writes().into_iter().all(|(k, v)| {
-                                        versioned_data_cache.write(&k, idx, v).is_ok()
-                                    }) {
-                                        scheduler.set_stop_version(idx + 1);
-                                        ExecutionStatus::SkipRest(output)
-                                    } else {
-                                        // Failed to write to the versioned data cache as
-                                        // transaction write to a key that wasn't estimated by the
-                                        // inferencer, aborting the entire execution.
-                                        ExecutionStatus::Abort(Error::UnestimatedWrite)
-                                    }
+                        let commit_result = match execute_result {
+                            ExecutionStatus::Success(output) => {
+                                // Commit the side effects to the versioned_data_cache.
+                                if output.get_writes().into_iter().all(|(k, v)| {
+                                    versioned_data_cache.write(&k, idx, Arc::new(v)).is_ok()
+                                }) {
+                                    ExecutionStatus::Success(output)
+                                } else {
+                                    // Failed to write to the versioned data cache as
+                                    // transaction write to a key that wasn't estimated by the
+                                    // inferencer, aborting the entire execution.
+                                    ExecutionStatus::Abort(Error::UnestimatedWrite)
                                 }
-                                ExecutionStatus::Abort(err) => {
-                                    // Abort the execution with user defined error.
+                            }
+                            ExecutionStatus::SkipRest(output) => {
+                                // Commit and skip the rest of the transactions.
+                                if output.get_writes().into_iter().all(|(k, v)| {
+                                    versioned_data_cache.write(&k, idx, Arc::new(v)).is_ok()
+                                }) {
                                     scheduler.set_stop_version(idx + 1);
-                                    ExecutionStatus::Abort(Error::UserError(err.clone()))
+                                    ExecutionStatus::SkipRest(output)
+                                } else {
+                                    // Failed to write to the versioned data cache as
+                                    // transaction write to a key that wasn't estimated by the
+                                    // inferencer, aborting the entire execution.
+                                    ExecutionStatus::Abort(Error::UnestimatedWrite)
                                 }
-                            };
+                            }
+                            ExecutionStatus::Abort(err) => {
+                                // Abort the execution with user defined error.
+                                scheduler.set_stop_version(idx + 1);
+                                ExecutionStatus::Abort(Error::UserError(err.clone()))
+                            }
+                        };
 
                         for write in txn_accesses.keys_written.iter() {
                             // Unwrap here is fine because all writes here should be in the mvhashmap.
@@ -1,6 +1,8 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod dynamic_executor;
+mod dynamic_scheduler;
 pub mod errors;
 pub mod executor;
 mod outcome_array;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    dynamic_executor::DynamicParallelTransactionExecutor,
     executor::ParallelTransactionExecutor,
     proptest_types::types::{
         ExpectedOutput, ImpreciseInferencer, Inferencer, Task, Transaction, TransactionGen,
@@ -10,12 +11,18 @@ use crate::{
 use proptest::{collection::vec, prelude::*, sample::Index, strategy::Strategy};
 use std::{fmt::Debug, hash::Hash};
 
+enum ExecutorMode {
+    Inferred,
+    ImpreciseRead,
+    Dynamic,
+}
+
 fn run_transactions<K, V>(
     key_universe: Vec<K>,
     transaction_gens: Vec<TransactionGen<V>>,
     abort_transactions: Vec<Index>,
     skip_rest_transactions: Vec<Index>,
-    imprecise_read: bool,
+    mode: ExecutorMode,
 ) -> bool
 where
     K: Hash + Clone + Debug + Eq + Send + Sync + PartialOrd + Ord + 'static,
@@ -38,16 +45,23 @@ where
 
     let baseline = ExpectedOutput::generate_baseline(&transactions);
 
-    let output = if imprecise_read {
-        ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>, ImpreciseInferencer<K, V>>::new(
-            ImpreciseInferencer::new(),
-        )
+    let output = match mode {
+        ExecutorMode::Inferred => {
+            ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>, Inferencer<K, V>>::new(
+                Inferencer::new(),
+            )
             .execute_transactions_parallel((), transactions)
-    } else {
-        ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>, Inferencer<K, V>>::new(
-            Inferencer::new(),
-        )
-        .execute_transactions_parallel((), transactions)
+        }
+        ExecutorMode::ImpreciseRead => ParallelTransactionExecutor::<
+            Transaction<K, V>,
+            Task<K, V>,
+            ImpreciseInferencer<K, V>,
+        >::new(ImpreciseInferencer::new())
+        .execute_transactions_parallel((), transactions),
+        ExecutorMode::Dynamic => {
+            DynamicParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new()
+                .execute_transactions_parallel((), transactions)
+        }
     };
 
     baseline.check_output(&output)
@@ -62,7 +76,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 0),
         skip_rest_transactions in vec(any::<Index>(), 0),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, false));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, ExecutorMode::Inferred));
     }
 
     #[test]
@@ -72,7 +86,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 5),
         skip_rest_transactions in vec(any::<Index>(), 0),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, false));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, ExecutorMode::Inferred));
     }
 
     #[test]
@@ -82,7 +96,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 0),
         skip_rest_transactions in vec(any::<Index>(), 5),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, false));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, ExecutorMode::Inferred));
     }
 
 
@@ -93,7 +107,7 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 5),
         skip_rest_transactions in vec(any::<Index>(), 5),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, false));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, ExecutorMode::Inferred));
     }
 
     #[test]
@@ -103,6 +117,26 @@ proptest! {
         abort_transactions in vec(any::<Index>(), 5),
         skip_rest_transactions in vec(any::<Index>(), 5),
     ) {
-        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, true));
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, ExecutorMode::ImpreciseRead));
+    }
+
+    #[test]
+    fn dynamic_dependencies(
+        universe in vec(any::<[u8; 32]>(), 100),
+        transaction_gen in vec(any::<TransactionGen<[u8;32]>>(), 3000).no_shrink(),
+        abort_transactions in vec(any::<Index>(), 5),
+        skip_rest_transactions in vec(any::<Index>(), 5),
+    ) {
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, ExecutorMode::Dynamic));
+    }
+
+    #[test]
+    fn dynamic_dependencies_contended(
+        universe in vec(any::<[u8; 32]>(), 10),
+        transaction_gen in vec(any::<TransactionGen<[u8;32]>>(), 1000).no_shrink(),
+        abort_transactions in vec(any::<Index>(), 0),
+        skip_rest_transactions in vec(any::<Index>(), 0),
+    ) {
+        prop_assert!(run_transactions(universe, transaction_gen, abort_transactions, skip_rest_transactions, ExecutorMode::Dynamic));
     }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    dynamic_executor::DynamicParallelTransactionExecutor,
     executor::ParallelTransactionExecutor,
     proptest_types::types::{ExpectedOutput, Inferencer, Task, Transaction},
 };
@@ -19,6 +20,11 @@ where
         ParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>, Inferencer<K, V>>::new(
             Inferencer::new(),
         )
+        .execute_transactions_parallel((), transactions.clone());
+
+    assert!(baseline.check_output(&output));
+
+    let output = DynamicParallelTransactionExecutor::<Transaction<K, V>, Task<K, V>>::new()
         .execute_transactions_parallel((), transactions);
 
     assert!(baseline.check_output(&output))
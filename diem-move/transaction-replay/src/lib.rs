@@ -25,7 +25,7 @@ use move_binary_format::{errors::VMResult, file_format::CompiledModule};
 use move_cli::sandbox::utils::on_disk_state_view::OnDiskStateView;
 use move_core_types::{effects::ChangeSet as MoveChanges, language_storage::TypeTag};
 use move_lang::{compiled_unit::AnnotatedCompiledUnit, Compiler, Flags};
-use move_vm_runtime::{move_vm::MoveVM, session::Session};
+use move_vm_runtime::{gas_profiler, move_vm::MoveVM, session::Session};
 use move_vm_test_utils::DeltaStorage;
 use move_vm_types::gas_schedule::GasStatus;
 use std::path::{Path, PathBuf};
@@ -64,6 +64,13 @@ impl DiemDebugger {
         txns: Vec<Transaction>,
     ) -> Result<Vec<TransactionOutput>> {
         let state_view = DebuggerStateView::new(&*self.debugger, version);
+        // The gas profiler only sees the transactions executed on the thread profiling, so they
+        // are not handed to the parallel executor while profiling
+        if gas_profiler::is_profiling() {
+            return DiemVM::execute_block_and_keep_vm_status(txns, &state_view)
+                .map(|outputs| outputs.into_iter().map(|(_, output)| output).collect())
+                .map_err(|err| format_err!("Unexpected VM Error: {:?}", err));
+        }
         DiemVM::execute_block(txns, &state_view)
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
@@ -171,6 +178,10 @@ impl DiemDebugger {
                     WriteOp::Deletion => state_view.delete_module(&module_id)?,
                     WriteOp::Value(bytes) => state_view.save_module(&module_id, bytes)?,
                 },
+                access_path::Path::TableItem { handle, key } => match op {
+                    WriteOp::Deletion => state_view.delete_table_item(&handle, &key)?,
+                    WriteOp::Value(bytes) => state_view.save_table_item(&handle, &key, bytes)?,
+                },
             }
         }
         for event in o.events() {
@@ -197,6 +208,9 @@ impl DiemDebugger {
                 access_path::Path::Resource(struct_tag) => {
                     disk_view.save_resource(account, struct_tag, value)?
                 }
+                access_path::Path::TableItem { handle, key } => {
+                    disk_view.save_table_item(&handle, &key, value)?
+                }
             }
         }
         Ok(())
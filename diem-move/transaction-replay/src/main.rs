@@ -10,6 +10,7 @@ use diem_types::{
 };
 use difference::Changeset;
 use move_core_types::effects::ChangeSet;
+use move_vm_runtime::gas_profiler;
 use std::{fs, path::PathBuf};
 use structopt::StructOpt;
 
@@ -24,6 +25,11 @@ struct Opt {
     /// If true, persist the effects of replaying transactions via `cmd` to disk in a format understood by the Move CLI
     #[structopt(short = "s", global = true)]
     save_write_sets: bool,
+    /// Profile the gas used by the transactions replayed via `cmd` and write the profile to this
+    /// file: a speedscope profile if the file has a `.json` extension, and folded stacks for
+    /// flamegraph tools otherwise
+    #[structopt(long, global = true, parse(from_os_str))]
+    gas_profile: Option<PathBuf>,
     #[structopt(subcommand)] // Note that we mark a field as a subcommand
     cmd: Command,
 }
@@ -100,6 +106,10 @@ fn main() -> Result<()> {
 
     println!("Connection Succeeded");
 
+    if opt.gas_profile.is_some() {
+        gas_profiler::start_gas_profiling();
+    }
+
     match opt.cmd {
         Command::ReplayTransactions { start, limit } => {
             println!(
@@ -233,5 +243,11 @@ fn main() -> Result<()> {
             )
         ),
     }
+
+    if let (Some(path), Some(gas_profile)) = (opt.gas_profile, gas_profiler::finish_gas_profiling())
+    {
+        gas_profile.write_to_file(&path, "Transaction replay")?;
+        println!("Wrote gas profile to {}", path.display());
+    }
     Ok(())
 }
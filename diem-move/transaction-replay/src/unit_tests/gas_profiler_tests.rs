@@ -0,0 +1,34 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{unit_tests::TestInterface, DiemDebugger};
+use diem_crypto::HashValue;
+use diem_types::{
+    account_config::reserved_vm_address, block_metadata::BlockMetadata, transaction::Transaction,
+};
+use move_vm_runtime::gas_profiler::{finish_gas_profiling, start_gas_profiling};
+
+#[test]
+fn profiles_replayed_transactions() {
+    let debugger = DiemDebugger::new(Box::new(TestInterface::genesis()));
+    // A NIL block, which keeps the timestamp set at genesis
+    let block = Transaction::BlockMetadata(BlockMetadata::new(
+        HashValue::zero(),
+        1,
+        0,
+        vec![],
+        reserved_vm_address(),
+    ));
+
+    start_gas_profiling();
+    debugger
+        .execute_transactions_at_version(1, vec![block])
+        .unwrap();
+    let profile = finish_gas_profiling().unwrap();
+
+    assert!(profile
+        .stacks
+        .keys()
+        .any(|stack| stack[0].ends_with("DiemBlock::block_prologue")));
+    assert!(profile.total().instructions > 0);
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod bisection_tests;
+mod gas_profiler_tests;
 
 use crate::DiemValidatorInterface;
 use anyhow::{bail, Result};
@@ -144,6 +144,19 @@ pub fn encode_enable_parallel_execution_with_config() -> WriteSetPayload {
     ))
     .expect("Failed to serialize analyze result");
 
+    encode_update_parallel_execution_config(payload)
+}
+
+/// Enable parallel execution without a static read write set analysis, detecting the conflicts
+/// between transactions while executing them.
+pub fn encode_enable_dynamic_parallel_execution() -> WriteSetPayload {
+    let payload =
+        bcs::to_bytes(&ReadWriteSetAnalysis::Dynamic).expect("Failed to serialize analyze result");
+
+    encode_update_parallel_execution_config(payload)
+}
+
+fn encode_update_parallel_execution_config(payload: Vec<u8>) -> WriteSetPayload {
     let mut script = template_path();
     script.push("update_parallel_execution_config.move");
     WriteSetPayload::Script {
@@ -9,8 +9,9 @@ mod writeset_builder;
 
 pub use admin_script_builder::{
     encode_custom_script, encode_disable_parallel_execution,
-    encode_enable_parallel_execution_with_config, encode_halt_network_payload,
-    encode_initialize_parallel_execution, encode_remove_validators_payload,
+    encode_enable_dynamic_parallel_execution, encode_enable_parallel_execution_with_config,
+    encode_halt_network_payload, encode_initialize_parallel_execution,
+    encode_remove_validators_payload,
 };
 
 pub use release_flow::{create_release, verify_release};
@@ -236,7 +236,11 @@ fn setup_chunk_executor(db: DbReaderWriter) -> Box<dyn ChunkExecutorTrait> {
     Box::new(ChunkExecutor::<DiemVM>::new(db).unwrap())
 }
 
-fn setup_debug_interface(config: &NodeConfig, logger: Option<Arc<Logger>>) -> NodeDebugService {
+fn setup_debug_interface(
+    config: &NodeConfig,
+    logger: Option<Arc<Logger>>,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+) -> NodeDebugService {
     let addr = format!(
         "{}:{}",
         config.debug_interface.address, config.debug_interface.admission_control_node_debug_port,
@@ -246,7 +250,7 @@ fn setup_debug_interface(config: &NodeConfig, logger: Option<Arc<Logger>>) -> No
     .next()
     .unwrap();
 
-    NodeDebugService::new(addr, logger, config)
+    NodeDebugService::new(addr, logger, config, peer_metadata_storage)
 }
 
 fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
@@ -433,7 +437,30 @@ async fn periodic_state_dump(node_config: NodeConfig, db: DbReaderWriter) {
 }
 
 pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>) -> DiemHandle {
-    let debug_if = setup_debug_interface(node_config, logger);
+    // Gather all network configs into a single vector.
+    let mut network_configs: Vec<&NetworkConfig> = node_config.full_node_networks.iter().collect();
+    if let Some(network_config) = node_config.validator_network.as_ref() {
+        network_configs.push(network_config);
+    }
+
+    // The peer metadata storage is created up front, as the debug interface serves peer bans
+    let mut network_ids = HashSet::new();
+    network_configs.iter().for_each(|config| {
+        let network_id = config.network_id;
+        // Guarantee there is only one of this network
+        if network_ids.contains(&network_id) {
+            panic!(
+                "Duplicate NetworkId: '{}'.  Can't start node with duplicate networks",
+                network_id
+            );
+        }
+        network_ids.insert(network_id);
+    });
+    let network_ids: Vec<_> = network_ids.into_iter().collect();
+
+    let peer_metadata_storage = PeerMetadataStorage::new(&network_ids);
+
+    let debug_if = setup_debug_interface(node_config, logger, peer_metadata_storage.clone());
 
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
@@ -450,6 +477,7 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
             &node_config.storage.dir(),
             false, /* readonly */
             node_config.storage.prune_window,
+            node_config.storage.ledger_prune_window,
             node_config.storage.rocksdb_config,
             node_config.storage.account_count_migration,
         )
@@ -509,28 +537,7 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         None
     };
 
-    // Gather all network configs into a single vector.
-    let mut network_configs: Vec<&NetworkConfig> = node_config.full_node_networks.iter().collect();
-    if let Some(network_config) = node_config.validator_network.as_ref() {
-        network_configs.push(network_config);
-    }
-
     // Instantiate every network and collect the requisite endpoints for state_sync, mempool, and consensus.
-    let mut network_ids = HashSet::new();
-    network_configs.iter().for_each(|config| {
-        let network_id = config.network_id;
-        // Guarantee there is only one of this network
-        if network_ids.contains(&network_id) {
-            panic!(
-                "Duplicate NetworkId: '{}'.  Can't start node with duplicate networks",
-                network_id
-            );
-        }
-        network_ids.insert(network_id);
-    });
-    let network_ids: Vec<_> = network_ids.into_iter().collect();
-
-    let peer_metadata_storage = PeerMetadataStorage::new(&network_ids);
     for network_config in network_configs.into_iter() {
         debug!("Creating runtime for {}", network_config.network_id);
         let runtime = Builder::new_multi_thread()
@@ -55,6 +55,7 @@ fn main() -> Result<()> {
             &opt.db_dir,
             false,
             None, /* pruner */
+            None, /* ledger_prune_window */
             RocksdbConfig::default(),
             opt.account_count_migration,
         )
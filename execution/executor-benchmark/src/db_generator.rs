@@ -48,6 +48,7 @@ pub fn run(
             &db_dir,
             false,        /* readonly */
             prune_window, /* pruner */
+            None,         /* ledger_prune_window */
             RocksdbConfig::default(),
             true, /* account_count_migration */
         )
@@ -58,7 +59,7 @@ pub fn run(
     let waypoint = generate_waypoint::<DiemVM>(&db_rw, get_genesis_txn(&config).unwrap()).unwrap();
     maybe_bootstrap::<DiemVM>(&db_rw, get_genesis_txn(&config).unwrap(), waypoint).unwrap();
 
-    let executor = Arc::new(BlockExecutor::new(db_rw));
+    let executor = Arc::new(BlockExecutor::<_, DiemVM>::new(db_rw));
     let genesis_block_id = executor.committed_block_id();
     let (block_sender, block_receiver) = mpsc::sync_channel(50 /* bound */);
 
@@ -5,15 +5,18 @@ pub mod db_generator;
 pub mod transaction_committer;
 pub mod transaction_executor;
 pub mod transaction_generator;
+pub mod vm;
 
 use crate::{
-    transaction_committer::TransactionCommitter, transaction_executor::TransactionExecutor,
+    transaction_committer::TransactionCommitter,
+    transaction_executor::TransactionExecutor,
     transaction_generator::TransactionGenerator,
+    vm::{DynamicParallelVM, ExecutionMode, SequentialVM, StaticParallelVM},
 };
 use diem_config::config::{NodeConfig, RocksdbConfig};
 use diem_logger::prelude::*;
 use diem_types::protocol_spec::DpnProto;
-use diem_vm::DiemVM;
+use diem_vm::VMExecutor;
 use diemdb::DiemDB;
 use executor::block_executor::BlockExecutor;
 use executor_types::BlockExecutorTrait;
@@ -24,14 +27,15 @@ use std::{
 };
 use storage_interface::{default_protocol::DbReaderWriter, DbReader};
 
-pub fn init_db_and_executor(
+pub fn init_db_and_executor<V: VMExecutor>(
     config: &NodeConfig,
-) -> (Arc<dyn DbReader<DpnProto>>, BlockExecutor<DpnProto, DiemVM>) {
+) -> (Arc<dyn DbReader<DpnProto>>, BlockExecutor<DpnProto, V>) {
     let (db, dbrw) = DbReaderWriter::wrap(
         DiemDB::open(
             &config.storage.dir(),
             false, /* readonly */
             None,  /* pruner */
+            None,  /* ledger_prune_window */
             RocksdbConfig::default(),
             true, /* account_count_migration */
         )
@@ -50,6 +54,39 @@ pub fn run_benchmark(
     source_dir: impl AsRef<Path>,
     checkpoint_dir: impl AsRef<Path>,
     verify: bool,
+    execution_mode: ExecutionMode,
+) {
+    match execution_mode {
+        ExecutionMode::Sequential => run_benchmark_impl::<SequentialVM>(
+            block_size,
+            num_transfer_blocks,
+            source_dir,
+            checkpoint_dir,
+            verify,
+        ),
+        ExecutionMode::StaticParallel => run_benchmark_impl::<StaticParallelVM>(
+            block_size,
+            num_transfer_blocks,
+            source_dir,
+            checkpoint_dir,
+            verify,
+        ),
+        ExecutionMode::DynamicParallel => run_benchmark_impl::<DynamicParallelVM>(
+            block_size,
+            num_transfer_blocks,
+            source_dir,
+            checkpoint_dir,
+            verify,
+        ),
+    }
+}
+
+fn run_benchmark_impl<V: VMExecutor>(
+    block_size: usize,
+    num_transfer_blocks: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    verify: bool,
 ) {
     // Create rocksdb checkpoint.
     if checkpoint_dir.as_ref().exists() {
@@ -61,6 +98,7 @@ pub fn run_benchmark(
         &source_dir,
         true, /* readonly */
         None, /* pruner */
+        None, /* ledger_prune_window */
         RocksdbConfig::default(),
         true, /* account_count_migration */
     )
@@ -71,7 +109,7 @@ pub fn run_benchmark(
     let (mut config, genesis_key) = diem_genesis_tool::test_config();
     config.storage.dir = checkpoint_dir.as_ref().to_path_buf();
 
-    let (db, executor) = init_db_and_executor(&config);
+    let (db, executor) = init_db_and_executor::<V>(&config);
     let parent_block_id = executor.committed_block_id();
     let executor_1 = Arc::new(executor);
     let executor_2 = executor_1.clone();
@@ -130,10 +168,10 @@ pub fn run_benchmark(
 
 #[cfg(test)]
 mod tests {
+    use crate::vm::ExecutionMode;
     use diem_temppath::TempPath;
 
-    #[test]
-    fn test_benchmark() {
+    fn test_benchmark(execution_mode: ExecutionMode) {
         let storage_dir = TempPath::new();
         let checkpoint_dir = TempPath::new();
         storage_dir.create_as_dir().unwrap();
@@ -153,6 +191,17 @@ mod tests {
             storage_dir.as_ref(),
             checkpoint_dir,
             false,
+            execution_mode,
         );
     }
+
+    #[test]
+    fn test_sequential_benchmark() {
+        test_benchmark(ExecutionMode::Sequential);
+    }
+
+    #[test]
+    fn test_dynamic_parallel_benchmark() {
+        test_benchmark(ExecutionMode::DynamicParallel);
+    }
 }
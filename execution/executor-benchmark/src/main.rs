@@ -1,6 +1,7 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use executor_benchmark::vm::ExecutionMode;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -50,6 +51,14 @@ enum Command {
             about = "Verify sequence number of all the accounts after execution finishes"
         )]
         verify: bool,
+
+        #[structopt(
+            long,
+            default_value = "sequential",
+            about = "How to execute blocks: sequential, static (parallel with the read write set \
+                     analysis) or dynamic (parallel without the read write set analysis)"
+        )]
+        execution_mode: ExecutionMode,
     },
 }
 
@@ -81,6 +90,7 @@ fn main() {
             data_dir,
             checkpoint_dir,
             verify,
+            execution_mode,
         } => {
             diem_logger::Logger::new().init();
             executor_benchmark::run_benchmark(
@@ -89,6 +99,7 @@ fn main() {
                 data_dir,
                 checkpoint_dir,
                 verify,
+                execution_mode,
             );
         }
     }
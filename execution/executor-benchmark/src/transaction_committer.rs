@@ -9,7 +9,7 @@ use diem_types::{
     protocol_spec::DpnProto,
     transaction::Version,
 };
-use diem_vm::DiemVM;
+use diem_vm::VMExecutor;
 use diemdb::metrics::DIEM_STORAGE_API_LATENCY_SECONDS;
 use executor::{
     block_executor::BlockExecutor,
@@ -44,15 +44,15 @@ pub(crate) fn gen_li_with_sigs(
     LedgerInfoWithSignatures::new(ledger_info, BTreeMap::new() /* signatures */)
 }
 
-pub struct TransactionCommitter {
-    executor: Arc<BlockExecutor<DpnProto, DiemVM>>,
+pub struct TransactionCommitter<V> {
+    executor: Arc<BlockExecutor<DpnProto, V>>,
     version: Version,
     block_receiver: mpsc::Receiver<(HashValue, HashValue, Instant, Instant, Duration, usize)>,
 }
 
-impl TransactionCommitter {
+impl<V: VMExecutor> TransactionCommitter<V> {
     pub fn new(
-        executor: Arc<BlockExecutor<DpnProto, DiemVM>>,
+        executor: Arc<BlockExecutor<DpnProto, V>>,
         version: Version,
         block_receiver: mpsc::Receiver<(HashValue, HashValue, Instant, Instant, Duration, usize)>,
     ) -> Self {
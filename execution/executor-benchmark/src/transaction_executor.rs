@@ -6,7 +6,7 @@ use diem_types::{
     protocol_spec::DpnProto,
     transaction::{Transaction, Version},
 };
-use diem_vm::DiemVM;
+use diem_vm::VMExecutor;
 use executor::block_executor::BlockExecutor;
 use executor_types::BlockExecutorTrait;
 use std::{
@@ -14,8 +14,8 @@ use std::{
     time::{Duration, Instant},
 };
 
-pub struct TransactionExecutor {
-    executor: Arc<BlockExecutor<DpnProto, DiemVM>>,
+pub struct TransactionExecutor<V> {
+    executor: Arc<BlockExecutor<DpnProto, V>>,
     parent_block_id: HashValue,
     start_time: Instant,
     version: Version,
@@ -23,9 +23,9 @@ pub struct TransactionExecutor {
     commit_sender: Option<mpsc::Sender<(HashValue, HashValue, Instant, Instant, Duration, usize)>>,
 }
 
-impl TransactionExecutor {
+impl<V: VMExecutor> TransactionExecutor<V> {
     pub fn new(
-        executor: Arc<BlockExecutor<DpnProto, DiemVM>>,
+        executor: Arc<BlockExecutor<DpnProto, V>>,
         parent_block_id: HashValue,
         version: Version,
         commit_sender: Option<
@@ -0,0 +1,92 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Error, Result};
+use diem_framework_releases::current_modules;
+use diem_state_view::StateView;
+use diem_types::{
+    transaction::{Transaction, TransactionOutput},
+    vm_status::VMStatus,
+};
+use diem_vm::{
+    parallel_executor::ParallelDiemVM, read_write_set_analysis::add_on_functions_list, DiemVM,
+    VMExecutor,
+};
+use once_cell::sync::Lazy;
+use read_write_set::analyze;
+use read_write_set_dynamic::NormalizedReadWriteSetAnalysis;
+use std::str::FromStr;
+
+/// How the benchmark executes blocks, regardless of the parallel execution config on chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecutionMode {
+    Sequential,
+    /// In parallel, using the read write set analysis of the Diem Framework to find the
+    /// dependencies between transactions upfront.
+    StaticParallel,
+    /// In parallel, detecting the conflicts between transactions while executing them.
+    DynamicParallel,
+}
+
+impl FromStr for ExecutionMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "sequential" => ExecutionMode::Sequential,
+            "static" => ExecutionMode::StaticParallel,
+            "dynamic" => ExecutionMode::DynamicParallel,
+            _ => bail!(
+                "Unknown execution mode {}, expected sequential, static or dynamic",
+                s
+            ),
+        })
+    }
+}
+
+static READ_WRITE_SET_ANALYSIS: Lazy<NormalizedReadWriteSetAnalysis> = Lazy::new(|| {
+    analyze(current_modules().iter())
+        .expect("Failed to get ReadWriteSet for current Diem Framework")
+        .normalize_all_scripts(add_on_functions_list())
+        .trim()
+});
+
+pub struct SequentialVM;
+
+impl VMExecutor for SequentialVM {
+    fn execute_block(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Ok(
+            DiemVM::execute_block_and_keep_vm_status(transactions, state_view)?
+                .into_iter()
+                .map(|(_vm_status, txn_output)| txn_output)
+                .collect(),
+        )
+    }
+}
+
+pub struct StaticParallelVM;
+
+impl VMExecutor for StaticParallelVM {
+    fn execute_block(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let (outputs, _) =
+            ParallelDiemVM::execute_block(&READ_WRITE_SET_ANALYSIS, transactions, state_view)?;
+        Ok(outputs)
+    }
+}
+
+pub struct DynamicParallelVM;
+
+impl VMExecutor for DynamicParallelVM {
+    fn execute_block(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        ParallelDiemVM::execute_block_dynamic(transactions, state_view)
+    }
+}
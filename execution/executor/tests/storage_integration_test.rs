@@ -6,10 +6,12 @@ use diem_transaction_builder::stdlib::{
     encode_peer_to_peer_with_metadata_script, encode_set_validator_config_and_reconfigure_script,
 };
 use diem_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
     account_config::{diem_root_address, treasury_compliance_account_address, xus_tag},
     account_state::AccountState,
     block_metadata::BlockMetadata,
-    transaction::{Script, Transaction, TransactionPayload, WriteSetPayload},
+    transaction::{ModuleBundle, Script, Transaction, TransactionPayload, WriteSetPayload},
     trusted_state::TrustedState,
     validator_signer::ValidatorSigner,
 };
@@ -20,6 +22,11 @@ use executor_test_helpers::{
     },
 };
 use executor_types::BlockExecutorTrait;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{StructTag, TableHandle, CORE_CODE_ADDRESS},
+    resolver::{ResourceResolver, TableResolver},
+};
 use move_ir_compiler::Compiler;
 use std::convert::TryFrom;
 
@@ -411,6 +418,153 @@ fn test_change_publishing_option_to_custom() {
     verify_committed_txn_status(txn3.as_ref(), &block2[1]).unwrap();
 }
 
+#[test]
+fn test_table_items_in_storage() {
+    let path = diem_temppath::TempPath::new();
+    path.create_as_dir().unwrap();
+    let (genesis, validators) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
+    let genesis_key = &vm_genesis::GENESIS_KEYPAIR.0;
+    let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
+    let (diem_db, db, executor, _waypoint) = create_db_and_executor(path.path(), &genesis_txn);
+    let parent_block_id = executor.committed_block_id();
+    let signer = ValidatorSigner::new(validators[0].data.address, validators[0].key.clone());
+    let genesis_account = diem_root_address();
+
+    // Module publishing is closed, so the module is published under the core code address.
+    let module_code = "
+    module 0x1.TableHolder {
+        import 0x1.Table;
+
+        struct Holder has key { items: Table.Table<u64, u64> }
+
+        public publish(account: &signer, key: u64, val: u64) {
+            let items: Table.Table<u64, u64>;
+        label b0:
+            items = Table.new<u64, u64>();
+            Table.add<u64, u64>(&mut items, move(key), move(val));
+            move_to<Holder>(move(account), Holder { items: move(items) });
+            return;
+        }
+    }
+";
+    let module = Compiler {
+        deps: diem_framework_releases::current_modules().iter().collect(),
+    }
+    .into_compiled_module(module_code)
+    .expect("Failed to compile");
+    let mut module_blob = vec![];
+    module.serialize(&mut module_blob).unwrap();
+
+    let script_code = "
+    import 0x1.TableHolder;
+
+    main(account: signer) {
+    label b0:
+      TableHolder.publish(&account, 7, 42);
+
+      return;
+    }
+";
+    let mut deps: Vec<_> = diem_framework_releases::current_modules().iter().collect();
+    deps.push(&module);
+    let script_blob = Compiler { deps }
+        .into_script_blob(script_code)
+        .expect("Failed to compile");
+
+    let txn1 = get_test_signed_transaction(
+        genesis_account,
+        /* sequence_number = */ 0,
+        genesis_key.clone(),
+        genesis_key.public_key(),
+        Some(TransactionPayload::ModuleBundle(ModuleBundle::new(vec![
+            module_blob,
+        ]))),
+    );
+    let txn2 = get_test_signed_transaction(
+        genesis_account,
+        /* sequence_number = */ 1,
+        genesis_key.clone(),
+        genesis_key.public_key(),
+        Some(TransactionPayload::Script(Script::new(
+            script_blob,
+            vec![],
+            vec![],
+        ))),
+    );
+
+    let block1_id = gen_block_id(1);
+    let block1 = vec![txn1, txn2];
+    let output1 = executor
+        .execute_block((block1_id, block1.clone()), parent_block_id)
+        .unwrap();
+    let ledger_info_with_sigs = gen_ledger_info_with_sigs(1, &output1, block1_id, vec![&signer]);
+    executor
+        .commit_blocks(vec![block1_id], ledger_info_with_sigs)
+        .unwrap();
+
+    let ledger_info = db.reader.get_latest_ledger_info().unwrap();
+    let current_version = ledger_info.ledger_info().version();
+    assert_eq!(current_version, 2);
+    for (sequence_number, txn) in block1.iter().enumerate() {
+        let committed_txn = db
+            .reader
+            .get_account_transaction(
+                genesis_account,
+                sequence_number as u64,
+                false,
+                current_version,
+            )
+            .unwrap();
+        verify_committed_txn_status(committed_txn.as_ref(), txn).unwrap();
+    }
+
+    // The resource only holds the handle of the table, whose item is stored on its own.
+    let holder_tag = StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new("TableHolder").unwrap(),
+        name: Identifier::new("Holder").unwrap(),
+        type_params: vec![],
+    };
+    let holder_blob = diem_db
+        .get_resource(&genesis_account, &holder_tag)
+        .unwrap()
+        .expect("Holder must be published");
+    let (handle, length): (AccountAddress, u64) = bcs::from_bytes(&holder_blob).unwrap();
+    assert_eq!(length, 1);
+    let handle = TableHandle(handle);
+
+    let key = bcs::to_bytes(&7u64).unwrap();
+    assert_eq!(
+        diem_db.get_table_item(&handle, &key).unwrap(),
+        Some(bcs::to_bytes(&42u64).unwrap())
+    );
+    assert_eq!(
+        diem_db
+            .get_table_item(&handle, &bcs::to_bytes(&8u64).unwrap())
+            .unwrap(),
+        None
+    );
+
+    // The item is proven like any other account state.
+    let access_path = AccessPath::table_item_access_path(handle, key);
+    let item_state_with_proof = db
+        .reader
+        .get_account_state_with_proof(access_path.address, current_version, current_version)
+        .unwrap();
+    item_state_with_proof
+        .verify(
+            ledger_info.ledger_info(),
+            current_version,
+            access_path.address,
+        )
+        .unwrap();
+    let item_state = AccountState::try_from(&item_state_with_proof.blob.unwrap()).unwrap();
+    assert_eq!(
+        item_state.get(&access_path.path),
+        Some(&bcs::to_bytes(&42u64).unwrap())
+    );
+}
+
 #[test]
 fn test_execution_with_storage() {
     test_execution_with_storage_impl();
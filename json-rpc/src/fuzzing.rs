@@ -80,6 +80,15 @@ fn test_method_fuzzer() {
         &gen_request_params!(["00000000000000000000000000000000000000000a550c18", 0]),
         "get_event_by_version_with_proof",
     );
+    method_fuzzer(
+        &gen_request_params!(["000000000000000000000000000000dd"]),
+        "get_mempool_account_transactions",
+    );
+    method_fuzzer(
+        &gen_request_params!(["0000000000000000000000000000000000000000000000000000000000000000"]),
+        "get_mempool_transaction",
+    );
+    method_fuzzer(&gen_request_params!([]), "get_mempool_stats");
 }
 
 pub fn method_fuzzer(params_data: &[u8], method: &str) {
@@ -136,6 +145,7 @@ pub fn request_fuzzer(json_request: serde_json::Value) {
         genesis: std::collections::HashMap::new(),
         all_accounts: std::collections::HashMap::new(),
         all_txns: vec![],
+        write_sets: vec![],
         events: vec![],
         account_state_with_proof: vec![account_state_with_proof],
         timestamps: vec![1598223353000000],
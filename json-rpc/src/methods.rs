@@ -8,23 +8,29 @@ use crate::{
     views::{
         AccountStateWithProofView, AccountTransactionsWithProofView, AccountView,
         AccumulatorConsistencyProofView, CurrencyInfoView, EventByVersionWithProofView, EventView,
-        EventWithProofView, MetadataView, StateProofView, TransactionListView, TransactionView,
-        TransactionsWithProofsView,
+        EventWithProofView, MempoolAccountTransactionsView, MempoolStatsView,
+        MempoolTransactionStateView, MempoolTransactionView, MetadataView, StateProofView,
+        TransactionListView, TransactionView, TransactionsWithProofsView,
     },
 };
 use anyhow::Result;
 use diem_config::config::RoleType;
+use diem_crypto::HashValue;
 use diem_json_rpc_types::request::{
     GetAccountParams, GetAccountStateWithProofParams, GetAccountTransactionParams,
     GetAccountTransactionsParams, GetAccountTransactionsWithProofsParams,
     GetAccumulatorConsistencyProofParams, GetCurrenciesParams, GetEventByVersionWithProof,
-    GetEventsParams, GetEventsWithProofsParams, GetMetadataParams, GetNetworkStatusParams,
+    GetEventsParams, GetEventsWithProofsParams, GetMempoolAccountTransactionsParams,
+    GetMempoolStatsParams, GetMempoolTransactionParams, GetMetadataParams, GetNetworkStatusParams,
     GetResourcesParams, GetStateProofParams, GetTransactionsParams,
     GetTransactionsWithProofsParams, MethodRequest, SubmitParams,
 };
-use diem_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use diem_mempool::{
+    AccountPendingTransactions, MempoolClientRequest, MempoolClientSender, MempoolStats,
+    MempoolTransactionState, PendingTransaction, SubmissionStatus,
+};
 use diem_types::{
-    chain_id::ChainId, ledger_info::LedgerInfoWithSignatures, mempool_status::MempoolStatusCode,
+    account_address::AccountAddress, chain_id::ChainId, ledger_info::LedgerInfoWithSignatures,
     protocol_spec::DpnProto, transaction::SignedTransaction,
 };
 use fail::fail_point;
@@ -80,6 +86,49 @@ impl JsonRpcService {
         callback.await?
     }
 
+    pub async fn get_mempool_account_transactions(
+        &self,
+        account: AccountAddress,
+    ) -> Result<AccountPendingTransactions> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mempool_sender
+            .clone()
+            .send(MempoolClientRequest::GetAccountTransactions(
+                account, req_sender,
+            ))
+            .await?;
+
+        callback.await?
+    }
+
+    pub async fn get_mempool_transaction(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<PendingTransaction>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mempool_sender
+            .clone()
+            .send(MempoolClientRequest::GetPendingTransactionByHash(
+                hash, req_sender,
+            ))
+            .await?;
+
+        Ok(callback.await?)
+    }
+
+    pub async fn get_mempool_stats(&self) -> Result<MempoolStats> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mempool_sender
+            .clone()
+            .send(MempoolClientRequest::GetStats(req_sender))
+            .await?;
+
+        Ok(callback.await?)
+    }
+
     pub fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
         fail_point!("jsonrpc::get_latest_ledger_info", |_| {
             Err(anyhow::anyhow!(
@@ -196,6 +245,15 @@ impl<'a> Handler<'a> {
             MethodRequest::GetEventByVersionWithProof(params) => {
                 serde_json::to_value(self.get_event_by_version_with_proof(params).await?)?
             }
+            MethodRequest::GetMempoolAccountTransactions(params) => {
+                serde_json::to_value(self.get_mempool_account_transactions(params).await?)?
+            }
+            MethodRequest::GetMempoolTransaction(params) => {
+                serde_json::to_value(self.get_mempool_transaction(params).await?)?
+            }
+            MethodRequest::GetMempoolStats(params) => {
+                serde_json::to_value(self.get_mempool_stats(params).await?)?
+            }
         };
         Ok(response)
     }
@@ -205,7 +263,7 @@ impl<'a> Handler<'a> {
 
         if let Some(vm_status) = vm_status_opt {
             Err(JsonRpcError::vm_status(vm_status))
-        } else if mempool_status.code == MempoolStatusCode::Accepted {
+        } else if mempool_status.code.is_accepted() {
             Ok(())
         } else {
             Err(JsonRpcError::mempool_error(mempool_status)?)
@@ -379,6 +437,56 @@ impl<'a> Handler<'a> {
         )
     }
 
+    /// Returns the transactions of an account pending in mempool, and why they are not ready
+    async fn get_mempool_account_transactions(
+        &self,
+        params: GetMempoolAccountTransactionsParams,
+    ) -> Result<MempoolAccountTransactionsView, JsonRpcError> {
+        let account = params.account;
+        let account_txns = self
+            .service
+            .get_mempool_account_transactions(account)
+            .await?;
+        Ok(MempoolAccountTransactionsView {
+            account,
+            sequence_number: account_txns.sequence_number,
+            missing_sequence_number: account_txns.missing_sequence_number(),
+            transactions: account_txns
+                .transactions
+                .into_iter()
+                .map(mempool_transaction_view)
+                .collect(),
+        })
+    }
+
+    /// Returns the transaction pending in mempool with the given hash, along with its state
+    async fn get_mempool_transaction(
+        &self,
+        params: GetMempoolTransactionParams,
+    ) -> Result<Option<MempoolTransactionView>, JsonRpcError> {
+        Ok(self
+            .service
+            .get_mempool_transaction(params.hash)
+            .await?
+            .map(mempool_transaction_view))
+    }
+
+    /// Returns the number of transactions in mempool, by state, and its capacity
+    async fn get_mempool_stats(
+        &self,
+        _params: GetMempoolStatsParams,
+    ) -> Result<MempoolStatsView, JsonRpcError> {
+        let stats = self.service.get_mempool_stats().await?;
+        Ok(MempoolStatsView {
+            size: stats.size as u64,
+            ready: stats.ready as u64,
+            parked: stats.parked as u64,
+            accounts: stats.accounts as u64,
+            capacity: stats.capacity as u64,
+            capacity_per_user: stats.capacity_per_user as u64,
+        })
+    }
+
     /// Returns meta information about supported currencies
     async fn get_currencies(
         &self,
@@ -449,3 +557,16 @@ impl<'a> Handler<'a> {
         )
     }
 }
+
+fn mempool_transaction_view(txn: PendingTransaction) -> MempoolTransactionView {
+    let state = match txn.state {
+        MempoolTransactionState::Ready => MempoolTransactionStateView::Ready,
+        MempoolTransactionState::Parked => MempoolTransactionStateView::Parked,
+    };
+    MempoolTransactionView::new(
+        txn.txn,
+        state,
+        txn.ranking_score,
+        txn.expiration_time.as_secs(),
+    )
+}
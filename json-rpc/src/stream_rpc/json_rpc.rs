@@ -10,7 +10,9 @@ use tokio::task::JoinHandle;
 use crate::stream_rpc::{
     connection::ClientConnection,
     subscription_types::{Subscription, SubscriptionHelper},
-    subscriptions::{EventsSubscription, TransactionsSubscription},
+    subscriptions::{
+        AccountSubscription, BlocksSubscription, EventsSubscription, TransactionsSubscription,
+    },
 };
 use diem_json_rpc_types::{stream::request::StreamMethodRequest, Id};
 
@@ -32,6 +34,12 @@ impl CallableStreamMethod {
             StreamMethodRequest::SubscribeToEvents(params) => {
                 EventsSubscription::default().run(helper, params)
             }
+            StreamMethodRequest::SubscribeToBlocks(params) => {
+                BlocksSubscription::default().run(helper, params)
+            }
+            StreamMethodRequest::SubscribeToAccount(params) => {
+                AccountSubscription::default().run(helper, params)
+            }
             // This is handled in the `handle_rpc_request` function, as we don't spawn a task
             StreamMethodRequest::Unsubscribe => unreachable!(),
         }
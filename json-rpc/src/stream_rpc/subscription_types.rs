@@ -18,8 +18,10 @@
 //!       returned. Otherwise if the parameters are valid and a subscription could be created, simply
 //!       return `Ok(())`
 //!
-//! 2. `next(&self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ParamType>;`
+//! 2. `next(&mut self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ParamType>;`
 //!     Within this method, use existing state set up in the `init` function to fetch the next batch of data.
+//!     Subscriptions filtering the data they fetch can record here how far they have scanned,
+//!     since a batch may not contain any item to send.
 //!     The size of the batch should be determined by `helper.client.config.fetch_size`.
 //!     If the function returns an array of data, each item will be serialized one by one and sent to the client.
 //!     The `on_send` method (below) will be called for each item in the array.
@@ -143,7 +145,7 @@ where
 {
     fn init(&mut self, helper: &SubscriptionHelper, params: &ParamType)
         -> Result<(), JsonRpcError>;
-    fn next(&self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ReturnType>;
+    fn next(&mut self, helper: &SubscriptionHelper, params: &ParamType) -> Vec<ReturnType>;
     fn on_send(&mut self, item: Option<&ReturnType>);
 
     fn run(
@@ -253,7 +255,7 @@ mod tests {
         }
 
         fn next(
-            &self,
+            &mut self,
             _helper: &SubscriptionHelper,
             params: &SubscribeTestParams,
         ) -> Vec<TestView> {
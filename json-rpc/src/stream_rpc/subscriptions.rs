@@ -2,15 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    data::{get_events, get_transactions},
+    data::{get_account, get_events, get_transactions},
     errors::JsonRpcError,
     stream_rpc::subscription_types::{Subscription, SubscriptionHelper},
-    views::{EventView, TransactionView},
+    views::{AccountView, BlockView, EventView, TransactionView},
 };
 use diem_json_rpc_types::stream::request::{
-    SubscribeToEventsParams, SubscribeToTransactionsParams,
+    SubscribeToAccountParams, SubscribeToBlocksParams, SubscribeToEventsParams,
+    SubscribeToTransactionsParams,
 };
 use diem_logger::warn;
+use diem_types::{block_metadata::BlockMetadata, transaction::Transaction};
 use std::borrow::Borrow;
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -29,7 +31,7 @@ impl Subscription<SubscribeToTransactionsParams, TransactionView> for Transactio
     }
 
     fn next(
-        &self,
+        &mut self,
         helper: &SubscriptionHelper,
         params: &SubscribeToTransactionsParams,
     ) -> Vec<TransactionView> {
@@ -74,7 +76,7 @@ impl Subscription<SubscribeToEventsParams, EventView> for EventsSubscription {
     }
 
     fn next(
-        &self,
+        &mut self,
         helper: &SubscriptionHelper,
         params: &SubscribeToEventsParams,
     ) -> Vec<EventView> {
@@ -99,3 +101,265 @@ impl Subscription<SubscribeToEventsParams, EventView> for EventsSubscription {
         }
     }
 }
+
+#[derive(Clone, Debug, Default)]
+pub struct BlocksSubscription {
+    /// The next transaction version to scan.
+    pub(crate) next_version: u64,
+    /// The version and metadata of the last block found, whose end is not known yet.
+    pub(crate) pending_block: Option<(u64, BlockMetadata)>,
+}
+
+impl BlocksSubscription {
+    fn finish_block(&mut self, next_block_version: u64) -> Option<BlockView> {
+        self.pending_block
+            .take()
+            .map(|(version, metadata)| BlockView::new(&metadata, version, next_block_version - 1))
+    }
+}
+
+impl Subscription<SubscribeToBlocksParams, BlockView> for BlocksSubscription {
+    fn init(
+        &mut self,
+        _helper: &SubscriptionHelper,
+        params: &SubscribeToBlocksParams,
+    ) -> Result<(), JsonRpcError> {
+        self.next_version = params.starting_version;
+        Ok(())
+    }
+
+    fn next(
+        &mut self,
+        helper: &SubscriptionHelper,
+        _params: &SubscribeToBlocksParams,
+    ) -> Vec<BlockView> {
+        let ledger_version = helper.db.get_latest_version().unwrap_or(0);
+        if self.next_version > ledger_version {
+            return vec![];
+        }
+        let transactions = match helper.db.get_transactions(
+            self.next_version,
+            helper.client.config.fetch_size,
+            ledger_version,
+            false,
+        ) {
+            Ok(transactions) => transactions.transactions,
+            Err(e) => {
+                warn!(
+                    "Client#{} Could not fetch transactions: {}",
+                    helper.client.id, e
+                );
+                return vec![];
+            }
+        };
+
+        let mut blocks = vec![];
+        for transaction in transactions {
+            let version = self.next_version;
+            self.next_version += 1;
+            match transaction {
+                Transaction::BlockMetadata(metadata) => {
+                    blocks.extend(self.finish_block(version));
+                    self.pending_block = Some((version, metadata));
+                }
+                // A write set transaction is not part of any block
+                Transaction::GenesisTransaction(_) => blocks.extend(self.finish_block(version)),
+                Transaction::UserTransaction(_) => (),
+            }
+        }
+        // A ledger info is only committed at the end of a block
+        if self.next_version > ledger_version {
+            blocks.extend(self.finish_block(ledger_version + 1));
+        }
+        blocks
+    }
+
+    fn on_send(&mut self, _block: Option<&BlockView>) {}
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AccountSubscription {
+    /// The next transaction version to scan.
+    pub(crate) next_version: u64,
+}
+
+impl Subscription<SubscribeToAccountParams, AccountView> for AccountSubscription {
+    fn init(
+        &mut self,
+        _helper: &SubscriptionHelper,
+        params: &SubscribeToAccountParams,
+    ) -> Result<(), JsonRpcError> {
+        self.next_version = params.starting_version;
+        Ok(())
+    }
+
+    fn next(
+        &mut self,
+        helper: &SubscriptionHelper,
+        params: &SubscribeToAccountParams,
+    ) -> Vec<AccountView> {
+        let ledger_version = helper.db.get_latest_version().unwrap_or(0);
+        if self.next_version > ledger_version {
+            return vec![];
+        }
+        let outputs = match helper.db.get_transaction_outputs(
+            self.next_version,
+            helper.client.config.fetch_size,
+            ledger_version,
+        ) {
+            Ok(outputs) => outputs.transactions_and_outputs,
+            Err(e) => {
+                warn!(
+                    "Client#{} Could not fetch transaction outputs: {}",
+                    helper.client.id, e
+                );
+                return vec![];
+            }
+        };
+
+        let mut accounts = vec![];
+        for (_, output) in outputs {
+            let version = self.next_version;
+            let touched = output
+                .write_set()
+                .iter()
+                .any(|(access_path, _)| access_path.address == params.address);
+            if touched {
+                match get_account(helper.db.borrow(), params.address, version) {
+                    Ok(account) => accounts.extend(account),
+                    Err(e) => {
+                        // Retry from this version next time
+                        warn!(
+                            "Client#{} Could not fetch account {}: {}",
+                            helper.client.id, params.address, e
+                        );
+                        break;
+                    }
+                }
+            }
+            self.next_version += 1;
+        }
+        accounts
+    }
+
+    fn on_send(&mut self, _account: Option<&AccountView>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream_rpc::tests::util::create_client_connection;
+    use diem_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
+    use diem_json_rpc_types::{stream::request::StreamMethod, Id};
+    use diem_types::{
+        access_path::AccessPath,
+        account_address::AccountAddress,
+        test_helpers::transaction_test_helpers::get_test_signed_txn,
+        vm_status::KeptVMStatus,
+        write_set::{WriteOp, WriteSet, WriteSetMut},
+    };
+    use std::sync::Arc;
+
+    fn user_transaction() -> Transaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        Transaction::UserTransaction(get_test_signed_txn(
+            AccountAddress::random(),
+            0,
+            &private_key,
+            private_key.public_key(),
+            None,
+        ))
+    }
+
+    fn block_metadata(round: u64) -> BlockMetadata {
+        BlockMetadata::new(
+            HashValue::random(),
+            round,
+            round * 1000,
+            vec![],
+            AccountAddress::random(),
+        )
+    }
+
+    #[test]
+    fn test_blocks_subscription() {
+        let (mut mock_db, client, _receiver) = create_client_connection();
+        let metadata = vec![block_metadata(1), block_metadata(2)];
+        mock_db.all_txns = vec![
+            Transaction::BlockMetadata(metadata[0].clone()),
+            user_transaction(),
+            user_transaction(),
+            Transaction::BlockMetadata(metadata[1].clone()),
+            user_transaction(),
+        ]
+        .into_iter()
+        .map(|txn| (txn, KeptVMStatus::Executed))
+        .collect();
+        mock_db.version = 4;
+        let helper = SubscriptionHelper::new(
+            Arc::new(mock_db),
+            client,
+            Id::Number(1),
+            StreamMethod::SubscribeToBlocks,
+        );
+
+        let params = SubscribeToBlocksParams {
+            starting_version: 0,
+        };
+        let mut subscription = BlocksSubscription::default();
+        subscription.init(&helper, &params).unwrap();
+        // The fetch size is 1, a block is only sent once its last transaction is scanned
+        let blocks: Vec<Vec<BlockView>> = (0..6)
+            .map(|_| subscription.next(&helper, &params))
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![
+                vec![],
+                vec![],
+                vec![],
+                vec![BlockView::new(&metadata[0], 0, 2)],
+                vec![BlockView::new(&metadata[1], 3, 4)],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_account_subscription() {
+        let (mut mock_db, client, _receiver) = create_client_connection();
+        let address = *mock_db.all_accounts.keys().next().unwrap();
+        let touch = WriteSetMut::new(vec![(
+            AccessPath::new(address, b"path".to_vec()),
+            WriteOp::Deletion,
+        )])
+        .freeze()
+        .unwrap();
+        mock_db.all_txns = (0..5)
+            .map(|_| (user_transaction(), KeptVMStatus::Executed))
+            .collect();
+        mock_db.write_sets = vec![WriteSet::default(); 5];
+        mock_db.write_sets[1] = touch.clone();
+        mock_db.write_sets[3] = touch;
+        mock_db.version = 4;
+        let helper = SubscriptionHelper::new(
+            Arc::new(mock_db),
+            client,
+            Id::Number(1),
+            StreamMethod::SubscribeToAccount,
+        );
+
+        let params = SubscribeToAccountParams {
+            address,
+            starting_version: 0,
+        };
+        let mut subscription = AccountSubscription::default();
+        subscription.init(&helper, &params).unwrap();
+        // The fetch size is 1
+        let versions: Vec<_> = (0..6)
+            .flat_map(|_| subscription.next(&helper, &params))
+            .map(|account| (account.address, account.version))
+            .collect();
+        assert_eq!(versions, vec![(address, Some(1)), (address, Some(3))]);
+    }
+}
@@ -49,7 +49,12 @@ async fn test_websocket_call_and_response() {
 async fn test_invalid_params() {
     let (mock_db, config) = ws_test_setup(5, 10, 100, 1000).await;
 
-    let endpoint_names = vec!["subscribe_to_transactions", "subscribe_to_events"];
+    let endpoint_names = vec![
+        "subscribe_to_transactions",
+        "subscribe_to_events",
+        "subscribe_to_blocks",
+        "subscribe_to_account",
+    ];
 
     for endpoint_name in endpoint_names {
         let name = format!("{}: invalid param", &endpoint_name);
@@ -14,7 +14,10 @@ use crate::{
 use diem_client::{views::TransactionDataView, BlockingClient, MethodRequest};
 use diem_config::{config::DEFAULT_CONTENT_LENGTH_LIMIT, utils};
 use diem_crypto::{ed25519::Ed25519PrivateKey, hash::CryptoHash, HashValue, PrivateKey, Uniform};
-use diem_mempool::MempoolClientRequest;
+use diem_mempool::{
+    AccountPendingTransactions, MempoolClientRequest, MempoolStats, MempoolTransactionState,
+    PendingTransaction,
+};
 use diem_metrics::get_all_metrics;
 use diem_types::{
     account_address::AccountAddress,
@@ -1112,6 +1115,115 @@ fn test_transaction_submission() {
     assert_eq!(status_code, StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST);
 }
 
+#[test]
+fn test_get_mempool_transactions() {
+    let (mp_sender, mut mp_events) = channel(1);
+    let mock_db = mock_db();
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+    let runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+    let url = format!("http://127.0.0.1:{}/v1", port);
+
+    let sender = AccountAddress::new([9; AccountAddress::LENGTH]);
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let pending_txn = |sequence_number, state| PendingTransaction {
+        txn: get_test_signed_txn(
+            sender,
+            sequence_number,
+            &privkey,
+            privkey.public_key(),
+            None,
+        ),
+        state,
+        ranking_score: 1,
+        expiration_time: Duration::from_secs(100),
+    };
+    let pending_txns = vec![
+        pending_txn(3, MempoolTransactionState::Ready),
+        pending_txn(5, MempoolTransactionState::Parked),
+    ];
+    let parked_txn = pending_txns[1].clone();
+    let parked_hash = Transaction::UserTransaction(parked_txn.txn.clone()).hash();
+
+    // future that mocks shared mempool execution
+    runtime.spawn(async move {
+        while let Some(request) = mp_events.next().await {
+            match request {
+                MempoolClientRequest::GetAccountTransactions(_, cb) => cb
+                    .send(Ok(AccountPendingTransactions {
+                        sequence_number: 3,
+                        transactions: pending_txns.clone(),
+                    }))
+                    .unwrap(),
+                MempoolClientRequest::GetPendingTransactionByHash(hash, cb) => {
+                    let txn = if hash == parked_hash {
+                        Some(parked_txn.clone())
+                    } else {
+                        None
+                    };
+                    cb.send(txn).unwrap()
+                }
+                MempoolClientRequest::GetStats(cb) => cb
+                    .send(MempoolStats {
+                        size: 2,
+                        ready: 1,
+                        parked: 1,
+                        accounts: 1,
+                        capacity: 100,
+                        capacity_per_user: 10,
+                    })
+                    .unwrap(),
+                _ => panic!("unexpected mempool request"),
+            }
+        }
+    });
+
+    let client = reqwest::blocking::Client::new();
+    let call = |method: &str, params: serde_json::Value| {
+        let request = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+        let resp: serde_json::Value = client
+            .post(&url)
+            .json(&request)
+            .send()
+            .unwrap()
+            .json()
+            .unwrap();
+        assert!(resp.get("error").is_none(), "{}", resp);
+        resp["result"].clone()
+    };
+
+    let result = call("get_mempool_account_transactions", json!([sender]));
+    assert_eq!(result["account"], json!(sender));
+    assert_eq!(result["sequence_number"], json!(3));
+    assert_eq!(result["missing_sequence_number"], json!(4));
+    let txns = result["transactions"].as_array().unwrap();
+    assert_eq!(txns.len(), 2);
+    assert_eq!(txns[0]["state"], json!("ready"));
+    assert_eq!(txns[0]["transaction"]["sequence_number"], json!(3));
+    assert_eq!(txns[1]["state"], json!("parked"));
+    assert_eq!(txns[1]["hash"], json!(parked_hash));
+    assert_eq!(txns[1]["system_expiration_timestamp_secs"], json!(100));
+
+    let result = call("get_mempool_transaction", json!([parked_hash]));
+    assert_eq!(result["state"], json!("parked"));
+    assert_eq!(result["transaction"]["sequence_number"], json!(5));
+    let result = call("get_mempool_transaction", json!([HashValue::zero()]));
+    assert!(result.is_null());
+
+    let result = call("get_mempool_stats", json!([]));
+    assert_eq!(
+        result,
+        json!({
+            "size": 2,
+            "ready": 1,
+            "parked": 1,
+            "accounts": 1,
+            "capacity": 100,
+            "capacity_per_user": 10,
+        })
+    );
+}
+
 #[test]
 fn test_get_account() {
     let (mock_db, client, _runtime) = create_database_client_and_runtime();
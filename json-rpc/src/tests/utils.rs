@@ -9,6 +9,7 @@ use diem_config::{
 use diem_crypto::HashValue;
 use diem_mempool::{MempoolClientSender, MempoolEventsReceiver};
 use diem_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
     account_state::AccountState,
     account_state_blob::{default_protocol::AccountStateWithProof, AccountStateBlob},
@@ -29,11 +30,14 @@ use diem_types::{
     state_proof::StateProof,
     transaction::{
         default_protocol::{
-            AccountTransactionsWithProof, TransactionListWithProof, TransactionWithProof,
+            AccountTransactionsWithProof, TransactionListWithProof, TransactionOutputListWithProof,
+            TransactionWithProof,
         },
-        Transaction, TransactionInfo, TransactionInfoTrait, Version,
+        Transaction, TransactionInfo, TransactionInfoTrait, TransactionOutput, TransactionStatus,
+        Version,
     },
     vm_status::KeptVMStatus,
+    write_set::WriteSet,
 };
 use diemdb::test_helper::arb_blocks_to_commit;
 
@@ -43,9 +47,9 @@ use diem_proptest_helpers::ValueGenerator;
 use diem_types::account_config::FreezingBit;
 use futures::channel::mpsc::channel;
 use move_core_types::{
-    language_storage::{ModuleId, StructTag, TypeTag},
+    language_storage::{ModuleId, StructTag, TableHandle, TypeTag},
     move_resource::MoveResource,
-    resolver::{ModuleResolver, ResourceResolver},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
     value::{MoveStructLayout, MoveTypeLayout},
 };
 use move_vm_types::values::{Struct, Value};
@@ -93,6 +97,7 @@ pub struct MockDiemDB {
     pub genesis: HashMap<AccountAddress, AccountStateBlob>,
     pub all_accounts: HashMap<AccountAddress, AccountStateBlob>,
     pub all_txns: Vec<(Transaction, KeptVMStatus)>,
+    pub write_sets: Vec<WriteSet>,
     pub events: Vec<(u64, ContractEvent)>,
     pub account_state_with_proof: Vec<AccountStateWithProof>,
     pub timestamps: Vec<u64>,
@@ -259,6 +264,36 @@ impl DbReader<DpnProto> for MockDiemDB {
         })
     }
 
+    fn get_transaction_outputs(
+        &self,
+        start_version: u64,
+        limit: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionOutputListWithProof> {
+        let txns = self.get_transactions(start_version, limit, ledger_version, true)?;
+        let transactions_and_outputs = txns
+            .transactions
+            .into_iter()
+            .zip(txns.events.unwrap_or_default())
+            .zip(txns.proof.transaction_infos.iter())
+            .enumerate()
+            .map(|(i, ((txn, events), info))| {
+                let write_set = self
+                    .write_sets
+                    .get(start_version as usize + i)
+                    .cloned()
+                    .unwrap_or_default();
+                let status = TransactionStatus::Keep(info.status().clone());
+                (txn, TransactionOutput::new(write_set, events, 0, status))
+            })
+            .collect();
+        Ok(TransactionOutputListWithProof::new(
+            transactions_and_outputs,
+            txns.first_transaction_version,
+            txns.proof,
+        ))
+    }
+
     fn get_events(
         &self,
         key: &EventKey,
@@ -399,6 +434,28 @@ impl ResourceResolver for MockDiemDB {
     }
 }
 
+impl TableResolver for MockDiemDB {
+    type Error = anyhow::Error;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        let access_path = AccessPath::table_item_access_path(*handle, key.to_vec());
+        let (account_state_with_proof, _) = self.get_account_state_with_proof_by_version(
+            access_path.address,
+            self.get_latest_version()?,
+        )?;
+        if let Some(account_state_blob) = account_state_with_proof {
+            let account_state = AccountState::try_from(&account_state_blob)?;
+            Ok(account_state.get(&access_path.path).cloned())
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 impl MoveDbReader<DpnProto> for MockDiemDB {}
 
 // returns MockDiemDB for unit-testing
@@ -411,6 +468,7 @@ pub fn mock_db() -> MockDiemDB {
     let mut version = 1;
     let mut all_accounts = HashMap::new();
     let mut all_txns = vec![];
+    let mut write_sets = vec![];
     let mut events = vec![];
     let mut timestamps = vec![0_u64];
 
@@ -449,6 +507,11 @@ pub fn mock_db() -> MockDiemDB {
                 txn_to_commit.status().clone(),
             )
         }));
+        write_sets.extend(
+            txns_to_commit
+                .iter()
+                .map(|txn_to_commit| txn_to_commit.write_set().clone()),
+        );
     }
 
     if account_state_with_proof.blob.is_none() {
@@ -475,6 +538,7 @@ pub fn mock_db() -> MockDiemDB {
         genesis,
         all_accounts,
         all_txns,
+        write_sets,
         events,
         account_state_with_proof,
         timestamps,
@@ -51,6 +51,7 @@ pub enum ServerCode {
     MempoolInvalidUpdate = -32010,
     MempoolVmError = -32011,
     MempoolUnknownError = -32012,
+    MempoolReplacementGasPriceTooLow = -32013,
 }
 
 /// JSON RPC server error codes for invalid request
@@ -203,7 +204,12 @@ impl JsonRpcError {
             MempoolStatusCode::InvalidUpdate => ServerCode::MempoolInvalidUpdate,
             MempoolStatusCode::VmError => ServerCode::MempoolVmError,
             MempoolStatusCode::UnknownStatus => ServerCode::MempoolUnknownError,
-            MempoolStatusCode::Accepted => {
+            MempoolStatusCode::ReplacementGasPriceTooLow => {
+                ServerCode::MempoolReplacementGasPriceTooLow
+            }
+            MempoolStatusCode::Accepted
+            | MempoolStatusCode::Replaced
+            | MempoolStatusCode::AcceptedWithEviction => {
                 return Err(anyhow::format_err!(
                     "[JSON RPC] cannot create mempool error for mempool accepted status"
                 ))
@@ -297,6 +303,12 @@ mod tests {
             message: "error msg".to_string(),
         });
         assert!(err.is_err());
+        for code in &[
+            MempoolStatusCode::Replaced,
+            MempoolStatusCode::AcceptedWithEviction,
+        ] {
+            assert!(JsonRpcError::mempool_error(MempoolStatus::new(*code)).is_err());
+        }
 
         assert_map_code(
             MempoolStatusCode::InvalidSeqNumber,
@@ -316,6 +328,10 @@ mod tests {
             MempoolStatusCode::UnknownStatus,
             ServerCode::MempoolUnknownError,
         );
+        assert_map_code(
+            MempoolStatusCode::ReplacementGasPriceTooLow,
+            ServerCode::MempoolReplacementGasPriceTooLow,
+        );
     }
 
     #[test]
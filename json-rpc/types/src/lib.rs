@@ -59,6 +59,9 @@ pub enum Method {
     GetAccountTransactionsWithProofs,
     GetEventsWithProofs,
     GetEventByVersionWithProof,
+    GetMempoolAccountTransactions,
+    GetMempoolTransaction,
+    GetMempoolStats,
 }
 
 impl Method {
@@ -81,6 +84,9 @@ impl Method {
             Method::GetAccountTransactionsWithProofs => "get_account_transactions_with_proofs",
             Method::GetEventsWithProofs => "get_events_with_proofs",
             Method::GetEventByVersionWithProof => "get_event_by_version_with_proof",
+            Method::GetMempoolAccountTransactions => "get_mempool_account_transactions",
+            Method::GetMempoolTransaction => "get_mempool_transaction",
+            Method::GetMempoolStats => "get_mempool_stats",
         }
     }
 }
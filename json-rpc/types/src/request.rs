@@ -3,6 +3,7 @@
 
 use super::{Id, JsonRpcVersion, Method};
 use crate::{errors::JsonRpcError, views::BytesView};
+use diem_crypto::HashValue;
 use diem_types::{
     account_address::AccountAddress, event::EventKey, transaction::SignedTransaction,
 };
@@ -84,6 +85,9 @@ pub enum MethodRequest {
     GetAccountTransactionsWithProofs(GetAccountTransactionsWithProofsParams),
     GetEventsWithProofs(GetEventsWithProofsParams),
     GetEventByVersionWithProof(GetEventByVersionWithProof),
+    GetMempoolAccountTransactions(GetMempoolAccountTransactionsParams),
+    GetMempoolTransaction(GetMempoolTransactionParams),
+    GetMempoolStats(GetMempoolStatsParams),
 }
 
 impl MethodRequest {
@@ -126,6 +130,15 @@ impl MethodRequest {
             Method::GetEventByVersionWithProof => {
                 MethodRequest::GetEventByVersionWithProof(serde_json::from_value(value)?)
             }
+            Method::GetMempoolAccountTransactions => {
+                MethodRequest::GetMempoolAccountTransactions(serde_json::from_value(value)?)
+            }
+            Method::GetMempoolTransaction => {
+                MethodRequest::GetMempoolTransaction(serde_json::from_value(value)?)
+            }
+            Method::GetMempoolStats => {
+                MethodRequest::GetMempoolStats(serde_json::from_value(value)?)
+            }
         };
 
         Ok(method_request)
@@ -154,6 +167,11 @@ impl MethodRequest {
             }
             MethodRequest::GetEventsWithProofs(_) => Method::GetEventsWithProofs,
             MethodRequest::GetEventByVersionWithProof(_) => Method::GetEventByVersionWithProof,
+            MethodRequest::GetMempoolAccountTransactions(_) => {
+                Method::GetMempoolAccountTransactions
+            }
+            MethodRequest::GetMempoolTransaction(_) => Method::GetMempoolTransaction,
+            MethodRequest::GetMempoolStats(_) => Method::GetMempoolStats,
         }
     }
 }
@@ -383,6 +401,30 @@ pub struct GetEventByVersionWithProof {
     pub version: Option<u64>,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetMempoolAccountTransactionsParams {
+    pub account: AccountAddress,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GetMempoolTransactionParams {
+    pub hash: HashValue,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GetMempoolStatsParams;
+
+impl<'de> Deserialize<'de> for GetMempoolStatsParams {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_option(NoParamsVisitor("get_mempool_stats params"))
+            .map(|_| GetMempoolStatsParams)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -996,4 +1038,47 @@ mod test {
         // Object with more params
         parse_ok(json!({ "key": key, "version": 10, "foo": 99 }));
     }
+
+    #[test]
+    fn get_mempool_account_transactions() {
+        let parse = |value| serde_json::from_value::<GetMempoolAccountTransactionsParams>(value);
+
+        let account = "000000000000000000000000000000dd";
+        parse(json!([account])).unwrap();
+        parse(json!({ "account": account })).unwrap();
+
+        parse(json!([])).unwrap_err();
+        parse(json!(["foo"])).unwrap_err();
+        parse(json!([account, 10])).unwrap_err();
+    }
+
+    #[test]
+    fn get_mempool_transaction() {
+        let parse = |value| serde_json::from_value::<GetMempoolTransactionParams>(value);
+
+        let hash = HashValue::zero().to_hex();
+        parse(json!([hash])).unwrap();
+        parse(json!({ "hash": hash })).unwrap();
+
+        parse(json!([])).unwrap_err();
+        parse(json!(["foo"])).unwrap_err();
+    }
+
+    #[test]
+    fn get_mempool_stats() {
+        let parse_ok = |value| serde_json::from_value::<GetMempoolStatsParams>(value).unwrap();
+        let parse_err = |value| serde_json::from_value::<GetMempoolStatsParams>(value).unwrap_err();
+
+        parse_err(json!([10]));
+        parse_ok(json!([]));
+        parse_ok(json!({}));
+        parse_ok(serde_json::Value::Null);
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": Method::GetMempoolStats,
+            "id": 1,
+        });
+        serde_json::from_value::<JsonRpcRequest>(request).unwrap();
+    }
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{errors::JsonRpcError, request::RawJsonRpcRequest, Id, JsonRpcVersion};
-use diem_types::event::EventKey;
+use diem_types::{account_address::AccountAddress, event::EventKey};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -74,6 +74,8 @@ impl FromStr for StreamJsonRpcRequest {
 pub enum StreamMethodRequest {
     SubscribeToTransactions(SubscribeToTransactionsParams),
     SubscribeToEvents(SubscribeToEventsParams),
+    SubscribeToBlocks(SubscribeToBlocksParams),
+    SubscribeToAccount(SubscribeToAccountParams),
     Unsubscribe,
 }
 
@@ -93,6 +95,12 @@ impl StreamMethodRequest {
             StreamMethod::SubscribeToEvents => {
                 StreamMethodRequest::SubscribeToEvents(serde_json::from_value(value)?)
             }
+            StreamMethod::SubscribeToBlocks => {
+                StreamMethodRequest::SubscribeToBlocks(serde_json::from_value(value)?)
+            }
+            StreamMethod::SubscribeToAccount => {
+                StreamMethodRequest::SubscribeToAccount(serde_json::from_value(value)?)
+            }
             StreamMethod::Unsubscribe => StreamMethodRequest::Unsubscribe,
         };
 
@@ -105,6 +113,8 @@ impl StreamMethodRequest {
                 StreamMethod::SubscribeToTransactions
             }
             StreamMethodRequest::SubscribeToEvents(_) => StreamMethod::SubscribeToEvents,
+            StreamMethodRequest::SubscribeToBlocks(_) => StreamMethod::SubscribeToBlocks,
+            StreamMethodRequest::SubscribeToAccount(_) => StreamMethod::SubscribeToAccount,
             StreamMethodRequest::Unsubscribe => StreamMethod::Unsubscribe,
         }
     }
@@ -115,6 +125,8 @@ impl StreamMethodRequest {
 pub enum StreamMethod {
     SubscribeToTransactions,
     SubscribeToEvents,
+    SubscribeToBlocks,
+    SubscribeToAccount,
     Unsubscribe,
 }
 
@@ -123,6 +135,8 @@ impl StreamMethod {
         match self {
             StreamMethod::SubscribeToTransactions => "subscribe_to_transactions",
             StreamMethod::SubscribeToEvents => "subscribe_to_events",
+            StreamMethod::SubscribeToBlocks => "subscribe_to_blocks",
+            StreamMethod::SubscribeToAccount => "subscribe_to_account",
             StreamMethod::Unsubscribe => "unsubscribe",
         }
     }
@@ -139,3 +153,14 @@ pub struct SubscribeToTransactionsParams {
     pub starting_version: u64,
     pub include_events: Option<bool>,
 }
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct SubscribeToBlocksParams {
+    pub starting_version: u64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct SubscribeToAccountParams {
+    pub address: AccountAddress,
+    pub starting_version: u64,
+}
@@ -14,6 +14,7 @@ use diem_types::{
     },
     account_state::AccountState,
     account_state_blob::{default_protocol::AccountStateWithProof, AccountStateBlob},
+    block_metadata::BlockMetadata,
     contract_event::{
         default_protocol::{EventByVersionWithProof, EventWithProof},
         ContractEvent,
@@ -27,8 +28,8 @@ use diem_types::{
     state_proof::StateProof,
     transaction::{
         default_protocol::{AccountTransactionsWithProof, TransactionListWithProof},
-        Script, ScriptFunction, Transaction, TransactionArgument, TransactionInfoTrait,
-        TransactionPayload,
+        Script, ScriptFunction, SignedTransaction, Transaction, TransactionArgument,
+        TransactionInfoTrait, TransactionPayload,
     },
     vm_status::KeptVMStatus,
 };
@@ -819,6 +820,35 @@ impl From<&KeptVMStatus> for VMStatusView {
     }
 }
 
+/// A committed block: the `BlockMetadata` transaction starting it, and the range of the versions of
+/// its transactions, including the `BlockMetadata` transaction.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct BlockView {
+    pub id: HashValue,
+    pub round: u64,
+    pub timestamp_usecs: u64,
+    pub proposer: AccountAddress,
+    pub first_transaction_version: u64,
+    pub last_transaction_version: u64,
+}
+
+impl BlockView {
+    pub fn new(
+        metadata: &BlockMetadata,
+        first_transaction_version: u64,
+        last_transaction_version: u64,
+    ) -> Self {
+        Self {
+            id: metadata.id(),
+            round: metadata.round(),
+            timestamp_usecs: metadata.timestamp_usec(),
+            proposer: metadata.proposer(),
+            first_transaction_version,
+            last_transaction_version,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct TransactionView {
     pub version: u64,
@@ -1560,6 +1590,65 @@ impl TryFrom<&AccountStateProofView> for AccountStateProof {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolTransactionStateView {
+    /// The transaction can be included in the next block.
+    Ready,
+    /// The transaction waits for a transaction with a lower sequence number.
+    Parked,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MempoolTransactionView {
+    pub hash: HashValue,
+    pub state: MempoolTransactionStateView,
+    pub ranking_score: u64,
+    /// Time after which Mempool drops the transaction, in seconds since the Unix epoch.
+    pub system_expiration_timestamp_secs: u64,
+    pub transaction: TransactionDataView,
+}
+
+impl MempoolTransactionView {
+    pub fn new(
+        txn: SignedTransaction,
+        state: MempoolTransactionStateView,
+        ranking_score: u64,
+        system_expiration_timestamp_secs: u64,
+    ) -> Self {
+        let txn = Transaction::UserTransaction(txn);
+        Self {
+            hash: txn.hash(),
+            state,
+            ranking_score,
+            system_expiration_timestamp_secs,
+            transaction: TransactionDataView::from(txn),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MempoolAccountTransactionsView {
+    pub account: AccountAddress,
+    /// Sequence number of the account in the latest committed state.
+    pub sequence_number: u64,
+    /// First sequence number missing for the parked transactions to become ready, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_sequence_number: Option<u64>,
+    /// Pending transactions, in increasing sequence number order.
+    pub transactions: Vec<MempoolTransactionView>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MempoolStatsView {
+    pub size: u64,
+    pub ready: u64,
+    pub parked: u64,
+    pub accounts: u64,
+    pub capacity: u64,
+    pub capacity_per_user: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::views::{
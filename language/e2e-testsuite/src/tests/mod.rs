@@ -30,6 +30,7 @@ mod preburn_queue;
 mod rotate_key;
 mod script_functions;
 mod scripts;
+mod tables;
 mod transaction_builder;
 mod transaction_fees;
 mod transaction_fuzzer;
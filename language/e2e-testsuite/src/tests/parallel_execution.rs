@@ -7,16 +7,21 @@ use diem_framework_releases::current_modules;
 use diem_parallel_executor::errors::Error;
 use diem_types::{
     block_metadata::BlockMetadata,
-    on_chain_config::{OnChainConfig, ParallelExecutionConfig, ValidatorSet},
+    on_chain_config::{OnChainConfig, ParallelExecutionConfig, ReadWriteSetAnalysis, ValidatorSet},
     transaction::{
-        authenticator::AuthenticationKey, Script, Transaction, TransactionArgument,
+        authenticator::AuthenticationKey, Module, Script, Transaction, TransactionArgument,
         TransactionStatus, WriteSetPayload,
     },
     vm_status::{KeptVMStatus, StatusCode},
 };
-use diem_vm::{parallel_executor::ParallelDiemVM, read_write_set_analysis::add_on_functions_list};
+use diem_vm::{
+    parallel_executor::ParallelDiemVM, read_write_set_analysis::add_on_functions_list, DiemVM,
+    VMExecutor,
+};
 use diem_writeset_generator::encode_initialize_parallel_execution;
-use language_e2e_tests::{account, common_transactions::rotate_key_txn, executor::FakeExecutor};
+use language_e2e_tests::{
+    account, common_transactions::rotate_key_txn, compile::compile_script, executor::FakeExecutor,
+};
 use move_ir_compiler::Compiler;
 use read_write_set::analyze;
 
@@ -159,6 +164,43 @@ fn parallel_execution_config() {
     );
 }
 
+#[test]
+fn dynamic_parallel_execution_config() {
+    let mut executor = FakeExecutor::from_fresh_genesis();
+    let account_size = 1000usize;
+    let initial_balance = 2_000_000u64;
+    let initial_seq_num = 10u64;
+    let accounts = executor.create_accounts(account_size, initial_balance, initial_seq_num);
+
+    // set up the transactions
+    let transfer_amount = 1_000;
+
+    // insert a block prologue transaction
+    let (txns_info, transfer_txns) = create_cyclic_transfers(&executor, &accounts, transfer_amount);
+
+    executor.enable_dynamic_parallel_execution();
+
+    assert_eq!(
+        ParallelExecutionConfig::fetch_config(executor.get_state_view()),
+        Some(ParallelExecutionConfig {
+            read_write_analysis_result: Some(ReadWriteSetAnalysis::Dynamic),
+        })
+    );
+
+    let outputs = executor.execute_block(transfer_txns).unwrap();
+
+    check_and_apply_transfer_output(&mut executor, &txns_info, &outputs);
+
+    executor.disable_parallel_execution();
+
+    assert_eq!(
+        ParallelExecutionConfig::fetch_config(executor.get_state_view()),
+        Some(ParallelExecutionConfig {
+            read_write_analysis_result: None,
+        })
+    );
+}
+
 #[test]
 fn parallel_execution_genesis() {
     let mut executor = FakeExecutor::parallel_genesis();
@@ -296,3 +338,74 @@ label b0:
 
     check_and_apply_transfer_output(&mut executor, &txns_info, &outputs);
 }
+
+#[test]
+fn dynamic_parallel_execution_publish_then_call() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let module_code = format!(
+        "
+        module 0x{}.M {{
+            public value(): u64 {{
+            label b0:
+                return 42;
+            }}
+        }}
+        ",
+        sender.address(),
+    );
+    let compiler = Compiler {
+        deps: current_modules().iter().collect(),
+    };
+    let module = compiler
+        .into_compiled_module(module_code.as_str())
+        .expect("Module compilation failed");
+    let mut module_blob = vec![];
+    module
+        .serialize(&mut module_blob)
+        .expect("Module must serialize");
+    let publish_txn = sender
+        .account()
+        .transaction()
+        .module(Module::new(module_blob))
+        .sequence_number(10)
+        .sign();
+
+    let script_code = format!(
+        "
+        import 0x{}.M;
+
+        main() {{
+        label b0:
+            assert(M.value() == 42, 77);
+            return;
+        }}
+        ",
+        sender.address(),
+    );
+    let call_txn = sender
+        .account()
+        .transaction()
+        .script(compile_script(&script_code, vec![module]))
+        .sequence_number(11)
+        .sign();
+
+    let txns = vec![
+        Transaction::UserTransaction(publish_txn),
+        Transaction::UserTransaction(call_txn),
+    ];
+    let sequential_outputs =
+        DiemVM::execute_block(txns.clone(), executor.get_state_view()).unwrap();
+    let parallel_outputs =
+        ParallelDiemVM::execute_block_dynamic(txns, executor.get_state_view()).unwrap();
+
+    assert_eq!(parallel_outputs, sequential_outputs);
+    for output in &parallel_outputs {
+        assert_eq!(
+            output.status(),
+            &TransactionStatus::Keep(KeptVMStatus::Executed)
+        );
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use diem_types::{
+    access_path::Path,
+    transaction::{Module, SignedTransaction, TransactionOutput, TransactionStatus},
+    vm_status::KeptVMStatus,
+    write_set::WriteOp,
+};
+use language_e2e_tests::{account::AccountData, compile::compile_script, executor::FakeExecutor};
+use move_binary_format::{access::ModuleAccess, CompiledModule};
+use move_core_types::language_storage::TableHandle;
+use move_ir_compiler::Compiler;
+
+#[test]
+fn table_items_across_transactions() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let (module, txn) = add_module_txn(&sender, 10);
+    executor.execute_and_apply(txn);
+
+    // the item is written on its own, under the handle created for the table
+    let output = executor.execute_transaction(call_txn(&sender, 11, &module, "publish"));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(KeptVMStatus::Executed)
+    );
+    let items = table_items(&output);
+    assert_eq!(items.len(), 1);
+    let (handle, key, value) = &items[0];
+    assert_eq!(key, &bcs::to_bytes(&7u64).unwrap());
+    assert_eq!(value, &Some(bcs::to_bytes(&42u64).unwrap()));
+    executor.apply_write_set(output.write_set());
+
+    // the item is read back from the storage by a later transaction
+    let output = executor.execute_transaction(call_txn(&sender, 12, &module, "check"));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(KeptVMStatus::Executed)
+    );
+    executor.apply_write_set(output.write_set());
+
+    // tables created by other transactions get other handles
+    let other = executor.create_raw_account_data(1_000_000, 10);
+    executor.add_account_data(&other);
+    let output = executor.execute_transaction(call_txn(&other, 10, &module, "publish"));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(KeptVMStatus::Executed)
+    );
+    let other_items = table_items(&output);
+    assert_eq!(other_items.len(), 1);
+    assert_ne!(&other_items[0].0, handle);
+    executor.apply_write_set(output.write_set());
+
+    // removing the item deletes it from the storage
+    let output = executor.execute_transaction(call_txn(&sender, 13, &module, "remove"));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(KeptVMStatus::Executed)
+    );
+    assert_eq!(table_items(&output), vec![(*handle, key.clone(), None)]);
+    executor.apply_write_set(output.write_set());
+
+    let output = executor.execute_transaction(call_txn(&sender, 14, &module, "check"));
+    assert!(matches!(
+        output.status().status(),
+        Ok(KeptVMStatus::MoveAbort(..))
+    ));
+}
+
+/// The table items written by `output`, as (handle, key, value) triples.
+fn table_items(output: &TransactionOutput) -> Vec<(TableHandle, Vec<u8>, Option<Vec<u8>>)> {
+    output
+        .write_set()
+        .iter()
+        .filter_map(|(access_path, op)| match access_path.get_path() {
+            Path::TableItem { handle, key } => {
+                let value = match op {
+                    WriteOp::Value(blob) => Some(blob.clone()),
+                    WriteOp::Deletion => None,
+                };
+                Some((handle, key, value))
+            }
+            Path::Code(_) | Path::Resource(_) => None,
+        })
+        .collect()
+}
+
+fn add_module_txn(sender: &AccountData, seq_num: u64) -> (CompiledModule, SignedTransaction) {
+    let module_code = format!(
+        "
+        module 0x{}.Tables {{
+            import 0x1.Signer;
+            import 0x1.Table;
+
+            struct Holder has key {{ items: Table.Table<u64, u64> }}
+
+            public publish(account: &signer) {{
+                let items: Table.Table<u64, u64>;
+            label b0:
+                items = Table.new<u64, u64>();
+                Table.add<u64, u64>(&mut items, 7, 42);
+                move_to<Holder>(move(account), Holder {{ items: move(items) }});
+                return;
+            }}
+
+            public check(account: &signer) acquires Holder {{
+                let holder: &Self.Holder;
+            label b0:
+                holder = borrow_global<Holder>(Signer.address_of(move(account)));
+                assert(*Table.borrow<u64, u64>(&move(holder).Holder::items, 7) == 42, 100);
+                return;
+            }}
+
+            public remove(account: &signer) acquires Holder {{
+                let holder: &mut Self.Holder;
+                let val: u64;
+            label b0:
+                holder = borrow_global_mut<Holder>(Signer.address_of(move(account)));
+                val = Table.remove<u64, u64>(&mut move(holder).Holder::items, 7);
+                return;
+            }}
+        }}
+        ",
+        sender.address(),
+    );
+
+    let compiler = Compiler {
+        deps: diem_framework_releases::current_modules().iter().collect(),
+    };
+    let module = compiler
+        .into_compiled_module(module_code.as_str())
+        .expect("Module compilation failed");
+    let mut module_blob = vec![];
+    module
+        .serialize(&mut module_blob)
+        .expect("Module must serialize");
+    (
+        module,
+        sender
+            .account()
+            .transaction()
+            .module(Module::new(module_blob))
+            .sequence_number(seq_num)
+            .sign(),
+    )
+}
+
+fn call_txn(
+    sender: &AccountData,
+    seq_num: u64,
+    module: &CompiledModule,
+    function: &str,
+) -> SignedTransaction {
+    let program = format!(
+        "
+            import 0x{}.Tables;
+
+            main(account: signer) {{
+            label b0:
+                Tables.{}(&account);
+                return;
+            }}
+        ",
+        module.self_id().address(),
+        function,
+    );
+
+    let script = compile_script(&program, vec![module.clone()]);
+    sender
+        .account()
+        .transaction()
+        .script(script)
+        .sequence_number(seq_num)
+        .sign()
+}
@@ -1,19 +1,36 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::Result;
+use crossbeam_channel::{select, unbounded, Receiver, Sender};
 use lsp_server::{Connection, Message, Notification, Request, Response};
 use lsp_types::{
-    notification::Notification as _, request::Request as _, CompletionOptions, SaveOptions,
-    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    notification::Notification as _, request::Request as _, CompletionOptions,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, HoverProviderCapability, OneOf,
+    SaveOptions, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions, Url,
     WorkDoneProgressOptions,
 };
 use move_analyzer::{
     completion::on_completion_request,
     context::Context,
+    diagnostics::publish_diagnostics,
+    symbols::{self, on_go_to_def_request, on_hover_request, on_references_request, Symbols},
     vfs::{on_text_document_sync_notification, VirtualFileSystem},
 };
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    thread,
+};
 use structopt::StructOpt;
 
+/// The result of building a package: its symbols, if it type checks, and the diagnostics of the
+/// build for each file they were reported in.
+type BuildResult = Result<(
+    Option<Symbols>,
+    BTreeMap<PathBuf, Vec<lsp_types::Diagnostic>>,
+)>;
+
 #[derive(StructOpt)]
 #[structopt(name = "move-analyzer", about = "A language server for Move")]
 struct Options {}
@@ -39,6 +56,8 @@ fn main() {
     let mut context = Context {
         connection,
         files: VirtualFileSystem::default(),
+        symbols: Symbols::default(),
+        files_with_diagnostics: BTreeSet::new(),
     };
     let capabilities = serde_json::to_value(lsp_types::ServerCapabilities {
         // The server receives notifications from the client as users open, close,
@@ -64,7 +83,11 @@ fn main() {
             },
         )),
         selection_range_provider: None,
-        hover_provider: None,
+        // The server answers hover, "go to definition," and "find references" requests using the
+        // symbols of the package that was most recently built.
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
         // The server provides completions as a user is typing.
         completion_provider: Some(CompletionOptions {
             resolve_provider: None,
@@ -88,12 +111,16 @@ fn main() {
         .initialize(capabilities)
         .expect("could not initialize the connection");
 
+    // Packages are built on a separate thread, so that requests are answered while a build is in
+    // progress, using the symbols of the previous build.
+    let (build_requests, build_results) = spawn_builder();
+
     loop {
-        match context.connection.receiver.recv() {
-            Ok(message) => match message {
-                Message::Request(request) => on_request(&context, &request),
-                Message::Response(response) => on_response(&context, &response),
-                Message::Notification(notification) => {
+        select! {
+            recv(context.connection.receiver) -> message => match message {
+                Ok(Message::Request(request)) => on_request(&context, &request),
+                Ok(Message::Response(response)) => on_response(&context, &response),
+                Ok(Message::Notification(notification)) => {
                     match notification.method.as_str() {
                         lsp_types::notification::Exit::METHOD => break,
                         lsp_types::notification::Cancel::METHOD => {
@@ -101,14 +128,21 @@ fn main() {
                             // It ought to, especially once it begins processing requests that may
                             // take a long time to respond to.
                         }
-                        _ => on_notification(&mut context, &notification),
+                        _ => on_notification(&mut context, &build_requests, &notification),
                     }
                 }
+                Err(error) => {
+                    eprintln!("error: {:?}", error);
+                    break;
+                }
+            },
+            recv(build_results) -> result => match result {
+                Ok((path, result)) => on_build_result(&mut context, &path, result),
+                Err(error) => {
+                    eprintln!("error: {:?}", error);
+                    break;
+                }
             },
-            Err(error) => {
-                eprintln!("error: {:?}", error);
-                break;
-            }
         }
     }
 
@@ -119,6 +153,9 @@ fn main() {
 fn on_request(context: &Context, request: &Request) {
     match request.method.as_str() {
         lsp_types::request::Completion::METHOD => on_completion_request(context, request),
+        lsp_types::request::GotoDefinition::METHOD => on_go_to_def_request(context, request),
+        lsp_types::request::HoverRequest::METHOD => on_hover_request(context, request),
+        lsp_types::request::References::METHOD => on_references_request(context, request),
         _ => todo!("handle request '{}' from client", request.method),
     }
 }
@@ -127,14 +164,89 @@ fn on_response(_context: &Context, _response: &Response) {
     todo!("handle response from client");
 }
 
-fn on_notification(context: &mut Context, notification: &Notification) {
+fn on_notification(
+    context: &mut Context,
+    build_requests: &Sender<PathBuf>,
+    notification: &Notification,
+) {
     match notification.method.as_str() {
         lsp_types::notification::DidOpenTextDocument::METHOD
         | lsp_types::notification::DidChangeTextDocument::METHOD
         | lsp_types::notification::DidSaveTextDocument::METHOD
         | lsp_types::notification::DidCloseTextDocument::METHOD => {
-            on_text_document_sync_notification(&mut context.files, notification)
+            on_text_document_sync_notification(&mut context.files, notification);
+            if let Some(path) =
+                saved_or_opened_document(notification).and_then(|uri| uri.to_file_path().ok())
+            {
+                build_requests
+                    .send(path)
+                    .expect("could not request a package build");
+            }
         }
         _ => todo!("handle notification '{}' from client", notification.method),
     }
 }
+
+/// Returns the document that was opened or saved, if the notification is about either, as both
+/// warrant building the document's package.
+fn saved_or_opened_document(notification: &Notification) -> Option<Url> {
+    match notification.method.as_str() {
+        lsp_types::notification::DidOpenTextDocument::METHOD => {
+            serde_json::from_value::<DidOpenTextDocumentParams>(notification.params.clone())
+                .ok()
+                .map(|parameters| parameters.text_document.uri)
+        }
+        lsp_types::notification::DidSaveTextDocument::METHOD => {
+            serde_json::from_value::<DidSaveTextDocumentParams>(notification.params.clone())
+                .ok()
+                .map(|parameters| parameters.text_document.uri)
+        }
+        _ => None,
+    }
+}
+
+/// Spawns the thread that builds the packages enclosing the documents it is sent. Returns the
+/// channel to send it documents on, and the channel it sends the result of each build on.
+fn spawn_builder() -> (Sender<PathBuf>, Receiver<(PathBuf, BuildResult)>) {
+    let (request_sender, request_receiver) = unbounded::<PathBuf>();
+    let (result_sender, result_receiver) = unbounded();
+    thread::spawn(move || {
+        while let Ok(path) = request_receiver.recv() {
+            // Documents saved while a build was in progress are built once, in the order they
+            // were first saved in.
+            let mut paths = vec![path];
+            for path in request_receiver.try_iter() {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+            for path in paths {
+                let result = symbols::build_package(&path);
+                if result_sender.send((path, result)).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    (request_sender, result_receiver)
+}
+
+/// Publishes the diagnostics of a build of the package enclosing the given document, and updates
+/// the symbols used to answer requests.
+fn on_build_result(context: &mut Context, path: &Path, result: BuildResult) {
+    match result {
+        Ok((symbols, diagnostics)) => {
+            // If the package does not type check, the symbols of the previous build are kept, so
+            // that requests may still be answered while errors are being fixed.
+            if let Some(symbols) = symbols {
+                context.symbols = symbols;
+            }
+            publish_diagnostics(context, diagnostics);
+        }
+        Err(error) => eprintln!(
+            "could not build package for '{}': {:#}",
+            path.display(),
+            error
+        ),
+    }
+}
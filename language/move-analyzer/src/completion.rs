@@ -164,14 +164,9 @@ pub fn on_completion_request(context: &Context, request: &Request) {
         let (identifiers, diagnostics) = identifiers(buffer);
         items.extend_from_slice(&identifiers);
         if !diagnostics.is_empty() {
-            // TODO: A language server is capable of surfacing diagnostics to its client, but to do so
-            // would require us to translate move_lang's diagnostic location (a private member
-            // represented as a byte offset) to a line and column number. The move_lang::diagnostics
-            // module currently only allows for printing a set of diagnostics to stderr, but that could
-            // be changed. (If this server *was* to surface diagnostics, it would also be responsible
-            // for notifying the client when the diagnostics were fixed and ought to be cleared. It
-            // would be a poor user experience to only surface and clear diagnostics here, when
-            // responding to a completion request.)
+            // Diagnostics are surfaced to the client when the file's package is built, upon the
+            // file being opened or saved. It would be a poor user experience to also surface them
+            // here, when responding to a completion request, so they are only logged.
             eprintln!(
                 "encountered {} diagnostic(s) while lexing '{}'",
                 diagnostics.len(),
@@ -1,8 +1,9 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::vfs::VirtualFileSystem;
+use crate::{symbols::Symbols, vfs::VirtualFileSystem};
 use lsp_server::Connection;
+use std::{collections::BTreeSet, path::PathBuf};
 
 /// The context within which the language server is running.
 pub struct Context {
@@ -10,4 +11,8 @@ pub struct Context {
     pub connection: Connection,
     /// The files that the language server is providing information about.
     pub files: VirtualFileSystem,
+    /// The symbols of the most recently built package.
+    pub symbols: Symbols,
+    /// The files for which diagnostics were last published, so that they may be cleared.
+    pub files_with_diagnostics: BTreeSet<PathBuf>,
 }
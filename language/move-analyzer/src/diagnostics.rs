@@ -0,0 +1,113 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Surfaces the compiler's diagnostics to the client. Diagnostics are published for a file, and
+//! replace any diagnostics previously published for it, so a file whose diagnostics were fixed is
+//! cleared by publishing an empty list.
+
+use crate::{
+    context::Context,
+    utils::{canonical_path, loc_to_location, loc_to_range},
+};
+use codespan_reporting::diagnostic::Severity;
+use lsp_server::Notification;
+use lsp_types::{
+    notification::Notification as _, DiagnosticRelatedInformation, DiagnosticSeverity,
+    PublishDiagnosticsParams, Url,
+};
+use move_lang::diagnostics::{Diagnostics, FilesSourceText};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+/// Converts the compiler's diagnostics to diagnostics for each file they were reported in.
+pub fn lsp_diagnostics(
+    diagnostics: Diagnostics,
+    files: &FilesSourceText,
+) -> BTreeMap<PathBuf, Vec<lsp_types::Diagnostic>> {
+    let mut lsp_diagnostics: BTreeMap<PathBuf, Vec<lsp_types::Diagnostic>> = BTreeMap::new();
+    for (severity, message, (loc, label), secondary_labels) in diagnostics.into_codespan_format() {
+        let (file_name, _) = match files.get(&loc.file_hash()) {
+            Some(file) => file,
+            None => continue,
+        };
+        let range = match loc_to_range(files, loc) {
+            Some(range) => range,
+            None => continue,
+        };
+        let related_information = secondary_labels
+            .into_iter()
+            .filter_map(|(loc, message)| {
+                Some(DiagnosticRelatedInformation {
+                    location: loc_to_location(files, loc)?,
+                    message,
+                })
+            })
+            .collect();
+        let severity = match severity {
+            Severity::Bug | Severity::Error => DiagnosticSeverity::Error,
+            Severity::Warning => DiagnosticSeverity::Warning,
+            Severity::Note => DiagnosticSeverity::Information,
+            Severity::Help => DiagnosticSeverity::Hint,
+        };
+        lsp_diagnostics
+            .entry(canonical_path(Path::new(file_name.as_str())))
+            .or_default()
+            .push(lsp_types::Diagnostic {
+                range,
+                severity: Some(severity),
+                source: Some("move-analyzer".to_string()),
+                message: format!("{}\n{}", message, label),
+                related_information: Some(related_information),
+                ..Default::default()
+            });
+    }
+    lsp_diagnostics
+}
+
+/// Publishes the given diagnostics to the client, and clears the diagnostics of every file that
+/// previously had diagnostics but no longer does.
+pub fn publish_diagnostics(
+    context: &mut Context,
+    diagnostics: BTreeMap<PathBuf, Vec<lsp_types::Diagnostic>>,
+) {
+    let cleared: Vec<_> = context
+        .files_with_diagnostics
+        .iter()
+        .filter(|path| !diagnostics.contains_key(*path))
+        .cloned()
+        .collect();
+    for path in cleared {
+        send_diagnostics(context, &path, vec![]);
+    }
+
+    context.files_with_diagnostics = diagnostics.keys().cloned().collect::<BTreeSet<_>>();
+    for (path, file_diagnostics) in diagnostics {
+        send_diagnostics(context, &path, file_diagnostics);
+    }
+}
+
+fn send_diagnostics(context: &Context, path: &Path, diagnostics: Vec<lsp_types::Diagnostic>) {
+    let uri = match Url::from_file_path(path) {
+        Ok(uri) => uri,
+        Err(()) => {
+            eprintln!("could not publish diagnostics for '{}'", path.display());
+            return;
+        }
+    };
+    let params = PublishDiagnosticsParams {
+        uri,
+        diagnostics,
+        version: None,
+    };
+    let notification = Notification::new(
+        lsp_types::notification::PublishDiagnostics::METHOD.to_string(),
+        params,
+    );
+    context
+        .connection
+        .sender
+        .send(lsp_server::Message::Notification(notification))
+        .expect("could not send diagnostics");
+}
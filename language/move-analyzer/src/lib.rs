@@ -3,4 +3,7 @@
 
 pub mod completion;
 pub mod context;
+pub mod diagnostics;
+pub mod symbols;
+pub mod utils;
 pub mod vfs;
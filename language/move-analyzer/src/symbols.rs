@@ -0,0 +1,782 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The language server answers "go to definition," "hover," and "find references" requests using
+//! the compiler's typing AST. Whenever a Move source file is opened or saved, the package that
+//! encloses it is built, and every identifier in the AST is recorded along with the location of
+//! the definition it refers to. Each definition is also recorded as a use of itself, so that
+//! requests made on a definition's name are answered as well.
+//!
+//! Requests are answered using the symbols of the most recent build that type checked. Edits made
+//! since then are not accounted for until the file is saved.
+
+use crate::{
+    context::Context,
+    diagnostics::lsp_diagnostics,
+    utils::{canonical_path, loc_to_location, position_to_offset},
+};
+use anyhow::{bail, Result};
+use lsp_server::Request;
+use lsp_types::{
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams, Location,
+    MarkupContent, MarkupKind, Position, ReferenceParams,
+};
+use move_command_line_common::files::FileHash;
+use move_ir_types::location::{ByteIndex, Loc};
+use move_lang::{
+    diagnostics::FilesSourceText,
+    expansion::ast::{Fields, ModuleIdent, ModuleIdent_},
+    naming::ast::{FunctionSignature, StructFields, Type, TypeName_, Type_},
+    parser::ast::{ConstantName, Field, FunctionName, StructName, Var, Visibility},
+    shared::{Flags, NumberFormat, NumericalAddress},
+    typing::ast::{
+        Constant, Exp, ExpListItem, Function, FunctionBody_, LValue, LValueList, LValue_,
+        ModuleDefinition, Program, Sequence, SequenceItem_, UnannotatedExp_,
+    },
+    CommentMap, Compiler, PASS_TYPING,
+};
+use move_package::BuildConfig;
+use move_symbol_pool::Symbol;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// What is shown when hovering over a definition, or over a use of it.
+#[derive(Debug, Clone)]
+struct DefInfo {
+    /// The definition's signature, e.g., `public fun M::f(x: u64): bool`.
+    signature: String,
+    /// The definition's documentation comment, if it has one.
+    doc: Option<String>,
+}
+
+/// An identifier in a source file, and the definition it refers to.
+#[derive(Debug, Clone, Copy)]
+struct UseDef {
+    /// The offset at which the identifier ends.
+    end: ByteIndex,
+    /// The location of the identifier of the definition.
+    def_loc: Loc,
+}
+
+/// The symbols of a package and its dependencies.
+#[derive(Debug, Default)]
+pub struct Symbols {
+    /// The contents of each source file, as they were when the package was built.
+    files: FilesSourceText,
+    /// The source files, by their canonical path.
+    file_hashes: BTreeMap<PathBuf, FileHash>,
+    /// The identifiers in each source file, keyed by the offset at which they start.
+    uses: BTreeMap<FileHash, BTreeMap<ByteIndex, UseDef>>,
+    /// Every definition, keyed by the location of its identifier.
+    defs: BTreeMap<Loc, DefInfo>,
+}
+
+/// The definitions of a module's members, so that uses of them can be resolved.
+#[derive(Debug, Default)]
+struct ModuleMembers {
+    structs: BTreeMap<Symbol, Loc>,
+    /// Fields, keyed by the name of their struct and their own name.
+    fields: BTreeMap<(Symbol, Symbol), Loc>,
+    functions: BTreeMap<Symbol, Loc>,
+    constants: BTreeMap<Symbol, Loc>,
+}
+
+/// Records the symbols of a typing AST.
+struct Symbolicator<'a> {
+    comments: &'a CommentMap,
+    members: BTreeMap<ModuleIdent_, ModuleMembers>,
+    /// The constants of the script whose body is being recorded.
+    script_constants: BTreeMap<Symbol, Loc>,
+    /// The local variables in scope, innermost scope last.
+    scopes: Vec<BTreeMap<Symbol, Loc>>,
+    symbols: Symbols,
+}
+
+/// Builds the package enclosing `path`. Returns the symbols of the package, if it type checks,
+/// along with the diagnostics of the build, for each file they were reported in.
+pub fn build_package(
+    path: &Path,
+) -> Result<(
+    Option<Symbols>,
+    BTreeMap<PathBuf, Vec<lsp_types::Diagnostic>>,
+)> {
+    // Test mode includes unit tests and test-only code, which developers edit as well.
+    let build_config = BuildConfig {
+        test_mode: true,
+        ..Default::default()
+    };
+    // Building in the editor must not create or update the package's Move.lock.
+    let resolution_graph = build_config.resolution_graph_for_package_without_lock_file(path)?;
+
+    // As when building a Move model, the root package must have the global resolution of all
+    // named addresses in the package graph in scope, so the package and its dependencies are
+    // compiled together.
+    for (package_name, package) in resolution_graph.package_table.iter() {
+        if !package.renaming.is_empty() {
+            bail!(
+                "Found address renaming in package '{}', which is not supported",
+                package_name
+            )
+        }
+    }
+    let root_name = &resolution_graph.root_package.package.name;
+    let root_package = resolution_graph.get_package(root_name);
+    let targets: Vec<_> = root_package
+        .get_sources(&resolution_graph.build_options)?
+        .into_iter()
+        .map(|symbol| symbol.to_string())
+        .collect();
+    let mut deps = vec![];
+    for (package_name, package) in resolution_graph.package_table.iter() {
+        if package_name != root_name {
+            deps.extend(
+                package
+                    .get_sources(&resolution_graph.build_options)?
+                    .into_iter()
+                    .map(|symbol| symbol.to_string()),
+            );
+        }
+    }
+    let named_addresses: BTreeMap<_, _> = root_package
+        .resolution_table
+        .iter()
+        .map(|(name, address)| {
+            let address = NumericalAddress::new(address.into_bytes(), NumberFormat::Hex);
+            (name.to_string(), address)
+        })
+        .collect();
+
+    let (files, result) = Compiler::new(&targets, &deps)
+        .set_flags(Flags::testing())
+        .set_named_address_values(named_addresses)
+        .run::<PASS_TYPING>()?;
+    let (comments, compiler) = match result {
+        Ok(result) => result,
+        Err(diagnostics) => return Ok((None, lsp_diagnostics(diagnostics, &files))),
+    };
+
+    // Record the symbols of the typing AST, and then run the remaining passes to surface their
+    // diagnostics, such as those of borrow checking.
+    let (compiler, program) = compiler.into_ast();
+    let symbols = Symbolicator::symbols(&program, &comments, files.clone());
+    let diagnostics = match compiler.at_typing(program).build() {
+        Ok((_units, warnings)) => warnings,
+        Err(diagnostics) => diagnostics,
+    };
+    Ok((Some(symbols), lsp_diagnostics(diagnostics, &files)))
+}
+
+impl Symbols {
+    /// Returns the location of the definition referred to by the identifier at `position` in the
+    /// file at `path`, or `None` if there is no identifier there.
+    fn def_loc(&self, path: &Path, position: &Position) -> Option<Loc> {
+        let file_hash = self.file_hashes.get(&canonical_path(path))?;
+        let (_, text) = self.files.get(file_hash)?;
+        let offset = position_to_offset(text, position)? as ByteIndex;
+        let (_, use_def) = self.uses.get(file_hash)?.range(..=offset).next_back()?;
+        // A cursor placed immediately after an identifier also refers to it.
+        if offset > use_def.end {
+            return None;
+        }
+        Some(use_def.def_loc)
+    }
+
+    /// Returns the locations of all identifiers that refer to the definition at `def_loc`.
+    fn references(&self, def_loc: Loc, include_declaration: bool) -> Vec<Location> {
+        let mut locations = vec![];
+        for (file_hash, uses) in &self.uses {
+            for (start, use_def) in uses {
+                let use_loc = Loc::new(*file_hash, *start, use_def.end);
+                if use_def.def_loc != def_loc || (!include_declaration && use_loc == def_loc) {
+                    continue;
+                }
+                if let Some(location) = loc_to_location(&self.files, use_loc) {
+                    locations.push(location);
+                }
+            }
+        }
+        locations
+    }
+}
+
+impl<'a> Symbolicator<'a> {
+    fn symbols(program: &Program, comments: &'a CommentMap, files: FilesSourceText) -> Symbols {
+        let file_hashes = files
+            .iter()
+            .map(|(file_hash, (file_name, _))| {
+                (canonical_path(Path::new(file_name.as_str())), *file_hash)
+            })
+            .collect();
+        let mut symbolicator = Self {
+            comments,
+            members: BTreeMap::new(),
+            script_constants: BTreeMap::new(),
+            scopes: vec![],
+            symbols: Symbols {
+                files,
+                file_hashes,
+                ..Default::default()
+            },
+        };
+
+        // Definitions are recorded first, so that their uses in any module can be resolved.
+        for (mident, mdef) in program.modules.key_cloned_iter() {
+            symbolicator.module_defs(&mident, mdef);
+        }
+        for (_, mdef) in program.modules.key_cloned_iter() {
+            symbolicator.module_type_uses(mdef);
+        }
+        for (mident, mdef) in program.modules.key_cloned_iter() {
+            for (_, constant) in mdef.constants.key_cloned_iter() {
+                symbolicator.exp(&constant.value);
+            }
+            for (_, function) in mdef.functions.key_cloned_iter() {
+                symbolicator.function_body(Some(&mident), function);
+            }
+        }
+        for script in program.scripts.values() {
+            symbolicator.script_constants.clear();
+            for (name, constant) in script.constants.key_cloned_iter() {
+                let def_loc = symbolicator.constant_def(&name, constant);
+                symbolicator.script_constants.insert(name.0.value, def_loc);
+            }
+            for (_, constant) in script.constants.key_cloned_iter() {
+                symbolicator.exp(&constant.value);
+            }
+            let signature = function_signature(None, &script.function_name, &script.function);
+            symbolicator.def(script.function_name.0.loc, signature, true);
+            symbolicator.function_body(None, &script.function);
+        }
+        symbolicator.symbols
+    }
+
+    /// Records the definitions of a module and its members.
+    fn module_defs(&mut self, mident: &ModuleIdent, mdef: &ModuleDefinition) {
+        self.def(
+            mident.value.module.0.loc,
+            format!("module {}", mident.value),
+            true,
+        );
+        let mut members = ModuleMembers::default();
+
+        for (name, sdef) in mdef.structs.key_cloned_iter() {
+            let mut signature = format!("struct {}::{}", mident.value.module, name);
+            if !sdef.type_parameters.is_empty() {
+                let type_parameters: Vec<_> = sdef
+                    .type_parameters
+                    .iter()
+                    .map(|tp| {
+                        let phantom = if tp.is_phantom { "phantom " } else { "" };
+                        format!("{}{}", phantom, tp.param.user_specified_name)
+                    })
+                    .collect();
+                signature.push_str(&format!("<{}>", type_parameters.join(", ")));
+            }
+            let abilities: Vec<_> = sdef.abilities.iter().map(|a| a.value.to_string()).collect();
+            if !abilities.is_empty() {
+                signature.push_str(&format!(" has {}", abilities.join(", ")));
+            }
+            self.def(name.0.loc, signature, true);
+            members.structs.insert(name.0.value, name.0.loc);
+
+            if let StructFields::Defined(fields) = &sdef.fields {
+                for (field, (_, ty)) in fields.key_cloned_iter() {
+                    self.def(field.0.loc, format!("{}: {}", field, type_string(ty)), true);
+                    members
+                        .fields
+                        .insert((name.0.value, field.0.value), field.0.loc);
+                }
+            }
+        }
+
+        for (name, constant) in mdef.constants.key_cloned_iter() {
+            let def_loc = self.constant_def(&name, constant);
+            members.constants.insert(name.0.value, def_loc);
+        }
+
+        for (name, function) in mdef.functions.key_cloned_iter() {
+            let signature = function_signature(Some(&mident.value), &name, function);
+            self.def(name.0.loc, signature, true);
+            members.functions.insert(name.0.value, name.0.loc);
+        }
+
+        self.members.insert(mident.value, members);
+    }
+
+    /// Records the uses of structs in the types of a module's fields and function signatures.
+    fn module_type_uses(&mut self, mdef: &ModuleDefinition) {
+        for (_, sdef) in mdef.structs.key_cloned_iter() {
+            if let StructFields::Defined(fields) = &sdef.fields {
+                for (_, (_, ty)) in fields.key_cloned_iter() {
+                    self.type_uses(ty);
+                }
+            }
+        }
+        for (_, function) in mdef.functions.key_cloned_iter() {
+            self.signature_type_uses(&function.signature);
+        }
+    }
+
+    fn constant_def(&mut self, name: &ConstantName, constant: &Constant) -> Loc {
+        let signature = format!("const {}: {}", name, type_string(&constant.signature));
+        self.def(name.0.loc, signature, true);
+        self.type_uses(&constant.signature);
+        name.0.loc
+    }
+
+    /// Records a definition, looking up its documentation comment if `documented`.
+    fn def(&mut self, loc: Loc, signature: String, documented: bool) {
+        let doc = if documented { self.doc(loc) } else { None };
+        self.symbols.defs.insert(loc, DefInfo { signature, doc });
+        self.use_def(loc, loc);
+    }
+
+    fn use_def(&mut self, use_loc: Loc, def_loc: Loc) {
+        self.symbols
+            .uses
+            .entry(use_loc.file_hash())
+            .or_default()
+            .insert(
+                use_loc.start(),
+                UseDef {
+                    end: use_loc.end(),
+                    def_loc,
+                },
+            );
+    }
+
+    /// Returns the documentation comment of the definition whose identifier is at `loc`.
+    ///
+    /// The parser keys documentation comments by the start of the item they document, and records
+    /// an entry, possibly empty, for every item that may be documented. So the comment of a
+    /// definition is the last entry that starts at or before its identifier.
+    fn doc(&self, loc: Loc) -> Option<String> {
+        let (_, doc) = self
+            .comments
+            .get(&loc.file_hash())?
+            .range(..=loc.start())
+            .next_back()?;
+        let doc = doc.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+        let doc = doc.trim();
+        if doc.is_empty() {
+            None
+        } else {
+            Some(doc.to_string())
+        }
+    }
+
+    /// Records the uses of structs in a type written in the source.
+    ///
+    /// Inferred types are not recorded, as their locations need not be where their structs are
+    /// named in the source.
+    fn type_uses(&mut self, ty: &Type) {
+        match &ty.value {
+            Type_::Ref(_, ty) => self.type_uses(ty),
+            Type_::Apply(_, type_name, type_arguments) => {
+                if let TypeName_::ModuleType(mident, name) = &type_name.value {
+                    self.struct_use(ty.loc, mident, name);
+                }
+                for ty in type_arguments {
+                    self.type_uses(ty);
+                }
+            }
+            Type_::Unit | Type_::Param(_) | Type_::Var(_) | Type_::Anything => (),
+            Type_::UnresolvedError => (),
+        }
+    }
+
+    fn signature_type_uses(&mut self, signature: &FunctionSignature) {
+        for (_, ty) in &signature.parameters {
+            self.type_uses(ty);
+        }
+        self.type_uses(&signature.return_type);
+    }
+
+    /// Records the use of a struct named in the type, expression, or pattern at `loc`.
+    fn struct_use(&mut self, loc: Loc, mident: &ModuleIdent, name: &StructName) {
+        let def_loc = self
+            .members
+            .get(&mident.value)
+            .and_then(|members| members.structs.get(&name.0.value))
+            .copied();
+        if let (Some(def_loc), Some(use_loc)) = (def_loc, self.struct_name_loc(loc, name)) {
+            self.use_def(use_loc, def_loc);
+        }
+    }
+
+    /// Returns the location of the name of the struct that the type, expression, or pattern at
+    /// `loc` starts with, e.g., of `S` in `&M::S<T>` or in `S { f }`.
+    ///
+    /// The compiler resolves the names of structs to their definitions, so the locations of the
+    /// names are those of the definitions, and the uses have to be found in the source instead.
+    fn struct_name_loc(&self, loc: Loc, name: &StructName) -> Option<Loc> {
+        let (_, text) = self.symbols.files.get(&loc.file_hash())?;
+        let source = text.get(loc.start() as usize..loc.end() as usize)?;
+        let prefix = source.split(|c| c == '<' || c == '{').next()?.trim_end();
+        let name = name.0.value.as_str();
+        if !prefix.ends_with(name) {
+            return None;
+        }
+        let end = loc.start() + prefix.len() as ByteIndex;
+        Some(Loc::new(
+            loc.file_hash(),
+            end - name.len() as ByteIndex,
+            end,
+        ))
+    }
+
+    fn field_use(&mut self, mident: &ModuleIdent, struct_name: &StructName, field: &Field) {
+        let def_loc = self
+            .members
+            .get(&mident.value)
+            .and_then(|members| members.fields.get(&(struct_name.0.value, field.0.value)))
+            .copied();
+        if let Some(def_loc) = def_loc {
+            self.use_def(field.0.loc, def_loc);
+        }
+    }
+
+    fn function_body(&mut self, mident: Option<&ModuleIdent>, function: &Function) {
+        let body = match &function.body.value {
+            FunctionBody_::Defined(body) => body,
+            FunctionBody_::Native => return,
+        };
+        self.scopes.push(BTreeMap::new());
+        for (var, ty) in &function.signature.parameters {
+            self.var_def(var, ty);
+        }
+        // Only structs of the function's own module can be acquired.
+        if let Some(mident) = mident {
+            for (name, loc) in &function.acquires {
+                self.struct_use(*loc, mident, name);
+            }
+        }
+        self.sequence(body);
+        self.scopes.pop();
+    }
+
+    fn var_def(&mut self, var: &Var, ty: &Type) {
+        self.def(var.0.loc, format!("{}: {}", var, type_string(ty)), false);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(var.0.value, var.0.loc);
+        }
+    }
+
+    fn var_use(&mut self, var: &Var) {
+        let def_loc = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&var.0.value))
+            .copied();
+        if let Some(def_loc) = def_loc {
+            self.use_def(var.0.loc, def_loc);
+        }
+    }
+
+    fn sequence(&mut self, sequence: &Sequence) {
+        self.scopes.push(BTreeMap::new());
+        for item in sequence {
+            match &item.value {
+                SequenceItem_::Seq(e) => self.exp(e),
+                SequenceItem_::Declare(lvalues) => self.lvalues(lvalues, true),
+                SequenceItem_::Bind(lvalues, _, e) => {
+                    self.exp(e);
+                    self.lvalues(lvalues, true);
+                }
+            }
+        }
+        self.scopes.pop();
+    }
+
+    /// Records the variables bound by `let`, if `declared`, or the variables assigned to.
+    fn lvalues(&mut self, lvalues: &LValueList, declared: bool) {
+        for lvalue in &lvalues.value {
+            self.lvalue(lvalue, declared);
+        }
+    }
+
+    fn lvalue(&mut self, lvalue: &LValue, declared: bool) {
+        match &lvalue.value {
+            LValue_::Ignore => (),
+            LValue_::Var(var, ty) => {
+                if declared {
+                    self.var_def(var, ty);
+                } else {
+                    self.var_use(var);
+                }
+            }
+            LValue_::Unpack(mident, name, _, fields)
+            | LValue_::BorrowUnpack(_, mident, name, _, fields) => {
+                self.struct_use(lvalue.loc, mident, name);
+                self.fields(mident, name, fields, |symbolicator, (_, lvalue)| {
+                    symbolicator.lvalue(lvalue, declared)
+                });
+            }
+        }
+    }
+
+    fn fields<T>(
+        &mut self,
+        mident: &ModuleIdent,
+        name: &StructName,
+        fields: &Fields<T>,
+        mut f: impl FnMut(&mut Self, &T),
+    ) {
+        for (field, (_, t)) in fields.key_cloned_iter() {
+            self.field_use(mident, name, &field);
+            f(self, t);
+        }
+    }
+
+    fn exp(&mut self, exp: &Exp) {
+        use UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::Unit { .. } | E::Value(_) | E::Break | E::Continue | E::UnresolvedError => (),
+            E::Spec(..) => (),
+            E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) | E::BorrowLocal(_, var) => {
+                self.var_use(var)
+            }
+            E::Constant(mident, name) => {
+                let def_loc = match mident {
+                    Some(mident) => self
+                        .members
+                        .get(&mident.value)
+                        .and_then(|members| members.constants.get(&name.0.value)),
+                    None => self.script_constants.get(&name.0.value),
+                }
+                .copied();
+                if let Some(def_loc) = def_loc {
+                    self.use_def(name.0.loc, def_loc);
+                }
+            }
+            E::ModuleCall(call) => {
+                let def_loc = self
+                    .members
+                    .get(&call.module.value)
+                    .and_then(|members| members.functions.get(&call.name.0.value))
+                    .copied();
+                if let Some(def_loc) = def_loc {
+                    self.use_def(call.name.0.loc, def_loc);
+                }
+                self.exp(&call.arguments);
+            }
+            E::Builtin(_, e)
+            | E::Vector(_, _, _, e)
+            | E::Loop { body: e, .. }
+            | E::Return(e)
+            | E::Abort(e)
+            | E::Dereference(e)
+            | E::UnaryExp(_, e)
+            | E::TempBorrow(_, e) => self.exp(e),
+            E::IfElse(condition, if_true, if_false) => {
+                self.exp(condition);
+                self.exp(if_true);
+                self.exp(if_false);
+            }
+            E::While(condition, body) => {
+                self.exp(condition);
+                self.exp(body);
+            }
+            E::Block(sequence) => self.sequence(sequence),
+            E::Assign(lvalues, _, e) => {
+                self.exp(e);
+                self.lvalues(lvalues, false);
+            }
+            E::Mutate(lhs, rhs) | E::BinopExp(lhs, _, _, rhs) => {
+                self.exp(lhs);
+                self.exp(rhs);
+            }
+            E::Pack(mident, name, _, fields) => {
+                self.struct_use(exp.exp.loc, mident, name);
+                self.fields(mident, name, fields, |symbolicator, (_, e)| {
+                    symbolicator.exp(e)
+                });
+            }
+            E::ExpList(items) => {
+                for item in items {
+                    match item {
+                        ExpListItem::Single(e, _) | ExpListItem::Splat(_, e, _) => self.exp(e),
+                    }
+                }
+            }
+            E::Borrow(_, e, field) => {
+                self.exp(e);
+                let mut ty = &e.ty;
+                while let Type_::Ref(_, inner) = &ty.value {
+                    ty = inner.as_ref();
+                }
+                if let Type_::Apply(_, type_name, _) = &ty.value {
+                    if let TypeName_::ModuleType(mident, name) = &type_name.value {
+                        self.field_use(mident, name, field);
+                    }
+                }
+            }
+            E::Cast(e, ty) | E::Annotate(e, ty) => {
+                self.exp(e);
+                self.type_uses(ty);
+            }
+        }
+    }
+}
+
+/// Returns the signature of a function, e.g., `public fun M::f<T>(x: u64): bool`. The module is
+/// omitted for script functions.
+fn function_signature(
+    mident: Option<&ModuleIdent_>,
+    name: &FunctionName,
+    function: &Function,
+) -> String {
+    let mut signature = String::new();
+    if function.visibility != Visibility::Internal {
+        signature.push_str(&format!("{} ", function.visibility));
+    }
+    if let FunctionBody_::Native = function.body.value {
+        signature.push_str("native ");
+    }
+    signature.push_str("fun ");
+    if let Some(mident) = mident {
+        signature.push_str(&format!("{}::", mident.module));
+    }
+    signature.push_str(&name.to_string());
+
+    let FunctionSignature {
+        type_parameters,
+        parameters,
+        return_type,
+    } = &function.signature;
+    if !type_parameters.is_empty() {
+        let type_parameters: Vec<_> = type_parameters
+            .iter()
+            .map(|tp| tp.user_specified_name.to_string())
+            .collect();
+        signature.push_str(&format!("<{}>", type_parameters.join(", ")));
+    }
+    let parameters: Vec<_> = parameters
+        .iter()
+        .map(|(var, ty)| format!("{}: {}", var, type_string(ty)))
+        .collect();
+    signature.push_str(&format!("({})", parameters.join(", ")));
+    if return_type.value != Type_::Unit {
+        signature.push_str(&format!(": {}", type_string(return_type)));
+    }
+    signature
+}
+
+/// Returns a type as it would be written in the source, with structs named by their module.
+fn type_string(ty: &Type) -> String {
+    match &ty.value {
+        Type_::Unit => "()".to_string(),
+        Type_::Ref(mutable, ty) => {
+            let mutable = if *mutable { "mut " } else { "" };
+            format!("&{}{}", mutable, type_string(ty))
+        }
+        Type_::Param(tp) => tp.user_specified_name.to_string(),
+        Type_::Apply(_, type_name, type_arguments) => {
+            let type_arguments: Vec<_> = type_arguments.iter().map(type_string).collect();
+            let type_arguments_string = if type_arguments.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", type_arguments.join(", "))
+            };
+            match &type_name.value {
+                // The elements of a tuple are its type arguments.
+                TypeName_::Multiple(_) => format!("({})", type_arguments.join(", ")),
+                TypeName_::Builtin(builtin) => {
+                    format!("{}{}", builtin.value, type_arguments_string)
+                }
+                TypeName_::ModuleType(mident, name) => {
+                    format!("{}::{}{}", mident.value.module, name, type_arguments_string)
+                }
+            }
+        }
+        Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => "_".to_string(),
+    }
+}
+
+/// Sends the given connection a response to a "go to definition" request.
+pub fn on_go_to_def_request(context: &Context, request: &Request) {
+    let parameters = serde_json::from_value::<GotoDefinitionParams>(request.params.clone())
+        .expect("could not deserialize request");
+    let position = parameters.text_document_position_params;
+
+    let location = request_path(&position.text_document.uri).and_then(|path| {
+        let symbols = &context.symbols;
+        let def_loc = symbols.def_loc(&path, &position.position)?;
+        loc_to_location(&symbols.files, def_loc)
+    });
+    let result = match location {
+        Some(location) => serde_json::to_value(GotoDefinitionResponse::Scalar(location)),
+        None => Ok(serde_json::Value::Null),
+    }
+    .expect("could not serialize response");
+    send_response(context, request, result);
+}
+
+/// Sends the given connection a response to a hover request, with the signature and
+/// documentation comment of the definition under the cursor.
+pub fn on_hover_request(context: &Context, request: &Request) {
+    let parameters = serde_json::from_value::<HoverParams>(request.params.clone())
+        .expect("could not deserialize request");
+    let position = parameters.text_document_position_params;
+
+    let hover = request_path(&position.text_document.uri).and_then(|path| {
+        let symbols = &context.symbols;
+        let def_loc = symbols.def_loc(&path, &position.position)?;
+        let DefInfo { signature, doc } = symbols.defs.get(&def_loc)?;
+        let mut value = format!("```move\n{}\n```", signature);
+        if let Some(doc) = doc {
+            value.push_str(&format!("\n\n{}", doc));
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    });
+    let result = match hover {
+        Some(hover) => serde_json::to_value(hover),
+        None => Ok(serde_json::Value::Null),
+    }
+    .expect("could not serialize response");
+    send_response(context, request, result);
+}
+
+/// Sends the given connection a response to a "find references" request.
+pub fn on_references_request(context: &Context, request: &Request) {
+    let parameters = serde_json::from_value::<ReferenceParams>(request.params.clone())
+        .expect("could not deserialize request");
+    let position = &parameters.text_document_position;
+
+    let references = request_path(&position.text_document.uri)
+        .and_then(|path| context.symbols.def_loc(&path, &position.position))
+        .map(|def_loc| {
+            context
+                .symbols
+                .references(def_loc, parameters.context.include_declaration)
+        })
+        .unwrap_or_default();
+    let result = serde_json::to_value(references).expect("could not serialize response");
+    send_response(context, request, result);
+}
+
+fn request_path(uri: &lsp_types::Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok();
+    if path.is_none() {
+        eprintln!("Could not handle request for non-file URI '{}'", uri);
+    }
+    path
+}
+
+fn send_response(context: &Context, request: &Request, result: serde_json::Value) {
+    let response = lsp_server::Response::new_ok(request.id.clone(), result);
+    context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .expect("could not send response");
+}
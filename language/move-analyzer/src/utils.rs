@@ -0,0 +1,69 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The compiler refers to source locations as byte offsets into a file, whereas the Language
+//! Server Protocol refers to them as a line number and a character offset into that line, counted
+//! in UTF-16 code units. This module converts between the two.
+
+use lsp_types::{Location, Position, Range, Url};
+use move_ir_types::location::Loc;
+use move_lang::diagnostics::FilesSourceText;
+use std::path::{Path, PathBuf};
+
+/// Converts a byte offset into `text` to a position within it.
+pub fn offset_to_position(text: &str, offset: usize) -> Position {
+    let offset = offset.min(text.len());
+    let preceding = &text[..offset];
+    let line_start = preceding.rfind('\n').map_or(0, |index| index + 1);
+    Position {
+        line: preceding.matches('\n').count() as u32,
+        character: preceding[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+/// Converts a position within `text` to a byte offset into it, or returns `None` if `text` does
+/// not contain the position.
+pub fn position_to_offset(text: &str, position: &Position) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        line_start += text[line_start..].find('\n')? + 1;
+    }
+    let line = text[line_start..].split('\n').next().unwrap_or("");
+
+    let mut character = 0;
+    for (index, c) in line.char_indices() {
+        if character >= position.character {
+            return Some(line_start + index);
+        }
+        character += c.len_utf16() as u32;
+    }
+    // Positions past the end of a line refer to the end of that line.
+    Some(line_start + line.len())
+}
+
+/// Converts a compiler location to the range it covers within its file, or returns `None` if the
+/// file is unknown.
+pub fn loc_to_range(files: &FilesSourceText, loc: Loc) -> Option<Range> {
+    let (_, text) = files.get(&loc.file_hash())?;
+    Some(Range {
+        start: offset_to_position(text, loc.start() as usize),
+        end: offset_to_position(text, loc.end() as usize),
+    })
+}
+
+/// Converts a compiler location to a location the client can navigate to, or returns `None` if
+/// the file is unknown.
+pub fn loc_to_location(files: &FilesSourceText, loc: Loc) -> Option<Location> {
+    let (file_name, _) = files.get(&loc.file_hash())?;
+    let uri = Url::from_file_path(canonical_path(Path::new(file_name.as_str()))).ok()?;
+    Some(Location {
+        uri,
+        range: loc_to_range(files, loc)?,
+    })
+}
+
+/// Returns the canonical form of `path`, so that the paths the compiler was given and the paths
+/// the client sends may be compared. If `path` cannot be canonicalized, it is returned as is.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
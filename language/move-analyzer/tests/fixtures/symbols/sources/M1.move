@@ -0,0 +1,15 @@
+module Symbols::M1 {
+    /// A counter.
+    struct Counter has drop {
+        value: u64,
+    }
+
+    /// Returns a new counter.
+    public fun new(value: u64): Counter {
+        Counter { value }
+    }
+
+    public fun value(counter: &Counter): u64 {
+        counter.value
+    }
+}
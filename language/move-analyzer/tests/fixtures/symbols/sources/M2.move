@@ -0,0 +1,8 @@
+module Symbols::M2 {
+    use Symbols::M1;
+
+    fun use_counter(): u64 {
+        let counter = M1::new(42);
+        M1::value(&counter)
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use lsp_server::{Connection, Message, Request, RequestId};
+use lsp_types::{
+    request::{GotoDefinition, HoverRequest, References, Request as _},
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams, Location,
+    Position, ReferenceContext, ReferenceParams, TextDocumentIdentifier,
+    TextDocumentPositionParams, Url,
+};
+use move_analyzer::{context::Context, symbols, vfs::VirtualFileSystem};
+use serde_json::Value;
+use std::{collections::BTreeSet, path::Path};
+
+const FIXTURE: &str = "tests/fixtures/symbols";
+
+/// The language server, with the symbols of the fixture package, and the client end of its
+/// connection.
+fn server() -> (Context, Connection) {
+    let (symbols, diagnostics) = symbols::build_package(Path::new(FIXTURE)).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    // Building the package in the editor must not modify it.
+    assert!(!Path::new(FIXTURE).join("Move.lock").exists());
+
+    let (connection, client) = Connection::memory();
+    let context = Context {
+        connection,
+        files: VirtualFileSystem::default(),
+        symbols: symbols.expect("fixture should type check"),
+        files_with_diagnostics: BTreeSet::new(),
+    };
+    (context, client)
+}
+
+fn position_params(file: &str, line: u32, character: u32) -> TextDocumentPositionParams {
+    let path = Path::new(FIXTURE)
+        .join("sources")
+        .join(file)
+        .canonicalize()
+        .unwrap();
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier {
+            uri: Url::from_file_path(path).unwrap(),
+        },
+        position: Position { line, character },
+    }
+}
+
+fn request(
+    context: &Context,
+    client: &Connection,
+    method: &str,
+    params: Value,
+    on_request: fn(&Context, &Request),
+) -> Value {
+    let request = Request::new(RequestId::from(0), method.to_string(), params);
+    on_request(context, &request);
+    match client.receiver.recv().unwrap() {
+        Message::Response(response) => response.result.unwrap(),
+        message => panic!("unexpected message: {:?}", message),
+    }
+}
+
+fn go_to_def(
+    context: &Context,
+    client: &Connection,
+    file: &str,
+    line: u32,
+    character: u32,
+) -> Value {
+    let params = GotoDefinitionParams {
+        text_document_position_params: position_params(file, line, character),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    request(
+        context,
+        client,
+        GotoDefinition::METHOD,
+        serde_json::to_value(params).unwrap(),
+        symbols::on_go_to_def_request,
+    )
+}
+
+fn hover(context: &Context, client: &Connection, file: &str, line: u32, character: u32) -> Value {
+    let params = HoverParams {
+        text_document_position_params: position_params(file, line, character),
+        work_done_progress_params: Default::default(),
+    };
+    request(
+        context,
+        client,
+        HoverRequest::METHOD,
+        serde_json::to_value(params).unwrap(),
+        symbols::on_hover_request,
+    )
+}
+
+/// Returns the file name and the start position of a location.
+fn file_and_start(location: &Location) -> (String, u32, u32) {
+    let path = location.uri.to_file_path().unwrap();
+    let file = path.file_name().unwrap().to_string_lossy().to_string();
+    (
+        file,
+        location.range.start.line,
+        location.range.start.character,
+    )
+}
+
+#[test]
+fn go_to_definition() {
+    let (context, client) = server();
+
+    // `M1::new` in M2 refers to the function defined in M1.
+    let response: GotoDefinitionResponse =
+        serde_json::from_value(go_to_def(&context, &client, "M2.move", 4, 27)).unwrap();
+    let location = match response {
+        GotoDefinitionResponse::Scalar(location) => location,
+        response => panic!("unexpected response: {:?}", response),
+    };
+    assert_eq!(file_and_start(&location), ("M1.move".to_string(), 7, 15));
+
+    // A local refers to its declaration, also when the cursor is right after it.
+    let response: GotoDefinitionResponse =
+        serde_json::from_value(go_to_def(&context, &client, "M2.move", 5, 26)).unwrap();
+    let location = match response {
+        GotoDefinitionResponse::Scalar(location) => location,
+        response => panic!("unexpected response: {:?}", response),
+    };
+    assert_eq!(file_and_start(&location), ("M2.move".to_string(), 4, 12));
+
+    // There is nothing to go to from whitespace.
+    assert_eq!(go_to_def(&context, &client, "M2.move", 2, 0), Value::Null);
+}
+
+#[test]
+fn hover_shows_signature_and_doc() {
+    let (context, client) = server();
+
+    let hover_value = |line, character| {
+        let hover: Hover =
+            serde_json::from_value(hover(&context, &client, "M2.move", line, character)).unwrap();
+        match hover.contents {
+            HoverContents::Markup(markup) => markup.value,
+            contents => panic!("unexpected contents: {:?}", contents),
+        }
+    };
+    assert_eq!(
+        hover_value(4, 27),
+        "```move\npublic fun M1::new(value: u64): M1::Counter\n```\n\nReturns a new counter."
+    );
+    // Definitions without a documentation comment only show their signature.
+    assert_eq!(
+        hover_value(5, 13),
+        "```move\npublic fun M1::value(counter: &M1::Counter): u64\n```"
+    );
+}
+
+/// Returns the file name and start position of each reference to the definition at a position.
+fn references(
+    context: &Context,
+    client: &Connection,
+    file: &str,
+    line: u32,
+    character: u32,
+) -> BTreeSet<(String, u32, u32)> {
+    let params = ReferenceParams {
+        text_document_position: position_params(file, line, character),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        context: ReferenceContext {
+            include_declaration: true,
+        },
+    };
+    let response = request(
+        context,
+        client,
+        References::METHOD,
+        serde_json::to_value(params).unwrap(),
+        symbols::on_references_request,
+    );
+    let locations: Vec<Location> = serde_json::from_value(response).unwrap();
+    locations.iter().map(file_and_start).collect()
+}
+
+#[test]
+fn find_references() {
+    let (context, client) = server();
+    let in_m1 = |positions: Vec<(u32, u32)>| -> BTreeSet<_> {
+        positions
+            .into_iter()
+            .map(|(line, character)| ("M1.move".to_string(), line, character))
+            .collect()
+    };
+
+    // The struct `Counter`, as a type and when packed
+    assert_eq!(
+        references(&context, &client, "M1.move", 2, 11),
+        in_m1(vec![(2, 11), (7, 32), (8, 8), (11, 31)])
+    );
+    // The field `value`, when borrowed
+    assert!(
+        references(&context, &client, "M1.move", 3, 8).is_superset(&in_m1(vec![(3, 8), (12, 16)]))
+    );
+    // The function `new`, across modules
+    assert_eq!(
+        references(&context, &client, "M1.move", 7, 15),
+        vec![
+            ("M1.move".to_string(), 7, 15),
+            ("M2.move".to_string(), 4, 26)
+        ]
+        .into_iter()
+        .collect()
+    );
+}
@@ -0,0 +1,44 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use lsp_types::Position;
+use move_analyzer::utils::{offset_to_position, position_to_offset};
+
+fn position(line: u32, character: u32) -> Position {
+    Position { line, character }
+}
+
+#[test]
+fn offsets_round_trip() {
+    let text = "module M {\n    fun f() {}\n}\n";
+    for offset in 0..=text.len() {
+        let position = offset_to_position(text, offset);
+        assert_eq!(position_to_offset(text, &position), Some(offset));
+    }
+    assert_eq!(offset_to_position(text, 0), position(0, 0));
+    assert_eq!(offset_to_position(text, 15), position(1, 4));
+    assert_eq!(offset_to_position(text, text.len()), position(3, 0));
+}
+
+#[test]
+fn characters_are_counted_in_utf16_code_units() {
+    // 'é' is 2 bytes and 1 UTF-16 code unit, '𝔐' is 4 bytes and 2 UTF-16 code units.
+    let text = "// é𝔐\nx";
+    assert_eq!(offset_to_position(text, 5), position(0, 4));
+    assert_eq!(offset_to_position(text, 9), position(0, 6));
+    assert_eq!(position_to_offset(text, &position(0, 4)), Some(5));
+    assert_eq!(position_to_offset(text, &position(0, 6)), Some(9));
+    assert_eq!(position_to_offset(text, &position(1, 0)), Some(10));
+}
+
+#[test]
+fn out_of_range_positions() {
+    let text = "ab\ncd";
+    // Offsets past the end of the text refer to its end.
+    assert_eq!(offset_to_position(text, 100), position(1, 2));
+    // Characters past the end of a line refer to the end of that line.
+    assert_eq!(position_to_offset(text, &position(0, 100)), Some(2));
+    assert_eq!(position_to_offset(text, &position(1, 100)), Some(5));
+    // Lines past the end of the text are not in it.
+    assert_eq!(position_to_offset(text, &position(2, 0)), None);
+}
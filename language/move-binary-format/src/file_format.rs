@@ -1545,7 +1545,7 @@ pub enum Bytecode {
     VecSwap(SignatureIndex),
 }
 
-pub const NUMBER_OF_NATIVE_FUNCTIONS: usize = 18;
+pub const NUMBER_OF_NATIVE_FUNCTIONS: usize = 23;
 
 impl ::std::fmt::Debug for Bytecode {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -4,7 +4,7 @@
 use crate::{
     account_address::AccountAddress,
     identifier::Identifier,
-    language_storage::{ModuleId, StructTag, TypeTag},
+    language_storage::{ModuleId, StructTag, TableHandle, TypeTag},
 };
 use anyhow::{format_err, Error, Result};
 use std::collections::btree_map::{self, BTreeMap};
@@ -149,17 +149,22 @@ impl AccountChangeSet {
     }
 }
 
+/// A collection of changes to the items of a table, keyed by the serialized keys of the items.
+pub type TableChangeSet = BTreeMap<Vec<u8>, Option<Vec<u8>>>;
+
 /// A collection of changes to a Move state. Each AccountChangeSet in the domain of `accounts`
 /// is guaranteed to be nonempty
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct ChangeSet {
     accounts: BTreeMap<AccountAddress, AccountChangeSet>,
+    tables: BTreeMap<TableHandle, TableChangeSet>,
 }
 
 impl ChangeSet {
     pub fn new() -> Self {
         Self {
             accounts: BTreeMap::new(),
+            tables: BTreeMap::new(),
         }
     }
 
@@ -167,8 +172,17 @@ impl ChangeSet {
         &self.accounts
     }
 
-    pub fn into_inner(self) -> BTreeMap<AccountAddress, AccountChangeSet> {
-        self.accounts
+    pub fn tables(&self) -> &BTreeMap<TableHandle, TableChangeSet> {
+        &self.tables
+    }
+
+    pub fn into_inner(
+        self,
+    ) -> (
+        BTreeMap<AccountAddress, AccountChangeSet>,
+        BTreeMap<TableHandle, TableChangeSet>,
+    ) {
+        (self.accounts, self.tables)
     }
 
     fn get_or_insert_account_changeset(&mut self, addr: AccountAddress) -> &mut AccountChangeSet {
@@ -235,6 +249,22 @@ impl ChangeSet {
             .unpublish_resource(struct_tag)
     }
 
+    pub fn publish_or_overwrite_table_item(
+        &mut self,
+        handle: TableHandle,
+        key: Vec<u8>,
+        blob: Vec<u8>,
+    ) {
+        self.tables
+            .entry(handle)
+            .or_default()
+            .insert(key, Some(blob));
+    }
+
+    pub fn remove_table_item(&mut self, handle: TableHandle, key: Vec<u8>) {
+        self.tables.entry(handle).or_default().insert(key, None);
+    }
+
     pub fn squash(&mut self, other: Self) -> Result<()> {
         for (addr, other_account_changeset) in other.accounts {
             match self.accounts.entry(addr) {
@@ -246,6 +276,14 @@ impl ChangeSet {
                 }
             }
         }
+        // Unlike modules and resources, table items can be added and removed any number of times,
+        // so the later change simply wins.
+        for (handle, other_table_changeset) in other.tables {
+            self.tables
+                .entry(handle)
+                .or_default()
+                .extend(other_table_changeset);
+        }
         Ok(())
     }
 
@@ -275,6 +313,15 @@ impl ChangeSet {
             })
         })
     }
+
+    pub fn table_items(&self) -> impl Iterator<Item = (TableHandle, &[u8], Option<&[u8]>)> {
+        self.tables.iter().flat_map(|(handle, table)| {
+            let handle = *handle;
+            table.iter().map(move |(key, blob_opt)| {
+                (handle, key.as_ref(), blob_opt.as_ref().map(|v| v.as_ref()))
+            })
+        })
+    }
 }
 
 pub type Event = (Vec<u8>, u64, TypeTag, Vec<u8>);
@@ -250,6 +250,13 @@ impl CostTable {
         precondition!(native_index < (self.native_table.len() as u8));
         &self.native_table[native_index as usize]
     }
+
+    /// Like `native_cost`, but returns `None` for natives that the table has no cost for, e.g.
+    /// natives added after the table was published on chain.
+    #[inline]
+    pub fn checked_native_cost(&self, native_index: u8) -> Option<&GasCost> {
+        self.native_table.get(native_index as usize)
+    }
 }
 
 /// The  `GasCost` tracks:
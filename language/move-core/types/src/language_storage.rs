@@ -12,6 +12,7 @@ use std::fmt::{Display, Formatter};
 
 pub const CODE_TAG: u8 = 0;
 pub const RESOURCE_TAG: u8 = 1;
+pub const TABLE_ITEM_TAG: u8 = 2;
 
 pub const CORE_CODE_ADDRESS: AccountAddress = AccountAddress::new([
     0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 1u8,
@@ -110,6 +111,13 @@ impl ModuleId {
     }
 }
 
+/// Identifies a table created with the `Table` module of the Move standard library. The items of
+/// the table are stored under this handle and their serialized keys, separately from the account
+/// holding the table.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Hash, Eq, Clone, Copy, PartialOrd, Ord)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct TableHandle(pub AccountAddress);
+
 impl Display for ModuleId {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(f, "{}::{}", self.address, self.name)
@@ -158,6 +166,12 @@ impl Display for ResourceKey {
     }
 }
 
+impl Display for TableHandle {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "0x{}", self.0.short_str_lossless())
+    }
+}
+
 impl From<StructTag> for TypeTag {
     fn from(t: StructTag) -> TypeTag {
         TypeTag::Struct(t)
@@ -3,7 +3,7 @@
 
 use crate::{
     account_address::AccountAddress,
-    language_storage::{ModuleId, StructTag},
+    language_storage::{ModuleId, StructTag, TableHandle},
 };
 use std::fmt::Debug;
 
@@ -43,15 +43,35 @@ pub trait ResourceResolver {
     ) -> Result<Option<Vec<u8>>, Self::Error>;
 }
 
-/// A persistent storage implementation that can resolve both resources and modules
+/// A persistent storage backend that can resolve table items by table handle + serialized key
+/// Storage backends should return
+///   - Ok(Some(..)) if the item exists
+///   - Ok(None)     if the item does not exist
+///   - Err(..)      only when something really wrong happens, for example
+///                    - storage encounters internal error
+pub trait TableResolver {
+    type Error: Debug;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error>;
+}
+
+/// A persistent storage implementation that can resolve resources, modules and table items
 pub trait MoveResolver:
-    ModuleResolver<Error = Self::Err> + ResourceResolver<Error = Self::Err>
+    ModuleResolver<Error = Self::Err>
+    + ResourceResolver<Error = Self::Err>
+    + TableResolver<Error = Self::Err>
 {
     type Err: Debug;
 }
 
-impl<E: Debug, T: ModuleResolver<Error = E> + ResourceResolver<Error = E> + ?Sized> MoveResolver
-    for T
+impl<E, T> MoveResolver for T
+where
+    E: Debug,
+    T: ModuleResolver<Error = E> + ResourceResolver<Error = E> + TableResolver<Error = E> + ?Sized,
 {
     type Err = E;
 }
@@ -74,3 +94,15 @@ impl<T: ModuleResolver + ?Sized> ModuleResolver for &T {
         (**self).get_module(module_id)
     }
 }
+
+impl<T: TableResolver + ?Sized> TableResolver for &T {
+    type Error = T::Error;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        (**self).get_table_item(handle, key)
+    }
+}
@@ -230,6 +230,15 @@ codes!(
         BytecodeGeneration: { msg: "BYTECODE GENERATION FAILED", severity: Bug },
         BytecodeVerification: { msg: "BYTECODE VERIFICATION FAILED", severity: Bug },
     ],
+    // warnings from the optional lint pass. see linter/
+    Linter: [
+        UnknownLint: { msg: "unknown lint", severity: Warning },
+        UnusedAcquires: { msg: "unused 'acquires' annotation", severity: Warning },
+        NeedlessCopy: { msg: "needless copy", severity: Warning },
+        LiteralAbortCode: { msg: "literal abort code", severity: Warning },
+        UnboundedVectorLoop: { msg: "unbounded loop over a vector", severity: Warning },
+        UnusedSigner: { msg: "unused signer", severity: Warning },
+    ],
 );
 
 //**************************************************************************************************
@@ -262,6 +271,10 @@ impl DiagnosticInfo {
     pub fn severity(&self) -> Severity {
         self.severity
     }
+
+    pub fn set_severity(&mut self, severity: Severity) {
+        self.severity = severity
+    }
 }
 
 impl Severity {
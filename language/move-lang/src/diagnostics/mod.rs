@@ -267,6 +267,11 @@ impl Diagnostic {
         self
     }
 
+    pub fn set_severity(mut self, severity: Severity) -> Self {
+        self.info.set_severity(severity);
+        self
+    }
+
     #[allow(unused)]
     pub fn add_secondary_labels(
         &mut self,
@@ -14,6 +14,7 @@ pub mod expansion;
 pub mod hlir;
 pub mod interface_generator;
 pub mod ir_translation;
+pub mod linter;
 pub mod naming;
 pub mod parser;
 pub mod shared;
@@ -0,0 +1,54 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports `assert!` and `abort` with a numeric literal as the abort code. Abort codes should be
+//! built from a named error constant with one of the `Errors::` functions, so that the reason and
+//! the category of the error can be decoded by clients.
+
+use super::{visit_function, Context, Lint};
+use crate::{diag, parser::ast::FunctionName, typing::ast as T};
+
+pub struct LiteralAbortCode;
+
+impl Lint for LiteralAbortCode {
+    fn name(&self) -> &'static str {
+        "literal_abort_code"
+    }
+
+    fn description(&self) -> &'static str {
+        "'assert!' or 'abort' with a numeric literal instead of an 'Errors::' abort code"
+    }
+
+    fn check_function(&self, context: &mut Context, _name: FunctionName, fdef: &T::Function) {
+        use T::UnannotatedExp_ as E;
+        visit_function(fdef, &mut |e| {
+            let code = match &e.exp.value {
+                E::Builtin(b, args) if matches!(b.value, T::BuiltinFunction_::Assert(_)) => {
+                    match &args.exp.value {
+                        E::ExpList(items) if items.len() == 2 => match &items[1] {
+                            T::ExpListItem::Single(code, _) => code,
+                            T::ExpListItem::Splat(_, _, _) => return,
+                        },
+                        _ => return,
+                    }
+                }
+                E::Abort(code) => code,
+                _ => return,
+            };
+            if is_literal(code) {
+                let msg = "Abort code is a numeric literal. Declare an error constant and build \
+                           the code with one of the 'Errors::' functions";
+                context.add_diag(diag!(Linter::LiteralAbortCode, (code.exp.loc, msg)))
+            }
+        })
+    }
+}
+
+fn is_literal(e: &T::Exp) -> bool {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Value(_) => true,
+        E::Annotate(e, _) => is_literal(e),
+        _ => false,
+    }
+}
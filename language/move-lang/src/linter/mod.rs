@@ -0,0 +1,326 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An optional pass over the typed AST that reports likely mistakes and style issues that the
+//! compiler itself accepts. Each lint can be allowed, warned on, or denied, either for a whole
+//! package (the `[lints]` section of `Move.toml`), or for a single module, script or function with
+//! the `#[allow(..)]`, `#[warn(..)]` and `#[deny(..)]` attributes.
+
+use crate::{
+    diag,
+    diagnostics::{codes::Severity, Diagnostic},
+    expansion::ast::{self as E, AttributeName_, Attribute_, ModuleIdent},
+    parser::ast::FunctionName,
+    shared::{
+        known_attributes::{KnownAttribute, LintAttribute, TestingAttribute},
+        CompilationEnv,
+    },
+    typing::ast as T,
+};
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+mod literal_abort_code;
+mod needless_copy;
+mod unbounded_vector_loop;
+mod unused_acquires;
+mod unused_signer;
+
+//**************************************************************************************************
+// Lints
+//**************************************************************************************************
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// The configured level of each lint, by lint name
+pub type LintLevels = BTreeMap<Symbol, LintLevel>;
+
+pub trait Lint: Sync {
+    /// The name used to refer to the lint in attributes and in `Move.toml`
+    fn name(&self) -> &'static str;
+
+    /// A one line description of what the lint reports
+    fn description(&self) -> &'static str;
+
+    /// The level used when neither the package nor the item configure one
+    fn default_level(&self) -> LintLevel {
+        LintLevel::Warn
+    }
+
+    /// Report any diagnostics for the function `name`, via `Context::add_diag`
+    fn check_function(&self, context: &mut Context, name: FunctionName, fdef: &T::Function);
+}
+
+/// Every lint run by `program`
+pub static LINTS: &[&dyn Lint] = &[
+    &unused_acquires::UnusedAcquires,
+    &needless_copy::NeedlessCopy,
+    &literal_abort_code::LiteralAbortCode,
+    &unbounded_vector_loop::UnboundedVectorLoop,
+    &unused_signer::UnusedSigner,
+];
+
+pub fn find_lint(name: &str) -> Option<&'static dyn Lint> {
+    LINTS.iter().find(|lint| lint.name() == name).copied()
+}
+
+pub struct Context<'env> {
+    env: &'env mut CompilationEnv,
+    level: LintLevel,
+    /// The program being linted, including the dependencies of its source modules
+    pub program: &'env T::Program,
+    /// `None` when checking the function of a script
+    pub current_module: Option<ModuleIdent>,
+}
+
+impl<'env> Context<'env> {
+    /// Reports `diag` at the level configured for the lint being run
+    pub fn add_diag(&mut self, diag: Diagnostic) {
+        match self.level {
+            LintLevel::Allow => (),
+            LintLevel::Warn => self.env.add_diag(diag),
+            LintLevel::Deny => self
+                .env
+                .add_diag(diag.set_severity(Severity::NonblockingError)),
+        }
+    }
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+/// Runs all lints over the source modules and scripts of `prog`. Test functions and test only
+/// items are not linted.
+pub fn program(env: &mut CompilationEnv, levels: &LintLevels, prog: &T::Program) {
+    for (mident, mdef) in prog.modules.key_cloned_iter() {
+        if !mdef.is_source_module || is_test_item(&mdef.attributes) {
+            continue;
+        }
+        let module_levels = attribute_levels(env, levels, &mdef.attributes);
+        for (name, fdef) in mdef.functions.key_cloned_iter() {
+            if is_test_item(&fdef.attributes) {
+                continue;
+            }
+            let function_levels = attribute_levels(env, &module_levels, &fdef.attributes);
+            function(env, prog, &function_levels, Some(mident), name, fdef)
+        }
+    }
+    for script in prog.scripts.values() {
+        let script_levels = attribute_levels(env, levels, &script.attributes);
+        let function_levels = attribute_levels(env, &script_levels, &script.function.attributes);
+        function(
+            env,
+            prog,
+            &function_levels,
+            None,
+            script.function_name,
+            &script.function,
+        )
+    }
+}
+
+fn function(
+    env: &mut CompilationEnv,
+    program: &T::Program,
+    levels: &LintLevels,
+    current_module: Option<ModuleIdent>,
+    name: FunctionName,
+    fdef: &T::Function,
+) {
+    for lint in LINTS {
+        let level = levels
+            .get(&Symbol::from(lint.name()))
+            .copied()
+            .unwrap_or_else(|| lint.default_level());
+        if level == LintLevel::Allow {
+            continue;
+        }
+        let mut context = Context {
+            env: &mut *env,
+            level,
+            program,
+            current_module,
+        };
+        lint.check_function(&mut context, name, fdef)
+    }
+}
+
+fn is_test_item(attributes: &E::Attributes) -> bool {
+    [TestingAttribute::Test, TestingAttribute::TestOnly]
+        .iter()
+        .any(|attr| {
+            attributes.contains_key_(&AttributeName_::Known(KnownAttribute::Testing(*attr)))
+        })
+}
+
+/// Overrides `levels` with the levels set by any lint attributes in `attributes`. If a lint is
+/// named by more than one attribute, the most severe level is used.
+fn attribute_levels(
+    env: &mut CompilationEnv,
+    levels: &LintLevels,
+    attributes: &E::Attributes,
+) -> LintLevels {
+    let mut levels = levels.clone();
+    for (attr, level) in [
+        (LintAttribute::Allow, LintLevel::Allow),
+        (LintAttribute::Warn, LintLevel::Warn),
+        (LintAttribute::Deny, LintLevel::Deny),
+    ] {
+        let inner = match attributes.get_(&AttributeName_::Known(KnownAttribute::Lint(attr))) {
+            Some(sp!(_, Attribute_::Parameterized(_, inner))) => inner,
+            _ => continue,
+        };
+        for (loc, name_, _) in inner {
+            match name_ {
+                AttributeName_::Unknown(name) if find_lint(name.as_str()).is_some() => {
+                    levels.insert(*name, level);
+                }
+                _ => {
+                    let msg = format!("Unknown lint '{}'", name_);
+                    env.add_diag(diag!(Linter::UnknownLint, (loc, msg)))
+                }
+            }
+        }
+    }
+    levels
+}
+
+//**************************************************************************************************
+// Traversal
+//**************************************************************************************************
+
+/// Calls `f` on every expression in `seq`, parents before their subexpressions
+pub fn visit_sequence<'a>(seq: &'a T::Sequence, f: &mut impl FnMut(&'a T::Exp)) {
+    use T::SequenceItem_ as S;
+    for item in seq {
+        match &item.value {
+            S::Seq(e) | S::Bind(_, _, e) => visit_exp(e, f),
+            S::Declare(_) => (),
+        }
+    }
+}
+
+/// Calls `f` on `e` and every subexpression of `e`, parents before their subexpressions
+pub fn visit_exp<'a>(e: &'a T::Exp, f: &mut impl FnMut(&'a T::Exp)) {
+    use T::UnannotatedExp_ as E;
+    f(e);
+    match &e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Use(_)
+        | E::Constant(_, _)
+        | E::BorrowLocal(_, _)
+        | E::Break
+        | E::Continue
+        | E::Spec(_, _)
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(call) => visit_exp(&call.arguments, f),
+        E::Builtin(_, args) | E::Vector(_, _, _, args) => visit_exp(args, f),
+        E::IfElse(eb, et, ef) => {
+            visit_exp(eb, f);
+            visit_exp(et, f);
+            visit_exp(ef, f);
+        }
+        E::While(eb, eloop) => {
+            visit_exp(eb, f);
+            visit_exp(eloop, f);
+        }
+        E::Loop { body, .. } => visit_exp(body, f),
+        E::Block(seq) => visit_sequence(seq, f),
+        E::Mutate(el, er) | E::BinopExp(el, _, _, er) => {
+            visit_exp(el, f);
+            visit_exp(er, f);
+        }
+        E::Assign(_, _, e)
+        | E::Return(e)
+        | E::Abort(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _)
+        | E::TempBorrow(_, e)
+        | E::Cast(e, _)
+        | E::Annotate(e, _) => visit_exp(e, f),
+        E::Pack(_, _, _, fields) => {
+            for (_, _, (_, (_, e))) in fields {
+                visit_exp(e, f)
+            }
+        }
+        E::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        visit_exp(e, f)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Calls `f` on every expression in the body of `fdef`, if it has one
+pub fn visit_function<'a>(fdef: &'a T::Function, f: &mut impl FnMut(&'a T::Exp)) {
+    match &fdef.body.value {
+        T::FunctionBody_::Defined(seq) => visit_sequence(seq, f),
+        T::FunctionBody_::Native => (),
+    }
+}
+
+/// Calls `f` on the body of `fdef`, if it has one, and on every block within it
+pub fn visit_function_sequences<'a>(fdef: &'a T::Function, f: &mut impl FnMut(&'a T::Sequence)) {
+    if let T::FunctionBody_::Defined(seq) = &fdef.body.value {
+        f(seq);
+        visit_sequence(seq, &mut |e| {
+            if let T::UnannotatedExp_::Block(seq) = &e.exp.value {
+                f(seq)
+            }
+        })
+    }
+}
+
+//**************************************************************************************************
+// impls
+//**************************************************************************************************
+
+impl LintLevel {
+    pub const ALLOW: &'static str = "allow";
+    pub const WARN: &'static str = "warn";
+    pub const DENY: &'static str = "deny";
+}
+
+impl FromStr for LintLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s {
+            Self::ALLOW => Self::Allow,
+            Self::WARN => Self::Warn,
+            Self::DENY => Self::Deny,
+            _ => anyhow::bail!(
+                "Invalid lint level '{}'. Expected one of '{}', '{}' or '{}'",
+                s,
+                Self::ALLOW,
+                Self::WARN,
+                Self::DENY
+            ),
+        })
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "{}", Self::ALLOW),
+            Self::Warn => write!(f, "{}", Self::WARN),
+            Self::Deny => write!(f, "{}", Self::DENY),
+        }
+    }
+}
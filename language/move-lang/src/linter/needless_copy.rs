@@ -0,0 +1,121 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports explicit copies of large structs and vectors that are only borrowed, e.g. `&copy x` or
+//! `(copy x).f`. The copy is never observable, but its cost grows with the size of the value.
+//! A struct is large when it holds more than `MAX_SMALL_STRUCT_FIELDS` primitive values, counting
+//! the fields of nested structs, or when it holds a vector. The length of a vector is only known
+//! at runtime, so vectors are always treated as large.
+
+use super::{visit_function, Context, Lint};
+use crate::{
+    diag,
+    expansion::ast::ModuleIdent,
+    naming::ast::{BuiltinTypeName_, StructFields, Type, TypeName_, Type_},
+    parser::ast::{FunctionName, StructName},
+    typing::{
+        ast as T,
+        core::{make_tparam_subst, subst_tparams},
+    },
+};
+
+/// Structs with at most this many primitive values are cheap to copy
+const MAX_SMALL_STRUCT_FIELDS: usize = 4;
+
+pub struct NeedlessCopy;
+
+impl Lint for NeedlessCopy {
+    fn name(&self) -> &'static str {
+        "needless_copy"
+    }
+
+    fn description(&self) -> &'static str {
+        "copies of large structs or vectors that are only borrowed"
+    }
+
+    fn check_function(&self, context: &mut Context, _name: FunctionName, fdef: &T::Function) {
+        use T::UnannotatedExp_ as E;
+        let program = context.program;
+        visit_function(fdef, &mut |e| {
+            let copied = match &e.exp.value {
+                E::TempBorrow(_, copied) => copied,
+                _ => return,
+            };
+            match &copied.exp.value {
+                E::Copy {
+                    from_user: true,
+                    var,
+                } if is_large(program, &copied.ty) => {
+                    let msg = format!(
+                        "Needless copy of '{}'. The copy is only borrowed, borrow '{}' directly",
+                        var, var
+                    );
+                    context.add_diag(diag!(Linter::NeedlessCopy, (copied.exp.loc, msg)))
+                }
+                _ => (),
+            }
+        })
+    }
+}
+
+/// The size of the values of a type
+enum Size {
+    /// The number of primitive values in a value, once nested structs are flattened
+    Fields(usize),
+    /// Values hold a vector, whose length is only known at runtime
+    Unbounded,
+    /// The size depends on a type parameter or on a native struct
+    Unknown,
+}
+
+fn is_large(program: &T::Program, ty: &Type) -> bool {
+    match &ty.value {
+        Type_::Apply(_, sp!(_, TypeName_::ModuleType(_, _)), _)
+        | Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, BuiltinTypeName_::Vector))), _) => {
+            match size(program, ty) {
+                Size::Fields(fields) => fields > MAX_SMALL_STRUCT_FIELDS,
+                Size::Unbounded => true,
+                Size::Unknown => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn size(program: &T::Program, ty: &Type) -> Size {
+    match &ty.value {
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, BuiltinTypeName_::Vector))), _) => {
+            Size::Unbounded
+        }
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(_)), _) | Type_::Ref(_, _) => Size::Fields(1),
+        Type_::Apply(_, sp!(_, TypeName_::ModuleType(m, s)), ty_args) => {
+            struct_size(program, m, s, ty_args)
+        }
+        _ => Size::Unknown,
+    }
+}
+
+fn struct_size(program: &T::Program, m: &ModuleIdent, s: &StructName, ty_args: &[Type]) -> Size {
+    let sdef = match program.modules.get(m).and_then(|mdef| mdef.structs.get(s)) {
+        Some(sdef) => sdef,
+        None => return Size::Unknown,
+    };
+    let fields = match &sdef.fields {
+        StructFields::Defined(fields) => fields,
+        StructFields::Native(_) => return Size::Unknown,
+    };
+    let subst = make_tparam_subst(
+        sdef.type_parameters.iter().map(|tp| &tp.param),
+        ty_args.iter().cloned(),
+    );
+    let mut total = Size::Fields(0);
+    for (_, _, (_, field_ty)) in fields {
+        let field_size = size(program, &subst_tparams(&subst, field_ty.clone()));
+        total = match (total, field_size) {
+            (Size::Unbounded, _) | (_, Size::Unbounded) => Size::Unbounded,
+            (Size::Unknown, _) | (_, Size::Unknown) => Size::Unknown,
+            (Size::Fields(total), Size::Fields(fields)) => Size::Fields(total + fields),
+        }
+    }
+    total
+}
@@ -0,0 +1,79 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports `while` loops bounded by `Vector::length`, either directly or through a local bound to
+//! it, in entry functions, that is script functions and the functions of scripts. The vector is
+//! usually built from transaction arguments, so the number of iterations, and the gas needed to
+//! run them, is chosen by the sender. Allow the lint where the length is checked against a limit
+//! before the loop.
+
+use super::{visit_exp, visit_function, visit_function_sequences, Context, Lint};
+use crate::{
+    diag,
+    parser::ast::{FunctionName, Var, Visibility},
+    typing::ast as T,
+};
+use std::collections::BTreeSet;
+
+const VECTOR_MODULE: &str = "Vector";
+const LENGTH_FUNCTION: &str = "length";
+
+pub struct UnboundedVectorLoop;
+
+impl Lint for UnboundedVectorLoop {
+    fn name(&self) -> &'static str {
+        "unbounded_vector_loop"
+    }
+
+    fn description(&self) -> &'static str {
+        "loops over the length of a vector in entry functions"
+    }
+
+    fn check_function(&self, context: &mut Context, _name: FunctionName, fdef: &T::Function) {
+        let is_entry =
+            context.current_module.is_none() || matches!(fdef.visibility, Visibility::Script(_));
+        if !is_entry {
+            return;
+        }
+        // Locals bound to the length of a vector, e.g. `let len = Vector::length(&v);`
+        let mut lengths = BTreeSet::new();
+        visit_function_sequences(fdef, &mut |seq| {
+            for item in seq {
+                if let T::SequenceItem_::Bind(sp!(_, lvalues), _, e) = &item.value {
+                    if let [sp!(_, T::LValue_::Var(v, _))] = &lvalues[..] {
+                        if uses_vector_length(&BTreeSet::new(), e) {
+                            lengths.insert(*v);
+                        }
+                    }
+                }
+            }
+        });
+        visit_function(fdef, &mut |e| {
+            let cond = match &e.exp.value {
+                T::UnannotatedExp_::While(cond, _) => cond,
+                _ => return,
+            };
+            if uses_vector_length(&lengths, cond) {
+                let msg = "Loop bounded by the length of a vector in an entry function. The \
+                           number of iterations is not limited by the code, consider checking \
+                           the length against a maximum before looping";
+                context.add_diag(diag!(Linter::UnboundedVectorLoop, (cond.exp.loc, msg)))
+            }
+        })
+    }
+}
+
+/// Returns true if `e` calls `Vector::length` or reads one of the locals in `lengths`
+fn uses_vector_length(lengths: &BTreeSet<Var>, e: &T::Exp) -> bool {
+    use T::UnannotatedExp_ as E;
+    let mut found = false;
+    visit_exp(e, &mut |e| match &e.exp.value {
+        E::ModuleCall(call) => {
+            found |= call.module.value.module.0.value.as_str() == VECTOR_MODULE
+                && call.name.0.value.as_str() == LENGTH_FUNCTION
+        }
+        E::Copy { var, .. } | E::Move { var, .. } | E::Use(var) => found |= lengths.contains(var),
+        _ => (),
+    });
+    found
+}
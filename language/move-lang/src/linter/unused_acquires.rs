@@ -0,0 +1,135 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports `acquires` annotations that are accepted by the compiler but are not needed: those on
+//! native functions, which are never checked, and those only needed because a global is borrowed
+//! to check that it exists.
+
+use super::{visit_function, visit_function_sequences, Context, Lint};
+use crate::{
+    diag,
+    naming::ast::{Type, TypeName_, Type_},
+    parser::ast::{FunctionName, StructName},
+    typing::ast as T,
+};
+use move_ir_types::location::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub struct UnusedAcquires;
+
+impl Lint for UnusedAcquires {
+    fn name(&self) -> &'static str {
+        "unused_acquires"
+    }
+
+    fn description(&self) -> &'static str {
+        "'acquires' annotations that are not needed to access global storage"
+    }
+
+    fn check_function(&self, context: &mut Context, name: FunctionName, fdef: &T::Function) {
+        if fdef.acquires.is_empty() {
+            return;
+        }
+        match &fdef.body.value {
+            T::FunctionBody_::Native => native_function(context, fdef),
+            T::FunctionBody_::Defined(_) => defined_function(context, name, fdef),
+        }
+    }
+}
+
+fn native_function(context: &mut Context, fdef: &T::Function) {
+    for (sn, loc) in &fdef.acquires {
+        let msg = format!(
+            "Unnecessary 'acquires' of '{}'. The 'acquires' list of a native function is not \
+             checked, but every caller is still required to list '{}' as acquired",
+            sn, sn
+        );
+        context.add_diag(diag!(Linter::UnusedAcquires, (*loc, msg)))
+    }
+}
+
+fn defined_function(context: &mut Context, name: FunctionName, fdef: &T::Function) {
+    // Global borrows whose reference is immediately dropped
+    let mut discarded = vec![];
+    visit_function_sequences(fdef, &mut |seq| discarded.extend(discarded_borrows(seq)));
+
+    let current_module = context.current_module;
+    let mut acquired = BTreeSet::new();
+    let mut existence_checks: BTreeMap<StructName, Vec<Loc>> = BTreeMap::new();
+    visit_function(fdef, &mut |e| match &e.exp.value {
+        T::UnannotatedExp_::Builtin(b, _) => match &b.value {
+            T::BuiltinFunction_::BorrowGlobal(_, ty) if discarded.contains(&e.exp.loc) => {
+                if let Some(sn) = struct_name(ty) {
+                    existence_checks.entry(sn).or_default().push(e.exp.loc)
+                }
+            }
+            T::BuiltinFunction_::BorrowGlobal(_, ty) | T::BuiltinFunction_::MoveFrom(ty) => {
+                acquired.extend(struct_name(ty))
+            }
+            _ => (),
+        },
+        // Recursive calls do not acquire anything beyond what the function itself acquires
+        T::UnannotatedExp_::ModuleCall(call)
+            if Some(call.module) == current_module && call.name == name => {}
+        T::UnannotatedExp_::ModuleCall(call) => acquired.extend(call.acquires.keys().copied()),
+        _ => (),
+    });
+
+    for (sn, loc) in &fdef.acquires {
+        if acquired.contains(sn) {
+            continue;
+        }
+        let borrow_locs = match existence_checks.get(sn) {
+            Some(locs) => locs,
+            None => continue,
+        };
+        let msg = format!(
+            "Unnecessary 'acquires' of '{}'. It is only borrowed to check that it exists",
+            sn
+        );
+        let mut diag = diag!(Linter::UnusedAcquires, (*loc, msg));
+        for borrow_loc in borrow_locs {
+            let borrow_msg = format!(
+                "The reference is discarded. Consider asserting 'exists<{}>' instead",
+                sn
+            );
+            diag.add_secondary_label((*borrow_loc, borrow_msg))
+        }
+        context.add_diag(diag)
+    }
+}
+
+fn discarded_borrows(seq: &T::Sequence) -> Vec<Loc> {
+    use T::SequenceItem_ as S;
+    seq.iter()
+        .filter_map(|item| match &item.value {
+            S::Seq(e) => Some(e),
+            S::Bind(lvalues, _, e)
+                if lvalues
+                    .value
+                    .iter()
+                    .all(|lvalue| matches!(lvalue.value, T::LValue_::Ignore)) =>
+            {
+                Some(e)
+            }
+            _ => None,
+        })
+        .filter(|e| is_global_borrow(e))
+        .map(|e| e.exp.loc)
+        .collect()
+}
+
+fn is_global_borrow(e: &T::Exp) -> bool {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Builtin(b, _) => matches!(b.value, T::BuiltinFunction_::BorrowGlobal(_, _)),
+        _ => false,
+    }
+}
+
+fn struct_name(ty: &Type) -> Option<StructName> {
+    match &ty.value {
+        Type_::Apply(_, sp!(_, TypeName_::ModuleType(_, sn)), _) => Some(*sn),
+        _ => None,
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports public and script functions that take a `signer` but never use it. Callers take the
+//! parameter to mean that the function checks the authority of the signer, so an unused signer is
+//! usually a missing access control check. This is reported even when the parameter is prefixed
+//! with `_`, which only silences the unused variable warning.
+
+use super::{visit_function, Context, Lint};
+use crate::{
+    diag,
+    naming::ast::{BuiltinTypeName_, Type, TypeName_, Type_},
+    parser::ast::{FunctionName, Visibility},
+    typing::ast as T,
+};
+use std::collections::BTreeSet;
+
+pub struct UnusedSigner;
+
+impl Lint for UnusedSigner {
+    fn name(&self) -> &'static str {
+        "unused_signer"
+    }
+
+    fn description(&self) -> &'static str {
+        "public functions that take a 'signer' but never use it"
+    }
+
+    fn check_function(&self, context: &mut Context, name: FunctionName, fdef: &T::Function) {
+        use T::UnannotatedExp_ as E;
+        let is_public = matches!(
+            fdef.visibility,
+            Visibility::Public(_) | Visibility::Script(_)
+        );
+        if !is_public || fdef.body.value == T::FunctionBody_::Native {
+            return;
+        }
+
+        let mut used = BTreeSet::new();
+        visit_function(fdef, &mut |e| match &e.exp.value {
+            E::Copy { var, .. } | E::Move { var, .. } | E::Use(var) | E::BorrowLocal(_, var) => {
+                used.insert(*var);
+            }
+            _ => (),
+        });
+
+        for (param, ty) in &fdef.signature.parameters {
+            if is_signer(ty) && !used.contains(param) {
+                let msg = format!(
+                    "Unused signer '{}'. '{}' takes a signer but never checks or uses it",
+                    param, name
+                );
+                context.add_diag(diag!(Linter::UnusedSigner, (param.0.loc, msg)))
+            }
+        }
+    }
+}
+
+fn is_signer(ty: &Type) -> bool {
+    match &ty.value {
+        Type_::Ref(_, inner) => is_signer(inner),
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, BuiltinTypeName_::Signer))), _) => true,
+        _ => false,
+    }
+}
@@ -514,6 +514,7 @@ pub mod known_attributes {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
     pub enum KnownAttribute {
         Testing(TestingAttribute),
+        Lint(LintAttribute),
     }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -526,6 +527,16 @@ pub mod known_attributes {
         ExpectedFailure,
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum LintAttribute {
+        // The listed lints are not reported for the annotated item
+        Allow,
+        // The listed lints are reported as warnings for the annotated item
+        Warn,
+        // The listed lints are reported as errors for the annotated item
+        Deny,
+    }
+
     impl fmt::Display for AttributePosition {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
@@ -550,6 +561,9 @@ pub mod known_attributes {
                 TestingAttribute::EXPECTED_FAILURE => {
                     Self::Testing(TestingAttribute::ExpectedFailure)
                 }
+                LintAttribute::ALLOW => Self::Lint(LintAttribute::Allow),
+                LintAttribute::WARN => Self::Lint(LintAttribute::Warn),
+                LintAttribute::DENY => Self::Lint(LintAttribute::Deny),
                 _ => return None,
             })
         }
@@ -557,12 +571,14 @@ pub mod known_attributes {
         pub const fn name(&self) -> &str {
             match self {
                 Self::Testing(a) => a.name(),
+                Self::Lint(a) => a.name(),
             }
         }
 
         pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
             match self {
                 Self::Testing(a) => a.expected_positions(),
+                Self::Lint(a) => a.expected_positions(),
             }
         }
     }
@@ -605,4 +621,30 @@ pub mod known_attributes {
             }
         }
     }
+
+    impl LintAttribute {
+        pub const ALLOW: &'static str = "allow";
+        pub const WARN: &'static str = "warn";
+        pub const DENY: &'static str = "deny";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                Self::Allow => Self::ALLOW,
+                Self::Warn => Self::WARN,
+                Self::Deny => Self::DENY,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static LINT_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::Module,
+                    AttributePosition::Script,
+                    AttributePosition::Function,
+                ])
+                .collect()
+            });
+            &*LINT_POSITIONS
+        }
+    }
 }
@@ -0,0 +1,24 @@
+#[allow(unused_signer)]
+module 0x42::M {
+    // allowed by the module attribute
+    public fun allowed(_account: &signer) {}
+
+    // the function attribute overrides the module attribute
+    #[warn(unused_signer)]
+    public fun warned(_account: &signer) {}
+
+    #[deny(literal_abort_code)]
+    fun denied(x: u64) {
+        assert!(x > 0, 1);
+    }
+
+    #[allow(literal_abort_code, not_a_lint)]
+    fun allowed_with_unknown(x: u64) {
+        assert!(x > 0, 1);
+    }
+}
+
+module 0x42::N {
+    // not allowed outside of the annotated module
+    public fun warned(_account: &signer) {}
+}
@@ -0,0 +1,20 @@
+module 0x42::M {
+    use Std::Errors;
+
+    const EINVALID: u64 = 0;
+
+    fun t(x: u64) {
+        assert!(x > 0, 1);
+        assert!(x > 1, 2u64);
+        if (x > 2) abort 3;
+        // error constants, no lint
+        assert!(x > 3, Errors::invalid_argument(EINVALID));
+        if (x > 4) abort Errors::invalid_state(EINVALID);
+        assert!(x > 5, EINVALID);
+    }
+
+    #[test]
+    fun test_t() {
+        assert!(true, 42);
+    }
+}
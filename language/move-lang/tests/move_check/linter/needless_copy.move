@@ -0,0 +1,38 @@
+module 0x42::M {
+    use Std::Vector;
+
+    struct S has copy, drop { f: u64 }
+    struct Large has copy, drop { a: u64, b: u64, c: u64, d: u64, e: u64 }
+    struct Nested has copy, drop { s: S, t: S, u: S, v: S, w: S }
+    struct WithVector has copy, drop { f: u64, v: vector<u8> }
+    struct Box<T> has copy, drop { t: T }
+
+    fun read(s: &Large): u64 {
+        s.a
+    }
+
+    fun t(l: Large, v: vector<u64>, x: u64, n: Nested, w: WithVector, b: Box<Large>): u64 {
+        let a = read(&copy l);
+        let b_ = (copy l).b;
+        let c = Vector::length(&copy v);
+        let _ = &copy n;
+        let _ = &copy w;
+        let _ = &copy b;
+        // primitives are cheap to copy, no lint
+        let d = *&copy x;
+        // the copy is used as a value, no lint
+        let e = copy l;
+        a + b_ + c + d + e.e + read(&l) + Vector::length(&v)
+    }
+
+    fun small(s: S, b: Box<S>): u64 {
+        // small structs are cheap to copy, no lint
+        (copy s).f + (copy b).t.f
+    }
+
+    fun generic<T: copy + drop>(b: Box<T>, t: T): Box<T> {
+        // the size of a type parameter is not known, no lint
+        let _ = &copy t;
+        *&copy b
+    }
+}
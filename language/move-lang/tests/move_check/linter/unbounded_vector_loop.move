@@ -0,0 +1,45 @@
+module 0x42::M {
+    use Std::Vector;
+
+    public(script) fun direct(v: vector<u64>) {
+        let i = 0;
+        while (i < Vector::length(&v)) {
+            i = i + 1;
+        }
+    }
+
+    public(script) fun through_local(v: vector<u64>) {
+        let i = 0;
+        let len = Vector::length(&v);
+        while (i < len) {
+            i = i + 1;
+        }
+    }
+
+    // bounded by a constant, no lint
+    public(script) fun bounded(n: u64) {
+        let i = 0;
+        while (i < 10) {
+            i = i + n;
+        }
+    }
+
+    // not an entry function, no lint
+    public fun not_entry(v: vector<u64>) {
+        let i = 0;
+        while (i < Vector::length(&v)) {
+            i = i + 1;
+        }
+    }
+}
+
+script {
+    use Std::Vector;
+
+    fun main(v: vector<u64>) {
+        let i = 0;
+        while (i < Vector::length(&v)) {
+            i = i + 1;
+        }
+    }
+}
@@ -0,0 +1,37 @@
+module 0x42::M {
+    struct R has key { f: u64 }
+
+    // natives can list any struct, the list is never checked
+    native fun n(): u64 acquires R;
+
+    // only borrowed to check that it exists
+    fun check(a: address) acquires R {
+        borrow_global<R>(a);
+    }
+
+    fun check_let(a: address) acquires R {
+        let _ = borrow_global_mut<R>(a);
+    }
+
+    // the reference is used, no lint
+    fun read(a: address): u64 acquires R {
+        borrow_global<R>(a).f
+    }
+
+    // one of the acquisitions is needed, no lint
+    fun check_and_remove(a: address): R acquires R {
+        borrow_global<R>(a);
+        move_from<R>(a)
+    }
+
+    // acquired through a call, no lint
+    fun call(a: address) acquires R {
+        borrow_global<R>(a);
+        read(a);
+    }
+
+    #[test_only]
+    fun test_check(a: address) acquires R {
+        borrow_global<R>(a);
+    }
+}
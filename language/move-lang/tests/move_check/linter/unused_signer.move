@@ -0,0 +1,25 @@
+module 0x42::M {
+    use Std::Signer;
+
+    struct R has key { f: u64 }
+
+    public fun unused(_account: &signer, x: u64): u64 {
+        x
+    }
+
+    public(script) fun unused_script(_account: signer) {}
+
+    // used, no lint
+    public fun used(account: &signer) {
+        move_to(account, R { f: 0 })
+    }
+
+    public fun used_address(account: &signer): address {
+        Signer::address_of(account)
+    }
+
+    // not public, no lint
+    fun internal(_account: &signer) {}
+
+    public(friend) fun friend_only(_account: &signer) {}
+}
@@ -8,8 +8,9 @@ use move_command_line_common::{
 use move_lang::{
     compiled_unit::AnnotatedCompiledUnit,
     diagnostics::*,
+    linter::{self, LintLevels},
     shared::{Flags, NumericalAddress},
-    unit_test, CommentMap, Compiler, SteppedCompiler, PASS_CFGIR, PASS_PARSER,
+    unit_test, CommentMap, Compiler, SteppedCompiler, PASS_CFGIR, PASS_PARSER, PASS_TYPING,
 };
 use std::{collections::BTreeMap, fs, path::Path};
 
@@ -18,6 +19,8 @@ const KEEP_TMP: &str = "KEEP";
 
 const TEST_EXT: &str = "unit_test";
 
+const LINT_EXT: &str = "lint";
+
 fn default_testing_addresses() -> BTreeMap<String, NumericalAddress> {
     let mapping = [
         ("Std", "0x1"),
@@ -51,17 +54,45 @@ fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
             Path::new(&test_exp_path),
             Path::new(&test_out_path),
             Flags::testing(),
+            false,
+        )?;
+    }
+
+    // A test is marked that it should also be run through the linter by having a `path.lint` file.
+    if path.with_extension(LINT_EXT).exists() {
+        let lint_exp_path = format!(
+            "{}.lint.{}",
+            path.with_extension("").to_string_lossy(),
+            EXP_EXT
+        );
+        let lint_out_path = format!(
+            "{}.lint.{}",
+            path.with_extension("").to_string_lossy(),
+            OUT_EXT
+        );
+        run_test(
+            path,
+            Path::new(&lint_exp_path),
+            Path::new(&lint_out_path),
+            Flags::empty(),
+            true,
         )?;
     }
 
     let exp_path = path.with_extension(EXP_EXT);
     let out_path = path.with_extension(OUT_EXT);
-    run_test(path, &exp_path, &out_path, Flags::empty())?;
+    run_test(path, &exp_path, &out_path, Flags::empty(), false)?;
     Ok(())
 }
 
 // Runs all tests under the test/testsuite directory.
-fn run_test(path: &Path, exp_path: &Path, out_path: &Path, flags: Flags) -> anyhow::Result<()> {
+fn run_test(
+    path: &Path,
+    exp_path: &Path,
+    out_path: &Path,
+    flags: Flags,
+    lint: bool,
+) -> anyhow::Result<()> {
     let targets: Vec<String> = vec![path.to_str().unwrap().to_owned()];
 
     let (files, comments_and_compiler_res) =
@@ -69,7 +100,7 @@ fn run_test(path: &Path, exp_path: &Path, out_path: &Path, flags: Flags) -> anyh
             .set_flags(flags)
             .set_named_address_values(default_testing_addresses())
             .run::<PASS_PARSER>()?;
-    let diags = move_check_for_errors(comments_and_compiler_res);
+    let diags = move_check_for_errors(comments_and_compiler_res, lint);
 
     let has_diags = !diags.is_empty();
     let diag_buffer = if has_diags {
@@ -129,15 +160,24 @@ fn run_test(path: &Path, exp_path: &Path, out_path: &Path, flags: Flags) -> anyh
 
 fn move_check_for_errors(
     comments_and_compiler_res: Result<(CommentMap, SteppedCompiler<'_, PASS_PARSER>), Diagnostics>,
+    lint: bool,
 ) -> Diagnostics {
     fn try_impl(
         comments_and_compiler_res: Result<
             (CommentMap, SteppedCompiler<'_, PASS_PARSER>),
             Diagnostics,
         >,
+        lint: bool,
     ) -> Result<(Vec<AnnotatedCompiledUnit>, Diagnostics), Diagnostics> {
         let (_, compiler) = comments_and_compiler_res?;
-        let (mut compiler, cfgir) = compiler.run::<PASS_CFGIR>()?.into_ast();
+        let compiler = if lint {
+            let (mut compiler, typing) = compiler.run::<PASS_TYPING>()?.into_ast();
+            linter::program(compiler.compilation_env(), &LintLevels::new(), &typing);
+            compiler.at_typing(typing).run::<PASS_CFGIR>()?
+        } else {
+            compiler.run::<PASS_CFGIR>()?
+        };
+        let (mut compiler, cfgir) = compiler.into_ast();
         let compilation_env = compiler.compilation_env();
         if compilation_env.flags().is_testing() {
             unit_test::plan_builder::construct_test_plan(compilation_env, &cfgir);
@@ -147,7 +187,7 @@ fn move_check_for_errors(
         Ok((units, diags))
     }
 
-    let (units, inner_diags) = match try_impl(comments_and_compiler_res) {
+    let (units, inner_diags) = match try_impl(comments_and_compiler_res, lint) {
         Ok((units, inner_diags)) => (units, inner_diags),
         Err(inner_diags) => return inner_diags,
     };
@@ -5,3 +5,4 @@
 
 pub const VECTOR_BORROW_MUT: &str = "0x1::Vector::borrow_mut";
 pub const EVENT_EMIT_EVENT: &str = "0x1::Event::emit_event";
+pub const TABLE_BORROW_BOX_MUT: &str = "0x1::Table::borrow_box_mut";
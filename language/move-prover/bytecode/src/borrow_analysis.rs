@@ -17,7 +17,7 @@ use move_binary_format::file_format::CodeOffset;
 use move_model::{
     ast::TempIndex,
     model::{FunctionEnv, GlobalEnv, QualifiedInstId},
-    native::VECTOR_BORROW_MUT,
+    native::{TABLE_BORROW_BOX_MUT, VECTOR_BORROW_MUT},
     ty::Type,
 };
 use std::{
@@ -433,6 +433,19 @@ fn native_annotation(fun_env: &FunctionEnv) -> BorrowAnnotation {
             an.summary.consolidate();
             an
         }
+        TABLE_BORROW_BOX_MUT => {
+            // The items of tables are not modeled, so the item is borrowed from the table itself.
+            let mut an = BorrowAnnotation::default();
+            let param_node = BorrowNode::Reference(0);
+            let return_node = BorrowNode::ReturnPlaceholder(0);
+            an.summary
+                .borrowed_by
+                .entry(param_node)
+                .or_default()
+                .insert((return_node, BorrowEdge::Direct));
+            an.summary.consolidate();
+            an
+        }
         _ => BorrowAnnotation::default(),
     }
 }
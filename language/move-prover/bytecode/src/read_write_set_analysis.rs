@@ -672,6 +672,27 @@ fn call_native_function(
                 state.assign_local(rets[0], args[0], func_env)
             }
         }
+        // The items of a table are modeled as the contents of the table itself
+        ("Table", "add_box") | ("Table", "remove_box") => {
+            if state.locals.local_exists(args[0], func_env) {
+                // checks whether the item exists, then adds or removes it
+                state.record_access(args[0], Access::Read, func_env);
+                state.record_access(args[0], Access::Write, func_env);
+            }
+        }
+        ("Table", "borrow_box") | ("Table", "borrow_box_mut") => {
+            if state.locals.local_exists(args[0], func_env) {
+                state.record_access(args[0], Access::Read, func_env);
+                // treat the reference to the item as a reference to the table
+                state.assign_local(rets[0], args[0], func_env)
+            }
+        }
+        ("Table", "contains_box") => {
+            if state.locals.local_exists(args[0], func_env) {
+                state.record_access(args[0], Access::Read, func_env)
+            }
+        }
+        ("Table", "new_table_handle") => (),
         ("Vector", "empty") | ("Vector", "destroy_empty") | ("Vector", "reverse") => (),
         ("Event", "write_to_event_store") => (),
         ("Hash", "sha3_256") | ("Hash", "sha2_256") => (),
@@ -26,6 +26,7 @@ fn test_runner(path: &Path) -> datatest_stable::Result<()> {
         named_address_values: move_stdlib::move_stdlib_named_addresses()
             .into_iter()
             .collect(),
+        gas_profile: None,
     };
 
     let test_plan = config.build_test_plan().unwrap();
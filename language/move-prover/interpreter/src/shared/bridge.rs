@@ -35,7 +35,8 @@ fn adapt_move_vm_change_set_internal<S: MoveResolver>(
     old_storage: &S,
 ) -> ChangeSet {
     let mut adapted = ChangeSet::new();
-    for (addr, state) in change_set.into_inner() {
+    let (accounts, tables) = change_set.into_inner();
+    for (addr, state) in accounts {
         let (modules, resources) = state.into_inner();
         for (tag, val) in resources {
             match val {
@@ -64,5 +65,20 @@ fn adapt_move_vm_change_set_internal<S: MoveResolver>(
             }
         }
     }
+    for (handle, table) in tables {
+        for (key, val) in table {
+            match val {
+                // deletion
+                None => adapted.remove_table_item(handle, key),
+                // addition / modification, the latter only if the value actually changes
+                Some(new_val) => {
+                    if old_storage.get_table_item(&handle, &key).unwrap().as_ref() != Some(&new_val)
+                    {
+                        adapted.publish_or_overwrite_table_item(handle, key, new_val);
+                    }
+                }
+            }
+        }
+    }
     adapted
 }
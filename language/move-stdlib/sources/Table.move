@@ -0,0 +1,106 @@
+/// Type of large-scale storage tables.
+///
+/// A `Table` maps keys of type `K` to values of type `V`. Unlike the fields of a resource, which are
+/// stored together with the resource, every item of a table is stored under its own key in global
+/// storage. Tables can thus hold a large number of items, of which a transaction only reads and
+/// writes the ones it accesses.
+module Std::Table {
+    use Std::Errors;
+
+    /// The key already exists in the table.
+    const EALREADY_EXISTS: u64 = 0;
+    /// The key does not exist in the table.
+    const ENOT_FOUND: u64 = 1;
+    /// The table still holds items.
+    const ENOT_EMPTY: u64 = 2;
+
+    /// Type of tables.
+    struct Table<phantom K: copy + drop, phantom V: store> has store {
+        handle: address,
+        length: u64,
+    }
+
+    /// Wrapper of the values of tables, which the natives hold like resources.
+    struct Box<V: store> has key, store {
+        val: V
+    }
+
+    /// Create a new, empty table.
+    public fun new<K: copy + drop, V: store>(): Table<K, V> {
+        Table { handle: new_table_handle(), length: 0 }
+    }
+
+    /// Destroy a table. Aborts if the table is not empty.
+    public fun destroy_empty<K: copy + drop, V: store>(table: Table<K, V>) {
+        assert!(table.length == 0, Errors::invalid_state(ENOT_EMPTY));
+        let Table { handle: _, length: _ } = table;
+    }
+
+    /// Add a new item to the table, mapping `key` to `val`. Aborts if an item for `key` already
+    /// exists.
+    public fun add<K: copy + drop, V: store>(table: &mut Table<K, V>, key: K, val: V) {
+        add_box<K, V, Box<V>>(table, key, Box { val });
+        table.length = table.length + 1;
+    }
+
+    /// Acquire an immutable reference to the value which `key` maps to. Aborts if there is no item
+    /// for `key`.
+    public fun borrow<K: copy + drop, V: store>(table: &Table<K, V>, key: K): &V {
+        &borrow_box<K, V, Box<V>>(table, key).val
+    }
+
+    /// Acquire a mutable reference to the value which `key` maps to. Aborts if there is no item
+    /// for `key`.
+    public fun borrow_mut<K: copy + drop, V: store>(table: &mut Table<K, V>, key: K): &mut V {
+        &mut borrow_box_mut<K, V, Box<V>>(table, key).val
+    }
+
+    /// Return true if `table` contains an item for `key`.
+    public fun contains<K: copy + drop, V: store>(table: &Table<K, V>, key: K): bool {
+        contains_box<K, V, Box<V>>(table, key)
+    }
+
+    /// Remove the item for `key` from `table`, and return the value which `key` mapped to. Aborts
+    /// if there is no item for `key`.
+    public fun remove<K: copy + drop, V: store>(table: &mut Table<K, V>, key: K): V {
+        let Box { val } = remove_box<K, V, Box<V>>(table, key);
+        table.length = table.length - 1;
+        val
+    }
+
+    /// Return the number of items in `table`.
+    public fun length<K: copy + drop, V: store>(table: &Table<K, V>): u64 {
+        table.length
+    }
+
+    /// Return true if `table` holds no items.
+    public fun empty<K: copy + drop, V: store>(table: &Table<K, V>): bool {
+        table.length == 0
+    }
+
+    // ****************** NATIVES *******************
+
+    // The natives abort with `Errors::already_published(EALREADY_EXISTS)` when adding an item
+    // that exists, and with `Errors::not_published(ENOT_FOUND)` when accessing an item that
+    // doesn't. `B` is always `Box<V>`: the natives need its type to load the items.
+
+    native fun new_table_handle(): address;
+
+    native fun add_box<K: copy + drop, V: store, B>(table: &mut Table<K, V>, key: K, val: Box<V>);
+
+    native fun borrow_box<K: copy + drop, V: store, B>(table: &Table<K, V>, key: K): &Box<V>;
+
+    native fun borrow_box_mut<K: copy + drop, V: store, B>(table: &mut Table<K, V>, key: K): &mut Box<V>;
+
+    native fun contains_box<K: copy + drop, V: store, B>(table: &Table<K, V>, key: K): bool;
+
+    native fun remove_box<K: copy + drop, V: store, B>(table: &mut Table<K, V>, key: K): Box<V>;
+
+    // ****************** SPECIFICATIONS *******************
+    spec module {} // switch documentation context to module
+
+    spec module {
+        /// The natives of tables are not modeled by the prover yet.
+        pragma verify = false;
+    }
+}
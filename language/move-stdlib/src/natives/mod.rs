@@ -5,6 +5,7 @@ pub mod bcs;
 pub mod event;
 pub mod hash;
 pub mod signer;
+pub mod table;
 pub mod vector;
 
 #[cfg(feature = "testing")]
@@ -23,6 +24,12 @@ pub fn all_natives(move_std_addr: AccountAddress) -> NativeFunctionTable {
         ("Hash", "sha2_256", hash::native_sha2_256),
         ("Hash", "sha3_256", hash::native_sha3_256),
         ("Signer", "borrow_address", signer::native_borrow_address),
+        ("Table", "new_table_handle", table::native_new_table_handle),
+        ("Table", "add_box", table::native_add_box),
+        ("Table", "borrow_box", table::native_borrow_box),
+        ("Table", "borrow_box_mut", table::native_borrow_box),
+        ("Table", "contains_box", table::native_contains_box),
+        ("Table", "remove_box", table::native_remove_box),
         ("Vector", "length", vector::native_length),
         ("Vector", "empty", vector::native_empty),
         ("Vector", "borrow", vector::native_borrow),
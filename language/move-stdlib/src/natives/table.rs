@@ -0,0 +1,204 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Natives of the `Table` module. The items of a table are loaded into the data store of the
+//! session under the handle of the table and their serialized keys, and held there as resources
+//! of type `Box<V>`, the last type argument of the natives.
+
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_schedule::{GasAlgebra, GasCarrier, InternalGasUnits},
+    language_storage::TableHandle,
+    vm_status::{sub_status::NFE_BCS_SERIALIZATION_FAILURE, StatusCode},
+};
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    gas_schedule::NativeCostIndex,
+    loaded_data::runtime_types::Type,
+    natives::function::{checked_native_gas, NativeResult},
+    pop_arg,
+    values::{Reference, StructRef, Value},
+};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// `Errors::already_published(EALREADY_EXISTS)` in the `Table` module.
+const ALREADY_EXISTS: u64 = 6;
+/// `Errors::not_published(ENOT_FOUND)` in the `Table` module.
+const NOT_FOUND: u64 = (1 << 8) + 5;
+
+/// Index of the `handle` field in the `Table` struct.
+const HANDLE_FIELD_INDEX: usize = 0;
+
+/// Rust implementation of Move's `native fun new_table_handle(): address`
+pub fn native_new_table_handle(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let cost = checked_native_gas(context.cost_table(), NativeCostIndex::TABLE_NEW, 1)?;
+    let handle = context.create_table_handle()?;
+
+    Ok(NativeResult::ok(cost, smallvec![Value::address(handle.0)]))
+}
+
+/// Rust implementation of Move's
+/// `native fun add_box<K: copy + drop, V: store, B>(table: &mut Table<K, V>, key: K, val: Box<V>)`
+pub fn native_add_box(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 3);
+    debug_assert!(args.len() == 3);
+
+    let val = args.pop_back().unwrap();
+    let key = args.pop_back().unwrap();
+    let handle = get_table_handle(pop_arg!(args, StructRef))?;
+
+    let key_bytes = match serialize_key(context, &ty_args[0], key)? {
+        Some(key_bytes) => key_bytes,
+        None => return serialization_failure(context),
+    };
+    let cost = checked_native_gas(
+        context.cost_table(),
+        NativeCostIndex::TABLE_INSERT,
+        key_bytes.len() + val.size().get() as usize,
+    )?;
+
+    let gv = context.load_table_item(handle, key_bytes, &ty_args[2])?;
+    match gv.move_to(val) {
+        Ok(()) => Ok(NativeResult::ok(cost, smallvec![])),
+        Err(err) if err.major_status() == StatusCode::RESOURCE_ALREADY_EXISTS => {
+            Ok(NativeResult::err(cost, ALREADY_EXISTS))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Rust implementation of Move's
+/// `native fun borrow_box<K: copy + drop, V: store, B>(table: &Table<K, V>, key: K): &Box<V>`
+/// and of `borrow_box_mut`, which only differs by the mutability of the references.
+pub fn native_borrow_box(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 3);
+    debug_assert!(args.len() == 2);
+
+    let key = args.pop_back().unwrap();
+    let handle = get_table_handle(pop_arg!(args, StructRef))?;
+
+    let key_bytes = match serialize_key(context, &ty_args[0], key)? {
+        Some(key_bytes) => key_bytes,
+        None => return serialization_failure(context),
+    };
+    let cost = checked_native_gas(
+        context.cost_table(),
+        NativeCostIndex::TABLE_BORROW,
+        key_bytes.len(),
+    )?;
+
+    let gv = context.load_table_item(handle, key_bytes, &ty_args[2])?;
+    map_missing_item(cost, gv.borrow_global())
+}
+
+/// Rust implementation of Move's
+/// `native fun contains_box<K: copy + drop, V: store, B>(table: &Table<K, V>, key: K): bool`
+pub fn native_contains_box(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 3);
+    debug_assert!(args.len() == 2);
+
+    let key = args.pop_back().unwrap();
+    let handle = get_table_handle(pop_arg!(args, StructRef))?;
+
+    let key_bytes = match serialize_key(context, &ty_args[0], key)? {
+        Some(key_bytes) => key_bytes,
+        None => return serialization_failure(context),
+    };
+    let cost = checked_native_gas(
+        context.cost_table(),
+        NativeCostIndex::TABLE_CONTAINS,
+        key_bytes.len(),
+    )?;
+
+    let gv = context.load_table_item(handle, key_bytes, &ty_args[2])?;
+    let exists = gv.exists()?;
+
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(exists)]))
+}
+
+/// Rust implementation of Move's
+/// `native fun remove_box<K: copy + drop, V: store, B>(table: &mut Table<K, V>, key: K): Box<V>`
+pub fn native_remove_box(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 3);
+    debug_assert!(args.len() == 2);
+
+    let key = args.pop_back().unwrap();
+    let handle = get_table_handle(pop_arg!(args, StructRef))?;
+
+    let key_bytes = match serialize_key(context, &ty_args[0], key)? {
+        Some(key_bytes) => key_bytes,
+        None => return serialization_failure(context),
+    };
+    let cost = checked_native_gas(
+        context.cost_table(),
+        NativeCostIndex::TABLE_REMOVE,
+        key_bytes.len(),
+    )?;
+
+    let gv = context.load_table_item(handle, key_bytes, &ty_args[2])?;
+    map_missing_item(cost, gv.move_from())
+}
+
+fn get_table_handle(table: StructRef) -> PartialVMResult<TableHandle> {
+    let handle = table
+        .borrow_field(HANDLE_FIELD_INDEX)?
+        .value_as::<Reference>()?
+        .read_ref()?
+        .value_as::<AccountAddress>()?;
+    Ok(TableHandle(handle))
+}
+
+/// Serialize `key` of type `key_ty`, returning None if the key can't be serialized.
+fn serialize_key(
+    context: &NativeContext,
+    key_ty: &Type,
+    key: Value,
+) -> PartialVMResult<Option<Vec<u8>>> {
+    Ok(context
+        .type_to_type_layout(key_ty)?
+        .and_then(|layout| key.simple_serialize(&layout)))
+}
+
+fn serialization_failure(context: &NativeContext) -> PartialVMResult<NativeResult> {
+    let cost = checked_native_gas(context.cost_table(), NativeCostIndex::TABLE_BORROW, 1)?;
+    Ok(NativeResult::err(cost, NFE_BCS_SERIALIZATION_FAILURE))
+}
+
+/// Abort with `NOT_FOUND` if the item accessed by `res` is not in the table.
+fn map_missing_item(
+    cost: InternalGasUnits<GasCarrier>,
+    res: PartialVMResult<Value>,
+) -> PartialVMResult<NativeResult> {
+    match res {
+        Ok(val) => Ok(NativeResult::ok(cost, smallvec![val])),
+        Err(err) if err.major_status() == StatusCode::MISSING_DATA => {
+            Ok(NativeResult::err(cost, NOT_FOUND))
+        }
+        Err(err) => Err(err),
+    }
+}
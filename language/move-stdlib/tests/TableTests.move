@@ -0,0 +1,95 @@
+#[test_only]
+module Std::TableTests {
+    use Std::Table::{Self, Table};
+
+    struct TableHolder has key {
+        t: Table<u64, vector<u8>>
+    }
+
+    #[test]
+    fun add_borrow_remove() {
+        let t = Table::new<u64, u64>();
+        assert!(Table::empty(&t), 0);
+        Table::add(&mut t, 1, 10);
+        Table::add(&mut t, 2, 20);
+        assert!(Table::length(&t) == 2, 1);
+        assert!(Table::contains(&t, 1), 2);
+        assert!(!Table::contains(&t, 3), 3);
+        assert!(*Table::borrow(&t, 2) == 20, 4);
+
+        *Table::borrow_mut(&mut t, 1) = 11;
+        assert!(*Table::borrow(&t, 1) == 11, 5);
+
+        assert!(Table::remove(&mut t, 1) == 11, 6);
+        assert!(!Table::contains(&t, 1), 7);
+        assert!(Table::remove(&mut t, 2) == 20, 8);
+        Table::destroy_empty(t);
+    }
+
+    #[test]
+    fun remove_then_add_again() {
+        let t = Table::new<u64, u64>();
+        Table::add(&mut t, 1, 10);
+        Table::remove(&mut t, 1);
+        Table::add(&mut t, 1, 12);
+        assert!(*Table::borrow(&t, 1) == 12, 0);
+        Table::remove(&mut t, 1);
+        Table::destroy_empty(t);
+    }
+
+    #[test]
+    fun tables_are_distinct() {
+        let t1 = Table::new<u64, u64>();
+        let t2 = Table::new<u64, u64>();
+        Table::add(&mut t1, 1, 10);
+        assert!(!Table::contains(&t2, 1), 0);
+        Table::remove(&mut t1, 1);
+        Table::destroy_empty(t1);
+        Table::destroy_empty(t2);
+    }
+
+    #[test(s = @0x42)]
+    fun table_in_resource(s: signer) acquires TableHolder {
+        let t = Table::new();
+        Table::add(&mut t, 1, b"one");
+        move_to(&s, TableHolder { t });
+
+        let holder = borrow_global_mut<TableHolder>(@0x42);
+        Table::add(&mut holder.t, 2, b"two");
+        assert!(Table::borrow(&holder.t, 1) == &b"one", 0);
+        assert!(Table::length(&holder.t) == 2, 1);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 6)]
+    fun add_existing_key() {
+        let t = Table::new<u64, u64>();
+        Table::add(&mut t, 1, 10);
+        Table::add(&mut t, 1, 11);
+        Table::destroy_empty(t);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 261)]
+    fun borrow_missing_key() {
+        let t = Table::new<u64, u64>();
+        Table::borrow(&t, 1);
+        Table::destroy_empty(t);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 261)]
+    fun remove_missing_key() {
+        let t = Table::new<u64, u64>();
+        Table::remove(&mut t, 1);
+        Table::destroy_empty(t);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 513)]
+    fun destroy_non_empty() {
+        let t = Table::new<u64, u64>();
+        Table::add(&mut t, 1, 10);
+        Table::destroy_empty(t);
+    }
+}
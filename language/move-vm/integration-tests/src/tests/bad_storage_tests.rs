@@ -7,8 +7,8 @@ use move_core_types::{
     account_address::AccountAddress,
     effects::ChangeSet,
     identifier::Identifier,
-    language_storage::{ModuleId, StructTag},
-    resolver::{ModuleResolver, ResourceResolver},
+    language_storage::{ModuleId, StructTag, TableHandle},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
     value::{serialize_values, MoveValue},
     vm_status::{StatusCode, StatusType},
 };
@@ -476,6 +476,18 @@ impl ResourceResolver for BogusStorage {
     }
 }
 
+impl TableResolver for BogusStorage {
+    type Error = VMError;
+
+    fn get_table_item(
+        &self,
+        _handle: &TableHandle,
+        _key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Err(PartialVMError::new(self.bad_status_code).finish(Location::Undefined))
+    }
+}
+
 const LIST_OF_ERROR_CODES: &[StatusCode] = &[
     StatusCode::UNKNOWN_VALIDATION_STATUS,
     StatusCode::INVALID_SIGNATURE,
@@ -0,0 +1,99 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::{serialize_values, MoveValue},
+};
+use move_vm_runtime::{
+    coverage::{finish_coverage_collection, start_coverage_collection},
+    move_vm::MoveVM,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas_schedule::GasStatus;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+fn setup() -> (InMemoryStorage, ModuleId) {
+    let code = r#"
+        module {{ADDR}}::M {
+            public fun clamp(x: u64): u64 {
+                if (x > 10) 10 else x
+            }
+
+            public fun never_called() {}
+        }
+    "#;
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR.to_string()));
+
+    let mut units = compile_units(&code).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let mut storage = InMemoryStorage::new();
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+    (storage, module_id)
+}
+
+fn call_clamp(storage: &InMemoryStorage, module_id: &ModuleId, x: u64) {
+    let vm = MoveVM::new(vec![]).unwrap();
+    let mut sess = vm.new_session(storage);
+    let mut gas_status = GasStatus::new_unmetered();
+    sess.execute_function(
+        module_id,
+        &Identifier::new("clamp").unwrap(),
+        vec![],
+        serialize_values(&vec![MoveValue::U64(x)]),
+        &mut gas_status,
+    )
+    .unwrap();
+}
+
+#[test]
+fn counts_instructions_and_branches() {
+    let (storage, module_id) = setup();
+
+    start_coverage_collection();
+    call_clamp(&storage, &module_id, 5);
+    call_clamp(&storage, &module_id, 20);
+    call_clamp(&storage, &module_id, 30);
+    let counts = finish_coverage_collection().unwrap();
+
+    let functions = &counts.modules[&module_id];
+    assert_eq!(
+        functions
+            .keys()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["clamp"]
+    );
+    let clamp = &functions[&Identifier::new("clamp").unwrap()];
+    assert_eq!(clamp.instructions[&0], 3);
+    // The only conditional branch goes one way for 5 and the other way for 20 and 30
+    assert_eq!(clamp.branches_taken.len(), 1);
+    let taken = *clamp.branches_taken.values().next().unwrap();
+    assert!(taken == 1 || taken == 2);
+
+    assert!(finish_coverage_collection().is_none());
+}
+
+#[test]
+fn collects_only_on_collecting_thread() {
+    let (storage, module_id) = setup();
+
+    start_coverage_collection();
+    call_clamp(&storage, &module_id, 5);
+    let other_module_id = module_id.clone();
+    std::thread::spawn(move || call_clamp(&storage, &other_module_id, 20))
+        .join()
+        .unwrap();
+    let counts = finish_coverage_collection().unwrap();
+
+    let clamp = &counts.modules[&module_id][&Identifier::new("clamp").unwrap()];
+    assert_eq!(clamp.instructions[&0], 1);
+}
@@ -0,0 +1,156 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_binary_format::file_format::Bytecode;
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    value::{serialize_values, MoveValue},
+};
+use move_vm_runtime::{
+    debugger::{install_debugger, DebugState, Debugger, Mutability, DEBUGGER_SUPPORTED},
+    move_vm::MoveVM,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas_schedule::GasStatus;
+use std::sync::{Arc, Mutex};
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([43; AccountAddress::LENGTH]);
+
+/// What the debugger saw at one instruction.
+#[derive(Debug)]
+struct Step {
+    function: String,
+    pc: u16,
+    call_depth: usize,
+    callers: Vec<String>,
+    instruction: Bytecode,
+    /// The type and value of each local holding a value.
+    locals: Vec<(TypeTag, Option<Mutability>, Vec<u8>)>,
+}
+
+/// Records the instructions of the test module. The debugger is shared by all the VMs of the
+/// process, so instructions of code run by other tests are ignored.
+struct Recorder(Arc<Mutex<Vec<Step>>>);
+
+impl Debugger for Recorder {
+    fn on_instruction(&mut self, state: &DebugState) {
+        let frame = state.frame();
+        if frame.module_id.as_ref().map(|id| *id.address()) != Some(TEST_ADDR) {
+            return;
+        }
+        self.0.lock().unwrap().push(Step {
+            function: frame.function_name,
+            pc: frame.pc,
+            call_depth: state.call_depth(),
+            callers: state
+                .call_stack()
+                .into_iter()
+                .map(|frame| frame.function_name)
+                .collect(),
+            instruction: state.instruction().clone(),
+            locals: state
+                .locals()
+                .unwrap()
+                .into_iter()
+                .filter_map(|local| Some((local.type_tag, local.reference, local.value?)))
+                .collect(),
+        });
+    }
+}
+
+fn setup() -> (InMemoryStorage, ModuleId) {
+    let code = r#"
+        module {{ADDR}}::M {
+            public fun run(x: u64): u64 {
+                double(&x) + 1
+            }
+
+            fun double(x: &u64): u64 {
+                let y = *x * 2;
+                y
+            }
+        }
+    "#;
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR.to_string()));
+
+    let mut units = compile_units(&code).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let mut storage = InMemoryStorage::new();
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+    (storage, module_id)
+}
+
+fn call_run(storage: &InMemoryStorage, module_id: &ModuleId, x: u64) {
+    let vm = MoveVM::new(vec![]).unwrap();
+    let mut sess = vm.new_session(storage);
+    let mut gas_status = GasStatus::new_unmetered();
+    sess.execute_function(
+        module_id,
+        &Identifier::new("run").unwrap(),
+        vec![],
+        serialize_values(&vec![MoveValue::U64(x)]),
+        &mut gas_status,
+    )
+    .unwrap();
+}
+
+fn u64_bytes(x: u64) -> Vec<u8> {
+    MoveValue::U64(x).simple_serialize().unwrap()
+}
+
+#[test]
+fn debugger_sees_frames_and_locals() {
+    if !DEBUGGER_SUPPORTED {
+        return;
+    }
+    let (storage, module_id) = setup();
+    let steps = Arc::new(Mutex::new(vec![]));
+
+    let guard = install_debugger(Box::new(Recorder(steps.clone())));
+    call_run(&storage, &module_id, 5);
+    drop(guard);
+    // Nothing is recorded once the debugger is uninstalled
+    let recorded = steps.lock().unwrap().len();
+    call_run(&storage, &module_id, 5);
+    assert_eq!(steps.lock().unwrap().len(), recorded);
+
+    let steps = steps.lock().unwrap();
+    let first = &steps[0];
+    assert_eq!((first.function.as_str(), first.pc), ("run", 0));
+    assert_eq!(first.call_depth, 0);
+    assert!(first.callers.is_empty());
+    assert_eq!(first.locals, vec![(TypeTag::U64, None, u64_bytes(5))]);
+
+    // Every instruction of `double` runs with `run` as its only caller
+    let double: Vec<_> = steps
+        .iter()
+        .filter(|step| step.function == "double")
+        .collect();
+    assert!(!double.is_empty());
+    for step in &double {
+        assert_eq!(step.call_depth, 1);
+        assert_eq!(step.callers, vec!["run".to_string()]);
+    }
+    // The parameter is read through the reference, and `y` is assigned twice its value
+    assert!(double.iter().any(|step| step.locals.contains(&(
+        TypeTag::U64,
+        Some(Mutability::Immutable),
+        u64_bytes(5)
+    ))));
+    assert!(double
+        .iter()
+        .any(|step| step.locals.contains(&(TypeTag::U64, None, u64_bytes(10)))));
+    assert_eq!(double.last().unwrap().instruction, Bytecode::Ret);
+
+    // Execution returns to `run` after the call
+    let last = steps.last().unwrap();
+    assert_eq!((last.function.as_str(), last.call_depth), ("run", 0));
+    assert_eq!(last.instruction, Bytecode::Ret);
+}
@@ -3,6 +3,8 @@
 
 mod bad_entry_point_tests;
 mod bad_storage_tests;
+mod coverage_tests;
+mod debugger_tests;
 mod function_arg_tests;
 mod loader_tests;
 mod mutated_accounts_tests;
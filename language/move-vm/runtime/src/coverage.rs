@@ -0,0 +1,163 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collection of the code coverage of Move execution.
+//!
+//! While a thread collects coverage, between [`start_coverage_collection`] and
+//! [`finish_coverage_collection`], the interpreter counts how many times each instruction of a
+//! module function was executed on that thread, and how many times each conditional branch jumped
+//! to its target. Scripts are not counted.
+//!
+//! Unlike the coverage computed from `MOVE_VM_TRACE` traces, collection works in release builds
+//! and does not go through a file, so executions on many threads can be collected at once and
+//! merged afterwards.
+
+use crate::{loader::Function, thread_hooks::ThreadHook};
+use move_binary_format::file_format::CodeOffset;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
+
+/// Whether the interpreter reports to the coverage collector. Without the `coverage` feature,
+/// collection can be started but the resulting counts are empty.
+pub const COVERAGE_SUPPORTED: bool = cfg!(feature = "coverage");
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<Collector>> = RefCell::new(None);
+}
+
+static COLLECTING: ThreadHook<Collector> = ThreadHook::new(COVERAGE_SUPPORTED, &COLLECTOR);
+
+/// The executions of the code of a function, by code offset. Only offsets that were executed
+/// at least once are present.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FunctionCounts {
+    /// Number of times the instruction at each offset was executed
+    pub instructions: BTreeMap<CodeOffset, u64>,
+    /// Number of times the `BrTrue` or `BrFalse` instruction at each offset jumped to its target,
+    /// rather than falling through to the next instruction
+    pub branches_taken: BTreeMap<CodeOffset, u64>,
+}
+
+impl FunctionCounts {
+    fn merge(&mut self, other: FunctionCounts) {
+        for (offset, count) in other.instructions {
+            *self.instructions.entry(offset).or_default() += count;
+        }
+        for (offset, count) in other.branches_taken {
+            *self.branches_taken.entry(offset).or_default() += count;
+        }
+    }
+}
+
+/// The executions of the functions of every module run while collecting coverage.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionCounts {
+    pub modules: BTreeMap<ModuleId, BTreeMap<Identifier, FunctionCounts>>,
+}
+
+impl ExecutionCounts {
+    /// Add the executions counted in `other` to these counts.
+    pub fn merge(&mut self, other: ExecutionCounts) {
+        for (module_id, functions) in other.modules {
+            let module = self.modules.entry(module_id).or_default();
+            for (function_name, counts) in functions {
+                module.entry(function_name).or_default().merge(counts);
+            }
+        }
+    }
+}
+
+/// Start collecting the coverage of the executions on the current thread, discarding the
+/// executions counted since collection was last started on it.
+pub fn start_coverage_collection() {
+    COLLECTING.start(Collector::default())
+}
+
+/// Stop collecting coverage on the current thread, and return the executions counted, if it was
+/// collecting.
+pub fn finish_coverage_collection() -> Option<ExecutionCounts> {
+    COLLECTING.finish().map(Collector::into_counts)
+}
+
+fn with_function_counts(function: &Function, f: impl FnOnce(&mut CodeCounts)) {
+    COLLECTING.report(|collector| {
+        if let Some(module_id) = function.module_id() {
+            f(collector.function_counts(module_id, function))
+        }
+    })
+}
+
+/// Called before the interpreter executes the instruction at `pc` in `function`.
+pub(crate) fn on_instruction(function: &Function, pc: CodeOffset) {
+    with_function_counts(function, |counts| counts.instructions[pc as usize] += 1)
+}
+
+/// Called when the conditional branch at `pc` in `function` jumps to its target.
+pub(crate) fn on_branch_taken(function: &Function, pc: CodeOffset) {
+    with_function_counts(function, |counts| counts.branches_taken[pc as usize] += 1)
+}
+
+/// Counts for every offset of the code of a function, so that counting an execution does not
+/// allocate.
+struct CodeCounts {
+    instructions: Vec<u64>,
+    branches_taken: Vec<u64>,
+}
+
+#[derive(Default)]
+struct Collector {
+    modules: HashMap<ModuleId, HashMap<String, CodeCounts>>,
+}
+
+impl Collector {
+    fn function_counts(&mut self, module_id: &ModuleId, function: &Function) -> &mut CodeCounts {
+        if !self.modules.contains_key(module_id) {
+            self.modules.insert(module_id.clone(), HashMap::new());
+        }
+        let functions = self.modules.get_mut(module_id).unwrap();
+        if !functions.contains_key(function.name()) {
+            let code_len = function.code().len();
+            functions.insert(
+                function.name().to_string(),
+                CodeCounts {
+                    instructions: vec![0; code_len],
+                    branches_taken: vec![0; code_len],
+                },
+            );
+        }
+        functions.get_mut(function.name()).unwrap()
+    }
+
+    fn into_counts(self) -> ExecutionCounts {
+        let non_zero = |counts: Vec<u64>| {
+            counts
+                .into_iter()
+                .enumerate()
+                .filter(|(_, count)| *count > 0)
+                .map(|(offset, count)| (offset as CodeOffset, count))
+                .collect()
+        };
+        let modules = self
+            .modules
+            .into_iter()
+            .map(|(module_id, functions)| {
+                let functions = functions
+                    .into_iter()
+                    .map(|(name, counts)| {
+                        let name = Identifier::new(name).expect("function names are identifiers");
+                        let counts = FunctionCounts {
+                            instructions: non_zero(counts.instructions),
+                            branches_taken: non_zero(counts.branches_taken),
+                        };
+                        (name, counts)
+                    })
+                    .collect();
+                (module_id, functions)
+            })
+            .collect();
+        ExecutionCounts { modules }
+    }
+}
@@ -8,7 +8,7 @@ use move_core_types::{
     account_address::AccountAddress,
     effects::{AccountChangeSet, ChangeSet, Event},
     identifier::Identifier,
-    language_storage::{ModuleId, TypeTag},
+    language_storage::{ModuleId, TableHandle, TypeTag},
     resolver::MoveResolver,
     value::MoveTypeLayout,
     vm_status::StatusCode,
@@ -16,10 +16,14 @@ use move_core_types::{
 use move_vm_types::{
     data_store::DataStore,
     loaded_data::runtime_types::Type,
-    values::{GlobalValue, GlobalValueEffect, Value},
+    values::{GlobalValue, GlobalValueEffect, Reference, Value},
 };
+use sha3::{Digest, Sha3_256};
 use std::collections::btree_map::BTreeMap;
 
+/// Salt of the hash from which table handles are derived.
+const TABLE_HANDLE_SALT: &[u8] = b"MOVE::TableHandle";
+
 pub struct AccountDataCache {
     data_map: BTreeMap<Type, (MoveTypeLayout, GlobalValue)>,
     module_map: BTreeMap<Identifier, Vec<u8>>,
@@ -50,7 +54,10 @@ impl AccountDataCache {
 pub(crate) struct TransactionDataCache<'r, 'l, S> {
     remote: &'r S,
     loader: &'l Loader,
+    session_id: Vec<u8>,
     account_map: BTreeMap<AccountAddress, AccountDataCache>,
+    num_created_tables: u64,
+    table_map: BTreeMap<TableHandle, BTreeMap<Vec<u8>, (MoveTypeLayout, GlobalValue)>>,
     event_data: Vec<(Vec<u8>, u64, Type, MoveTypeLayout, Value)>,
 }
 
@@ -58,10 +65,23 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
     /// Create a `TransactionDataCache` with a `RemoteCache` that provides access to data
     /// not updated in the transaction.
     pub(crate) fn new(remote: &'r S, loader: &'l Loader) -> Self {
+        Self::new_with_session_id(remote, loader, vec![])
+    }
+
+    /// Create a `TransactionDataCache` for the session identified by `session_id`, from which
+    /// the handles of the tables created in the session are derived.
+    pub(crate) fn new_with_session_id(
+        remote: &'r S,
+        loader: &'l Loader,
+        session_id: Vec<u8>,
+    ) -> Self {
         TransactionDataCache {
             remote,
             loader,
+            session_id,
             account_map: BTreeMap::new(),
+            num_created_tables: 0,
+            table_map: BTreeMap::new(),
             event_data: vec![],
         }
     }
@@ -107,6 +127,21 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
             );
         }
 
+        for (handle, items) in self.table_map {
+            for (key, (layout, gv)) in items {
+                match gv.into_effect()? {
+                    GlobalValueEffect::None => (),
+                    GlobalValueEffect::Deleted => change_set.remove_table_item(handle, key),
+                    GlobalValueEffect::Changed(val) => {
+                        let blob = val
+                            .simple_serialize(&layout)
+                            .ok_or_else(|| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))?;
+                        change_set.publish_or_overwrite_table_item(handle, key, blob);
+                    }
+                }
+            }
+        }
+
         let mut events = vec![];
         for (guid, seq_num, ty, ty_layout, val) in self.event_data {
             let ty_tag = self.loader.type_to_type_tag(&ty)?;
@@ -127,6 +162,11 @@ impl<'r, 'l, S: MoveResolver> TransactionDataCache<'r, 'l, S> {
                 total_mutated_accounts += 1;
             }
         }
+        // Table items are stored apart from the accounts holding the tables, so every mutated
+        // item is charged like a mutated account.
+        for items in self.table_map.values() {
+            total_mutated_accounts += items.values().filter(|(_, v)| v.is_mutated()).count() as u64;
+        }
         total_mutated_accounts
     }
 
@@ -254,6 +294,89 @@ impl<'r, 'l, S: MoveResolver> DataStore for TransactionDataCache<'r, 'l, S> {
             .is_some())
     }
 
+    fn debug_resources(&self) -> PartialVMResult<Vec<(AccountAddress, TypeTag, Vec<u8>)>> {
+        let mut resources = vec![];
+        for (addr, account_cache) in &self.account_map {
+            for (ty, (layout, gv)) in &account_cache.data_map {
+                if !gv.exists()? {
+                    continue;
+                }
+                let value = gv.borrow_global()?.value_as::<Reference>()?.read_ref()?;
+                let blob = value
+                    .simple_serialize(layout)
+                    .ok_or_else(|| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))?;
+                resources.push((*addr, self.loader.type_to_type_tag(ty)?, blob));
+            }
+        }
+        Ok(resources)
+    }
+
+    fn create_table_handle(&mut self) -> PartialVMResult<TableHandle> {
+        // Sessions without an id can't tell their tables apart from those of other sessions.
+        if self.session_id.is_empty() {
+            return Err(
+                PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR).with_message(
+                    "tables can only be created in a session with an id".to_string(),
+                ),
+            );
+        }
+        let mut sha3_256 = Sha3_256::new();
+        sha3_256.update(TABLE_HANDLE_SALT);
+        sha3_256.update(&self.session_id);
+        sha3_256.update(&self.num_created_tables.to_le_bytes());
+        let hash = sha3_256.finalize();
+        self.num_created_tables += 1;
+
+        let addr = AccountAddress::from_bytes(&hash[hash.len() - AccountAddress::LENGTH..])
+            .map_err(|_| PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR))?;
+        Ok(TableHandle(addr))
+    }
+
+    fn load_table_item(
+        &mut self,
+        handle: TableHandle,
+        key: Vec<u8>,
+        ty: &Type,
+    ) -> PartialVMResult<&mut GlobalValue> {
+        let items = self.table_map.entry(handle).or_insert_with(BTreeMap::new);
+
+        if !items.contains_key(&key) {
+            let ty_layout = self.loader.type_to_type_layout(ty)?;
+
+            let gv = match self.remote.get_table_item(&handle, &key) {
+                Ok(Some(blob)) => {
+                    let val = match Value::simple_deserialize(&blob, &ty_layout) {
+                        Some(val) => val,
+                        None => {
+                            let msg = format!("Failed to deserialize item of table {}!", handle);
+                            return Err(PartialVMError::new(
+                                StatusCode::FAILED_TO_DESERIALIZE_RESOURCE,
+                            )
+                            .with_message(msg));
+                        }
+                    };
+
+                    GlobalValue::cached(val)?
+                }
+                Ok(None) => GlobalValue::none(),
+                Err(err) => {
+                    let msg = format!("Unexpected storage error: {:?}", err);
+                    return Err(
+                        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                            .with_message(msg),
+                    );
+                }
+            };
+
+            items.insert(key.clone(), (ty_layout, gv));
+        }
+
+        Ok(items
+            .get_mut(&key)
+            .map(|(_ty_layout, gv)| gv)
+            .expect("global value must exist"))
+    }
+
     fn emit_event(
         &mut self,
         guid: Vec<u8>,
@@ -0,0 +1,254 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hooks for debuggers that drive the interpreter one instruction at a time.
+//!
+//! A [`Debugger`] installed with [`install_debugger`] is called before every instruction the
+//! interpreter executes, until the [`DebuggerGuard`] returned on installation is dropped, with a [`DebugState`] through which it can inspect the frame being
+//! executed, the frames of its callers and the resources loaded so far. Execution resumes once
+//! the debugger returns, so a debugger can pause execution simply by not returning, e.g., while
+//! it waits for user input.
+//!
+//! Like the `MOVE_VM_STEP` stepper, the hook is only compiled into debug builds of the VM; see
+//! [`DEBUGGER_SUPPORTED`].
+
+use crate::{
+    interpreter::Interpreter,
+    loader::{Function, Loader},
+};
+use move_binary_format::{
+    errors::{PartialVMError, PartialVMResult},
+    file_format::{Bytecode, FunctionDefinitionIndex},
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, TypeTag},
+    vm_status::StatusCode,
+};
+use move_vm_types::{
+    data_store::DataStore,
+    loaded_data::runtime_types::Type,
+    values::{Locals, Reference, Value},
+};
+use once_cell::sync::Lazy;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+/// Whether the interpreter calls the installed debugger. The hook is compiled out of release
+/// builds, where installing a debugger has no effect.
+pub const DEBUGGER_SUPPORTED: bool = cfg!(debug_assertions);
+
+static DEBUGGER: Lazy<Mutex<Option<Box<dyn Debugger>>>> = Lazy::new(|| Mutex::new(None));
+
+// Checked before every instruction, so that the interpreter does not have to take the lock
+// on `DEBUGGER` when no debugger is installed.
+static DEBUGGER_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// A debugger driving the interpreter.
+pub trait Debugger: Send {
+    /// Called before the interpreter executes the instruction at `state.frame().pc`.
+    fn on_instruction(&mut self, state: &DebugState);
+}
+
+/// Install `debugger`, replacing any debugger installed before. The debugger is shared by all
+/// the VMs of the process, so clients debugging execution should only run one VM at a time.
+///
+/// The debugger stays installed until the returned guard is dropped.
+pub fn install_debugger(debugger: Box<dyn Debugger>) -> DebuggerGuard {
+    *DEBUGGER.lock().unwrap_or_else(|err| err.into_inner()) = Some(debugger);
+    DEBUGGER_INSTALLED.store(true, Ordering::SeqCst);
+    DebuggerGuard(())
+}
+
+/// Uninstall the installed debugger, if any, and return it.
+pub fn uninstall_debugger() -> Option<Box<dyn Debugger>> {
+    DEBUGGER_INSTALLED.store(false, Ordering::SeqCst);
+    // The lock is poisoned if a debugger panicked, which must not keep it from being uninstalled
+    DEBUGGER
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .take()
+}
+
+/// Uninstalls the debugger when dropped, including when unwinding from a panic during the
+/// execution being debugged.
+#[must_use = "the debugger is uninstalled as soon as the guard is dropped"]
+pub struct DebuggerGuard(());
+
+impl Drop for DebuggerGuard {
+    fn drop(&mut self) {
+        uninstall_debugger();
+    }
+}
+
+#[cfg(debug_assertions)]
+pub(crate) fn is_installed() -> bool {
+    DEBUGGER_INSTALLED.load(Ordering::Relaxed)
+}
+
+#[cfg(debug_assertions)]
+pub(crate) fn on_instruction(state: &DebugState) {
+    if let Some(debugger) = &mut *DEBUGGER.lock().unwrap() {
+        debugger.on_instruction(state)
+    }
+}
+
+/// A function on the call stack and the instruction it is at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebugFrame {
+    /// The module declaring the function, or `None` if the function is a script.
+    pub module_id: Option<ModuleId>,
+    pub function_name: String,
+    pub function_index: FunctionDefinitionIndex,
+    /// The offset of the instruction in the function's code. For the callers of the function
+    /// being executed, this is the offset of the call they are executing.
+    pub pc: u16,
+}
+
+impl DebugFrame {
+    fn new(function: &Function, pc: u16) -> Self {
+        Self {
+            module_id: function.module_id().cloned(),
+            function_name: function.name().to_string(),
+            function_index: function.index(),
+            pc,
+        }
+    }
+}
+
+/// Whether a reference allows mutating the value it refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutability {
+    Immutable,
+    Mutable,
+}
+
+/// A parameter or local of the function being executed.
+#[derive(Clone, Debug)]
+pub struct DebugLocal {
+    /// The type of the local or, if the local is a reference, the type of the value referred to.
+    pub type_tag: TypeTag,
+    /// `None` unless the local is a reference.
+    pub reference: Option<Mutability>,
+    /// The BCS-serialized value of the local, read through the reference if the local is one, or
+    /// `None` if the local holds no value because it has not been assigned yet or has been moved.
+    pub value: Option<Vec<u8>>,
+}
+
+/// The state of the interpreter before executing an instruction.
+pub struct DebugState<'a> {
+    function: &'a Function,
+    ty_args: &'a [Type],
+    locals: &'a Locals,
+    pc: u16,
+    instruction: &'a Bytecode,
+    loader: &'a Loader,
+    interpreter: &'a Interpreter,
+    data_store: &'a dyn DataStore,
+}
+
+impl<'a> DebugState<'a> {
+    #[cfg(debug_assertions)]
+    pub(crate) fn new(
+        function: &'a Function,
+        ty_args: &'a [Type],
+        locals: &'a Locals,
+        pc: u16,
+        instruction: &'a Bytecode,
+        loader: &'a Loader,
+        interpreter: &'a Interpreter,
+        data_store: &'a dyn DataStore,
+    ) -> Self {
+        Self {
+            function,
+            ty_args,
+            locals,
+            pc,
+            instruction,
+            loader,
+            interpreter,
+            data_store,
+        }
+    }
+
+    /// The function being executed and the instruction about to be executed.
+    pub fn frame(&self) -> DebugFrame {
+        DebugFrame::new(self.function, self.pc)
+    }
+
+    pub fn instruction(&self) -> &Bytecode {
+        self.instruction
+    }
+
+    /// The number of callers of the function being executed.
+    pub fn call_depth(&self) -> usize {
+        self.interpreter.call_depth()
+    }
+
+    /// The callers of the function being executed, outermost first.
+    pub fn call_stack(&self) -> Vec<DebugFrame> {
+        self.interpreter
+            .debug_call_stack()
+            .iter()
+            .map(|(function, pc)| DebugFrame::new(function, *pc))
+            .collect()
+    }
+
+    /// The type arguments the function being executed was instantiated with.
+    pub fn type_arguments(&self) -> PartialVMResult<Vec<TypeTag>> {
+        self.ty_args
+            .iter()
+            .map(|ty| self.loader.type_to_type_tag(ty))
+            .collect()
+    }
+
+    /// The parameters and locals of the function being executed, by index.
+    pub fn locals(&self) -> PartialVMResult<Vec<DebugLocal>> {
+        let local_types = self.loader.local_types(self.function, self.ty_args)?;
+        local_types
+            .iter()
+            .enumerate()
+            .map(|(idx, ty)| {
+                let (ty, reference) = match ty {
+                    Type::Reference(ty) => (ty.as_ref(), Some(Mutability::Immutable)),
+                    Type::MutableReference(ty) => (ty.as_ref(), Some(Mutability::Mutable)),
+                    ty => (ty, None),
+                };
+                // The only locals that cannot be copied are those that hold no value
+                let value = match self.locals.copy_loc(idx) {
+                    Ok(value) => Some(self.serialize_local(value, ty, reference.is_some())?),
+                    Err(_) => None,
+                };
+                Ok(DebugLocal {
+                    type_tag: self.loader.type_to_type_tag(ty)?,
+                    reference,
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    fn serialize_local(
+        &self,
+        value: Value,
+        ty: &Type,
+        is_reference: bool,
+    ) -> PartialVMResult<Vec<u8>> {
+        let value = if is_reference {
+            value.value_as::<Reference>()?.read_ref()?
+        } else {
+            value
+        };
+        value
+            .simple_serialize(&self.loader.type_to_type_layout(ty)?)
+            .ok_or_else(|| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
+    }
+
+    /// The resources loaded from, or published to, global storage so far, with their addresses
+    /// and BCS-serialized values.
+    pub fn resources(&self) -> PartialVMResult<Vec<(AccountAddress, TypeTag, Vec<u8>)>> {
+        self.data_store.debug_resources()
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiling of the gas used by Move execution.
+//!
+//! While a thread profiles gas, between [`start_gas_profiling`] and [`finish_gas_profiling`], the
+//! interpreter attributes the gas charged and the instructions executed on that thread to the
+//! call stack they were executed in, including calls to native functions. The resulting
+//! [`GasProfile`] can be exported as folded stacks, the input of `flamegraph.pl` and `inferno`,
+//! or as a [speedscope](https://www.speedscope.app) profile.
+//!
+//! Gas is measured in internal gas units, so that the cost of cheap instructions is not rounded
+//! away. Nothing is attributed to a stack when executing without gas metering.
+
+use crate::{loader::Function, thread_hooks::ThreadHook};
+use move_core_types::gas_schedule::GasAlgebra;
+use move_vm_types::gas_schedule::GasStatus;
+use serde_json::json;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io,
+    path::Path,
+};
+
+/// Whether the interpreter reports to the gas profiler. Without the `gas-profiling` feature,
+/// profiling can be started but the resulting profiles are empty.
+pub const GAS_PROFILING_SUPPORTED: bool = cfg!(feature = "gas-profiling");
+
+thread_local! {
+    static PROFILER: RefCell<Option<Profiler>> = RefCell::new(None);
+}
+
+static PROFILING: ThreadHook<Profiler> = ThreadHook::new(GAS_PROFILING_SUPPORTED, &PROFILER);
+
+/// The cost of executing a call stack, not including the functions it calls.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StackCost {
+    /// Gas charged, in internal gas units
+    pub gas: u64,
+    /// Number of bytecode instructions executed
+    pub instructions: u64,
+}
+
+impl StackCost {
+    fn add(&mut self, other: StackCost) {
+        self.gas += other.gas;
+        self.instructions += other.instructions;
+    }
+}
+
+/// The cost of every call stack executed while profiling. Stacks are listed from the outermost
+/// function to the innermost one.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GasProfile {
+    pub stacks: BTreeMap<Vec<String>, StackCost>,
+}
+
+impl GasProfile {
+    /// Add the costs recorded in `other` to this profile.
+    pub fn merge(&mut self, other: GasProfile) {
+        for (stack, cost) in other.stacks {
+            self.stacks.entry(stack).or_default().add(cost);
+        }
+    }
+
+    pub fn total(&self) -> StackCost {
+        let mut total = StackCost::default();
+        for cost in self.stacks.values() {
+            total.add(*cost);
+        }
+        total
+    }
+
+    /// The gas of every stack in the folded format, one `outer;...;inner gas` line per stack.
+    pub fn to_folded_stacks(&self) -> String {
+        self.stacks
+            .iter()
+            .filter(|(_, cost)| cost.gas > 0)
+            .map(|(stack, cost)| format!("{} {}\n", stack.join(";"), cost.gas))
+            .collect()
+    }
+
+    /// A speedscope profile named `name`, holding one sampled profile of the gas used by every
+    /// stack and one of the instructions it executed.
+    pub fn to_speedscope_json(&self, name: &str) -> String {
+        let mut frames: Vec<&str> = vec![];
+        let mut frame_indices = HashMap::new();
+        let samples: Vec<Vec<usize>> = self
+            .stacks
+            .keys()
+            .map(|stack| {
+                stack
+                    .iter()
+                    .map(|frame| {
+                        *frame_indices.entry(frame.as_str()).or_insert_with(|| {
+                            frames.push(frame.as_str());
+                            frames.len() - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+        let sampled_profile = |unit: &str, weights: Vec<u64>| {
+            json!({
+                "type": "sampled",
+                "name": format!("{} ({})", name, unit),
+                "unit": "none",
+                "startValue": 0,
+                "endValue": weights.iter().sum::<u64>(),
+                "samples": samples,
+                "weights": weights,
+            })
+        };
+        let profile = json!({
+            "$schema": "https://www.speedscope.app/file-format-schema.json",
+            "name": name,
+            "exporter": "move-vm-runtime",
+            "shared": {
+                "frames": frames
+                    .iter()
+                    .map(|frame| json!({ "name": frame }))
+                    .collect::<Vec<_>>(),
+            },
+            "profiles": [
+                sampled_profile("gas", self.stacks.values().map(|cost| cost.gas).collect()),
+                sampled_profile(
+                    "instructions",
+                    self.stacks.values().map(|cost| cost.instructions).collect()
+                ),
+            ],
+        });
+        serde_json::to_string_pretty(&profile).expect("profile is serializable")
+    }
+
+    /// Write the profile to `path`, as a speedscope profile named `name` if `path` has a `.json`
+    /// extension and as folded stacks otherwise.
+    pub fn write_to_file(&self, path: &Path, name: &str) -> io::Result<()> {
+        let contents = if path.extension().map_or(false, |ext| ext == "json") {
+            self.to_speedscope_json(name)
+        } else {
+            self.to_folded_stacks()
+        };
+        std::fs::write(path, contents)
+    }
+}
+
+/// Start profiling the gas used by the executions on the current thread, discarding the costs
+/// recorded since profiling was last started on it.
+pub fn start_gas_profiling() {
+    PROFILING.start(Profiler::new())
+}
+
+/// Stop profiling the current thread, and return what was profiled, if it was profiling.
+pub fn finish_gas_profiling() -> Option<GasProfile> {
+    PROFILING.finish().map(Profiler::into_profile)
+}
+
+/// Whether the current thread is profiling gas.
+pub fn is_profiling() -> bool {
+    PROFILING.is_reporting()
+}
+
+fn gas_left(gas_status: &GasStatus) -> u64 {
+    gas_status.remaining_internal_gas().get()
+}
+
+/// Called when the interpreter starts executing `function` on behalf of a client of the VM.
+pub(crate) fn on_entrypoint(function: &Function, gas_status: &GasStatus) {
+    PROFILING.report(|profiler| {
+        profiler.current = ROOT;
+        profiler.last_gas_left = gas_left(gas_status);
+        profiler.enter(function);
+    })
+}
+
+/// Called when `function` is called, after the cost of the call is charged to the caller.
+pub(crate) fn on_call(function: &Function, gas_status: &GasStatus) {
+    PROFILING.report(|profiler| {
+        profiler.charge(gas_left(gas_status));
+        profiler.enter(function);
+    })
+}
+
+/// Called when the function being executed returns.
+pub(crate) fn on_return(gas_status: &GasStatus) {
+    PROFILING.report(|profiler| {
+        profiler.charge(gas_left(gas_status));
+        profiler.exit();
+    })
+}
+
+/// Called before the interpreter executes an instruction of the function being executed.
+pub(crate) fn on_instruction(gas_status: &GasStatus) {
+    PROFILING.report(|profiler| {
+        profiler.charge(gas_left(gas_status));
+        profiler.nodes[profiler.current].cost.instructions += 1;
+    })
+}
+
+const ROOT: usize = 0;
+
+/// A node of the call tree, standing for the call stack made of the functions on its path
+/// from the root.
+struct Node {
+    function: String,
+    parent: usize,
+    children: HashMap<String, usize>,
+    cost: StackCost,
+}
+
+struct Profiler {
+    nodes: Vec<Node>,
+    current: usize,
+    last_gas_left: u64,
+}
+
+impl Profiler {
+    fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                function: String::new(),
+                parent: ROOT,
+                children: HashMap::new(),
+                cost: StackCost::default(),
+            }],
+            current: ROOT,
+            last_gas_left: 0,
+        }
+    }
+
+    /// Attribute the gas charged since the last call to the current stack.
+    fn charge(&mut self, gas_left: u64) {
+        self.nodes[self.current].cost.gas += self.last_gas_left.saturating_sub(gas_left);
+        self.last_gas_left = gas_left;
+    }
+
+    fn enter(&mut self, function: &Function) {
+        let name = function.pretty_string();
+        let next_index = self.nodes.len();
+        let index = *self.nodes[self.current]
+            .children
+            .entry(name.clone())
+            .or_insert(next_index);
+        if index == next_index {
+            self.nodes.push(Node {
+                function: name,
+                parent: self.current,
+                children: HashMap::new(),
+                cost: StackCost::default(),
+            });
+        }
+        self.current = index;
+    }
+
+    fn exit(&mut self) {
+        self.current = self.nodes[self.current].parent;
+    }
+
+    fn into_profile(self) -> GasProfile {
+        let mut profile = GasProfile::default();
+        for (index, node) in self.nodes.iter().enumerate().skip(1) {
+            if node.cost == StackCost::default() {
+                continue;
+            }
+            let mut stack = vec![];
+            let mut ancestor = index;
+            while ancestor != ROOT {
+                stack.push(self.nodes[ancestor].function.clone());
+                ancestor = self.nodes[ancestor].parent;
+            }
+            stack.reverse();
+            profile.stacks.entry(stack).or_default().add(node.cost);
+        }
+        profile
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    coverage, gas_profiler,
     loader::{Function, Loader, Resolver},
     native_functions::NativeContext,
     trace,
@@ -79,6 +80,7 @@ impl Interpreter {
         // We count the intrinsic cost of the transaction here, since that needs to also cover the
         // setup of the function.
         let mut interp = Self::new();
+        gas_profiler::on_entrypoint(&function, gas_status);
         interp.execute(loader, data_store, gas_status, function, ty_args, args)
     }
 
@@ -138,6 +140,7 @@ impl Interpreter {
                 .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
             match exit_code {
                 ExitCode::Return => {
+                    gas_profiler::on_return(gas_status);
                     if let Some(frame) = self.call_stack.pop() {
                         current_frame = frame;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
@@ -156,8 +159,10 @@ impl Interpreter {
                             AbstractMemorySize::new(func.arg_count() as GasCarrier),
                         )
                         .map_err(|e| set_err_info!(current_frame, e))?;
+                    gas_profiler::on_call(&func, gas_status);
                     if func.is_native() {
                         self.call_native(&resolver, data_store, gas_status, func, vec![])?;
+                        gas_profiler::on_return(gas_status);
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
                     }
@@ -189,8 +194,10 @@ impl Interpreter {
                             AbstractMemorySize::new(func.arg_count() as GasCarrier),
                         )
                         .map_err(|e| set_err_info!(current_frame, e))?;
+                    gas_profiler::on_call(&func, gas_status);
                     if func.is_native() {
                         self.call_native(&resolver, data_store, gas_status, func, ty_args)?;
+                        gas_profiler::on_return(gas_status);
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                         continue;
                     }
@@ -475,6 +482,20 @@ impl Interpreter {
         Ok(())
     }
 
+    pub(crate) fn call_depth(&self) -> usize {
+        self.call_stack.0.len()
+    }
+
+    /// The function and program counter of every frame on the call stack, outermost first. The
+    /// frame currently executing is not on the call stack.
+    pub(crate) fn debug_call_stack(&self) -> Vec<(Arc<Function>, u16)> {
+        self.call_stack
+            .0
+            .iter()
+            .map(|frame| (Arc::clone(&frame.function), frame.pc))
+            .collect()
+    }
+
     #[allow(dead_code)]
     pub(crate) fn debug_print_stack_trace<B: Write>(
         &self,
@@ -688,12 +709,16 @@ impl Frame {
             for instruction in &code[self.pc as usize..] {
                 trace!(
                     &self.function,
+                    &self.ty_args,
                     &self.locals,
                     self.pc,
                     instruction,
                     resolver,
-                    interpreter
+                    interpreter,
+                    data_store
                 );
+                gas_profiler::on_instruction(gas_status);
+                coverage::on_instruction(&self.function, self.pc);
 
                 fail_point!("move_vm::interpreter_loop", |_| {
                     Err(
@@ -715,6 +740,7 @@ impl Frame {
                     Bytecode::BrTrue(offset) => {
                         gas_status.charge_instr(Opcodes::BR_TRUE)?;
                         if interpreter.operand_stack.pop_as::<bool>()? {
+                            coverage::on_branch_taken(&self.function, self.pc);
                             self.pc = *offset;
                             break;
                         }
@@ -722,6 +748,7 @@ impl Frame {
                     Bytecode::BrFalse(offset) => {
                         gas_status.charge_instr(Opcodes::BR_FALSE)?;
                         if !interpreter.operand_stack.pop_as::<bool>()? {
+                            coverage::on_branch_taken(&self.function, self.pc);
                             self.pc = *offset;
                             break;
                         }
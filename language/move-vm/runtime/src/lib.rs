@@ -12,7 +12,10 @@
 #[macro_use]
 extern crate mirai_annotations;
 
+pub mod coverage;
 pub mod data_cache;
+pub mod debugger;
+pub mod gas_profiler;
 mod interpreter;
 mod loader;
 pub mod logging;
@@ -20,6 +23,7 @@ pub mod move_vm;
 pub mod native_functions;
 mod runtime;
 pub mod session;
+mod thread_hooks;
 #[macro_use]
 mod tracing;
 
@@ -2141,6 +2141,34 @@ impl Loader {
     pub(crate) fn type_to_type_layout(&self, ty: &Type) -> PartialVMResult<MoveTypeLayout> {
         self.type_to_type_layout_impl(ty, 1)
     }
+
+    // The types of the parameters and locals of `function` instantiated with `ty_args`.
+    // Only the debugger needs them; the interpreter itself never looks at the type of a local.
+    pub(crate) fn local_types(
+        &self,
+        function: &Function,
+        ty_args: &[Type],
+    ) -> PartialVMResult<Vec<Type>> {
+        let make_types = |view: BinaryIndexedView| {
+            let cache = self.module_cache.read();
+            function
+                .locals
+                .0
+                .iter()
+                .map(|tok| cache.make_type(view, tok)?.subst(ty_args))
+                .collect()
+        };
+        match &function.scope {
+            Scope::Module(module_id) => {
+                let module = self.get_module(module_id);
+                make_types(BinaryIndexedView::Module(module.module()))
+            }
+            Scope::Script(script_hash) => {
+                let script = self.get_script(script_hash);
+                make_types(BinaryIndexedView::Script(&script.script))
+            }
+        }
+    }
 }
 
 // Public APIs for external uses.
@@ -43,6 +43,19 @@ impl MoveVM {
         self.runtime.new_session(remote)
     }
 
+    /// Create a new Session like `new_session`, identified by `session_id`.
+    ///
+    /// The handles of the tables created in a Session are derived from its id, so the id of every
+    /// Session whose effects are committed to the storage must be unique and non-empty. Sessions
+    /// created with `new_session` have an empty id and fail to create tables.
+    pub fn new_session_with_id<'r, S: MoveResolver>(
+        &self,
+        remote: &'r S,
+        session_id: Vec<u8>,
+    ) -> Session<'r, '_, S> {
+        self.runtime.new_session_with_id(remote, session_id)
+    }
+
     /// Load a module into VM's code cache
     pub fn load_module<'r, S: MoveResolver>(
         &self,
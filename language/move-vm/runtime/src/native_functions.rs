@@ -7,12 +7,16 @@ use move_core_types::{
     account_address::AccountAddress,
     gas_schedule::CostTable,
     identifier::Identifier,
+    language_storage::TableHandle,
     value::MoveTypeLayout,
     vm_status::{StatusCode, StatusType},
 };
 use move_vm_types::{
-    data_store::DataStore, gas_schedule::GasStatus, loaded_data::runtime_types::Type,
-    natives::function::NativeResult, values::Value,
+    data_store::DataStore,
+    gas_schedule::GasStatus,
+    loaded_data::runtime_types::Type,
+    natives::function::NativeResult,
+    values::{GlobalValue, Value},
 };
 use std::{
     collections::{HashMap, VecDeque},
@@ -104,6 +108,19 @@ impl<'a> NativeContext<'a> {
         }
     }
 
+    pub fn create_table_handle(&mut self) -> PartialVMResult<TableHandle> {
+        self.data_store.create_table_handle()
+    }
+
+    pub fn load_table_item(
+        &mut self,
+        handle: TableHandle,
+        key: Vec<u8>,
+        ty: &Type,
+    ) -> PartialVMResult<&mut GlobalValue> {
+        self.data_store.load_table_item(handle, key, ty)
+    }
+
     pub fn type_to_type_layout(&self, ty: &Type) -> PartialVMResult<Option<MoveTypeLayout>> {
         match self.resolver.type_to_type_layout(ty) {
             Ok(ty_layout) => Ok(Some(ty_layout)),
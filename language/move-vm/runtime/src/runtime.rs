@@ -59,6 +59,17 @@ impl VMRuntime {
         }
     }
 
+    pub fn new_session_with_id<'r, S: MoveResolver>(
+        &self,
+        remote: &'r S,
+        session_id: Vec<u8>,
+    ) -> Session<'r, '_, S> {
+        Session {
+            runtime: self,
+            data_cache: TransactionDataCache::new_with_session_id(remote, &self.loader, session_id),
+        }
+    }
+
     pub(crate) fn publish_module_bundle(
         &self,
         modules: Vec<Vec<u8>>,
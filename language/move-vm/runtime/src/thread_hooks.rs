@@ -0,0 +1,73 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-thread state reported to by interpreter hooks, shared by the gas profiler and the coverage
+//! collector.
+//!
+//! A thread starts and finishes reporting to the state of a hook, and only the executions on that
+//! thread are reported to it. The hooks are called before every instruction, so they first check
+//! a count of the threads reporting to them, and only look up the state of the current thread
+//! when some thread is reporting.
+//!
+//! Each hook is only compiled in with its own feature, so that VMs that never report to it, like
+//! the one run by validators, do not check whether to report before every instruction. Without
+//! the feature, reporting can be started and finished but nothing is reported.
+
+use std::{
+    cell::RefCell,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread::LocalKey,
+};
+
+pub(crate) struct ThreadHook<T: 'static> {
+    supported: bool,
+    state: &'static LocalKey<RefCell<Option<T>>>,
+    reporting_threads: AtomicUsize,
+}
+
+impl<T> ThreadHook<T> {
+    /// A hook reporting to `state` on each thread, if `supported` by the features the VM is built
+    /// with.
+    pub const fn new(supported: bool, state: &'static LocalKey<RefCell<Option<T>>>) -> Self {
+        Self {
+            supported,
+            state,
+            reporting_threads: AtomicUsize::new(0),
+        }
+    }
+
+    /// Start reporting to `state` on the current thread, replacing the state it was reporting to.
+    pub fn start(&self, state: T) {
+        if self
+            .state
+            .with(|current| current.replace(Some(state)))
+            .is_none()
+        {
+            self.reporting_threads.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Stop reporting on the current thread, and return the state it reported to, if any.
+    pub fn finish(&self) -> Option<T> {
+        let state = self.state.with(|current| current.borrow_mut().take())?;
+        self.reporting_threads.fetch_sub(1, Ordering::SeqCst);
+        Some(state)
+    }
+
+    /// Whether the current thread is reporting.
+    pub fn is_reporting(&self) -> bool {
+        self.state.with(|current| current.borrow().is_some())
+    }
+
+    /// Report to the state of the current thread, if it is reporting.
+    pub fn report(&self, f: impl FnOnce(&mut T)) {
+        if !self.supported || self.reporting_threads.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        self.state.with(|current| {
+            if let Some(state) = current.borrow_mut().as_mut() {
+                f(state)
+            }
+        })
+    }
+}
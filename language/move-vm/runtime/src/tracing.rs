@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 #[cfg(debug_assertions)]
-use crate::debug::DebugContext;
+use crate::{
+    debug::DebugContext,
+    debugger::{self, DebugState},
+};
 
 #[cfg(debug_assertions)]
 use ::{
     move_binary_format::file_format::Bytecode,
-    move_vm_types::values::Locals,
+    move_vm_types::{data_store::DataStore, loaded_data::runtime_types::Type, values::Locals},
     once_cell::sync::Lazy,
     std::{
         env,
@@ -62,11 +65,13 @@ static DEBUG_CONTEXT: Lazy<Mutex<DebugContext>> = Lazy::new(|| Mutex::new(DebugC
 #[cfg(debug_assertions)]
 pub(crate) fn trace(
     function_desc: &Function,
+    ty_args: &[Type],
     locals: &Locals,
     pc: u16,
     instr: &Bytecode,
     loader: &Loader,
     interp: &Interpreter,
+    data_store: &dyn DataStore,
 ) {
     if *TRACING_ENABLED {
         let f = &mut *LOGGING_FILE.lock().unwrap();
@@ -87,20 +92,34 @@ pub(crate) fn trace(
             .unwrap()
             .debug_loop(function_desc, locals, pc, instr, loader, interp);
     }
+    if debugger::is_installed() {
+        debugger::on_instruction(&DebugState::new(
+            function_desc,
+            ty_args,
+            locals,
+            pc,
+            instr,
+            loader,
+            interp,
+            data_store,
+        ));
+    }
 }
 
 #[macro_export]
 macro_rules! trace {
-    ($function_desc:expr, $locals:expr, $pc:expr, $instr:tt, $resolver:expr, $interp:expr) => {
+    ($function_desc:expr, $ty_args:expr, $locals:expr, $pc:expr, $instr:tt, $resolver:expr, $interp:expr, $data_store:expr) => {
         // Only include this code in debug releases
         #[cfg(debug_assertions)]
         crate::tracing::trace(
             &$function_desc,
+            $ty_args,
             $locals,
             $pc,
             &$instr,
             $resolver.loader(),
             $interp,
+            &*$data_store,
         )
     };
 }
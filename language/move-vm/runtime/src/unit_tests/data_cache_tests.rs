@@ -0,0 +1,77 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{data_cache::TransactionDataCache, loader::Loader, native_functions::NativeFunctions};
+use move_binary_format::errors::VMError;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, StructTag, TableHandle},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
+    vm_status::StatusCode,
+};
+use move_vm_types::data_store::DataStore;
+
+struct EmptyStore;
+
+impl ModuleResolver for EmptyStore {
+    type Error = VMError;
+
+    fn get_module(&self, _module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl ResourceResolver for EmptyStore {
+    type Error = VMError;
+
+    fn get_resource(
+        &self,
+        _address: &AccountAddress,
+        _tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl TableResolver for EmptyStore {
+    type Error = VMError;
+
+    fn get_table_item(
+        &self,
+        _handle: &TableHandle,
+        _key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+fn table_handles(session_id: Vec<u8>, count: usize) -> Vec<TableHandle> {
+    let loader = Loader::new(NativeFunctions::new(vec![]).unwrap());
+    let mut data_cache =
+        TransactionDataCache::new_with_session_id(&EmptyStore, &loader, session_id);
+    (0..count)
+        .map(|_| data_cache.create_table_handle().unwrap())
+        .collect()
+}
+
+#[test]
+fn table_handles_are_unique() {
+    let handles = table_handles(vec![0], 2);
+    assert_ne!(handles[0], handles[1]);
+    // handles only depend on the session id and the order of creation
+    assert_eq!(handles, table_handles(vec![0], 2));
+    let other_handles = table_handles(vec![1], 2);
+    assert!(!other_handles.contains(&handles[0]));
+    assert!(!other_handles.contains(&handles[1]));
+}
+
+#[test]
+fn sessions_without_id_cannot_create_tables() {
+    let loader = Loader::new(NativeFunctions::new(vec![]).unwrap());
+    let mut data_cache = TransactionDataCache::new(&EmptyStore, &loader);
+    let err = data_cache.create_table_handle().unwrap_err();
+    assert_eq!(
+        err.major_status(),
+        StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR
+    );
+}
@@ -0,0 +1,92 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::gas_profiler::{
+    finish_gas_profiling, is_profiling, start_gas_profiling, GasProfile, StackCost,
+};
+
+fn stack(frames: &[&str]) -> Vec<String> {
+    frames.iter().map(|frame| frame.to_string()).collect()
+}
+
+fn profile(stacks: &[(&[&str], u64, u64)]) -> GasProfile {
+    GasProfile {
+        stacks: stacks
+            .iter()
+            .map(|(frames, gas, instructions)| {
+                (
+                    stack(frames),
+                    StackCost {
+                        gas: *gas,
+                        instructions: *instructions,
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn merge_adds_costs_of_same_stacks() {
+    let mut merged = profile(&[(&["0x1::M::f"], 3, 2), (&["0x1::M::f", "0x1::M::g"], 5, 4)]);
+    merged.merge(profile(&[(&["0x1::M::f"], 1, 1), (&["0x1::M::h"], 2, 0)]));
+
+    assert_eq!(
+        merged,
+        profile(&[
+            (&["0x1::M::f"], 4, 3),
+            (&["0x1::M::f", "0x1::M::g"], 5, 4),
+            (&["0x1::M::h"], 2, 0),
+        ])
+    );
+    assert_eq!(
+        merged.total(),
+        StackCost {
+            gas: 11,
+            instructions: 7
+        }
+    );
+}
+
+#[test]
+fn folded_stacks_skip_stacks_without_gas() {
+    let profile = profile(&[
+        (&["0x1::M::f"], 3, 2),
+        (&["0x1::M::f", "0x1::M::g"], 5, 4),
+        (&["0x1::M::h"], 0, 6),
+    ]);
+    assert_eq!(
+        profile.to_folded_stacks(),
+        "0x1::M::f 3\n0x1::M::f;0x1::M::g 5\n"
+    );
+}
+
+#[test]
+fn speedscope_profile_shares_frames() {
+    let profile = profile(&[(&["0x1::M::f"], 3, 2), (&["0x1::M::f", "0x1::M::g"], 5, 4)]);
+    let json: serde_json::Value =
+        serde_json::from_str(&profile.to_speedscope_json("test")).unwrap();
+
+    assert_eq!(
+        json["shared"]["frames"],
+        serde_json::json!([{ "name": "0x1::M::f" }, { "name": "0x1::M::g" }])
+    );
+    assert_eq!(
+        json["profiles"][0]["samples"],
+        serde_json::json!([[0], [0, 1]])
+    );
+    assert_eq!(json["profiles"][0]["weights"], serde_json::json!([3, 5]));
+    assert_eq!(json["profiles"][0]["endValue"], 8);
+    assert_eq!(json["profiles"][1]["weights"], serde_json::json!([2, 4]));
+}
+
+#[test]
+fn profiling_is_per_thread() {
+    assert!(!is_profiling());
+    start_gas_profiling();
+    assert!(is_profiling());
+    assert!(!std::thread::spawn(is_profiling).join().unwrap());
+    assert_eq!(finish_gas_profiling(), Some(GasProfile::default()));
+    assert!(!is_profiling());
+    assert_eq!(finish_gas_profiling(), None);
+}
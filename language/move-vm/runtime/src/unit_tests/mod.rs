@@ -1,4 +1,6 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod data_cache_tests;
+pub mod gas_profiler_tests;
 pub mod vm_arguments_tests;
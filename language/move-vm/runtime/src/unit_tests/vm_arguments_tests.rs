@@ -17,8 +17,8 @@ use move_binary_format::{
 use move_core_types::{
     account_address::AccountAddress,
     identifier::{IdentStr, Identifier},
-    language_storage::{ModuleId, StructTag, TypeTag},
-    resolver::{ModuleResolver, ResourceResolver},
+    language_storage::{ModuleId, StructTag, TableHandle, TypeTag},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
     value::{serialize_values, MoveValue},
     vm_status::{StatusCode, StatusType},
 };
@@ -254,6 +254,18 @@ impl ResourceResolver for RemoteStore {
     }
 }
 
+impl TableResolver for RemoteStore {
+    type Error = VMError;
+
+    fn get_table_item(
+        &self,
+        _handle: &TableHandle,
+        _key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
 fn call_script_with_args_ty_args_signers(
     script: Vec<u8>,
     args: Vec<Vec<u8>>,
@@ -4,10 +4,10 @@
 use anyhow::{format_err, Result};
 use move_core_types::{
     account_address::AccountAddress,
-    effects::{AccountChangeSet, ChangeSet},
+    effects::{AccountChangeSet, ChangeSet, TableChangeSet},
     identifier::Identifier,
-    language_storage::{ModuleId, StructTag},
-    resolver::{ModuleResolver, MoveResolver, ResourceResolver},
+    language_storage::{ModuleId, StructTag, TableHandle},
+    resolver::{ModuleResolver, MoveResolver, ResourceResolver, TableResolver},
 };
 use std::collections::{btree_map, BTreeMap};
 
@@ -41,6 +41,18 @@ impl ResourceResolver for BlankStorage {
     }
 }
 
+impl TableResolver for BlankStorage {
+    type Error = ();
+
+    fn get_table_item(
+        &self,
+        _handle: &TableHandle,
+        _key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
 // A storage adapter created by stacking a change set on top of an existing storage backend.
 /// The new storage can be used for additional computations without modifying the base.
 #[derive(Debug, Clone)]
@@ -81,6 +93,24 @@ impl<'a, 'b, S: ResourceResolver> ResourceResolver for DeltaStorage<'a, 'b, S> {
     }
 }
 
+impl<'a, 'b, S: TableResolver> TableResolver for DeltaStorage<'a, 'b, S> {
+    type Error = S::Error;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, S::Error> {
+        if let Some(table_changes) = self.delta.tables().get(handle) {
+            if let Some(blob_opt) = table_changes.get(key) {
+                return Ok(blob_opt.clone());
+            }
+        }
+
+        self.base.get_table_item(handle, key)
+    }
+}
+
 impl<'a, 'b, S: MoveResolver> DeltaStorage<'a, 'b, S> {
     pub fn new(base: &'a S, delta: &'b ChangeSet) -> Self {
         Self { base, delta }
@@ -98,6 +128,7 @@ struct InMemoryAccountStorage {
 #[derive(Debug, Clone)]
 pub struct InMemoryStorage {
     accounts: BTreeMap<AccountAddress, InMemoryAccountStorage>,
+    tables: BTreeMap<TableHandle, BTreeMap<Vec<u8>, Vec<u8>>>,
 }
 
 fn apply_changes<K, V, F, E>(
@@ -156,7 +187,8 @@ impl InMemoryAccountStorage {
 
 impl InMemoryStorage {
     pub fn apply(&mut self, changeset: ChangeSet) -> Result<()> {
-        for (addr, account_changeset) in changeset.into_inner() {
+        let (accounts, tables) = changeset.into_inner();
+        for (addr, account_changeset) in accounts {
             match self.accounts.entry(addr) {
                 btree_map::Entry::Occupied(entry) => {
                     entry.into_mut().apply(account_changeset)?;
@@ -168,12 +200,31 @@ impl InMemoryStorage {
                 }
             }
         }
+        for (handle, table_changes) in tables {
+            self.apply_table_changes(handle, table_changes)?;
+        }
+        Ok(())
+    }
+
+    fn apply_table_changes(&mut self, handle: TableHandle, changes: TableChangeSet) -> Result<()> {
+        let table = self.tables.entry(handle).or_insert_with(BTreeMap::new);
+        apply_changes(table, changes, |key| {
+            format_err!(
+                "Failed to delete item of table {}: key {:?} does not exist.",
+                handle,
+                key
+            )
+        })?;
+        if table.is_empty() {
+            self.tables.remove(&handle);
+        }
         Ok(())
     }
 
     pub fn new() -> Self {
         Self {
             accounts: BTreeMap::new(),
+            tables: BTreeMap::new(),
         }
     }
 
@@ -220,3 +271,19 @@ impl ResourceResolver for InMemoryStorage {
         Ok(None)
     }
 }
+
+impl TableResolver for InMemoryStorage {
+    type Error = ();
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self
+            .tables
+            .get(handle)
+            .and_then(|table| table.get(key))
+            .cloned())
+    }
+}
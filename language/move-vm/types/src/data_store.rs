@@ -6,7 +6,10 @@ use crate::{
     values::{GlobalValue, Value},
 };
 use move_binary_format::errors::{PartialVMResult, VMResult};
-use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, TableHandle, TypeTag},
+};
 
 /// Provide an implementation for bytecodes related to data with a given data store.
 ///
@@ -36,6 +39,33 @@ pub trait DataStore {
     /// Check if this module exists.
     fn exists_module(&self, module_id: &ModuleId) -> VMResult<bool>;
 
+    /// Return the resources that have been loaded into the data store so far and still exist,
+    /// along with their types and their current values serialized.
+    ///
+    /// This is only used by debuggers to inspect global storage, so data stores that do not
+    /// keep track of their resources can rely on the default, which reports none.
+    fn debug_resources(&self) -> PartialVMResult<Vec<(AccountAddress, TypeTag, Vec<u8>)>> {
+        Ok(vec![])
+    }
+
+    // ---
+    // TableStore operations
+    // ---
+
+    /// Create the handle of a new table. Handles are derived from the session the data store
+    /// belongs to, so they are unique as long as sessions are.
+    fn create_table_handle(&mut self) -> PartialVMResult<TableHandle>;
+
+    /// Try to load an item of a table, stored under its serialized key, from remote storage and
+    /// create a corresponding GlobalValue that is owned by the data store. `ty` is the type of
+    /// the values of the table.
+    fn load_table_item(
+        &mut self,
+        handle: TableHandle,
+        key: Vec<u8>,
+        ty: &Type,
+    ) -> PartialVMResult<&mut GlobalValue>;
+
     // ---
     // EventStore operations
     // ---
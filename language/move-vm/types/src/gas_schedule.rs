@@ -76,6 +76,11 @@ impl<'a> GasStatus<'a> {
             .to_external_units(self.gas_left)
     }
 
+    /// Return the gas left, in the internal units costs are charged in.
+    pub fn remaining_internal_gas(&self) -> InternalGasUnits<GasCarrier> {
+        self.gas_left
+    }
+
     /// Charge a given amount of gas and fail if not enough gas units are left.
     pub fn deduct_gas(&mut self, amount: InternalGasUnits<GasCarrier>) -> PartialVMResult<()> {
         if !self.charge {
@@ -423,6 +428,11 @@ pub static INITIAL_GAS_SCHEDULE: Lazy<CostTable> = Lazy::new(|| {
         (N::CREATE_SIGNER, GasCost::new(24, 1)),
         (N::DESTROY_SIGNER, GasCost::new(212, 1)),
         (N::EMIT_EVENT, GasCost::new(52, 1)),
+        (N::TABLE_NEW, GasCost::new(64, 1)),
+        (N::TABLE_INSERT, GasCost::new(68, 1)),
+        (N::TABLE_BORROW, GasCost::new(68, 1)),
+        (N::TABLE_CONTAINS, GasCost::new(41, 1)),
+        (N::TABLE_REMOVE, GasCost::new(68, 1)),
     ];
     native_table.sort_by_key(|cost| cost.0 as u64);
     let raw_native_table = native_table
@@ -470,4 +480,9 @@ pub enum NativeCostIndex {
     CREATE_SIGNER = 15,
     DESTROY_SIGNER = 16,
     EMIT_EVENT = 17,
+    TABLE_NEW = 18,
+    TABLE_INSERT = 19,
+    TABLE_BORROW = 20,
+    TABLE_CONTAINS = 21,
+    TABLE_REMOVE = 22,
 }
@@ -117,6 +117,21 @@ pub fn native_gas(
     gas_amt.total().mul(memory_size)
 }
 
+/// Like `native_gas`, but fails instead of panicking if `CostTable` has no entry for the key, as
+/// happens for the natives added after the gas schedule in use was published.
+pub fn checked_native_gas(
+    table: &CostTable,
+    key: NativeCostIndex,
+    size: usize,
+) -> PartialVMResult<InternalGasUnits<GasCarrier>> {
+    let gas_amt = table.checked_native_cost(key as u8).ok_or_else(|| {
+        PartialVMError::new(StatusCode::FEATURE_UNDER_GATING)
+            .with_message(format!("No gas cost for native {:?}", key))
+    })?;
+    let memory_size = AbstractMemorySize::new(std::cmp::max(1, size) as GasCarrier);
+    Ok(gas_amt.total().mul(memory_size))
+}
+
 /// Return the argument at the top of the stack.
 ///
 /// Arguments are passed to a native as a stack with first arg at the bottom of the stack.
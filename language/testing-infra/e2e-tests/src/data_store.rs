@@ -16,8 +16,8 @@ use diem_vm::data_cache::RemoteStorage;
 use move_binary_format::errors::*;
 use move_core_types::{
     account_address::AccountAddress,
-    language_storage::{ModuleId, StructTag},
-    resolver::{ModuleResolver, ResourceResolver},
+    language_storage::{ModuleId, StructTag, TableHandle},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -132,3 +132,15 @@ impl ResourceResolver for FakeDataStore {
         RemoteStorage::new(self).get_resource(address, tag)
     }
 }
+
+impl TableResolver for FakeDataStore {
+    type Error = VMError;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        RemoteStorage::new(self).get_table_item(handle, key)
+    }
+}
@@ -33,7 +33,7 @@ use diem_types::{
     },
     transaction::{
         ChangeSet, SignedTransaction, Transaction, TransactionOutput, TransactionStatus,
-        VMValidatorResult,
+        VMValidatorResult, WriteSetPayload,
     },
     vm_status::{KeptVMStatus, VMStatus},
     write_set::WriteSet,
@@ -43,7 +43,8 @@ use diem_vm::{
     read_write_set_analysis::add_on_functions_list, DiemVM, VMExecutor, VMValidator,
 };
 use diem_writeset_generator::{
-    encode_disable_parallel_execution, encode_enable_parallel_execution_with_config,
+    encode_disable_parallel_execution, encode_enable_dynamic_parallel_execution,
+    encode_enable_parallel_execution_with_config,
 };
 use move_core_types::{
     account_address::AccountAddress,
@@ -402,8 +403,11 @@ impl FakeExecutor {
         }
 
         let output = DiemVM::execute_block(txn_block.clone(), &self.data_store);
-        let parallel_output = self.execute_transaction_block_parallel(txn_block);
+        let parallel_output = self.execute_transaction_block_parallel(txn_block.clone());
         assert_eq!(output, parallel_output);
+        let dynamic_parallel_output =
+            ParallelDiemVM::execute_block_dynamic(txn_block, &self.data_store);
+        assert_eq!(output, dynamic_parallel_output);
 
         if let Some(logger) = &self.executed_output {
             logger.log(format!("{:?}\n", output).as_str());
@@ -502,6 +506,14 @@ impl FakeExecutor {
     }
 
     pub fn enable_parallel_execution(&mut self) {
+        self.update_parallel_execution_config(encode_enable_parallel_execution_with_config());
+    }
+
+    pub fn enable_dynamic_parallel_execution(&mut self) {
+        self.update_parallel_execution_config(encode_enable_dynamic_parallel_execution());
+    }
+
+    fn update_parallel_execution_config(&mut self, write_set: WriteSetPayload) {
         let diem_root = Account::new_diem_root();
         let seq_num = self
             .read_account_resource_at_address(diem_root.address())
@@ -510,7 +522,7 @@ impl FakeExecutor {
 
         let txn = diem_root
             .transaction()
-            .write_set(encode_enable_parallel_execution_with_config())
+            .write_set(write_set)
             .sequence_number(seq_num)
             .sign();
         self.execute_and_apply(txn);
@@ -41,6 +41,8 @@ struct SimpleVMTestAdapter<'a> {
     compiled_state: CompiledState<'a>,
     storage: InMemoryStorage,
     default_syntax: SyntaxChoice,
+    /// The number of sessions run so far, which identifies the next session
+    num_sessions: u64,
 }
 
 pub fn view_resource_in_move_storage(
@@ -105,6 +107,7 @@ impl<'a> MoveTestAdapter<'a> for SimpleVMTestAdapter<'a> {
             compiled_state: CompiledState::new(named_address_mapping, pre_compiled_deps),
             default_syntax,
             storage: InMemoryStorage::new(),
+            num_sessions: 0,
         };
 
         adapter
@@ -265,7 +268,9 @@ impl<'a> SimpleVMTestAdapter<'a> {
         let vm = MoveVM::new(move_stdlib::natives::all_natives(STD_ADDR)).unwrap();
         let (mut session, mut gas_status) = {
             let gas_status = move_cli::sandbox::utils::get_gas_status(gas_budget).unwrap();
-            let session = vm.new_session(&self.storage);
+            let session_id = self.num_sessions.to_le_bytes().to_vec();
+            self.num_sessions += 1;
+            let session = vm.new_session_with_id(&self.storage, session_id);
             (session, gas_status)
         };
 
@@ -0,0 +1,805 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An interactive, source-level debugger for the Move code run by `package test --debug` and
+//! `sandbox run --debug`.
+//!
+//! The debugger is driven by the VM's debugger hook (see `move_vm_runtime::debugger`) and uses
+//! the source maps emitted by the compiler to map the bytecode being executed back to the lines
+//! of Move source it was compiled from. Execution pauses before the first line of Move code that
+//! is executed; from then on, the debugger reads commands from stdin to set breakpoints by file
+//! and line, step through the code, and print locals, global resources and the call stack.
+
+use anyhow::{anyhow, bail, Result};
+use move_binary_format::file_format::{CompiledModule, FunctionDefinitionIndex};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_command_line_common::files::FileHash;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, StructTag, TableHandle, TypeTag},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
+};
+use move_resource_viewer::MoveValueAnnotator;
+use move_vm_runtime::debugger::{
+    self, DebugFrame, DebugState, Debugger, DebuggerGuard, Mutability,
+};
+use std::{
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap},
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+const HELP: &str = "\
+Commands:
+    step (s)                  run until the next line, stepping into calls
+    next (n)                  run until the next line of this function or of its callers
+    out (o)                   run until the function being executed returns
+    continue (c)              run until a breakpoint is hit
+    break (b) <file>:<line>   set a breakpoint; <file> may be any suffix of the file's path
+    delete (d) <file>:<line>  delete a breakpoint
+    breakpoints               list the breakpoints
+    locals (l)                print the parameters and locals of the function being executed
+    resources (r)             print the global resources loaded so far
+    backtrace (bt)            print the call stack
+    help (h)                  print this message";
+
+/// Fail unless the VM was built with support for debuggers, i.e., unless this is a debug build.
+pub fn check_debugger_supported() -> Result<()> {
+    if !debugger::DEBUGGER_SUPPORTED {
+        bail!("`--debug` requires a debug build of the Move CLI, i.e. built without `--release`")
+    }
+    Ok(())
+}
+
+/// A line of source, as the index of its file and its (1-based) line number.
+type SourceLine = (usize, usize);
+
+struct SourceFile {
+    path: PathBuf,
+    text: String,
+    /// The byte offset at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    fn new(path: PathBuf, text: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Self {
+            path,
+            text,
+            line_starts,
+        }
+    }
+
+    /// The line containing the byte at `offset`.
+    fn line_at(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(idx) => idx + 1,
+            Err(idx) => idx,
+        }
+    }
+
+    fn line_text(&self, line: usize) -> &str {
+        let start = self.line_starts[line - 1];
+        let end = self
+            .line_starts
+            .get(line)
+            .copied()
+            .unwrap_or_else(|| self.text.len());
+        self.text[start..end].trim_end()
+    }
+}
+
+/// A compiled module or script, and the source map relating its code to its source.
+struct CodeUnit {
+    source_map: SourceMap,
+    function_count: usize,
+}
+
+/// The modules being debugged, against which the values of locals and resources are annotated
+/// with the names of struct fields.
+#[derive(Default)]
+struct DebugModules(BTreeMap<ModuleId, Vec<u8>>);
+
+impl ModuleResolver for DebugModules {
+    type Error = anyhow::Error;
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.0.get(id).cloned())
+    }
+}
+
+impl ResourceResolver for DebugModules {
+    type Error = anyhow::Error;
+
+    fn get_resource(
+        &self,
+        _address: &AccountAddress,
+        _typ: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl TableResolver for DebugModules {
+    type Error = anyhow::Error;
+
+    fn get_table_item(
+        &self,
+        _handle: &TableHandle,
+        _key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+/// How far execution should proceed before pausing again, besides pausing at breakpoints.
+#[derive(Clone, Copy, Debug)]
+enum Resume {
+    Continue,
+    StepInto,
+    /// Pause at the next line of a function with at most this many callers.
+    StepOver(usize),
+    /// Pause at the next line of a function with fewer than this many callers.
+    StepOut(usize),
+}
+
+/// The instruction a frame is at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Position {
+    module_id: Option<ModuleId>,
+    function_index: FunctionDefinitionIndex,
+    pc: u16,
+    line: Option<SourceLine>,
+}
+
+impl Position {
+    /// Whether `next` is an instruction of the same line that follows this one, rather than the
+    /// start of a new line of source, in the same frame.
+    fn continues_to(&self, next: &Position) -> bool {
+        self.module_id == next.module_id
+            && self.function_index == next.function_index
+            && self.line == next.line
+            && self.pc < next.pc
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum DebugCommand {
+    Step,
+    Next,
+    Out,
+    Continue,
+    Break(String),
+    Delete(String),
+    Breakpoints,
+    Locals,
+    Resources,
+    Backtrace,
+    Help,
+}
+
+impl FromStr for DebugCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        use DebugCommand::*;
+        let words: Vec<_> = s.split_whitespace().collect();
+        Ok(match words.as_slice() {
+            ["step" | "s"] => Step,
+            ["next" | "n"] => Next,
+            ["out" | "o"] => Out,
+            ["continue" | "c"] => Continue,
+            ["break" | "b", breakpoint] => Break(breakpoint.to_string()),
+            ["delete" | "d", breakpoint] => Delete(breakpoint.to_string()),
+            ["breakpoints"] => Breakpoints,
+            ["locals" | "l"] => Locals,
+            ["resources" | "r"] => Resources,
+            ["backtrace" | "bt"] => Backtrace,
+            ["help" | "h"] => Help,
+            _ => bail!(
+                "Unrecognized command: '{}'. Type 'help' for the list of commands",
+                s.trim()
+            ),
+        })
+    }
+}
+
+/// A debugger for code compiled from Move source.
+///
+/// The debugger needs the sources and source maps of all the code to debug, added with
+/// `add_source_file`, `add_module` and `add_script`, before it is installed. Code it does not
+/// have a source map for is run through without pausing.
+pub struct SourceDebugger {
+    files: Vec<SourceFile>,
+    file_indices: HashMap<FileHash, usize>,
+    /// Keyed by module; the script being run, if any, is keyed by `None`.
+    units: BTreeMap<Option<ModuleId>, CodeUnit>,
+    modules: DebugModules,
+    breakpoints: BTreeSet<SourceLine>,
+    resume: Resume,
+    /// The last instruction executed at each depth of the call stack.
+    positions: BTreeMap<usize, Position>,
+    /// Where commands are read from and where their results are written to.
+    input: Box<dyn BufRead + Send>,
+    output: Box<dyn Write + Send>,
+}
+
+impl Default for SourceDebugger {
+    fn default() -> Self {
+        Self::with_io(
+            Box::new(io::BufReader::new(io::stdin())),
+            Box::new(io::stdout()),
+        )
+    }
+}
+
+impl SourceDebugger {
+    /// A debugger reading commands from `input` rather than from stdin, and writing to `output`
+    /// rather than to stdout.
+    pub fn with_io(input: Box<dyn BufRead + Send>, output: Box<dyn Write + Send>) -> Self {
+        Self {
+            files: vec![],
+            file_indices: HashMap::new(),
+            units: BTreeMap::new(),
+            modules: DebugModules::default(),
+            breakpoints: BTreeSet::new(),
+            resume: Resume::StepInto,
+            positions: BTreeMap::new(),
+            input,
+            output,
+        }
+    }
+
+    /// Add a source file the code to debug was compiled from.
+    pub fn add_source_file(&mut self, path: impl Into<PathBuf>, text: String) {
+        let file_hash = FileHash::new(&text);
+        if let Entry::Vacant(entry) = self.file_indices.entry(file_hash) {
+            entry.insert(self.files.len());
+            self.files.push(SourceFile::new(path.into(), text));
+        }
+    }
+
+    /// Add a module to debug, along with its source map.
+    pub fn add_module(&mut self, module: &CompiledModule, source_map: SourceMap) -> Result<()> {
+        let mut bytes = vec![];
+        module.serialize(&mut bytes)?;
+        self.modules.0.insert(module.self_id(), bytes);
+        self.units.insert(
+            Some(module.self_id()),
+            CodeUnit {
+                source_map,
+                function_count: module.function_defs.len(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Add the source map of the script being run.
+    pub fn add_script(&mut self, source_map: SourceMap) {
+        self.units.insert(
+            None,
+            CodeUnit {
+                source_map,
+                function_count: 1,
+            },
+        );
+    }
+
+    /// Install the debugger in the VM, so that it takes over the next execution, until the
+    /// returned guard is dropped. Fails if the VM was built without support for debuggers.
+    pub fn install(mut self) -> Result<DebuggerGuard> {
+        check_debugger_supported()?;
+        self.write(
+            "Execution will pause before the first line of Move code. \
+             Type 'help' for the list of commands.",
+        );
+        Ok(debugger::install_debugger(Box::new(self)))
+    }
+
+    /// Write a line of output. The debugger has no way of reporting errors to the VM, so failing
+    /// to write is ignored, as is done by the VM when failing to print debug output.
+    fn write(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+
+    fn source_line(&self, frame: &DebugFrame) -> Option<SourceLine> {
+        let loc = self
+            .units
+            .get(&frame.module_id)?
+            .source_map
+            .get_code_location(frame.function_index, frame.pc)
+            .ok()?;
+        let file = *self.file_indices.get(&loc.file_hash())?;
+        Some((file, self.files[file].line_at(loc.start() as usize)))
+    }
+
+    fn display_line(&self, (file, line): SourceLine) -> String {
+        format!("{}:{}", self.files[file].path.display(), line)
+    }
+
+    /// The lines that some instruction was compiled from, which are the lines execution can
+    /// pause at.
+    fn lines_with_code(&self) -> BTreeSet<SourceLine> {
+        let mut lines = BTreeSet::new();
+        for unit in self.units.values() {
+            for idx in 0..unit.function_count {
+                let function_map = match unit
+                    .source_map
+                    .get_function_source_map(FunctionDefinitionIndex(idx as u16))
+                {
+                    Ok(function_map) => function_map,
+                    Err(_) => continue,
+                };
+                for loc in function_map.code_map.values() {
+                    if let Some(file) = self.file_indices.get(&loc.file_hash()) {
+                        lines.insert((*file, self.files[*file].line_at(loc.start() as usize)));
+                    }
+                }
+            }
+        }
+        lines
+    }
+
+    fn resolve_breakpoint(&self, breakpoint: &str) -> Result<SourceLine> {
+        let (path, line) = breakpoint.rsplit_once(':').ok_or_else(|| {
+            anyhow!(
+                "Expected a breakpoint of the form <file>:<line>, got '{}'",
+                breakpoint
+            )
+        })?;
+        let line = line
+            .parse::<usize>()
+            .map_err(|_| anyhow!("Invalid line number '{}'", line))?;
+        let matching_files: Vec<_> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.path.ends_with(Path::new(path)))
+            .map(|(idx, _)| idx)
+            .collect();
+        let file = match matching_files.as_slice() {
+            [file] => *file,
+            [] => bail!("No source file matches '{}'", path),
+            _ => bail!(
+                "'{}' matches more than one source file: {}",
+                path,
+                matching_files
+                    .iter()
+                    .map(|file| self.files[*file].path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        if !self.lines_with_code().contains(&(file, line)) {
+            bail!(
+                "There is no code at line {} of {}",
+                line,
+                self.files[file].path.display()
+            )
+        }
+        Ok((file, line))
+    }
+
+    /// Take commands until one resumes execution.
+    fn pause(&mut self, state: &DebugState) {
+        loop {
+            let _ = write!(self.output, "> ");
+            let _ = self.output.flush();
+            let mut input = String::new();
+            match self.input.read_line(&mut input) {
+                Ok(0) | Err(_) => {
+                    // There is no one left to take commands from, so run to completion
+                    self.breakpoints.clear();
+                    self.resume = Resume::Continue;
+                    return;
+                }
+                Ok(_) => (),
+            }
+            if input.trim().is_empty() {
+                continue;
+            }
+            let command = match input.parse::<DebugCommand>() {
+                Ok(command) => command,
+                Err(err) => {
+                    self.write(&err.to_string());
+                    continue;
+                }
+            };
+            match command {
+                DebugCommand::Step => {
+                    self.resume = Resume::StepInto;
+                    return;
+                }
+                DebugCommand::Next => {
+                    self.resume = Resume::StepOver(state.call_depth());
+                    return;
+                }
+                DebugCommand::Out => {
+                    self.resume = Resume::StepOut(state.call_depth());
+                    return;
+                }
+                DebugCommand::Continue => {
+                    self.resume = Resume::Continue;
+                    return;
+                }
+                DebugCommand::Break(breakpoint) => {
+                    let text = match self.resolve_breakpoint(&breakpoint) {
+                        Ok(line) => {
+                            self.breakpoints.insert(line);
+                            format!("Breakpoint set at {}", self.display_line(line))
+                        }
+                        Err(err) => err.to_string(),
+                    };
+                    self.write(&text)
+                }
+                DebugCommand::Delete(breakpoint) => {
+                    let text = match self.resolve_breakpoint(&breakpoint) {
+                        Ok(line) if self.breakpoints.remove(&line) => {
+                            format!("Breakpoint deleted at {}", self.display_line(line))
+                        }
+                        Ok(line) => format!("No breakpoint at {}", self.display_line(line)),
+                        Err(err) => err.to_string(),
+                    };
+                    self.write(&text)
+                }
+                DebugCommand::Breakpoints => {
+                    let text = self.format_breakpoints();
+                    self.write(&text)
+                }
+                DebugCommand::Locals => {
+                    let text = self.format_locals(state);
+                    self.write(&text)
+                }
+                DebugCommand::Resources => {
+                    let text = self.format_resources(state);
+                    self.write(&text)
+                }
+                DebugCommand::Backtrace => {
+                    let text = self.format_backtrace(state);
+                    self.write(&text)
+                }
+                DebugCommand::Help => self.write(HELP),
+            }
+        }
+    }
+
+    fn format_breakpoints(&self) -> String {
+        if self.breakpoints.is_empty() {
+            return "No breakpoints".to_string();
+        }
+        self.breakpoints
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| format!("[{}] {}", idx, self.display_line(*line)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn format_locals(&self, state: &DebugState) -> String {
+        let locals = match state.locals() {
+            Ok(locals) => locals,
+            Err(err) => return format!("Unable to read the locals: {:?}", err),
+        };
+        let frame = state.frame();
+        let function_map = self.units.get(&frame.module_id).and_then(|unit| {
+            unit.source_map
+                .get_function_source_map(frame.function_index)
+                .ok()
+        });
+        let annotator = MoveValueAnnotator::new(&self.modules);
+        let mut text = String::new();
+        for (idx, local) in locals.iter().enumerate() {
+            // Locals with no value are either not assigned yet or moved, or are shadowed by a
+            // local of the same name, so they are not shown
+            let value = match &local.value {
+                Some(value) => value,
+                None => continue,
+            };
+            let name =
+                match function_map.and_then(|map| map.get_parameter_or_local_name(idx as u64)) {
+                    // Temporaries introduced by the compiler are not shown either
+                    Some((name, _)) if name.starts_with('%') => continue,
+                    // The compiler makes the names of locals unique by suffixing them with `#<n>`
+                    Some((name, _)) => name.split('#').next().unwrap_or_default().to_string(),
+                    None => format!("local{}", idx),
+                };
+            let reference = match local.reference {
+                Some(Mutability::Immutable) => "&",
+                Some(Mutability::Mutable) => "&mut ",
+                None => "",
+            };
+            writeln!(
+                text,
+                "    {}: {}{} = {}",
+                name,
+                reference,
+                local.type_tag,
+                format_value(&annotator, &local.type_tag, value).replace('\n', "\n    ")
+            )
+            .unwrap();
+        }
+        if text.is_empty() {
+            return "    (none)".to_string();
+        }
+        text.truncate(text.trim_end().len());
+        text
+    }
+
+    fn format_resources(&self, state: &DebugState) -> String {
+        let resources = match state.resources() {
+            Ok(resources) => resources,
+            Err(err) => return format!("Unable to read the resources: {:?}", err),
+        };
+        if resources.is_empty() {
+            return "No resources have been loaded yet".to_string();
+        }
+        let annotator = MoveValueAnnotator::new(&self.modules);
+        let mut text = String::new();
+        let mut current_address = None;
+        for (address, type_tag, value) in resources {
+            if current_address != Some(address) {
+                writeln!(text, "0x{}:", address.short_str_lossless()).unwrap();
+                current_address = Some(address);
+            }
+            writeln!(
+                text,
+                "    {}",
+                format_value(&annotator, &type_tag, &value).replace('\n', "\n    ")
+            )
+            .unwrap();
+        }
+        text.truncate(text.trim_end().len());
+        text
+    }
+
+    fn format_backtrace(&self, state: &DebugState) -> String {
+        let mut frames = state.call_stack();
+        frames.push(state.frame());
+        frames
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(idx, frame)| match self.source_line(frame) {
+                Some(line) => format!(
+                    "#{} {} at {}",
+                    idx,
+                    function_name(frame),
+                    self.display_line(line)
+                ),
+                None => format!("#{} {} at pc {}", idx, function_name(frame), frame.pc),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Debugger for SourceDebugger {
+    fn on_instruction(&mut self, state: &DebugState) {
+        let frame = state.frame();
+        let depth = state.call_depth();
+        let line = self.source_line(&frame);
+        let position = Position {
+            module_id: frame.module_id.clone(),
+            function_index: frame.function_index,
+            pc: frame.pc,
+            line,
+        };
+        // Frames deeper than this one have returned
+        self.positions.split_off(&(depth + 1));
+        let at_new_line = match self.positions.insert(depth, position.clone()) {
+            Some(last) => !last.continues_to(&position),
+            None => true,
+        };
+        let line = match line {
+            Some(line) if at_new_line => line,
+            _ => return,
+        };
+        let should_pause = self.breakpoints.contains(&line)
+            || match self.resume {
+                Resume::Continue => false,
+                Resume::StepInto => true,
+                Resume::StepOver(max_depth) => depth <= max_depth,
+                Resume::StepOut(max_depth) => depth < max_depth,
+            };
+        if should_pause {
+            let text = format!(
+                "{} at {}\n{:>5} | {}",
+                function_name(&frame),
+                self.display_line(line),
+                line.1,
+                self.files[line.0].line_text(line.1)
+            );
+            self.write(&text);
+            self.pause(state);
+        }
+    }
+}
+
+fn function_name(frame: &DebugFrame) -> String {
+    match &frame.module_id {
+        Some(module_id) => format!(
+            "0x{}::{}::{}",
+            module_id.address().short_str_lossless(),
+            module_id.name(),
+            frame.function_name
+        ),
+        None => format!("script::{}", frame.function_name),
+    }
+}
+
+fn format_value(
+    annotator: &MoveValueAnnotator<DebugModules>,
+    type_tag: &TypeTag,
+    value: &[u8],
+) -> String {
+    if let TypeTag::Signer = type_tag {
+        if let Ok(address) = bcs::from_bytes::<AccountAddress>(value) {
+            return format!("signer(0x{})", address.short_str_lossless());
+        }
+    }
+    match annotator.view_value(type_tag, value) {
+        Ok(value) => value.to_string(),
+        Err(_) => "<unable to display the value>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::{
+        identifier::Identifier,
+        value::{serialize_values, MoveValue},
+    };
+    use move_lang::{compiled_unit::AnnotatedCompiledUnit, Compiler, Flags};
+    use move_vm_runtime::move_vm::MoveVM;
+    use move_vm_test_utils::InMemoryStorage;
+    use move_vm_types::gas_schedule::GasStatus;
+    use std::{
+        fs,
+        sync::{Arc, Mutex},
+    };
+
+    const SOURCE: &str = "\
+module 0x2::M {
+    public fun run(x: u64): u64 {
+        let y = double(x);
+        y + 1
+    }
+
+    fun double(x: u64): u64 {
+        x * 2
+    }
+}
+";
+
+    /// An output that can still be read once the debugger owning it is installed.
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Run `M::run(5)` under a debugger taking `commands`, and return the debugger's output with
+    /// the prompts removed and the path of the source file replaced by `M.move`.
+    fn debug_run(commands: &str) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("M.move");
+        fs::write(&path, SOURCE).unwrap();
+        let (_, units) = Compiler::new(&[path.to_str().unwrap().to_string()], &[])
+            .set_flags(Flags::empty().set_sources_shadow_deps(false))
+            .build_and_report()
+            .unwrap();
+        let module = match units.into_iter().next().unwrap() {
+            AnnotatedCompiledUnit::Module(module) => module.named_module,
+            AnnotatedCompiledUnit::Script(_) => panic!("expected a module"),
+        };
+
+        let output = SharedOutput::default();
+        let mut debugger = SourceDebugger::with_io(
+            Box::new(io::Cursor::new(commands.to_string())),
+            Box::new(output.clone()),
+        );
+        debugger.add_source_file(&path, SOURCE.to_string());
+        debugger
+            .add_module(&module.module, module.source_map)
+            .unwrap();
+
+        let mut storage = InMemoryStorage::new();
+        let mut blob = vec![];
+        module.module.serialize(&mut blob).unwrap();
+        storage.publish_or_overwrite_module(module.module.self_id(), blob);
+
+        let guard = debugger.install().unwrap();
+        let vm = MoveVM::new(vec![]).unwrap();
+        let mut session = vm.new_session(&storage);
+        session
+            .execute_function(
+                &module.module.self_id(),
+                &Identifier::new("run").unwrap(),
+                vec![],
+                serialize_values(&vec![MoveValue::U64(5)]),
+                &mut GasStatus::new_unmetered(),
+            )
+            .unwrap();
+        drop(guard);
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        output
+            .replace("> ", "")
+            .replace(path.to_str().unwrap(), "M.move")
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!("s".parse::<DebugCommand>().unwrap(), DebugCommand::Step);
+        assert_eq!(
+            " next ".parse::<DebugCommand>().unwrap(),
+            DebugCommand::Next
+        );
+        assert_eq!(
+            "b M.move:3".parse::<DebugCommand>().unwrap(),
+            DebugCommand::Break("M.move:3".to_string())
+        );
+        assert_eq!(
+            "delete M.move:3".parse::<DebugCommand>().unwrap(),
+            DebugCommand::Delete("M.move:3".to_string())
+        );
+        assert!("b".parse::<DebugCommand>().is_err());
+        assert!("jump 3".parse::<DebugCommand>().is_err());
+    }
+
+    #[test]
+    fn source_lines() {
+        let file = SourceFile::new(PathBuf::from("M.move"), "ab\n  cd  \n\nef".to_string());
+        assert_eq!(file.line_at(0), 1);
+        assert_eq!(file.line_at(2), 1);
+        assert_eq!(file.line_at(3), 2);
+        assert_eq!(file.line_at(9), 2);
+        assert_eq!(file.line_at(10), 3);
+        assert_eq!(file.line_at(11), 4);
+        assert_eq!(file.line_text(2), "  cd");
+        assert_eq!(file.line_text(3), "");
+        assert_eq!(file.line_text(4), "ef");
+    }
+
+    // The debugger is shared by all the VMs of the process, so all the sessions that install it
+    // are run by this one test rather than by tests that could run concurrently
+    #[test]
+    fn debug_sessions() {
+        if !debugger::DEBUGGER_SUPPORTED {
+            return;
+        }
+
+        // Breakpoints, locals and backtraces
+        let output = debug_run("b M.move:1\nb M.move:8\nbreakpoints\nc\nl\nbt\nc\n");
+        assert!(output.contains("There is no code at line 1 of M.move"));
+        assert!(output.contains("Breakpoint set at M.move:8"));
+        assert!(output.contains("[0] M.move:8"));
+        assert!(output.contains("0x2::M::double at M.move:8\n    8 |         x * 2"));
+        assert!(output.contains("    x: u64 = 5"));
+        assert!(output.contains("#0 0x2::M::double at M.move:8\n#1 0x2::M::run at M.move:3"));
+
+        // Stepping over the call, and unrecognized commands
+        let output = debug_run("n\njump\nn\n");
+        assert!(output.contains("0x2::M::run at M.move:3"));
+        assert!(output.contains("Unrecognized command: 'jump'"));
+        assert!(output.contains("0x2::M::run at M.move:4"));
+        assert!(!output.contains("0x2::M::double"));
+
+        // Stepping into the call, and running to completion once there are no more commands
+        let output = debug_run("s\n");
+        assert!(output.contains("0x2::M::double at M.move:8"));
+    }
+}
@@ -4,6 +4,7 @@
 use move_package::BuildConfig;
 
 pub mod base;
+pub mod debugger;
 pub mod experimental;
 pub mod package;
 pub mod sandbox;
@@ -4,38 +4,50 @@
 use std::{
     collections::HashMap,
     fmt::Display,
-    fs::{create_dir_all, read_to_string},
+    fs::{create_dir_all, read_to_string, File},
     io::Write,
     os::unix::prelude::ExitStatusExt,
     path::{Path, PathBuf},
     process::ExitStatus,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{bail, Result};
+use difference::{Changeset, Difference};
 
-use move_command_line_common::files::{FileHash, MOVE_COVERAGE_MAP_EXTENSION};
+use move_command_line_common::files::{find_move_filenames, FileHash, MOVE_COVERAGE_MAP_EXTENSION};
 use move_coverage::{
     coverage_map::{output_map_to_file, CoverageMap},
     format_csv_summary, format_human_summary,
+    line_coverage::{write_cobertura, write_lcov, ModuleLineCoverage},
     source_coverage::SourceCoverageBuilder,
     summary::summarize_inst_cov,
 };
 use move_disassembler::disassembler::Disassembler;
 use move_lang::{
     compiled_unit::{CompiledUnit, NamedCompiledModule},
-    diagnostics::{self, codes::Severity},
+    diagnostics::{self, codes::Severity, FilesSourceText},
+    linter,
     unit_test::{plan_builder::construct_test_plan, TestPlan},
-    PASS_CFGIR,
+    PASS_CFGIR, PASS_TYPING,
 };
 use move_package::{
-    compilation::{build_plan::BuildPlan, compiled_package::CompiledUnitWithSource},
+    compilation::{
+        build_plan::BuildPlan, compiled_package::CompiledUnitWithSource,
+        package_layout::CompiledPackageLayout,
+    },
     source_package::layout::SourcePackageLayout,
     ModelConfig,
 };
 use move_unit_test::UnitTestingConfig;
+use move_vm_runtime::coverage::ExecutionCounts;
 use structopt::StructOpt;
 
-use crate::{package::prover::run_move_prover, NativeFunctionRecord};
+use crate::{
+    debugger::{check_debugger_supported, SourceDebugger},
+    package::prover::run_move_prover,
+    NativeFunctionRecord,
+};
 
 #[derive(StructOpt)]
 pub enum CoverageSummaryOptions {
@@ -75,9 +87,23 @@ pub enum PackageCommand {
     /// Build the package at `path`. If no path is provided defaults to current directory.
     #[structopt(name = "build")]
     Build,
+    /// Check the package at `path` for errors and warnings. If no path is provided defaults to
+    /// current directory.
+    #[structopt(name = "check")]
+    Check {
+        /// Also run the linter over the package's sources. Lints are configured in the `[lints]`
+        /// section of the manifest, and with `#[allow(..)]`, `#[warn(..)]` and `#[deny(..)]`
+        /// attributes on modules, scripts and functions
+        #[structopt(long = "lint")]
+        lint: bool,
+    },
     /// Print address information.
     #[structopt(name = "info")]
     Info,
+    /// Fetch the latest commit of the revision of each git dependency, and record the resolved
+    /// dependencies in Move.lock.
+    #[structopt(name = "update")]
+    Update,
     /// Generate error map for the package and its dependencies at `path` for use by the Move
     /// explanation tool.
     #[structopt(name = "errmap")]
@@ -151,9 +177,31 @@ pub enum PackageCommand {
         /// Verbose mode
         #[structopt(long = "verbose")]
         verbose_mode: bool,
-        /// Collect coverage information for later use with the various `package coverage` subcommands
+        /// Collect coverage information for later use with the various `package coverage`
+        /// subcommands, and write line and branch coverage reports for the package's modules to
+        /// `build/coverage/lcov.info` and `build/coverage/cobertura.xml`
         #[structopt(long = "coverage")]
         compute_coverage: bool,
+        /// Debug the tests interactively: pause before the first line of Move code executed, then
+        /// set breakpoints, step through the code, and print locals and global state. Tests are
+        /// run on a single thread. Requires a debug build of the CLI
+        #[structopt(long = "debug")]
+        debug: bool,
+        /// Profile the gas used by the tests and write the profile to this file: a speedscope
+        /// profile if the file has a `.json` extension, and folded stacks for flamegraph tools
+        /// otherwise
+        #[structopt(long = "gas-profile", parse(from_os_str))]
+        gas_profile: Option<PathBuf>,
+    },
+    /// Format the Move source files of the package in place. Comments are kept, spacing and
+    /// indentation are normalized, `use` declarations are sorted and merged, and lines longer than
+    /// 100 characters are wrapped.
+    #[structopt(name = "fmt")]
+    Fmt {
+        /// Do not write any files; print the changes formatting would make and fail if there are
+        /// any
+        #[structopt(long = "check")]
+        check: bool,
     },
     /// Disassemble the Move bytecode pointed to
     #[structopt(name = "disassemble")]
@@ -278,11 +326,22 @@ pub fn handle_package_commands(
         PackageCommand::Build => {
             config.compile_package(&rerooted_path, &mut std::io::stdout())?;
         }
+        PackageCommand::Check { lint } => {
+            check_package(&rerooted_path, config, *lint)?;
+        }
         PackageCommand::Info => {
             config
                 .resolution_graph_for_package(&rerooted_path)?
                 .print_info()?;
         }
+        PackageCommand::Update => {
+            let lock_file = config.update_lock_file(&rerooted_path)?;
+            for package in &lock_file.packages {
+                if let (Some(git), Some(commit)) = (&package.git, &package.commit) {
+                    println!("Locked {} at {} ({})", package.name, commit, git);
+                }
+            }
+        }
         PackageCommand::BytecodeView {
             interactive,
             package_name,
@@ -374,16 +433,22 @@ pub fn handle_package_commands(
             check_stackless_vm,
             verbose_mode,
             compute_coverage,
+            debug,
+            gas_profile,
         } => {
+            if *debug {
+                check_debugger_supported()?;
+            }
             let unit_test_config = UnitTestingConfig {
                 instruction_execution_bound: *instruction_execution_bound,
                 filter: filter.clone(),
                 list: *list,
-                num_threads: *num_threads,
+                num_threads: if *debug { 1 } else { *num_threads },
                 report_statistics: *report_statistics,
                 report_storage_on_error: *report_storage_on_error,
                 check_stackless_vm: *check_stackless_vm,
                 verbose: *verbose_mode,
+                gas_profile: gas_profile.clone(),
                 ..UnitTestingConfig::default_with_bound(None)
             };
             let result = run_move_unit_tests(
@@ -392,6 +457,7 @@ pub fn handle_package_commands(
                 unit_test_config,
                 natives,
                 *compute_coverage,
+                *debug,
             )?;
 
             if let UnitTestResult::Failure = result {
@@ -401,6 +467,11 @@ pub fn handle_package_commands(
         PackageCommand::CoverageReport { options } => {
             options.handle_command(config, &rerooted_path)?;
         }
+        PackageCommand::Fmt { check } => {
+            if !format_package(&rerooted_path, *check)? {
+                std::process::exit(1)
+            }
+        }
         PackageCommand::New { .. } => {
             panic!("Hit a package new command after it should have been handled -- this should never happen")
         }
@@ -408,16 +479,54 @@ pub fn handle_package_commands(
     Ok(())
 }
 
+pub fn check_package(
+    pkg_path: &Path,
+    mut build_config: move_package::BuildConfig,
+    lint: bool,
+) -> Result<()> {
+    if !lint {
+        build_config.compile_package(pkg_path, &mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    // The linter runs over the typed AST of the root package, so it can't be loaded from the cache
+    build_config.force_recompilation = true;
+    let resolution_graph = build_config.resolution_graph_for_package(pkg_path)?;
+    let lint_levels = resolution_graph.root_package.lints.clone();
+    let build_plan = BuildPlan::create(resolution_graph)?;
+    build_plan.compile_with_driver(&mut std::io::stdout(), |compiler, is_root| {
+        if !is_root {
+            return compiler.build_and_report();
+        }
+        let (files, comments_and_compiler_res) = compiler.run::<PASS_TYPING>()?;
+        let (_, compiler) =
+            diagnostics::unwrap_or_report_diagnostics(&files, comments_and_compiler_res);
+        let (mut compiler, typing) = compiler.into_ast();
+        linter::program(compiler.compilation_env(), &lint_levels, &typing);
+
+        let compilation_result = compiler.at_typing(typing).build();
+        let (units, warnings) =
+            diagnostics::unwrap_or_report_diagnostics(&files, compilation_result);
+        diagnostics::report_warnings(&files, warnings);
+        Ok((files, units))
+    })?;
+    Ok(())
+}
+
 pub fn run_move_unit_tests(
     pkg_path: &Path,
     mut build_config: move_package::BuildConfig,
     unit_test_config: UnitTestingConfig,
     natives: Vec<NativeFunctionRecord>,
     compute_coverage: bool,
+    debug: bool,
 ) -> Result<UnitTestResult> {
     let mut test_plan = None;
-    build_config.test_mode = true;
     build_config.dev_mode = true;
+    // Coverage is reported for the package's modules as they are compiled outside of test mode,
+    // but still in dev mode so that packages that need their dev-addresses build
+    let coverage_build_config = build_config.clone();
+    build_config.test_mode = true;
 
     let resolution_graph = build_config.resolution_graph_for_package(pkg_path)?;
     let dep_file_map: HashMap<_, _> = resolution_graph
@@ -479,36 +588,174 @@ pub fn run_move_unit_tests(
         }
     }
 
-    let trace_path = pkg_path.join(".trace");
     let coverage_map_path = pkg_path
         .join(".coverage_map")
         .with_extension(MOVE_COVERAGE_MAP_EXTENSION);
-    let cleanup_trace = || {
-        if compute_coverage && trace_path.exists() {
-            std::fs::remove_file(&trace_path).unwrap();
+    let source_files = if compute_coverage {
+        test_plan.files.clone()
+    } else {
+        FilesSourceText::new()
+    };
+
+    // Uninstalls the debugger once the tests have run, even if running them panics
+    let _debugger_guard = if debug {
+        let mut debugger = SourceDebugger::default();
+        for (name, text) in test_plan.files.values() {
+            debugger.add_source_file(name.as_str(), text.clone());
         }
+        for info in test_plan.module_info.values() {
+            debugger.add_module(&info.module, info.source_map.clone())?;
+        }
+        Some(debugger.install()?)
+    } else {
+        None
     };
 
-    cleanup_trace();
+    let (_, passed, coverage) = if compute_coverage {
+        unit_test_config
+            .run_and_report_unit_tests_with_coverage(test_plan, Some(natives), std::io::stdout())
+            .unwrap()
+    } else {
+        let (writer, passed) = unit_test_config
+            .run_and_report_unit_tests(test_plan, Some(natives), std::io::stdout())
+            .unwrap();
+        (writer, passed, ExecutionCounts::default())
+    };
 
-    if compute_coverage {
-        std::env::set_var("MOVE_VM_TRACE", &trace_path);
+    // The coverage is written even if tests fail, so that CI can still report it
+    if compute_coverage && !no_tests {
+        let coverage_map = CoverageMap::from_execution_counts("unit_tests", &coverage);
+        output_map_to_file(&coverage_map_path, &coverage_map).unwrap();
+        write_coverage_reports(pkg_path, coverage_build_config, &source_files, &coverage)?;
     }
-
-    if !unit_test_config
-        .run_and_report_unit_tests(test_plan, Some(natives), std::io::stdout())
-        .unwrap()
-        .1
-    {
-        cleanup_trace();
+    if !passed {
         return Ok(UnitTestResult::Failure);
     }
+    Ok(UnitTestResult::Success)
+}
 
-    if compute_coverage && !no_tests {
-        let coverage_map = CoverageMap::from_trace_file(trace_path);
-        output_map_to_file(&coverage_map_path, &coverage_map).unwrap();
+/// Writes the line and branch coverage of the modules of the package at `pkg_path` in the lcov and
+/// Cobertura XML formats, under `build/coverage`. Sources are looked up in `source_files`, the
+/// files the tests were compiled from, so that reports point to the package sources rather than
+/// to their copies in the build directory.
+fn write_coverage_reports(
+    pkg_path: &Path,
+    build_config: move_package::BuildConfig,
+    source_files: &FilesSourceText,
+    coverage: &ExecutionCounts,
+) -> Result<()> {
+    let package = build_config.compile_package(pkg_path, &mut Vec::new())?;
+    let mut modules = vec![];
+    for unit in package.modules()? {
+        let (module, source_map) = match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) => (module, source_map),
+            CompiledUnit::Script(_) => continue,
+        };
+        let (source_path, source) =
+            match source_files.get(&source_map.definition_location.file_hash()) {
+                Some((name, contents)) => (PathBuf::from(name.as_str()), contents.clone()),
+                None => (unit.source_path.clone(), read_to_string(&unit.source_path)?),
+            };
+        let source_path = source_path.canonicalize().unwrap_or(source_path);
+        modules.push(ModuleLineCoverage::new(
+            module,
+            source_map,
+            source_path,
+            &source,
+            coverage.modules.get(&module.self_id()),
+        ));
+    }
+
+    let coverage_dir = CompiledPackageLayout::Root.path().join("coverage");
+    create_dir_all(pkg_path.join(&coverage_dir))?;
+    let lcov_path = coverage_dir.join("lcov.info");
+    write_lcov(&modules, &mut File::create(pkg_path.join(&lcov_path))?)?;
+    let cobertura_path = coverage_dir.join("cobertura.xml");
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    write_cobertura(
+        &modules,
+        &pkg_path.canonicalize()?,
+        timestamp,
+        &mut File::create(pkg_path.join(&cobertura_path))?,
+    )?;
+    println!(
+        "Wrote coverage reports to {} and {}",
+        lcov_path.display(),
+        cobertura_path.display()
+    );
+    Ok(())
+}
+
+/// Formats the Move sources of the package at `pkg_path` in place or, if `check` is set, prints the
+/// changes formatting would make. Returns false if `check` is set and some files are not formatted.
+pub fn format_package(pkg_path: &Path, check: bool) -> Result<bool> {
+    let dirs = [
+        SourcePackageLayout::Sources,
+        SourcePackageLayout::Scripts,
+        SourcePackageLayout::Examples,
+        SourcePackageLayout::Tests,
+        SourcePackageLayout::Specifications,
+    ]
+    .iter()
+    .map(|layout| pkg_path.join(layout.path()))
+    .filter(|dir| dir.is_dir())
+    .collect::<Vec<_>>();
+    let mut files = find_move_filenames(&dirs, false)?;
+    files.sort();
+
+    let mut unformatted = vec![];
+    for file in files {
+        let source = read_to_string(&file)?;
+        let formatted = move_fmt::format_source(&file, &source)?;
+        if formatted == source {
+            continue;
+        }
+        if check {
+            print_format_diff(&file, &source, &formatted);
+        } else {
+            std::fs::write(&file, formatted)?;
+        }
+        unformatted.push(file);
+    }
+    if check && !unformatted.is_empty() {
+        println!(
+            "{} file(s) are not formatted: {}",
+            unformatted.len(),
+            unformatted.join(", ")
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Prints the lines of `file` that formatting changes, before and after formatting.
+fn print_format_diff(file: &str, source: &str, formatted: &str) {
+    let Changeset { diffs, .. } = Changeset::new(source, formatted, "\n");
+    let mut line = 1;
+    let mut in_hunk = false;
+    for diff in &diffs {
+        let (prefix, text) = match diff {
+            Difference::Same(text) => {
+                line += text.split('\n').count();
+                in_hunk = false;
+                continue;
+            }
+            Difference::Rem(text) => ("-", text),
+            Difference::Add(text) => ("+", text),
+        };
+        if !in_hunk {
+            println!("Diff in {} at line {}:", file, line);
+            in_hunk = true;
+        }
+        for text_line in text.split('\n') {
+            println!("{}{}", prefix, text_line);
+        }
+        if prefix == "-" {
+            line += text.split('\n').count();
+        }
     }
-    Ok(UnitTestResult::Success)
 }
 
 pub fn create_move_package<S: AsRef<str> + Display>(name: S, creation_path: &Path) -> Result<()> {
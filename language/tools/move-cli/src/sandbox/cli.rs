@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    debugger::check_debugger_supported,
     sandbox::{
         self,
         utils::{on_disk_state_view::OnDiskStateView, PackageContext},
@@ -77,6 +78,11 @@ pub enum SandboxCommand {
         /// deleted resources) will NOT be committed to disk.
         #[structopt(long = "dry-run", short = "n")]
         dry_run: bool,
+        /// Debug the execution interactively: pause before the first line of Move code executed,
+        /// then set breakpoints, step through the code, and print locals and global state.
+        /// Requires a debug build of the CLI.
+        #[structopt(long = "debug")]
+        debug: bool,
     },
     /// Run expected value tests using the given batch file.
     #[structopt(name = "exp-test")]
@@ -171,7 +177,11 @@ impl SandboxCommand {
                 type_args,
                 gas_budget,
                 dry_run,
+                debug,
             } => {
+                if *debug {
+                    check_debugger_supported()?;
+                }
                 let context =
                     PackageContext::new(&move_args.package_path, &move_args.build_config)?;
                 let state = context.prepare_state(storage_dir)?;
@@ -188,6 +198,7 @@ impl SandboxCommand {
                     *gas_budget,
                     *dry_run,
                     move_args.verbose,
+                    *debug,
                 )
             }
             SandboxCommand::Test {
@@ -53,7 +53,7 @@ pub fn publish(
     if !ignore_breaking_changes {
         let vm = MoveVM::new(natives).unwrap();
         let mut gas_status = get_gas_status(None)?;
-        let mut session = vm.new_session(state);
+        let mut session = vm.new_session_with_id(state, state.next_session_id()?);
 
         let mut has_error = false;
         match override_ordering {
@@ -122,6 +122,7 @@ pub fn publish(
                 .map(|(module_id, blob_opt)| (module_id, blob_opt.expect("must be non-deletion")))
                 .collect();
             state.save_modules(&modules)?;
+            state.commit_session()?;
         }
     } else {
         // NOTE: the VM enforces the most strict way of module republishing and does not allow
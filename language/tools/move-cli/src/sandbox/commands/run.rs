@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    debugger::SourceDebugger,
     sandbox::utils::{
         contains_module, explain_execution_effects, explain_execution_error, get_gas_status,
         is_bytecode_file, maybe_commit_effects, on_disk_state_view::OnDiskStateView,
@@ -17,8 +18,9 @@ use move_core_types::{
     language_storage::TypeTag,
     transaction_argument::{convert_txn_args, TransactionArgument},
 };
+use move_lang::compiled_unit::CompiledUnit;
 use move_package::compilation::compiled_package::CompiledPackage;
-use move_vm_runtime::move_vm::MoveVM;
+use move_vm_runtime::{debugger::DebuggerGuard, move_vm::MoveVM};
 use std::{fs, path::Path};
 
 pub fn run(
@@ -34,6 +36,7 @@ pub fn run(
     gas_budget: Option<u64>,
     dry_run: bool,
     verbose: bool,
+    debug: bool,
 ) -> Result<()> {
     if !script_path.exists() {
         bail!("Script file {:?} does not exist", script_path)
@@ -67,9 +70,16 @@ move run` must be applied to a module inside `storage/`",
     // TODO: parse Value's directly instead of going through the indirection of TransactionArgument?
     let vm_args: Vec<Vec<u8>> = convert_txn_args(txn_args);
 
+    // Uninstalls the debugger once the script has run, even if running it panics
+    let debugger_guard = if debug {
+        Some(install_debugger(package, script_path)?)
+    } else {
+        None
+    };
+
     let vm = MoveVM::new(natives).unwrap();
     let mut gas_status = get_gas_status(gas_budget)?;
-    let mut session = vm.new_session(state);
+    let mut session = vm.new_session_with_id(state, state.next_session_id()?);
 
     let script_type_parameters = vec![];
     let script_parameters = vec![];
@@ -97,6 +107,7 @@ move run` must be applied to a module inside `storage/`",
             &mut gas_status,
         ),
     };
+    drop(debugger_guard);
 
     if let Err(err) = res {
         explain_execution_error(
@@ -117,3 +128,24 @@ move run` must be applied to a module inside `storage/`",
         maybe_commit_effects(!dry_run, changeset, events, state)
     }
 }
+
+/// Install a debugger for the modules of `package` and its dependencies, and for the script at
+/// `script_path` if it is one of the package's scripts.
+fn install_debugger(package: &CompiledPackage, script_path: &Path) -> Result<DebuggerGuard> {
+    let mut debugger = SourceDebugger::default();
+    for unit in package.transitive_compiled_units_with_source() {
+        let text = fs::read_to_string(&unit.source_path)?;
+        debugger.add_source_file(&unit.source_path, text);
+        match unit.unit {
+            CompiledUnit::Module(module) => {
+                debugger.add_module(&module.module, module.source_map)?;
+            }
+            CompiledUnit::Script(script) => {
+                if unit.source_path.file_stem() == script_path.file_stem() {
+                    debugger.add_script(script.source_map);
+                }
+            }
+        }
+    }
+    debugger.install()
+}
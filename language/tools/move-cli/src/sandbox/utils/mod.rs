@@ -232,6 +232,27 @@ pub(crate) fn explain_execution_effects(
             total_bytes_written += bytes_to_write;
         }
     }
+    for (handle, table) in changeset.tables() {
+        println!("Changed {:?} item(s) of table {}:", table.len(), handle);
+        for (key, blob_opt) in table {
+            print!("    ");
+            let mut bytes_to_write = key.len();
+            match blob_opt {
+                Some(blob) => {
+                    bytes_to_write += blob.len();
+                    println!(
+                        "Wrote item with key {:?}: {:?} (wrote {:?} bytes)",
+                        key, blob, bytes_to_write
+                    );
+                }
+                None => println!(
+                    "Deleted item with key {:?} (wrote {:?} bytes)",
+                    key, bytes_to_write
+                ),
+            }
+            total_bytes_written += bytes_to_write;
+        }
+    }
     if total_bytes_written != 0 {
         println!(
             "Wrote {:?} bytes of resource ID's and data",
@@ -252,7 +273,8 @@ pub(crate) fn maybe_commit_effects(
     // similar to explain effects, all module publishing happens via save_modules(), so effects
     // shouldn't contain modules
     if commit {
-        for (addr, account) in changeset.into_inner() {
+        let (accounts, tables) = changeset.into_inner();
+        for (addr, account) in accounts {
             for (struct_tag, blob_opt) in account.into_resources() {
                 match blob_opt {
                     Some(blob) => state.save_resource(addr, struct_tag, &blob)?,
@@ -261,10 +283,23 @@ pub(crate) fn maybe_commit_effects(
             }
         }
 
+        for (handle, table) in tables {
+            for (key, blob_opt) in table {
+                match blob_opt {
+                    Some(blob) => state.save_table_item(&handle, &key, &blob)?,
+                    None => state.delete_table_item(&handle, &key)?,
+                }
+            }
+        }
+
         for (event_key, event_sequence_number, event_type, event_data) in events {
             state.save_event(&event_key, event_sequence_number, event_type, event_data)?
         }
-    } else if !(changeset.resources().next().is_none() && events.is_empty()) {
+        state.commit_session()?;
+    } else if !(changeset.resources().next().is_none()
+        && changeset.table_items().next().is_none()
+        && events.is_empty())
+    {
         println!("Discarding changes; re-run without --dry-run if you would like to keep them.")
     }
 
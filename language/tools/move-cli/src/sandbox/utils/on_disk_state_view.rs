@@ -13,9 +13,9 @@ use move_command_line_common::files::MOVE_COMPILED_EXTENSION;
 use move_core_types::{
     account_address::AccountAddress,
     identifier::Identifier,
-    language_storage::{ModuleId, StructTag, TypeTag},
+    language_storage::{ModuleId, StructTag, TableHandle, TypeTag},
     parser,
-    resolver::{ModuleResolver, ResourceResolver},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
     value::MoveStructLayout,
 };
 use move_disassembler::disassembler::Disassembler;
@@ -35,8 +35,12 @@ pub const RESOURCES_DIR: &str = "resources";
 pub const MODULES_DIR: &str = "modules";
 /// subdirectory of `DEFAULT_STORAGE_DIR`/<addr> where events are stored
 pub const EVENTS_DIR: &str = "events";
+/// subdirectory of `DEFAULT_STORAGE_DIR`/<handle> where the items of a table are stored
+pub const TABLES_DIR: &str = "tables";
 /// subdirectory of `DEFAULT_BUILD_DIR`/<addr> where generated struct layouts are stored
 pub const STRUCT_LAYOUTS_DIR: &str = "struct_layouts";
+/// file in `DEFAULT_STORAGE_DIR` counting the sessions whose effects were committed
+pub const SESSION_COUNTER_FILE: &str = "session_counter";
 
 #[derive(Debug)]
 pub struct OnDiskStateView {
@@ -97,6 +101,36 @@ impl OnDiskStateView {
         self.is_data_path(p, MODULES_DIR)
     }
 
+    pub fn is_table_item_path(&self, p: &Path) -> bool {
+        self.is_data_path(p, TABLES_DIR)
+    }
+
+    /// Return the id of the next session whose effects are committed, so that the tables it
+    /// creates get handles distinct from those of all the previously committed sessions.
+    pub fn next_session_id(&self) -> Result<Vec<u8>> {
+        Ok(self.get_session_counter()?.to_le_bytes().to_vec())
+    }
+
+    /// Record that the effects of the session with id `next_session_id()` were committed.
+    pub fn commit_session(&self) -> Result<()> {
+        let counter = self.get_session_counter()? + 1;
+        Ok(fs::write(
+            self.storage_dir.join(SESSION_COUNTER_FILE),
+            counter.to_string(),
+        )?)
+    }
+
+    fn get_session_counter(&self) -> Result<u64> {
+        let path = self.storage_dir.join(SESSION_COUNTER_FILE);
+        if !path.exists() {
+            return Ok(0);
+        }
+        fs::read_to_string(&path)?
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("Malformed session counter in {:?}", path))
+    }
+
     fn get_addr_path(&self, addr: &AccountAddress) -> PathBuf {
         let mut path = self.storage_dir.clone();
         path.push(format!("0x{}", addr.to_string()));
@@ -110,6 +144,14 @@ impl OnDiskStateView {
         path.with_extension(BCS_EXTENSION)
     }
 
+    // Table items are stored under table handle/hex-encoded key
+    fn get_table_item_path(&self, handle: &TableHandle, key: &[u8]) -> PathBuf {
+        let mut path = self.get_addr_path(&handle.0);
+        path.push(TABLES_DIR);
+        path.push(hex::encode(key));
+        path.with_extension(BCS_EXTENSION)
+    }
+
     // Events are stored under address/handle creation number
     fn get_event_path(&self, key: &[u8]) -> PathBuf {
         // TODO: this is a hacky way to get the account address and creation number from the event key.
@@ -157,6 +199,15 @@ impl OnDiskStateView {
         Self::get_bytes(&self.get_resource_path(addr, tag))
     }
 
+    /// Read the bytes of the table item stored on-disk at `handle`/`key`
+    pub fn get_table_item_bytes(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        Self::get_bytes(&self.get_table_item_path(handle, key))
+    }
+
     /// Read the resource bytes stored on-disk at `addr`/`tag`
     fn get_module_bytes(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>> {
         Self::get_bytes(&self.get_module_path(module_id))
@@ -299,6 +350,36 @@ impl OnDiskStateView {
         Ok(fs::write(path, bcs_bytes)?)
     }
 
+    /// Delete the table item stored on disk at the path `handle`/`key`
+    pub fn delete_table_item(&self, handle: &TableHandle, key: &[u8]) -> Result<()> {
+        let path = self.get_table_item_path(handle, key);
+        fs::remove_file(path)?;
+
+        // delete the directories of the table if it is now empty
+        let tables_path = self.get_addr_path(&handle.0).join(TABLES_DIR);
+        if tables_path.read_dir()?.next().is_none() {
+            fs::remove_dir(tables_path)?
+        }
+        let addr_path = self.get_addr_path(&handle.0);
+        if addr_path.read_dir()?.next().is_none() {
+            fs::remove_dir(addr_path)?
+        }
+        Ok(())
+    }
+
+    pub fn save_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+        bcs_bytes: &[u8],
+    ) -> Result<()> {
+        let path = self.get_table_item_path(handle, key);
+        if !path.exists() {
+            fs::create_dir_all(path.parent().unwrap())?;
+        }
+        Ok(fs::write(path, bcs_bytes)?)
+    }
+
     pub fn save_event(
         &self,
         event_key: &[u8],
@@ -388,6 +469,10 @@ impl OnDiskStateView {
         self.iter_paths(move |p| self.is_event_path(p))
     }
 
+    pub fn table_item_paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.iter_paths(move |p| self.is_table_item_path(p))
+    }
+
     /// Build all modules in the self.storage_dir.
     /// Returns an Err if a module does not deserialize.
     pub fn get_all_modules(&self) -> Result<Vec<CompiledModule>> {
@@ -419,6 +504,18 @@ impl ResourceResolver for OnDiskStateView {
     }
 }
 
+impl TableResolver for OnDiskStateView {
+    type Error = anyhow::Error;
+
+    fn get_table_item(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.get_table_item_bytes(handle, key)
+    }
+}
+
 impl GetModule for OnDiskStateView {
     type Error = anyhow::Error;
     type Item = CompiledModule;
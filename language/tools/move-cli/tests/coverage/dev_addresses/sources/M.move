@@ -0,0 +1,10 @@
+module A::M {
+    public fun sign(x: u64): u64 {
+        if (x > 0) 1 else 0
+    }
+
+    #[test]
+    fun positive() {
+        assert!(sign(2) == 1, 0)
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_cli::package::cli::{run_move_unit_tests, UnitTestResult};
+use move_command_line_common::testing::{format_diff, read_env_update_baseline, EXP_EXT};
+use move_core_types::account_address::AccountAddress;
+use move_package::BuildConfig;
+use move_unit_test::UnitTestingConfig;
+use std::{fs, path::Path};
+use tempfile::tempdir;
+
+/// Runs the unit tests of a copy of the package at `path` with coverage, and compares the lcov
+/// report written for it to the `lcov.info.exp` file in `path`, with the paths of the package's
+/// sources made relative to the package.
+fn check_lcov_report(path: &Path) {
+    let work_dir = tempdir().unwrap();
+    let pkg_path = work_dir.path().canonicalize().unwrap();
+    for entry in walkdir::WalkDir::new(path) {
+        let entry = entry.unwrap();
+        let target = pkg_path.join(entry.path().strip_prefix(path).unwrap());
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(target).unwrap();
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+    // Local dependencies stay where they are relative to the original package
+    let manifest = fs::read_to_string(path.join("Move.toml")).unwrap().replace(
+        "local = \"",
+        &format!("local = \"{}/", path.canonicalize().unwrap().display()),
+    );
+    fs::write(pkg_path.join("Move.toml"), manifest).unwrap();
+
+    let result = run_move_unit_tests(
+        &pkg_path,
+        BuildConfig::default(),
+        UnitTestingConfig::default_with_bound(None),
+        move_stdlib::natives::all_natives(AccountAddress::from_hex_literal("0x1").unwrap()),
+        true,
+        false,
+    )
+    .unwrap();
+    assert!(result == UnitTestResult::Success);
+
+    let lcov = fs::read_to_string(pkg_path.join("build/coverage/lcov.info"))
+        .unwrap()
+        .replace(&format!("{}/", pkg_path.display()), "");
+    let exp_path = path.join(format!("lcov.info.{}", EXP_EXT));
+    if read_env_update_baseline() {
+        fs::write(&exp_path, lcov).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&exp_path).unwrap_or_default();
+    assert!(
+        expected == lcov,
+        "Expected lcov report differs from actual report for {}:\n{}",
+        path.display(),
+        format_diff(&expected, &lcov)
+    );
+}
+
+#[test]
+fn coverage_of_package_with_dev_addresses() {
+    check_lcov_report(Path::new("tests/coverage/dev_addresses"));
+}
@@ -0,0 +1,17 @@
+module A::Coin {
+    struct Coin has key { value: u64 }
+
+    public fun value(_account: &signer, addr: address): u64 acquires Coin {
+        assert!(exists<Coin>(addr), 1);
+        borrow_global<Coin>(addr).value
+    }
+
+    #[allow(unused_signer)]
+    public fun zero(_account: &signer): u64 {
+        0
+    }
+
+    fun check(addr: address) acquires Coin {
+        borrow_global<Coin>(addr);
+    }
+}
@@ -0,0 +1,13 @@
+module Std::Counter {
+    struct Counter has key { value: u64 }
+
+    /// Publish a counter under `account`.
+    public fun publish(account: &signer) {
+      move_to(account, Counter{value:0})
+    }
+
+    public fun increment(addr: address) acquires Counter {
+        let counter = borrow_global_mut<Counter>(addr);
+        counter.value = counter.value+1;
+    }
+}
@@ -0,0 +1,10 @@
+#[test_only]
+module Std::CounterTests {
+    use Std::Counter;
+
+    #[test(account = @0x1)]
+    fun increment(account: signer) {
+        Counter::publish(&account);
+        Counter::increment(@0x1);
+    }
+}
@@ -9,6 +9,7 @@ use move_core_types::{
     account_address::AccountAddress,
     identifier::{IdentStr, Identifier},
 };
+use move_vm_runtime::coverage::ExecutionCounts;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
@@ -92,6 +93,28 @@ impl CoverageMap {
         empty_module_map.update_coverage_from_trace_file(filename)
     }
 
+    /// Takes in the executions counted by the VM while collecting coverage, and returns a coverage
+    /// map with a single execution `exec_id`.
+    pub fn from_execution_counts(exec_id: &str, counts: &ExecutionCounts) -> Self {
+        let mut exec_map = ExecCoverageMap::new(exec_id.to_owned());
+        for (module_id, functions) in &counts.modules {
+            for (func_name, func_counts) in functions {
+                for (pc, count) in &func_counts.instructions {
+                    exec_map.insert_multi(
+                        *module_id.address(),
+                        module_id.name().to_owned(),
+                        func_name.clone(),
+                        *pc as u64,
+                        *count,
+                    );
+                }
+            }
+        }
+        let mut exec_maps = BTreeMap::new();
+        exec_maps.insert(exec_id.to_owned(), exec_map);
+        CoverageMap { exec_maps }
+    }
+
     /// Takes in a file containing a serialized coverage map and returns a coverage map.
     pub fn from_binary_file<P: AsRef<Path>>(filename: P) -> Self {
         let mut bytes = Vec::new();
@@ -0,0 +1,432 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Line and branch coverage of Move source files, computed by mapping the instructions executed
+//! by the VM through the source maps of the modules, and reported in the lcov tracefile and
+//! Cobertura XML formats understood by most CI coverage tools.
+//!
+//! A line is covered if any instruction whose source location starts on it was executed. Every
+//! `BrTrue` and `BrFalse` instruction is a branch point with two branches: jumping to its target
+//! and falling through to the next instruction.
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+    CompiledModule,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_core_types::identifier::Identifier;
+use move_vm_runtime::coverage::FunctionCounts;
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The coverage of a conditional branch instruction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BranchCoverage {
+    pub line: u32,
+    /// The code offset of the branch instruction
+    pub offset: CodeOffset,
+    /// How many times the branch jumped to its target and fell through, or `None` if the branch
+    /// instruction was never executed
+    pub taken_and_not_taken: Option<(u64, u64)>,
+}
+
+impl BranchCoverage {
+    fn covered(&self) -> usize {
+        match self.taken_and_not_taken {
+            None => 0,
+            Some((taken, not_taken)) => (taken > 0) as usize + (not_taken > 0) as usize,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FunctionLineCoverage {
+    pub name: Identifier,
+    /// The line of the function declaration
+    pub line: u32,
+    /// How many times the function was called
+    pub calls: u64,
+    /// Executions of every line with code in the function, by line number
+    pub lines: BTreeMap<u32, u64>,
+    pub branches: Vec<BranchCoverage>,
+}
+
+/// The line and branch coverage of a module. Line numbers start at 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModuleLineCoverage {
+    /// The name of the module, e.g. `0x1::Vector`
+    pub name: String,
+    pub source_path: PathBuf,
+    /// Native functions are not included
+    pub functions: Vec<FunctionLineCoverage>,
+}
+
+/// Totals over a set of lines and branches
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CoverageTotals {
+    pub lines: usize,
+    pub lines_covered: usize,
+    pub branches: usize,
+    pub branches_covered: usize,
+}
+
+impl CoverageTotals {
+    fn of<'a>(
+        lines: &BTreeMap<u32, u64>,
+        branches: impl IntoIterator<Item = &'a BranchCoverage>,
+    ) -> Self {
+        let mut totals = CoverageTotals {
+            lines: lines.len(),
+            lines_covered: lines.values().filter(|hits| **hits > 0).count(),
+            ..CoverageTotals::default()
+        };
+        for branch in branches {
+            totals.branches += 2;
+            totals.branches_covered += branch.covered();
+        }
+        totals
+    }
+
+    fn add(&mut self, other: CoverageTotals) {
+        self.lines += other.lines;
+        self.lines_covered += other.lines_covered;
+        self.branches += other.branches;
+        self.branches_covered += other.branches_covered;
+    }
+
+    pub fn line_rate(&self) -> f64 {
+        rate(self.lines_covered, self.lines)
+    }
+
+    pub fn branch_rate(&self) -> f64 {
+        rate(self.branches_covered, self.branches)
+    }
+}
+
+fn rate(covered: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        covered as f64 / total as f64
+    }
+}
+
+impl ModuleLineCoverage {
+    /// Compute the coverage of `module`, compiled from the file at `source_path` with the contents
+    /// `source`, from the executions of its functions. Instructions with a location outside of
+    /// the file are skipped.
+    pub fn new(
+        module: &CompiledModule,
+        source_map: &SourceMap,
+        source_path: PathBuf,
+        source: &str,
+        counts: Option<&BTreeMap<Identifier, FunctionCounts>>,
+    ) -> Self {
+        let module_id = module.self_id();
+        let file_hash = source_map.definition_location.file_hash();
+        let line_starts: Vec<u32> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i as u32 + 1))
+            .collect();
+        let line_of = |byte: u32| line_starts.partition_point(|start| *start <= byte) as u32;
+
+        let no_counts = FunctionCounts::default();
+        let functions = module
+            .function_defs()
+            .iter()
+            .enumerate()
+            .filter_map(|(function_def_idx, function_def)| {
+                let code = &function_def.code.as_ref()?.code;
+                let function_def_idx = FunctionDefinitionIndex(function_def_idx as u16);
+                let fn_handle = module.function_handle_at(function_def.function);
+                let name = module.identifier_at(fn_handle.name).to_owned();
+                let fn_counts = counts.and_then(|c| c.get(&name)).unwrap_or(&no_counts);
+                let executions =
+                    |offset: CodeOffset| fn_counts.instructions.get(&offset).copied().unwrap_or(0);
+
+                let mut lines = BTreeMap::new();
+                let mut branches = vec![];
+                for (offset, instruction) in code.iter().enumerate() {
+                    let offset = offset as CodeOffset;
+                    let loc = match source_map.get_code_location(function_def_idx, offset) {
+                        Ok(loc) if loc.file_hash() == file_hash => loc,
+                        _ => continue,
+                    };
+                    let line = line_of(loc.start());
+                    let hits = lines.entry(line).or_insert(0);
+                    *hits = executions(offset).max(*hits);
+
+                    if matches!(instruction, Bytecode::BrTrue(_) | Bytecode::BrFalse(_)) {
+                        let executed = executions(offset);
+                        let taken = fn_counts.branches_taken.get(&offset).copied().unwrap_or(0);
+                        branches.push(BranchCoverage {
+                            line,
+                            offset,
+                            taken_and_not_taken: if executed == 0 {
+                                None
+                            } else {
+                                Some((taken, executed - taken))
+                            },
+                        })
+                    }
+                }
+                let line = source_map
+                    .get_function_source_map(function_def_idx)
+                    .map_or(0, |function_map| {
+                        line_of(function_map.definition_location.start())
+                    });
+
+                Some(FunctionLineCoverage {
+                    name,
+                    line,
+                    calls: executions(0),
+                    lines,
+                    branches,
+                })
+            })
+            .collect();
+
+        Self {
+            name: format!(
+                "0x{}::{}",
+                module_id.address().short_str_lossless(),
+                module_id.name()
+            ),
+            source_path,
+            functions,
+        }
+    }
+
+    /// Executions of every line with code in the module, by line number
+    pub fn lines(&self) -> BTreeMap<u32, u64> {
+        let mut lines = BTreeMap::new();
+        for function in &self.functions {
+            for (line, hits) in &function.lines {
+                let entry = lines.entry(*line).or_insert(0);
+                *entry = (*hits).max(*entry);
+            }
+        }
+        lines
+    }
+
+    pub fn branches(&self) -> impl Iterator<Item = &BranchCoverage> {
+        self.functions
+            .iter()
+            .flat_map(|function| function.branches.iter())
+    }
+
+    pub fn totals(&self) -> CoverageTotals {
+        CoverageTotals::of(&self.lines(), self.branches())
+    }
+}
+
+impl FunctionLineCoverage {
+    pub fn totals(&self) -> CoverageTotals {
+        CoverageTotals::of(&self.lines, &self.branches)
+    }
+}
+
+/// Write the coverage of `modules` as an lcov tracefile, with one record per source file.
+pub fn write_lcov<W: Write>(modules: &[ModuleLineCoverage], writer: &mut W) -> io::Result<()> {
+    let mut files: BTreeMap<&Path, Vec<&ModuleLineCoverage>> = BTreeMap::new();
+    for module in modules {
+        files
+            .entry(module.source_path.as_path())
+            .or_default()
+            .push(module);
+    }
+
+    for (source_path, modules) in files {
+        writeln!(writer, "TN:")?;
+        writeln!(writer, "SF:{}", source_path.display())?;
+        let functions: Vec<_> = modules
+            .iter()
+            .flat_map(|module| {
+                let module_name = module.name.rsplit("::").next().unwrap_or(&module.name);
+                module
+                    .functions
+                    .iter()
+                    .map(move |function| (format!("{}::{}", module_name, function.name), function))
+            })
+            .collect();
+        for (name, function) in &functions {
+            writeln!(writer, "FN:{},{}", function.line, name)?;
+        }
+        for (name, function) in &functions {
+            writeln!(writer, "FNDA:{},{}", function.calls, name)?;
+        }
+        writeln!(writer, "FNF:{}", functions.len())?;
+        writeln!(
+            writer,
+            "FNH:{}",
+            functions.iter().filter(|(_, f)| f.calls > 0).count()
+        )?;
+
+        let mut lines = BTreeMap::new();
+        let mut totals = CoverageTotals::default();
+        for module in &modules {
+            for branch in module.branches() {
+                // Branch 0 jumps to the target of the instruction, branch 1 falls through
+                let counts = match branch.taken_and_not_taken {
+                    Some((taken, not_taken)) => [taken.to_string(), not_taken.to_string()],
+                    None => ["-".to_string(), "-".to_string()],
+                };
+                for (index, count) in counts.iter().enumerate() {
+                    let (line, block) = (branch.line, branch.offset);
+                    writeln!(writer, "BRDA:{},{},{},{}", line, block, index, count)?;
+                }
+            }
+            lines.extend(module.lines());
+            totals.add(module.totals());
+        }
+        writeln!(writer, "BRF:{}", totals.branches)?;
+        writeln!(writer, "BRH:{}", totals.branches_covered)?;
+        for (line, hits) in &lines {
+            writeln!(writer, "DA:{},{}", line, hits)?;
+        }
+        writeln!(writer, "LF:{}", totals.lines)?;
+        writeln!(writer, "LH:{}", totals.lines_covered)?;
+        writeln!(writer, "end_of_record")?;
+    }
+    Ok(())
+}
+
+/// Write the coverage of `modules` as a Cobertura XML report, with one package of a single class
+/// per module, so that coverage can be checked per module. File names are relative to
+/// `source_root`, and `timestamp` is in milliseconds since the Unix epoch.
+pub fn write_cobertura<W: Write>(
+    modules: &[ModuleLineCoverage],
+    source_root: &Path,
+    timestamp: u128,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut totals = CoverageTotals::default();
+    for module in modules {
+        totals.add(module.totals());
+    }
+
+    writeln!(writer, r#"<?xml version="1.0" ?>"#)?;
+    writeln!(
+        writer,
+        r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+    )?;
+    writeln!(
+        writer,
+        r#"<coverage line-rate="{:.4}" branch-rate="{:.4}" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}" complexity="0" version="{}" timestamp="{}">"#,
+        totals.line_rate(),
+        totals.branch_rate(),
+        totals.lines_covered,
+        totals.lines,
+        totals.branches_covered,
+        totals.branches,
+        env!("CARGO_PKG_VERSION"),
+        timestamp
+    )?;
+    writeln!(writer, "  <sources>")?;
+    writeln!(
+        writer,
+        "    <source>{}</source>",
+        escape_xml(&source_root.display().to_string())
+    )?;
+    writeln!(writer, "  </sources>")?;
+    writeln!(writer, "  <packages>")?;
+    for module in modules {
+        let module_totals = module.totals();
+        let name = escape_xml(&module.name);
+        let filename = module
+            .source_path
+            .strip_prefix(source_root)
+            .unwrap_or(&module.source_path);
+        writeln!(
+            writer,
+            r#"    <package name="{}" line-rate="{:.4}" branch-rate="{:.4}" complexity="0">"#,
+            name,
+            module_totals.line_rate(),
+            module_totals.branch_rate()
+        )?;
+        writeln!(writer, "      <classes>")?;
+        writeln!(
+            writer,
+            r#"        <class name="{}" filename="{}" line-rate="{:.4}" branch-rate="{:.4}" complexity="0">"#,
+            name,
+            escape_xml(&filename.display().to_string()),
+            module_totals.line_rate(),
+            module_totals.branch_rate()
+        )?;
+        writeln!(writer, "          <methods>")?;
+        for function in &module.functions {
+            let function_totals = function.totals();
+            writeln!(
+                writer,
+                r#"            <method name="{}" signature="" line-rate="{:.4}" branch-rate="{:.4}" complexity="0">"#,
+                function.name,
+                function_totals.line_rate(),
+                function_totals.branch_rate()
+            )?;
+            write_cobertura_lines(
+                &function.lines,
+                function.branches.iter(),
+                "              ",
+                writer,
+            )?;
+            writeln!(writer, "            </method>")?;
+        }
+        writeln!(writer, "          </methods>")?;
+        write_cobertura_lines(&module.lines(), module.branches(), "          ", writer)?;
+        writeln!(writer, "        </class>")?;
+        writeln!(writer, "      </classes>")?;
+        writeln!(writer, "    </package>")?;
+    }
+    writeln!(writer, "  </packages>")?;
+    writeln!(writer, "</coverage>")?;
+    Ok(())
+}
+
+fn write_cobertura_lines<'a, W: Write>(
+    lines: &BTreeMap<u32, u64>,
+    branches: impl Iterator<Item = &'a BranchCoverage>,
+    indent: &str,
+    writer: &mut W,
+) -> io::Result<()> {
+    // Covered and total branches, by line
+    let mut line_branches: BTreeMap<u32, (usize, usize)> = BTreeMap::new();
+    for branch in branches {
+        let entry = line_branches.entry(branch.line).or_default();
+        entry.0 += branch.covered();
+        entry.1 += 2;
+    }
+
+    writeln!(writer, "{}<lines>", indent)?;
+    for (line, hits) in lines {
+        match line_branches.get(line) {
+            None => writeln!(
+                writer,
+                r#"{}  <line number="{}" hits="{}" branch="false"/>"#,
+                indent, line, hits
+            )?,
+            Some((covered, total)) => writeln!(
+                writer,
+                r#"{}  <line number="{}" hits="{}" branch="true" condition-coverage="{}% ({}/{})"/>"#,
+                indent,
+                line,
+                hits,
+                covered * 100 / total,
+                covered,
+                total
+            )?,
+        }
+    }
+    writeln!(writer, "{}</lines>", indent)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
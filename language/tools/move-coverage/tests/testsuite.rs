@@ -0,0 +1,104 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{access::ModuleAccess, file_format::Visibility};
+use move_command_line_common::testing::{format_diff, read_env_update_baseline, EXP_EXT};
+use move_coverage::line_coverage::{write_cobertura, write_lcov, ModuleLineCoverage};
+use move_lang::{compiled_unit::AnnotatedCompiledUnit, Compiler, Flags};
+use move_vm_runtime::{
+    coverage::{finish_coverage_collection, start_coverage_collection},
+    move_vm::MoveVM,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas_schedule::GasStatus;
+use std::{fs, path::Path};
+
+/// Compiles the modules of the file at `path` and calls each of their public functions whose
+/// name starts with `test`, which must take no arguments, while collecting coverage. Aborts are
+/// ignored, so that the coverage of aborting code is also reported. The lcov and Cobertura
+/// reports of the modules are compared to the `.lcov.exp` and `.cobertura.exp` files next to
+/// `path`.
+fn run_test(path: &Path) -> datatest_stable::Result<()> {
+    let (_, units) = Compiler::new(&[path.to_string_lossy().to_string()], &[])
+        .set_flags(Flags::empty().set_sources_shadow_deps(false))
+        .build_and_report()?;
+    let modules: Vec<_> = units
+        .into_iter()
+        .map(|unit| match unit {
+            AnnotatedCompiledUnit::Module(annot_module) => annot_module.named_module,
+            AnnotatedCompiledUnit::Script(_) => panic!("expected modules, got a script"),
+        })
+        .collect();
+
+    let mut storage = InMemoryStorage::new();
+    for named_module in &modules {
+        let mut blob = vec![];
+        named_module.module.serialize(&mut blob)?;
+        storage.publish_or_overwrite_module(named_module.module.self_id(), blob);
+    }
+
+    let vm = MoveVM::new(vec![]).unwrap();
+    start_coverage_collection();
+    for named_module in &modules {
+        let module = &named_module.module;
+        for function_def in module.function_defs() {
+            let fn_handle = module.function_handle_at(function_def.function);
+            let name = module.identifier_at(fn_handle.name);
+            if function_def.visibility != Visibility::Public || !name.as_str().starts_with("test") {
+                continue;
+            }
+            let mut session = vm.new_session(&storage);
+            let _ = session.execute_function(
+                &module.self_id(),
+                name,
+                vec![],
+                vec![],
+                &mut GasStatus::new_unmetered(),
+            );
+        }
+    }
+    let counts = finish_coverage_collection().unwrap();
+
+    let source = fs::read_to_string(path)?;
+    let line_coverage: Vec<_> = modules
+        .iter()
+        .map(|named_module| {
+            ModuleLineCoverage::new(
+                &named_module.module,
+                &named_module.source_map,
+                path.to_path_buf(),
+                &source,
+                counts.modules.get(&named_module.module.self_id()),
+            )
+        })
+        .collect();
+
+    let mut lcov = vec![];
+    write_lcov(&line_coverage, &mut lcov)?;
+    let mut cobertura = vec![];
+    write_cobertura(&line_coverage, path.parent().unwrap(), 0, &mut cobertura)?;
+
+    check_output(path, "lcov", &String::from_utf8(lcov)?)?;
+    check_output(path, "cobertura", &String::from_utf8(cobertura)?)
+}
+
+fn check_output(path: &Path, format: &str, output: &str) -> datatest_stable::Result<()> {
+    let exp_path = path.with_extension(format!("{}.{}", format, EXP_EXT));
+    if read_env_update_baseline() {
+        fs::write(&exp_path, output)?;
+        return Ok(());
+    }
+    let expected = fs::read_to_string(&exp_path).unwrap_or_default();
+    if expected != output {
+        return Err(anyhow::anyhow!(
+            "Expected {} report differs from actual report for {}:\n{}",
+            format,
+            path.display(),
+            format_diff(&expected, output)
+        )
+        .into());
+    }
+    Ok(())
+}
+
+datatest_stable::harness!(run_test, "tests/testsuite", r".*\.move$");
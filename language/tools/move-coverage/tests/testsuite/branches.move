@@ -0,0 +1,32 @@
+module 0x42::Branches {
+    public fun clamp(x: u64): u64 {
+        if (x > 10) {
+            10
+        } else {
+            x
+        }
+    }
+
+    public fun sum_to(n: u64): u64 {
+        let i = 0;
+        let sum = 0;
+        while (i < n) {
+            i = i + 1;
+            sum = sum + i;
+        };
+        sum
+    }
+
+    public fun never_called(b: bool): u64 {
+        if (b) 1 else 0
+    }
+
+    public fun test_clamp() {
+        assert!(clamp(3) == 3, 1);
+        assert!(clamp(5) == 5, 2);
+    }
+
+    public fun test_sum_to() {
+        assert!(sum_to(3) == 6, 3);
+    }
+}
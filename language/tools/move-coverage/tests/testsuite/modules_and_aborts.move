@@ -0,0 +1,18 @@
+module 0x42::A {
+    public fun check(x: u64) {
+        assert!(x < 100, 1);
+    }
+
+    public fun test_passes() {
+        check(1);
+    }
+}
+
+module 0x42::B {
+    use 0x42::A;
+
+    public fun test_aborts() {
+        A::check(100);
+        A::check(0);
+    }
+}
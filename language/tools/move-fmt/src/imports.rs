@@ -0,0 +1,226 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Normalization of the `use` declarations of modules and scripts.
+//!
+//! Consecutive `use` declarations, i.e., declarations that are not separated by a blank line, a
+//! comment or another module member, form a group. Within a group, the imports of the same module
+//! are merged into a single declaration, duplicates are dropped and both declarations and the
+//! members they import are sorted. Declarations with attributes are left alone.
+
+use anyhow::{anyhow, Result};
+use move_command_line_common::files::FileHash;
+use move_lang::parser::{
+    ast::{Definition, ModuleMember, Use, UseDecl},
+    lexer::{Lexer, Tok},
+};
+use move_symbol_pool::Symbol;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
+
+/// Returns `source` with the `use` declarations of its modules and scripts normalized.
+pub(crate) fn normalize(source: &str, defs: &[Definition]) -> Result<String> {
+    let tokens = tokenize(source)?;
+    let mut groups = vec![];
+    for def in defs {
+        match def {
+            Definition::Module(module) => groups.extend(module_groups(source, &tokens, module)),
+            Definition::Address(address) => {
+                for module in &address.modules {
+                    groups.extend(module_groups(source, &tokens, module))
+                }
+            }
+            Definition::Script(script) => {
+                let uses = script.uses.iter().collect::<Vec<_>>();
+                groups.extend(use_groups(source, &tokens, &uses))
+            }
+        }
+    }
+
+    let mut normalized = String::new();
+    let mut last_end = 0;
+    for group in groups {
+        normalized.push_str(&source[last_end..group.span.start]);
+        normalized.push_str(&group.render());
+        last_end = group.span.end;
+    }
+    normalized.push_str(&source[last_end..]);
+    Ok(normalized)
+}
+
+/// The start offset, end offset and kind of every token in `source`.
+fn tokenize(source: &str) -> Result<Vec<(usize, usize, Tok)>> {
+    let mut lexer = Lexer::new(source, FileHash::new(source));
+    let mut tokens = vec![];
+    loop {
+        lexer
+            .advance()
+            .map_err(|_| anyhow!("Unable to tokenize the source"))?;
+        if lexer.peek() == Tok::EOF {
+            return Ok(tokens);
+        }
+        let start = lexer.start_loc();
+        tokens.push((start, start + lexer.content().len(), lexer.peek()));
+    }
+}
+
+fn module_groups(
+    source: &str,
+    tokens: &[(usize, usize, Tok)],
+    module: &move_lang::parser::ast::ModuleDefinition,
+) -> Vec<UseGroup> {
+    let mut groups = vec![];
+    let mut uses = vec![];
+    for member in &module.members {
+        match member {
+            ModuleMember::Use(use_decl) => uses.push(use_decl),
+            _ => groups.extend(use_groups(source, tokens, &std::mem::take(&mut uses))),
+        }
+    }
+    groups.extend(use_groups(source, tokens, &uses));
+    groups
+}
+
+/// Splits a sequence of `use` declarations into groups of consecutive declarations.
+fn use_groups(source: &str, tokens: &[(usize, usize, Tok)], uses: &[&UseDecl]) -> Vec<UseGroup> {
+    let mut groups: Vec<UseGroup> = vec![];
+    for use_decl in uses {
+        if !use_decl.attributes.is_empty() {
+            continue;
+        }
+        let span = match use_span(tokens, use_decl) {
+            Some(span) => span,
+            None => continue,
+        };
+        let module = module_path(source, use_decl);
+        match groups.last_mut() {
+            Some(group) if is_blank_gap(&source[group.span.end..span.start]) => {
+                group.span.end = span.end;
+                group.add(module, &use_decl.use_);
+            }
+            _ => {
+                let mut group = UseGroup::new(span);
+                group.add(module, &use_decl.use_);
+                groups.push(group);
+            }
+        }
+    }
+    groups
+}
+
+/// The source span of a `use` declaration, from the `use` keyword to the closing semicolon.
+fn use_span(tokens: &[(usize, usize, Tok)], use_decl: &UseDecl) -> Option<Range<usize>> {
+    let ident = match &use_decl.use_ {
+        Use::Module(ident, _) | Use::Members(ident, _) => ident,
+    };
+    let ident_start = ident.loc.start() as usize;
+    let ident_idx = tokens
+        .iter()
+        .position(|(start, _, _)| *start == ident_start)?;
+    let (use_start, _, use_tok) = tokens[ident_idx.checked_sub(1)?];
+    if use_tok != Tok::Use {
+        return None;
+    }
+    let (_, semicolon_end, _) = tokens[ident_idx..]
+        .iter()
+        .find(|(_, _, tok)| *tok == Tok::Semicolon)?;
+    Some(use_start..*semicolon_end)
+}
+
+/// Whether two declarations separated by `gap` are consecutive: the gap holds no comments and
+/// no blank lines.
+fn is_blank_gap(gap: &str) -> bool {
+    gap.chars().all(|c| c == ' ' || c == '\t' || c == '\n')
+        && gap.chars().filter(|c| *c == '\n').count() <= 1
+}
+
+/// The path of the imported module as written, e.g., `Std::Vector`.
+fn module_path(source: &str, use_decl: &UseDecl) -> String {
+    let ident = match &use_decl.use_ {
+        Use::Module(ident, _) | Use::Members(ident, _) => ident,
+    };
+    let address = &source[ident.value.address.loc.usize_range()];
+    let address = address.split_whitespace().collect::<String>();
+    format!("{}::{}", address, ident.value.module)
+}
+
+/// The names imported from one module.
+#[derive(Default)]
+struct ModuleImports {
+    /// Whether the module itself is imported under its own name.
+    module: bool,
+    /// The aliases the module itself is imported under.
+    module_aliases: BTreeSet<Symbol>,
+    /// The imported members, with their aliases.
+    members: BTreeSet<(Symbol, Option<Symbol>)>,
+}
+
+impl ModuleImports {
+    fn add_module(&mut self, alias: Option<Symbol>) {
+        match alias {
+            None => self.module = true,
+            Some(alias) => {
+                self.module_aliases.insert(alias);
+            }
+        }
+    }
+}
+
+struct UseGroup {
+    span: Range<usize>,
+    modules: BTreeMap<String, ModuleImports>,
+}
+
+impl UseGroup {
+    fn new(span: Range<usize>) -> Self {
+        Self {
+            span,
+            modules: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, module: String, use_: &Use) {
+        let imports = self.modules.entry(module).or_default();
+        match use_ {
+            Use::Module(_, alias) => imports.add_module(alias.map(|alias| alias.0.value)),
+            Use::Members(_, members) => {
+                for (member, alias) in members {
+                    let alias = alias.map(|alias| alias.value);
+                    if member.value.as_str() == "Self" {
+                        imports.add_module(alias)
+                    } else {
+                        imports.members.insert((member.value, alias));
+                    }
+                }
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut lines = vec![];
+        for (module, imports) in &self.modules {
+            let mut names = vec![];
+            if imports.module {
+                names.push("Self".to_string());
+            }
+            for (member, alias) in &imports.members {
+                names.push(match alias {
+                    Some(alias) => format!("{} as {}", member, alias),
+                    None => member.to_string(),
+                });
+            }
+            match names.len() {
+                0 => (),
+                1 if imports.module => lines.push(format!("use {};", module)),
+                1 => lines.push(format!("use {}::{};", module, names[0])),
+                _ => lines.push(format!("use {}::{{{}}};", module, names.join(", "))),
+            }
+            for alias in &imports.module_aliases {
+                lines.push(format!("use {} as {};", module, alias));
+            }
+        }
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,813 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Token-based layout of Move source code.
+//!
+//! The source is split into tokens and comments, each remembering how many line breaks preceded
+//! it. Lines are then laid out again: tokens on a line are separated by normalized spacing, every
+//! line is indented according to the brackets it is nested in, and a line which continues the
+//! expression or declaration of the previous line gets one extra level of indentation. Lines that
+//! are too long are wrapped by adding line breaks, until all of them fit or cannot be broken any
+//! further.
+
+use crate::{INDENT_WIDTH, MAX_LINE_WIDTH};
+use anyhow::{anyhow, Result};
+use move_command_line_common::files::FileHash;
+use move_lang::parser::lexer::{Lexer, Tok};
+use std::ops::Range;
+
+/// Spec keywords which are lexed as identifiers. They are followed by expressions rather than
+/// being operands themselves, so their spacing is kept as written where it would be ambiguous.
+const SPEC_KEYWORDS: &[&str] = &[
+    "aborts_if",
+    "aborts_with",
+    "assert",
+    "assume",
+    "axiom",
+    "decreases",
+    "emits",
+    "ensures",
+    "except",
+    "include",
+    "modifies",
+    "requires",
+    "succeeds_if",
+    "to",
+    "update",
+    "where",
+    "with",
+];
+
+/// The role a token plays, as far as spacing and wrapping are concerned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Role {
+    Plain,
+    /// A binary operator, surrounded by spaces.
+    BinaryOp,
+    /// A prefix operator: `!`, `&`, `&mut`, `*` or `@`.
+    UnaryOp,
+    /// The `!` of a macro call like `assert!(..)`.
+    MacroBang,
+    /// The `<` opening a list of type arguments or type parameters.
+    GenericOpen,
+    /// A `>` (or `>>`) closing a list of type arguments or type parameters.
+    GenericClose,
+    /// A `*` in a function pattern of a spec `apply`, like `apply Schema to *<T> except f;`.
+    PatternStar,
+}
+
+/// The kind of a bracket pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Bracket {
+    Normal,
+    /// The braces around the members of a `use` declaration.
+    Use,
+    /// The brackets of an attribute, `#[..]`.
+    Attribute,
+}
+
+#[derive(Debug)]
+struct Token<'a> {
+    tok: Tok,
+    text: &'a str,
+    role: Role,
+    bracket: Bracket,
+    /// Whether the token directly follows the previous token in the source, without any whitespace
+    /// or comment in between.
+    adjacent: bool,
+    /// Whether the token is part of the function patterns of a spec `apply`, where adjacency of
+    /// names and `*` is significant.
+    in_pattern: bool,
+}
+
+#[derive(Debug)]
+enum Item<'a> {
+    Token(Token<'a>),
+    /// A `//` comment, without the line break ending it.
+    LineComment(&'a str),
+    /// A `/* .. */` comment, which may span multiple lines.
+    BlockComment(&'a str),
+}
+
+#[derive(Debug)]
+struct Element<'a> {
+    item: Item<'a>,
+    /// The number of line breaks between the previous element and this one.
+    newlines: usize,
+}
+
+impl<'a> Element<'a> {
+    fn token(&self) -> Option<&Token<'a>> {
+        match &self.item {
+            Item::Token(token) => Some(token),
+            Item::LineComment(_) | Item::BlockComment(_) => None,
+        }
+    }
+}
+
+/// A line of output: a range of elements, and whether it is preceded by a blank line.
+struct Line {
+    elements: Range<usize>,
+    blank_before: bool,
+}
+
+/// Lays out `source`, which must be valid Move code.
+pub(crate) fn format(source: &str) -> Result<String> {
+    let mut elements = scan(source)?;
+    assign_roles(&mut elements);
+    loop {
+        let lines = split_lines(&elements);
+        let indents = indent_lines(&elements, &lines);
+        let mut wrapped = false;
+        for (line, indent) in lines.iter().zip(&indents) {
+            if line_width(&elements, line, *indent) <= MAX_LINE_WIDTH {
+                continue;
+            }
+            for idx in break_points(&elements, line) {
+                elements[idx].newlines = 1;
+                wrapped = true;
+            }
+        }
+        if !wrapped {
+            return Ok(render(&elements, &lines, &indents));
+        }
+    }
+}
+
+//**************************************************************************************************
+// Scanning
+//**************************************************************************************************
+
+fn scan(source: &str) -> Result<Vec<Element>> {
+    let mut lexer = Lexer::new(source, FileHash::new(source));
+    let mut elements = vec![];
+    let mut prev_end = 0;
+    loop {
+        lexer
+            .advance()
+            .map_err(|_| anyhow!("Unable to tokenize the source"))?;
+        let start = lexer.start_loc();
+        let newlines = scan_comments(&source[prev_end..start], &mut elements);
+        if lexer.peek() == Tok::EOF {
+            return Ok(elements);
+        }
+        let text = lexer.content();
+        elements.push(Element {
+            item: Item::Token(Token {
+                tok: lexer.peek(),
+                // The `&mut` token includes the space following it.
+                text: text.trim_end(),
+                role: Role::Plain,
+                bracket: Bracket::Normal,
+                adjacent: start == prev_end,
+                in_pattern: false,
+            }),
+            newlines,
+        });
+        prev_end = start + text.len();
+    }
+}
+
+/// Collects the comments in `gap`, the text between two tokens, returning the number of line
+/// breaks after the last comment.
+fn scan_comments<'a>(gap: &'a str, elements: &mut Vec<Element<'a>>) -> usize {
+    let mut newlines = 0;
+    let mut rest = gap;
+    while let Some(c) = rest.chars().next() {
+        if c == '\n' {
+            newlines += 1;
+            rest = &rest[1..];
+        } else if rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or_else(|| rest.len());
+            elements.push(Element {
+                item: Item::LineComment(rest[..end].trim_end()),
+                newlines,
+            });
+            newlines = 0;
+            rest = &rest[end..];
+        } else if rest.starts_with("/*") {
+            let end = block_comment_len(rest);
+            elements.push(Element {
+                item: Item::BlockComment(&rest[..end]),
+                newlines,
+            });
+            newlines = 0;
+            rest = &rest[end..];
+        } else {
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    newlines
+}
+
+/// The length of the (possibly nested) block comment at the start of `text`.
+fn block_comment_len(text: &str) -> usize {
+    let mut depth = 0;
+    let mut idx = 0;
+    while idx < text.len() {
+        if text[idx..].starts_with("/*") {
+            depth += 1;
+            idx += 2;
+        } else if text[idx..].starts_with("*/") {
+            depth -= 1;
+            idx += 2;
+            if depth == 0 {
+                return idx;
+            }
+        } else {
+            idx += text[idx..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
+
+//**************************************************************************************************
+// Token roles
+//**************************************************************************************************
+
+fn token_indices(elements: &[Element]) -> Vec<usize> {
+    (0..elements.len())
+        .filter(|idx| elements[*idx].token().is_some())
+        .collect()
+}
+
+fn token_mut<'a, 'b>(elements: &'b mut [Element<'a>], idx: usize) -> &'b mut Token<'a> {
+    match &mut elements[idx].item {
+        Item::Token(token) => token,
+        Item::LineComment(_) | Item::BlockComment(_) => unreachable!(),
+    }
+}
+
+fn assign_roles(elements: &mut [Element]) {
+    let indices = token_indices(elements);
+    let mut brackets = vec![];
+    let mut in_apply = false;
+    let mut in_pattern = false;
+    for pos in 0..indices.len() {
+        let token = elements[indices[pos]].token().unwrap();
+        let prev = pos
+            .checked_sub(1)
+            .map(|prev_pos| elements[indices[prev_pos]].token().unwrap());
+        let next = indices
+            .get(pos + 1)
+            .map(|next_idx| elements[*next_idx].token().unwrap());
+
+        match token.tok {
+            Tok::Identifier
+                if token.text == "apply"
+                    && prev.map_or(true, |prev| {
+                        matches!(prev.tok, Tok::Semicolon | Tok::LBrace | Tok::RBrace)
+                    }) =>
+            {
+                in_apply = true
+            }
+            Tok::Identifier if in_apply && token.text == "to" => in_pattern = true,
+            _ => (),
+        }
+        let token_in_pattern = in_pattern && matches!(token.tok, Tok::Identifier | Tok::Star);
+
+        let mut bracket = Bracket::Normal;
+        let role = match token.tok {
+            // Closing generic brackets are marked when the opening one is seen.
+            _ if token.role != Role::Plain => token.role,
+            Tok::Less if is_generic_open(&indices, elements, pos) => Role::GenericOpen,
+            Tok::Star if token_in_pattern => Role::PatternStar,
+            Tok::Exclaim
+                if prev.map_or(false, |prev| prev.tok == Tok::Identifier)
+                    && token.adjacent
+                    && next.map_or(false, |next| next.tok == Tok::LParen) =>
+            {
+                Role::MacroBang
+            }
+            Tok::Exclaim | Tok::AmpMut | Tok::AtSign => Role::UnaryOp,
+            Tok::Amp | Tok::Star if !is_operand_end(prev, token, next) => Role::UnaryOp,
+            tok if is_binary_op(tok) => Role::BinaryOp,
+            Tok::LBrace | Tok::LBracket | Tok::LParen => {
+                bracket = match (token.tok, prev.map(|prev| prev.tok)) {
+                    (Tok::LBrace, Some(Tok::ColonColon)) => Bracket::Use,
+                    (Tok::LBracket, Some(Tok::NumSign)) => Bracket::Attribute,
+                    _ => Bracket::Normal,
+                };
+                brackets.push(bracket);
+                Role::Plain
+            }
+            Tok::RBrace | Tok::RBracket | Tok::RParen => {
+                bracket = brackets.pop().unwrap_or(Bracket::Normal);
+                Role::Plain
+            }
+            _ => Role::Plain,
+        };
+        if token.tok == Tok::Semicolon {
+            in_apply = false;
+            in_pattern = false;
+        }
+
+        if role == Role::GenericOpen {
+            mark_generic(&indices, elements, pos);
+        }
+        let token = token_mut(elements, indices[pos]);
+        token.role = role;
+        token.bracket = bracket;
+        token.in_pattern = token_in_pattern;
+    }
+}
+
+/// Whether the `<` at position `pos` opens a list of types. Just like the parser, a `<` directly
+/// following a name starts type arguments; it must also be followed by a well-formed list of types.
+fn is_generic_open(indices: &[usize], elements: &[Element], pos: usize) -> bool {
+    let token = |pos: usize| elements[indices[pos]].token().unwrap();
+    if pos == 0 || !token(pos).adjacent {
+        return false;
+    }
+    let prev = token(pos - 1);
+    let after_name = match prev.tok {
+        Tok::Identifier => pos < 2 || token(pos - 2).tok != Tok::Period,
+        Tok::Star => prev.in_pattern,
+        tok => is_keyword(tok),
+    };
+    after_name && generic_close_positions(indices, elements, pos).is_some()
+}
+
+/// The positions of the tokens closing the generic brackets opened at `pos`, if the tokens that
+/// follow form a list of types.
+fn generic_close_positions(
+    indices: &[usize],
+    elements: &[Element],
+    pos: usize,
+) -> Option<Vec<usize>> {
+    let mut depth = 0usize;
+    let mut closers = vec![];
+    for (pos, idx) in indices.iter().enumerate().skip(pos) {
+        let token = elements[*idx].token().unwrap();
+        match token.tok {
+            Tok::Less => depth += 1,
+            Tok::Greater => {
+                depth -= 1;
+                closers.push(pos);
+            }
+            Tok::GreaterGreater if depth >= 2 => {
+                depth -= 2;
+                closers.push(pos);
+            }
+            Tok::Identifier
+            | Tok::ColonColon
+            | Tok::Comma
+            | Tok::Colon
+            | Tok::Plus
+            | Tok::Copy
+            | Tok::Amp
+            | Tok::AmpMut => (),
+            _ => return None,
+        }
+        if depth == 0 {
+            return Some(closers);
+        }
+    }
+    None
+}
+
+fn mark_generic(indices: &[usize], elements: &mut [Element], pos: usize) {
+    let closers = generic_close_positions(indices, elements, pos).unwrap_or_default();
+    let last = closers.last().copied().unwrap_or(pos);
+    for idx in indices.iter().take(last).skip(pos + 1) {
+        let token = token_mut(elements, *idx);
+        if token.tok == Tok::Less {
+            token.role = Role::GenericOpen;
+        }
+    }
+    for closer in closers {
+        token_mut(elements, indices[closer]).role = Role::GenericClose;
+    }
+}
+
+/// Whether the `&` or `*` token `token` follows an operand, and so is a binary operator.
+fn is_operand_end(prev: Option<&Token>, token: &Token, next: Option<&Token>) -> bool {
+    let prev = match prev {
+        Some(prev) => prev,
+        None => return false,
+    };
+    match prev.tok {
+        Tok::Identifier if SPEC_KEYWORDS.contains(&prev.text) => {
+            // Either a spec keyword followed by a prefix operator, or a variable named like one
+            // followed by a binary operator: go by how the operator was spaced.
+            token.adjacent || next.map_or(true, |next| !next.adjacent)
+        }
+        Tok::Identifier
+        | Tok::NumValue
+        | Tok::NumTypedValue
+        | Tok::ByteStringValue
+        | Tok::True
+        | Tok::False
+        | Tok::RParen
+        | Tok::RBracket => true,
+        Tok::Greater | Tok::GreaterGreater => prev.role == Role::GenericClose,
+        _ => false,
+    }
+}
+
+fn is_binary_op(tok: Tok) -> bool {
+    binary_op_precedence(tok).is_some() || matches!(tok, Tok::Equal)
+}
+
+/// The precedence of a binary operator that lines can be broken before, as in the parser.
+fn binary_op_precedence(tok: Tok) -> Option<u32> {
+    Some(match tok {
+        Tok::EqualEqualGreater | Tok::LessEqualEqualGreater => 2,
+        Tok::PipePipe => 3,
+        Tok::AmpAmp => 4,
+        Tok::EqualEqual
+        | Tok::ExclaimEqual
+        | Tok::Less
+        | Tok::Greater
+        | Tok::LessEqual
+        | Tok::GreaterEqual => 5,
+        Tok::Pipe => 7,
+        Tok::Caret => 8,
+        Tok::Amp => 9,
+        Tok::LessLess | Tok::GreaterGreater => 10,
+        Tok::Plus | Tok::Minus => 11,
+        Tok::Star | Tok::Slash | Tok::Percent => 12,
+        _ => return None,
+    })
+}
+
+fn is_keyword(tok: Tok) -> bool {
+    matches!(
+        tok,
+        Tok::Abort
+            | Tok::Acquires
+            | Tok::As
+            | Tok::Break
+            | Tok::Continue
+            | Tok::Copy
+            | Tok::Else
+            | Tok::If
+            | Tok::Invariant
+            | Tok::Let
+            | Tok::Loop
+            | Tok::Module
+            | Tok::Move
+            | Tok::Native
+            | Tok::Public
+            | Tok::Return
+            | Tok::Spec
+            | Tok::Struct
+            | Tok::Use
+            | Tok::While
+            | Tok::Fun
+            | Tok::Script
+            | Tok::Const
+            | Tok::Friend
+    )
+}
+
+fn is_opener(token: &Token) -> bool {
+    matches!(token.tok, Tok::LParen | Tok::LBracket | Tok::LBrace)
+}
+
+fn is_closer(token: &Token) -> bool {
+    matches!(token.tok, Tok::RParen | Tok::RBracket | Tok::RBrace)
+}
+
+//**************************************************************************************************
+// Spacing
+//**************************************************************************************************
+
+/// Whether a space separates the tokens `prev` and `cur` on a line.
+fn space_between(prev: &Token, cur: &Token) -> bool {
+    use Tok::*;
+
+    if prev.in_pattern && cur.in_pattern {
+        // Adjacent fragments form a single function pattern.
+        return !cur.adjacent;
+    }
+    match (prev.tok, cur.tok) {
+        (_, Comma) | (_, Semicolon) | (_, Colon) => return false,
+        (Colon, _) => return true,
+        (ColonColon, _) | (_, ColonColon) => return false,
+        (Period, _) | (_, Period) | (PeriodPeriod, _) | (_, PeriodPeriod) => return false,
+        (LParen, _) | (LBracket, _) | (_, RParen) | (_, RBracket) => return false,
+        (LBrace, RBrace) => return false,
+        (LBrace, _) => return prev.bracket != Bracket::Use,
+        (_, RBrace) => return cur.bracket != Bracket::Use,
+        (_, LBrace) => return true,
+        _ => (),
+    }
+    match (prev.role, cur.role) {
+        (Role::GenericOpen, _) | (_, Role::GenericOpen) | (_, Role::GenericClose) => false,
+        (Role::GenericClose, _) => !matches!(cur.tok, LParen | LBracket),
+        (Role::UnaryOp, _) => prev.tok == AmpMut,
+        (Role::MacroBang, _) | (_, Role::MacroBang) => false,
+        _ if prev.tok == NumSign => false,
+        _ => match cur.tok {
+            LParen => match prev.tok {
+                Identifier if SPEC_KEYWORDS.contains(&prev.text) => !cur.adjacent,
+                Identifier | Public => false,
+                _ => true,
+            },
+            LBracket => match prev.tok {
+                Identifier if SPEC_KEYWORDS.contains(&prev.text) => !cur.adjacent,
+                Identifier | RParen | RBracket | NumSign => false,
+                _ => true,
+            },
+            _ => true,
+        },
+    }
+}
+
+/// Whether a space separates two elements on a line.
+fn space_between_elements(prev: &Element, cur: &Element) -> bool {
+    match (prev.token(), cur.token()) {
+        (Some(prev), Some(cur)) => space_between(prev, cur),
+        (Some(prev), None) => !matches!(prev.tok, Tok::LParen | Tok::LBracket),
+        (None, Some(cur)) => !matches!(
+            cur.tok,
+            Tok::Comma | Tok::Semicolon | Tok::RParen | Tok::RBracket
+        ),
+        (None, None) => true,
+    }
+}
+
+//**************************************************************************************************
+// Lines and indentation
+//**************************************************************************************************
+
+fn split_lines(elements: &[Element]) -> Vec<Line> {
+    let mut lines: Vec<Line> = vec![];
+    for (idx, element) in elements.iter().enumerate() {
+        let after_line_comment = idx > 0 && matches!(elements[idx - 1].item, Item::LineComment(_));
+        if idx == 0 || element.newlines > 0 || after_line_comment {
+            lines.push(Line {
+                elements: idx..idx + 1,
+                blank_before: !lines.is_empty() && element.newlines > 1,
+            });
+        } else {
+            lines.last_mut().unwrap().elements.end = idx + 1;
+        }
+    }
+
+    // No blank lines at the start or at the end of a block.
+    for idx in 1..lines.len() {
+        let opens_block = last_token(elements, &lines[idx - 1]).map_or(false, is_opener);
+        let closes_block = first_token(elements, &lines[idx]).map_or(false, is_closer);
+        if opens_block || closes_block {
+            lines[idx].blank_before = false;
+        }
+    }
+    lines
+}
+
+fn first_token<'a, 'b>(elements: &'b [Element<'a>], line: &Line) -> Option<&'b Token<'a>> {
+    elements[line.elements.clone()]
+        .first()
+        .and_then(Element::token)
+}
+
+fn last_token<'a, 'b>(elements: &'b [Element<'a>], line: &Line) -> Option<&'b Token<'a>> {
+    elements[line.elements.clone()]
+        .iter()
+        .rev()
+        .find_map(Element::token)
+}
+
+/// The indentation level of every line.
+fn indent_lines(elements: &[Element], lines: &[Line]) -> Vec<usize> {
+    // For every open bracket: the indentation of the lines inside, and of the line closing it.
+    let mut frames: Vec<(usize, usize)> = vec![];
+    let mut prev_token: Option<&Token> = None;
+    let mut indents = vec![];
+    for line in lines {
+        let first = first_token(elements, line);
+        let indent = match first {
+            Some(first) if is_closer(first) => frames.last().map_or(0, |frame| frame.1),
+            _ => {
+                let inner = frames.last().map_or(0, |frame| frame.0);
+                if is_continuation(prev_token, first) {
+                    inner + 1
+                } else {
+                    inner
+                }
+            }
+        };
+        indents.push(indent);
+
+        for token in elements[line.elements.clone()]
+            .iter()
+            .filter_map(Element::token)
+        {
+            if is_opener(token) {
+                frames.push((indent + 1, indent));
+            } else if is_closer(token) {
+                frames.pop();
+            }
+            prev_token = Some(token);
+        }
+    }
+    indents
+}
+
+/// Whether a line starting with `first` continues the code of the previous line, which ended with
+/// `prev`.
+fn is_continuation(prev: Option<&Token>, first: Option<&Token>) -> bool {
+    let prev = match prev {
+        Some(prev) => prev,
+        None => return false,
+    };
+    if let Some(first) = first {
+        // `acquires` lists go on the same level as the function they belong to, and a block may
+        // open on a line of its own.
+        if matches!(first.tok, Tok::Acquires | Tok::LBrace) {
+            return false;
+        }
+    }
+    match prev.tok {
+        Tok::Semicolon | Tok::Comma | Tok::LBrace | Tok::RBrace | Tok::LParen | Tok::LBracket => {
+            false
+        }
+        Tok::RBracket => prev.bracket != Bracket::Attribute,
+        _ => true,
+    }
+}
+
+//**************************************************************************************************
+// Wrapping
+//**************************************************************************************************
+
+/// The width of a line, not counting a trailing line comment. Lines with multi-line comments are
+/// never wrapped, and so are given a width of zero.
+fn line_width(elements: &[Element], line: &Line, indent: usize) -> usize {
+    let elements = &elements[line.elements.clone()];
+    if elements
+        .iter()
+        .any(|element| matches!(element.item, Item::BlockComment(text) if text.contains('\n')))
+    {
+        return 0;
+    }
+    let code = match elements.last() {
+        Some(Element {
+            item: Item::LineComment(_),
+            ..
+        }) => &elements[..elements.len() - 1],
+        _ => elements,
+    };
+    indent * INDENT_WIDTH + render_elements(code).len()
+}
+
+/// The elements of a too long line before which a line break should be added. The line is broken
+/// before its outermost binary operators with the lowest precedence or, if there are none, inside
+/// the first bracket pair which opens and closes on the line: after the opening bracket, after
+/// every separator and before the closing bracket.
+fn break_points(elements: &[Element], line: &Line) -> Vec<usize> {
+    let tokens = line
+        .elements
+        .clone()
+        .filter_map(|idx| elements[idx].token().map(|token| (idx, token)))
+        .collect::<Vec<_>>();
+
+    // The nesting depth of every token, counting parentheses and braces only.
+    let mut depth = 0isize;
+    let mut bracket_depth = 0isize;
+    let mut depths = vec![];
+    for (_, token) in &tokens {
+        if is_closer(token) {
+            if token.tok == Tok::RBracket {
+                bracket_depth -= 1;
+            } else {
+                depth -= 1;
+            }
+        }
+        depths.push((depth, bracket_depth));
+        if is_opener(token) {
+            if token.tok == Tok::LBracket {
+                bracket_depth += 1;
+            } else {
+                depth += 1;
+            }
+        }
+    }
+    let min_depth = match depths.iter().map(|(depth, _)| *depth).min() {
+        Some(min_depth) => min_depth,
+        None => return vec![],
+    };
+    let min_bracket_depth = depths.iter().map(|(_, depth)| *depth).min().unwrap();
+
+    // Break before binary operators.
+    let operators = tokens
+        .iter()
+        .zip(&depths)
+        .skip(1)
+        .filter(|(_, depth)| **depth == (min_depth, min_bracket_depth))
+        .filter_map(|((idx, token), _)| {
+            let precedence = match token.role {
+                Role::BinaryOp => binary_op_precedence(token.tok)?,
+                Role::Plain if token.tok == Tok::Identifier && token.text == "with" => 0,
+                _ => return None,
+            };
+            Some((*idx, precedence))
+        })
+        .collect::<Vec<_>>();
+    if let Some(lowest) = operators.iter().map(|(_, precedence)| *precedence).min() {
+        return operators
+            .into_iter()
+            .filter(|(_, precedence)| *precedence == lowest)
+            .map(|(idx, _)| idx)
+            .collect();
+    }
+
+    // Break inside the first bracket pair.
+    for (pos, (_, token)) in tokens.iter().enumerate() {
+        if depths[pos].0 != min_depth || !matches!(token.tok, Tok::LParen | Tok::LBrace) {
+            continue;
+        }
+        let close_pos = match matching_closer(&tokens, pos) {
+            Some(close_pos) if close_pos > pos + 1 => close_pos,
+            _ => continue,
+        };
+        let mut points = vec![tokens[pos].0 + 1];
+        let mut nesting = 0isize;
+        let mut generic_nesting = 0isize;
+        for (idx, token) in &tokens[pos + 1..close_pos] {
+            match token.role {
+                Role::GenericOpen => generic_nesting += 1,
+                Role::GenericClose => {
+                    generic_nesting -= if token.tok == Tok::GreaterGreater {
+                        2
+                    } else {
+                        1
+                    }
+                }
+                _ => (),
+            }
+            if is_opener(token) {
+                nesting += 1;
+            } else if is_closer(token) {
+                nesting -= 1;
+            } else if nesting == 0
+                && generic_nesting == 0
+                && matches!(token.tok, Tok::Comma | Tok::Semicolon)
+            {
+                points.push(idx + 1);
+            }
+        }
+        points.push(tokens[close_pos].0);
+        points.dedup();
+        // Never break before a trailing line comment, it would end up on the wrong line.
+        points.retain(|idx| {
+            *idx < line.elements.end && !matches!(elements[*idx].item, Item::LineComment(_))
+        });
+        return points;
+    }
+    vec![]
+}
+
+fn matching_closer(tokens: &[(usize, &Token)], open_pos: usize) -> Option<usize> {
+    let mut nesting = 0;
+    for (pos, (_, token)) in tokens.iter().enumerate().skip(open_pos) {
+        if is_opener(token) {
+            nesting += 1;
+        } else if is_closer(token) {
+            nesting -= 1;
+            if nesting == 0 {
+                return Some(pos);
+            }
+        }
+    }
+    None
+}
+
+//**************************************************************************************************
+// Rendering
+//**************************************************************************************************
+
+fn render_elements(elements: &[Element]) -> String {
+    let mut out = String::new();
+    for (idx, element) in elements.iter().enumerate() {
+        if idx > 0 && space_between_elements(&elements[idx - 1], element) {
+            out.push(' ');
+        }
+        match &element.item {
+            Item::Token(token) => out.push_str(token.text),
+            Item::LineComment(text) | Item::BlockComment(text) => out.push_str(text),
+        }
+    }
+    out
+}
+
+fn render(elements: &[Element], lines: &[Line], indents: &[usize]) -> String {
+    let mut out = String::new();
+    for (line, indent) in lines.iter().zip(indents) {
+        if line.blank_before {
+            out.push('\n');
+        }
+        let text = render_elements(&elements[line.elements.clone()]);
+        for (idx, text) in text.split('\n').enumerate() {
+            // Only the first line of a multi-line comment is indented, the others are kept as is.
+            if idx == 0 {
+                out.push_str(&" ".repeat(indent * INDENT_WIDTH));
+            }
+            out.push_str(text.trim_end());
+            out.push('\n');
+        }
+    }
+    out
+}
@@ -0,0 +1,168 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A source formatter for Move.
+//!
+//! Formatting happens in two phases. First, the `use` declarations at the top of every module and
+//! script are sorted and merged, based on the AST produced by the Move parser. Then the file is
+//! laid out again token by token: spacing between tokens is normalized, blocks are re-indented,
+//! runs of blank lines are collapsed and lines that are too long are wrapped. Comments and doc
+//! comments are kept where they were written, and so are the line breaks chosen by the author
+//! (the formatter only ever adds line breaks, it never joins lines).
+//!
+//! As a safety net, the formatted source is parsed again and compared against the original one;
+//! if the two programs differ in anything but the order and grouping of their imports, formatting
+//! fails instead of changing the meaning of the code.
+
+mod imports;
+mod layout;
+
+use anyhow::{anyhow, bail, Result};
+use move_command_line_common::files::FileHash;
+use move_lang::{
+    diagnostics::{codes::Severity, report_diagnostics_to_buffer, Diagnostics, FilesSourceText},
+    parser::{
+        ast::{Definition, ModuleDefinition, ModuleMember, Use, UseDecl},
+        comments::{verify_string, MatchedFileCommentMap},
+        syntax::parse_file_string,
+    },
+    shared::{CompilationEnv, Flags},
+};
+use move_symbol_pool::Symbol;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+/// The maximum width of a line; longer lines are wrapped where possible.
+pub const MAX_LINE_WIDTH: usize = 100;
+
+/// The number of spaces used for one level of indentation.
+pub const INDENT_WIDTH: usize = 4;
+
+/// Formats the Move source file at `path`, returning the formatted source.
+pub fn format_file(path: &Path) -> Result<String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|err| anyhow!("Unable to read {}: {}", path.display(), err))?;
+    format_source(&path.to_string_lossy(), &source)
+}
+
+/// Formats the Move source `source`, returning the formatted source. The `file_name` is only
+/// used when reporting errors.
+pub fn format_source(file_name: &str, source: &str) -> Result<String> {
+    let (defs, doc_comments) = parse(file_name, source)?;
+    let source_with_imports = imports::normalize(source, &defs)?;
+    let formatted = layout::format(&source_with_imports)?;
+
+    // Make sure formatting did not change the meaning of the program.
+    let (formatted_defs, formatted_doc_comments) = parse(file_name, &formatted).map_err(|err| {
+        anyhow!(
+            "Formatting {} produced invalid code; this is a bug in the formatter:\n{}",
+            file_name,
+            err
+        )
+    })?;
+    if canonicalize(defs) != canonicalize(formatted_defs)
+        || doc_comment_texts(&doc_comments) != doc_comment_texts(&formatted_doc_comments)
+    {
+        bail!(
+            "Formatting {} would change the meaning of the code; this is a bug in the formatter",
+            file_name
+        )
+    }
+    Ok(formatted)
+}
+
+/// Parses `source`, reporting any diagnostics as an error.
+fn parse(file_name: &str, source: &str) -> Result<(Vec<Definition>, MatchedFileCommentMap)> {
+    let file_hash = FileHash::new(source);
+    let mut env = CompilationEnv::new(Flags::empty(), BTreeMap::new());
+    let result = verify_string(file_hash, source)
+        .and_then(|()| parse_file_string(&mut env, file_hash, source))
+        .and_then(|parsed| {
+            env.check_diags_at_or_above_severity(Severity::NonblockingError)?;
+            Ok(parsed)
+        });
+    result.map_err(|diags: Diagnostics| {
+        let mut files = FilesSourceText::new();
+        files.insert(file_hash, (Symbol::from(file_name), source.to_string()));
+        anyhow!(
+            "{}",
+            String::from_utf8_lossy(&report_diagnostics_to_buffer(&files, diags))
+        )
+    })
+}
+
+/// Renders the parsed program in a form that is independent of source locations and of the order
+/// and grouping of the `use` declarations the formatter normalizes.
+fn canonicalize(defs: Vec<Definition>) -> String {
+    static LOC: Lazy<Regex> = Lazy::new(|| Regex::new(r"Loc \{[^}]*\}").unwrap());
+
+    let mut out = String::new();
+    for def in defs {
+        match def {
+            Definition::Module(module) => canonicalize_module(module, &mut out),
+            Definition::Address(mut address) => {
+                for module in std::mem::take(&mut address.modules) {
+                    canonicalize_module(module, &mut out);
+                }
+                out.push_str(&format!("{:?}\n", address));
+            }
+            Definition::Script(mut script) => {
+                let uses = std::mem::take(&mut script.uses);
+                out.push_str(&format!("{:?}\n{:?}\n", flatten_uses(uses), script));
+            }
+        }
+    }
+    LOC.replace_all(&out, "Loc").into_owned()
+}
+
+fn canonicalize_module(mut module: ModuleDefinition, out: &mut String) {
+    let mut uses = vec![];
+    module.members.retain(|member| match member {
+        ModuleMember::Use(use_decl) => {
+            uses.push(use_decl.clone());
+            false
+        }
+        _ => true,
+    });
+    out.push_str(&format!("{:?}\n{:?}\n", flatten_uses(uses), module));
+}
+
+/// Splits `use` declarations into the individual names they import.
+fn flatten_uses(uses: Vec<UseDecl>) -> BTreeSet<String> {
+    let mut flattened = BTreeSet::new();
+    for UseDecl { attributes, use_ } in uses {
+        match use_ {
+            Use::Module(ident, alias) => {
+                flattened.insert(format!("{:?} {:?} as {:?}", attributes, ident, alias));
+            }
+            Use::Members(ident, members) => {
+                for (member, alias) in members {
+                    flattened.insert(if member.value.as_str() == "Self" {
+                        format!("{:?} {:?} as {:?}", attributes, ident, alias)
+                    } else {
+                        format!("{:?} {:?}::{} as {:?}", attributes, ident, member, alias)
+                    });
+                }
+            }
+        }
+    }
+    flattened
+}
+
+/// The text of all non-empty doc comments, ignoring trailing whitespace.
+fn doc_comment_texts(doc_comments: &MatchedFileCommentMap) -> Vec<String> {
+    doc_comments
+        .values()
+        .filter(|text| !text.is_empty())
+        .map(|text| {
+            text.lines()
+                .map(|line| line.trim_end())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect()
+}
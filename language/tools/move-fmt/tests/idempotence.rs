@@ -0,0 +1,37 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::files::find_move_filenames;
+use std::path::{Path, PathBuf};
+
+/// Formats every Move file under `dirs` twice, checking that the second round leaves the code as
+/// is.
+fn check_idempotent(dirs: &[PathBuf]) {
+    let files = find_move_filenames(dirs, false).expect("unable to find Move sources");
+    assert!(!files.is_empty());
+    for file in files {
+        let formatted = move_fmt::format_file(Path::new(&file)).unwrap();
+        let reformatted = move_fmt::format_source(&file, &formatted).unwrap();
+        assert_eq!(
+            formatted, reformatted,
+            "formatting {} is not idempotent",
+            file
+        );
+    }
+}
+
+#[test]
+fn stdlib_formatting_is_idempotent() {
+    let stdlib = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../move-stdlib");
+    check_idempotent(&[stdlib.join("sources"), stdlib.join("nursery")]);
+}
+
+#[test]
+fn framework_formatting_is_idempotent() {
+    let framework = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../diem-move/diem-framework");
+    check_idempotent(&[
+        framework.join("core/sources"),
+        framework.join("DPN/sources"),
+        framework.join("experimental/sources"),
+    ]);
+}
@@ -0,0 +1,44 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::testing::{format_diff, read_env_update_baseline, EXP_EXT};
+use std::{fs, path::Path};
+
+fn run_test(path: &Path) -> datatest_stable::Result<()> {
+    let update_baseline = read_env_update_baseline();
+    let exp_path = path.with_extension(EXP_EXT);
+
+    let output = match move_fmt::format_file(path) {
+        Ok(formatted) => {
+            // Formatting must be idempotent.
+            let reformatted = move_fmt::format_source(&path.to_string_lossy(), &formatted)?;
+            if reformatted != formatted {
+                return Err(anyhow::anyhow!(
+                    "Formatting {} is not idempotent:\n{}",
+                    path.display(),
+                    format_diff(&formatted, &reformatted)
+                )
+                .into());
+            }
+            formatted
+        }
+        Err(err) => format!("{:#}\n", err),
+    };
+
+    if update_baseline {
+        fs::write(&exp_path, &output)?;
+        return Ok(());
+    }
+    let expected = fs::read_to_string(&exp_path).unwrap_or_default();
+    if expected != output {
+        return Err(anyhow::anyhow!(
+            "Expected output differs from actual output for {}:\n{}",
+            path.display(),
+            format_diff(&expected, &output)
+        )
+        .into());
+    }
+    Ok(())
+}
+
+datatest_stable::harness!(run_test, "tests/testsuite", r".*\.move$");
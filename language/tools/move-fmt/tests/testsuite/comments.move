@@ -0,0 +1,11 @@
+/// Doc comments and comments stay where they were written.
+module 0x1::Comments {
+    /* A block comment
+       spanning lines. */
+    const C: u64 = 1;   // trailing comment
+
+
+    /// A documented function.
+    public fun f(): u64 { /* inline */ C }
+    // A comment before the closing brace.
+}
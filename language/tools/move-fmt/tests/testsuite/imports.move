@@ -0,0 +1,16 @@
+module 0x1::Imports {
+    use Std::Vector;
+    use Std::Option::{Self, Option};
+    use Std::Errors;
+    use Std::Vector::{length, push_back as push};
+    use Std::Option::some;
+
+    // Uses separated by a comment are sorted separately.
+    use Std::Signer;
+    use Std::BCS;
+
+    fun f(v: vector<u64>): Option<u64> {
+        push(&mut v, 1);
+        if (length(&v) > 0) some(Vector::pop_back(&mut v)) else Option::none()
+    }
+}
@@ -0,0 +1,3 @@
+module 0x1::ParseError {
+    fun f( {}
+}
@@ -0,0 +1,21 @@
+module 0x1::Spacing {
+    struct S<T: copy+drop> has copy,drop { f: T, g: vector<vector<u8>> }
+
+    fun arithmetic(x: u64, y: u64): u64 {
+        let z = x+y*2;
+        let w = (z<<1)>>2;
+        if (x < y && y>=z || !(x==y)) w-1 else z%3
+    }
+
+    fun references(s: &mut S<u64>): &u64 {
+        let r = &mut s.f;
+        *r = *r*2;
+        let v = vector<u64>[1,2,3];
+        assert!(Vector::length(&v)==3, 0);
+        &s.f
+    }
+
+    fun generics<T: copy+drop>(x: T): S<T> {
+        S<T>{ f: x, g: vector[] }
+    }
+}
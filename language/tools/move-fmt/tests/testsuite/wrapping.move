@@ -0,0 +1,13 @@
+module 0x1::Wrapping {
+    fun long_signature_and_result(first_argument: u64, second_argument: u64, third_argument: u64): (u64, u64) {
+        (first_argument, second_argument)
+    }
+
+    fun long_condition(first_argument: u64, second_argument: u64, third_argument: u64) {
+        assert!(first_argument + second_argument > third_argument && second_argument > first_argument, 1);
+    }
+
+    spec long_condition {
+        aborts_if first_argument + second_argument <= third_argument || second_argument <= first_argument with 1;
+    }
+}
@@ -5,7 +5,7 @@ pub mod compilation;
 pub mod resolution;
 pub mod source_package;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use compilation::compiled_package::CompilationCachingStatus;
 use move_core_types::account_address::AccountAddress;
 use move_model::model::GlobalEnv;
@@ -22,8 +22,11 @@ use crate::{
     compilation::{
         build_plan::BuildPlan, compiled_package::CompiledPackage, model_builder::ModelBuilder,
     },
-    resolution::resolution_graph::{ResolutionGraph, ResolvedGraph},
-    source_package::{layout, manifest_parser},
+    resolution::{
+        lock_file::LockFile,
+        resolution_graph::{ResolutionGraph, ResolvedGraph},
+    },
+    source_package::{layout, manifest_parser, parsed_manifest::SourceManifest},
 };
 
 #[derive(Debug, StructOpt, Clone, Serialize, Deserialize, Eq, PartialEq, PartialOrd)]
@@ -59,6 +62,12 @@ pub struct BuildConfig {
     #[structopt(name = "force-recompilation", long = "force", global = true)]
     pub force_recompilation: bool,
 
+    /// Require the dependencies recorded in Move.lock to be up to date, and fail instead of
+    /// updating Move.lock if they are not.
+    #[structopt(name = "locked", long = "locked", global = true)]
+    #[serde(default)]
+    pub locked: bool,
+
     /// Additional named address mapping. Useful for tools in rust
     #[structopt(skip)]
     pub additional_named_addresses: BTreeMap<String, AccountAddress>,
@@ -73,6 +82,7 @@ impl Default for BuildConfig {
             generate_abis: false,
             install_dir: None,
             force_recompilation: false,
+            locked: false,
             additional_named_addresses: BTreeMap::new(),
         }
     }
@@ -123,11 +133,78 @@ impl BuildConfig {
             self.dev_mode = true;
         }
         let path = SourcePackageLayout::try_find_root(path)?;
+        let manifest = Self::parse_manifest(&path)?;
+        let resolved_graph =
+            ResolutionGraph::new(manifest, path.clone(), self.clone())?.resolve()?;
+        self.check_or_update_lock_file(&path, &resolved_graph)?;
+        Ok(resolved_graph)
+    }
+
+    /// Resolve the package at `path` or the containing Move package like
+    /// `resolution_graph_for_package`, but without checking or updating its Move.lock file. For
+    /// tools that must not modify the package, such as the language server.
+    pub fn resolution_graph_for_package_without_lock_file(
+        mut self,
+        path: &Path,
+    ) -> Result<ResolvedGraph> {
+        if self.test_mode {
+            self.dev_mode = true;
+        }
+        let path = SourcePackageLayout::try_find_root(path)?;
+        let manifest = Self::parse_manifest(&path)?;
+        ResolutionGraph::new(manifest, path, self)?.resolve()
+    }
+
+    /// Update the Move.lock file of the package at `path` or the containing Move package, after
+    /// fetching the latest commit of the revision of each git dependency and dev-dependency.
+    pub fn update_lock_file(mut self, path: &Path) -> Result<LockFile> {
+        // Dev-dependencies are only resolved in dev mode, and pinned along with the dependencies
+        self.dev_mode = true;
+        self.test_mode = false;
+        let path = SourcePackageLayout::try_find_root(path)?;
+        let manifest = Self::parse_manifest(&path)?;
+        let resolved_graph =
+            ResolutionGraph::new_with_latest_dependencies(manifest, path.clone(), self)?
+                .resolve()?;
+        let lock_file = LockFile::from_resolved_graph(&resolved_graph)?;
+        lock_file.write(&path)?;
+        Ok(lock_file)
+    }
+
+    fn parse_manifest(path: &Path) -> Result<SourceManifest> {
         let manifest_string =
             std::fs::read_to_string(path.join(layout::SourcePackageLayout::Manifest.path()))?;
         let toml_manifest = manifest_parser::parse_move_manifest_string(manifest_string)?;
-        let manifest = manifest_parser::parse_source_manifest(toml_manifest)?;
-        let resolution_graph = ResolutionGraph::new(manifest, path, self)?;
-        resolution_graph.resolve()
+        manifest_parser::parse_source_manifest(toml_manifest)
+    }
+
+    // Packages only get a lock file once they depend on a git dependency, whose contents can
+    // change between resolutions, but keep it up to date from then on.
+    fn check_or_update_lock_file(&self, path: &Path, resolved_graph: &ResolvedGraph) -> Result<()> {
+        let existing_lock_file = LockFile::read(path)?;
+        if existing_lock_file.is_none() && !self.locked && !resolved_graph.has_git_dependencies() {
+            return Ok(());
+        }
+
+        let mut lock_file = LockFile::from_resolved_graph(resolved_graph)?;
+        // Dev-dependencies are not part of the graph outside of dev mode, so their pins are kept
+        // as they are until the next build in dev mode
+        if !self.dev_mode {
+            if let Some(existing_lock_file) = &existing_lock_file {
+                lock_file = lock_file.with_dev_packages_of(existing_lock_file);
+            }
+        }
+
+        match existing_lock_file {
+            Some(existing_lock_file) if existing_lock_file == lock_file => Ok(()),
+            Some(existing_lock_file) if self.locked => bail!(
+                "Move.lock is out of date:\n{}\nRun `move package update` to update it",
+                existing_lock_file.differences(&lock_file).join("\n")
+            ),
+            None if self.locked => {
+                bail!("Move.lock not found. Run `move package update` to create it")
+            }
+            _ => lock_file.write(path),
+        }
     }
 }
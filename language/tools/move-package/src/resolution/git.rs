@@ -0,0 +1,145 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Context, Result};
+use std::{path::Path, process::Command};
+
+use crate::{
+    resolution::lock_file::LockFile,
+    source_package::{
+        manifest_parser::git_download_path,
+        parsed_manifest::{Dependency, PackageName},
+    },
+};
+
+/// Decides which commit each git dependency is checked out at, and downloads the dependency's
+/// repository if it is not already downloaded.
+///
+/// Repositories are downloaded under `MOVE_HOME`, keyed by the revision named in the manifest
+/// and, for dependencies pinned by a lock file, by the pinned commit. Resolution only needs the
+/// network when a revision or commit has not been downloaded before, or when fetching the latest
+/// commits.
+pub(crate) struct GitCheckouts {
+    lock_file: Option<LockFile>,
+    fetch_latest: bool,
+}
+
+impl GitCheckouts {
+    /// Check out dependencies at the commits pinned by `lock_file`, if any, and check out
+    /// dependencies that are not pinned at the commit their revision was first downloaded at.
+    pub fn locked(lock_file: Option<LockFile>) -> Self {
+        Self {
+            lock_file,
+            fetch_latest: false,
+        }
+    }
+
+    /// Fetch and check out the latest commit of the revision of every dependency.
+    pub fn latest() -> Self {
+        Self {
+            lock_file: None,
+            fetch_latest: true,
+        }
+    }
+
+    /// Check out `dep` if it is a git dependency, and return it with its local path pointing to
+    /// the checkout.
+    pub fn checkout(&self, dep_name: PackageName, dep: Dependency) -> Result<Dependency> {
+        let git_info = match &dep.git_info {
+            None => return Ok(dep),
+            Some(git_info) => git_info.clone(),
+        };
+        let pinned_commit = self
+            .lock_file
+            .as_ref()
+            .and_then(|lock_file| lock_file.pinned_commit(dep_name, &git_info));
+        let repo_path = match pinned_commit {
+            // The download of the revision is reused while it is at the pinned commit, as it is
+            // right after the lock file is updated
+            Some(commit) if head_commit(&git_info.download_to).ok().as_deref() != Some(commit) => {
+                let repo_path = git_download_path(&git_info.git_url, commit);
+                download(dep_name, &git_info.git_url, commit, &repo_path, false)?;
+                repo_path
+            }
+            _ => {
+                download(
+                    dep_name,
+                    &git_info.git_url,
+                    &git_info.git_rev,
+                    &git_info.download_to,
+                    self.fetch_latest,
+                )?;
+                git_info.download_to.clone()
+            }
+        };
+        Ok(Dependency {
+            local: repo_path.join(&git_info.subdir),
+            ..dep
+        })
+    }
+}
+
+/// The commit checked out in the repository containing `path`.
+pub(crate) fn head_commit(path: &Path) -> Result<String> {
+    run_git(&["-C", &path.to_string_lossy(), "rev-parse", "HEAD"])
+}
+
+fn download(
+    dep_name: PackageName,
+    git_url: &str,
+    rev: &str,
+    repo_path: &Path,
+    fetch_latest: bool,
+) -> Result<()> {
+    let repo = repo_path.to_string_lossy();
+    if !repo_path.exists() {
+        run_git(&["clone", git_url, &repo]).with_context(|| {
+            format!("Failed to clone Git repository for package '{}'", dep_name)
+        })?;
+    } else if fetch_latest {
+        run_git(&["-C", &repo, "fetch", "origin"]).with_context(|| {
+            format!("Failed to fetch Git repository for package '{}'", dep_name)
+        })?;
+    } else {
+        return Ok(());
+    }
+
+    // Branches are checked out from the remote so that fetching moves them to their latest commit
+    let remote_branch = format!("origin/{}", rev);
+    let is_branch = run_git(&[
+        "-C",
+        &repo,
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &remote_branch,
+    ])
+    .is_ok();
+    let rev = if is_branch {
+        remote_branch.as_str()
+    } else {
+        rev
+    };
+    run_git(&["-C", &repo, "checkout", "--quiet", "--detach", rev]).with_context(|| {
+        format!(
+            "Failed to checkout Git reference '{}' for package '{}'",
+            rev, dep_name
+        )
+    })?;
+    Ok(())
+}
+
+fn run_git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,221 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    resolution::{git::head_commit, resolution_graph::ResolvedGraph},
+    source_package::{
+        layout::SourcePackageLayout,
+        parsed_manifest::{GitInfo, PackageName},
+    },
+};
+
+const LOCK_FILE_HEADER: &str =
+    "# This file is generated by the Move package system. Do not edit it manually.\n\n";
+
+/// The contents of a `Move.lock` file, which records how every transitive dependency of a package
+/// was resolved: the commit its repository was checked out at if it is a git dependency, the
+/// digest of its sources and the values of its named addresses.
+///
+/// Git dependencies are checked out at the commit recorded in the lock file, even if the revision
+/// named in the manifest is a branch that has since moved, until the lock file is updated.
+///
+/// Digests and named addresses are the ones of the packages outside of dev mode. Packages that
+/// are only dev-dependencies are recorded as well, but only checked by builds in dev mode.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LockFile {
+    #[serde(rename = "package", default)]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    /// The digest of the contents of all source files and manifest of the package
+    pub digest: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
+    /// The revision named in the manifest of the package depending on this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdir: Option<String>,
+    /// The commit the revision was resolved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Whether the package is only depended on through dev-dependencies
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub dev: bool,
+    #[serde(default)]
+    pub addresses: BTreeMap<String, String>,
+}
+
+impl LockFile {
+    pub fn path(package_path: &Path) -> PathBuf {
+        package_path.join(SourcePackageLayout::LockFile.path())
+    }
+
+    /// Read the lock file of the package at `package_path`, if it has one.
+    pub fn read(package_path: &Path) -> Result<Option<Self>> {
+        let path = Self::path(package_path);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        let lock_file = toml::from_str(&contents)
+            .with_context(|| format!("Unable to parse lock file {:?}", path))?;
+        Ok(Some(lock_file))
+    }
+
+    pub fn write(&self, package_path: &Path) -> Result<()> {
+        let contents = format!("{}{}", LOCK_FILE_HEADER, toml::to_string(self)?);
+        std::fs::write(Self::path(package_path), contents)?;
+        Ok(())
+    }
+
+    /// Record the dependencies of the root package of `resolved_graph`.
+    pub fn from_resolved_graph(resolved_graph: &ResolvedGraph) -> Result<Self> {
+        let root_name = resolved_graph.root_package.package.name;
+        let dev_only_packages = resolved_graph.dev_only_packages();
+        let packages = resolved_graph
+            .package_table
+            .iter()
+            .filter(|(name, _)| **name != root_name)
+            .map(|(name, package)| {
+                let git_info = resolved_graph.git_info(name);
+                let commit = git_info
+                    .map(|_| head_commit(&package.package_path))
+                    .transpose()
+                    .with_context(|| format!("Unable to find the commit of package '{}'", name))?;
+                let subdir = git_info
+                    .map(|git_info| git_info.subdir.to_string_lossy().to_string())
+                    .filter(|subdir| !subdir.is_empty());
+                let digest =
+                    package.source_digest_outside_dev_mode(&resolved_graph.build_options)?;
+                Ok(LockedPackage {
+                    name: name.to_string(),
+                    digest: digest.to_string(),
+                    git: git_info.map(|git_info| git_info.git_url.to_string()),
+                    rev: git_info.map(|git_info| git_info.git_rev.to_string()),
+                    subdir,
+                    commit,
+                    dev: dev_only_packages.contains(name),
+                    addresses: package
+                        .resolution_table
+                        .iter()
+                        .map(|(name, addr)| {
+                            (name.to_string(), format!("0x{}", addr.short_str_lossless()))
+                        })
+                        .collect(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::sorted(packages))
+    }
+
+    /// Add the packages of `other` that are only dev-dependencies and not recorded in this lock
+    /// file, which is how the dev-dependencies pinned by a build in dev mode are kept by builds
+    /// outside of it.
+    pub fn with_dev_packages_of(self, other: &LockFile) -> Self {
+        let mut packages = self.packages;
+        let dev_packages = other
+            .packages
+            .iter()
+            .filter(|package| package.dev && !packages.iter().any(|p| p.name == package.name))
+            .cloned()
+            .collect::<Vec<_>>();
+        packages.extend(dev_packages);
+        Self::sorted(packages)
+    }
+
+    fn sorted(mut packages: Vec<LockedPackage>) -> Self {
+        packages.sort_by(|package, other| package.name.cmp(&other.name));
+        Self { packages }
+    }
+
+    /// The commit the dependency `name` is pinned to, if it is still downloaded from the same
+    /// repository and revision as when it was pinned.
+    pub fn pinned_commit(&self, name: PackageName, git_info: &GitInfo) -> Option<&str> {
+        let subdir = git_info.subdir.to_string_lossy();
+        self.packages
+            .iter()
+            .find(|package| {
+                package.name == name.as_str()
+                    && package.git.as_deref() == Some(git_info.git_url.as_str())
+                    && package.rev.as_deref() == Some(git_info.git_rev.as_str())
+                    && package.subdir.as_deref().unwrap_or("") == subdir
+            })
+            .and_then(|package| package.commit.as_deref())
+    }
+
+    /// Describe how the dependencies recorded in `other` differ from the ones recorded in this
+    /// lock file.
+    pub fn differences(&self, other: &LockFile) -> Vec<String> {
+        let packages = self
+            .packages
+            .iter()
+            .map(|package| (package.name.as_str(), package))
+            .collect::<BTreeMap<_, _>>();
+        let other_packages = other
+            .packages
+            .iter()
+            .map(|package| (package.name.as_str(), package))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut differences = vec![];
+        for (name, other_package) in &other_packages {
+            let package = match packages.get(name) {
+                None => {
+                    differences.push(format!("Dependency '{}' is not locked", name));
+                    continue;
+                }
+                Some(package) => package,
+            };
+            let mut changes = vec![];
+            if (&package.git, &package.rev, &package.subdir)
+                != (
+                    &other_package.git,
+                    &other_package.rev,
+                    &other_package.subdir,
+                )
+            {
+                changes.push("source");
+            }
+            if package.dev != other_package.dev {
+                changes.push("kind");
+            }
+            if package.commit != other_package.commit {
+                changes.push("commit");
+            }
+            if package.digest != other_package.digest {
+                changes.push("digest");
+            }
+            if package.addresses != other_package.addresses {
+                changes.push("addresses");
+            }
+            if !changes.is_empty() {
+                differences.push(format!(
+                    "Dependency '{}' has a different {} than locked",
+                    name,
+                    changes.join(", ")
+                ));
+            }
+        }
+        for name in packages.keys() {
+            if !other_packages.contains_key(name) {
+                differences.push(format!("Locked dependency '{}' is no longer used", name));
+            }
+        }
+        differences
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
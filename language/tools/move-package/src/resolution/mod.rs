@@ -2,4 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod digest;
+mod git;
+pub mod lock_file;
 pub mod resolution_graph;
@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    resolution::digest::compute_digest,
+    resolution::{digest::compute_digest, git::GitCheckouts, lock_file::LockFile},
     source_package::{
         layout::SourcePackageLayout,
         manifest_parser::{parse_move_manifest_string, parse_source_manifest},
         parsed_manifest::{
-            Dependency, FileName, NamedAddress, PackageDigest, PackageName, SourceManifest,
-            SubstOrRename,
+            Dependency, FileName, GitInfo, NamedAddress, PackageDigest, PackageName,
+            SourceManifest, SubstOrRename,
         },
     },
     BuildConfig,
@@ -23,7 +23,6 @@ use std::{
     cell::RefCell,
     collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
-    process::Command,
     rc::Rc,
 };
 
@@ -87,10 +86,43 @@ pub struct ResolutionPackage<T> {
 }
 
 impl ResolvingGraph {
+    /// Start resolving the dependencies of `root_package`. Git dependencies are checked out at
+    /// the commits pinned by the package's lock file, if it has one.
     pub fn new(
         root_package: SourceManifest,
         root_package_path: PathBuf,
         build_options: BuildConfig,
+    ) -> Result<ResolvingGraph> {
+        let lock_file = LockFile::read(&root_package_path)?;
+        Self::new_with_checkouts(
+            root_package,
+            root_package_path,
+            build_options,
+            GitCheckouts::locked(lock_file),
+        )
+    }
+
+    /// Start resolving the dependencies of `root_package`, fetching the latest commit of the
+    /// revision of each git dependency rather than using the commits pinned by the package's lock
+    /// file.
+    pub fn new_with_latest_dependencies(
+        root_package: SourceManifest,
+        root_package_path: PathBuf,
+        build_options: BuildConfig,
+    ) -> Result<ResolvingGraph> {
+        Self::new_with_checkouts(
+            root_package,
+            root_package_path,
+            build_options,
+            GitCheckouts::latest(),
+        )
+    }
+
+    fn new_with_checkouts(
+        root_package: SourceManifest,
+        root_package_path: PathBuf,
+        build_options: BuildConfig,
+        checkouts: GitCheckouts,
     ) -> Result<ResolvingGraph> {
         let mut resolution_graph = Self {
             root_package_path: root_package_path.clone(),
@@ -101,7 +133,7 @@ impl ResolvingGraph {
         };
 
         resolution_graph
-            .build_resolution_graph(root_package.clone(), root_package_path, true)
+            .build_resolution_graph(root_package.clone(), root_package_path, true, &checkouts)
             .with_context(|| {
                 format!(
                     "Unable to resolve packages for package '{}'",
@@ -182,6 +214,7 @@ impl ResolvingGraph {
         package: SourceManifest,
         package_path: PathBuf,
         is_root_package: bool,
+        checkouts: &GitCheckouts,
     ) -> Result<()> {
         let package_name = package.package.name;
         let package_node_id = match self.package_table.get(&package_name) {
@@ -234,7 +267,7 @@ impl ResolvingGraph {
             self.graph.add_edge(package_node_id, dep_node_id, ());
 
             let (dep_renaming, dep_resolution_table) = self
-                .process_dependency(dep_name, dep, package_path.clone())
+                .process_dependency(dep_name, dep, package_path.clone(), checkouts)
                 .with_context(|| {
                     format!(
                         "While resolving dependency '{}' in package '{}'",
@@ -351,12 +384,13 @@ impl ResolvingGraph {
         dep_name_in_pkg: PackageName,
         dep: Dependency,
         root_path: PathBuf,
+        checkouts: &GitCheckouts,
     ) -> Result<(Renaming, ResolvingTable)> {
-        Self::download_and_update_if_repo(dep_name_in_pkg, &dep)?;
+        let dep = checkouts.checkout(dep_name_in_pkg, dep)?;
         let (dep_package, dep_package_dir) =
             Self::parse_package_manifest(&dep, &dep_name_in_pkg, root_path)
                 .with_context(|| format!("While processing dependency '{}'", dep_name_in_pkg))?;
-        self.build_resolution_graph(dep_package.clone(), dep_package_dir, false)
+        self.build_resolution_graph(dep_package.clone(), dep_package_dir, false, checkouts)
             .with_context(|| {
                 format!("Unable to resolve package dependency '{}'", dep_name_in_pkg)
             })?;
@@ -474,39 +508,6 @@ impl ResolvingGraph {
             )),
         }
     }
-
-    fn download_and_update_if_repo(dep_name: PackageName, dep: &Dependency) -> Result<()> {
-        if let Some(git_info) = &dep.git_info {
-            if !git_info.download_to.exists() {
-                Command::new("git")
-                    .args([
-                        "clone",
-                        &git_info.git_url,
-                        &git_info.download_to.to_string_lossy(),
-                    ])
-                    .output()
-                    .map_err(|_| {
-                        anyhow::anyhow!("Failed to clone Git repository for package '{}'", dep_name)
-                    })?;
-                Command::new("git")
-                    .args([
-                        "-C",
-                        &git_info.download_to.to_string_lossy(),
-                        "checkout",
-                        &git_info.git_rev,
-                    ])
-                    .output()
-                    .map_err(|_| {
-                        anyhow::anyhow!(
-                            "Failed to checkout Git reference '{}' for package '{}'",
-                            &git_info.git_rev,
-                            dep_name
-                        )
-                    })?;
-            }
-        }
-        Ok(())
-    }
 }
 
 impl ResolvingPackage {
@@ -633,6 +634,47 @@ impl ResolvedGraph {
         self.package_table.get(package_ident).unwrap()
     }
 
+    /// The repository the package `package_ident` is downloaded from, if it is a git dependency
+    pub fn git_info(&self, package_ident: &PackageName) -> Option<&GitInfo> {
+        self.package_table.values().find_map(|package| {
+            let manifest = &package.source_package;
+            manifest
+                .dependencies
+                .get(package_ident)
+                .or_else(|| manifest.dev_dependencies.get(package_ident))
+                .and_then(|dep| dep.git_info.as_ref())
+        })
+    }
+
+    /// Whether any package in the graph depends on a package downloaded from a git repository
+    pub fn has_git_dependencies(&self) -> bool {
+        self.package_table.values().any(|package| {
+            let manifest = &package.source_package;
+            manifest
+                .dependencies
+                .values()
+                .chain(manifest.dev_dependencies.values())
+                .any(|dep| dep.git_info.is_some())
+        })
+    }
+
+    /// The packages that are only dependencies of the root package in dev mode, through the
+    /// dev-dependencies of some package in the graph
+    pub fn dev_only_packages(&self) -> BTreeSet<PackageName> {
+        let mut non_dev_packages = BTreeSet::new();
+        let mut to_visit = vec![self.root_package.package.name];
+        while let Some(name) = to_visit.pop() {
+            if non_dev_packages.insert(name) {
+                to_visit.extend(self.get_package(&name).source_package.dependencies.keys());
+            }
+        }
+        self.package_table
+            .keys()
+            .filter(|name| !non_dev_packages.contains(name))
+            .copied()
+            .collect()
+    }
+
     fn print_info_dfs(&self, current_node: &PackageName, tree: &mut TreeBuilder) -> Result<()> {
         let pkg = self.package_table.get(current_node).unwrap();
 
@@ -659,6 +701,19 @@ impl ResolvedGraph {
 }
 
 impl ResolvedPackage {
+    /// The digest of the package outside of dev mode, which doesn't cover its tests and examples
+    pub fn source_digest_outside_dev_mode(&self, config: &BuildConfig) -> Result<PackageDigest> {
+        if !config.dev_mode {
+            return Ok(self.source_digest);
+        }
+        let config = BuildConfig {
+            dev_mode: false,
+            test_mode: false,
+            ..config.clone()
+        };
+        ResolvingPackage::get_package_digest_for_config(&self.package_path, &config)
+    }
+
     pub fn get_sources(&self, config: &BuildConfig) -> Result<Vec<FileName>> {
         let places_to_look =
             ResolvingPackage::get_source_paths_for_config(&self.package_path, config)?
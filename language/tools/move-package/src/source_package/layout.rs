@@ -16,6 +16,7 @@ pub enum SourcePackageLayout {
     Scripts,
     Examples,
     Manifest,
+    LockFile,
     DocTemplates,
 }
 
@@ -23,6 +24,7 @@ impl SourcePackageLayout {
     /// A Move source package is laid out on-disk as
     /// a_move_package
     /// ├── Move.toml      (required)
+    /// ├── Move.lock      (optional)
     /// ├── sources        (required)
     /// ├── examples       (optional, dev mode)
     /// ├── scripts        (optional)
@@ -52,6 +54,7 @@ impl SourcePackageLayout {
         match self {
             Self::Sources => "sources",
             Self::Manifest => "Move.toml",
+            Self::LockFile => "Move.lock",
             Self::Tests => "tests",
             Self::Scripts => "scripts",
             Self::Examples => "examples",
@@ -64,6 +67,7 @@ impl SourcePackageLayout {
         match self {
             Self::Sources | Self::Manifest => false,
             Self::Tests
+            | Self::LockFile
             | Self::Scripts
             | Self::Examples
             | Self::Specifications
@@ -4,6 +4,7 @@
 use crate::source_package::parsed_manifest as PM;
 use anyhow::{bail, format_err, Context, Result};
 use move_core_types::account_address::{AccountAddress, AccountAddressParseError};
+use move_lang::linter::{self, LintLevel, LintLevels};
 use move_symbol_pool::symbol::Symbol;
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -21,6 +22,7 @@ const ADDRESSES_NAME: &str = "addresses";
 const DEV_ADDRESSES_NAME: &str = "dev-addresses";
 const DEPENDENCY_NAME: &str = "dependencies";
 const DEV_DEPENDENCY_NAME: &str = "dev-dependencies";
+const LINTS_NAME: &str = "lints";
 
 const KNOWN_NAMES: &[&str] = &[
     PACKAGE_NAME,
@@ -29,6 +31,7 @@ const KNOWN_NAMES: &[&str] = &[
     DEV_ADDRESSES_NAME,
     DEPENDENCY_NAME,
     DEV_DEPENDENCY_NAME,
+    LINTS_NAME,
 ];
 
 const REQUIRED_FIELDS: &[&str] = &[PACKAGE_NAME];
@@ -85,6 +88,12 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 .transpose()
                 .context("Error parsing '[dev-dependencies]' section of manifest")?
                 .unwrap_or_else(BTreeMap::new);
+            let lints = table
+                .remove(LINTS_NAME)
+                .map(parse_lints)
+                .transpose()
+                .context("Error parsing '[lints]' section of manifest")?
+                .unwrap_or_else(BTreeMap::new);
             Ok(PM::SourceManifest {
                 package,
                 addresses,
@@ -92,6 +101,7 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 build,
                 dependencies,
                 dev_dependencies,
+                lints,
             })
         }
         x => {
@@ -274,6 +284,41 @@ pub fn parse_dev_addresses(tval: TV) -> Result<PM::DevAddressDeclarations> {
     }
 }
 
+pub fn parse_lints(tval: TV) -> Result<LintLevels> {
+    match tval {
+        TV::Table(table) => {
+            let lint_names = linter::LINTS
+                .iter()
+                .map(|lint| lint.name())
+                .collect::<Vec<_>>();
+            warn_if_unknown_field_names(&table, &lint_names);
+            let mut lints = BTreeMap::new();
+            for (lint_name, entry) in table.into_iter() {
+                if linter::find_lint(&lint_name).is_none() {
+                    continue;
+                }
+                let level = match entry.as_str() {
+                    Some(level_str) => level_str
+                        .parse::<LintLevel>()
+                        .with_context(|| format!("Invalid level for lint '{}'", lint_name))?,
+                    None => bail!(
+                        "Invalid level for lint '{}'. Expected a string but found a {}",
+                        lint_name,
+                        entry.type_str()
+                    ),
+                };
+                lints.insert(Symbol::from(lint_name), level);
+            }
+            Ok(lints)
+        }
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
 // Safely parses address for both the 0x and non prefixed hex format.
 fn parse_address_literal(address_str: &str) -> Result<AccountAddress, AccountAddressParseError> {
     if !address_str.starts_with("0x") {
@@ -319,13 +364,6 @@ fn parse_dependency(tval: TV) -> Result<PM::Dependency> {
                     })
                 }
                 (None, Some(git)) => {
-                    // Look to see if a MOVE_HOME has been set. Otherwise default to $HOME
-                    let move_home = std::env::var("MOVE_HOME").unwrap_or_else(|_| {
-                        format!(
-                            "{}/.move",
-                            std::env::var("HOME").expect("env var 'HOME' must be set")
-                        )
-                    });
                     let rev_name = match table.remove("rev") {
                         None => bail!("Git revision not supplied for dependency"),
                         Some(r) => Symbol::from(
@@ -333,16 +371,11 @@ fn parse_dependency(tval: TV) -> Result<PM::Dependency> {
                                 .ok_or_else(|| format_err!("Git revision not a string"))?,
                         ),
                     };
-                    // Downloaded packages are of the form <sanitized_git_url>_<rev_name>
-                    let local_path = PathBuf::from(move_home).join(format!(
-                        "{}_{}",
-                        regex::Regex::new(r"/|:|\.|@").unwrap().replace_all(
-                            git.as_str()
-                                .ok_or_else(|| anyhow::anyhow!("Git URL not a string"))?,
-                            "_"
-                        ),
-                        rev_name.replace('/', "__")
-                    ));
+                    let local_path = git_download_path(
+                        git.as_str()
+                            .ok_or_else(|| anyhow::anyhow!("Git URL not a string"))?,
+                        &rev_name,
+                    );
                     let subdir = PathBuf::from(match table.remove("subdir") {
                         None => "".to_string(),
                         Some(path) => path
@@ -380,6 +413,26 @@ fn parse_dependency(tval: TV) -> Result<PM::Dependency> {
     }
 }
 
+/// The directory the repository at `git_url` is downloaded to when checked out at `rev`, which
+/// may be a branch, a tag or a commit.
+pub fn git_download_path(git_url: &str, rev: &str) -> PathBuf {
+    // Look to see if a MOVE_HOME has been set. Otherwise default to $HOME
+    let move_home = std::env::var("MOVE_HOME").unwrap_or_else(|_| {
+        format!(
+            "{}/.move",
+            std::env::var("HOME").expect("env var 'HOME' must be set")
+        )
+    });
+    // Downloaded packages are of the form <sanitized_git_url>_<rev_name>
+    PathBuf::from(move_home).join(format!(
+        "{}_{}",
+        regex::Regex::new(r"/|:|\.|@")
+            .unwrap()
+            .replace_all(git_url, "_"),
+        rev.replace('/', "__")
+    ))
+}
+
 fn parse_substitution(tval: TV) -> Result<PM::Substitution> {
     match tval {
         TV::Table(table) => {
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use move_core_types::account_address::AccountAddress;
+use move_lang::linter::LintLevels;
 use move_symbol_pool::symbol::Symbol;
 use std::{collections::BTreeMap, path::PathBuf};
 
@@ -24,6 +25,7 @@ pub struct SourceManifest {
     pub build: Option<BuildInfo>,
     pub dependencies: Dependencies,
     pub dev_dependencies: Dependencies,
+    pub lints: LintLevels,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
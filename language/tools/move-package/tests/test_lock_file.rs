@@ -0,0 +1,330 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_package::{
+    resolution::lock_file::{LockFile, LockedPackage},
+    BuildConfig,
+};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Once,
+};
+use tempfile::{tempdir, TempDir};
+
+#[test]
+fn lock_file_round_trip() {
+    let path = Path::new("tests/test_sources/resolution/one_dep");
+    let resolved_graph = BuildConfig {
+        install_dir: Some(tempdir().unwrap().path().to_path_buf()),
+        ..Default::default()
+    }
+    .resolution_graph_for_package(path)
+    .unwrap();
+
+    let lock_file = LockFile::from_resolved_graph(&resolved_graph).unwrap();
+    assert_eq!(lock_file.packages.len(), 1);
+    let locked_dep = &lock_file.packages[0];
+    assert_eq!(locked_dep.name, "OtherDep");
+    assert_eq!(locked_dep.commit, None);
+    assert_eq!(
+        locked_dep.addresses.get("B").map(String::as_str),
+        Some("0x1")
+    );
+
+    let lock_dir = tempdir().unwrap();
+    lock_file.write(lock_dir.path()).unwrap();
+    let read_lock_file = LockFile::read(lock_dir.path()).unwrap().unwrap();
+    assert_eq!(read_lock_file, lock_file);
+    assert!(lock_file.differences(&read_lock_file).is_empty());
+
+    let mut changed_lock_file = lock_file.clone();
+    changed_lock_file.packages[0].digest = "0".to_string();
+    assert_eq!(
+        lock_file.differences(&changed_lock_file),
+        vec!["Dependency 'OtherDep' has a different digest than locked".to_string()]
+    );
+}
+
+#[test]
+fn locked_build_requires_lock_file() {
+    let path = Path::new("tests/test_sources/resolution/one_dep");
+    let err = BuildConfig {
+        install_dir: Some(tempdir().unwrap().path().to_path_buf()),
+        locked: true,
+        ..Default::default()
+    }
+    .resolution_graph_for_package(path)
+    .unwrap_err();
+    assert!(err.to_string().contains("Move.lock not found"));
+}
+
+#[test]
+fn resolution_without_lock_file() {
+    let path = Path::new("tests/test_sources/resolution/one_dep");
+    BuildConfig {
+        install_dir: Some(tempdir().unwrap().path().to_path_buf()),
+        locked: true,
+        ..Default::default()
+    }
+    .resolution_graph_for_package_without_lock_file(path)
+    .unwrap();
+    assert!(!path.join("Move.lock").exists());
+}
+
+/// A Move package in a git repository, pushed to a local bare repository that packages depend on
+struct GitPackage {
+    dir: TempDir,
+}
+
+impl GitPackage {
+    fn new() -> Self {
+        // Git dependencies are downloaded under MOVE_HOME, which is shared by all tests
+        static SET_MOVE_HOME: Once = Once::new();
+        SET_MOVE_HOME.call_once(|| {
+            std::env::set_var("MOVE_HOME", tempdir().unwrap().into_path());
+        });
+
+        let package = Self {
+            dir: tempdir().unwrap(),
+        };
+        git(
+            &package.dir.path().join("remote.git"),
+            &["init", "--quiet", "--bare"],
+        );
+        git(
+            &package.remote(),
+            &["symbolic-ref", "HEAD", "refs/heads/main"],
+        );
+        let work = package.work();
+        std::fs::create_dir_all(work.join("sources")).unwrap();
+        std::fs::write(
+            work.join("Move.toml"),
+            "[package]\nname = \"GitDep\"\nversion = \"0.0.0\"\n\n[addresses]\nGitDep = \"0x2\"\n",
+        )
+        .unwrap();
+        git(&work, &["init", "--quiet"]);
+        git(
+            &work,
+            &[
+                "remote",
+                "add",
+                "origin",
+                &package.remote().to_string_lossy(),
+            ],
+        );
+        package
+    }
+
+    fn remote(&self) -> PathBuf {
+        self.dir.path().join("remote.git")
+    }
+
+    fn work(&self) -> PathBuf {
+        self.dir.path().join("work")
+    }
+
+    /// Commit a new version of the package and push it to the `main` branch of the remote.
+    fn push_version(&self, version: u64) -> String {
+        let work = self.work();
+        std::fs::write(
+            work.join("sources").join("M.move"),
+            format!(
+                "module GitDep::M {{ public fun version(): u64 {{ {} }} }}\n",
+                version
+            ),
+        )
+        .unwrap();
+        git(&work, &["add", "."]);
+        git(
+            &work,
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                &format!("version {}", version),
+            ],
+        );
+        git(
+            &work,
+            &["push", "--quiet", "origin", "HEAD:refs/heads/main"],
+        );
+        git(&work, &["rev-parse", "HEAD"])
+    }
+
+    /// Create a package depending on this one at `rev` in its `section` of dependencies.
+    fn dependent_package(&self, section: &str, rev: &str) -> TempDir {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("sources")).unwrap();
+        std::fs::write(
+            dir.path().join("Move.toml"),
+            format!(
+                "[package]\nname = \"Root\"\nversion = \"0.0.0\"\n\n[{}]\nGitDep = {{ git = \"{}\", rev = \"{}\" }}\n",
+                section,
+                self.remote().to_string_lossy(),
+                rev
+            ),
+        )
+        .unwrap();
+        dir
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    std::fs::create_dir_all(dir).unwrap();
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "`git {}` failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn build_config(install_dir: &Path) -> BuildConfig {
+    BuildConfig {
+        install_dir: Some(install_dir.to_path_buf()),
+        ..Default::default()
+    }
+}
+
+/// Resolve the package at `path` and return the commit its git dependency was checked out at.
+fn resolve(config: BuildConfig, path: &Path) -> String {
+    let resolved_graph = config.resolution_graph_for_package(path).unwrap();
+    let package = resolved_graph.get_package(&"GitDep".into());
+    git(&package.package_path, &["rev-parse", "HEAD"])
+}
+
+fn locked_git_dep(path: &Path) -> LockedPackage {
+    let lock_file = LockFile::read(path).unwrap().unwrap();
+    assert_eq!(lock_file.packages.len(), 1);
+    lock_file.packages[0].clone()
+}
+
+#[test]
+fn git_dependency_is_pinned_when_its_branch_moves() {
+    let git_package = GitPackage::new();
+    let first_commit = git_package.push_version(1);
+    let root = git_package.dependent_package("dependencies", "main");
+    let install_dir = tempdir().unwrap();
+
+    assert_eq!(
+        resolve(build_config(install_dir.path()), root.path()),
+        first_commit
+    );
+    let locked = locked_git_dep(root.path());
+    assert_eq!(locked.commit.as_deref(), Some(first_commit.as_str()));
+    assert!(!locked.dev);
+    assert_eq!(
+        locked.addresses.get("GitDep").map(String::as_str),
+        Some("0x2")
+    );
+
+    git_package.push_version(2);
+    let config = BuildConfig {
+        locked: true,
+        ..build_config(install_dir.path())
+    };
+    assert_eq!(resolve(config.clone(), root.path()), first_commit);
+    let dev_config = BuildConfig {
+        dev_mode: true,
+        ..config
+    };
+    assert_eq!(resolve(dev_config, root.path()), first_commit);
+    assert_eq!(locked_git_dep(root.path()), locked);
+}
+
+#[test]
+fn git_dev_dependency_is_pinned() {
+    let git_package = GitPackage::new();
+    let first_commit = git_package.push_version(1);
+    let root = git_package.dependent_package("dev-dependencies", "main");
+    let install_dir = tempdir().unwrap();
+    let dev_config = BuildConfig {
+        dev_mode: true,
+        ..build_config(install_dir.path())
+    };
+
+    assert_eq!(resolve(dev_config.clone(), root.path()), first_commit);
+    let locked = locked_git_dep(root.path());
+    assert_eq!(locked.commit.as_deref(), Some(first_commit.as_str()));
+    assert!(locked.dev);
+
+    // Builds outside of dev mode don't resolve the dev-dependency, but keep its pin
+    build_config(install_dir.path())
+        .resolution_graph_for_package(root.path())
+        .unwrap();
+    assert_eq!(locked_git_dep(root.path()), locked);
+
+    git_package.push_version(2);
+    let locked_config = BuildConfig {
+        locked: true,
+        ..dev_config
+    };
+    assert_eq!(resolve(locked_config, root.path()), first_commit);
+    assert_eq!(locked_git_dep(root.path()), locked);
+}
+
+#[test]
+fn locked_build_fails_when_dependency_changes() {
+    let git_package = GitPackage::new();
+    git_package.push_version(1);
+    let root = git_package.dependent_package("dependencies", "main");
+    let install_dir = tempdir().unwrap();
+    resolve(build_config(install_dir.path()), root.path());
+
+    git_package.push_version(2);
+    git(&git_package.work(), &["tag", "v2"]);
+    git(&git_package.work(), &["push", "--quiet", "origin", "v2"]);
+    let manifest = root.path().join("Move.toml");
+    let contents = std::fs::read_to_string(&manifest).unwrap();
+    std::fs::write(&manifest, contents.replace("\"main\"", "\"v2\"")).unwrap();
+
+    let err = BuildConfig {
+        locked: true,
+        ..build_config(install_dir.path())
+    }
+    .resolution_graph_for_package(root.path())
+    .unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("Move.lock is out of date"), "{}", message);
+    assert!(
+        message.contains("Dependency 'GitDep' has a different source, commit, digest than locked"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn package_update_locks_latest_commits() {
+    let git_package = GitPackage::new();
+    git_package.push_version(1);
+    let root = git_package.dependent_package("dependencies", "main");
+    let install_dir = tempdir().unwrap();
+    resolve(build_config(install_dir.path()), root.path());
+
+    let second_commit = git_package.push_version(2);
+    let lock_file = build_config(install_dir.path())
+        .update_lock_file(root.path())
+        .unwrap();
+    assert_eq!(LockFile::read(root.path()).unwrap().unwrap(), lock_file);
+    assert_eq!(
+        locked_git_dep(root.path()).commit.as_deref(),
+        Some(second_commit.as_str())
+    );
+
+    let config = BuildConfig {
+        locked: true,
+        ..build_config(install_dir.path())
+    };
+    assert_eq!(resolve(config, root.path()), second_commit);
+}
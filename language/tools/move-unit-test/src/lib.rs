@@ -13,11 +13,12 @@ use move_lang::{
     unit_test::{self, TestPlan},
     Compiler, Flags, PASS_CFGIR,
 };
-use move_vm_runtime::native_functions::NativeFunctionTable;
+use move_vm_runtime::{coverage::ExecutionCounts, native_functions::NativeFunctionTable};
 use std::{
     collections::BTreeMap,
     io::{Result, Write},
     marker::Send,
+    path::PathBuf,
     sync::Mutex,
 };
 use structopt::*;
@@ -84,6 +85,11 @@ pub struct UnitTestingConfig {
     /// Verbose mode
     #[structopt(short = "v", long = "verbose")]
     pub verbose: bool,
+
+    /// Profile the gas used by the tests and write the profile to this file, as a speedscope
+    /// profile if the file has a `.json` extension and as folded stacks otherwise
+    #[structopt(long = "gas-profile", parse(from_os_str))]
+    pub gas_profile: Option<PathBuf>,
 }
 
 fn format_module_id(module_id: &ModuleId) -> String {
@@ -109,6 +115,7 @@ impl UnitTestingConfig {
             verbose: false,
             list: false,
             named_address_values: vec![],
+            gas_profile: None,
         }
     }
 
@@ -171,6 +178,29 @@ impl UnitTestingConfig {
         native_function_table: Option<NativeFunctionTable>,
         writer: W,
     ) -> Result<(W, bool)> {
+        let (writer, all_tests_passed, _) =
+            self.run_and_report(test_plan, native_function_table, writer, false)?;
+        Ok((writer, all_tests_passed))
+    }
+
+    /// Same as `run_and_report_unit_tests`, but also collects the coverage of the tests while they
+    /// run, and returns the executions counted over all tests.
+    pub fn run_and_report_unit_tests_with_coverage<W: Write + Send>(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        writer: W,
+    ) -> Result<(W, bool, ExecutionCounts)> {
+        self.run_and_report(test_plan, native_function_table, writer, true)
+    }
+
+    fn run_and_report<W: Write + Send>(
+        &self,
+        test_plan: TestPlan,
+        native_function_table: Option<NativeFunctionTable>,
+        writer: W,
+        collect_coverage: bool,
+    ) -> Result<(W, bool, ExecutionCounts)> {
         let shared_writer = Mutex::new(writer);
 
         if self.list {
@@ -184,7 +214,11 @@ impl UnitTestingConfig {
                     )?;
                 }
             }
-            return Ok((shared_writer.into_inner().unwrap(), true));
+            return Ok((
+                shared_writer.into_inner().unwrap(),
+                true,
+                ExecutionCounts::default(),
+            ));
         }
 
         writeln!(shared_writer.lock().unwrap(), "Running Move unit tests")?;
@@ -194,6 +228,8 @@ impl UnitTestingConfig {
             self.check_stackless_vm,
             self.verbose,
             self.report_storage_on_error,
+            self.gas_profile.is_some(),
+            collect_coverage,
             test_plan,
             native_function_table,
             shared::verify_and_create_named_address_mapping(self.named_address_values.clone())
@@ -209,9 +245,20 @@ impl UnitTestingConfig {
         if self.report_statistics {
             test_results.report_statistics(&shared_writer)?;
         }
+        if let Some(gas_profile_path) = &self.gas_profile {
+            test_results
+                .gas_profile()
+                .write_to_file(gas_profile_path, "Move unit tests")?;
+            writeln!(
+                shared_writer.lock().unwrap(),
+                "Wrote gas profile to {}",
+                gas_profile_path.display()
+            )?;
+        }
+        let coverage = test_results.coverage().clone();
         let all_tests_passed = test_results.summarize(&shared_writer)?;
 
         let writer = shared_writer.into_inner().unwrap();
-        Ok((writer, all_tests_passed))
+        Ok((writer, all_tests_passed, coverage))
     }
 }
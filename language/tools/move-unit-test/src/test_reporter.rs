@@ -9,6 +9,7 @@ use move_lang::{
     diagnostics::{self, Diagnostic},
     unit_test::{ModuleTestPlan, TestPlan},
 };
+use move_vm_runtime::{coverage::ExecutionCounts, gas_profiler::GasProfile};
 use std::{
     collections::{BTreeMap, BTreeSet},
     io::{Result, Write},
@@ -58,6 +59,8 @@ pub struct TestRunInfo {
 pub struct TestStatistics {
     passed: BTreeMap<ModuleId, BTreeSet<TestRunInfo>>,
     failed: BTreeMap<ModuleId, BTreeSet<TestFailure>>,
+    gas_profile: GasProfile,
+    coverage: ExecutionCounts,
 }
 
 #[derive(Debug, Clone)]
@@ -254,6 +257,8 @@ impl TestStatistics {
         Self {
             passed: BTreeMap::new(),
             failed: BTreeMap::new(),
+            gas_profile: GasProfile::default(),
+            coverage: ExecutionCounts::default(),
         }
     }
 
@@ -271,6 +276,14 @@ impl TestStatistics {
             .insert(test_info);
     }
 
+    pub fn record_gas_profile(&mut self, gas_profile: GasProfile) {
+        self.gas_profile.merge(gas_profile);
+    }
+
+    pub fn record_coverage(&mut self, coverage: ExecutionCounts) {
+        self.coverage.merge(coverage);
+    }
+
     pub fn combine(mut self, other: Self) -> Self {
         for (module_id, test_result) in other.passed {
             let entry = self.passed.entry(module_id).or_default();
@@ -280,6 +293,8 @@ impl TestStatistics {
             let entry = self.failed.entry(module_id).or_default();
             entry.extend(test_result.into_iter());
         }
+        self.gas_profile.merge(other.gas_profile);
+        self.coverage.merge(other.coverage);
         self
     }
 }
@@ -292,6 +307,16 @@ impl TestResults {
         }
     }
 
+    /// The gas profile of all tests, if they were run with gas profiling.
+    pub fn gas_profile(&self) -> &GasProfile {
+        &self.final_statistics.gas_profile
+    }
+
+    /// The executions of all tests, if they were run collecting coverage.
+    pub fn coverage(&self) -> &ExecutionCounts {
+        &self.final_statistics.coverage
+    }
+
     pub fn report_statistics<W: Write>(&self, writer: &Mutex<W>) -> Result<()> {
         writeln!(writer.lock().unwrap(), "\nTest Statistics:\n")?;
 
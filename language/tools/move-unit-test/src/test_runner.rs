@@ -31,7 +31,12 @@ use move_model::{
     run_model_builder_with_options_and_compilation_flags,
 };
 use move_resource_viewer::MoveValueAnnotator;
-use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
+use move_vm_runtime::{
+    coverage::{self, ExecutionCounts},
+    gas_profiler::{self, GasProfile},
+    move_vm::MoveVM,
+    native_functions::NativeFunctionTable,
+};
 use move_vm_test_utils::InMemoryStorage;
 use move_vm_types::gas_schedule::{zero_cost_schedule, GasStatus};
 use rayon::prelude::*;
@@ -48,6 +53,8 @@ pub struct SharedTestingConfig {
     named_address_values: BTreeMap<String, NumericalAddress>,
     check_stackless_vm: bool,
     verbose: bool,
+    profile_gas: bool,
+    collect_coverage: bool,
 }
 
 pub struct TestRunner {
@@ -118,6 +125,8 @@ impl TestRunner {
         check_stackless_vm: bool,
         verbose: bool,
         save_storage_state_on_failure: bool,
+        profile_gas: bool,
+        collect_coverage: bool,
         tests: TestPlan,
         native_function_table: Option<NativeFunctionTable>,
         named_address_values: BTreeMap<String, NumericalAddress>,
@@ -142,6 +151,8 @@ impl TestRunner {
                 source_files,
                 check_stackless_vm,
                 verbose,
+                profile_gas,
+                collect_coverage,
                 named_address_values,
             },
             num_threads,
@@ -187,12 +198,27 @@ impl SharedTestingConfig {
         test_plan: &ModuleTestPlan,
         function_name: &str,
         test_info: &TestCase,
-    ) -> (VMResult<ChangeSet>, VMResult<Vec<Vec<u8>>>, TestRunInfo) {
+    ) -> (
+        VMResult<ChangeSet>,
+        VMResult<Vec<Vec<u8>>>,
+        TestRunInfo,
+        Option<GasProfile>,
+        Option<ExecutionCounts>,
+    ) {
         let move_vm = MoveVM::new(self.native_function_table.clone()).unwrap();
-        let mut session = move_vm.new_session(&self.starting_storage_state);
+        // Every test starts from the same storage, so naming its session after the test is enough
+        // to give the tables it creates distinct handles.
+        let session_id = format!("{}::{}", test_plan.module_id, function_name).into_bytes();
+        let mut session = move_vm.new_session_with_id(&self.starting_storage_state, session_id);
         let mut gas_meter = GasStatus::new(&self.cost_table, GasUnits::new(self.execution_bound));
         // TODO: collect VM logs if the verbose flag (i.e, `self.verbose`) is set
 
+        if self.profile_gas {
+            gas_profiler::start_gas_profiling();
+        }
+        if self.collect_coverage {
+            coverage::start_coverage_collection();
+        }
         let now = Instant::now();
         let return_result = session.execute_function(
             &test_plan.module_id,
@@ -201,6 +227,8 @@ impl SharedTestingConfig {
             serialize_values(test_info.arguments.iter()),
             &mut gas_meter,
         );
+        let gas_profile = gas_profiler::finish_gas_profiling();
+        let coverage = coverage::finish_coverage_collection();
         let test_run_info = TestRunInfo::new(
             function_name.to_string(),
             now.elapsed(),
@@ -210,6 +238,8 @@ impl SharedTestingConfig {
             session.finish().map(|(cs, _)| cs),
             return_result,
             test_run_info,
+            gas_profile,
+            coverage,
         )
     }
 
@@ -314,8 +344,14 @@ impl SharedTestingConfig {
         };
 
         for (function_name, test_info) in &test_plan.tests {
-            let (cs_result, exec_result, test_run_info) =
+            let (cs_result, exec_result, test_run_info, gas_profile, coverage) =
                 self.execute_via_move_vm(test_plan, function_name, test_info);
+            if let Some(gas_profile) = gas_profile {
+                stats.record_gas_profile(gas_profile);
+            }
+            if let Some(coverage) = coverage {
+                stats.record_coverage(coverage);
+            }
             if self.check_stackless_vm {
                 let (stackless_vm_change_set, stackless_vm_result, _, prop_check_result) = self
                     .execute_via_stackless_vm(
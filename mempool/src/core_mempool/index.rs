@@ -65,6 +65,11 @@ impl PriorityIndex {
         self.data.iter().rev()
     }
 
+    /// Returns the lowest priority transaction which is not sent by `address`.
+    pub(crate) fn lowest_excluding(&self, address: &AccountAddress) -> Option<&OrderedQueueKey> {
+        self.data.iter().find(|key| &key.address != address)
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.data.len()
     }
@@ -0,0 +1,219 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Journal of the transactions accepted by Mempool, used to restore them after a node restart.
+//!
+//! The journal is an append-only file of length-prefixed BCS-encoded `JournalEntry`s. Entries are
+//! never removed individually: transactions which left Mempool are dropped when the journal is
+//! compacted, i.e. rewritten with the transactions still in Mempool. The new content is written
+//! without holding the Mempool lock, and the entries appended meanwhile are carried over to it.
+//!
+//! Appends are not synced to disk, so that inserting a transaction doesn't wait for the disk: the
+//! journal survives a crash of the process, but the last transactions accepted before a crash of
+//! the machine may be lost. They are resubmitted by clients or broadcast again by peers anyway.
+//!
+//! A journaled transaction keeps the system TTL it had when it was first accepted, so that it
+//! doesn't outlive it by being restored on every restart.
+use crate::{
+    core_mempool::transaction::MempoolTransaction,
+    counters,
+    logging::{LogEntry, LogSchema},
+};
+use anyhow::{format_err, Result};
+use diem_logger::prelude::*;
+use diem_types::transaction::SignedTransaction;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct JournalEntry {
+    pub txn: SignedTransaction,
+    /// System TTL of the transaction in Mempool, in seconds since the Unix epoch.
+    pub expiration_time_secs: u64,
+}
+
+impl JournalEntry {
+    pub(crate) fn new(txn: &MempoolTransaction) -> Self {
+        Self {
+            txn: txn.txn.clone(),
+            expiration_time_secs: txn.expiration_time.as_secs(),
+        }
+    }
+
+    /// Whether the transaction expired, by system TTL or client-specified expiration time.
+    pub(crate) fn is_expired(&self, now: Duration) -> bool {
+        self.expiration_time_secs <= now.as_secs()
+            || self.txn.expiration_timestamp_secs() <= now.as_secs()
+    }
+}
+
+pub(crate) struct Journal {
+    path: PathBuf,
+    file: File,
+    // number of entries in the journal file
+    len: usize,
+    // entries appended since the ongoing compaction started, if any
+    appended_during_compaction: Option<Vec<JournalEntry>>,
+}
+
+/// A compaction of the journal, started with `Journal::start_compaction`. Its content is to be
+/// written with `write`, while Mempool keeps accepting transactions.
+pub(crate) struct JournalCompaction {
+    tmp_path: PathBuf,
+    entries: Vec<JournalEntry>,
+}
+
+impl JournalCompaction {
+    /// Writes the content of the compacted journal to a temporary file.
+    pub(crate) fn write(self) -> Result<CompactedJournal> {
+        let mut file = File::create(&self.tmp_path)?;
+        for entry in &self.entries {
+            file.write_all(&encode(entry)?)?;
+        }
+        Ok(CompactedJournal {
+            tmp_path: self.tmp_path,
+            file,
+            len: self.entries.len(),
+        })
+    }
+}
+
+/// The written content of a compacted journal, to be passed to `Journal::finish_compaction`.
+pub(crate) struct CompactedJournal {
+    tmp_path: PathBuf,
+    file: File,
+    len: usize,
+}
+
+impl Journal {
+    /// Opens the journal at `path`, creating it if needed.
+    /// Returns the journal and the entries it already holds. An incomplete entry at the end of the
+    /// file, left by a crash in the middle of a write, is discarded.
+    pub(crate) fn open(path: &Path) -> Result<(Self, Vec<JournalEntry>)> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        let (entries, valid_len) = read_entries(&bytes);
+        if valid_len < bytes.len() {
+            warn!(
+                LogSchema::new(LogEntry::MempoolJournal),
+                "discarding {} bytes of incomplete entries at the end of mempool journal {}",
+                bytes.len() - valid_len,
+                path.display(),
+            );
+            file.set_len(valid_len as u64)?;
+        }
+
+        let journal = Self {
+            path: path.to_path_buf(),
+            file,
+            len: entries.len(),
+            appended_during_compaction: None,
+        };
+        Ok((journal, entries))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn append(&mut self, entry: &JournalEntry) -> Result<()> {
+        self.file.write_all(&encode(entry)?)?;
+        self.len += 1;
+        if let Some(appended) = &mut self.appended_during_compaction {
+            appended.push(entry.clone());
+        }
+        Ok(())
+    }
+
+    /// Starts replacing the content of the journal by `entries`, unless a compaction is already
+    /// ongoing.
+    pub(crate) fn start_compaction(
+        &mut self,
+        entries: Vec<JournalEntry>,
+    ) -> Option<JournalCompaction> {
+        if self.appended_during_compaction.is_some() {
+            return None;
+        }
+        self.appended_during_compaction = Some(vec![]);
+        Some(JournalCompaction {
+            tmp_path: self.path.with_extension("tmp"),
+            entries,
+        })
+    }
+
+    /// Completes the compaction with its `compacted` content, adding the entries appended since
+    /// it started. The compacted journal is renamed over the journal, so a crash leaves either
+    /// the old or the new content.
+    pub(crate) fn finish_compaction(&mut self, compacted: Result<CompactedJournal>) -> Result<()> {
+        let appended = self.appended_during_compaction.take().unwrap_or_default();
+        let mut compacted = compacted?;
+        for entry in &appended {
+            compacted.file.write_all(&encode(entry)?)?;
+        }
+        compacted.file.sync_all()?;
+        fs::rename(&compacted.tmp_path, &self.path)?;
+
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.len = compacted.len + appended.len();
+        Ok(())
+    }
+}
+
+fn encode(entry: &JournalEntry) -> Result<Vec<u8>> {
+    let data = bcs::to_bytes(entry)?;
+    let len: u32 = data
+        .len()
+        .try_into()
+        .map_err(|_| format_err!("journal entry too large: {} bytes", data.len()))?;
+    let mut bytes = Vec::with_capacity(LENGTH_PREFIX_SIZE + data.len());
+    bytes.extend_from_slice(&len.to_le_bytes());
+    bytes.extend_from_slice(&data);
+    Ok(bytes)
+}
+
+/// Decodes the entries in `bytes`, stopping at the first incomplete or invalid one.
+/// Returns the entries and the length of the bytes they were decoded from.
+fn read_entries(bytes: &[u8]) -> (Vec<JournalEntry>, usize) {
+    let mut entries = vec![];
+    let mut offset = 0;
+    while bytes.len() - offset >= LENGTH_PREFIX_SIZE {
+        let mut len_bytes = [0u8; LENGTH_PREFIX_SIZE];
+        len_bytes.copy_from_slice(&bytes[offset..offset + LENGTH_PREFIX_SIZE]);
+        let start = offset + LENGTH_PREFIX_SIZE;
+        let end = start + u32::from_le_bytes(len_bytes) as usize;
+        if end > bytes.len() {
+            break;
+        }
+        match bcs::from_bytes(&bytes[start..end]) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                let e = anyhow::Error::from(e);
+                counters::MEMPOOL_JOURNAL_ERROR_COUNT.inc();
+                error!(
+                    LogSchema::new(LogEntry::MempoolJournal).error(&e),
+                    "invalid mempool journal entry at offset {}", offset
+                );
+                break;
+            }
+        }
+        offset = end;
+    }
+    (entries, offset)
+}
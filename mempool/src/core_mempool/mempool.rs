@@ -6,13 +6,15 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
-        transaction::{MempoolTransaction, TimelineState},
+        journal::{CompactedJournal, Journal, JournalCompaction, JournalEntry},
+        transaction::{MempoolStats, MempoolTransaction, PendingTransaction, TimelineState},
         transaction_store::TransactionStore,
         ttl_cache::TtlCache,
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
 };
+use anyhow::Result;
 use diem_config::config::NodeConfig;
 use diem_crypto::HashValue;
 use diem_logger::prelude::*;
@@ -24,7 +26,7 @@ use diem_types::{
 };
 use std::{
     cmp::max,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     time::{Duration, SystemTime},
 };
 
@@ -38,10 +40,26 @@ pub struct Mempool {
     // takes to pick it up by consensus.
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), SystemTime>,
     pub system_transaction_timeout: Duration,
+
+    // Journal of accepted transactions, if enabled.
+    journal: Option<Journal>,
+    // Entries read from the journal on startup, to be restored.
+    journaled_entries: Vec<JournalEntry>,
+    // System TTL of the journaled transactions being restored, by committed hash.
+    restored_expiration_times: HashMap<HashValue, Duration>,
 }
 
 impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
+        let (journal, journaled_entries) = if config.mempool.enable_journal {
+            let path = config.mempool.journal_path();
+            let (journal, entries) = Journal::open(&path).unwrap_or_else(|e| {
+                panic!("[mempool] failed to open journal {}: {}", path.display(), e)
+            });
+            (Some(journal), entries)
+        } else {
+            (None, vec![])
+        };
         Mempool {
             transactions: TransactionStore::new(&config.mempool),
             sequence_number_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
@@ -49,6 +67,9 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            journal,
+            journaled_entries,
+            restored_expiration_times: HashMap::new(),
         }
     }
 
@@ -123,6 +144,25 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    pub(crate) fn get_pending_transaction_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Option<PendingTransaction> {
+        self.transactions.get_pending_transaction_by_hash(hash)
+    }
+
+    /// Returns the transactions of `address` in Mempool, in increasing sequence number order.
+    pub(crate) fn get_account_transactions(
+        &self,
+        address: &AccountAddress,
+    ) -> Vec<PendingTransaction> {
+        self.transactions.get_account_transactions(address)
+    }
+
+    pub(crate) fn get_stats(&self) -> MempoolStats {
+        self.transactions.get_stats()
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -147,8 +187,7 @@ impl Mempool {
                 cached_value.map_or(db_sequence_number, |value| max(*value, db_sequence_number)),
             ),
         };
-        self.sequence_number_cache
-            .insert(txn.sender(), sequence_number.min_seq());
+        let sender = txn.sender();
 
         // don't accept old transactions (e.g. seq is less than account's current seq_number)
         if txn.sequence_number() < sequence_number.min_seq() {
@@ -159,8 +198,16 @@ impl Mempool {
             ));
         }
 
-        let expiration_time =
-            diem_infallible::duration_since_epoch() + self.system_transaction_timeout;
+        // A restored transaction keeps the system TTL it was journaled with.
+        let restored_expiration_time = if self.restored_expiration_times.is_empty() {
+            None
+        } else {
+            self.restored_expiration_times
+                .remove(&txn.clone().committed_hash())
+        };
+        let expiration_time = restored_expiration_time.unwrap_or_else(|| {
+            diem_infallible::duration_since_epoch() + self.system_transaction_timeout
+        });
         if timeline_state != TimelineState::NonQualified {
             self.metrics_cache
                 .insert((txn.sender(), txn.sequence_number()), SystemTime::now());
@@ -175,8 +222,116 @@ impl Mempool {
             governance_role,
             sequence_number,
         );
+        // Resubmitting a transaction already in Mempool is accepted without inserting it again,
+        // so it must not be journaled again either.
+        let journal_entry = match &self.journal {
+            Some(_)
+                if self
+                    .transactions
+                    .get(&txn_info.get_sender(), txn_info.txn.sequence_number())
+                    .as_ref()
+                    != Some(&txn_info.txn) =>
+            {
+                Some(JournalEntry::new(&txn_info))
+            }
+            _ => None,
+        };
+
+        let status = self.transactions.insert(txn_info);
+        // Only accepted transactions are cached, so that a rejected one can't evict the sequence
+        // number of an account with transactions in Mempool, which `get_block` relies on.
+        if status.code.is_accepted() {
+            self.sequence_number_cache
+                .insert(sender, sequence_number.min_seq());
+        }
+        if let (Some(journal), Some(entry)) = (&mut self.journal, journal_entry) {
+            if status.code.is_accepted() {
+                if let Err(e) = journal.append(&entry) {
+                    counters::MEMPOOL_JOURNAL_ERROR_COUNT.inc();
+                    error!(
+                        LogSchema::new(LogEntry::MempoolJournal)
+                            .txns(TxnsLog::new_txn(
+                                entry.txn.sender(),
+                                entry.txn.sequence_number()
+                            ))
+                            .error(&e),
+                        "failed to journal transaction"
+                    );
+                }
+            }
+        }
+        status
+    }
 
-        self.transactions.insert(txn_info)
+    /// Takes the unexpired transactions read from the journal on startup, in journal order.
+    /// They have to be validated again before being re-inserted, which keeps their journaled
+    /// system TTL until `finish_journal_restore` is called.
+    pub(crate) fn take_journaled_transactions(&mut self) -> Vec<SignedTransaction> {
+        let now = diem_infallible::duration_since_epoch();
+        let entries: Vec<_> = std::mem::take(&mut self.journaled_entries)
+            .into_iter()
+            .filter(|entry| !entry.is_expired(now))
+            .collect();
+        self.restored_expiration_times = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.txn.clone().committed_hash(),
+                    Duration::from_secs(entry.expiration_time_secs),
+                )
+            })
+            .collect();
+        entries.into_iter().map(|entry| entry.txn).collect()
+    }
+
+    /// Ends the restore of the journaled transactions, and drops the entries of the ones which
+    /// were not restored from the journal.
+    pub(crate) fn finish_journal_restore(&mut self) {
+        self.restored_expiration_times.clear();
+        self.compact_journal();
+    }
+
+    /// Rewrites the journal with the transactions currently in Mempool, dropping the ones which
+    /// left it.
+    pub(crate) fn compact_journal(&mut self) {
+        if let Some(compaction) = self.start_journal_compaction() {
+            self.finish_journal_compaction(compaction.write());
+        }
+    }
+
+    /// Starts compacting the journal if most of its entries are for transactions which left
+    /// Mempool. The returned compaction is meant to be written without holding the Mempool lock,
+    /// and then passed to `finish_journal_compaction`.
+    pub(crate) fn start_journal_compaction_if_needed(&mut self) -> Option<JournalCompaction> {
+        match &self.journal {
+            Some(journal) if journal.len() > 2 * self.transactions.size() => {
+                self.start_journal_compaction()
+            }
+            _ => None,
+        }
+    }
+
+    fn start_journal_compaction(&mut self) -> Option<JournalCompaction> {
+        let journal = self.journal.as_mut()?;
+        let entries = self.transactions.iter().map(JournalEntry::new).collect();
+        journal.start_compaction(entries)
+    }
+
+    pub(crate) fn finish_journal_compaction(&mut self, compacted: Result<CompactedJournal>) {
+        if let Some(journal) = &mut self.journal {
+            if let Err(e) = journal.finish_compaction(compacted) {
+                counters::MEMPOOL_JOURNAL_ERROR_COUNT.inc();
+                error!(
+                    LogSchema::new(LogEntry::MempoolJournal).error(&e),
+                    "failed to compact journal"
+                );
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn journal_len(&self) -> Option<usize> {
+        self.journal.as_ref().map(Journal::len)
     }
 
     /// Fetches next block of transactions for consensus.
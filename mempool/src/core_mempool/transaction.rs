@@ -73,3 +73,72 @@ pub struct SequenceInfo {
     pub transaction_sequence_number: u64,
     pub account_sequence_number_type: AccountSequenceInfo,
 }
+
+/// State of a pending transaction in Mempool.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MempoolTransactionState {
+    /// The transaction can be included in the next block.
+    Ready,
+    /// The transaction can't be included in the next block and waits in the parking lot, e.g.
+    /// because of a gap between its sequence number and the account's sequence number.
+    Parked,
+}
+
+/// Transaction pending in Mempool, along with its current state.
+#[derive(Clone, Debug)]
+pub struct PendingTransaction {
+    pub txn: SignedTransaction,
+    pub state: MempoolTransactionState,
+    pub ranking_score: u64,
+    // System expiration time of the transaction, since the Unix epoch.
+    pub expiration_time: Duration,
+}
+
+/// Transactions of an account pending in Mempool.
+#[derive(Clone, Debug)]
+pub struct AccountPendingTransactions {
+    /// Sequence number of the account in the latest committed state.
+    pub sequence_number: u64,
+    /// Pending transactions, in increasing sequence number order.
+    pub transactions: Vec<PendingTransaction>,
+}
+
+impl AccountPendingTransactions {
+    /// Returns the first sequence number missing for the parked transactions to become ready, if
+    /// any transaction is parked.
+    pub fn missing_sequence_number(&self) -> Option<u64> {
+        if !self
+            .transactions
+            .iter()
+            .any(|t| t.state == MempoolTransactionState::Parked)
+        {
+            return None;
+        }
+        let mut expected = self.sequence_number;
+        for txn in &self.transactions {
+            let sequence_number = txn.txn.sequence_number();
+            if sequence_number > expected {
+                break;
+            }
+            if sequence_number == expected {
+                expected += 1;
+            }
+        }
+        Some(expected)
+    }
+}
+
+/// Overall statistics of Mempool.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MempoolStats {
+    /// Number of transactions in Mempool.
+    pub size: usize,
+    /// Number of transactions which can be included in the next block.
+    pub ready: usize,
+    /// Number of transactions in the parking lot.
+    pub parked: usize,
+    /// Number of accounts with transactions in Mempool.
+    pub accounts: usize,
+    pub capacity: usize,
+    pub capacity_per_user: usize,
+}
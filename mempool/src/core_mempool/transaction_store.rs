@@ -5,9 +5,12 @@ use crate::{
     core_mempool::{
         index::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            TimelineIndex, TxnPointer,
+        },
+        transaction::{
+            MempoolStats, MempoolTransaction, MempoolTransactionState, PendingTransaction,
+            TimelineState,
         },
-        transaction::{MempoolTransaction, TimelineState},
         ttl_cache::TtlCache,
     },
     counters,
@@ -55,6 +58,7 @@ pub struct TransactionStore {
     // configuration
     capacity: usize,
     capacity_per_user: usize,
+    replace_by_fee_min_gas_price_bump_pct: u64,
 }
 
 impl TransactionStore {
@@ -76,6 +80,7 @@ impl TransactionStore {
             // configuration
             capacity: config.capacity,
             capacity_per_user: config.capacity_per_user,
+            replace_by_fee_min_gas_price_bump_pct: config.replace_by_fee_min_gas_price_bump_pct,
         }
     }
 
@@ -102,6 +107,49 @@ impl TransactionStore {
         }
     }
 
+    /// Fetch pending transaction, with its current state, by hash.
+    pub(crate) fn get_pending_transaction_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Option<PendingTransaction> {
+        let (address, sequence_number) = self.hash_index.get(&hash)?;
+        self.transactions
+            .get(address)
+            .and_then(|txns| txns.get(sequence_number))
+            .map(|txn| self.pending_transaction(txn))
+    }
+
+    /// Fetch pending transactions of account, in increasing sequence number order.
+    pub(crate) fn get_account_transactions(
+        &self,
+        address: &AccountAddress,
+    ) -> Vec<PendingTransaction> {
+        self.transactions
+            .get(address)
+            .map_or_else(Vec::new, |txns| {
+                txns.values()
+                    .map(|txn| self.pending_transaction(txn))
+                    .collect()
+            })
+    }
+
+    fn pending_transaction(&self, txn: &MempoolTransaction) -> PendingTransaction {
+        let state = if self
+            .parking_lot_index
+            .contains(&txn.get_sender(), &txn.txn.sequence_number())
+        {
+            MempoolTransactionState::Parked
+        } else {
+            MempoolTransactionState::Ready
+        };
+        PendingTransaction {
+            txn: txn.txn.clone(),
+            state,
+            ranking_score: txn.ranking_score,
+            expiration_time: txn.expiration_time,
+        }
+    }
+
     /// Fetch mempool transaction by account address + sequence_number.
     pub(crate) fn get_mempool_txn(
         &self,
@@ -118,43 +166,39 @@ impl TransactionStore {
     pub(crate) fn insert(&mut self, txn: MempoolTransaction) -> MempoolStatus {
         let address = txn.get_sender();
         let sequence_number = txn.sequence_info;
+        let mut status_code = MempoolStatusCode::Accepted;
 
         // check if transaction is already present in Mempool
         // e.g. given request is update
-        // we allow increase in gas price to speed up process.
+        // we allow replacement by a transaction with a high enough gas price to speed up process.
         // ignores the case transaction hash is same for retrying submit transaction.
-        if let Some(txns) = self.transactions.get_mut(&address) {
-            if let Some(current_version) =
-                txns.get_mut(&sequence_number.transaction_sequence_number)
+        if let Some(current_version) = self
+            .transactions
+            .get(&address)
+            .and_then(|txns| txns.get(&sequence_number.transaction_sequence_number))
+        {
+            if current_version.txn == txn.txn {
+                return MempoolStatus::new(MempoolStatusCode::Accepted);
+            }
+            if let Err(status) = self.check_replacement(current_version, &txn) {
+                return status;
+            }
+            if let Some(current_version) = self
+                .transactions
+                .get_mut(&address)
+                .and_then(|txns| txns.remove(&sequence_number.transaction_sequence_number))
             {
-                if current_version.txn == txn.txn {
-                    return MempoolStatus::new(MempoolStatusCode::Accepted);
-                }
-                if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
-                    && current_version.txn.payload() == txn.txn.payload()
-                    && current_version.txn.expiration_timestamp_secs()
-                        == txn.txn.expiration_timestamp_secs()
-                    && current_version.get_gas_price() < txn.get_gas_price()
-                {
-                    if let Some(txn) = txns.remove(&txn.sequence_info.transaction_sequence_number) {
-                        self.index_remove(&txn);
-                    }
-                } else {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate)
-                        .with_message("Transaction already in mempool".to_string());
-                }
+                debug!(
+                    LogSchema::new(LogEntry::ReplacedTxn).txns(TxnsLog::new_txn(
+                        address,
+                        sequence_number.transaction_sequence_number
+                    )),
+                    old_gas_price = current_version.get_gas_price(),
+                    new_gas_price = txn.get_gas_price(),
+                );
+                self.index_remove(&current_version);
             }
-        }
-
-        if self.check_is_full_after_eviction(
-            &txn,
-            sequence_number.account_sequence_number_type.min_seq(),
-        ) {
-            return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
-                "mempool size: {}, capacity: {}",
-                self.system_ttl_index.size(),
-                self.capacity,
-            ));
+            status_code = MempoolStatusCode::Replaced;
         }
 
         self.transactions
@@ -166,18 +210,40 @@ impl TransactionStore {
             sequence_number.account_sequence_number_type.min_seq(),
         );
 
-        if let Some(txns) = self.transactions.get_mut(&address) {
+        // a replacement takes the place of the replaced transaction, so doesn't need extra space
+        if status_code != MempoolStatusCode::Replaced {
             // capacity check
-            if txns.len() >= self.capacity_per_user {
+            let txns_len = self.transactions.get(&address).map_or(0, |txns| txns.len());
+            if txns_len >= self.capacity_per_user {
                 return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
                     format!(
                         "txns length: {} capacity per user: {}",
-                        txns.len(),
-                        self.capacity_per_user,
+                        txns_len, self.capacity_per_user,
                     ),
                 );
             }
 
+            if self.check_is_full_after_eviction(
+                &txn,
+                sequence_number.account_sequence_number_type.min_seq(),
+            ) {
+                if !self.evict_lower_priority_txn(
+                    &txn,
+                    sequence_number.account_sequence_number_type.min_seq(),
+                ) {
+                    return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(
+                        format!(
+                            "mempool size: {}, capacity: {}",
+                            self.system_ttl_index.size(),
+                            self.capacity,
+                        ),
+                    );
+                }
+                status_code = MempoolStatusCode::AcceptedWithEviction;
+            }
+        }
+
+        if let Some(txns) = self.transactions.get_mut(&address) {
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
@@ -192,7 +258,39 @@ impl TransactionStore {
             self.track_indices();
         }
         self.process_ready_transactions(&address, sequence_number.account_sequence_number_type);
-        MempoolStatus::new(MempoolStatusCode::Accepted)
+        MempoolStatus::new(status_code)
+    }
+
+    /// Checks if `txn` can replace the pending transaction `current` with the same sender and
+    /// sequence number: its gas price must be higher by at least
+    /// `replace_by_fee_min_gas_price_bump_pct` percent.
+    fn check_replacement(
+        &self,
+        current: &MempoolTransaction,
+        txn: &MempoolTransaction,
+    ) -> Result<(), MempoolStatus> {
+        let current_gas_price = current.get_gas_price();
+        let gas_price = txn.get_gas_price();
+        if gas_price <= current_gas_price {
+            return Err(MempoolStatus::new(MempoolStatusCode::InvalidUpdate)
+                .with_message("Transaction already in mempool".to_string()));
+        }
+        // computed in u128 so it can't overflow, rounded up
+        let min_gas_price = (current_gas_price as u128
+            * (100 + self.replace_by_fee_min_gas_price_bump_pct as u128)
+            + 99)
+            / 100;
+        if (gas_price as u128) < min_gas_price {
+            return Err(
+                MempoolStatus::new(MempoolStatusCode::ReplacementGasPriceTooLow).with_message(
+                    format!(
+                        "gas unit price: {}, minimum gas unit price for replacement: {}",
+                        gas_price, min_gas_price,
+                    ),
+                ),
+            );
+        }
+        Ok(())
     }
 
     fn track_indices(&self) {
@@ -253,6 +351,53 @@ impl TransactionStore {
         self.system_ttl_index.size() >= self.capacity
     }
 
+    /// Tries to free some space in a full Mempool by evicting the ready transaction with the lowest
+    /// priority, if `txn` has a higher priority (i.e. pays a higher fee).
+    /// Transactions of the same sender are never evicted for `txn`, as they may be needed for `txn`
+    /// to be ready.
+    /// Like for ParkingLot eviction, we only evict on attempt to insert a transaction that would be
+    /// ready for broadcast upon insertion.
+    /// Returns true if a transaction was evicted.
+    fn evict_lower_priority_txn(
+        &mut self,
+        txn: &MempoolTransaction,
+        curr_sequence_number: u64,
+    ) -> bool {
+        if !self.check_txn_ready(txn, curr_sequence_number) {
+            return false;
+        }
+        let (address, sequence_number) = match self
+            .priority_index
+            .lowest_excluding(&txn.get_sender())
+            .filter(|key| {
+                (key.governance_role.priority(), key.gas_ranking_score)
+                    < (txn.governance_role.priority(), txn.ranking_score)
+            }) {
+            Some(key) => TxnPointer::from(key),
+            None => return false,
+        };
+
+        if let Some(txns) = self.transactions.get_mut(&address) {
+            // mark all following txns as non-ready, i.e. park them
+            for (_, t) in txns.range((Bound::Excluded(sequence_number), Bound::Unbounded)) {
+                self.parking_lot_index.insert(t);
+                self.priority_index.remove(t);
+                self.timeline_index.remove(t);
+            }
+            if let Some(evicted) = txns.remove(&sequence_number) {
+                debug!(
+                    LogSchema::new(LogEntry::MempoolFullEvictedTxn)
+                        .txns(TxnsLog::new_txn(address, sequence_number)),
+                    evicted_gas_price = evicted.get_gas_price(),
+                    gas_price = txn.get_gas_price(),
+                );
+                self.index_remove(&evicted);
+                return true;
+            }
+        }
+        false
+    }
+
     /// Check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it).
     /// Two ways this can happen:
     /// 1. txn sequence number == curr_sequence_number
@@ -565,6 +710,29 @@ impl TransactionStore {
         txns_log
     }
 
+    /// Iterates over all transactions, in increasing sequence number order for each account.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &MempoolTransaction> {
+        self.transactions.values().flat_map(|txns| txns.values())
+    }
+
+    /// Number of transactions in Mempool.
+    pub(crate) fn size(&self) -> usize {
+        self.system_ttl_index.size()
+    }
+
+    pub(crate) fn get_stats(&self) -> MempoolStats {
+        let size = self.size();
+        let parked = self.parking_lot_index.size();
+        MempoolStats {
+            size,
+            ready: size.saturating_sub(parked),
+            parked,
+            accounts: self.transactions.len(),
+            capacity: self.capacity,
+            capacity_per_user: self.capacity_per_user,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
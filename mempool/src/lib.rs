@@ -57,6 +57,9 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
+pub use core_mempool::{
+    AccountPendingTransactions, MempoolStats, MempoolTransactionState, PendingTransaction,
+};
 pub use shared_mempool::{
     bootstrap, network,
     types::{
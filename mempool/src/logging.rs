@@ -130,6 +130,8 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetAccountTransactions,
+    GetStats,
     GetBlock,
     Consensus,
     StateSyncCommit,
@@ -140,6 +142,7 @@ pub enum LogEntry {
     AddTxn,
     RemoveTxn,
     MempoolFullEvictedTxn,
+    ReplacedTxn,
     GCRemoveTxns,
     CleanCommittedTxn,
     CleanRejectedTxn,
@@ -147,6 +150,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    MempoolJournal,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -147,6 +147,59 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::GetPendingTransactionByHash(hash, callback) => {
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_pending_transaction(
+                    smp.clone(),
+                    hash,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetAccountTransactions(address, callback) => {
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_account_transactions(
+                    smp.clone(),
+                    address,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetStats(callback) => {
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_STATS_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_STATS_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_stats(
+                    smp.clone(),
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
     }
 }
 
@@ -335,6 +388,16 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
             info!(LogSchema::event_log(LogEntry::GCRuntime, LogEvent::Live))
         );
         mempool.lock().gc();
+
+        // Compact the journal without holding the lock while its new content is written.
+        let compaction = mempool.lock().start_journal_compaction_if_needed();
+        if let Some(compaction) = compaction {
+            let compacted = tokio::task::spawn_blocking(move || compaction.write())
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|compacted| compacted);
+            mempool.lock().finish_journal_compaction(compacted);
+        }
     }
 
     error!(LogSchema::event_log(
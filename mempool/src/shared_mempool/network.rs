@@ -93,7 +93,10 @@ pub struct MempoolNetworkSender {
 
 pub fn network_endpoint_config(max_broadcasts_per_peer: usize) -> AppConfig {
     AppConfig::p2p(
-        [ProtocolId::MempoolDirectSend],
+        [
+            ProtocolId::MempoolDirectSend,
+            ProtocolId::MempoolDirectSendCompressed,
+        ],
         diem_channel::Config::new(max_broadcasts_per_peer)
             .queue_style(QueueStyle::KLAST)
             .counters(&counters::PENDING_MEMPOOL_NETWORK_EVENTS),
@@ -111,15 +114,26 @@ impl NewNetworkSender for MempoolNetworkSender {
     }
 }
 
-#[async_trait]
-impl ApplicationNetworkSender<MempoolSyncMsg> for MempoolNetworkSender {
-    fn send_to(&self, recipient: PeerId, message: MempoolSyncMsg) -> Result<(), NetworkError> {
+impl MempoolNetworkSender {
+    /// Sends over either `MempoolDirectSend`, or its compressed variant.
+    pub fn send_to_with_protocol(
+        &self,
+        recipient: PeerId,
+        protocol: ProtocolId,
+        message: MempoolSyncMsg,
+    ) -> Result<(), NetworkError> {
         fail_point!("mempool::send_to", |_| {
             Err(anyhow::anyhow!("Injected error in mempool::send_to").into())
         });
-        let protocol = ProtocolId::MempoolDirectSend;
         self.inner.send_to(recipient, protocol, message)
     }
+}
+
+#[async_trait]
+impl ApplicationNetworkSender<MempoolSyncMsg> for MempoolNetworkSender {
+    fn send_to(&self, recipient: PeerId, message: MempoolSyncMsg) -> Result<(), NetworkError> {
+        self.send_to_with_protocol(recipient, ProtocolId::MempoolDirectSend, message)
+    }
 
     async fn send_rpc(
         &self,
@@ -461,7 +475,21 @@ impl MempoolNetworkInterface {
             transactions,
         };
 
-        if let Err(e) = self.sender.send_to(peer, request) {
+        // Batches can be large, so compress them if the peer supports it
+        let supports_compression = self.peer_metadata_storage.read(peer).map_or(false, |info| {
+            info.supports_protocol(ProtocolId::MempoolDirectSendCompressed)
+        });
+        let protocol = if supports_compression {
+            ProtocolId::MempoolDirectSendCompressed
+        } else {
+            ProtocolId::MempoolDirectSend
+        };
+
+        if let Err(e) = self
+            .sender
+            .sender(&peer.network_id())
+            .send_to_with_protocol(peer.peer_id(), protocol, request)
+        {
             counters::network_send_fail_inc(counters::BROADCAST_TXNS);
             return Err(BroadcastError::NetworkError(peer, e.into()));
         }
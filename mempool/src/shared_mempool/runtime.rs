@@ -6,6 +6,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        tasks::restore_journaled_transactions,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     ConsensusRequest,
@@ -23,7 +24,8 @@ use tokio::runtime::{Builder, Handle, Runtime};
 use vm_validator::vm_validator::{TransactionValidation, VMValidator};
 
 /// Bootstrap of SharedMempool.
-/// Creates a separate Tokio Runtime that runs the following routines:
+/// Restores the journaled transactions if the journal is enabled, then
+/// creates a separate Tokio Runtime that runs the following routines:
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
@@ -62,6 +64,7 @@ pub(crate) fn start_shared_mempool<V>(
         config.base.role,
         peer_metadata_storage,
     );
+    restore_journaled_transactions(&smp);
 
     executor.spawn(coordinator(
         smp,
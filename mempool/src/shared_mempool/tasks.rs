@@ -3,7 +3,10 @@
 
 //! Tasks that are executed by coordinators (short-lived compared to coordinators)
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    core_mempool::{
+        AccountPendingTransactions, CoreMempool, MempoolStats, PendingTransaction, TimelineState,
+        TxnPointer,
+    },
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, MempoolSyncMsg},
@@ -20,13 +23,14 @@ use diem_infallible::{Mutex, RwLock};
 use diem_logger::prelude::*;
 use diem_metrics::HistogramTimer;
 use diem_types::{
+    account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     on_chain_config::OnChainConfigPayload,
     transaction::SignedTransaction,
     vm_status::DiscardedVMStatus,
 };
 use futures::{channel::oneshot, stream::FuturesUnordered};
-use network::application::interface::NetworkInterface;
+use network::application::{interface::NetworkInterface, reputation::Misbehavior};
 use rayon::prelude::*;
 use std::{
     cmp,
@@ -141,6 +145,76 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes get pending transaction by hash request by client.
+pub(crate) async fn process_client_get_pending_transaction<V>(
+    smp: SharedMempool<V>,
+    hash: HashValue,
+    callback: oneshot::Sender<Option<PendingTransaction>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let _timer = counters::process_get_txn_latency_timer_client();
+    let txn = smp.mempool.lock().get_pending_transaction_by_hash(hash);
+
+    if callback.send(txn).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetTransaction,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get pending transactions of account request by client.
+/// The account sequence number is read from storage, so that clients can tell which transactions
+/// are waiting for a missing sequence number.
+pub(crate) async fn process_client_get_account_transactions<V>(
+    smp: SharedMempool<V>,
+    address: AccountAddress,
+    callback: oneshot::Sender<Result<AccountPendingTransactions>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let result = get_account_sequence_number(smp.db.as_ref(), address).map(|seq_info| {
+        AccountPendingTransactions {
+            sequence_number: seq_info.min_seq(),
+            transactions: smp.mempool.lock().get_account_transactions(&address),
+        }
+    });
+
+    if callback.send(result).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetAccountTransactions,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get mempool stats request by client.
+pub(crate) async fn process_client_get_stats<V>(
+    smp: SharedMempool<V>,
+    callback: oneshot::Sender<MempoolStats>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let stats = smp.mempool.lock().get_stats();
+
+    if callback.send(stats).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetStats,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -157,6 +231,18 @@ pub(crate) async fn process_transaction_broadcast<V>(
     let results = process_incoming_transactions(&smp, transactions, timeline_state);
     log_txn_process_results(&results, Some(peer));
 
+    // Honest peers validate transactions before broadcasting them, so an invalid signature is
+    // reported as misbehavior of the peer
+    if results
+        .iter()
+        .any(|(_, (_, vm_status))| *vm_status == Some(DiscardedVMStatus::INVALID_SIGNATURE))
+    {
+        smp.network_interface
+            .peer_metadata_storage()
+            .reputation()
+            .report(peer, Misbehavior::InvalidProof);
+    }
+
     let ack_response = gen_ack_response(request_id, results, &peer);
     let network_sender = smp.network_interface.sender();
     if let Err(e) = network_sender.send_to(peer, ack_response) {
@@ -326,6 +412,30 @@ where
     statuses
 }
 
+/// Re-inserts the transactions journaled before a restart. They are validated again, like newly
+/// submitted transactions, as they may have been committed or invalidated in the meantime.
+pub(crate) fn restore_journaled_transactions<V>(smp: &SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let transactions = smp.mempool.lock().take_journaled_transactions();
+    if !transactions.is_empty() {
+        let num_journaled = transactions.len();
+        let statuses = process_incoming_transactions(smp, transactions, TimelineState::NotReady);
+        let num_restored = statuses
+            .iter()
+            .filter(|(_, (mempool_status, _))| mempool_status.code.is_accepted())
+            .count();
+        info!(
+            LogSchema::new(LogEntry::MempoolJournal),
+            num_journaled = num_journaled,
+            num_restored = num_restored,
+            "restored journaled transactions"
+        );
+    }
+    smp.mempool.lock().finish_journal_restore();
+}
+
 fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<PeerNetworkId>) {
     let network = match sender {
         Some(peer) => peer.network_id().to_string(),
@@ -345,15 +455,13 @@ fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<Pe
             );
             continue;
         }
-        match mempool_status.code {
-            MempoolStatusCode::Accepted => counters::shared_mempool_transactions_processed_inc(
-                counters::SUCCESS_LABEL,
-                &network,
-            ),
-            _ => counters::shared_mempool_transactions_processed_inc(
+        if mempool_status.code.is_accepted() {
+            counters::shared_mempool_transactions_processed_inc(counters::SUCCESS_LABEL, &network)
+        } else {
+            counters::shared_mempool_transactions_processed_inc(
                 &mempool_status.code.to_string(),
                 &network,
-            ),
+            )
         }
     }
 }
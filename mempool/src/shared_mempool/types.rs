@@ -3,7 +3,8 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool, network::MempoolNetworkInterface,
+    core_mempool::{AccountPendingTransactions, CoreMempool, MempoolStats, PendingTransaction},
+    network::MempoolNetworkInterface,
     shared_mempool::network::MempoolNetworkSender,
 };
 use anyhow::Result;
@@ -216,6 +217,12 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    GetPendingTransactionByHash(HashValue, oneshot::Sender<Option<PendingTransaction>>),
+    GetAccountTransactions(
+        AccountAddress,
+        oneshot::Sender<Result<AccountPendingTransactions>>,
+    ),
+    GetStats(oneshot::Sender<MempoolStats>),
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
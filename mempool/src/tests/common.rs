@@ -9,7 +9,6 @@ use diem_types::{
     account_address::AccountAddress,
     account_config::{AccountSequenceInfo, XUS_NAME},
     chain_id::ChainId,
-    mempool_status::MempoolStatusCode,
     transaction::{GovernanceRole, RawTransaction, Script, SignedTransaction},
 };
 use once_cell::sync::Lazy;
@@ -145,7 +144,7 @@ pub(crate) fn add_signed_txn(pool: &mut CoreMempool, transaction: SignedTransact
         )
         .code
     {
-        MempoolStatusCode::Accepted => Ok(()),
+        code if code.is_accepted() => Ok(()),
         _ => Err(format_err!("insertion failure")),
     }
 }
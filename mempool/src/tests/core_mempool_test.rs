@@ -2,23 +2,42 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TtlCache},
+    core_mempool::{
+        AccountPendingTransactions, CoreMempool, MempoolTransactionState, TimelineState, TtlCache,
+    },
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
-        TestTransaction,
+        ConsensusMock, TestTransaction,
     },
 };
 use diem_config::config::NodeConfig;
 use diem_crypto::HashValue;
+use diem_temppath::TempPath;
 use diem_types::{
     account_config::AccountSequenceInfo,
+    mempool_status::MempoolStatusCode,
     transaction::{GovernanceRole, SignedTransaction},
 };
 use std::{
     collections::HashSet,
+    fs::OpenOptions,
+    io::Write,
     time::{Duration, SystemTime},
 };
 
+fn add_txn_status(pool: &mut CoreMempool, transaction: TestTransaction) -> MempoolStatusCode {
+    let txn = transaction.make_signed_transaction();
+    pool.add_txn(
+        txn.clone(),
+        0,
+        txn.gas_unit_price(),
+        transaction.account_seqno_type,
+        TimelineState::NotReady,
+        transaction.governance_role,
+    )
+    .code
+}
+
 #[test]
 fn test_transaction_ordering_only_seqnos() {
     let (mut mempool, mut consensus) = setup_mempool();
@@ -323,7 +342,7 @@ fn test_fail_for_same_gas_amount_and_not_same_expiration_time_crsn() {
 }
 
 #[test]
-fn test_update_invalid_transaction_in_mempool() {
+fn test_replace_transaction_with_updated_max_gas_amount() {
     let (mut mempool, mut consensus) = setup_mempool();
     let txns = add_txns_to_mempool(
         &mut mempool,
@@ -333,18 +352,16 @@ fn test_update_invalid_transaction_in_mempool() {
         &TestTransaction::new(0, 0, 5),
         200,
     );
-    let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
+    add_signed_txn(&mut mempool, updated_txn.clone()).unwrap();
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // Any transaction with a high enough gas price replaces the pending one, so the updated
+    // transaction with gas price 5 should come first.
+    assert_eq!(consensus.get_block(&mut mempool, 1), vec![updated_txn]);
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
-    let next_tnx = consensus.get_block(&mut mempool, 1);
-    assert_eq!(next_tnx, vec![txns[0].clone()]);
-    assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
 #[test]
-fn test_update_invalid_transaction_in_mempool_crsn() {
+fn test_replace_transaction_with_updated_max_gas_amount_crsn() {
     let (mut mempool, mut consensus) = setup_mempool();
     let txns = add_txns_to_mempool(
         &mut mempool,
@@ -357,14 +374,52 @@ fn test_update_invalid_transaction_in_mempool_crsn() {
         &TestTransaction::new(0, 0, 5).crsn(0),
         200,
     );
-    let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
+    add_signed_txn(&mut mempool, updated_txn.clone()).unwrap();
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // Any transaction with a high enough gas price replaces the pending one, so the updated
+    // transaction with gas price 5 should come first.
+    assert_eq!(consensus.get_block(&mut mempool, 1), vec![updated_txn]);
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
-    let next_tnx = consensus.get_block(&mut mempool, 1);
-    assert_eq!(next_tnx, vec![txns[0].clone()]);
-    assert_eq!(next_tnx[0].gas_unit_price(), 1);
+}
+
+#[test]
+fn test_replacement_gas_price_too_low() {
+    let mut config = NodeConfig::random();
+    config.mempool.replace_by_fee_min_gas_price_bump_pct = 10;
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 0, 100)),
+        MempoolStatusCode::Accepted
+    );
+
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 0, 90)),
+        MempoolStatusCode::InvalidUpdate
+    );
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 0, 109)),
+        MempoolStatusCode::ReplacementGasPriceTooLow
+    );
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 0, 110)),
+        MempoolStatusCode::Replaced
+    );
+    let txn = TestTransaction::new(0, 0, 110).make_signed_transaction();
+    assert_eq!(pool.get_by_hash(txn.clone().committed_hash()), Some(txn));
+}
+
+#[test]
+fn test_replacement_when_mempool_is_full() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 1;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+
+    // The replacement takes the place of the pending transaction.
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 0, 5)),
+        MempoolStatusCode::Replaced
+    );
 }
 
 #[test]
@@ -603,6 +658,90 @@ fn test_parking_lot_evict_only_for_ready_txn_insertion() {
     }
 }
 
+#[test]
+fn test_lowest_fee_eviction() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 2;
+    let (mut pool, mut consensus) = (CoreMempool::new(&config), ConsensusMock::new());
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 3)],
+    );
+
+    // Mempool is full, a transaction paying more than the lowest fee evicts it.
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(2, 0, 2)),
+        MempoolStatusCode::AcceptedWithEviction
+    );
+    assert!(pool.get_by_hash(txns[0].clone().committed_hash()).is_none());
+
+    // A transaction paying no more than the lowest fee is rejected.
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(3, 0, 2)),
+        MempoolStatusCode::MempoolIsFull
+    );
+
+    assert_eq!(consensus.get_block(&mut pool, 1), vec![txns[1].clone()]);
+    assert_eq!(
+        consensus.get_block(&mut pool, 1),
+        vec![TestTransaction::new(2, 0, 2).make_signed_transaction()]
+    );
+}
+
+#[test]
+fn test_lowest_fee_eviction_parks_following_transactions() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 5),
+            TestTransaction::new(1, 0, 2),
+        ],
+    );
+
+    // The transaction with sequence number 1 can't be included in a block without its
+    // predecessor, so it's parked once the predecessor is evicted.
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(2, 0, 3)),
+        MempoolStatusCode::AcceptedWithEviction
+    );
+    let mut block: Vec<_> = pool
+        .get_block(3, HashSet::new())
+        .iter()
+        .map(|t| t.gas_unit_price())
+        .collect();
+    block.sort_unstable();
+    assert_eq!(block, vec![2, 3]);
+    assert_eq!(pool.get_parking_lot_size(), 1);
+
+    // The parked transaction is evicted first when more room is needed.
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(3, 0, 1)),
+        MempoolStatusCode::Accepted
+    );
+    assert!(pool.get_by_hash(txns[1].clone().committed_hash()).is_none());
+}
+
+#[test]
+fn test_lowest_fee_eviction_skips_same_sender() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 2;
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 7)],
+    );
+
+    // Evicting the sender's own lower fee transaction would make the new one not ready.
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 1, 5)),
+        MempoolStatusCode::MempoolIsFull
+    );
+}
+
 #[test]
 fn test_gc_ready_transaction() {
     let mut pool = setup_mempool().0;
@@ -740,3 +879,275 @@ fn test_get_transaction_by_hash_after_the_txn_is_updated() {
     let txn_by_new_hash = pool.get_by_hash(new_txn_hash);
     assert_eq!(txn_by_new_hash, Some(new_txn));
 }
+
+#[test]
+fn test_get_pending_transaction_by_hash() {
+    let mut pool = setup_mempool().0;
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(0, 2, 1)],
+    );
+
+    let ready = pool
+        .get_pending_transaction_by_hash(txns[0].clone().committed_hash())
+        .unwrap();
+    assert_eq!(ready.txn, txns[0]);
+    assert_eq!(ready.state, MempoolTransactionState::Ready);
+    let parked = pool
+        .get_pending_transaction_by_hash(txns[1].clone().committed_hash())
+        .unwrap();
+    assert_eq!(parked.txn, txns[1]);
+    assert_eq!(parked.state, MempoolTransactionState::Parked);
+
+    assert!(pool
+        .get_pending_transaction_by_hash(HashValue::random())
+        .is_none());
+}
+
+#[test]
+fn test_get_account_transactions() {
+    let mut pool = setup_mempool().0;
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 3, 1),
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 1),
+        ],
+    );
+
+    let txns = pool.get_account_transactions(&TestTransaction::get_address(0));
+    let states: Vec<_> = txns
+        .iter()
+        .map(|t| (t.txn.sequence_number(), t.state))
+        .collect();
+    assert_eq!(
+        states,
+        vec![
+            (0, MempoolTransactionState::Ready),
+            (1, MempoolTransactionState::Ready),
+            (3, MempoolTransactionState::Parked),
+        ]
+    );
+    let account_txns = AccountPendingTransactions {
+        sequence_number: 0,
+        transactions: txns,
+    };
+    assert_eq!(account_txns.missing_sequence_number(), Some(2));
+
+    // Filling the gap makes all transactions ready.
+    add_txn(&mut pool, TestTransaction::new(0, 2, 1)).unwrap();
+    let account_txns = AccountPendingTransactions {
+        sequence_number: 0,
+        transactions: pool.get_account_transactions(&TestTransaction::get_address(0)),
+    };
+    assert!(account_txns
+        .transactions
+        .iter()
+        .all(|t| t.state == MempoolTransactionState::Ready));
+    assert_eq!(account_txns.missing_sequence_number(), None);
+
+    assert!(pool
+        .get_account_transactions(&TestTransaction::get_address(2))
+        .is_empty());
+}
+
+#[test]
+fn test_missing_sequence_number_behind_account() {
+    let mut pool = setup_mempool().0;
+    add_txn(&mut pool, TestTransaction::new(0, 5, 1)).unwrap();
+
+    // The account's next transaction, sequence number 3, is missing.
+    let account_txns = AccountPendingTransactions {
+        sequence_number: 3,
+        transactions: pool.get_account_transactions(&TestTransaction::get_address(0)),
+    };
+    assert_eq!(account_txns.missing_sequence_number(), Some(3));
+}
+
+#[test]
+fn test_get_stats() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 10;
+    config.mempool.capacity_per_user = 5;
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(0, 4, 1),
+            TestTransaction::new(1, 2, 1),
+        ],
+    );
+
+    let stats = pool.get_stats();
+    assert_eq!(stats.size, 4);
+    assert_eq!(stats.ready, 2);
+    assert_eq!(stats.parked, 2);
+    assert_eq!(stats.accounts, 2);
+    assert_eq!(stats.capacity, 10);
+    assert_eq!(stats.capacity_per_user, 5);
+}
+
+fn journal_config(path: &TempPath) -> NodeConfig {
+    let mut config = NodeConfig::random();
+    config.mempool.enable_journal = true;
+    config.mempool.journal_path = path.path().to_path_buf();
+    config
+}
+
+#[test]
+fn test_journal_restore() {
+    let path = TempPath::new();
+    let config = journal_config(&path);
+
+    let mut pool = CoreMempool::new(&config);
+    let mut txns = add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 1)],
+    );
+    // Rejected transactions are not journaled.
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 0, 0)).is_err());
+    // Expired transactions are not restored.
+    let expired_txn = TestTransaction::new(2, 0, 1).make_signed_transaction_with_expiration_time(0);
+    add_signed_txn(&mut pool, expired_txn).unwrap();
+    let replacement = TestTransaction::new(1, 0, 5).make_signed_transaction();
+    add_signed_txn(&mut pool, replacement.clone()).unwrap();
+    txns.push(replacement);
+    drop(pool);
+
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(pool.take_journaled_transactions(), txns);
+    assert!(pool.take_journaled_transactions().is_empty());
+}
+
+#[test]
+fn test_journal_compaction() {
+    let path = TempPath::new();
+    let config = journal_config(&path);
+
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 1)],
+    );
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    pool.compact_journal();
+    drop(pool);
+
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(pool.take_journaled_transactions(), vec![txns[1].clone()]);
+}
+
+#[test]
+fn test_journal_discards_incomplete_entry() {
+    let path = TempPath::new();
+    let config = journal_config(&path);
+
+    let mut pool = CoreMempool::new(&config);
+    let mut txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 1)]);
+    drop(pool);
+
+    // Simulate a crash in the middle of writing an entry.
+    let mut file = OpenOptions::new().append(true).open(path.path()).unwrap();
+    file.write_all(&[100, 0, 0, 0, 1, 2, 3]).unwrap();
+    drop(file);
+
+    let mut pool = CoreMempool::new(&config);
+    txns.extend(add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(1, 0, 1)],
+    ));
+    drop(pool);
+
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(pool.take_journaled_transactions(), txns);
+}
+
+#[test]
+fn test_journal_ignores_resubmission() {
+    let path = TempPath::new();
+    let config = journal_config(&path);
+
+    let mut pool = CoreMempool::new(&config);
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    add_signed_txn(&mut pool, txn.clone()).unwrap();
+    add_signed_txn(&mut pool, txn.clone()).unwrap();
+    assert_eq!(pool.journal_len(), Some(1));
+    drop(pool);
+
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(pool.take_journaled_transactions(), vec![txn]);
+}
+
+#[test]
+fn test_journal_compaction_keeps_concurrent_appends() {
+    let path = TempPath::new();
+    let config = journal_config(&path);
+
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(2, 0, 1),
+        ],
+    );
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+
+    // Transactions accepted while the compacted journal is written are kept.
+    let compaction = pool.start_journal_compaction_if_needed().unwrap();
+    assert!(pool.start_journal_compaction_if_needed().is_none());
+    let appended = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(3, 0, 1)]);
+    pool.finish_journal_compaction(compaction.write());
+    assert_eq!(pool.journal_len(), Some(2));
+    assert!(pool.start_journal_compaction_if_needed().is_none());
+    drop(pool);
+
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(
+        pool.take_journaled_transactions(),
+        vec![txns[2].clone(), appended[0].clone()]
+    );
+}
+
+#[test]
+fn test_journal_restore_keeps_system_ttl() {
+    let path = TempPath::new();
+    let mut config = journal_config(&path);
+
+    let mut pool = CoreMempool::new(&config);
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    add_signed_txn(&mut pool, txn.clone()).unwrap();
+    let expiration_time = pool
+        .get_pending_transaction_by_hash(txn.clone().committed_hash())
+        .unwrap()
+        .expiration_time;
+    drop(pool);
+
+    config.mempool.system_transaction_timeout_secs += 1_000;
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(pool.take_journaled_transactions(), vec![txn.clone()]);
+    add_signed_txn(&mut pool, txn.clone()).unwrap();
+    pool.finish_journal_restore();
+    assert_eq!(
+        pool.get_pending_transaction_by_hash(txn.committed_hash())
+            .unwrap()
+            .expiration_time,
+        Duration::from_secs(expiration_time.as_secs())
+    );
+
+    // Transactions submitted after the restore get a fresh system TTL.
+    let new_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    add_signed_txn(&mut pool, new_txn.clone()).unwrap();
+    assert!(
+        pool.get_pending_transaction_by_hash(new_txn.committed_hash())
+            .unwrap()
+            .expiration_time
+            > expiration_time + Duration::from_secs(500)
+    );
+}
@@ -21,6 +21,10 @@ use network::{
 use std::time::Duration;
 
 const ALL_PROTOCOLS: [ProtocolId; 1] = [ProtocolId::MempoolDirectSend];
+const COMPRESSED_PROTOCOLS: [ProtocolId; 2] = [
+    ProtocolId::MempoolDirectSend,
+    ProtocolId::MempoolDirectSendCompressed,
+];
 static ALL_TXNS: &[TestTransaction] = &[test_transaction(0), test_transaction(1)];
 static TXN_1: &[TestTransaction] = &[test_transaction(0)];
 static TXN_2: &[TestTransaction] = &[test_transaction(1)];
@@ -122,6 +126,33 @@ async fn single_outbound_node_test() {
     }
 }
 
+/// Tests broadcasts with peers supporting compression
+#[tokio::test]
+async fn compressed_broadcast_test() {
+    // Compressed broadcasts are accepted
+    let mut node = MempoolTestFrameworkBuilder::single_validator();
+    let (other_peer_network_id, other_metadata) =
+        validator_mock_connection(ConnectionOrigin::Inbound, &COMPRESSED_PROTOCOLS);
+    node.connect_self(other_peer_network_id.network_id(), other_metadata);
+    node.receive_message(
+        ProtocolId::MempoolDirectSendCompressed,
+        other_peer_network_id,
+        ALL_TXNS,
+    )
+    .await;
+    node.assert_only_txns_in_mempool(ALL_TXNS);
+
+    // And sent to peers supporting them
+    let mut node = MempoolTestFrameworkBuilder::single_vfn();
+    let (other_peer_network_id, other_metadata) =
+        vfn_validator_mock_connection(ConnectionOrigin::Outbound, &COMPRESSED_PROTOCOLS);
+    node.add_txns_via_client(TXN_1).await;
+    node.connect_self(other_peer_network_id.network_id(), other_metadata);
+    node.send_broadcast_and_receive_ack(other_peer_network_id, TXN_1)
+        .await;
+    node.assert_only_txns_in_mempool(TXN_1);
+}
+
 /// Tests if the node is a VFN, and it's getting forwarded messages from a PFN.  It should forward
 /// messages to the upstream VAL.  Upstream and downstream nodes are mocked.
 #[tokio::test]
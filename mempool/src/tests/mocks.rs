@@ -51,6 +51,11 @@ impl MockSharedMempool {
     /// Returns the runtime on which the shared mempool is running
     /// and the channel through which shared mempool receives client events.
     pub fn new() -> Self {
+        Self::new_with_config(NodeConfig::random())
+    }
+
+    /// Creates a mock of a running instance of shared mempool with the given config.
+    pub fn new_with_config(config: NodeConfig) -> Self {
         let runtime = Builder::new_multi_thread()
             .thread_name("mock-shared-mem")
             .enable_all()
@@ -58,6 +63,7 @@ impl MockSharedMempool {
             .expect("[mock shared mempool] failed to create runtime");
         let (ac_client, mempool, consensus_sender, mempool_notifier) = Self::start(
             runtime.handle(),
+            config,
             &DbReaderWriter::new(MockDbReaderWriter),
             MockVMValidator,
         );
@@ -79,7 +85,7 @@ impl MockSharedMempool {
     ) -> Self {
         let handle = Handle::current();
         let (ac_client, mempool, consensus_sender, mempool_notifier) =
-            Self::start(&handle, db, validator);
+            Self::start(&handle, NodeConfig::random(), db, validator);
         Self {
             _runtime: None,
             _handle: Some(handle),
@@ -92,6 +98,7 @@ impl MockSharedMempool {
 
     pub fn start<V: TransactionValidation + 'static>(
         handle: &Handle,
+        mut config: NodeConfig,
         db: &DbReaderWriter<DpnProto>,
         validator: V,
     ) -> (
@@ -100,7 +107,6 @@ impl MockSharedMempool {
         mpsc::Sender<ConsensusRequest>,
         MempoolNotifier,
     ) {
-        let mut config = NodeConfig::random();
         config.validator_network = Some(NetworkConfig::network_with_id(NetworkId::Validator));
 
         let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
@@ -7,6 +7,8 @@ use crate::{
     tests::common::{batch_add_signed_txn, TestTransaction},
     ConsensusRequest,
 };
+use diem_config::config::NodeConfig;
+use diem_temppath::TempPath;
 use diem_types::transaction::Transaction;
 use futures::{channel::oneshot, executor::block_on, sink::SinkExt};
 use mempool_notifications::MempoolNotificationSender;
@@ -93,3 +95,32 @@ fn test_mempool_notify_committed_txns() {
     assert_eq!(timeline.len(), 1);
     assert_eq!(timeline.get(0).unwrap(), &kept_txn);
 }
+
+#[test]
+fn test_restore_journaled_txns() {
+    let path = TempPath::new();
+    let mut config = NodeConfig::random();
+    config.mempool.enable_journal = true;
+    config.mempool.journal_path = path.path().to_path_buf();
+
+    let txns = vec![
+        TestTransaction::new(0, 0, 1).make_signed_transaction(),
+        TestTransaction::new(1, 0, 1).make_signed_transaction(),
+    ];
+    {
+        let smp = MockSharedMempool::new_with_config(config.clone());
+        let expired_txn =
+            TestTransaction::new(2, 0, 1).make_signed_transaction_with_expiration_time(0);
+        smp.add_txns(vec![txns[0].clone(), txns[1].clone(), expired_txn])
+            .unwrap();
+    }
+
+    // The journaled transactions are validated and inserted again on restart, except the
+    // expired one.
+    let smp = MockSharedMempool::new_with_config(config);
+    let mut restored = smp.get_txns(10);
+    restored.sort_by_key(|txn| txn.sender());
+    let mut expected = txns;
+    expected.sort_by_key(|txn| txn.sender());
+    assert_eq!(restored, expected);
+}
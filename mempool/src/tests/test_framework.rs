@@ -145,7 +145,10 @@ impl MempoolNode {
                 .await
                 .unwrap();
             let status = receiver.await.unwrap().unwrap();
-            assert_eq!(status.0.code, MempoolStatusCode::Accepted)
+            assert!(matches!(
+                status.0.code,
+                MempoolStatusCode::Accepted | MempoolStatusCode::Replaced
+            ));
         }
     }
 
@@ -241,7 +244,7 @@ impl MempoolNode {
         };
         let data = protocol_id.to_bytes(&msg).unwrap().into();
         let (notif, maybe_receiver) = match protocol_id {
-            ProtocolId::MempoolDirectSend => (
+            ProtocolId::MempoolDirectSend | ProtocolId::MempoolDirectSendCompressed => (
                 PeerManagerNotification::RecvMessage(
                     remote_peer_id,
                     Message {
@@ -346,7 +349,7 @@ impl MempoolNode {
             }
         };
         assert_eq!(peer_id, expected_peer_id);
-        let request_id = match bcs::from_bytes(&data).unwrap() {
+        let request_id = match protocol_id.from_bytes(&data).unwrap() {
             MempoolSyncMsg::BroadcastTransactionsRequest {
                 request_id,
                 transactions,
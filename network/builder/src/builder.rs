@@ -37,7 +37,7 @@ use network::{
         network::{AppConfig, NewNetworkEvents, NewNetworkSender},
     },
 };
-use network_discovery::DiscoveryChangeListener;
+use network_discovery::{peer_exchange, DiscoveryChangeListener};
 use std::{
     clone::Clone,
     collections::{HashMap, HashSet},
@@ -238,6 +238,13 @@ impl NetworkBuilder {
                 None
             };
 
+            // Peers discovered through peer exchange are trusted, so anyone could join
+            assert!(
+                !(config.mutual_authentication
+                    && matches!(discovery_method, DiscoveryMethod::PeerExchange(_))),
+                "Peer exchange discovery can't be used with mutual authentication!"
+            );
+
             network_builder.add_discovery_change_listener(
                 discovery_method,
                 pubkey,
@@ -362,6 +369,7 @@ impl NetworkBuilder {
             pm_conn_mgr_notifs_rx,
             outbound_connection_limit,
             mutual_authentication,
+            self.peer_metadata_storage.clone(),
         ));
         self
     }
@@ -394,6 +402,26 @@ impl NetworkBuilder {
                 *interval_duration,
                 self.time_service.clone(),
             ),
+            DiscoveryMethod::Dns(dns_name, interval_duration) => DiscoveryChangeListener::dns(
+                self.network_context,
+                conn_mgr_reqs_tx,
+                dns_name,
+                *interval_duration,
+                self.time_service.clone(),
+            ),
+            DiscoveryMethod::PeerExchange(interval_duration) => {
+                let (network_tx, network_rx) =
+                    self.add_p2p_service(&peer_exchange::network_endpoint_config());
+                DiscoveryChangeListener::peer_exchange(
+                    self.network_context,
+                    conn_mgr_reqs_tx,
+                    self.peer_metadata_storage.clone(),
+                    network_tx,
+                    network_rx,
+                    *interval_duration,
+                    self.time_service.clone(),
+                )
+            }
             DiscoveryMethod::None => return,
         };
 
@@ -0,0 +1,235 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::DiscoveryError;
+use diem_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
+use diem_crypto::{traits::ValidCryptoMaterialStringExt, x25519};
+use diem_logger::prelude::*;
+use diem_time_service::{Interval, TimeService, TimeServiceTrait};
+use diem_types::{
+    account_address::from_identity_public_key,
+    network_address::{DnsName, NetworkAddress, Protocol},
+};
+use futures::{future::BoxFuture, Stream};
+use std::{
+    collections::hash_map::Entry,
+    convert::TryFrom,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use trust_dns_resolver::TokioAsyncResolver;
+
+/// Prefix of the TXT record holding a node's x25519 network public key
+const NOISE_KEY_TXT_PREFIX: &str = "ln-noise-ik=";
+
+/// A SRV record target, along with the TXT records found at that target
+type DnsRecord = (String, u16, Vec<String>);
+
+/// Discovers peers from DNS.
+///
+/// Every interval, the SRV records of `dns_name` are resolved to a set of
+/// `(target, port)` pairs. Each target must publish a `ln-noise-ik=<hex>`
+/// TXT record holding its network public key, and is then dialed at
+/// `/dns/<target>/tcp/<port>/ln-noise-ik/<key>/ln-handshake/0`.
+pub struct DnsStream {
+    dns_name: String,
+    interval: Pin<Box<Interval>>,
+    pending_lookup: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl DnsStream {
+    pub(crate) fn new(
+        dns_name: &str,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        DnsStream {
+            dns_name: dns_name.to_string(),
+            interval: Box::pin(time_service.interval(interval_duration)),
+            pending_lookup: None,
+        }
+    }
+}
+
+impl Stream for DnsStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            // Finish any in flight lookup before waiting on the next interval
+            if let Some(lookup) = self.pending_lookup.as_mut() {
+                let result = futures::ready!(lookup.as_mut().poll(cx));
+                self.pending_lookup = None;
+                return Poll::Ready(Some(result));
+            }
+
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            self.pending_lookup = Some(Box::pin(lookup_peers(self.dns_name.clone())));
+        }
+    }
+}
+
+/// Resolves the SRV records of `dns_name`, and the TXT records of each SRV target
+async fn lookup_peers(dns_name: String) -> Result<PeerSet, DiscoveryError> {
+    // Build a new resolver every lookup, so changes to the system config are picked up
+    let resolver = TokioAsyncResolver::tokio_from_system_conf().map_err(resolve_error)?;
+    let srv_lookup = resolver
+        .srv_lookup(dns_name.as_str())
+        .await
+        .map_err(resolve_error)?;
+
+    let mut records = Vec::new();
+    for srv in srv_lookup.iter() {
+        let target = srv.target().to_utf8();
+        // A single unresolvable target shouldn't hide the rest of the peers
+        let txt_lookup = match resolver.txt_lookup(target.as_str()).await {
+            Ok(txt_lookup) => txt_lookup,
+            Err(error) => {
+                warn!(
+                    "Skipping DNS target {} of {}: TXT lookup failed: {}",
+                    target, dns_name, error
+                );
+                continue;
+            }
+        };
+        let txt_records = txt_lookup
+            .iter()
+            .flat_map(|txt| txt.txt_data().iter())
+            .map(|data| String::from_utf8_lossy(data).into_owned())
+            .collect();
+        records.push((target, srv.port(), txt_records));
+    }
+
+    Ok(peers_from_records(records))
+}
+
+fn resolve_error(error: trust_dns_resolver::error::ResolveError) -> DiscoveryError {
+    DiscoveryError::IO(io::Error::new(io::ErrorKind::Other, error))
+}
+
+/// Converts resolved DNS records into a `PeerSet`.  Targets sharing the same
+/// key are merged into a single peer with multiple addresses.  Malformed
+/// records are logged and skipped.
+fn peers_from_records(records: Vec<DnsRecord>) -> PeerSet {
+    let mut peers = PeerSet::new();
+    for record in records {
+        let (pubkey, addr) = match parse_record(&record) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                warn!("Skipping DNS record {:?}: {:?}", record, error);
+                continue;
+            }
+        };
+
+        match peers.entry(from_identity_public_key(pubkey)) {
+            Entry::Occupied(mut entry) => entry.get_mut().addresses.push(addr),
+            Entry::Vacant(entry) => {
+                entry.insert(Peer::from_addrs(PeerRole::Upstream, vec![addr]));
+            }
+        }
+    }
+    peers
+}
+
+/// Parses the network key and the address to dial out of a single record
+fn parse_record(
+    (target, port, txt_records): &DnsRecord,
+) -> Result<(x25519::PublicKey, NetworkAddress), DiscoveryError> {
+    let pubkey = txt_records
+        .iter()
+        .find_map(|txt| txt.strip_prefix(NOISE_KEY_TXT_PREFIX))
+        .ok_or_else(|| DiscoveryError::Parsing(format!("No network key TXT record for {}", target)))
+        .and_then(|key| {
+            x25519::PublicKey::from_encoded_string(key.trim()).map_err(|err| {
+                DiscoveryError::Parsing(format!("Invalid network key for {}: {}", target, err))
+            })
+        })?;
+
+    // SRV targets are fully qualified, so strip the root label
+    let dns_name = DnsName::try_from(target.trim_end_matches('.').to_string())
+        .map_err(|err| DiscoveryError::Parsing(err.to_string()))?;
+    let addr = NetworkAddress::from(Protocol::Dns(dns_name))
+        .push(Protocol::Tcp(*port))
+        .append_prod_protos(pubkey, HANDSHAKE_VERSION);
+    Ok((pubkey, addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const KEY: &str = "080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120";
+
+    fn record(target: &str, port: u16, txt: &[&str]) -> DnsRecord {
+        (
+            target.to_string(),
+            port,
+            txt.iter().map(|txt| txt.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_peers_from_records() {
+        let noise_txt = format!("{}{}", NOISE_KEY_TXT_PREFIX, KEY);
+        let records = vec![
+            record("fn0.example.com.", 6182, &["v=1", &noise_txt]),
+            record("fn1.example.com.", 6183, &[&noise_txt]),
+        ];
+        let peers = peers_from_records(records);
+
+        let pubkey = x25519::PublicKey::from_encoded_string(KEY).unwrap();
+        let peer = peers.get(&from_identity_public_key(pubkey)).unwrap();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peer.role, PeerRole::Upstream);
+        assert!(peer.keys.contains(&pubkey));
+        assert_eq!(
+            peer.addresses,
+            vec![
+                NetworkAddress::from_str(&format!(
+                    "/dns/fn0.example.com/tcp/6182/ln-noise-ik/{}/ln-handshake/0",
+                    KEY
+                ))
+                .unwrap(),
+                NetworkAddress::from_str(&format!(
+                    "/dns/fn1.example.com/tcp/6183/ln-noise-ik/{}/ln-handshake/0",
+                    KEY
+                ))
+                .unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_peers_from_bad_records() {
+        let noise_txt = format!("{}{}", NOISE_KEY_TXT_PREFIX, KEY);
+        let records = vec![
+            // Missing key
+            record("fn0.example.com.", 6182, &["v=1"]),
+            // Malformed key
+            record("fn1.example.com.", 6182, &["ln-noise-ik=1234"]),
+            // Invalid DNS name
+            record("fn/2.example.com.", 6182, &[&noise_txt]),
+            record("fn3.example.com.", 6182, &[&noise_txt]),
+        ];
+        let peers = peers_from_records(records);
+
+        // Bad records are skipped, without dropping the good ones
+        let pubkey = x25519::PublicKey::from_encoded_string(KEY).unwrap();
+        let peer = peers.get(&from_identity_public_key(pubkey)).unwrap();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(
+            peer.addresses,
+            vec![NetworkAddress::from_str(&format!(
+                "/dns/fn3.example.com/tcp/6182/ln-noise-ik/{}/ln-handshake/0",
+                KEY
+            ))
+            .unwrap()]
+        );
+
+        let records = vec![record("fn0.example.com.", 6182, &["v=1"])];
+        assert!(peers_from_records(records).is_empty());
+    }
+}
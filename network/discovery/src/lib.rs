@@ -1,7 +1,10 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::DISCOVERY_COUNTS, file::FileStream, validator_set::ValidatorSetStream};
+use crate::{
+    counters::DISCOVERY_COUNTS, dns::DnsStream, file::FileStream,
+    peer_exchange::PeerExchangeStream, validator_set::ValidatorSetStream,
+};
 use diem_config::{config::PeerSet, network_id::NetworkContext};
 use diem_crypto::x25519;
 use diem_logger::prelude::*;
@@ -9,6 +12,7 @@ use diem_time_service::TimeService;
 use event_notifications::ReconfigNotificationListener;
 use futures::{Stream, StreamExt};
 use network::{
+    application::storage::PeerMetadataStorage,
     connectivity_manager::{ConnectivityRequest, DiscoverySource},
     counters::inc_by_with_context,
     logging::NetworkSchema,
@@ -16,13 +20,16 @@ use network::{
 use std::{
     path::Path,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
 use tokio::runtime::Handle;
 
 mod counters;
+mod dns;
 mod file;
+pub mod peer_exchange;
 mod validator_set;
 
 #[derive(Debug)]
@@ -42,6 +49,8 @@ pub struct DiscoveryChangeListener {
 enum DiscoveryChangeStream {
     ValidatorSet(ValidatorSetStream),
     File(FileStream),
+    Dns(DnsStream),
+    PeerExchange(Box<PeerExchangeStream>),
 }
 
 impl Stream for DiscoveryChangeStream {
@@ -51,6 +60,8 @@ impl Stream for DiscoveryChangeStream {
         match self.get_mut() {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
+            Self::Dns(stream) => Pin::new(stream).poll_next(cx),
+            Self::PeerExchange(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -95,6 +106,48 @@ impl DiscoveryChangeListener {
         }
     }
 
+    pub fn dns(
+        network_context: NetworkContext,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        dns_name: &str,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream =
+            DiscoveryChangeStream::Dns(DnsStream::new(dns_name, interval_duration, time_service));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Dns,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
+    pub fn peer_exchange(
+        network_context: NetworkContext,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
+        network_tx: peer_exchange::PeerExchangeNetworkSender,
+        network_rx: peer_exchange::PeerExchangeNetworkEvents,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::PeerExchange(Box::new(PeerExchangeStream::new(
+            network_context,
+            peer_metadata_storage,
+            network_tx,
+            network_rx,
+            interval_duration,
+            time_service,
+        )));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::PeerExchange,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         executor.spawn(Box::pin(self).run());
     }
@@ -0,0 +1,550 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peer exchange discovery
+//!
+//! Every interval, each connected peer that supports `PeerExchangeRpc` is asked
+//! for the addresses of the peers it dialed out to.  Once all responses of a
+//! round are in, the union of them is sent to the `ConnectivityManager`.  In turn,
+//! we answer the same request from other peers with the addresses we dialed.
+//!
+//! Shared addresses are not trusted: an address is only accepted if its noise
+//! key derives the advertised `PeerId`.  Since discovered keys become trusted
+//! peers, this must never run on a network with mutual authentication.
+
+use crate::DiscoveryError;
+use bytes::Bytes;
+use channel::{diem_channel, message_queues::QueueStyle};
+use diem_config::{
+    config::{Peer, PeerRole, PeerSet},
+    network_id::NetworkContext,
+};
+use diem_logger::prelude::*;
+use diem_time_service::{Interval, TimeService, TimeServiceTrait};
+use diem_types::{
+    account_address::from_identity_public_key, network_address::NetworkAddress, PeerId,
+};
+use futures::{
+    channel::oneshot,
+    future::BoxFuture,
+    stream::{FuturesUnordered, StreamExt},
+    Stream,
+};
+use netcore::transport::ConnectionOrigin;
+use network::{
+    application::storage::PeerMetadataStorage,
+    constants::NETWORK_CHANNEL_SIZE,
+    counters,
+    logging::NetworkSchema,
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::{
+        AppConfig, Event, NetworkEvents, NetworkSender, NewNetworkSender, RpcError,
+    },
+    ProtocolId,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// Maximum number of addresses sent in, or accepted from, a single response
+pub const MAX_PEERS_PER_RESPONSE: usize = 64;
+
+/// Timeout for a single peer exchange request
+const PEER_EXCHANGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum PeerExchangeMsg {
+    GetPeers,
+    Peers(Vec<(PeerId, NetworkAddress)>),
+}
+
+/// Configuration for the network endpoints to support peer exchange.
+pub fn network_endpoint_config() -> AppConfig {
+    AppConfig::p2p(
+        [ProtocolId::PeerExchangeRpc],
+        diem_channel::Config::new(NETWORK_CHANNEL_SIZE)
+            .queue_style(QueueStyle::LIFO)
+            .counters(&counters::PENDING_DISCOVERY_NETWORK_EVENTS),
+    )
+}
+
+pub type PeerExchangeNetworkEvents = NetworkEvents<PeerExchangeMsg>;
+
+#[derive(Clone)]
+pub struct PeerExchangeNetworkSender {
+    inner: NetworkSender<PeerExchangeMsg>,
+}
+
+impl NewNetworkSender for PeerExchangeNetworkSender {
+    fn new(
+        peer_mgr_reqs_tx: PeerManagerRequestSender,
+        connection_reqs_tx: ConnectionRequestSender,
+    ) -> Self {
+        Self {
+            inner: NetworkSender::new(peer_mgr_reqs_tx, connection_reqs_tx),
+        }
+    }
+}
+
+impl PeerExchangeNetworkSender {
+    /// Ask `recipient` for the addresses of the peers it knows about
+    pub async fn get_peers(
+        &self,
+        recipient: PeerId,
+        timeout: Duration,
+    ) -> Result<Vec<(PeerId, NetworkAddress)>, RpcError> {
+        let response = self
+            .inner
+            .send_rpc(
+                recipient,
+                ProtocolId::PeerExchangeRpc,
+                PeerExchangeMsg::GetPeers,
+                timeout,
+            )
+            .await?;
+        match response {
+            PeerExchangeMsg::Peers(peers) => Ok(peers),
+            PeerExchangeMsg::GetPeers => Err(RpcError::InvalidRpcResponse),
+        }
+    }
+}
+
+type PeerExchangeResponse = (PeerId, Result<Vec<(PeerId, NetworkAddress)>, RpcError>);
+
+pub struct PeerExchangeStream {
+    network_context: NetworkContext,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+    network_tx: PeerExchangeNetworkSender,
+    network_rx: PeerExchangeNetworkEvents,
+    interval: Pin<Box<Interval>>,
+    pending_requests: FuturesUnordered<BoxFuture<'static, PeerExchangeResponse>>,
+    /// Whether a round of requests has been sent, and not yet reported
+    round_in_progress: bool,
+    /// The latest addresses shared by each connected peer
+    responses: HashMap<PeerId, Vec<(PeerId, NetworkAddress)>>,
+}
+
+impl PeerExchangeStream {
+    pub(crate) fn new(
+        network_context: NetworkContext,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
+        network_tx: PeerExchangeNetworkSender,
+        network_rx: PeerExchangeNetworkEvents,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        PeerExchangeStream {
+            network_context,
+            peer_metadata_storage,
+            network_tx,
+            network_rx,
+            interval: Box::pin(time_service.interval(interval_duration)),
+            pending_requests: FuturesUnordered::new(),
+            round_in_progress: false,
+            responses: HashMap::new(),
+        }
+    }
+
+    fn handle_event(&self, event: Event<PeerExchangeMsg>) {
+        match event {
+            Event::RpcRequest(peer_id, PeerExchangeMsg::GetPeers, protocol, res_tx) => {
+                self.handle_get_peers(peer_id, protocol, res_tx)
+            }
+            Event::RpcRequest(peer_id, msg, _, _) | Event::Message(peer_id, msg) => {
+                warn!(
+                    NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                    "{} Unexpected peer exchange message from {}: {:?}",
+                    self.network_context,
+                    peer_id,
+                    msg
+                );
+            }
+            // Connected peers are read from the peer metadata storage
+            Event::NewPeer(_) | Event::LostPeer(_) => (),
+        }
+    }
+
+    /// Responds with the addresses of the peers we dialed out to, as those are
+    /// the only addresses known to be dialable.
+    fn handle_get_peers(
+        &self,
+        requester: PeerId,
+        protocol: ProtocolId,
+        res_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) {
+        let peers = self
+            .peer_metadata_storage
+            .read_filtered(self.network_context.network_id(), |(peer_id, info)| {
+                **peer_id != requester
+                    && info.active_connection.origin == ConnectionOrigin::Outbound
+            })
+            .into_iter()
+            .map(|(peer_network_id, info)| (peer_network_id.peer_id(), info.active_connection.addr))
+            .take(MAX_PEERS_PER_RESPONSE)
+            .collect();
+
+        let message = match protocol.to_bytes(&PeerExchangeMsg::Peers(peers)) {
+            Ok(message) => message,
+            Err(error) => {
+                warn!(
+                    NetworkSchema::new(&self.network_context),
+                    error = ?error,
+                    "{} Unable to serialize peer exchange response: {}",
+                    self.network_context,
+                    error
+                );
+                return;
+            }
+        };
+        let _ = res_tx.send(Ok(message.into()));
+    }
+
+    /// Sends a request to every connected peer supporting peer exchange
+    fn start_round(&mut self) {
+        let peers: Vec<_> = self
+            .peer_metadata_storage
+            .read_filtered(self.network_context.network_id(), |(_, info)| {
+                info.active_connection
+                    .application_protocols
+                    .contains(ProtocolId::PeerExchangeRpc)
+            })
+            .into_iter()
+            .map(|(peer_network_id, _)| peer_network_id.peer_id())
+            .collect();
+
+        // Keep the previous results if we lost every connection, so we can redial
+        if peers.is_empty() {
+            trace!(
+                NetworkSchema::new(&self.network_context),
+                "{} No connected peers to exchange peers with",
+                self.network_context
+            );
+            return;
+        }
+
+        self.responses.retain(|peer_id, _| peers.contains(peer_id));
+        for peer_id in peers {
+            let network_tx = self.network_tx.clone();
+            self.pending_requests.push(Box::pin(async move {
+                let result = network_tx.get_peers(peer_id, PEER_EXCHANGE_TIMEOUT).await;
+                (peer_id, result)
+            }));
+        }
+        self.round_in_progress = true;
+    }
+
+    fn handle_response(&mut self, (peer_id, result): PeerExchangeResponse) {
+        match result {
+            Ok(peers) => {
+                let peers = peers
+                    .into_iter()
+                    .take(MAX_PEERS_PER_RESPONSE)
+                    .filter(|(peer_id, addr)| is_valid_shared_addr(peer_id, addr))
+                    .collect();
+                self.responses.insert(peer_id, peers);
+            }
+            Err(error) => {
+                debug!(
+                    NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                    "{} Peer exchange with {} failed: {}", self.network_context, peer_id, error
+                );
+            }
+        }
+    }
+
+    fn discovered_peers(&self) -> PeerSet {
+        let mut addrs: HashMap<PeerId, Vec<NetworkAddress>> = HashMap::new();
+        for (peer_id, addr) in self.responses.values().flatten() {
+            let peer_addrs = addrs.entry(*peer_id).or_default();
+            if !peer_addrs.contains(addr) {
+                peer_addrs.push(addr.clone());
+            }
+        }
+        addrs
+            .into_iter()
+            .map(|(peer_id, addrs)| (peer_id, Peer::from_addrs(PeerRole::Upstream, addrs)))
+            .collect()
+    }
+}
+
+/// A shared address is only accepted if it's dialable, and its noise key
+/// derives the peer id it's shared for
+fn is_valid_shared_addr(peer_id: &PeerId, addr: &NetworkAddress) -> bool {
+    addr.is_diemnet_addr()
+        && addr
+            .find_noise_proto()
+            .map_or(false, |pubkey| from_identity_public_key(pubkey) == *peer_id)
+}
+
+impl Stream for PeerExchangeStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            // Serve inbound requests, stopping when the network shuts down
+            while let Poll::Ready(maybe_event) = self.network_rx.poll_next_unpin(cx) {
+                match maybe_event {
+                    Some(event) => self.handle_event(event),
+                    None => return Poll::Ready(None),
+                }
+            }
+
+            while let Poll::Ready(Some(response)) = self.pending_requests.poll_next_unpin(cx) {
+                self.handle_response(response);
+            }
+
+            if self.round_in_progress && self.pending_requests.is_empty() {
+                self.round_in_progress = false;
+                return Poll::Ready(Some(Ok(self.discovered_peers())));
+            }
+
+            if !self.round_in_progress {
+                futures::ready!(self.interval.as_mut().poll_next(cx));
+                self.start_round();
+            } else {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diem_config::{config::HANDSHAKE_VERSION, network_id::PeerNetworkId};
+    use diem_crypto::x25519;
+    use diem_time_service::MockTimeService;
+    use futures::{executor::block_on, future};
+    use network::{
+        peer_manager::{conn_notifs_channel, PeerManagerNotification, PeerManagerRequest},
+        protocols::{network::NewNetworkEvents, rpc::InboundRpcRequest},
+        transport::ConnectionMetadata,
+    };
+    use std::str::FromStr;
+
+    const KEY: &str = "080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120";
+    const INTERVAL: Duration = Duration::from_secs(30);
+
+    struct TestHarness {
+        network_context: NetworkContext,
+        mock_time: MockTimeService,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
+        peer_mgr_reqs_rx: diem_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+        peer_mgr_notifs_tx: diem_channel::Sender<(PeerId, ProtocolId), PeerManagerNotification>,
+        _connection_notifs_tx: conn_notifs_channel::Sender,
+    }
+
+    impl TestHarness {
+        fn new() -> (Self, PeerExchangeStream) {
+            ::diem_logger::Logger::init_for_testing();
+            let network_context = NetworkContext::mock();
+            let mock_time = TimeService::mock();
+            let peer_metadata_storage = PeerMetadataStorage::new(&[network_context.network_id()]);
+
+            let (peer_mgr_reqs_tx, peer_mgr_reqs_rx) = diem_channel::new(QueueStyle::FIFO, 8, None);
+            let (connection_reqs_tx, _) = diem_channel::new(QueueStyle::FIFO, 8, None);
+            let (peer_mgr_notifs_tx, peer_mgr_notifs_rx) =
+                diem_channel::new(QueueStyle::FIFO, 8, None);
+            let (connection_notifs_tx, connection_notifs_rx) = conn_notifs_channel::new();
+
+            let stream = PeerExchangeStream::new(
+                network_context,
+                peer_metadata_storage.clone(),
+                PeerExchangeNetworkSender::new(
+                    PeerManagerRequestSender::new(peer_mgr_reqs_tx),
+                    ConnectionRequestSender::new(connection_reqs_tx),
+                ),
+                PeerExchangeNetworkEvents::new(peer_mgr_notifs_rx, connection_notifs_rx),
+                INTERVAL,
+                mock_time.clone(),
+            );
+
+            (
+                Self {
+                    network_context,
+                    mock_time: mock_time.into_mock(),
+                    peer_metadata_storage,
+                    peer_mgr_reqs_rx,
+                    peer_mgr_notifs_tx,
+                    _connection_notifs_tx: connection_notifs_tx,
+                },
+                stream,
+            )
+        }
+
+        /// Adds a connected peer, returning its id and the address it's connected at
+        fn connect_peer(
+            &self,
+            seed: u8,
+            origin: ConnectionOrigin,
+            peer_exchange: bool,
+        ) -> (PeerId, NetworkAddress) {
+            let (peer_id, addr) = peer_addr(seed);
+            let mut conn_meta =
+                ConnectionMetadata::mock_with_role_and_origin(peer_id, PeerRole::Unknown, origin);
+            conn_meta.addr = addr.clone();
+            if peer_exchange {
+                conn_meta.application_protocols = [ProtocolId::PeerExchangeRpc].iter().collect();
+            }
+            self.peer_metadata_storage
+                .insert_connection(self.network_context.network_id(), conn_meta);
+            (peer_id, addr)
+        }
+
+        fn disconnect_peer(&self, peer_id: PeerId) {
+            self.peer_metadata_storage.remove(&PeerNetworkId::new(
+                self.network_context.network_id(),
+                peer_id,
+            ));
+        }
+
+        async fn start_round(&self) {
+            self.mock_time.advance_async(INTERVAL).await;
+        }
+
+        async fn expect_get_peers(&mut self) -> (PeerId, oneshot::Sender<Result<Bytes, RpcError>>) {
+            let (peer_id, rpc_req) = match self.peer_mgr_reqs_rx.next().await.unwrap() {
+                PeerManagerRequest::SendRpc(peer_id, rpc_req) => (peer_id, rpc_req),
+                req => panic!("Unexpected PeerManagerRequest: {:?}", req),
+            };
+            assert_eq!(rpc_req.protocol_id, ProtocolId::PeerExchangeRpc);
+            assert!(matches!(
+                rpc_req.protocol_id.from_bytes(&rpc_req.data).unwrap(),
+                PeerExchangeMsg::GetPeers
+            ));
+            (peer_id, rpc_req.res_tx)
+        }
+
+        /// Sends a `GetPeers` request from `requester`, returning the shared peers
+        async fn send_get_peers(&mut self, requester: PeerId) -> Vec<(PeerId, NetworkAddress)> {
+            let protocol_id = ProtocolId::PeerExchangeRpc;
+            let (res_tx, res_rx) = oneshot::channel();
+            let request = InboundRpcRequest {
+                protocol_id,
+                data: protocol_id
+                    .to_bytes(&PeerExchangeMsg::GetPeers)
+                    .unwrap()
+                    .into(),
+                res_tx,
+            };
+            self.peer_mgr_notifs_tx
+                .push(
+                    (requester, protocol_id),
+                    PeerManagerNotification::RecvRpc(requester, request),
+                )
+                .unwrap();
+            let response = res_rx.await.unwrap().unwrap();
+            match protocol_id.from_bytes(&response).unwrap() {
+                PeerExchangeMsg::Peers(peers) => peers,
+                msg => panic!("Unexpected PeerExchangeMsg: {:?}", msg),
+            }
+        }
+    }
+
+    /// A dialable address, and the peer id its key derives
+    fn peer_addr(seed: u8) -> (PeerId, NetworkAddress) {
+        let pubkey = x25519::PrivateKey::from([seed; 32]).public_key();
+        let addr = NetworkAddress::from_str(&format!("/ip4/1.2.3.{}/tcp/6180", seed))
+            .unwrap()
+            .append_prod_protos(pubkey, HANDSHAKE_VERSION);
+        (from_identity_public_key(pubkey), addr)
+    }
+
+    fn respond(
+        res_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+        peers: Vec<(PeerId, NetworkAddress)>,
+    ) {
+        let response = ProtocolId::PeerExchangeRpc
+            .to_bytes(&PeerExchangeMsg::Peers(peers))
+            .unwrap();
+        res_tx.send(Ok(response.into())).unwrap();
+    }
+
+    #[test]
+    fn test_is_valid_shared_addr() {
+        let addr = NetworkAddress::from_str(&format!(
+            "/ip4/1.2.3.4/tcp/6180/ln-noise-ik/{}/ln-handshake/0",
+            KEY
+        ))
+        .unwrap();
+        let peer_id = from_identity_public_key(addr.find_noise_proto().unwrap());
+        assert!(is_valid_shared_addr(&peer_id, &addr));
+
+        // Key doesn't match the peer id
+        assert!(!is_valid_shared_addr(&PeerId::random(), &addr));
+
+        // Not a dialable address
+        let addr = NetworkAddress::from_str(&format!("/memory/1234/ln-noise-ik/{}", KEY)).unwrap();
+        assert!(!is_valid_shared_addr(&peer_id, &addr));
+    }
+
+    #[test]
+    fn test_round() {
+        let (mut harness, mut stream) = TestHarness::new();
+        let (peer_a, _) = harness.connect_peer(1, ConnectionOrigin::Outbound, true);
+        let (peer_b, _) = harness.connect_peer(2, ConnectionOrigin::Inbound, true);
+        // Doesn't support peer exchange, so it's never asked
+        harness.connect_peer(3, ConnectionOrigin::Outbound, false);
+        let (shared_peer, shared_addr) = peer_addr(4);
+
+        let test = async {
+            harness.start_round().await;
+            let mut requests = HashMap::new();
+            for _ in 0..2 {
+                let (peer_id, res_tx) = harness.expect_get_peers().await;
+                requests.insert(peer_id, res_tx);
+            }
+            assert!(requests.contains_key(&peer_a) && requests.contains_key(&peer_b));
+
+            // Addresses whose key doesn't derive the shared peer id are dropped
+            respond(
+                requests.remove(&peer_a).unwrap(),
+                vec![
+                    (shared_peer, shared_addr.clone()),
+                    (PeerId::random(), shared_addr.clone()),
+                ],
+            );
+            requests
+                .remove(&peer_b)
+                .unwrap()
+                .send(Err(RpcError::TimedOut))
+                .unwrap();
+        };
+        let (discovered, _) = block_on(future::join(stream.next(), test));
+        let discovered = discovered.unwrap().unwrap();
+        assert_eq!(discovered.len(), 1);
+        assert_eq!(discovered[&shared_peer].addresses, vec![shared_addr]);
+
+        // The responses of disconnected peers are dropped in the next round
+        harness.disconnect_peer(peer_a);
+        let test = async {
+            harness.start_round().await;
+            let (peer_id, res_tx) = harness.expect_get_peers().await;
+            assert_eq!(peer_id, peer_b);
+            respond(res_tx, vec![]);
+        };
+        let (discovered, _) = block_on(future::join(stream.next(), test));
+        assert!(discovered.unwrap().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_peers() {
+        let (mut harness, mut stream) = TestHarness::new();
+        let (requester, _) = harness.connect_peer(1, ConnectionOrigin::Outbound, false);
+        let (outbound_peer, outbound_addr) =
+            harness.connect_peer(2, ConnectionOrigin::Outbound, false);
+        harness.connect_peer(3, ConnectionOrigin::Inbound, false);
+
+        // Only the addresses we dialed out to, other than the requester's, are shared
+        let test = async {
+            let peers = harness.send_get_peers(requester).await;
+            assert_eq!(peers, vec![(outbound_peer, outbound_addr)]);
+        };
+        block_on(future::select(stream.next(), Box::pin(test)));
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::transport::Transport;
+use crate::transport::{SubstreamSocket, Transport};
 use diem_types::{
     network_address::{parse_memory, NetworkAddress, Protocol},
     PeerId,
@@ -98,6 +98,8 @@ impl Stream for Listener {
     }
 }
 
+impl SubstreamSocket for MemorySocket {}
+
 #[cfg(test)]
 mod test {
     use crate::transport::{memory::MemoryTransport, Transport};
@@ -21,6 +21,7 @@ pub mod boxed;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 pub mod memory;
 pub mod proxy_protocol;
+pub mod quic;
 pub mod tcp;
 
 /// Origin of how a Connection was established.
@@ -114,6 +115,29 @@ pub trait Transport {
         Self: Sized;
 }
 
+/// A socket produced by a base [`Transport`] which may carry additional,
+/// independent streams next to its primary byte-stream.
+///
+/// Upper layers use the primary byte-stream for connection setup (e.g.
+/// authentication and version negotiation). Transports with native stream
+/// multiplexing, like [`QuicTransport`](quic::QuicTransport), also hand out a
+/// [`Substreams`](quic::Substreams) handle so that independent traffic is not
+/// head-of-line blocked behind a single ordered byte-stream.
+pub trait SubstreamSocket {
+    /// Take the [`Substreams`](quic::Substreams) handle of this connection, if
+    /// the underlying transport supports them. Subsequent calls return `None`.
+    fn take_substreams(&mut self) -> Option<quic::Substreams> {
+        None
+    }
+
+    /// Bytes identifying the secure channel this socket runs over, if the
+    /// underlying transport has one. Upper layers bind their own handshake to
+    /// it, so that it cannot be relayed onto a different channel.
+    fn channel_binding(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
 impl<T: ?Sized> TransportExt for T where T: Transport {}
 
 /// An extension trait for [`Transport`]s that provides a variety of convenient
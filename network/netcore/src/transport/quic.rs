@@ -0,0 +1,701 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! QUIC Transport
+//!
+//! Each QUIC connection exposes its first bidirectional stream as the primary
+//! byte-stream of a [`QuicSocket`], which upper layers use exactly like a TCP
+//! socket (e.g. for the Noise IK handshake). All further streams are
+//! unidirectional and are handed out through the connection's [`Substreams`].
+//!
+//! Peer authentication is *not* done by TLS: each listener presents a
+//! throwaway self-signed certificate and the dialer accepts any server
+//! certificate. Authentication is instead provided by the Noise IK handshake on
+//! the primary stream, which is bound to the QUIC connection through
+//! [`SubstreamSocket::channel_binding`]: keying material exported from the
+//! connection's TLS session, which both sides only agree on if they share the
+//! same session. A man in the middle, who has to run a separate session with
+//! each side, therefore cannot relay the Noise handshake, and the substreams,
+//! which are encrypted with the keys of that same session, can only be read
+//! and written by the authenticated peer.
+//!
+//! All outbound connections of a transport (and its clones) are dialed from
+//! one endpoint per address family, bound to an ephemeral port on first use.
+use crate::transport::{SubstreamSocket, Transport};
+use diem_infallible::Mutex;
+use diem_types::{
+    network_address::{parse_dns_quic, parse_ip_quic, NetworkAddress, Protocol},
+    PeerId,
+};
+use futures::{
+    future::{Future, FutureExt},
+    io::{AsyncRead, AsyncWrite},
+    stream::{Stream, StreamExt},
+};
+use quinn::{
+    Certificate, CertificateChain, ClientConfig, ClientConfigBuilder, Connecting, Endpoint,
+    Incoming, IncomingUniStreams, NewConnection, PrivateKey, ServerConfig, ServerConfigBuilder,
+    TransportConfig,
+};
+pub use quinn::{RecvStream, SendStream};
+use std::{
+    fmt, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{net::lookup_host, runtime::Handle};
+
+/// The server name the dialer expects in the listener's certificate. Since
+/// certificates are not used for authentication, this is a fixed placeholder.
+const SERVER_NAME: &str = "diem";
+
+/// The ALPN protocol identifier for DiemNet over QUIC.
+const ALPN_PROTOCOL: &[u8] = b"diemnet";
+
+/// The label of the keying material exported as the channel binding.
+const CHANNEL_BINDING_LABEL: &[u8] = b"EXPORTER-diemnet-channel-binding";
+
+/// The length of the keying material exported as the channel binding.
+const CHANNEL_BINDING_LENGTH: usize = 32;
+
+/// Transport to build QUIC connections
+#[derive(Clone)]
+pub struct QuicTransport {
+    /// Maximum duration of inactivity before a connection is timed out, or
+    /// `None` to keep the default.
+    max_idle_timeout: Option<Duration>,
+    /// Interval at which keep-alive packets are sent on an otherwise idle
+    /// connection, or `None` to keep the default (no keep-alives).
+    keep_alive_interval: Option<Duration>,
+    /// The endpoints outbound connections are dialed from, shared by all
+    /// clones of this transport.
+    client_endpoints: Arc<Mutex<ClientEndpoints>>,
+}
+
+/// The client endpoint of each address family, once bound.
+#[derive(Default)]
+struct ClientEndpoints {
+    v4: Option<Endpoint>,
+    v6: Option<Endpoint>,
+    /// The runtime the endpoint drivers were spawned on.
+    runtime: Option<Handle>,
+}
+
+impl Drop for ClientEndpoints {
+    fn drop(&mut self) {
+        // Dropping the last handle to an endpoint wakes its driver while
+        // holding the endpoint lock, which deadlocks when done outside of the
+        // runtime the driver runs on.
+        let _guard = self.runtime.as_ref().map(Handle::enter);
+        self.v4 = None;
+        self.v6 = None;
+    }
+}
+
+impl Default for QuicTransport {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl fmt::Debug for QuicTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuicTransport")
+            .field("max_idle_timeout", &self.max_idle_timeout)
+            .field("keep_alive_interval", &self.keep_alive_interval)
+            .finish()
+    }
+}
+
+impl QuicTransport {
+    pub fn new(max_idle_timeout: Option<Duration>, keep_alive_interval: Option<Duration>) -> Self {
+        Self {
+            max_idle_timeout,
+            keep_alive_interval,
+            client_endpoints: Arc::new(Mutex::new(ClientEndpoints::default())),
+        }
+    }
+
+    fn transport_config(&self) -> io::Result<Arc<TransportConfig>> {
+        let mut config = TransportConfig::default();
+        config
+            .max_idle_timeout(self.max_idle_timeout)
+            .map_err(invalid_config_error)?;
+        config.keep_alive_interval(self.keep_alive_interval);
+        Ok(Arc::new(config))
+    }
+
+    fn server_config(&self) -> io::Result<ServerConfig> {
+        let self_signed = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])
+            .map_err(invalid_config_error)?;
+        let cert_der = self_signed.serialize_der().map_err(invalid_config_error)?;
+        let cert = Certificate::from_der(&cert_der).map_err(invalid_config_error)?;
+        let key = PrivateKey::from_der(&self_signed.serialize_private_key_der())
+            .map_err(invalid_config_error)?;
+
+        let mut builder = ServerConfigBuilder::default();
+        builder.protocols(&[ALPN_PROTOCOL]);
+        builder
+            .certificate(CertificateChain::from_certs(vec![cert]), key)
+            .map_err(invalid_config_error)?;
+        let mut config = builder.build();
+        config.transport = self.transport_config()?;
+        Ok(config)
+    }
+
+    fn client_config(&self) -> io::Result<ClientConfig> {
+        let mut builder = ClientConfigBuilder::default();
+        builder.protocols(&[ALPN_PROTOCOL]);
+        let mut config = builder.build();
+        Arc::get_mut(&mut config.crypto)
+            .expect("freshly built crypto config is not shared")
+            .dangerous()
+            .set_certificate_verifier(Arc::new(SkipServerVerification));
+        config.transport = self.transport_config()?;
+        Ok(config)
+    }
+
+    /// Return the endpoint to dial `socket_addr` from, binding it to an
+    /// ephemeral local port on the first dial to its address family.
+    fn client_endpoint(&self, socket_addr: SocketAddr) -> io::Result<Endpoint> {
+        let mut endpoints = self.client_endpoints.lock();
+        let (slot, bind_addr) = match socket_addr.ip() {
+            IpAddr::V4(_) => (
+                &mut endpoints.v4,
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            ),
+            IpAddr::V6(_) => (
+                &mut endpoints.v6,
+                SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+            ),
+        };
+        if let Some(endpoint) = slot.as_ref() {
+            return Ok(endpoint.clone());
+        }
+
+        let mut builder = Endpoint::builder();
+        builder.default_client_config(self.client_config()?);
+        // The endpoint only dials out, so its `Incoming` half is dropped, which
+        // refuses any connection dialed to it.
+        let (endpoint, _incoming) = builder.bind(&bind_addr).map_err(endpoint_error)?;
+        let endpoint = slot.insert(endpoint).clone();
+        endpoints.runtime = Some(Handle::current());
+        Ok(endpoint)
+    }
+
+    /// Dial `socket_addr` from the client endpoint of its address family.
+    async fn connect(self, socket_addr: SocketAddr) -> io::Result<QuicSocket> {
+        let endpoint = self.client_endpoint(socket_addr)?;
+
+        let new_conn = endpoint
+            .connect(&socket_addr, SERVER_NAME)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?
+            .await
+            .map_err(connection_error)?;
+        let NewConnection {
+            connection,
+            uni_streams,
+            ..
+        } = new_conn;
+
+        // The dialer always initiates the primary stream. QUIC only announces
+        // a stream to the remote once data is written to it, which the upper
+        // layer's handshake will do right away.
+        let (send, recv) = connection.open_bi().await.map_err(connection_error)?;
+        let channel_binding = channel_binding(&connection)?;
+
+        Ok(QuicSocket {
+            send,
+            recv,
+            channel_binding,
+            substreams: Some(Substreams {
+                connection,
+                incoming: uni_streams,
+            }),
+            remote_addr: socket_addr,
+            _endpoint: endpoint,
+        })
+    }
+}
+
+impl Transport for QuicTransport {
+    type Output = QuicSocket;
+    type Error = io::Error;
+    type Listener = QuicListenerStream;
+    type Inbound = Pin<Box<dyn Future<Output = io::Result<QuicSocket>> + Send + 'static>>;
+    type Outbound = Pin<Box<dyn Future<Output = io::Result<QuicSocket>> + Send + 'static>>;
+
+    fn listen_on(
+        &self,
+        addr: NetworkAddress,
+    ) -> Result<(Self::Listener, NetworkAddress), Self::Error> {
+        let ((ipaddr, port), addr_suffix) =
+            parse_ip_quic(addr.as_slice()).ok_or_else(|| invalid_addr_error(&addr))?;
+        if !addr_suffix.is_empty() {
+            return Err(invalid_addr_error(&addr));
+        }
+
+        let mut builder = Endpoint::builder();
+        builder.listen(self.server_config()?);
+        let (endpoint, incoming) = builder
+            .bind(&SocketAddr::new(ipaddr, port))
+            .map_err(endpoint_error)?;
+        let listen_addr = quic_addr(endpoint.local_addr()?);
+
+        Ok((QuicListenerStream { endpoint, incoming }, listen_addr))
+    }
+
+    fn dial(&self, _peer_id: PeerId, addr: NetworkAddress) -> Result<Self::Outbound, Self::Error> {
+        let protos = addr.as_slice();
+
+        // ensure addr is well formed to save some work before potentially
+        // spawning a dial task that will fail anyway.
+        parse_ip_quic(protos)
+            .map(|_| ())
+            .or_else(|| parse_dns_quic(protos).map(|_| ()))
+            .ok_or_else(|| invalid_addr_error(&addr))?;
+
+        let transport = self.clone();
+        Ok(async move {
+            let protos = addr.as_slice();
+            if let Some(((ipaddr, port), _addr_suffix)) = parse_ip_quic(protos) {
+                transport.connect(SocketAddr::new(ipaddr, port)).await
+            } else if let Some(((ip_filter, dns_name, port), _addr_suffix)) = parse_dns_quic(protos)
+            {
+                // resolve dns name and filter, then try to connect until the
+                // first succeeds
+                let socketaddrs = lookup_host((dns_name.as_ref(), port))
+                    .await?
+                    .filter(|socketaddr| ip_filter.matches(socketaddr.ip()));
+                let mut last_err = None;
+                for socketaddr in socketaddrs {
+                    match transport.clone().connect(socketaddr).await {
+                        Ok(socket) => return Ok(socket),
+                        Err(err) => last_err = Some(err),
+                    }
+                }
+                Err(last_err.unwrap_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "could not resolve dns name to any address: name: {}, ip filter: {:?}",
+                            dns_name.as_ref(),
+                            ip_filter,
+                        ),
+                    )
+                }))
+            } else {
+                Err(invalid_addr_error(&addr))
+            }
+        }
+        .boxed())
+    }
+}
+
+/// Accept an inbound connection and wait for the dialer to open the primary
+/// stream.
+async fn accept(endpoint: Endpoint, connecting: Connecting) -> io::Result<QuicSocket> {
+    let remote_addr = connecting.remote_address();
+    let NewConnection {
+        connection,
+        mut bi_streams,
+        uni_streams,
+        ..
+    } = connecting.await.map_err(connection_error)?;
+
+    let (send, recv) = match bi_streams.next().await {
+        Some(stream) => stream.map_err(connection_error)?,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before the primary stream was opened",
+            ))
+        }
+    };
+    let channel_binding = channel_binding(&connection)?;
+
+    Ok(QuicSocket {
+        send,
+        recv,
+        channel_binding,
+        substreams: Some(Substreams {
+            connection,
+            incoming: uni_streams,
+        }),
+        remote_addr,
+        _endpoint: endpoint,
+    })
+}
+
+/// Export the keying material of the TLS session of `connection` that binds
+/// upper layer handshakes to it.
+fn channel_binding(connection: &quinn::Connection) -> io::Result<[u8; CHANNEL_BINDING_LENGTH]> {
+    let mut binding = [0; CHANNEL_BINDING_LENGTH];
+    connection
+        .export_keying_material(&mut binding, CHANNEL_BINDING_LABEL, &[])
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "failed to export the channel binding of the connection",
+            )
+        })?;
+    Ok(binding)
+}
+
+/// Build a `"/ip4/<addr>/quic/<port>"` or `"/ip6/<addr>/quic/<port>"` address.
+fn quic_addr(socket_addr: SocketAddr) -> NetworkAddress {
+    NetworkAddress::from(Protocol::from(socket_addr.ip())).push(Protocol::Quic(socket_addr.port()))
+}
+
+fn invalid_addr_error(addr: &NetworkAddress) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Invalid NetworkAddress: '{}'", addr),
+    )
+}
+
+fn invalid_config_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, err)
+}
+
+fn endpoint_error(err: quinn::EndpointError) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+fn connection_error(err: quinn::ConnectionError) -> io::Error {
+    let kind = match err {
+        quinn::ConnectionError::TimedOut => io::ErrorKind::TimedOut,
+        quinn::ConnectionError::Reset => io::ErrorKind::ConnectionReset,
+        _ => io::ErrorKind::ConnectionAborted,
+    };
+    io::Error::new(kind, err)
+}
+
+/// Accepts any server certificate. Peers are authenticated by the Noise IK
+/// handshake on the primary stream instead, which is bound to the TLS session.
+struct SkipServerVerification;
+
+impl rustls::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _roots: &rustls::RootCertStore,
+        _presented_certs: &[rustls::Certificate],
+        _dns_name: webpki::DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> Result<rustls::ServerCertVerified, rustls::TLSError> {
+        Ok(rustls::ServerCertVerified::assertion())
+    }
+}
+
+#[must_use = "streams do nothing unless polled"]
+pub struct QuicListenerStream {
+    endpoint: Endpoint,
+    incoming: Incoming,
+}
+
+impl Stream for QuicListenerStream {
+    type Item = io::Result<(
+        Pin<Box<dyn Future<Output = io::Result<QuicSocket>> + Send + 'static>>,
+        NetworkAddress,
+    )>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+        match self.incoming.poll_next_unpin(context) {
+            Poll::Ready(Some(connecting)) => {
+                let dialer_addr = quic_addr(connecting.remote_address());
+                let inbound = accept(self.endpoint.clone(), connecting).boxed();
+                Poll::Ready(Some(Ok((inbound, dialer_addr))))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A QUIC connection, exposing its primary bidirectional stream as a byte-stream.
+pub struct QuicSocket {
+    send: SendStream,
+    recv: RecvStream,
+    channel_binding: [u8; CHANNEL_BINDING_LENGTH],
+    substreams: Option<Substreams>,
+    remote_addr: SocketAddr,
+    // Keep the local endpoint alive for as long as the connection is in use.
+    _endpoint: Endpoint,
+}
+
+impl fmt::Debug for QuicSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuicSocket")
+            .field("remote_addr", &self.remote_addr)
+            .finish()
+    }
+}
+
+impl SubstreamSocket for QuicSocket {
+    fn take_substreams(&mut self) -> Option<Substreams> {
+        self.substreams.take()
+    }
+
+    fn channel_binding(&self) -> Option<&[u8]> {
+        Some(&self.channel_binding)
+    }
+}
+
+impl AsyncRead for QuicSocket {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.recv).poll_read(context, buf)
+    }
+}
+
+impl AsyncWrite for QuicSocket {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.send).poll_write(context, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_flush(context)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.send).poll_close(context)
+    }
+}
+
+/// The additional streams of a QUIC connection, next to its primary stream.
+///
+/// Substreams are unidirectional: each side writes its own outbound traffic on
+/// the substreams it opened and reads the remote's traffic from the substreams
+/// it accepted. Each substream is flow-controlled independently, so a stalled
+/// substream does not block the others.
+pub struct Substreams {
+    connection: quinn::Connection,
+    incoming: IncomingUniStreams,
+}
+
+impl fmt::Debug for Substreams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Substreams")
+            .field("remote_addr", &self.connection.remote_address())
+            .finish()
+    }
+}
+
+impl Substreams {
+    /// Split into a handle for opening outbound substreams and a stream of
+    /// inbound substreams opened by the remote.
+    pub fn split(
+        self,
+    ) -> (
+        SubstreamOpener,
+        impl Stream<Item = io::Result<RecvStream>> + Send + Unpin + 'static,
+    ) {
+        let incoming = self.incoming.map(|stream| stream.map_err(connection_error));
+        (
+            SubstreamOpener {
+                connection: self.connection,
+            },
+            incoming,
+        )
+    }
+}
+
+/// A cloneable handle for opening outbound substreams on a QUIC connection.
+#[derive(Clone)]
+pub struct SubstreamOpener {
+    connection: quinn::Connection,
+}
+
+impl fmt::Debug for SubstreamOpener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubstreamOpener")
+            .field("remote_addr", &self.connection.remote_address())
+            .finish()
+    }
+}
+
+impl SubstreamOpener {
+    /// Open a new outbound substream.
+    pub async fn open(&self) -> io::Result<SendStream> {
+        self.connection.open_uni().await.map_err(connection_error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transport::{ConnectionOrigin, TransportExt};
+    use futures::{
+        future::join,
+        io::{AsyncReadExt, AsyncWriteExt},
+    };
+
+    #[tokio::test]
+    async fn simple_listen_and_dial() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::default().and_then(|mut out, _addr, origin| async move {
+            match origin {
+                ConnectionOrigin::Inbound => {
+                    let mut buf = [0; 3];
+                    out.read_exact(&mut buf).await?;
+                    assert_eq!(&buf, b"Air");
+                    out.write_all(b"Earth").await?;
+                    // unlike a TCP socket, dropping the connection discards
+                    // data the remote has not acknowledged yet
+                    out.close().await?;
+                }
+                ConnectionOrigin::Outbound => {
+                    out.write_all(b"Air").await?;
+                    out.flush().await?;
+                    let mut buf = [0; 5];
+                    out.read_exact(&mut buf).await?;
+                    assert_eq!(&buf, b"Earth");
+                }
+            }
+            Ok(())
+        });
+
+        let (listener, addr) = t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+        assert!(matches!(
+            addr.as_slice(),
+            [Protocol::Ip4(_), Protocol::Quic(port)] if *port != 0
+        ));
+        let peer_id = PeerId::random();
+        let dial = t.dial(peer_id, addr)?;
+        let listener = listener.into_future().then(|(maybe_result, _stream)| {
+            let (incoming, _addr) = maybe_result.unwrap().unwrap();
+            incoming.map(Result::unwrap)
+        });
+
+        let (outgoing, _incoming) = join(dial, listener).await;
+        assert!(outgoing.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn substreams_are_independent() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::default();
+        let (mut listener, addr) = t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+
+        let listener_task = async move {
+            let (inbound, _addr) = listener.next().await.unwrap().unwrap();
+            let mut socket = inbound.await.unwrap();
+            let mut buf = [0; 5];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"hello");
+
+            // read the second substream without ever reading from the first
+            let (_opener, mut incoming) = socket.take_substreams().unwrap().split();
+            let _stalled = incoming.next().await.unwrap().unwrap();
+            let mut second = incoming.next().await.unwrap().unwrap();
+            let mut buf = [0; 6];
+            second.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"second");
+            socket
+        };
+
+        let dialer_task = async move {
+            let mut socket = t.dial(PeerId::random(), addr).unwrap().await.unwrap();
+            socket.write_all(b"hello").await.unwrap();
+            socket.flush().await.unwrap();
+
+            let (opener, _incoming) = socket.take_substreams().unwrap().split();
+            let mut first = opener.open().await.unwrap();
+            first.write_all(b"first").await.unwrap();
+            first.flush().await.unwrap();
+            let mut second = opener.open().await.unwrap();
+            second.write_all(b"second").await.unwrap();
+            second.flush().await.unwrap();
+            (socket, first, second)
+        };
+
+        let _ = join(listener_task, dialer_task).await;
+        Ok(())
+    }
+
+    /// Dial `addr` and accept the connection on `listener`, returning the
+    /// dialer's and the listener's socket.
+    async fn connect_pair(
+        t: &QuicTransport,
+        listener: &mut QuicListenerStream,
+        addr: NetworkAddress,
+    ) -> (QuicSocket, QuicSocket) {
+        let dialer_task = async {
+            let mut socket = t.dial(PeerId::random(), addr).unwrap().await.unwrap();
+            // the listener only sees the primary stream once data is written to it
+            socket.write_all(b"hello").await.unwrap();
+            socket.flush().await.unwrap();
+            socket
+        };
+        let listener_task = async {
+            let (inbound, _addr) = listener.next().await.unwrap().unwrap();
+            inbound.await.unwrap()
+        };
+        join(dialer_task, listener_task).await
+    }
+
+    #[tokio::test]
+    async fn channel_binding_is_per_connection() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::default();
+        let (mut listener, addr) = t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+
+        let (first_dialer, first_listener) = connect_pair(&t, &mut listener, addr.clone()).await;
+        let (second_dialer, second_listener) = connect_pair(&t, &mut listener, addr).await;
+
+        // both sides of a connection agree on its binding
+        assert_eq!(
+            first_dialer.channel_binding(),
+            first_listener.channel_binding()
+        );
+        assert_eq!(
+            second_dialer.channel_binding(),
+            second_listener.channel_binding()
+        );
+        // but no two connections share one, even between the same endpoints
+        assert_ne!(
+            first_dialer.channel_binding(),
+            second_dialer.channel_binding()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dials_share_an_endpoint() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::default();
+        let (mut first_listener, first_addr) =
+            t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+        let (mut second_listener, second_addr) =
+            t.listen_on("/ip4/127.0.0.1/quic/0".parse().unwrap())?;
+
+        let (first, _) = connect_pair(&t, &mut first_listener, first_addr).await;
+        // clones of the transport dial from the same endpoint, too
+        let (second, _) = connect_pair(&t.clone(), &mut second_listener, second_addr).await;
+
+        assert_eq!(
+            first._endpoint.local_addr()?,
+            second._endpoint.local_addr()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_multiaddrs() {
+        let t = QuicTransport::default();
+
+        let result = t.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap());
+        assert!(result.is_err());
+
+        let peer_id = PeerId::random();
+        let result = t.dial(peer_id, "/memory/22".parse().unwrap());
+        assert!(result.is_err());
+    }
+}
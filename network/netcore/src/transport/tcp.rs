@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! TCP Transport
-use crate::transport::Transport;
+use crate::transport::{SubstreamSocket, Transport};
 use diem_types::{
     network_address::{parse_dns_tcp, parse_ip_tcp, parse_tcp, IpFilter, NetworkAddress},
     PeerId,
@@ -318,6 +318,8 @@ impl AsyncWrite for TcpSocket {
     }
 }
 
+impl SubstreamSocket for TcpSocket {}
+
 #[cfg(test)]
 mod test {
     use super::*;
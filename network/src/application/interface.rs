@@ -77,7 +77,9 @@ impl<TMessage: Clone + Message + Send, Sender: ApplicationNetworkSender<TMessage
         }
     }
 
-    fn sender(&self, network_id: &NetworkId) -> &Sender {
+    /// The sender for a single network, for sends that need more control
+    /// than the methods below, e.g., choosing the `ProtocolId`.
+    pub fn sender(&self, network_id: &NetworkId) -> &Sender {
         self.senders.get(network_id).expect("Unknown NetworkId")
     }
 
@@ -0,0 +1,226 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Peer reputation, shared by every application on a node.
+//!
+//! Applications report a peer's misbehavior, e.g., an invalid proof or a
+//! malformed message. Every report lowers the peer's score, which recovers
+//! over time. Once a peer's score drops to [`BAN_THRESHOLD`], the peer is banned
+//! for [`BAN_DURATION`]: the `ConnectivityManager` disconnects it and won't dial
+//! it, and the `PeerManager` rejects its inbound connections.
+//!
+//! Peers on the validator network are never banned: the validator set is fixed
+//! on-chain, and disconnecting a validator would only hurt consensus liveness.
+//! Their misbehavior is still counted.
+
+use crate::counters;
+use diem_config::network_id::{NetworkId, PeerNetworkId};
+use diem_infallible::RwLock;
+use diem_logger::prelude::*;
+use diem_time_service::{TimeService, TimeServiceTrait};
+use diem_types::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    time::Duration,
+};
+
+/// The score of a peer without any reported misbehavior.
+pub const MAX_SCORE: f64 = 100.0;
+/// A peer is banned once its score drops to this threshold.
+pub const BAN_THRESHOLD: f64 = 0.0;
+/// How long a peer stays banned.
+pub const BAN_DURATION: Duration = Duration::from_secs(10 * 60);
+/// Score regained per second, so a peer fully recovers in 10 minutes.
+const RECOVERY_PER_SEC: f64 = MAX_SCORE / 600.0;
+
+/// Misbehavior an application can report about a peer.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Misbehavior {
+    /// A message that couldn't be deserialized, or violates the protocol.
+    MalformedMessage,
+    /// A proof or signature that doesn't verify.
+    InvalidProof,
+}
+
+impl Misbehavior {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Misbehavior::MalformedMessage => "malformed_message",
+            Misbehavior::InvalidProof => "invalid_proof",
+        }
+    }
+
+    /// Score lost for each report.
+    fn penalty(self) -> f64 {
+        match self {
+            Misbehavior::MalformedMessage => 25.0,
+            Misbehavior::InvalidProof => 50.0,
+        }
+    }
+}
+
+impl fmt::Display for Misbehavior {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A currently banned peer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PeerBan {
+    pub peer_network_id: PeerNetworkId,
+    /// The misbehavior which dropped the peer below the threshold.
+    pub reason: Misbehavior,
+    /// Unix time, in seconds, when the ban ends.
+    pub expiration_timestamp_secs: u64,
+}
+
+#[derive(Debug)]
+struct Reputation {
+    score: f64,
+    /// Unix time the score was last updated, to recover it since.
+    last_updated: Duration,
+    /// Unix time the ban ends, and its reason.
+    banned_until: Option<(Duration, Misbehavior)>,
+}
+
+impl Reputation {
+    fn new(now: Duration) -> Self {
+        Self {
+            score: MAX_SCORE,
+            last_updated: now,
+            banned_until: None,
+        }
+    }
+
+    /// Lift an expired ban, and recover the score for the time passed.
+    fn refresh(&mut self, now: Duration) {
+        if matches!(self.banned_until, Some((until, _)) if until <= now) {
+            self.banned_until = None;
+        }
+        let elapsed = now.saturating_sub(self.last_updated).as_secs_f64();
+        self.score = f64::min(self.score + elapsed * RECOVERY_PER_SEC, MAX_SCORE);
+        self.last_updated = now;
+    }
+
+    fn is_banned(&self, now: Duration) -> bool {
+        matches!(self.banned_until, Some((until, _)) if until > now)
+    }
+
+    /// Whether there's nothing left to remember about the peer.
+    fn is_forgotten(&self) -> bool {
+        self.banned_until.is_none() && self.score >= MAX_SCORE
+    }
+}
+
+/// Reputation of peers across all networks.
+#[derive(Debug)]
+pub struct PeerReputation {
+    time_service: TimeService,
+    reputations: RwLock<HashMap<PeerNetworkId, Reputation>>,
+}
+
+impl PeerReputation {
+    pub fn new(time_service: TimeService) -> Self {
+        Self {
+            time_service,
+            reputations: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Report misbehavior of `peer`.  Returns true if this got the peer banned.
+    pub fn report(&self, peer: PeerNetworkId, misbehavior: Misbehavior) -> bool {
+        let now = self.time_service.now_unix_time();
+        counters::peer_misbehavior(peer.network_id(), misbehavior).inc();
+        if peer.network_id().is_validator_network() {
+            return false;
+        }
+
+        let mut reputations = self.reputations.write();
+        // Peers don't need to be tracked once they've fully recovered
+        reputations.retain(|_, reputation| {
+            reputation.refresh(now);
+            !reputation.is_forgotten()
+        });
+
+        let reputation = reputations
+            .entry(peer)
+            .or_insert_with(|| Reputation::new(now));
+        if reputation.is_banned(now) {
+            return false;
+        }
+        reputation.score -= misbehavior.penalty();
+        if reputation.score > BAN_THRESHOLD {
+            return false;
+        }
+
+        // Start from scratch once the ban is over
+        reputation.score = MAX_SCORE;
+        reputation.banned_until = Some((now + BAN_DURATION, misbehavior));
+        counters::peer_bans(peer.network_id()).inc();
+        warn!(
+            peer = %peer,
+            reason = misbehavior.as_str(),
+            "Banning peer {} for {} seconds after {}",
+            peer,
+            BAN_DURATION.as_secs(),
+            misbehavior
+        );
+        true
+    }
+
+    pub fn is_banned(&self, peer: PeerNetworkId) -> bool {
+        let now = self.time_service.now_unix_time();
+        self.reputations
+            .read()
+            .get(&peer)
+            .map_or(false, |reputation| reputation.is_banned(now))
+    }
+
+    /// The currently banned peers of a network.
+    pub fn banned_peers(&self, network_id: NetworkId) -> HashSet<PeerId> {
+        let now = self.time_service.now_unix_time();
+        self.reputations
+            .read()
+            .iter()
+            .filter(|(peer, reputation)| {
+                peer.network_id() == network_id && reputation.is_banned(now)
+            })
+            .map(|(peer, _)| peer.peer_id())
+            .collect()
+    }
+
+    /// All current bans, across all networks.
+    pub fn bans(&self) -> Vec<PeerBan> {
+        let now = self.time_service.now_unix_time();
+        self.reputations
+            .read()
+            .iter()
+            .filter_map(|(peer, reputation)| match reputation.banned_until {
+                Some((until, reason)) if until > now => Some(PeerBan {
+                    peer_network_id: *peer,
+                    reason,
+                    expiration_timestamp_secs: until.as_secs(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Lift the ban of `peer`, and forget its misbehavior.  Returns true if the
+    /// peer was banned.
+    pub fn clear_ban(&self, peer: PeerNetworkId) -> bool {
+        let now = self.time_service.now_unix_time();
+        self.reputations
+            .write()
+            .remove(&peer)
+            .map_or(false, |reputation| reputation.is_banned(now))
+    }
+
+    /// Lift all bans, and forget all misbehavior.
+    pub fn clear_all_bans(&self) {
+        self.reputations.write().clear();
+    }
+}
@@ -2,11 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    application::types::{PeerError, PeerInfo},
+    application::{
+        reputation::PeerReputation,
+        types::{PeerError, PeerInfo},
+    },
     transport::ConnectionMetadata,
 };
 use diem_config::network_id::{NetworkId, PeerNetworkId};
 use diem_infallible::{RwLock, RwLockWriteGuard};
+use diem_time_service::TimeService;
 use diem_types::{account_address::AccountAddress, PeerId};
 use std::{
     collections::{hash_map::Entry, HashMap},
@@ -20,6 +24,7 @@ use std::{
 #[derive(Debug)]
 pub struct PeerMetadataStorage {
     storage: HashMap<NetworkId, LockingHashMap<PeerId, PeerInfo>>,
+    reputation: PeerReputation,
 }
 
 impl PeerMetadataStorage {
@@ -30,8 +35,16 @@ impl PeerMetadataStorage {
 
     /// Create a new `PeerMetadataStorage` `NetworkId`s must be known at construction time
     pub fn new(network_ids: &[NetworkId]) -> Arc<PeerMetadataStorage> {
+        Self::new_with_time_service(network_ids, TimeService::real())
+    }
+
+    pub fn new_with_time_service(
+        network_ids: &[NetworkId],
+        time_service: TimeService,
+    ) -> Arc<PeerMetadataStorage> {
         let mut peer_metadata_storage = PeerMetadataStorage {
             storage: HashMap::new(),
+            reputation: PeerReputation::new(time_service),
         };
         network_ids.iter().for_each(|network_id| {
             peer_metadata_storage
@@ -41,6 +54,11 @@ impl PeerMetadataStorage {
         Arc::new(peer_metadata_storage)
     }
 
+    /// The reputation of peers, for applications to report misbehavior to
+    pub fn reputation(&self) -> &PeerReputation {
+        &self.reputation
+    }
+
     pub fn networks(&self) -> impl Iterator<Item = NetworkId> + '_ {
         self.storage.keys().copied()
     }
@@ -4,6 +4,7 @@
 use crate::{
     application::{
         interface::NetworkInterface,
+        reputation::{Misbehavior, PeerBan, PeerReputation, BAN_DURATION},
         storage::{LockingHashMap, PeerMetadataStorage},
         types::{PeerError, PeerState},
     },
@@ -11,6 +12,7 @@ use crate::{
     transport::ConnectionMetadata,
 };
 use diem_config::network_id::{NetworkId, PeerNetworkId};
+use diem_time_service::{TimeService, TimeServiceTrait};
 use diem_types::PeerId;
 use std::{collections::hash_map::Entry, sync::Arc};
 
@@ -80,6 +82,94 @@ fn test_interface() {
     assert_eq!(0, interface.connected_peers(network_id).len());
 }
 
+#[test]
+fn test_reputation_ban() {
+    let time_service = TimeService::mock();
+    let mock_time = time_service.clone().into_mock();
+    let reputation = PeerReputation::new(time_service);
+    let peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+    let other_peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+    // A peer is banned once its score is exhausted
+    for _ in 0..3 {
+        assert!(!reputation.report(peer, Misbehavior::MalformedMessage));
+    }
+    assert!(!reputation.is_banned(peer));
+    assert!(reputation.report(peer, Misbehavior::MalformedMessage));
+    assert!(reputation.is_banned(peer));
+    assert!(!reputation.is_banned(other_peer));
+
+    // Bans are per network
+    assert_eq!(1, reputation.banned_peers(NetworkId::Vfn).len());
+    assert!(reputation.banned_peers(NetworkId::Public).is_empty());
+    let expiration = mock_time.now_unix_time() + BAN_DURATION;
+    assert_eq!(
+        vec![PeerBan {
+            peer_network_id: peer,
+            reason: Misbehavior::MalformedMessage,
+            expiration_timestamp_secs: expiration.as_secs(),
+        }],
+        reputation.bans()
+    );
+
+    // Reports while banned don't extend the ban
+    assert!(!reputation.report(peer, Misbehavior::InvalidProof));
+
+    // The ban is lifted once it expires
+    mock_time.advance(BAN_DURATION);
+    assert!(!reputation.is_banned(peer));
+    assert!(reputation.bans().is_empty());
+}
+
+#[test]
+fn test_reputation_recovery() {
+    let time_service = TimeService::mock();
+    let mock_time = time_service.clone().into_mock();
+    let reputation = PeerReputation::new(time_service);
+    let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+
+    // The score recovers over time, so spread out misbehavior doesn't get a peer banned
+    for _ in 0..10 {
+        assert!(!reputation.report(peer, Misbehavior::InvalidProof));
+        mock_time.advance(BAN_DURATION);
+    }
+    assert!(!reputation.is_banned(peer));
+
+    assert!(!reputation.report(peer, Misbehavior::InvalidProof));
+    assert!(reputation.report(peer, Misbehavior::InvalidProof));
+}
+
+#[test]
+fn test_reputation_clear_ban() {
+    let reputation = PeerReputation::new(TimeService::mock());
+    let peer = PeerNetworkId::new(NetworkId::Public, PeerId::random());
+    let other_peer = PeerNetworkId::new(NetworkId::Vfn, PeerId::random());
+
+    assert!(!reputation.clear_ban(peer));
+    while !reputation.report(peer, Misbehavior::InvalidProof) {}
+    while !reputation.report(other_peer, Misbehavior::MalformedMessage) {}
+    assert_eq!(2, reputation.bans().len());
+
+    assert!(reputation.clear_ban(peer));
+    assert!(!reputation.is_banned(peer));
+    assert!(reputation.is_banned(other_peer));
+
+    reputation.clear_all_bans();
+    assert!(reputation.bans().is_empty());
+}
+
+#[test]
+fn test_reputation_validators_not_banned() {
+    let reputation = PeerReputation::new(TimeService::mock());
+    let peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+
+    for _ in 0..10 {
+        assert!(!reputation.report(peer, Misbehavior::InvalidProof));
+    }
+    assert!(!reputation.is_banned(peer));
+    assert!(reputation.banned_peers(NetworkId::Validator).is_empty());
+}
+
 fn update_state(
     peer_metadata_storage: Arc<PeerMetadataStorage>,
     peer_network_id: PeerNetworkId,
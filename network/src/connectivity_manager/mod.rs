@@ -9,15 +9,19 @@
 //! Consensus actor informs the ConnectivityManager of eligible nodes.
 //!
 //! Different discovery sources notify the ConnectivityManager of updates to
-//! peers' addresses. Currently, there are 2 discovery sources (ordered by
+//! peers' addresses. Currently, there are 5 discovery sources (ordered by
 //! decreasing dial priority, i.e., first is highest priority):
 //!
 //! 1. Onchain discovery protocol
-//! 2. Seed peers from config
+//! 2. Peers from a local file
+//! 3. Peers from DNS SRV/TXT records
+//! 4. Seed peers from config
+//! 5. Peers shared by connected peers via the peer-exchange protocol
 //!
 //! In other words, if a we have some addresses discovered via onchain discovery
 //! and some seed addresses from our local config, we will try the onchain
-//! discovery addresses first and the local seed addresses after.
+//! discovery addresses first and the local seed addresses after. Addresses
+//! learned from other peers are the least trusted, so they are tried last.
 //!
 //! When dialing a peer with a given list of addresses, we attempt each address
 //! in order with a capped exponential backoff delay until we eventually connect
@@ -27,6 +31,7 @@
 //! using a relay protocol.
 
 use crate::{
+    application::storage::PeerMetadataStorage,
     counters,
     logging::NetworkSchema,
     peer_manager::{self, conn_notifs_channel, ConnectionRequestSender, PeerManagerError},
@@ -113,16 +118,20 @@ pub struct ConnectivityManager<TBackoff> {
     rng: SmallRng,
     /// Whether we are using mutual authentication or not
     mutual_authentication: bool,
+    /// Peers banned for misbehavior are disconnected and not dialed.
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
 }
 
 /// Different sources for peer addresses, ordered by priority (Onchain=highest,
-/// Config=lowest).
+/// PeerExchange=lowest).
 #[repr(u8)]
 #[derive(Copy, Clone, Eq, Hash, PartialEq, Ord, PartialOrd, NumVariants, Serialize)]
 pub enum DiscoverySource {
     OnChainValidatorSet,
     File,
+    Dns,
     Config,
+    PeerExchange,
 }
 
 impl fmt::Debug for DiscoverySource {
@@ -139,7 +148,9 @@ impl fmt::Display for DiscoverySource {
             match self {
                 DiscoverySource::OnChainValidatorSet => "OnChainValidatorSet",
                 DiscoverySource::File => "File",
+                DiscoverySource::Dns => "Dns",
                 DiscoverySource::Config => "Config",
+                DiscoverySource::PeerExchange => "PeerExchange",
             }
         )
     }
@@ -263,6 +274,7 @@ where
         max_delay: Duration,
         outbound_connection_limit: Option<usize>,
         mutual_authentication: bool,
+        peer_metadata_storage: Arc<PeerMetadataStorage>,
     ) -> Self {
         assert!(
             eligible.read().is_empty(),
@@ -293,6 +305,7 @@ where
             outbound_connection_limit,
             rng: SmallRng::from_entropy(),
             mutual_authentication,
+            peer_metadata_storage,
         };
 
         // set the initial config addresses and pubkeys
@@ -354,23 +367,32 @@ where
         );
     }
 
-    /// Disconnect from all peers that are no longer eligible.
+    /// The peers currently banned for misbehavior on this network.
+    fn banned_peers(&self) -> HashSet<PeerId> {
+        self.peer_metadata_storage
+            .reputation()
+            .banned_peers(self.network_context.network_id())
+    }
+
+    /// Disconnect from all peers that are no longer eligible, or are banned.
     ///
     /// For instance, a validator might leave the validator set after a
     /// reconfiguration. If we are currently connected to this validator, calling
     /// this function will close our connection to it.
     async fn close_stale_connections(&mut self) {
         let eligible = self.eligible.read().clone();
+        let banned = self.banned_peers();
         let stale_connections: Vec<_> = self
             .connected
             .iter()
-            .filter(|(peer_id, _)| !eligible.contains_key(peer_id))
+            .filter(|(peer_id, _)| !eligible.contains_key(peer_id) || banned.contains(peer_id))
             .filter_map(|(peer_id, metadata)| {
                 // If we're using server only auth, we need to not evict unknown peers
                 // TODO: We should prevent `Unknown` from discovery sources
                 if !self.mutual_authentication
                     && metadata.origin == ConnectionOrigin::Inbound
                     && metadata.role == PeerRole::Unknown
+                    && !banned.contains(peer_id)
                 {
                     None
                 } else {
@@ -401,17 +423,18 @@ where
         }
     }
 
-    /// Cancel all pending dials to peers that are no longer eligible.
+    /// Cancel all pending dials to peers that are no longer eligible, or are banned.
     ///
     /// For instance, a validator might leave the validator set after a
     /// reconfiguration. If there is a pending dial to this validator, calling
     /// this function will remove it from the dial queue.
     async fn cancel_stale_dials(&mut self) {
         let eligible = self.eligible.read().clone();
+        let banned = self.banned_peers();
         let stale_dials: Vec<_> = self
             .dial_queue
             .keys()
-            .filter(|peer_id| !eligible.contains_key(peer_id))
+            .filter(|peer_id| !eligible.contains_key(peer_id) || banned.contains(peer_id))
             .cloned()
             .collect();
 
@@ -440,6 +463,7 @@ where
         let network_id = self.network_context.network_id();
         let role = self.network_context.role();
         let roles_to_dial = network_id.upstream_roles(&role);
+        let banned = self.banned_peers();
         let mut eligible: Vec<_> = self
             .discovered_peers
             .0
@@ -449,6 +473,7 @@ where
                 && !self.connected.contains_key(peer_id) // The node is not already connected.
                 && !self.dial_queue.contains_key(peer_id) // There is no pending dial to this node.
                 && roles_to_dial.contains(&peer.role) // We can dial this role
+                && !banned.contains(peer_id) // The node isn't banned for misbehavior
             })
             .collect();
 
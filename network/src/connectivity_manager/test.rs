@@ -3,12 +3,16 @@
 
 use super::*;
 use crate::{
+    application::reputation::Misbehavior,
     peer::DisconnectReason,
     peer_manager::{conn_notifs_channel, ConnectionRequest},
     transport::ConnectionMetadata,
 };
 use channel::{diem_channel, message_queues::QueueStyle};
-use diem_config::config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION};
+use diem_config::{
+    config::{Peer, PeerRole, PeerSet, RoleType, HANDSHAKE_VERSION},
+    network_id::{NetworkId, PeerNetworkId},
+};
 use diem_crypto::{test_utils::TEST_SEED, x25519, Uniform};
 use diem_logger::info;
 use diem_time_service::{MockTimeService, TimeService};
@@ -74,7 +78,9 @@ fn update_peer_with_address(mut peer: Peer, addr_str: &'static str) -> (Peer, Ne
 }
 
 struct TestHarness {
+    network_context: NetworkContext,
     trusted_peers: Arc<RwLock<PeerSet>>,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
     mock_time: MockTimeService,
     connection_reqs_rx: diem_channel::Receiver<PeerId, ConnectionRequest>,
     connection_notifs_tx: conn_notifs_channel::Sender,
@@ -83,12 +89,22 @@ struct TestHarness {
 
 impl TestHarness {
     fn new(seeds: PeerSet) -> (Self, ConnectivityManager<FixedInterval>) {
-        let network_context = NetworkContext::mock();
+        Self::new_with_network_context(seeds, NetworkContext::mock())
+    }
+
+    fn new_with_network_context(
+        seeds: PeerSet,
+        network_context: NetworkContext,
+    ) -> (Self, ConnectivityManager<FixedInterval>) {
         let time_service = TimeService::mock();
         let (connection_reqs_tx, connection_reqs_rx) = diem_channel::new(QueueStyle::FIFO, 1, None);
         let (connection_notifs_tx, connection_notifs_rx) = conn_notifs_channel::new();
         let (conn_mgr_reqs_tx, conn_mgr_reqs_rx) = channel::new_test(0);
         let trusted_peers = Arc::new(RwLock::new(HashMap::new()));
+        let peer_metadata_storage = PeerMetadataStorage::new_with_time_service(
+            &[network_context.network_id()],
+            time_service.clone(),
+        );
 
         let conn_mgr = ConnectivityManager::new(
             network_context,
@@ -103,9 +119,12 @@ impl TestHarness {
             MAX_CONNECTION_DELAY,
             Some(MAX_TEST_CONNECTIONS),
             true, /* mutual_authentication */
+            peer_metadata_storage.clone(),
         );
         let mock = Self {
+            network_context,
             trusted_peers,
+            peer_metadata_storage,
             mock_time: time_service.into_mock(),
             connection_reqs_rx,
             connection_notifs_tx,
@@ -162,7 +181,7 @@ impl TestHarness {
             ConnectionOrigin::Outbound,
         );
         metadata.addr = address;
-        let notif = peer_manager::ConnectionNotification::NewPeer(metadata, NetworkContext::mock());
+        let notif = peer_manager::ConnectionNotification::NewPeer(metadata, self.network_context);
         self.send_notification_await_delivery(peer_id, notif).await;
     }
 
@@ -179,7 +198,7 @@ impl TestHarness {
         metadata.addr = address;
         let notif = peer_manager::ConnectionNotification::LostPeer(
             metadata,
-            NetworkContext::mock(),
+            self.network_context,
             DisconnectReason::ConnectionLost,
         );
         self.send_notification_await_delivery(peer_id, notif).await;
@@ -301,6 +320,12 @@ impl TestHarness {
         self.wait_until_empty_dial_queue().await;
     }
 
+    fn ban_peer(&self, peer_id: PeerId) {
+        let peer = PeerNetworkId::new(self.network_context.network_id(), peer_id);
+        let reputation = self.peer_metadata_storage.reputation();
+        while !reputation.report(peer, Misbehavior::InvalidProof) {}
+    }
+
     async fn send_update_discovered_peers(&mut self, src: DiscoverySource, peers: PeerSet) {
         info!("Sending UpdateDiscoveredPeers");
         self.conn_mgr_reqs_tx
@@ -466,6 +491,49 @@ fn disconnect() {
     block_on(future::join(conn_mgr.start(), test));
 }
 
+// Tests that connectivity manager disconnects banned peers, and doesn't dial them
+// until the ban is lifted.
+#[test]
+fn banned_peer() {
+    let (other_peer_id, other_peer, _, other_addr) = test_peer(0);
+    // Peers on the validator network are never banned, so this is a VFN dialing its validator
+    let network_context = NetworkContext::new(RoleType::FullNode, NetworkId::Vfn, PeerId::random());
+    let (mut mock, conn_mgr) =
+        TestHarness::new_with_network_context(HashMap::new(), network_context);
+
+    let test = async move {
+        // Sending pubkey & address of other peer
+        let peers = hashmap! {other_peer_id => other_peer};
+        mock.send_update_discovered_peers(DiscoverySource::OnChainValidatorSet, peers)
+            .await;
+
+        // Waiting to receive dial request
+        mock.trigger_connectivity_check().await;
+        mock.trigger_pending_dials().await;
+        mock.expect_one_dial_success(other_peer_id, other_addr.clone())
+            .await;
+
+        // Peer is now banned, we should disconnect from them
+        mock.ban_peer(other_peer_id);
+        mock.trigger_connectivity_check().await;
+        mock.expect_disconnect_success(other_peer_id, other_addr.clone())
+            .await;
+
+        // We shouldn't dial a banned peer
+        mock.trigger_connectivity_check().await;
+        assert_eq!(0, mock.get_dial_queue_size().await);
+
+        // Once the ban is lifted, we should dial the peer again
+        let peer = PeerNetworkId::new(NetworkId::Vfn, other_peer_id);
+        assert!(mock.peer_metadata_storage.reputation().clear_ban(peer));
+        mock.trigger_connectivity_check().await;
+        mock.trigger_pending_dials().await;
+        mock.expect_one_dial_success(other_peer_id, other_addr)
+            .await;
+    };
+    block_on(future::join(conn_mgr.start(), test));
+}
+
 // Tests that connectivity manager retries dials and disconnects on failure.
 #[test]
 fn retry_on_failure() {
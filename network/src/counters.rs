@@ -1,8 +1,8 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::protocols::wire::handshake::v1::ProtocolId;
-use diem_config::network_id::NetworkContext;
+use crate::{application::reputation::Misbehavior, protocols::wire::handshake::v1::ProtocolId};
+use diem_config::network_id::{NetworkContext, NetworkId};
 use diem_metrics::{
     register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
     Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
@@ -303,6 +303,29 @@ pub fn direct_send_bytes(
     ])
 }
 
+pub static DIEM_NETWORK_OUTBOUND_PROTOCOL_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "diem_network_outbound_protocol_bytes",
+        "Number of outbound message bytes by protocol",
+        &["role_type", "network_id", "peer_id", "protocol_id", "state"]
+    )
+    .unwrap()
+});
+
+pub fn outbound_protocol_bytes(
+    network_context: &NetworkContext,
+    protocol_id: ProtocolId,
+    state_label: &'static str,
+) -> IntCounter {
+    DIEM_NETWORK_OUTBOUND_PROTOCOL_BYTES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        protocol_id.as_str(),
+        state_label,
+    ])
+}
+
 /// Counters(queued,dequeued,dropped) related to inbound network notifications for RPCs and
 /// DirectSends.
 pub static PENDING_NETWORK_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -474,3 +497,29 @@ pub static NETWORK_RATE_LIMIT_METRICS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static DIEM_NETWORK_PEER_MISBEHAVIOR: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "diem_network_peer_misbehavior",
+        "Number of peer misbehavior reports from applications",
+        &["network_id", "misbehavior"]
+    )
+    .unwrap()
+});
+
+pub fn peer_misbehavior(network_id: NetworkId, misbehavior: Misbehavior) -> IntCounter {
+    DIEM_NETWORK_PEER_MISBEHAVIOR.with_label_values(&[network_id.as_str(), misbehavior.as_str()])
+}
+
+pub static DIEM_NETWORK_PEER_BANS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "diem_network_peer_bans",
+        "Number of peers banned for misbehavior",
+        &["network_id"]
+    )
+    .unwrap()
+});
+
+pub fn peer_bans(network_id: NetworkId) -> IntCounter {
+    DIEM_NETWORK_PEER_BANS.with_label_values(&[network_id.as_str()])
+}
@@ -210,10 +210,33 @@ impl NoiseUpgrader {
     /// Noise handshake payload. Currently this counter is always a millisecond-
     /// granularity unix epoch timestamp.
     pub async fn upgrade_outbound<TSocket, F>(
+        &self,
+        socket: TSocket,
+        remote_public_key: x25519::PublicKey,
+        time_provider: F,
+    ) -> Result<NoiseStream<TSocket>, NoiseHandshakeError>
+    where
+        TSocket: AsyncRead + AsyncWrite + Debug + Unpin,
+        F: Fn() -> [u8; AntiReplayTimestamps::TIMESTAMP_SIZE],
+    {
+        self.upgrade_outbound_with_channel_binding(socket, remote_public_key, time_provider, &[])
+            .await
+    }
+
+    /// Perform an outbound protocol upgrade on this connection, binding the
+    /// handshake to the underlying secure channel.
+    ///
+    /// The `channel_binding` is appended to the Noise prologue but never sent,
+    /// so the handshake only succeeds if the server upgrades with the same
+    /// `channel_binding` (see [`upgrade_inbound_with_channel_binding`]).
+    ///
+    /// [`upgrade_inbound_with_channel_binding`]: Self::upgrade_inbound_with_channel_binding
+    pub async fn upgrade_outbound_with_channel_binding<TSocket, F>(
         &self,
         mut socket: TSocket,
         remote_public_key: x25519::PublicKey,
         time_provider: F,
+        channel_binding: &[u8],
     ) -> Result<NoiseStream<TSocket>, NoiseHandshakeError>
     where
         TSocket: AsyncRead + AsyncWrite + Debug + Unpin,
@@ -228,6 +251,7 @@ impl NoiseUpgrader {
             .copy_from_slice(remote_public_key.as_slice());
 
         let (prologue_msg, mut client_noise_msg) = client_message.split_at_mut(Self::PROLOGUE_SIZE);
+        let prologue = [&*prologue_msg, channel_binding].concat();
 
         // craft 8-byte payload as current timestamp (in milliseconds)
         let payload = time_provider();
@@ -238,7 +262,7 @@ impl NoiseUpgrader {
             .noise_config
             .initiate_connection(
                 &mut rng,
-                prologue_msg,
+                &prologue,
                 remote_public_key,
                 Some(&payload),
                 &mut client_noise_msg,
@@ -296,8 +320,26 @@ impl NoiseUpgrader {
     /// In addition, we will expect the client to include an anti replay attack
     /// counter in the Noise handshake payload in mutual auth scenarios.
     pub async fn upgrade_inbound<TSocket>(
+        &self,
+        socket: TSocket,
+    ) -> Result<(NoiseStream<TSocket>, PeerId, PeerRole), NoiseHandshakeError>
+    where
+        TSocket: AsyncRead + AsyncWrite + Debug + Unpin,
+    {
+        self.upgrade_inbound_with_channel_binding(socket, &[]).await
+    }
+
+    /// Perform an inbound protocol upgrade on this connection, binding the
+    /// handshake to the underlying secure channel.
+    ///
+    /// The handshake fails if the client did not upgrade with the same
+    /// `channel_binding` (see [`upgrade_outbound_with_channel_binding`]).
+    ///
+    /// [`upgrade_outbound_with_channel_binding`]: Self::upgrade_outbound_with_channel_binding
+    pub async fn upgrade_inbound_with_channel_binding<TSocket>(
         &self,
         mut socket: TSocket,
+        channel_binding: &[u8],
     ) -> Result<(NoiseStream<TSocket>, PeerId, PeerRole), NoiseHandshakeError>
     where
         TSocket: AsyncRead + AsyncWrite + Debug + Unpin,
@@ -341,9 +383,10 @@ impl NoiseUpgrader {
 
         // parse it
         let (prologue, client_init_message) = client_message.split_at(Self::PROLOGUE_SIZE);
+        let prologue = [prologue, channel_binding].concat();
         let (remote_public_key, handshake_state, payload) = self
             .noise_config
-            .parse_client_init_message(prologue, client_init_message)
+            .parse_client_init_message(&prologue, client_init_message)
             .map_err(|err| NoiseHandshakeError::ServerParseClient(remote_peer_short, err))?;
 
         // if mutual auth mode, verify the remote pubkey is in our set of trusted peers
@@ -649,6 +692,39 @@ mod test {
         test_handshake_self_fails(true /* is_mutual_auth */);
     }
 
+    #[test]
+    fn test_handshake_channel_binding() {
+        let ((client, _), (server, server_public_key)) =
+            build_peers(false /* is_mutual_auth */);
+        let handshake = |client_binding: &'static [u8], server_binding: &'static [u8]| {
+            let (dialer_socket, listener_socket) = MemorySocket::new_pair();
+            block_on(join(
+                client.upgrade_outbound_with_channel_binding(
+                    dialer_socket,
+                    server_public_key,
+                    AntiReplayTimestamps::now,
+                    client_binding,
+                ),
+                server.upgrade_inbound_with_channel_binding(listener_socket, server_binding),
+            ))
+        };
+
+        // the same binding on both sides, it should work
+        let (client_res, server_res) = handshake(b"channel", b"channel");
+        client_res.unwrap();
+        server_res.unwrap();
+
+        // a different binding, as seen through a man in the middle, it should fail
+        let (client_res, server_res) = handshake(b"channel", b"other channel");
+        client_res.unwrap_err();
+        server_res.unwrap_err();
+
+        // a binding on one side only, it should fail
+        let (client_res, server_res) = handshake(b"", b"channel");
+        client_res.unwrap_err();
+        server_res.unwrap_err();
+    }
+
     #[test]
     fn test_handshake_unauthed_keypair_fails_mutual_auth() {
         let mut rng = ::rand::rngs::StdRng::from_seed(TEST_SEED_2);
@@ -85,7 +85,11 @@ pub fn fuzz(data: &[u8]) {
         ProtocolIdSet::all_known(),
         PeerRole::Unknown,
     );
-    let connection = Connection { socket, metadata };
+    let connection = Connection {
+        socket,
+        metadata,
+        substreams: None,
+    };
 
     let (connection_notifs_tx, connection_notifs_rx) = channel::new_test(8);
     let channel_size = 8;
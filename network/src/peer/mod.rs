@@ -12,13 +12,26 @@
 //! [`Peer`] owns the actual underlying connection socket and is reponsible for
 //! the socket's shutdown, graceful or otherwise.
 //!
+//! Outbound messages are not written in the order they're sent, but by the
+//! priority, weight and bandwidth quota of their [`ProtocolId`] (see
+//! [`ProtocolPriority`]), so e.g. consensus messages aren't stuck behind a
+//! backlog of state-sync chunks.
+//!
+//! If the connection also provides substreams (e.g. QUIC), outbound rpc requests
+//! and direct-send messages are written on a separate substream per [`ProtocolId`],
+//! so that a large or slow message for one protocol does not head-of-line block
+//! the others. Rpc responses and error messages still use the primary socket.
+//! Messages written on a substream are not scheduled, and are dropped if their
+//! substream's queue is full.
+//!
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
-    counters::{self, RECEIVED_LABEL, SENT_LABEL},
+    counters::{self, DECLINED_LABEL, RECEIVED_LABEL, SENT_LABEL},
     logging::NetworkSchema,
     peer_manager::{PeerManagerError, TransportNotification},
     protocols::{
+        compression,
         direct_send::Message,
         rpc::{InboundRpcRequest, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
         wire::messaging::v1::{
@@ -39,24 +52,46 @@ use diem_types::PeerId;
 use futures::{
     self,
     channel::oneshot,
+    future,
     io::{AsyncRead, AsyncWrite},
-    stream::StreamExt,
+    stream::{self, SelectAll, StreamExt},
     FutureExt, SinkExt, TryFutureExt,
 };
+use netcore::transport::quic::{RecvStream, SubstreamOpener, Substreams};
 use serde::Serialize;
 use short_hex_str::AsShortHexStr;
-use std::{fmt, panic, time::Duration};
+use std::{collections::HashMap, fmt, panic, time::Duration};
 use tokio::runtime::Handle;
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
 
+mod scheduler;
 #[cfg(test)]
 mod test;
 
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing;
 
+use scheduler::OutboundScheduler;
+pub use scheduler::ProtocolPriority;
+
+/// The size of the queue of outbound messages handed to the writer task.
+const WRITE_QUEUE_SIZE: usize = 1024;
+
+/// The maximum number of outbound messages queued in the writer task per
+/// protocol. Once a protocol's queue is full, the writer task holds on to one
+/// more of that protocol's messages until there is room again, and drops the
+/// messages after it. Messages of other protocols are still taken meanwhile.
+const MAX_QUEUED_MESSAGES_PER_PROTOCOL: usize = 1024;
+
+type AckSender = oneshot::Sender<Result<(), PeerManagerError>>;
+
+/// An outbound message for the writer task, along with the protocol it's
+/// scheduled under (`None` for error messages) and a channel notified once it's
+/// written.
+pub(crate) type WriteRequest = (Option<ProtocolId>, NetworkMessage, AckSender);
+
 /// Requests [`Peer`] receives from the [`PeerManager`](crate::peer_manager::PeerManager).
 #[derive(Debug)]
 pub enum PeerRequest {
@@ -102,6 +137,34 @@ enum State {
     ShuttingDown(DisconnectReason),
 }
 
+/// Everything needed to start writing outbound messages on substreams.
+#[derive(Clone)]
+struct SubstreamWriterConfig {
+    opener: SubstreamOpener,
+    max_frame_size: usize,
+    rate_limiter: Option<SharedBucket>,
+}
+
+/// Returns the protocol whose substream `message` should be written on, or
+/// `None` if it should be written on the primary socket.
+fn substream_protocol(message: &NetworkMessage) -> Option<ProtocolId> {
+    match message {
+        NetworkMessage::RpcRequest(request) => Some(request.protocol_id),
+        NetworkMessage::DirectSendMsg(message) => Some(message.protocol_id),
+        NetworkMessage::RpcResponse(_) | NetworkMessage::Error(_) => None,
+    }
+}
+
+/// The size of `message`'s payload, used for scheduling and metrics.
+fn payload_len(message: &NetworkMessage) -> usize {
+    match message {
+        NetworkMessage::RpcRequest(request) => request.raw_request.len(),
+        NetworkMessage::RpcResponse(response) => response.raw_response.len(),
+        NetworkMessage::DirectSendMsg(message) => message.raw_msg.len(),
+        NetworkMessage::Error(_) => 0,
+    }
+}
+
 /// The `Peer` actor manages a single connection to another remote peer after
 /// the initial connection establishment and handshake.
 pub struct Peer<TSocket> {
@@ -115,6 +178,8 @@ pub struct Peer<TSocket> {
     connection_metadata: ConnectionMetadata,
     /// Underlying connection.
     connection: Option<TSocket>,
+    /// Additional streams of the underlying connection, if supported.
+    substreams: Option<Substreams>,
     /// Channel to notify PeerManager that we've disconnected.
     connection_notifs_tx: channel::Sender<TransportNotification<TSocket>>,
     /// Channel to receive requests from PeerManager to send messages and rpcs.
@@ -158,6 +223,7 @@ where
         let Connection {
             metadata: connection_metadata,
             socket,
+            substreams,
         } = connection;
         let remote_peer_id = connection_metadata.remote_peer_id;
         Self {
@@ -166,6 +232,7 @@ where
             time_service: time_service.clone(),
             connection_metadata,
             connection: Some(socket),
+            substreams,
             connection_notifs_tx,
             peer_reqs_rx,
             peer_notifs_tx,
@@ -175,12 +242,14 @@ where
                 remote_peer_id,
                 inbound_rpc_timeout,
                 max_concurrent_inbound_rpcs,
+                max_frame_size,
             ),
             outbound_rpcs: OutboundRpcs::new(
                 network_context,
                 time_service,
                 remote_peer_id,
                 max_concurrent_outbound_rpcs,
+                max_frame_size,
             ),
             state: State::Connected,
             max_frame_size,
@@ -219,6 +288,18 @@ where
             self.outbound_rate_limiter.clone(),
         );
 
+        // If the connection supports substreams, split them into a handle for
+        // opening our own outbound substreams and a stream of the remote's.
+        let (substream_opener, incoming_substreams) = match self.substreams.take() {
+            Some(substreams) => {
+                let (opener, incoming) = substreams.split();
+                (Some(opener), Some(incoming))
+            }
+            None => (None, None),
+        };
+        let mut incoming_substreams = stream::iter(incoming_substreams).flatten().fuse();
+        let mut substream_readers: SelectAll<NetworkMessageStream<RecvStream>> = SelectAll::new();
+
         // Start writer "process" as a separate task. We receive two handles to
         // communicate with the task:
         //   1. `write_reqs_tx`: Queue of pending NetworkMessages to write.
@@ -229,6 +310,11 @@ where
             self.connection_metadata.clone(),
             self.network_context,
             writer,
+            substream_opener.map(|opener| SubstreamWriterConfig {
+                opener,
+                max_frame_size: self.max_frame_size,
+                rate_limiter: self.outbound_rate_limiter.clone(),
+            }),
         );
 
         // Start main Peer event loop.
@@ -268,6 +354,42 @@ where
                         None => self.shutdown(DisconnectReason::ConnectionLost),
                     }
                 },
+                // Start reading from a new substream opened by the remote peer.
+                maybe_substream = incoming_substreams.select_next_some() => {
+                    match maybe_substream {
+                        Ok(substream) => substream_readers.push(NetworkMessageStream::new(
+                            substream,
+                            self.max_frame_size,
+                            self.inbound_rate_limiter.clone(),
+                        )),
+                        // The connection is gone; reading from the socket will
+                        // notice and shut us down.
+                        Err(err) => debug!(
+                            NetworkSchema::new(&self.network_context)
+                                .connection_metadata(&self.connection_metadata),
+                            error = %err,
+                            "{} Error accepting substream from peer: {}, error: {}",
+                            self.network_context,
+                            remote_peer_id.short_str(),
+                            err
+                        ),
+                    }
+                },
+                // Handle a new inbound NetworkMessage read from one of the
+                // remote peer's substreams.
+                message = substream_readers.select_next_some() => {
+                    if let Err(err) = self.handle_inbound_message(message, &mut write_reqs_tx).await {
+                        warn!(
+                            NetworkSchema::new(&self.network_context)
+                                .connection_metadata(&self.connection_metadata),
+                            error = %err,
+                            "{} Error in handling inbound message from peer: {}, error: {}",
+                            self.network_context,
+                            remote_peer_id.short_str(),
+                            err
+                        );
+                    }
+                },
                 // Drive the queue of pending inbound rpcs. When one is fulfilled
                 // by an upstream protocol, send the response to the remote peer.
                 maybe_response = self.inbound_rpcs.next_completed_response() => {
@@ -299,54 +421,178 @@ where
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
     // them and immediately closes the connection.
+    // If `substreams` is given, messages for a specific protocol are handed off to that
+    // protocol's substream writer task instead of being written on the socket directly.
+    // Messages written on the socket are scheduled by their protocol's priority and weight.
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
         connection_metadata: ConnectionMetadata,
         network_context: NetworkContext,
         mut writer: NetworkMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
-    ) -> (
-        channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
-        oneshot::Sender<()>,
-    ) {
+        substreams: Option<SubstreamWriterConfig>,
+    ) -> (channel::Sender<WriteRequest>, oneshot::Sender<()>) {
         let remote_peer_id = connection_metadata.remote_peer_id;
-        let (write_reqs_tx, mut write_reqs_rx): (
-            channel::Sender<(
-                NetworkMessage,
-                oneshot::Sender<Result<(), PeerManagerError>>,
-            )>,
-            _,
-        ) = channel::new(1024, &counters::PENDING_WIRE_MESSAGES);
+        let (write_reqs_tx, mut write_reqs_rx): (channel::Sender<WriteRequest>, _) =
+            channel::new(WRITE_QUEUE_SIZE, &counters::PENDING_WIRE_MESSAGES);
         let (close_tx, close_rx) = oneshot::channel();
+        let substream_executor = executor.clone();
         let writer_task = async move {
             let mut close_rx = close_rx.into_stream();
-            loop {
-                futures::select! {
-                    (message, ack_ch) = write_reqs_rx.select_next_some() => {
-                        if let Err(err) = writer
-                            .send(&message)
-                            .map_ok(|_| ack_ch.send(Ok(())))
-                            .await
-                        {
-                            warn!(
-                                NetworkSchema::new(&network_context)
-                                    .connection_metadata(&connection_metadata),
-                                error = %err,
-                                "{} Error in sending message to peer: {}, error: {}",
-                                network_context,
-                                remote_peer_id.short_str(),
-                                err
-                            );
-                            break;
+            let mut substream_writers = HashMap::new();
+            let mut scheduler = OutboundScheduler::new(MAX_QUEUED_MESSAGES_PER_PROTOCOL);
+            // The requests whose protocol queue was full, at most one per protocol. New requests
+            // keep being read, so that a full protocol doesn't hold back the others, and the
+            // requests of a protocol with a parked request are dropped.
+            let mut parked_requests: HashMap<ProtocolId, (usize, _)> = HashMap::new();
+            let mut next_request = None;
+            'writer: loop {
+                for (protocol_id, (message_len, request)) in std::mem::take(&mut parked_requests) {
+                    if let Err(request) =
+                        scheduler.push(Some(protocol_id), message_len, request, time_service.now())
+                    {
+                        parked_requests.insert(protocol_id, (message_len, request));
+                    }
+                }
+
+                // Queue up all requests that are already waiting, so that we write the most
+                // important message next rather than the oldest one. This is bounded by the
+                // channel size, so that a busy channel can't starve the socket.
+                let mut num_queued = 0;
+                while num_queued < WRITE_QUEUE_SIZE {
+                    let (protocol_id, message, ack_ch) = match next_request
+                        .take()
+                        .or_else(|| write_reqs_rx.next().now_or_never().flatten())
+                    {
+                        Some(request) => request,
+                        None => break,
+                    };
+                    num_queued += 1;
+
+                    let substream = substreams
+                        .as_ref()
+                        .and_then(|config| Some((config, substream_protocol(&message)?)));
+                    if let Some((config, protocol_id)) = substream {
+                        let substream_tx =
+                            substream_writers.entry(protocol_id).or_insert_with(|| {
+                                Self::start_substream_writer_task(
+                                    &substream_executor,
+                                    time_service.clone(),
+                                    connection_metadata.clone(),
+                                    network_context,
+                                    config.clone(),
+                                    protocol_id,
+                                )
+                            });
+                        // Waiting for a slow substream would hold back the other protocols,
+                        // so a message that doesn't fit in its channel is dropped.
+                        let message_len = payload_len(&message);
+                        match substream_tx.try_send((message, ack_ch)) {
+                            Ok(()) => (),
+                            Err(err) if err.is_full() => {
+                                counters::outbound_protocol_bytes(
+                                    &network_context,
+                                    protocol_id,
+                                    DECLINED_LABEL,
+                                )
+                                .inc_by(message_len as u64);
+                            }
+                            // The substream writer only stops on a write error,
+                            // so treat this like failing to write on the socket.
+                            Err(_) => {
+                                warn!(
+                                    NetworkSchema::new(&network_context)
+                                        .connection_metadata(&connection_metadata),
+                                    protocol_id = protocol_id,
+                                    "{} Substream writer for protocol {} to peer: {} terminated",
+                                    network_context,
+                                    protocol_id,
+                                    remote_peer_id.short_str(),
+                                );
+                                break 'writer;
+                            }
                         }
-                    },
-                    _ = close_rx.select_next_some() => {
+                    } else {
+                        let message_len = payload_len(&message);
+                        let protocol_id = match protocol_id {
+                            Some(protocol_id) if parked_requests.contains_key(&protocol_id) => {
+                                counters::outbound_protocol_bytes(
+                                    &network_context,
+                                    protocol_id,
+                                    DECLINED_LABEL,
+                                )
+                                .inc_by(message_len as u64);
+                                continue;
+                            }
+                            protocol_id => protocol_id,
+                        };
+                        if let Err(request) = scheduler.push(
+                            protocol_id,
+                            message_len,
+                            (message, ack_ch),
+                            time_service.now(),
+                        ) {
+                            // Only the queues of protocols have a size limit.
+                            if let Some(protocol_id) = protocol_id {
+                                parked_requests.insert(protocol_id, (message_len, request));
+                            }
+                        }
+                    }
+                }
+
+                if let Some((protocol_id, (message, ack_ch))) = scheduler.pop(time_service.now()) {
+                    if close_rx.next().now_or_never().is_some() {
                         break;
                     }
+                    let message_len = payload_len(&message);
+                    if let Err(err) = writer.send(&message).map_ok(|_| ack_ch.send(Ok(()))).await {
+                        warn!(
+                            NetworkSchema::new(&network_context)
+                                .connection_metadata(&connection_metadata),
+                            error = %err,
+                            "{} Error in sending message to peer: {}, error: {}",
+                            network_context,
+                            remote_peer_id.short_str(),
+                            err
+                        );
+                        break;
+                    }
+                    if let Some(protocol_id) = protocol_id {
+                        counters::outbound_protocol_bytes(
+                            &network_context,
+                            protocol_id,
+                            SENT_LABEL,
+                        )
+                        .inc_by(message_len as u64);
+                    }
+                    continue;
                 }
+
+                // Nothing can be written right now. Wait for a new request, or for a throttled
+                // protocol to be allowed to write again.
+                let quota_refill = match scheduler.available_at() {
+                    Some(available_at) => time_service
+                        .sleep(available_at.saturating_duration_since(time_service.now()))
+                        .left_future(),
+                    None => future::pending().right_future(),
+                };
+                futures::select! {
+                    request = write_reqs_rx.next().fuse() => match request {
+                        Some(request) => next_request = Some(request),
+                        None => break,
+                    },
+                    _ = quota_refill.fuse() => (),
+                    _ = close_rx.select_next_some() => break,
+                }
+            }
+            if !scheduler.is_empty() || !parked_requests.is_empty() {
+                warn!(
+                    NetworkSchema::new(&network_context).connection_metadata(&connection_metadata),
+                    "{} Dropping {} queued messages to peer: {}",
+                    network_context,
+                    scheduler.len() + parked_requests.len(),
+                    remote_peer_id.short_str()
+                );
             }
             info!(
                 NetworkSchema::new(&network_context).connection_metadata(&connection_metadata),
@@ -398,13 +644,71 @@ where
         (write_reqs_tx, close_tx)
     }
 
+    // Start a new task on the given executor which opens a substream and writes all outbound
+    // messages of a single protocol on it. The task finishes the substream once the returned
+    // channel is dropped, and terminates early if opening or writing to the substream fails.
+    fn start_substream_writer_task(
+        executor: &Handle,
+        time_service: TimeService,
+        connection_metadata: ConnectionMetadata,
+        network_context: NetworkContext,
+        config: SubstreamWriterConfig,
+        protocol_id: ProtocolId,
+    ) -> channel::Sender<(NetworkMessage, AckSender)> {
+        let remote_peer_id = connection_metadata.remote_peer_id;
+        let (write_reqs_tx, mut write_reqs_rx): (channel::Sender<(NetworkMessage, AckSender)>, _) =
+            channel::new(WRITE_QUEUE_SIZE, &counters::PENDING_WIRE_MESSAGES);
+        let writer_task = async move {
+            let substream = match config.opener.open().await {
+                Ok(substream) => substream,
+                Err(err) => {
+                    warn!(
+                        NetworkSchema::new(&network_context)
+                            .connection_metadata(&connection_metadata),
+                        error = %err,
+                        protocol_id = protocol_id,
+                        "{} Error in opening substream for protocol {} to peer: {}, error: {}",
+                        network_context,
+                        protocol_id,
+                        remote_peer_id.short_str(),
+                        err
+                    );
+                    return;
+                }
+            };
+            let mut writer =
+                NetworkMessageSink::new(substream, config.max_frame_size, config.rate_limiter);
+            while let Some((message, ack_ch)) = write_reqs_rx.next().await {
+                let message_len = payload_len(&message);
+                if let Err(err) = writer.send(&message).map_ok(|_| ack_ch.send(Ok(()))).await {
+                    warn!(
+                        NetworkSchema::new(&network_context)
+                            .connection_metadata(&connection_metadata),
+                        error = %err,
+                        protocol_id = protocol_id,
+                        "{} Error in sending message for protocol {} to peer: {}, error: {}",
+                        network_context,
+                        protocol_id,
+                        remote_peer_id.short_str(),
+                        err
+                    );
+                    return;
+                }
+                counters::outbound_protocol_bytes(&network_context, protocol_id, SENT_LABEL)
+                    .inc_by(message_len as u64);
+            }
+            let _ = time_service
+                .timeout(transport::TRANSPORT_TIMEOUT, writer.close())
+                .await;
+        };
+        executor.spawn(writer_task);
+        write_reqs_tx
+    }
+
     async fn handle_inbound_message(
         &mut self,
         message: Result<NetworkMessage, ReadError>,
-        write_reqs_tx: &mut channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
+        write_reqs_tx: &mut channel::Sender<WriteRequest>,
     ) -> Result<(), PeerManagerError> {
         trace!(
             NetworkSchema::new(&self.network_context)
@@ -427,7 +731,7 @@ where
                     let message = NetworkMessage::Error(error_code);
 
                     let (ack_tx, _) = oneshot::channel();
-                    write_reqs_tx.send((message, ack_tx)).await?;
+                    write_reqs_tx.send((None, message, ack_tx)).await?;
                     return Err(err.into());
                 }
                 ReadError::IoError(_) => {
@@ -494,6 +798,22 @@ where
         counters::direct_send_bytes(&self.network_context, RECEIVED_LABEL)
             .inc_by(data.len() as u64);
 
+        // Drop compressed messages that would decompress to more than a frame.
+        if protocol_id.is_compressed() {
+            if let Err(err) = compression::check_decompressed_size(&data, self.max_frame_size) {
+                counters::direct_send_messages(&self.network_context, DECLINED_LABEL).inc();
+                warn!(
+                    NetworkSchema::new(&self.network_context).remote_peer(&peer_id),
+                    error = %err,
+                    "{} Dropping inbound DirectSend message from peer {}: {}",
+                    self.network_context,
+                    peer_id.short_str(),
+                    err
+                );
+                return;
+            }
+        }
+
         let notif = PeerNotification::RecvMessage(Message {
             protocol_id,
             mdata: Bytes::from(data),
@@ -513,10 +833,7 @@ where
     async fn handle_outbound_request(
         &mut self,
         request: PeerRequest,
-        write_reqs_tx: &mut channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
+        write_reqs_tx: &mut channel::Sender<WriteRequest>,
     ) {
         trace!(
             "Peer {} PeerRequest::{:?}",
@@ -536,7 +853,10 @@ where
                 });
                 let (ack_tx, _ack_rx) = oneshot::channel();
 
-                match write_reqs_tx.send((message, ack_tx)).await {
+                match write_reqs_tx
+                    .send((Some(protocol_id), message, ack_tx))
+                    .await
+                {
                     Ok(_) => {
                         counters::direct_send_messages(&self.network_context, SENT_LABEL).inc();
                         counters::direct_send_bytes(&self.network_context, SENT_LABEL)
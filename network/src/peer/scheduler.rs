@@ -0,0 +1,432 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scheduling of outbound messages on a single connection.
+//!
+//! Every [`ProtocolId`] is assigned a [`ProtocolPriority`]. Messages of a lower
+//! `priority` value are always written before messages of a higher one, e.g.,
+//! consensus messages preempt any queued state-sync or mempool traffic. Within
+//! the same priority, protocols share the connection by their `weight`, using a
+//! deficit round robin over message bytes, so a protocol sending large messages
+//! can't starve one sending small messages.
+//!
+//! A protocol may also have a `quota`, capping the bytes per second it writes
+//! on the connection even when nothing else is queued. A message is written as
+//! long as the protocol isn't already over its quota, so messages larger than
+//! the quota still get through, after which the protocol waits until it has
+//! paid off the excess.
+//!
+//! Messages without a protocol (e.g., error messages) are always written first.
+//!
+//! Scheduling happens per message: a large message being written still delays
+//! everything queued behind it until it's done.
+
+use crate::protocols::wire::handshake::v1::ProtocolId;
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+
+/// The number of bytes a protocol of weight 1 may write per round.
+const QUANTUM_BYTES: u64 = 64 * 1024;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The bytes per second state sync may write on a single connection.
+const STATE_SYNC_QUOTA_BYTES: u64 = 8 * 1024 * 1024;
+
+/// How a protocol's outbound messages are scheduled relative to other protocols.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProtocolPriority {
+    /// Lower values are written first.
+    pub priority: u8,
+    /// Share of bytes written relative to protocols of the same priority.
+    pub weight: u32,
+    /// Maximum bytes per second written, if any.
+    pub quota: Option<u64>,
+}
+
+impl ProtocolPriority {
+    pub const fn new(priority: u8, weight: u32) -> Self {
+        Self {
+            priority,
+            weight,
+            quota: None,
+        }
+    }
+
+    pub const fn with_quota(mut self, bytes_per_sec: u64) -> Self {
+        self.quota = Some(bytes_per_sec);
+        self
+    }
+
+    pub fn for_protocol(protocol_id: ProtocolId) -> Self {
+        use ProtocolId::*;
+        match protocol_id {
+            ConsensusRpcBcs
+            | ConsensusDirectSendBcs
+            | ConsensusDirectSendJson
+            | ConsensusRpcJson => Self::new(0, 1),
+            // Health checks must get through a busy connection, or we'd disconnect the peer
+            HealthCheckerRpc => Self::new(0, 1),
+            MempoolDirectSend | MempoolDirectSendCompressed | MempoolRpc => Self::new(1, 2),
+            // Keep a lagging peer syncing from us from using up all of our upload bandwidth
+            StateSyncDirectSend | StorageServiceRpc | StorageServiceRpcCompressed => {
+                Self::new(1, 1).with_quota(STATE_SYNC_QUOTA_BYTES)
+            }
+            DiscoveryDirectSend | PeerExchangeRpc => Self::new(1, 1),
+        }
+    }
+}
+
+/// A token bucket of bytes, refilled at `bytes_per_sec` up to one second's worth.
+/// Writing a message may overdraw it, in which case the protocol is throttled
+/// until it's positive again.
+#[derive(Debug)]
+struct Quota {
+    bytes_per_sec: i64,
+    available: i64,
+    last_refill: Instant,
+}
+
+impl Quota {
+    fn new(bytes_per_sec: u64, now: Instant) -> Self {
+        let bytes_per_sec = i64::try_from(bytes_per_sec).unwrap_or(i64::MAX).max(1);
+        Self {
+            bytes_per_sec,
+            available: bytes_per_sec,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refill = elapsed.as_nanos() * self.bytes_per_sec as u128 / NANOS_PER_SEC;
+        if refill == 0 {
+            return;
+        }
+        let available = self
+            .available
+            .saturating_add(i64::try_from(refill).unwrap_or(i64::MAX));
+        if available >= self.bytes_per_sec {
+            self.available = self.bytes_per_sec;
+            self.last_refill = now;
+        } else {
+            // Only account for the time the refilled bytes took, so fractions of a
+            // byte aren't lost between refills
+            self.available = available;
+            let nanos = refill * NANOS_PER_SEC / self.bytes_per_sec as u128;
+            self.last_refill += Duration::from_nanos(nanos as u64);
+        }
+    }
+
+    fn is_throttled(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.available <= 0
+    }
+
+    fn charge(&mut self, size: usize) {
+        let size = i64::try_from(size).unwrap_or(i64::MAX);
+        self.available = self.available.saturating_sub(size);
+    }
+
+    /// The earliest time the quota is no longer throttled.
+    fn available_at(&self) -> Instant {
+        let needed = self.available.unsigned_abs() as u128 + 1;
+        let bytes_per_sec = self.bytes_per_sec as u128;
+        let nanos = (needed * NANOS_PER_SEC + bytes_per_sec - 1) / bytes_per_sec;
+        self.last_refill + Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+}
+
+#[derive(Debug)]
+struct ProtocolQueue<T> {
+    weight: u32,
+    quota: Option<Quota>,
+    /// Bytes this protocol may still write before yielding to the next one
+    deficit: u64,
+    messages: VecDeque<(usize, T)>,
+}
+
+/// All protocols of the same priority.
+#[derive(Debug)]
+struct PriorityClass<T> {
+    queues: HashMap<ProtocolId, ProtocolQueue<T>>,
+    /// Protocols with queued messages, in round robin order
+    active: VecDeque<ProtocolId>,
+}
+
+impl<T> Default for PriorityClass<T> {
+    fn default() -> Self {
+        Self {
+            queues: HashMap::new(),
+            active: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> ProtocolQueue<T> {
+    fn is_throttled(&mut self, now: Instant) -> bool {
+        self.quota
+            .as_mut()
+            .map_or(false, |quota| quota.is_throttled(now))
+    }
+}
+
+impl<T> PriorityClass<T> {
+    fn pop(&mut self, now: Instant) -> Option<(ProtocolId, T)> {
+        let queues = &mut self.queues;
+        if self
+            .active
+            .iter()
+            .all(|protocol_id| queues.get_mut(protocol_id).unwrap().is_throttled(now))
+        {
+            return None;
+        }
+
+        loop {
+            let protocol_id = *self.active.front()?;
+            let queue = self
+                .queues
+                .get_mut(&protocol_id)
+                .expect("Active protocols must have a queue");
+            // Protocols over their quota sit out until it's refilled
+            if queue.is_throttled(now) {
+                self.active.rotate_left(1);
+                continue;
+            }
+            let (size, _) = queue
+                .messages
+                .front()
+                .expect("Active queues can't be empty");
+            if queue.deficit < *size as u64 {
+                queue.deficit += QUANTUM_BYTES * u64::from(queue.weight.max(1));
+                self.active.rotate_left(1);
+                continue;
+            }
+
+            let (size, message) = queue.messages.pop_front().unwrap();
+            queue.deficit -= size as u64;
+            if let Some(quota) = queue.quota.as_mut() {
+                quota.charge(size);
+            }
+            if queue.messages.is_empty() {
+                // Idle protocols don't get to save up their deficit
+                queue.deficit = 0;
+                self.active.pop_front();
+            }
+            return Some((protocol_id, message));
+        }
+    }
+
+    /// The earliest time a throttled protocol with queued messages may write again.
+    fn available_at(&self) -> Option<Instant> {
+        self.active
+            .iter()
+            .filter_map(|protocol_id| self.queues[protocol_id].quota.as_ref())
+            .filter(|quota| quota.available <= 0)
+            .map(Quota::available_at)
+            .min()
+    }
+}
+
+/// A queue of outbound messages, popped in order of their protocol's priority.
+#[derive(Debug)]
+pub struct OutboundScheduler<T> {
+    unprioritized: VecDeque<T>,
+    classes: BTreeMap<u8, PriorityClass<T>>,
+    max_queue_size_per_protocol: usize,
+    len: usize,
+}
+
+impl<T> OutboundScheduler<T> {
+    pub fn new(max_queue_size_per_protocol: usize) -> Self {
+        Self {
+            unprioritized: VecDeque::new(),
+            classes: BTreeMap::new(),
+            max_queue_size_per_protocol,
+            len: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Queues a `message` of `size` bytes. If the protocol's queue is full,
+    /// the message is handed back.
+    pub fn push(
+        &mut self,
+        protocol_id: Option<ProtocolId>,
+        size: usize,
+        message: T,
+        now: Instant,
+    ) -> Result<(), T> {
+        let protocol_id = match protocol_id {
+            Some(protocol_id) => protocol_id,
+            None => {
+                self.unprioritized.push_back(message);
+                self.len += 1;
+                return Ok(());
+            }
+        };
+
+        let ProtocolPriority {
+            priority,
+            weight,
+            quota,
+        } = ProtocolPriority::for_protocol(protocol_id);
+        let class = self.classes.entry(priority).or_default();
+        let queue = class
+            .queues
+            .entry(protocol_id)
+            .or_insert_with(|| ProtocolQueue {
+                weight,
+                quota: quota.map(|bytes_per_sec| Quota::new(bytes_per_sec, now)),
+                deficit: 0,
+                messages: VecDeque::new(),
+            });
+        if queue.messages.len() >= self.max_queue_size_per_protocol {
+            return Err(message);
+        }
+        if queue.messages.is_empty() {
+            class.active.push_back(protocol_id);
+        }
+        queue.messages.push_back((size, message));
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the next message to write, along with its protocol. Returns
+    /// `None` if nothing is queued, or every queued protocol is over its quota.
+    pub fn pop(&mut self, now: Instant) -> Option<(Option<ProtocolId>, T)> {
+        if let Some(message) = self.unprioritized.pop_front() {
+            self.len -= 1;
+            return Some((None, message));
+        }
+        let (protocol_id, message) = self.classes.values_mut().find_map(|class| class.pop(now))?;
+        self.len -= 1;
+        Some((Some(protocol_id), message))
+    }
+
+    /// If messages are only held back by quotas, the earliest time one of them
+    /// may be written.
+    pub fn available_at(&self) -> Option<Instant> {
+        self.classes
+            .values()
+            .filter_map(PriorityClass::available_at)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn drain(scheduler: &mut OutboundScheduler<u32>, now: Instant) -> Vec<u32> {
+        std::iter::from_fn(|| scheduler.pop(now).map(|(_, message)| message)).collect()
+    }
+
+    #[test]
+    fn higher_priority_preempts() {
+        let now = Instant::now();
+        let mut scheduler = OutboundScheduler::new(10);
+        scheduler
+            .push(Some(ProtocolId::StorageServiceRpc), 1, 0, now)
+            .unwrap();
+        scheduler
+            .push(Some(ProtocolId::MempoolDirectSend), 1, 1, now)
+            .unwrap();
+        scheduler
+            .push(Some(ProtocolId::ConsensusRpcBcs), 1, 2, now)
+            .unwrap();
+        scheduler.push(None, 1, 3, now).unwrap();
+
+        assert_eq!(scheduler.pop(now), Some((None, 3)));
+        assert_eq!(
+            scheduler.pop(now),
+            Some((Some(ProtocolId::ConsensusRpcBcs), 2))
+        );
+        assert_eq!(drain(&mut scheduler, now).len(), 2);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn same_priority_shares_by_weight() {
+        let now = Instant::now();
+        let mut scheduler = OutboundScheduler::new(100);
+        // State sync queues large messages, mempool small ones
+        let large = QUANTUM_BYTES as usize;
+        let small = QUANTUM_BYTES as usize / 4;
+        for i in 0..4 {
+            scheduler
+                .push(Some(ProtocolId::StateSyncDirectSend), large, i, now)
+                .unwrap();
+        }
+        for i in 10..26 {
+            scheduler
+                .push(Some(ProtocolId::MempoolDirectSend), small, i, now)
+                .unwrap();
+        }
+
+        // Mempool has twice the weight, so gets 8 small messages per large one
+        let order = drain(&mut scheduler, now);
+        assert_eq!(&order[..9], &[0, 10, 11, 12, 13, 14, 15, 16, 17]);
+        assert_eq!(order.len(), 20);
+    }
+
+    #[test]
+    fn full_queue_rejects() {
+        let now = Instant::now();
+        let mut scheduler = OutboundScheduler::new(1);
+        scheduler
+            .push(Some(ProtocolId::MempoolRpc), 1, 0, now)
+            .unwrap();
+        assert_eq!(
+            scheduler.push(Some(ProtocolId::MempoolRpc), 1, 1, now),
+            Err(1)
+        );
+        // Other protocols are unaffected
+        scheduler
+            .push(Some(ProtocolId::ConsensusRpcBcs), 1, 2, now)
+            .unwrap();
+        assert_eq!(drain(&mut scheduler, now), vec![2, 0]);
+    }
+
+    #[test]
+    fn quota_throttles() {
+        let now = Instant::now();
+        let mut scheduler = OutboundScheduler::new(10);
+        let half_quota = STATE_SYNC_QUOTA_BYTES as usize / 2;
+        for i in 0..4 {
+            scheduler
+                .push(Some(ProtocolId::StorageServiceRpc), half_quota + 1, i, now)
+                .unwrap();
+        }
+
+        // The second message overdraws the quota, which holds back the rest
+        assert_eq!(drain(&mut scheduler, now), vec![0, 1]);
+        assert_eq!(scheduler.len(), 2);
+        let available_at = scheduler.available_at().unwrap();
+        assert!(available_at > now);
+        assert!(drain(&mut scheduler, available_at - Duration::from_nanos(1)).is_empty());
+
+        // Protocols without a quota aren't held back
+        scheduler
+            .push(Some(ProtocolId::MempoolDirectSend), half_quota, 10, now)
+            .unwrap();
+        assert_eq!(drain(&mut scheduler, now), vec![10]);
+
+        // Once the excess is paid off, the next message may overdraw it again
+        assert_eq!(drain(&mut scheduler, available_at), vec![2]);
+        let later = now + Duration::from_secs(1);
+        assert_eq!(drain(&mut scheduler, later), vec![3]);
+        assert!(scheduler.is_empty());
+        assert_eq!(scheduler.available_at(), None);
+    }
+}
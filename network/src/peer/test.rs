@@ -6,7 +6,10 @@ use crate::{
         INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE, NETWORK_CHANNEL_SIZE,
     },
-    peer::{DisconnectReason, Peer, PeerNotification, PeerRequest},
+    peer::{
+        DisconnectReason, Peer, PeerNotification, PeerRequest, ProtocolPriority,
+        MAX_QUEUED_MESSAGES_PER_PROTOCOL,
+    },
     peer_manager::TransportNotification,
     protocols::{
         direct_send::Message,
@@ -68,6 +71,7 @@ fn build_test_peer(
             PeerRole::Unknown,
         ),
         socket: a,
+        substreams: None,
     };
 
     let (connection_notifs_tx, connection_notifs_rx) = channel::new_test(1);
@@ -893,3 +897,88 @@ fn peer_terminates_when_request_tx_has_dropped() {
     };
     rt.block_on(future::join(peer.start(), drop));
 }
+
+// The writer task writes queued messages by their protocol's priority and quota. Once a protocol's
+// queue is full, it parks one more of its messages and drops the next ones, while still taking the
+// messages of other protocols.
+#[test]
+fn peer_writer_schedules_and_parks_full_protocols() {
+    ::diem_logger::Logger::init_for_testing();
+    // The writer task only runs when we yield, so messages can be queued up front
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let time_service = TimeService::mock();
+    let mock_time = time_service.clone().into_mock();
+    let (socket, connection) = MemorySocket::new_pair();
+    let (mut write_reqs_tx, _close_tx) = Peer::<MemorySocket>::start_writer_task(
+        rt.handle(),
+        time_service,
+        ConnectionMetadata::mock(PeerId::random()),
+        NetworkContext::mock(),
+        NetworkMessageSink::new(socket, MAX_FRAME_SIZE, None),
+        None,
+    );
+    let mut client_stream = NetworkMessageStream::new(connection, MAX_FRAME_SIZE, None);
+
+    let message = |protocol_id, raw_msg: Vec<u8>| {
+        NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: 0,
+            raw_msg,
+        })
+    };
+    let mut send = move |message: NetworkMessage| {
+        let protocol_id = match &message {
+            NetworkMessage::DirectSendMsg(message) => message.protocol_id,
+            _ => unreachable!(),
+        };
+        let (ack_tx, _) = oneshot::channel();
+        write_reqs_tx.try_send((Some(protocol_id), message, ack_tx))
+    };
+
+    // Together, the two large messages overdraw the state sync quota
+    let quota = ProtocolPriority::for_protocol(ProtocolId::StateSyncDirectSend)
+        .quota
+        .unwrap() as usize;
+    let large_0 = message(ProtocolId::StateSyncDirectSend, vec![0; quota / 2 + 1]);
+    let large_1 = message(ProtocolId::StateSyncDirectSend, vec![1; quota / 2 + 1]);
+    let small = |i: u32| message(ProtocolId::StateSyncDirectSend, i.to_le_bytes().to_vec());
+    let mempool = message(ProtocolId::MempoolDirectSend, vec![2]);
+    let consensus = message(ProtocolId::ConsensusDirectSendBcs, vec![3]);
+    for message in [large_0.clone(), large_1.clone()]
+        .iter()
+        .cloned()
+        .chain((0..10).map(small))
+        .chain([mempool.clone(), consensus.clone()].iter().cloned())
+    {
+        send(message).unwrap();
+    }
+
+    let test = async move {
+        for expected in [consensus.clone(), mempool, large_0, large_1].iter() {
+            assert_eq!(&client_stream.next().await.unwrap().unwrap(), expected);
+        }
+
+        // State sync is over its quota, so its queue fills up. The message after that is parked
+        // and the following ones are dropped.
+        let num_kept = MAX_QUEUED_MESSAGES_PER_PROTOCOL as u32 + 1;
+        for i in 10..num_kept + 5 {
+            send(small(i)).unwrap();
+            tokio::task::yield_now().await;
+        }
+
+        // Consensus messages are still taken and written meanwhile
+        send(consensus.clone()).unwrap();
+        assert_eq!(client_stream.next().await.unwrap().unwrap(), consensus);
+
+        mock_time.advance_async(Duration::from_secs(1)).await;
+        for i in 0..num_kept {
+            assert_eq!(client_stream.next().await.unwrap().unwrap(), small(i));
+        }
+        send(consensus.clone()).unwrap();
+        assert_eq!(client_stream.next().await.unwrap().unwrap(), consensus);
+    };
+    rt.block_on(test);
+}
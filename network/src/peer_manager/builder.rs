@@ -11,7 +11,7 @@ use crate::{
         PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
     },
     protocols::{network::AppConfig, wire::handshake::v1::ProtocolIdSet},
-    transport::{self, Connection, DiemNetTransport, DIEM_TCP_TRANSPORT},
+    transport::{self, Connection, DiemNetTransport, DIEM_QUIC_TRANSPORT, DIEM_TCP_TRANSPORT},
     ProtocolId,
 };
 use channel::{self, diem_channel, message_queues::QueueStyle};
@@ -28,6 +28,7 @@ use diem_types::{chain_id::ChainId, network_address::NetworkAddress, PeerId};
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 use netcore::transport::memory::MemoryTransport;
 use netcore::transport::{
+    quic::{QuicSocket, QuicTransport},
     tcp::{TcpSocket, TcpTransport},
     Transport,
 };
@@ -146,11 +147,13 @@ impl PeerManagerContext {
 type MemoryPeerManager =
     PeerManager<DiemNetTransport<MemoryTransport>, NoiseStream<memsocket::MemorySocket>>;
 type TcpPeerManager = PeerManager<DiemNetTransport<TcpTransport>, NoiseStream<TcpSocket>>;
+type QuicPeerManager = PeerManager<DiemNetTransport<QuicTransport>, NoiseStream<QuicSocket>>;
 
 enum TransportPeerManager {
     #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
     Memory(MemoryPeerManager),
     Tcp(TcpPeerManager),
+    Quic(QuicPeerManager),
 }
 
 pub struct PeerManagerBuilder {
@@ -290,6 +293,27 @@ impl PeerManagerBuilder {
                     executor,
                 )))
             }
+            [Ip4(_), Quic(_)] | [Ip6(_), Quic(_)] => {
+                assert!(
+                    !enable_proxy_protocol,
+                    "{} Proxy protocol is not supported on QUIC listen address: '{}'",
+                    self.network_context, self.listen_address
+                );
+                Some(TransportPeerManager::Quic(self.build_with_transport(
+                    DiemNetTransport::new(
+                        DIEM_QUIC_TRANSPORT.clone(),
+                        self.network_context,
+                        self.time_service.clone(),
+                        key,
+                        auth_mode,
+                        HANDSHAKE_VERSION,
+                        chain_id,
+                        protos,
+                        enable_proxy_protocol,
+                    ),
+                    executor,
+                )))
+            }
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             [Memory(_)] => Some(TransportPeerManager::Memory(self.build_with_transport(
                 DiemNetTransport::new(
@@ -307,7 +331,8 @@ impl PeerManagerBuilder {
             ))),
             _ => panic!(
                 "{} Unsupported listen_address: '{}', expected '/memory/<port>', \
-                 '/ip4/<addr>/tcp/<port>', or '/ip6/<addr>/tcp/<port>'.",
+                 '/ip4/<addr>/tcp/<port>', '/ip6/<addr>/tcp/<port>', \
+                 '/ip4/<addr>/quic/<port>', or '/ip6/<addr>/quic/<port>'.",
                 self.network_context, self.listen_address
             ),
         };
@@ -390,6 +415,7 @@ impl PeerManagerBuilder {
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             TransportPeerManager::Memory(pm) => self.start_peer_manager(pm, executor),
             TransportPeerManager::Tcp(pm) => self.start_peer_manager(pm, executor),
+            TransportPeerManager::Quic(pm) => self.start_peer_manager(pm, executor),
         }
     }
 
@@ -22,7 +22,7 @@ use crate::{
     ProtocolId,
 };
 use channel::{self, diem_channel, message_queues::QueueStyle};
-use diem_config::network_id::NetworkContext;
+use diem_config::network_id::{NetworkContext, PeerNetworkId};
 use diem_logger::prelude::*;
 use diem_rate_limiter::rate_limit::TokenBucketRateLimiter;
 use diem_time_service::{TimeService, TimeServiceTrait};
@@ -286,6 +286,29 @@ where
         self.sample_connected_peers();
         match event {
             TransportNotification::NewConnection(mut conn) => {
+                // Reject connections from peers banned for misbehavior, regardless of origin
+                let peer_network_id = PeerNetworkId::new(
+                    self.network_context.network_id(),
+                    conn.metadata.remote_peer_id,
+                );
+                if self
+                    .peer_metadata_storage
+                    .reputation()
+                    .is_banned(peer_network_id)
+                {
+                    info!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata_with_address(&conn.metadata),
+                        "{} Connection rejected due to peer ban: {}",
+                        self.network_context,
+                        conn.metadata
+                    );
+                    counters::connections_rejected(&self.network_context, conn.metadata.origin)
+                        .inc();
+                    self.disconnect(conn);
+                    return;
+                }
+
                 match conn.metadata.origin {
                     ConnectionOrigin::Outbound => {
                         // TODO: This is right now a hack around having to feed trusted peers deeper in the outbound path.  Inbound ones are assigned at Noise handshake time.
@@ -59,6 +59,7 @@ pub fn build_test_transport(
                     ProtocolIdSet::mock(),
                     PeerRole::Unknown,
                 ),
+                substreams: None,
             })
         })
         .boxed()
@@ -241,6 +242,7 @@ fn create_connection<TSocket: transport::TSocket>(
             ProtocolIdSet::mock(),
             PeerRole::Unknown,
         ),
+        substreams: None,
     }
 }
 
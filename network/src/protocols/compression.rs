@@ -0,0 +1,97 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! LZ4 compression of application messages.
+//!
+//! A compressed message is the LZ4 block of the serialized message, prefixed by
+//! the length of the uncompressed message as a little-endian `u32`.  The prefix
+//! lets a receiver reject a message that would decompress to more than it's
+//! willing to hold, before allocating anything (a "decompression bomb").
+
+use anyhow::{bail, ensure};
+use std::convert::TryInto;
+
+/// Length of the uncompressed size prefix
+const SIZE_PREFIX_LEN: usize = 4;
+
+/// Compresses `bytes`, prefixed by their uncompressed size
+pub fn compress(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    ensure!(
+        bytes.len() <= i32::MAX as usize,
+        "Message of {} bytes is too large to compress",
+        bytes.len()
+    );
+    // Prepends the uncompressed size as a little-endian u32
+    Ok(lz4::block::compress(bytes, None, true)?)
+}
+
+/// The uncompressed size declared by a compressed message
+pub fn decompressed_size(compressed: &[u8]) -> anyhow::Result<usize> {
+    if compressed.len() < SIZE_PREFIX_LEN {
+        bail!("Compressed message is missing its size prefix");
+    }
+    let prefix = compressed[..SIZE_PREFIX_LEN].try_into().unwrap();
+    Ok(u32::from_le_bytes(prefix) as usize)
+}
+
+/// Fails if a compressed message declares more than `max_size` bytes
+pub fn check_decompressed_size(compressed: &[u8], max_size: usize) -> anyhow::Result<usize> {
+    let size = decompressed_size(compressed)?;
+    ensure!(
+        size <= max_size && size <= i32::MAX as usize,
+        "Compressed message declares {} bytes, more than the limit of {} bytes",
+        size,
+        max_size
+    );
+    Ok(size)
+}
+
+/// Decompresses a message, failing if it declares more than `max_size` bytes
+pub fn decompress(compressed: &[u8], max_size: usize) -> anyhow::Result<Vec<u8>> {
+    let size = check_decompressed_size(compressed, max_size)?;
+    // The block decoder rejects a size of zero
+    if size == 0 {
+        return Ok(Vec::new());
+    }
+    // The block decoder never writes past the declared size
+    let bytes = lz4::block::decompress(&compressed[SIZE_PREFIX_LEN..], Some(size as i32))?;
+    ensure!(
+        bytes.len() == size,
+        "Compressed message declares {} bytes, but decompressed to {} bytes",
+        size,
+        bytes.len()
+    );
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn compress_roundtrip() {
+        let bytes = vec![7u8; 100_000];
+        let compressed = compress(&bytes).unwrap();
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(decompressed_size(&compressed).unwrap(), bytes.len());
+        assert_eq!(decompress(&compressed, bytes.len()).unwrap(), bytes);
+
+        let compressed = compress(&[]).unwrap();
+        assert_eq!(decompress(&compressed, 0).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn decompress_respects_limit() {
+        let bytes = vec![7u8; 100_000];
+        let compressed = compress(&bytes).unwrap();
+        decompress(&compressed, bytes.len() - 1).unwrap_err();
+
+        // A tiny message claiming to decompress to 4 GiB is rejected up front
+        let mut bomb = compressed;
+        bomb[..SIZE_PREFIX_LEN].copy_from_slice(&u32::MAX.to_le_bytes());
+        decompress(&bomb, usize::MAX).unwrap_err();
+
+        // Missing prefix
+        decompress(&[0, 1], usize::MAX).unwrap_err();
+    }
+}
@@ -27,6 +27,9 @@ pub enum RpcError {
     #[error("Received invalid rpc response message")]
     InvalidRpcResponse,
 
+    #[error("Failed to deserialize rpc response: {0:?}")]
+    MalformedResponse(anyhow::Error),
+
     #[error("Application layer unexpectedly dropped response channel")]
     UnexpectedResponseChannelCancel,
 
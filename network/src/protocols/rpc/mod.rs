@@ -49,9 +49,9 @@ use crate::{
         RESPONSE_LABEL, SENT_LABEL,
     },
     logging::NetworkSchema,
-    peer::PeerNotification,
-    peer_manager::PeerManagerError,
+    peer::{PeerNotification, WriteRequest},
     protocols::{
+        compression,
         network::SerializedRequest,
         wire::messaging::v1::{NetworkMessage, Priority, RequestId, RpcRequest, RpcResponse},
     },
@@ -174,7 +174,8 @@ pub struct InboundRpcs {
     remote_peer_id: PeerId,
     /// The core async queue of pending inbound rpc tasks. The tasks are driven
     /// to completion by the `InboundRpcs::next_completed_response()` method.
-    inbound_rpc_tasks: FuturesUnordered<BoxFuture<'static, Result<RpcResponse, RpcError>>>,
+    inbound_rpc_tasks:
+        FuturesUnordered<BoxFuture<'static, Result<(ProtocolId, RpcResponse), RpcError>>>,
     /// A blanket timeout on all inbound rpc requests. If the application handler
     /// doesn't respond to the request before this timeout, the request will be
     /// dropped.
@@ -182,6 +183,8 @@ pub struct InboundRpcs {
     /// Only allow this many concurrent inbound rpcs at one time from this remote
     /// peer.  New inbound requests exceeding this limit will be dropped.
     max_concurrent_inbound_rpcs: u32,
+    /// Compressed requests may not decompress to more than this many bytes.
+    max_frame_size: usize,
 }
 
 impl InboundRpcs {
@@ -191,6 +194,7 @@ impl InboundRpcs {
         remote_peer_id: PeerId,
         inbound_rpc_timeout: Duration,
         max_concurrent_inbound_rpcs: u32,
+        max_frame_size: usize,
     ) -> Self {
         Self {
             network_context,
@@ -199,6 +203,7 @@ impl InboundRpcs {
             inbound_rpc_tasks: FuturesUnordered::new(),
             inbound_rpc_timeout,
             max_concurrent_inbound_rpcs,
+            max_frame_size,
         }
     }
 
@@ -222,6 +227,16 @@ impl InboundRpcs {
         let priority = request.priority;
         let req_len = request.raw_request.len() as u64;
 
+        // Drop compressed requests that would decompress to more than a frame.
+        if protocol_id.is_compressed() {
+            if let Err(err) =
+                compression::check_decompressed_size(&request.raw_request, self.max_frame_size)
+            {
+                counters::rpc_messages(network_context, REQUEST_LABEL, DECLINED_LABEL).inc();
+                return Err(err.into());
+            }
+        }
+
         trace!(
             NetworkSchema::new(network_context).remote_peer(&self.remote_peer_id),
             "{} Received inbound rpc request from peer {} with request_id {} and protocol_id {}",
@@ -256,11 +271,14 @@ impl InboundRpcs {
             .map(move |result| {
                 // Flatten the errors
                 let maybe_response = match result {
-                    Ok(Ok(Ok(response_bytes))) => Ok(RpcResponse {
-                        request_id,
-                        priority,
-                        raw_response: Vec::from(response_bytes.as_ref()),
-                    }),
+                    Ok(Ok(Ok(response_bytes))) => Ok((
+                        protocol_id,
+                        RpcResponse {
+                            request_id,
+                            priority,
+                            raw_response: Vec::from(response_bytes.as_ref()),
+                        },
+                    )),
                     Ok(Ok(Err(err))) => Err(err),
                     Ok(Err(oneshot::Canceled)) => Err(RpcError::UnexpectedResponseChannelCancel),
                     Err(timeout::Elapsed) => Err(RpcError::TimedOut),
@@ -286,7 +304,7 @@ impl InboundRpcs {
     /// `futures::select!`.
     pub fn next_completed_response(
         &mut self,
-    ) -> impl Future<Output = Result<RpcResponse, RpcError>> + FusedFuture + '_ {
+    ) -> impl Future<Output = Result<(ProtocolId, RpcResponse), RpcError>> + FusedFuture + '_ {
         self.inbound_rpc_tasks.select_next_some()
     }
 
@@ -295,14 +313,11 @@ impl InboundRpcs {
     /// the outbound write queue.
     pub async fn send_outbound_response(
         &mut self,
-        write_reqs_tx: &mut channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
-        maybe_response: Result<RpcResponse, RpcError>,
+        write_reqs_tx: &mut channel::Sender<WriteRequest>,
+        maybe_response: Result<(ProtocolId, RpcResponse), RpcError>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
-        let response = match maybe_response {
+        let (protocol_id, response) = match maybe_response {
             Ok(response) => response,
             Err(err) => {
                 counters::rpc_messages(network_context, RESPONSE_LABEL, FAILED_LABEL).inc();
@@ -321,7 +336,9 @@ impl InboundRpcs {
         );
         let message = NetworkMessage::RpcResponse(response);
         let (ack_tx, _) = oneshot::channel();
-        write_reqs_tx.send((message, ack_tx)).await?;
+        write_reqs_tx
+            .send((Some(protocol_id), message, ack_tx))
+            .await?;
 
         // Collect counters for sent response.
         counters::rpc_messages(network_context, RESPONSE_LABEL, SENT_LABEL).inc();
@@ -358,6 +375,8 @@ pub struct OutboundRpcs {
     /// Only allow this many concurrent outbound rpcs at one time from this remote
     /// peer. New outbound requests exceeding this limit will be dropped.
     max_concurrent_outbound_rpcs: u32,
+    /// Compressed responses may not decompress to more than this many bytes.
+    max_frame_size: usize,
 }
 
 impl OutboundRpcs {
@@ -366,6 +385,7 @@ impl OutboundRpcs {
         time_service: TimeService,
         remote_peer_id: PeerId,
         max_concurrent_outbound_rpcs: u32,
+        max_frame_size: usize,
     ) -> Self {
         Self {
             network_context,
@@ -375,6 +395,7 @@ impl OutboundRpcs {
             outbound_rpc_tasks: FuturesUnordered::new(),
             pending_outbound_rpcs: HashMap::new(),
             max_concurrent_outbound_rpcs,
+            max_frame_size,
         }
     }
 
@@ -382,10 +403,7 @@ impl OutboundRpcs {
     pub async fn handle_outbound_request(
         &mut self,
         request: OutboundRpcRequest,
-        write_reqs_tx: &mut channel::Sender<(
-            NetworkMessage,
-            oneshot::Sender<Result<(), PeerManagerError>>,
-        )>,
+        write_reqs_tx: &mut channel::Sender<WriteRequest>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
         let peer_id = &self.remote_peer_id;
@@ -437,7 +455,9 @@ impl OutboundRpcs {
             raw_request: Vec::from(request_data.as_ref()),
         });
         let (ack_tx, _) = oneshot::channel();
-        write_reqs_tx.send((message, ack_tx)).await?;
+        write_reqs_tx
+            .send((Some(protocol_id), message, ack_tx))
+            .await?;
 
         // Collect counters for requests sent.
         counters::rpc_messages(network_context, REQUEST_LABEL, SENT_LABEL).inc();
@@ -453,17 +473,29 @@ impl OutboundRpcs {
         // A future that waits for the rpc response with a timeout. We create the
         // timeout out here to start the timer as soon as we push onto the queue
         // (as opposed to whenever it first gets polled on the queue).
-        let wait_for_response = self
-            .time_service
-            .timeout(timeout, response_rx)
-            .map(|result| {
-                // Flatten errors.
-                match result {
-                    Ok(Ok(response)) => Ok(Bytes::from(response.raw_response)),
-                    Ok(Err(oneshot::Canceled)) => Err(RpcError::UnexpectedResponseChannelCancel),
-                    Err(timeout::Elapsed) => Err(RpcError::TimedOut),
-                }
-            });
+        let max_frame_size = self.max_frame_size;
+        let wait_for_response =
+            self.time_service
+                .timeout(timeout, response_rx)
+                .map(move |result| {
+                    // Flatten errors.
+                    match result {
+                        // Drop compressed responses that would decompress to more than a frame.
+                        Ok(Ok(response)) if protocol_id.is_compressed() => {
+                            compression::check_decompressed_size(
+                                &response.raw_response,
+                                max_frame_size,
+                            )
+                            .map(|_| Bytes::from(response.raw_response))
+                            .map_err(RpcError::from)
+                        }
+                        Ok(Ok(response)) => Ok(Bytes::from(response.raw_response)),
+                        Ok(Err(oneshot::Canceled)) => {
+                            Err(RpcError::UnexpectedResponseChannelCancel)
+                        }
+                        Err(timeout::Elapsed) => Err(RpcError::TimedOut),
+                    }
+                });
 
         // A future that waits for the response and sends it to the application.
         let notify_application = async move {
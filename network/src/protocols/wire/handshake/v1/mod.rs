@@ -12,10 +12,11 @@
 //!
 //! [DiemNet Handshake v1 Specification]: https://github.com/diem/diem/blob/main/specifications/network/handshake-v1.md
 
+use crate::protocols::compression;
 use anyhow::anyhow;
 use diem_config::network_id::NetworkId;
 use diem_types::chain_id::ChainId;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fmt,
@@ -34,6 +35,10 @@ mod test;
 // ProtocolId
 //
 
+/// Upper bound on the size of a decompressed message, regardless of the
+/// connection's `max_frame_size`.
+pub const MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024; /* 64 MiB */
+
 /// Unique identifier associated with each application protocol.
 #[repr(u8)]
 #[derive(Clone, Copy, Hash, Eq, PartialEq, Deserialize, Serialize)]
@@ -51,12 +56,17 @@ pub enum ProtocolId {
     ConsensusRpcJson = 7,
     StorageServiceRpc = 8,
     MempoolRpc = 9,
+    PeerExchangeRpc = 10,
+    // lz4 compressed bcs, for protocols with large messages
+    StorageServiceRpcCompressed = 11,
+    MempoolDirectSendCompressed = 12,
 }
 
 /// The encoding types for Protocols
 enum Encoding {
     Bcs,
     Json,
+    CompressedBcs,
 }
 
 impl ProtocolId {
@@ -73,6 +83,9 @@ impl ProtocolId {
             ConsensusRpcJson => "ConsensusRpcJson",
             StorageServiceRpc => "StorageServiceRpc",
             MempoolRpc => "MempoolRpc",
+            PeerExchangeRpc => "PeerExchangeRpc",
+            StorageServiceRpcCompressed => "StorageServiceRpcCompressed",
+            MempoolDirectSendCompressed => "MempoolDirectSendCompressed",
         }
     }
 
@@ -88,6 +101,9 @@ impl ProtocolId {
             ProtocolId::ConsensusRpcJson,
             ProtocolId::StorageServiceRpc,
             ProtocolId::MempoolRpc,
+            ProtocolId::PeerExchangeRpc,
+            ProtocolId::StorageServiceRpcCompressed,
+            ProtocolId::MempoolDirectSendCompressed,
         ]
     }
 
@@ -95,10 +111,18 @@ impl ProtocolId {
     fn encoding(self) -> Encoding {
         match self {
             ProtocolId::ConsensusDirectSendJson | ProtocolId::ConsensusRpcJson => Encoding::Json,
+            ProtocolId::StorageServiceRpcCompressed | ProtocolId::MempoolDirectSendCompressed => {
+                Encoding::CompressedBcs
+            }
             _ => Encoding::Bcs,
         }
     }
 
+    /// Whether messages of this protocol are compressed
+    pub fn is_compressed(self) -> bool {
+        matches!(self.encoding(), Encoding::CompressedBcs)
+    }
+
     #[cfg(test)]
     pub fn mock() -> Self {
         ProtocolId::DiscoveryDirectSend
@@ -108,13 +132,24 @@ impl ProtocolId {
         match self.encoding() {
             Encoding::Json => serde_json::to_vec(value).map_err(|e| anyhow!("{:?}", e)),
             Encoding::Bcs => bcs::to_bytes(value).map_err(|e| anyhow! {"{:?}", e}),
+            Encoding::CompressedBcs => {
+                let bytes = bcs::to_bytes(value).map_err(|e| anyhow! {"{:?}", e})?;
+                compression::compress(&bytes)
+            }
         }
     }
 
-    pub fn from_bytes<'a, T: Deserialize<'a>>(&self, bytes: &'a [u8]) -> anyhow::Result<T> {
+    /// Compressed messages may decompress to at most [`MAX_DECOMPRESSED_SIZE`].
+    /// Inbound messages are already limited to the connection's `max_frame_size`
+    /// by the `Peer` actor.
+    pub fn from_bytes<T: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<T> {
         match self.encoding() {
             Encoding::Json => serde_json::from_slice(bytes).map_err(|e| anyhow!("{:?}", e)),
             Encoding::Bcs => bcs::from_bytes(bytes).map_err(|e| anyhow! {"{:?}", e}),
+            Encoding::CompressedBcs => {
+                let bytes = compression::decompress(bytes, MAX_DECOMPRESSED_SIZE)?;
+                bcs::from_bytes(&bytes).map_err(|e| anyhow! {"{:?}", e})
+            }
         }
     }
 }
@@ -182,3 +182,26 @@ fn ignore_unknown_protocols() {
         ProtocolIdSet::empty(),
     );
 }
+
+#[test]
+fn compressed_encoding() {
+    let value = vec![7u64; 1000];
+    let uncompressed = ProtocolId::StorageServiceRpc.to_bytes(&value).unwrap();
+    let compressed = ProtocolId::StorageServiceRpcCompressed
+        .to_bytes(&value)
+        .unwrap();
+    assert!(compressed.len() < uncompressed.len());
+    assert_eq!(
+        ProtocolId::StorageServiceRpcCompressed
+            .from_bytes::<Vec<u64>>(&compressed)
+            .unwrap(),
+        value
+    );
+
+    // The encodings aren't interchangeable
+    ProtocolId::StorageServiceRpc
+        .from_bytes::<Vec<u64>>(&compressed)
+        .unwrap_err();
+    assert!(ProtocolId::StorageServiceRpcCompressed.is_compressed());
+    assert!(!ProtocolId::StorageServiceRpc.is_compressed());
+}
@@ -19,7 +19,9 @@ use diem_logger::prelude::*;
 use diem_time_service::{timeout, TimeService, TimeServiceTrait};
 use diem_types::{
     chain_id::ChainId,
-    network_address::{parse_dns_tcp, parse_ip_tcp, parse_memory, NetworkAddress},
+    network_address::{
+        parse_dns_quic, parse_dns_tcp, parse_ip_quic, parse_ip_tcp, parse_memory, NetworkAddress,
+    },
     PeerId,
 };
 use futures::{
@@ -27,7 +29,8 @@ use futures::{
     io::{AsyncRead, AsyncWrite},
     stream::{Stream, StreamExt, TryStreamExt},
 };
-use netcore::transport::{proxy_protocol, tcp, ConnectionOrigin, Transport};
+use netcore::transport::{proxy_protocol, quic, tcp, ConnectionOrigin, SubstreamSocket, Transport};
+use once_cell::sync::Lazy;
 use serde::Serialize;
 use short_hex_str::AsShortHexStr;
 use std::{collections::BTreeMap, convert::TryFrom, fmt, io, pin::Pin, sync::Arc, time::Duration};
@@ -53,6 +56,17 @@ pub const DIEM_TCP_TRANSPORT: tcp::TcpTransport = tcp::TcpTransport {
     nodelay: Some(true),
 };
 
+/// quic::Transport with Diem-specific configuration applied. Its clones share
+/// the client endpoints that outbound connections are dialed from.
+pub static DIEM_QUIC_TRANSPORT: Lazy<quic::QuicTransport> = Lazy::new(|| {
+    quic::QuicTransport::new(
+        // Time out connections whose remote has silently gone away.
+        Some(Duration::from_secs(60)),
+        // Keep otherwise idle connections (and any NAT bindings) alive.
+        Some(Duration::from_secs(20)),
+    )
+});
+
 /// A trait alias for "socket-like" things.
 pub trait TSocket: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static {}
 
@@ -172,6 +186,9 @@ impl fmt::Display for ConnectionMetadata {
 pub struct Connection<TSocket> {
     pub socket: TSocket,
     pub metadata: ConnectionMetadata,
+    /// Additional streams to the remote peer, if the base transport supports
+    /// them (e.g. QUIC). Otherwise, all traffic goes over `socket`.
+    pub substreams: Option<quic::Substreams>,
 }
 
 /// Convenience function for adding a timeout to a Future that returns an `io::Result`.
@@ -230,7 +247,7 @@ fn add_pp_addr(proxy_protocol_enabled: bool, error: io::Error, addr: &NetworkAdd
 /// `ctxt.trusted_peers` is `Some(_)`, then we will only allow connections from
 /// peers with a pubkey in this set. Otherwise, we will allow inbound connections
 /// from any pubkey.
-async fn upgrade_inbound<T: TSocket>(
+async fn upgrade_inbound<T: TSocket + SubstreamSocket>(
     ctxt: Arc<UpgradeContext>,
     fut_socket: impl Future<Output = io::Result<T>>,
     addr: NetworkAddress,
@@ -256,10 +273,18 @@ async fn upgrade_inbound<T: TSocket>(
     } else {
         addr
     };
+    let substreams = socket.take_substreams();
+    let channel_binding = socket
+        .channel_binding()
+        .map(<[u8]>::to_vec)
+        .unwrap_or_default();
 
     // try authenticating via noise handshake
-    let (mut socket, remote_peer_id, peer_role) =
-        ctxt.noise.upgrade_inbound(socket).await.map_err(|err| {
+    let (mut socket, remote_peer_id, peer_role) = ctxt
+        .noise
+        .upgrade_inbound_with_channel_binding(socket, &channel_binding)
+        .await
+        .map_err(|err| {
             if err.should_security_log() {
                 sample!(
                     SampleRate::Duration(Duration::from_secs(15)),
@@ -316,12 +341,13 @@ async fn upgrade_inbound<T: TSocket>(
             application_protocols,
             peer_role,
         ),
+        substreams,
     })
 }
 
 /// Upgrade an inbound connection. This means we run a Noise IK handshake for
 /// authentication and then negotiate common supported protocols.
-pub async fn upgrade_outbound<T: TSocket>(
+pub async fn upgrade_outbound<T: TSocket + SubstreamSocket>(
     ctxt: Arc<UpgradeContext>,
     fut_socket: impl Future<Output = io::Result<T>>,
     addr: NetworkAddress,
@@ -329,12 +355,22 @@ pub async fn upgrade_outbound<T: TSocket>(
     remote_pubkey: x25519::PublicKey,
 ) -> io::Result<Connection<NoiseStream<T>>> {
     let origin = ConnectionOrigin::Outbound;
-    let socket = fut_socket.await?;
+    let mut socket = fut_socket.await?;
+    let substreams = socket.take_substreams();
+    let channel_binding = socket
+        .channel_binding()
+        .map(<[u8]>::to_vec)
+        .unwrap_or_default();
 
     // noise handshake
     let mut socket = ctxt
         .noise
-        .upgrade_outbound(socket, remote_pubkey, AntiReplayTimestamps::now)
+        .upgrade_outbound_with_channel_binding(
+            socket,
+            remote_pubkey,
+            AntiReplayTimestamps::now,
+            &channel_binding,
+        )
         .await
         .map_err(|err| {
             if err.should_security_log() {
@@ -386,6 +422,7 @@ pub async fn upgrade_outbound<T: TSocket>(
             application_protocols,
             PeerRole::Unknown,
         ),
+        substreams,
     })
 }
 
@@ -393,7 +430,9 @@ pub async fn upgrade_outbound<T: TSocket>(
 ///
 /// The base transport layer is pluggable, so long as it provides a reliable,
 /// ordered, connection-oriented, byte-stream abstraction (e.g., TCP). We currently
-/// use either `MemoryTransport` or `TcpTransport` as this base layer.
+/// use either `MemoryTransport`, `TcpTransport` or `QuicTransport` as this base layer.
+/// If the base transport also provides substreams (e.g., QUIC), they are passed
+/// along with the upgraded connection.
 ///
 /// Inbound and outbound connections are first established with the `base_transport`
 /// and then negotiate a secure, authenticated transport layer (currently Noise
@@ -412,7 +451,7 @@ pub struct DiemNetTransport<TTransport> {
 impl<TTransport> DiemNetTransport<TTransport>
 where
     TTransport: Transport<Error = io::Error>,
-    TTransport::Output: TSocket,
+    TTransport::Output: TSocket + SubstreamSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
@@ -465,13 +504,15 @@ where
         let (base_transport_protos, base_transport_suffix) = parse_ip_tcp(protos)
             .map(|x| (&protos[..2], x.1))
             .or_else(|| parse_dns_tcp(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_ip_quic(protos).map(|x| (&protos[..2], x.1)))
+            .or_else(|| parse_dns_quic(protos).map(|x| (&protos[..2], x.1)))
             .or_else(|| parse_memory(protos).map(|x| (&protos[..1], x.1)))
             .ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::InvalidInput,
                     format!(
                         "Unexpected dialing network address: '{}', expected: \
-                         memory, ip+tcp, or dns+tcp",
+                         memory, ip+tcp, dns+tcp, ip+quic, or dns+quic",
                         addr
                     ),
                 )
@@ -517,6 +558,9 @@ where
     /// `/dns/<ipaddr>/tcp/<port>` or
     /// `/dns4/<ipaddr>/tcp/<port>` or
     /// `/dns6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then `/<base_transport>` is any
+    /// of the above with `/quic/<port>` in place of `/tcp/<port>`.
     pub fn dial(
         &self,
         peer_id: PeerId,
@@ -563,6 +607,11 @@ where
     ///
     /// `/ip4/<ipaddr>/tcp/<port>` or
     /// `/ip6/<ipaddr>/tcp/<port>`
+    ///
+    /// If the base transport is `QuicTransport`, then we expect:
+    ///
+    /// `/ip4/<ipaddr>/quic/<port>` or
+    /// `/ip6/<ipaddr>/quic/<port>`
     pub fn listen_on(
         &self,
         addr: NetworkAddress,
@@ -619,7 +668,7 @@ where
 impl<TTransport: Transport> Transport for DiemNetTransport<TTransport>
 where
     TTransport: Transport<Error = io::Error> + Send + 'static,
-    TTransport::Output: TSocket,
+    TTransport::Output: TSocket + SubstreamSocket,
     TTransport::Outbound: Send + 'static,
     TTransport::Inbound: Send + 'static,
     TTransport::Listener: Send + 'static,
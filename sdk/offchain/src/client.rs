@@ -0,0 +1,299 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    credentials::{CredentialError, CredentialProvider},
+    identifier::{decode_account, IntentIdentifierError},
+    jws::{self, JwsError},
+    payment_command::{Actor, Origin, PaymentCommand, PaymentCommandError},
+    storage::{CommandStore, StorageError},
+    types::{
+        CommandResponseObject, CommandStatus, PaymentObject, REQUEST_ID_HEADER,
+        REQUEST_SENDER_ADDRESS,
+    },
+};
+use diem_sdk::crypto::ed25519::ed25519_dalek::Keypair;
+use std::{sync::Arc, time::Duration};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error(transparent)]
+    Command(#[from] PaymentCommandError),
+    #[error(transparent)]
+    Address(#[from] IntentIdentifierError),
+    #[error(transparent)]
+    Credential(#[from] CredentialError),
+    #[error(transparent)]
+    Jws(#[from] JwsError),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("storage error: {0}")]
+    Storage(StorageError),
+    #[error("command rejected by the counterparty: {0:?}")]
+    Rejected(CommandResponseObject),
+    #[error("response to command {0} has a different cid: {1:?}")]
+    UnexpectedCid(Uuid, CommandResponseObject),
+}
+
+/// How long the client waits for a counterparty to respond to a command by default.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends the commands of a VASP to the off-chain APIs of its counterparties, signed with the
+/// compliance key of the VASP. Commands are validated against the prior command of their payment
+/// before being sent, and recorded in the [`CommandStore`] once the counterparty accepts them.
+pub struct OffchainClient {
+    http: reqwest::Client,
+    compliance_key: Keypair,
+    credentials: Arc<dyn CredentialProvider>,
+    store: Arc<dyn CommandStore>,
+}
+
+impl OffchainClient {
+    pub fn new(
+        compliance_key: Keypair,
+        credentials: Arc<dyn CredentialProvider>,
+        store: Arc<dyn CommandStore>,
+    ) -> Self {
+        Self::new_with_timeout(compliance_key, credentials, store, DEFAULT_TIMEOUT)
+    }
+
+    /// A client giving up on requests the counterparty does not respond to within `timeout`.
+    pub fn new_with_timeout(
+        compliance_key: Keypair,
+        credentials: Arc<dyn CredentialProvider>,
+        store: Arc<dyn CommandStore>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            http: reqwest::Client::builder().timeout(timeout).build().unwrap(),
+            compliance_key,
+            credentials,
+            store,
+        }
+    }
+
+    /// Send `payment`, updated by `my_actor`, to the VASP of the other actor of the payment, and
+    /// return the command the counterparty accepted.
+    pub async fn send_payment(
+        &self,
+        payment: PaymentObject,
+        my_actor: Actor,
+    ) -> Result<PaymentCommand, ClientError> {
+        let prior = self
+            .store
+            .payment_command(payment.reference_id())
+            .await
+            .map_err(ClientError::Storage)?;
+        let command = PaymentCommand::new(
+            payment,
+            Origin::Outbound,
+            my_actor,
+            Uuid::new_v4(),
+            prior.as_ref(),
+        )?;
+        let (_, counterparty_account, _) =
+            decode_account(&command.counterparty_actor_object().address)?;
+        let credential = self.credentials.credential(counterparty_account).await?;
+
+        let request = jws::serialize(&command.to_request(), &self.compliance_key)?;
+        let response = self
+            .http
+            .post(format!(
+                "{}/v2/command",
+                credential.base_url.trim_end_matches('/')
+            ))
+            .header(REQUEST_ID_HEADER, Uuid::new_v4().to_string())
+            .header(REQUEST_SENDER_ADDRESS, &*command.my_actor_object().address)
+            .body(request)
+            .send()
+            .await?
+            .bytes()
+            .await?;
+        let response: CommandResponseObject =
+            jws::deserialize(&response, &credential.compliance_key)?;
+        // Only protocol errors, raised before the command is read, are responded to without a cid
+        let cid_matches = match response.cid() {
+            Some(cid) => cid == command.cid(),
+            None => *response.status() == CommandStatus::Failure,
+        };
+        if !cid_matches {
+            return Err(ClientError::UnexpectedCid(command.cid(), response));
+        }
+        if *response.status() != CommandStatus::Success {
+            return Err(ClientError::Rejected(response));
+        }
+
+        self.store
+            .save_payment_command(command.clone())
+            .await
+            .map_err(ClientError::Storage)?;
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        credentials::DualAttestationCredential,
+        server::OffchainServer,
+        storage::InMemoryCommandStore,
+        test_utils::{initial_payment, Vasp},
+        types::ErrorCode,
+    };
+    use diem_sdk::types::account_address::AccountAddress;
+    use std::collections::BTreeMap;
+    use warp::{Filter, Reply};
+
+    fn credentials(
+        vasp: &Vasp,
+        base_url: &str,
+    ) -> Arc<BTreeMap<AccountAddress, DualAttestationCredential>> {
+        Arc::new(
+            vec![(vasp.account, vasp.credential(base_url))]
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    /// Serve `filter` on an ephemeral port, and return its base URL.
+    fn serve<F>(filter: F) -> String
+    where
+        F: Filter + Clone + Send + Sync + 'static,
+        F::Extract: Reply,
+    {
+        let (address, server) = warp::serve(filter).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", address)
+    }
+
+    /// Serve the off-chain API of `receiver`, for `accounts`, which knows the credential of
+    /// `sender`, and return its base URL and store.
+    fn serve_vasp(
+        sender: &Vasp,
+        receiver: &Vasp,
+        accounts: Vec<AccountAddress>,
+    ) -> (String, Arc<InMemoryCommandStore>) {
+        let store = Arc::new(InMemoryCommandStore::new());
+        let server = Arc::new(OffchainServer::new(
+            accounts,
+            receiver.compliance_key(),
+            credentials(sender, "http://localhost"),
+            store.clone(),
+        ));
+        (serve(server.routes()), store)
+    }
+
+    // A client for `sender`, which sends commands to `receiver` at `base_url`
+    fn client(
+        sender: &Vasp,
+        receiver: &Vasp,
+        base_url: &str,
+        timeout: Duration,
+    ) -> (OffchainClient, Arc<InMemoryCommandStore>) {
+        let store = Arc::new(InMemoryCommandStore::new());
+        let client = OffchainClient::new_with_timeout(
+            sender.compliance_key(),
+            credentials(receiver, base_url),
+            store.clone(),
+            timeout,
+        );
+        (client, store)
+    }
+
+    #[tokio::test]
+    async fn sends_payment_to_server() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let (base_url, server_store) = serve_vasp(&sender, &receiver, vec![receiver.account]);
+        let (client, client_store) = client(&sender, &receiver, &base_url, DEFAULT_TIMEOUT);
+        let payment = initial_payment(&sender, &receiver);
+
+        let command = client
+            .send_payment(payment.clone(), Actor::Sender)
+            .await
+            .unwrap();
+        assert_eq!(*command.payment(), payment);
+        assert_eq!(command.my_actor(), Actor::Sender);
+
+        let sent = client_store
+            .payment_command(payment.reference_id())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sent.cid(), command.cid());
+        let received = server_store
+            .payment_command(payment.reference_id())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.cid(), command.cid());
+        assert_eq!(*received.payment(), payment);
+        assert_eq!(received.my_actor(), Actor::Receiver);
+    }
+
+    #[tokio::test]
+    async fn does_not_record_rejected_command() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        // The server does not own the account of the receiver
+        let (base_url, _) = serve_vasp(&sender, &receiver, vec![]);
+        let (client, client_store) = client(&sender, &receiver, &base_url, DEFAULT_TIMEOUT);
+        let payment = initial_payment(&sender, &receiver);
+
+        match client.send_payment(payment.clone(), Actor::Sender).await {
+            Err(ClientError::Rejected(response)) => {
+                assert_eq!(response.error().unwrap().code(), &ErrorCode::UnknownAddress)
+            }
+            result => panic!("unexpected result: {:?}", result.map(|_| ())),
+        }
+        assert!(client_store
+            .payment_command(payment.reference_id())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_response_to_other_command() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let receiver_key = Arc::new(receiver.compliance_key());
+        let base_url = serve(warp::post().map(move || {
+            jws::serialize(
+                &CommandResponseObject::success(Uuid::new_v4()),
+                &*receiver_key,
+            )
+            .unwrap()
+        }));
+        let (client, client_store) = client(&sender, &receiver, &base_url, DEFAULT_TIMEOUT);
+        let payment = initial_payment(&sender, &receiver);
+
+        assert!(matches!(
+            client.send_payment(payment.clone(), Actor::Sender).await,
+            Err(ClientError::UnexpectedCid(..))
+        ));
+        assert!(client_store
+            .payment_command(payment.reference_id())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let base_url = serve(warp::post().and_then(|| async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok::<_, std::convert::Infallible>("")
+        }));
+        let (client, _) = client(&sender, &receiver, &base_url, Duration::from_millis(100));
+
+        match client
+            .send_payment(initial_payment(&sender, &receiver), Actor::Sender)
+            .await
+        {
+            Err(ClientError::Http(error)) => assert!(error.is_timeout()),
+            result => panic!("unexpected result: {:?}", result.map(|_| ())),
+        }
+    }
+}
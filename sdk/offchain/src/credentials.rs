@@ -0,0 +1,115 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use diem_sdk::{
+    client::{views::AccountRoleView, Client},
+    crypto::ed25519::ed25519_dalek::PublicKey,
+    types::account_address::AccountAddress,
+};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The parts of a VASP's on-chain `DualAttestation::Credential` needed to exchange off-chain
+/// commands with it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DualAttestationCredential {
+    /// The URL of the VASP's off-chain API, without the `/v2/command` path
+    pub base_url: String,
+    /// The key the VASP signs its requests and responses with
+    pub compliance_key: PublicKey,
+}
+
+#[derive(Debug, Error)]
+pub enum CredentialError {
+    #[error(transparent)]
+    Client(#[from] diem_sdk::client::Error),
+    #[error("account {0} does not exist")]
+    AccountNotFound(AccountAddress),
+    #[error("account {0} has no dual attestation credential")]
+    NoCredential(AccountAddress),
+    #[error("the compliance key of account {0} is not a valid Ed25519 public key")]
+    InvalidComplianceKey(AccountAddress),
+}
+
+/// Finds the credential of the VASP owning an account.
+#[async_trait]
+pub trait CredentialProvider: Send + Sync {
+    async fn credential(
+        &self,
+        address: AccountAddress,
+    ) -> Result<DualAttestationCredential, CredentialError>;
+}
+
+/// Reads credentials from the chain. The credential of a child VASP account is the one of its
+/// parent VASP.
+pub struct OnChainCredentials {
+    client: Client,
+}
+
+impl OnChainCredentials {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    async fn account_role(
+        &self,
+        address: AccountAddress,
+    ) -> Result<AccountRoleView, CredentialError> {
+        self.client
+            .get_account(address)
+            .await?
+            .into_inner()
+            .map(|account| account.role)
+            .ok_or(CredentialError::AccountNotFound(address))
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for OnChainCredentials {
+    async fn credential(
+        &self,
+        address: AccountAddress,
+    ) -> Result<DualAttestationCredential, CredentialError> {
+        let (address, role) = match self.account_role(address).await? {
+            AccountRoleView::ChildVASP {
+                parent_vasp_address,
+            } => (
+                parent_vasp_address,
+                self.account_role(parent_vasp_address).await?,
+            ),
+            role => (address, role),
+        };
+        let (base_url, compliance_key) = match role {
+            AccountRoleView::ParentVASP {
+                base_url,
+                compliance_key,
+                ..
+            }
+            | AccountRoleView::DesignatedDealer {
+                base_url,
+                compliance_key,
+                ..
+            } => (base_url, compliance_key),
+            _ => return Err(CredentialError::NoCredential(address)),
+        };
+        Ok(DualAttestationCredential {
+            base_url,
+            compliance_key: PublicKey::from_bytes(compliance_key.inner())
+                .map_err(|_| CredentialError::InvalidComplianceKey(address))?,
+        })
+    }
+}
+
+/// A fixed set of credentials, e.g., for tests or for counterparties known ahead of time.
+#[async_trait]
+impl CredentialProvider for BTreeMap<AccountAddress, DualAttestationCredential> {
+    async fn credential(
+        &self,
+        address: AccountAddress,
+    ) -> Result<DualAttestationCredential, CredentialError> {
+        self.get(&address)
+            .cloned()
+            .ok_or(CredentialError::NoCredential(address))
+    }
+}
@@ -101,14 +101,21 @@ struct InnerError {
     source: Option<BoxError>,
 }
 
-#[derive(PartialEq, Eq, Debug)]
-enum ErrorKind {
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorKind {
+    /// The message is not a valid compact JWS, or its header is not `{ "alg": "EdDSA" }`
     JwsCompact,
+    /// The payload is not valid JSON for the expected type
     Json,
+    /// The signature is invalid
     Signature,
 }
 
 impl JwsError {
+    pub fn kind(&self) -> ErrorKind {
+        self.inner.kind
+    }
+
     //
     // Constructors
     //
@@ -6,3 +6,15 @@ pub mod jws;
 pub mod payment_command;
 pub mod subaddress;
 pub mod types;
+
+#[cfg(any(test, feature = "offchain-server"))]
+pub mod client;
+#[cfg(any(test, feature = "offchain-server"))]
+pub mod credentials;
+#[cfg(any(test, feature = "offchain-server"))]
+pub mod server;
+#[cfg(any(test, feature = "offchain-server"))]
+pub mod storage;
+
+#[cfg(test)]
+mod test_utils;
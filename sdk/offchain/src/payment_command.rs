@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::types::{
-    Command, CommandRequestObject, PaymentActorObject, PaymentCommandObject, PaymentObject, Status,
+    Command, CommandRequestObject, ErrorCode, PaymentActorObject, PaymentCommandObject,
+    PaymentObject, Status,
 };
+use thiserror::Error;
 use uuid::Uuid;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -37,7 +39,7 @@ impl Actor {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct PaymentCommand {
     payment: PaymentObject,
     payment_state: PaymentState,
@@ -53,7 +55,7 @@ impl PaymentCommand {
         my_actor: Actor,
         cid: Uuid,
         prior: Option<&Self>,
-    ) -> Result<Self, &'static str> {
+    ) -> Result<Self, PaymentCommandError> {
         let payment_state = Self::validate(origin, my_actor, &payment, prior)?;
 
         Ok(Self {
@@ -116,40 +118,44 @@ impl PaymentCommand {
         my_actor: Actor,
         payment_object: &PaymentObject,
         prior: Option<&PaymentCommand>,
-    ) -> Result<PaymentState, &'static str> {
-        let payment_state =
-            PaymentState::from_payment(payment_object).ok_or("invalid payment state")?;
+    ) -> Result<PaymentState, PaymentCommandError> {
+        let payment_state = PaymentState::from_payment(payment_object)
+            .ok_or(PaymentCommandError::InvalidPaymentState)?;
         // Validate state trigger actor
-        if origin.is_inbound() && my_actor.counterparty_actor() != payment_state.trigger_actor() {
-            return Err("Should not produce");
+        let producer = match origin {
+            Origin::Inbound => my_actor.counterparty_actor(),
+            Origin::Outbound => my_actor,
+        };
+        if producer != payment_state.trigger_actor() {
+            return Err(PaymentCommandError::InvalidCommandProducer);
         }
 
         if let Some(prior) = prior {
             // Does the prior command have the same reference_id?
             if payment_object.reference_id() != prior.payment().reference_id() {
-                return Err("payment object reference_id does not match");
+                return Err(PaymentCommandError::ReferenceIdMismatch);
             }
 
             // Validate actor object
             if origin.is_inbound()
                 && payment_object.actor_object_by_actor(my_actor) != prior.my_actor_object()
             {
-                return Err("invalid overwrite");
+                return Err(PaymentCommandError::InvalidOverwrite);
             }
 
             // Validate WriteOnce fields
             payment_object
                 .validate_write_once_fields(prior.payment())
-                .map_err(|_| "write once fields error")?;
+                .map_err(|_| PaymentCommandError::WriteOnceFields)?;
 
             // Validate transition
             if !PaymentState::is_valid_transition(prior.payment_state(), payment_state) {
-                return Err("invalid state transition");
+                return Err(PaymentCommandError::InvalidTransition);
             }
         } else {
             // Must be an initial Command
             if !matches!(payment_state, PaymentState::SenderInit) {
-                return Err("must be initial or unable to find prior payment object");
+                return Err(PaymentCommandError::InvalidInitialOrPriorNotFound);
             }
         }
 
@@ -157,6 +163,44 @@ impl PaymentCommand {
     }
 }
 
+/// The reasons a payment object is not a valid command, given the command it follows.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum PaymentCommandError {
+    #[error("invalid payment state")]
+    InvalidPaymentState,
+    #[error("Should not produce")]
+    InvalidCommandProducer,
+    #[error("payment object reference_id does not match")]
+    ReferenceIdMismatch,
+    #[error("invalid overwrite")]
+    InvalidOverwrite,
+    #[error("write once fields error")]
+    WriteOnceFields,
+    #[error("invalid state transition")]
+    InvalidTransition,
+    #[error("must be initial or unable to find prior payment object")]
+    InvalidInitialOrPriorNotFound,
+}
+
+impl PaymentCommandError {
+    /// The error code to report to the counterparty that sent the invalid command.
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            PaymentCommandError::InvalidPaymentState | PaymentCommandError::InvalidTransition => {
+                ErrorCode::InvalidTransition
+            }
+            PaymentCommandError::InvalidCommandProducer => ErrorCode::InvalidCommandProducer,
+            PaymentCommandError::ReferenceIdMismatch => ErrorCode::InvalidFieldValue,
+            PaymentCommandError::InvalidOverwrite | PaymentCommandError::WriteOnceFields => {
+                ErrorCode::InvalidOverwrite
+            }
+            PaymentCommandError::InvalidInitialOrPriorNotFound => {
+                ErrorCode::InvalidInitialOrPriorNotFound
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PaymentState {
     // S_INIT
@@ -0,0 +1,455 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    credentials::CredentialProvider,
+    identifier::decode_account,
+    jws::{self, ErrorKind},
+    payment_command::{Actor, Origin, PaymentCommand},
+    storage::{CommandStore, StorageError},
+    types::{
+        Command, CommandRequestObject, CommandResponseObject, CommandStatus, ErrorCode,
+        OffChainError, REQUEST_ID_HEADER, REQUEST_SENDER_ADDRESS,
+    },
+};
+use diem_sdk::{crypto::ed25519::ed25519_dalek::Keypair, types::account_address::AccountAddress};
+use std::{collections::BTreeSet, convert::Infallible, error::Error, net::SocketAddr, sync::Arc};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+use warp::{
+    http::{HeaderValue, StatusCode},
+    hyper::body::Bytes,
+    reply::Response,
+    Filter, Rejection, Reply,
+};
+
+/// The largest request body the server reads, in bytes. Commands are small JSON objects, so this
+/// leaves plenty of room for KYC data while keeping counterparties from exhausting memory.
+pub const MAX_REQUEST_SIZE: u64 = 256 * 1024;
+
+/// Serves the off-chain API of a VASP: receives the commands of counterparty VASPs at
+/// `/v2/command`, checks that they are signed with the compliance key of the VASP sending them,
+/// validates them against the prior command of the same payment, and records them in a
+/// [`CommandStore`]. Responses are signed with the compliance key of this VASP.
+///
+/// Accepted commands are only recorded; the VASP acts on them, e.g., according to
+/// [`PaymentCommand::follow_up_action`], by reading them from the store.
+pub struct OffchainServer {
+    accounts: BTreeSet<AccountAddress>,
+    compliance_key: Keypair,
+    credentials: Arc<dyn CredentialProvider>,
+    store: Arc<dyn CommandStore>,
+    // Held while a command is validated and recorded, so that commands are always validated
+    // against the latest command of their payment
+    processing: Mutex<()>,
+}
+
+enum RequestError {
+    Rejected(OffChainError, Option<Uuid>),
+    Storage(StorageError),
+}
+
+impl From<StorageError> for RequestError {
+    fn from(error: StorageError) -> Self {
+        RequestError::Storage(error)
+    }
+}
+
+fn protocol_error(code: ErrorCode, message: impl Into<String>) -> RequestError {
+    RequestError::Rejected(OffChainError::protocol_error(code, message), None)
+}
+
+fn command_error(code: ErrorCode, message: impl Into<String>, cid: Uuid) -> RequestError {
+    RequestError::Rejected(OffChainError::command_error(code, message), Some(cid))
+}
+
+impl OffchainServer {
+    /// A server for the VASP owning `accounts`, its parent VASP account and child VASP accounts.
+    pub fn new(
+        accounts: impl IntoIterator<Item = AccountAddress>,
+        compliance_key: Keypair,
+        credentials: Arc<dyn CredentialProvider>,
+        store: Arc<dyn CommandStore>,
+    ) -> Self {
+        Self {
+            accounts: accounts.into_iter().collect(),
+            compliance_key,
+            credentials,
+            store,
+            processing: Mutex::new(()),
+        }
+    }
+
+    /// Serve the off-chain API on `address` until the returned future is dropped.
+    pub async fn run(self: Arc<Self>, address: impl Into<SocketAddr>) {
+        warp::serve(self.routes()).run(address).await
+    }
+
+    pub fn routes(
+        self: Arc<Self>,
+    ) -> impl Filter<Extract = (Response,), Error = Rejection> + Clone {
+        warp::path!("v2" / "command")
+            .and(warp::post())
+            .and(warp::header::optional::<String>(REQUEST_ID_HEADER))
+            .and(warp::header::optional::<String>(REQUEST_SENDER_ADDRESS))
+            .and(warp::body::content_length_limit(MAX_REQUEST_SIZE))
+            .and(warp::body::bytes())
+            .and_then(
+                move |request_id: Option<String>, sender_address: Option<String>, body: Bytes| {
+                    let server = self.clone();
+                    async move {
+                        Ok::<_, Infallible>(server.reply(request_id, sender_address, &body).await)
+                    }
+                },
+            )
+    }
+
+    async fn reply(
+        &self,
+        request_id: Option<String>,
+        sender_address: Option<String>,
+        body: &[u8],
+    ) -> Response {
+        let response = match self
+            .handle_request(request_id.as_deref(), sender_address.as_deref(), body)
+            .await
+        {
+            Ok(response) => response,
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        };
+        let status = match response.status() {
+            CommandStatus::Success => StatusCode::OK,
+            CommandStatus::Failure => StatusCode::BAD_REQUEST,
+        };
+        let body = match jws::serialize(&response, &self.compliance_key) {
+            Ok(body) => body,
+            Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        };
+        let mut reply = warp::reply::with_status(body, status).into_response();
+        if let Some(request_id) = request_id.and_then(|id| HeaderValue::from_str(&id).ok()) {
+            reply.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+        }
+        reply
+    }
+
+    /// Process a request with the given `X-REQUEST-ID` and `X-REQUEST-SENDER-ADDRESS` headers
+    /// and JWS `body`, returning the response to sign and send back. Only fails if the store
+    /// fails.
+    pub async fn handle_request(
+        &self,
+        request_id: Option<&str>,
+        sender_address: Option<&str>,
+        body: &[u8],
+    ) -> Result<CommandResponseObject, StorageError> {
+        match self.process_request(request_id, sender_address, body).await {
+            Ok(cid) => Ok(CommandResponseObject::success(cid)),
+            Err(RequestError::Rejected(error, cid)) => {
+                Ok(CommandResponseObject::failure(error, cid))
+            }
+            Err(RequestError::Storage(error)) => Err(error),
+        }
+    }
+
+    async fn process_request(
+        &self,
+        request_id: Option<&str>,
+        sender_address: Option<&str>,
+        body: &[u8],
+    ) -> Result<Uuid, RequestError> {
+        let request_id = request_id.ok_or_else(|| {
+            protocol_error(
+                ErrorCode::MissingHttpHeader,
+                format!("missing {} header", REQUEST_ID_HEADER),
+            )
+        })?;
+        Uuid::parse_str(request_id).map_err(|_| {
+            protocol_error(
+                ErrorCode::InvalidHttpHeader,
+                format!("{} header is not a UUID", REQUEST_ID_HEADER),
+            )
+        })?;
+        let sender_address = sender_address.ok_or_else(|| {
+            protocol_error(
+                ErrorCode::MissingHttpHeader,
+                format!("missing {} header", REQUEST_SENDER_ADDRESS),
+            )
+        })?;
+        let (_, sender_account, _) = decode_account(sender_address)
+            .map_err(|error| protocol_error(ErrorCode::InvalidHttpHeader, error.to_string()))?;
+        let credential = self
+            .credentials
+            .credential(sender_account)
+            .await
+            .map_err(|error| protocol_error(ErrorCode::InvalidHttpHeader, error.to_string()))?;
+
+        let request: CommandRequestObject = jws::deserialize(body, &credential.compliance_key)
+            .map_err(|error| {
+                let code = match error.kind() {
+                    ErrorKind::JwsCompact => ErrorCode::InvalidJws,
+                    ErrorKind::Signature => ErrorCode::InvalidJwsSignature,
+                    ErrorKind::Json => match error
+                        .source()
+                        .and_then(|source| source.downcast_ref::<serde_json::Error>())
+                    {
+                        Some(json_error) if json_error.is_data() => ErrorCode::InvalidObject,
+                        _ => ErrorCode::InvalidJson,
+                    },
+                };
+                protocol_error(code, error.to_string())
+            })?;
+        let (command, cid) = request.into_parts();
+        let payment = match command {
+            Command::PaymentCommand(payment_command) => payment_command.into_payment(),
+            Command::FundPullPreApprovalCommand => {
+                return Err(command_error(
+                    ErrorCode::UnknownCommandType,
+                    "only payment commands are supported",
+                    cid,
+                ))
+            }
+        };
+
+        let my_actor = if *payment.sender().address == *sender_address {
+            Actor::Receiver
+        } else if *payment.receiver().address == *sender_address {
+            Actor::Sender
+        } else {
+            return Err(protocol_error(
+                ErrorCode::InvalidHttpHeader,
+                format!(
+                    "{} header is not the address of an actor of the payment",
+                    REQUEST_SENDER_ADDRESS
+                ),
+            ));
+        };
+        let (_, my_account, _) = decode_account(&payment.actor_object_by_actor(my_actor).address)
+            .map_err(|error| {
+            command_error(ErrorCode::InvalidFieldValue, error.to_string(), cid)
+        })?;
+        if !self.accounts.contains(&my_account) {
+            return Err(command_error(
+                ErrorCode::UnknownAddress,
+                format!("account {} is not an account of this VASP", my_account),
+                cid,
+            ));
+        }
+
+        let _processing = self.processing.lock().await;
+        let prior = self.store.payment_command(payment.reference_id()).await?;
+        if let Some(prior) = &prior {
+            // Counterparties retry commands they did not receive a response for
+            if prior.cid() == cid {
+                return if *prior.payment() == payment {
+                    Ok(cid)
+                } else {
+                    Err(command_error(
+                        ErrorCode::Conflict,
+                        "cid is already used by a different command",
+                        cid,
+                    ))
+                };
+            }
+        }
+        let command = PaymentCommand::new(payment, Origin::Inbound, my_actor, cid, prior.as_ref())
+            .map_err(|error| command_error(error.error_code(), error.to_string(), cid))?;
+        self.store.save_payment_command(command).await?;
+        Ok(cid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        payment_command::PaymentState,
+        storage::InMemoryCommandStore,
+        test_utils::{actor, initial_payment, Vasp},
+        types::{PaymentObject, Status},
+    };
+    use std::collections::BTreeMap;
+
+    // A server for `receiver`, which knows the credential of `sender`
+    fn server(sender: &Vasp, receiver: &Vasp) -> (OffchainServer, Arc<InMemoryCommandStore>) {
+        let credentials: BTreeMap<_, _> =
+            vec![(sender.account, sender.credential("http://localhost"))]
+                .into_iter()
+                .collect();
+        let store = Arc::new(InMemoryCommandStore::new());
+        let server = OffchainServer::new(
+            vec![receiver.account],
+            receiver.compliance_key(),
+            Arc::new(credentials),
+            store.clone(),
+        );
+        (server, store)
+    }
+
+    fn error_code(response: &CommandResponseObject) -> &ErrorCode {
+        assert_eq!(*response.status(), CommandStatus::Failure);
+        response.error().unwrap().code()
+    }
+
+    #[tokio::test]
+    async fn accepts_initial_payment_command() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let (server, store) = server(&sender, &receiver);
+        let payment = initial_payment(&sender, &receiver);
+        let cid = Uuid::new_v4();
+        let request_id = Uuid::new_v4().to_string();
+
+        let response = server
+            .handle_request(
+                Some(&request_id),
+                Some(&sender.address),
+                sender.sign(payment.clone(), cid).as_bytes(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response, CommandResponseObject::success(cid));
+
+        let command = store
+            .payment_command(payment.reference_id())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(command.payment_state(), PaymentState::SenderInit);
+        assert_eq!(command.my_actor(), Actor::Receiver);
+        assert_eq!(command.cid(), cid);
+    }
+
+    #[tokio::test]
+    async fn accepts_retried_command_and_rejects_reused_cid() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let (server, _) = server(&sender, &receiver);
+        let payment = initial_payment(&sender, &receiver);
+        let cid = Uuid::new_v4();
+        let request_id = Uuid::new_v4().to_string();
+        let send = |payment: PaymentObject| {
+            let request = sender.sign(payment, cid);
+            let server = &server;
+            let request_id = &request_id;
+            let sender_address = &sender.address;
+            async move {
+                server
+                    .handle_request(Some(request_id), Some(sender_address), request.as_bytes())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        assert_eq!(
+            send(payment.clone()).await,
+            CommandResponseObject::success(cid)
+        );
+        assert_eq!(
+            send(payment.clone()).await,
+            CommandResponseObject::success(cid)
+        );
+        let changed_payment = PaymentObject {
+            description: Some("changed".to_string()),
+            ..payment
+        };
+        assert_eq!(
+            error_code(&send(changed_payment).await),
+            &ErrorCode::Conflict
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let (server, _) = server(&sender, &receiver);
+        let request_id = Uuid::new_v4().to_string();
+        let request = sender.sign(initial_payment(&sender, &receiver), Uuid::new_v4());
+
+        let response = server
+            .handle_request(None, Some(&sender.address), request.as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(error_code(&response), &ErrorCode::MissingHttpHeader);
+
+        let impostor = Vasp::new();
+        let forged_request = impostor.sign(initial_payment(&sender, &receiver), Uuid::new_v4());
+        let response = server
+            .handle_request(
+                Some(&request_id),
+                Some(&sender.address),
+                forged_request.as_bytes(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(error_code(&response), &ErrorCode::InvalidJwsSignature);
+        assert_eq!(response.cid(), None);
+
+        let response = server
+            .handle_request(Some(&request_id), Some(&sender.address), b"{}")
+            .await
+            .unwrap();
+        assert_eq!(error_code(&response), &ErrorCode::InvalidJws);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_transitions() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let (server, _) = server(&sender, &receiver);
+        let request_id = Uuid::new_v4().to_string();
+
+        // A payment both actors are ready to settle cannot start a payment
+        let mut payment = initial_payment(&sender, &receiver);
+        payment.sender.status.status = Status::ReadyForSettlement;
+        payment.receiver = actor(&receiver.address, Status::ReadyForSettlement, true);
+        let cid = Uuid::new_v4();
+        let response = server
+            .handle_request(
+                Some(&request_id),
+                Some(&sender.address),
+                sender.sign(payment, cid).as_bytes(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            error_code(&response),
+            &ErrorCode::InvalidInitialOrPriorNotFound
+        );
+        assert_eq!(response.cid(), Some(cid));
+
+        // Only the receiver can abort a payment the sender just initiated
+        let payment = initial_payment(&sender, &receiver);
+        let response = server
+            .handle_request(
+                Some(&request_id),
+                Some(&sender.address),
+                sender.sign(payment.clone(), Uuid::new_v4()).as_bytes(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(*response.status(), CommandStatus::Success);
+        let mut abort = payment;
+        abort.receiver.status.status = Status::Abort;
+        let response = server
+            .handle_request(
+                Some(&request_id),
+                Some(&sender.address),
+                sender.sign(abort, Uuid::new_v4()).as_bytes(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(error_code(&response), &ErrorCode::InvalidCommandProducer);
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_requests() {
+        let (sender, receiver) = (Vasp::new(), Vasp::new());
+        let (server, _) = server(&sender, &receiver);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/v2/command")
+            .header(REQUEST_ID_HEADER, Uuid::new_v4().to_string())
+            .header(REQUEST_SENDER_ADDRESS, sender.address.as_str())
+            .body(vec![b'a'; MAX_REQUEST_SIZE as usize + 1])
+            .reply(&Arc::new(server).routes())
+            .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::payment_command::PaymentCommand;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+pub type StorageError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Persists the latest command of every payment, inbound or outbound.
+#[async_trait]
+pub trait CommandStore: Send + Sync {
+    /// The latest command of the payment with `reference_id`, if any.
+    async fn payment_command(
+        &self,
+        reference_id: Uuid,
+    ) -> Result<Option<PaymentCommand>, StorageError>;
+
+    /// Record `command` as the latest command of its payment.
+    async fn save_payment_command(&self, command: PaymentCommand) -> Result<(), StorageError>;
+}
+
+/// A store keeping commands in memory, which loses them on restart.
+#[derive(Default)]
+pub struct InMemoryCommandStore {
+    payment_commands: Mutex<HashMap<Uuid, PaymentCommand>>,
+}
+
+impl InMemoryCommandStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CommandStore for InMemoryCommandStore {
+    async fn payment_command(
+        &self,
+        reference_id: Uuid,
+    ) -> Result<Option<PaymentCommand>, StorageError> {
+        Ok(self
+            .payment_commands
+            .lock()
+            .await
+            .get(&reference_id)
+            .cloned())
+    }
+
+    async fn save_payment_command(&self, command: PaymentCommand) -> Result<(), StorageError> {
+        self.payment_commands
+            .lock()
+            .await
+            .insert(command.reference_id(), command);
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! VASPs and payments shared by the tests of the off-chain server and client.
+
+use crate::{
+    credentials::DualAttestationCredential,
+    identifier::{encode_account, HumanReadablePrefix},
+    jws,
+    subaddress::Subaddress,
+    types::{
+        ActionType, Command, CommandRequestObject, KycDataObject, PaymentActionObject,
+        PaymentActorObject, PaymentCommandObject, PaymentObject, Status, StatusObject,
+    },
+};
+use diem_sdk::{crypto::ed25519::ed25519_dalek::Keypair, types::account_address::AccountAddress};
+use uuid::Uuid;
+
+pub struct Vasp {
+    pub account: AccountAddress,
+    pub address: String,
+    pub keypair: Keypair,
+}
+
+impl Vasp {
+    pub fn new() -> Self {
+        let account = AccountAddress::random();
+        let subaddress = Subaddress::generate(&mut rand_core::OsRng);
+        Self {
+            account,
+            address: encode_account(HumanReadablePrefix::TDM, account, subaddress).unwrap(),
+            keypair: Keypair::generate(&mut rand_core::OsRng),
+        }
+    }
+
+    /// A copy of the compliance key of the VASP, for the server or client signing for it.
+    pub fn compliance_key(&self) -> Keypair {
+        Keypair::from_bytes(&self.keypair.to_bytes()).unwrap()
+    }
+
+    pub fn credential(&self, base_url: &str) -> DualAttestationCredential {
+        DualAttestationCredential {
+            base_url: base_url.to_string(),
+            compliance_key: self.keypair.public,
+        }
+    }
+
+    pub fn sign(&self, payment: PaymentObject, cid: Uuid) -> String {
+        let command = Command::PaymentCommand(PaymentCommandObject::new(payment));
+        jws::serialize(&CommandRequestObject::new(command, cid), &self.keypair).unwrap()
+    }
+}
+
+pub fn actor(address: &str, status: Status, with_kyc_data: bool) -> PaymentActorObject {
+    PaymentActorObject {
+        address: address.into(),
+        kyc_data: if with_kyc_data {
+            Some(KycDataObject::new_individual())
+        } else {
+            None
+        },
+        status: StatusObject {
+            status,
+            abort_code: None,
+            abort_message: None,
+        },
+        metadata: vec![],
+        additional_kyc_data: None,
+    }
+}
+
+pub fn initial_payment(sender: &Vasp, receiver: &Vasp) -> PaymentObject {
+    PaymentObject {
+        sender: actor(&sender.address, Status::NeedsKycData, true),
+        receiver: actor(&receiver.address, Status::None, false),
+        reference_id: Uuid::new_v4(),
+        originial_payment_reference_id: None,
+        recipient_signature: None,
+        action: PaymentActionObject {
+            amount: 1_000_000,
+            currency: "XUS".to_string(),
+            action: ActionType::Charge,
+            timestamp: 0,
+        },
+        description: None,
+    }
+}
@@ -117,6 +117,36 @@ impl CommandResponseObject {
             cid: None,
         }
     }
+
+    /// A response acknowledging that the command with `cid` was processed successfully.
+    pub fn success(cid: Uuid) -> Self {
+        Self {
+            cid: Some(cid),
+            ..Self::new(CommandStatus::Success)
+        }
+    }
+
+    /// A response rejecting a request with `error`, along with the `cid` of the rejected command
+    /// if the request could be parsed.
+    pub fn failure(error: OffChainError, cid: Option<Uuid>) -> Self {
+        Self {
+            error: Some(error),
+            cid,
+            ..Self::new(CommandStatus::Failure)
+        }
+    }
+
+    pub fn status(&self) -> &CommandStatus {
+        &self.status
+    }
+
+    pub fn error(&self) -> Option<&OffChainError> {
+        self.error.as_ref()
+    }
+
+    pub fn cid(&self) -> Option<Uuid> {
+        self.cid
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -257,6 +287,41 @@ pub struct OffChainError {
     message: Option<String>,
 }
 
+impl OffChainError {
+    /// An error in the HTTP headers, JWS or JSON of a request, i.e., before its command object
+    /// could be processed.
+    pub fn protocol_error(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            error_type: OffChainErrorType::Protocol,
+            field: None,
+            code,
+            message: Some(message.into()),
+        }
+    }
+
+    /// An error in the command object of a request.
+    pub fn command_error(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            error_type: OffChainErrorType::Command,
+            field: None,
+            code,
+            message: Some(message.into()),
+        }
+    }
+
+    pub fn error_type(&self) -> &OffChainErrorType {
+        &self.error_type
+    }
+
+    pub fn code(&self) -> &ErrorCode {
+        &self.code
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(tag = "command_type", content = "command")]
 pub enum Command {
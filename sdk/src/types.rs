@@ -2,14 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    client::AccountAddress,
     crypto::{
-        ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-        traits::Uniform,
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+        multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+        traits::{signing_message, Signature, SigningKey, Uniform},
     },
+    move_types::account_address::AccountAddress,
     transaction_builder::TransactionBuilder,
-    types::transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+    types::transaction::{
+        authenticator::{AccountAuthenticator, AuthenticationKey, TransactionAuthenticator},
+        RawTransaction, RawTransactionWithData, SignedTransaction,
+    },
 };
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
 
 pub use diem_types::*;
 
@@ -162,3 +169,542 @@ impl From<Ed25519PrivateKey> for AccountKey {
         Self::from_private_key(private_key)
     }
 }
+
+/// The public key an account authenticates its transactions with.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AccountPublicKey {
+    Ed25519(Ed25519PublicKey),
+    /// A K-of-N key, where K of the N keys need to sign a transaction
+    MultiEd25519(MultiEd25519PublicKey),
+}
+
+impl AccountPublicKey {
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        match self {
+            AccountPublicKey::Ed25519(public_key) => AuthenticationKey::ed25519(public_key),
+            AccountPublicKey::MultiEd25519(public_key) => {
+                AuthenticationKey::multi_ed25519(public_key)
+            }
+        }
+    }
+
+    fn public_keys(&self) -> &[Ed25519PublicKey] {
+        match self {
+            AccountPublicKey::Ed25519(public_key) => std::slice::from_ref(public_key),
+            AccountPublicKey::MultiEd25519(public_key) => public_key.public_keys(),
+        }
+    }
+
+    fn threshold(&self) -> usize {
+        match self {
+            AccountPublicKey::Ed25519(_) => 1,
+            AccountPublicKey::MultiEd25519(public_key) => *public_key.threshold() as usize,
+        }
+    }
+}
+
+impl From<Ed25519PublicKey> for AccountPublicKey {
+    fn from(public_key: Ed25519PublicKey) -> Self {
+        AccountPublicKey::Ed25519(public_key)
+    }
+}
+
+impl From<MultiEd25519PublicKey> for AccountPublicKey {
+    fn from(public_key: MultiEd25519PublicKey) -> Self {
+        AccountPublicKey::MultiEd25519(public_key)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PartialSigningError {
+    #[error("{0} is not a signer of the transaction")]
+    UnknownSigner(AccountAddress),
+    #[error("the key is not a key of signer {0}")]
+    UnknownKey(AccountAddress),
+    #[error("the signature of signer {0} does not verify")]
+    InvalidSignature(AccountAddress),
+    #[error("signers {0:?} have not signed the transaction yet")]
+    MissingSignatures(Vec<AccountAddress>),
+    #[error("{0} is a signer of the transaction more than once")]
+    DuplicateSigner(AccountAddress),
+}
+
+/// The signatures collected for one of the signers of a transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+struct SignerSignatures {
+    address: AccountAddress,
+    public_key: AccountPublicKey,
+    /// Signatures by the index of the signing key among the keys of `public_key`
+    signatures: BTreeMap<u8, Ed25519Signature>,
+}
+
+impl SignerSignatures {
+    fn new(address: AccountAddress, public_key: AccountPublicKey) -> Self {
+        Self {
+            address,
+            public_key,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.signatures.len() >= self.public_key.threshold()
+    }
+
+    /// Check that every signature signs `message` with the key it is recorded for.
+    fn verify(&self, message: &[u8]) -> Result<(), PartialSigningError> {
+        let public_keys = self.public_key.public_keys();
+        for (index, signature) in &self.signatures {
+            public_keys
+                .get(*index as usize)
+                .and_then(|public_key| signature.verify_arbitrary_msg(message, public_key).ok())
+                .ok_or(PartialSigningError::InvalidSignature(self.address))?;
+        }
+        Ok(())
+    }
+
+    fn authenticator(&self) -> AccountAuthenticator {
+        match &self.public_key {
+            AccountPublicKey::Ed25519(public_key) => {
+                AccountAuthenticator::ed25519(public_key.clone(), self.signatures[&0].clone())
+            }
+            AccountPublicKey::MultiEd25519(public_key) => {
+                let signatures = self
+                    .signatures
+                    .iter()
+                    .take(self.public_key.threshold())
+                    .map(|(index, signature)| (signature.clone(), *index))
+                    .collect();
+                AccountAuthenticator::multi_ed25519(
+                    public_key.clone(),
+                    MultiEd25519Signature::new(signatures)
+                        .expect("signature indices are unique and in range"),
+                )
+            }
+        }
+    }
+}
+
+/// A transaction whose signatures are collected one at a time, e.g., from signers on different
+/// machines. It can be serialized with BCS to be passed between signers.
+///
+/// Signers are the sender of the transaction and, for multi-agent transactions, its secondary
+/// signers. Each signer authenticates with either a single Ed25519 key or a K-of-N
+/// `MultiEd25519` key, in which case K of the N key holders need to sign. Signatures are verified
+/// as they are added, so that a bad signature is caught before it is passed on.
+///
+/// ## Example
+///
+/// ```
+/// use diem_sdk::{
+///     crypto::{ed25519::Ed25519PrivateKey, multi_ed25519::MultiEd25519PublicKey, traits::Uniform},
+///     transaction_builder::{Currency, TransactionFactory},
+///     types::{
+///         account_address::AccountAddress, chain_id::ChainId, AccountPublicKey,
+///         PartiallySignedTransaction,
+///     },
+/// };
+/// use rand_core::OsRng;
+///
+/// // A 2-of-3 account
+/// let private_keys: Vec<_> = (0..3).map(|_| Ed25519PrivateKey::generate(&mut OsRng)).collect();
+/// let public_key = AccountPublicKey::MultiEd25519(
+///     MultiEd25519PublicKey::new(private_keys.iter().map(Into::into).collect(), 2).unwrap(),
+/// );
+/// let sender = public_key.authentication_key().derived_address();
+/// let raw_txn = TransactionFactory::new(ChainId::test())
+///     .peer_to_peer(Currency::XUS, AccountAddress::random(), 10)
+///     .sender(sender)
+///     .sequence_number(0)
+///     .build();
+///
+/// let mut txn = PartiallySignedTransaction::new(raw_txn, public_key);
+/// txn.sign(sender, &private_keys[0]).unwrap();
+///
+/// // Pass the transaction on to the holder of the third key
+/// let mut txn: PartiallySignedTransaction =
+///     bcs::from_bytes(&bcs::to_bytes(&txn).unwrap()).unwrap();
+/// assert_eq!(txn.missing_signers(), vec![sender]);
+/// txn.sign(sender, &private_keys[2]).unwrap();
+///
+/// let signed_txn = txn.into_signed_transaction().unwrap();
+/// assert!(signed_txn.check_signature().is_ok());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PartiallySignedTransaction {
+    raw_txn: RawTransaction,
+    sender: SignerSignatures,
+    /// The secondary signers of a multi-agent transaction, `None` for other transactions
+    secondary_signers: Option<Vec<SignerSignatures>>,
+}
+
+impl PartiallySignedTransaction {
+    /// A transaction to be signed by its sender, authenticating with `sender_key`.
+    pub fn new<T: Into<AccountPublicKey>>(raw_txn: RawTransaction, sender_key: T) -> Self {
+        Self {
+            sender: SignerSignatures::new(raw_txn.sender(), sender_key.into()),
+            raw_txn,
+            secondary_signers: None,
+        }
+    }
+
+    /// A multi-agent transaction to be signed by its sender and by `secondary_signers`, given
+    /// with the key each of them authenticates with. Fails if an account is given more than once,
+    /// including as both the sender and a secondary signer.
+    pub fn new_multi_agent<T: Into<AccountPublicKey>>(
+        raw_txn: RawTransaction,
+        sender_key: T,
+        secondary_signers: Vec<(AccountAddress, AccountPublicKey)>,
+    ) -> Result<Self, PartialSigningError> {
+        let txn = Self {
+            sender: SignerSignatures::new(raw_txn.sender(), sender_key.into()),
+            raw_txn,
+            secondary_signers: Some(
+                secondary_signers
+                    .into_iter()
+                    .map(|(address, public_key)| SignerSignatures::new(address, public_key))
+                    .collect(),
+            ),
+        };
+        txn.check_distinct_signers()?;
+        Ok(txn)
+    }
+
+    pub fn raw_transaction(&self) -> &RawTransaction {
+        &self.raw_txn
+    }
+
+    /// The message every signer signs, for signers signing with external tools, e.g., an HSM.
+    pub fn signing_message(&self) -> Vec<u8> {
+        match self.multi_agent_message() {
+            Some(message) => signing_message(&message),
+            None => self.raw_txn.signing_message(),
+        }
+    }
+
+    /// Sign the transaction on behalf of `signer` with `private_key`, one of its keys.
+    pub fn sign(
+        &mut self,
+        signer: AccountAddress,
+        private_key: &Ed25519PrivateKey,
+    ) -> Result<(), PartialSigningError> {
+        let signature = match self.multi_agent_message() {
+            Some(message) => private_key.sign(&message),
+            None => private_key.sign(&self.raw_txn),
+        };
+        self.add_signature(signer, &private_key.into(), signature)
+    }
+
+    /// Add the signature of the transaction by `public_key`, one of the keys of `signer`.
+    pub fn add_signature(
+        &mut self,
+        signer: AccountAddress,
+        public_key: &Ed25519PublicKey,
+        signature: Ed25519Signature,
+    ) -> Result<(), PartialSigningError> {
+        let verified = match self.multi_agent_message() {
+            Some(message) => signature.verify(&message, public_key),
+            None => signature.verify(&self.raw_txn, public_key),
+        };
+        verified.map_err(|_| PartialSigningError::InvalidSignature(signer))?;
+
+        let signer_signatures = self
+            .signers_mut()
+            .find(|signer_signatures| signer_signatures.address == signer)
+            .ok_or(PartialSigningError::UnknownSigner(signer))?;
+        let index = signer_signatures
+            .public_key
+            .public_keys()
+            .iter()
+            .position(|key| key == public_key)
+            .ok_or(PartialSigningError::UnknownKey(signer))?;
+        signer_signatures.signatures.insert(index as u8, signature);
+        Ok(())
+    }
+
+    /// The signers that have not collected enough signatures yet.
+    pub fn missing_signers(&self) -> Vec<AccountAddress> {
+        self.signers()
+            .filter(|signer_signatures| !signer_signatures.is_complete())
+            .map(|signer_signatures| signer_signatures.address)
+            .collect()
+    }
+
+    pub fn is_fully_signed(&self) -> bool {
+        self.signers()
+            .all(|signer_signatures| signer_signatures.is_complete())
+    }
+
+    /// The signed transaction, once every signer has collected enough signatures.
+    ///
+    /// The signers and signatures are checked again, as a transaction deserialized from an
+    /// untrusted source may not have been built through `new_multi_agent` and `add_signature`.
+    pub fn into_signed_transaction(self) -> Result<SignedTransaction, PartialSigningError> {
+        self.check_distinct_signers()?;
+        let missing_signers = self.missing_signers();
+        if !missing_signers.is_empty() {
+            return Err(PartialSigningError::MissingSignatures(missing_signers));
+        }
+        let message = self.signing_message();
+        for signer_signatures in self.signers() {
+            signer_signatures.verify(&message)?;
+        }
+
+        let authenticator = match &self.secondary_signers {
+            Some(secondary_signers) => TransactionAuthenticator::multi_agent(
+                self.sender.authenticator(),
+                secondary_signers
+                    .iter()
+                    .map(|signer_signatures| signer_signatures.address)
+                    .collect(),
+                secondary_signers
+                    .iter()
+                    .map(SignerSignatures::authenticator)
+                    .collect(),
+            ),
+            None => match self.sender.authenticator() {
+                AccountAuthenticator::Ed25519 {
+                    public_key,
+                    signature,
+                } => TransactionAuthenticator::Ed25519 {
+                    public_key,
+                    signature,
+                },
+                AccountAuthenticator::MultiEd25519 {
+                    public_key,
+                    signature,
+                } => TransactionAuthenticator::MultiEd25519 {
+                    public_key,
+                    signature,
+                },
+            },
+        };
+        Ok(SignedTransaction::new_with_authenticator(
+            self.raw_txn,
+            authenticator,
+        ))
+    }
+
+    fn multi_agent_message(&self) -> Option<RawTransactionWithData> {
+        self.secondary_signers.as_ref().map(|secondary_signers| {
+            RawTransactionWithData::new_multi_agent(
+                self.raw_txn.clone(),
+                secondary_signers
+                    .iter()
+                    .map(|signer_signatures| signer_signatures.address)
+                    .collect(),
+            )
+        })
+    }
+
+    fn check_distinct_signers(&self) -> Result<(), PartialSigningError> {
+        let mut addresses = BTreeSet::new();
+        for signer_signatures in self.signers() {
+            if !addresses.insert(signer_signatures.address) {
+                return Err(PartialSigningError::DuplicateSigner(
+                    signer_signatures.address,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn signers(&self) -> impl Iterator<Item = &SignerSignatures> {
+        std::iter::once(&self.sender).chain(self.secondary_signers.iter().flatten())
+    }
+
+    fn signers_mut(&mut self) -> impl Iterator<Item = &mut SignerSignatures> {
+        std::iter::once(&mut self.sender).chain(self.secondary_signers.iter_mut().flatten())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transaction_builder::{Currency, TransactionFactory},
+        types::chain_id::ChainId,
+    };
+    use rand_core::OsRng;
+
+    fn raw_txn(sender: AccountAddress) -> RawTransaction {
+        TransactionFactory::new(ChainId::test())
+            .peer_to_peer(Currency::XUS, AccountAddress::random(), 10)
+            .sender(sender)
+            .sequence_number(0)
+            .build()
+    }
+
+    fn ed25519_signer() -> (AccountAddress, Ed25519PrivateKey, AccountPublicKey) {
+        let private_key = Ed25519PrivateKey::generate(&mut OsRng);
+        let public_key = AccountPublicKey::Ed25519((&private_key).into());
+        (
+            public_key.authentication_key().derived_address(),
+            private_key,
+            public_key,
+        )
+    }
+
+    fn multi_ed25519_signer(
+        threshold: u8,
+        count: usize,
+    ) -> (AccountAddress, Vec<Ed25519PrivateKey>, AccountPublicKey) {
+        let private_keys: Vec<_> = (0..count)
+            .map(|_| Ed25519PrivateKey::generate(&mut OsRng))
+            .collect();
+        let public_key = AccountPublicKey::MultiEd25519(
+            MultiEd25519PublicKey::new(private_keys.iter().map(Into::into).collect(), threshold)
+                .unwrap(),
+        );
+        (
+            public_key.authentication_key().derived_address(),
+            private_keys,
+            public_key,
+        )
+    }
+
+    #[test]
+    fn multi_agent() {
+        let (sender, sender_key, sender_public_key) = ed25519_signer();
+        let (secondary, secondary_keys, secondary_public_key) = multi_ed25519_signer(2, 3);
+        let mut txn = PartiallySignedTransaction::new_multi_agent(
+            raw_txn(sender),
+            sender_public_key,
+            vec![(secondary, secondary_public_key)],
+        )
+        .unwrap();
+        assert_eq!(txn.missing_signers(), vec![sender, secondary]);
+
+        txn.sign(sender, &sender_key).unwrap();
+        txn.sign(secondary, &secondary_keys[1]).unwrap();
+        assert_eq!(txn.missing_signers(), vec![secondary]);
+        txn.sign(secondary, &secondary_keys[2]).unwrap();
+        assert!(txn.is_fully_signed());
+
+        let signed_txn = txn.into_signed_transaction().unwrap();
+        assert_eq!(
+            signed_txn.authenticator().secondary_signer_addreses(),
+            vec![secondary]
+        );
+        assert!(signed_txn.check_signature().is_ok());
+    }
+
+    #[test]
+    fn rejects_duplicate_signers() {
+        let (sender, _, sender_public_key) = ed25519_signer();
+        assert!(matches!(
+            PartiallySignedTransaction::new_multi_agent(
+                raw_txn(sender),
+                sender_public_key.clone(),
+                vec![(sender, sender_public_key.clone())],
+            ),
+            Err(PartialSigningError::DuplicateSigner(address)) if address == sender
+        ));
+
+        let (secondary, _, secondary_public_key) = ed25519_signer();
+        assert!(matches!(
+            PartiallySignedTransaction::new_multi_agent(
+                raw_txn(sender),
+                sender_public_key,
+                vec![
+                    (secondary, secondary_public_key.clone()),
+                    (secondary, secondary_public_key),
+                ],
+            ),
+            Err(PartialSigningError::DuplicateSigner(address)) if address == secondary
+        ));
+    }
+
+    #[test]
+    fn rejects_wrong_signer_and_key() {
+        let (sender, _, sender_public_key) = ed25519_signer();
+        let mut txn = PartiallySignedTransaction::new(raw_txn(sender), sender_public_key);
+        let other_key = Ed25519PrivateKey::generate(&mut OsRng);
+
+        assert!(matches!(
+            txn.sign(sender, &other_key),
+            Err(PartialSigningError::UnknownKey(address)) if address == sender
+        ));
+        let other_signer = AccountAddress::random();
+        assert!(matches!(
+            txn.sign(other_signer, &other_key),
+            Err(PartialSigningError::UnknownSigner(address)) if address == other_signer
+        ));
+        assert_eq!(txn.missing_signers(), vec![sender]);
+    }
+
+    #[test]
+    fn rejects_bad_signature() {
+        let (sender, sender_key, sender_public_key) = ed25519_signer();
+        let mut txn = PartiallySignedTransaction::new(raw_txn(sender), sender_public_key);
+        // A signature of another transaction
+        let signature = sender_key.sign(&raw_txn(AccountAddress::random()));
+
+        assert!(matches!(
+            txn.add_signature(sender, &(&sender_key).into(), signature),
+            Err(PartialSigningError::InvalidSignature(address)) if address == sender
+        ));
+        assert!(!txn.is_fully_signed());
+    }
+
+    #[test]
+    fn requires_threshold() {
+        let (sender, private_keys, public_key) = multi_ed25519_signer(2, 3);
+        let mut txn = PartiallySignedTransaction::new(raw_txn(sender), public_key);
+        txn.sign(sender, &private_keys[0]).unwrap();
+        // Signing twice with the same key does not count twice
+        txn.sign(sender, &private_keys[0]).unwrap();
+
+        assert_eq!(txn.missing_signers(), vec![sender]);
+        assert!(matches!(
+            txn.into_signed_transaction(),
+            Err(PartialSigningError::MissingSignatures(signers)) if signers == vec![sender]
+        ));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let (sender, sender_key, sender_public_key) = ed25519_signer();
+        let (secondary, secondary_key, secondary_public_key) = ed25519_signer();
+        let mut txn = PartiallySignedTransaction::new_multi_agent(
+            raw_txn(sender),
+            sender_public_key,
+            vec![(secondary, secondary_public_key)],
+        )
+        .unwrap();
+        txn.sign(sender, &sender_key).unwrap();
+
+        let mut txn: PartiallySignedTransaction =
+            bcs::from_bytes(&bcs::to_bytes(&txn).unwrap()).unwrap();
+        assert_eq!(txn.missing_signers(), vec![secondary]);
+        txn.sign(secondary, &secondary_key).unwrap();
+        assert!(txn
+            .into_signed_transaction()
+            .unwrap()
+            .check_signature()
+            .is_ok());
+    }
+
+    #[test]
+    fn verifies_deserialized_signatures() {
+        let (sender, sender_key, sender_public_key) = ed25519_signer();
+        let mut txn = PartiallySignedTransaction::new(raw_txn(sender), sender_public_key);
+        // Signatures that were never checked, as if they came from a tampered serialization
+        txn.sender
+            .signatures
+            .insert(0, sender_key.sign(&raw_txn(AccountAddress::random())));
+        assert!(matches!(
+            txn.clone().into_signed_transaction(),
+            Err(PartialSigningError::InvalidSignature(address)) if address == sender
+        ));
+
+        txn.sender.signatures.clear();
+        txn.sender
+            .signatures
+            .insert(1, sender_key.sign(txn.raw_transaction()));
+        assert!(matches!(
+            txn.into_signed_transaction(),
+            Err(PartialSigningError::InvalidSignature(address)) if address == sender
+        ));
+    }
+}
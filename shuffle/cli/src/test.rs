@@ -169,6 +169,7 @@ pub fn run_move_unit_tests(project_path: &Path) -> Result<UnitTestResult> {
         unit_test_config,
         diem_vm::natives::diem_natives(),
         false,
+        false,
     )
 }
 
@@ -26,7 +26,7 @@ use diem_types::{
 };
 use futures::StreamExt;
 use network::{
-    application::interface::NetworkInterface,
+    application::{interface::NetworkInterface, reputation::Misbehavior},
     protocols::{rpc::error::RpcError, wire::handshake::v1::ProtocolId},
 };
 use rand::seq::SliceRandom;
@@ -239,7 +239,13 @@ impl DiemNetDataClient {
                 let client_err = match err {
                     storage_service_client::Error::RpcError(err) => match err {
                         RpcError::NotConnected(_) => Error::DataIsUnavailable(err.to_string()),
+                        // An honest but overloaded peer can time out too, so
+                        // timeouts only count against its score here
                         RpcError::TimedOut => Error::TimeoutWaitingForResponse(err.to_string()),
+                        RpcError::MalformedResponse(_) | RpcError::InvalidRpcResponse => {
+                            self.report_misbehavior(peer, Misbehavior::MalformedMessage);
+                            Error::UnexpectedErrorEncountered(err.to_string())
+                        }
                         _ => Error::UnexpectedErrorEncountered(err.to_string()),
                     },
                     storage_service_client::Error::StorageServiceError(err) => {
@@ -260,10 +266,22 @@ impl DiemNetDataClient {
         _request: &StorageServiceRequest,
         error_type: ErrorType,
     ) {
+        if let ErrorType::Malicious = error_type {
+            self.report_misbehavior(peer, Misbehavior::InvalidProof);
+        }
         self.peer_states
             .write()
             .update_score_error(peer, error_type);
     }
+
+    /// Reports misbehavior to the node-wide peer reputation, which bans peers
+    /// that misbehave too often.
+    fn report_misbehavior(&self, peer: PeerNetworkId, misbehavior: Misbehavior) {
+        self.network_client
+            .peer_metadata_storage()
+            .reputation()
+            .report(peer, misbehavior);
+    }
 }
 
 #[async_trait]
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{DataSummaryPoller, DiemDataClient, DiemNetDataClient, Error};
+use bytes::Bytes;
 use channel::{diem_channel, message_queues::QueueStyle};
 use claim::{assert_err, assert_matches};
 use diem_config::{
@@ -16,12 +17,12 @@ use diem_types::{
     transaction::{TransactionListWithProof, Version},
     PeerId,
 };
-use futures::StreamExt;
+use futures::{channel::oneshot, StreamExt};
 use maplit::hashmap;
 use network::{
     application::{interface::MultiNetworkSender, storage::PeerMetadataStorage},
     peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
-    protocols::{network::NewNetworkSender, wire::handshake::v1::ProtocolId},
+    protocols::{network::NewNetworkSender, rpc::error::RpcError, wire::handshake::v1::ProtocolId},
     transport::ConnectionMetadata,
 };
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
@@ -72,14 +73,16 @@ impl MockNetwork {
         let (peer_mgr_reqs_tx, peer_mgr_reqs_rx) = queue_cfg.build();
         let (connection_reqs_tx, _connection_reqs_rx) = queue_cfg.build();
 
+        let network_sender = StorageServiceNetworkSender::new(
+            PeerManagerRequestSender::new(peer_mgr_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        );
         let network_sender = MultiNetworkSender::new(hashmap! {
-            NetworkId::Validator => StorageServiceNetworkSender::new(
-                PeerManagerRequestSender::new(peer_mgr_reqs_tx),
-                ConnectionRequestSender::new(connection_reqs_tx),
-            )
+            NetworkId::Validator => network_sender.clone(),
+            NetworkId::Public => network_sender,
         });
 
-        let peer_infos = PeerMetadataStorage::new(&[NetworkId::Validator]);
+        let peer_infos = PeerMetadataStorage::new(&[NetworkId::Validator, NetworkId::Public]);
         let network_client = StorageServiceClient::new(network_sender, peer_infos.clone());
 
         let mock_time = TimeService::mock();
@@ -99,7 +102,11 @@ impl MockNetwork {
 
     /// Add a new random connected peer to the network peer DB
     fn add_connected_peer(&mut self) -> PeerNetworkId {
-        let network_id = NetworkId::Validator;
+        self.add_connected_peer_on(NetworkId::Validator)
+    }
+
+    /// Add a new random connected peer on the given network to the network peer DB
+    fn add_connected_peer_on(&mut self, network_id: NetworkId) -> PeerNetworkId {
         let peer_id = PeerId::random();
         let mut connection_metadata = ConnectionMetadata::mock(peer_id);
         connection_metadata
@@ -113,20 +120,37 @@ impl MockNetwork {
 
     /// Get the next request sent from the client.
     async fn next_request(&mut self) -> Option<NetworkRequest> {
+        self.next_raw_request()
+            .await
+            .map(|(peer_id, protocol, request, res_tx)| {
+                let response_sender = ResponseSender::new(protocol, res_tx);
+                (peer_id, protocol, request, response_sender)
+            })
+    }
+
+    /// Get the next request sent from the client, along with the channel to
+    /// respond with raw bytes or an rpc error.
+    async fn next_raw_request(
+        &mut self,
+    ) -> Option<(
+        PeerId,
+        ProtocolId,
+        StorageServiceRequest,
+        oneshot::Sender<Result<Bytes, RpcError>>,
+    )> {
         match self.peer_mgr_reqs_rx.next().await {
             Some(PeerManagerRequest::SendRpc(peer_id, network_request)) => {
                 let protocol = network_request.protocol_id;
                 let data = network_request.data;
                 let res_tx = network_request.res_tx;
 
-                let message: StorageServiceMessage = bcs::from_bytes(data.as_ref()).unwrap();
+                let message: StorageServiceMessage = protocol.from_bytes(data.as_ref()).unwrap();
                 let request = match message {
                     StorageServiceMessage::Request(request) => request,
                     _ => panic!("unexpected: {:?}", message),
                 };
-                let response_sender = ResponseSender::new(res_tx);
 
-                Some((peer_id, protocol, request, response_sender))
+                Some((peer_id, protocol, request, res_tx))
             }
             Some(PeerManagerRequest::SendDirectSend(_, _)) => panic!("Unexpected direct send msg"),
             None => None,
@@ -353,6 +377,62 @@ async fn bad_peer_is_eventually_banned_callback() {
         .contains(&CompleteDataRange::new(0, 200).unwrap()));
 }
 
+#[tokio::test]
+async fn malformed_responses_get_peer_banned() {
+    ::diem_logger::Logger::init_for_testing();
+    let (mut mock_network, _mock_time, client, _poller) = MockNetwork::new();
+    let peer_infos = mock_network.peer_infos.clone();
+
+    let bad_peer = mock_network.add_connected_peer_on(NetworkId::Public);
+    client.update_summary(bad_peer, mock_storage_summary(200));
+    client.update_global_summary_cache();
+
+    // The peer responds with bytes that don't deserialize.
+    tokio::spawn(async move {
+        while let Some((_, _, _, res_tx)) = mock_network.next_raw_request().await {
+            let _ = res_tx.send(Ok(vec![0xff; 4].into()));
+        }
+    });
+
+    while !peer_infos.reputation().is_banned(bad_peer) {
+        let result = client
+            .get_transactions_with_proof(200, 200, 200, false)
+            .await;
+        assert_matches!(result, Err(Error::UnexpectedErrorEncountered(_)));
+    }
+}
+
+#[tokio::test]
+async fn timeouts_dont_get_peer_banned() {
+    ::diem_logger::Logger::init_for_testing();
+    let (mut mock_network, _mock_time, client, _poller) = MockNetwork::new();
+    let peer_infos = mock_network.peer_infos.clone();
+
+    let slow_peer = mock_network.add_connected_peer_on(NetworkId::Public);
+    client.update_summary(slow_peer, mock_storage_summary(200));
+    client.update_global_summary_cache();
+
+    // The peer never responds in time.
+    tokio::spawn(async move {
+        while let Some((_, _, _, res_tx)) = mock_network.next_raw_request().await {
+            let _ = res_tx.send(Err(RpcError::TimedOut));
+        }
+    });
+
+    // The peer is eventually ignored by the data client, but never banned.
+    loop {
+        let result = client
+            .get_transactions_with_proof(200, 200, 200, false)
+            .await;
+        match result {
+            Err(Error::TimeoutWaitingForResponse(_)) => {}
+            Err(Error::DataIsUnavailable(_)) => break,
+            _ => panic!("unexpected result: {:?}", result),
+        }
+    }
+    assert!(!peer_infos.reputation().is_banned(slow_peer));
+}
+
 #[tokio::test]
 async fn bad_peer_is_eventually_added_back() {
     ::diem_logger::Logger::init_for_testing();
@@ -60,9 +60,25 @@ impl StorageServiceClient {
         request: StorageServiceRequest,
         timeout: Duration,
     ) -> Result<StorageServiceResponse, Error> {
+        // Responses can be large, so ask for compression if the peer supports it.
+        let supports_compression = self.peer_metadata.read(recipient).map_or(false, |info| {
+            info.supports_protocol(ProtocolId::StorageServiceRpcCompressed)
+        });
+        let protocol = if supports_compression {
+            ProtocolId::StorageServiceRpcCompressed
+        } else {
+            ProtocolId::StorageServiceRpc
+        };
+
         let message = self
             .network_sender
-            .send_rpc(recipient, StorageServiceMessage::Request(request), timeout)
+            .sender(&recipient.network_id())
+            .send_rpc_with_protocol(
+                recipient.peer_id(),
+                protocol,
+                StorageServiceMessage::Request(request),
+                timeout,
+            )
             .await?;
         match message {
             StorageServiceMessage::Response(Ok(response)) => Ok(response),
@@ -99,7 +115,10 @@ pub type StorageServiceMultiSender =
     MultiNetworkSender<StorageServiceMessage, StorageServiceNetworkSender>;
 
 pub fn network_endpoint_config() -> AppConfig {
-    AppConfig::client([ProtocolId::StorageServiceRpc])
+    AppConfig::client([
+        ProtocolId::StorageServiceRpc,
+        ProtocolId::StorageServiceRpcCompressed,
+    ])
 }
 
 // TODO(philiphayes): this is a lot of boilerplate for what is effectively a
@@ -122,6 +141,21 @@ impl NewNetworkSender for StorageServiceNetworkSender {
     }
 }
 
+impl StorageServiceNetworkSender {
+    /// Sends a request over either `StorageServiceRpc`, or its compressed variant.
+    pub async fn send_rpc_with_protocol(
+        &self,
+        recipient: PeerId,
+        protocol: ProtocolId,
+        message: StorageServiceMessage,
+        timeout: Duration,
+    ) -> Result<StorageServiceMessage, RpcError> {
+        self.inner
+            .send_rpc(recipient, protocol, message, timeout)
+            .await
+    }
+}
+
 #[async_trait]
 impl ApplicationNetworkSender<StorageServiceMessage> for StorageServiceNetworkSender {
     fn send_to(
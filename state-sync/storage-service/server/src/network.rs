@@ -27,7 +27,10 @@ const INBOUND_CHANNEL_SIZE: usize = 100;
 
 pub fn network_endpoint_config() -> AppConfig {
     AppConfig::service(
-        [ProtocolId::StorageServiceRpc],
+        [
+            ProtocolId::StorageServiceRpc,
+            ProtocolId::StorageServiceRpcCompressed,
+        ],
         diem_channel::Config::new(INBOUND_CHANNEL_SIZE)
             .queue_style(QueueStyle::FIFO)
             .counters(&metrics::PENDING_STORAGE_SERVER_NETWORK_EVENTS),
@@ -64,7 +67,7 @@ impl StorageServiceNetworkEvents {
                 protocol_id,
                 response_tx,
             ) => {
-                let response_tx = ResponseSender::new(response_tx);
+                let response_tx = ResponseSender::new(protocol_id, response_tx);
                 Some((peer_id, protocol_id, request, response_tx))
             }
             // We don't use DirectSend and don't care about connection events.
@@ -84,19 +87,30 @@ impl Stream for StorageServiceNetworkEvents {
 /// A channel for fulfilling a pending StorageService RPC request.
 /// Provides a more strongly typed interface around the raw RPC response channel.
 pub struct ResponseSender {
+    /// The response is encoded for the protocol of the request, so it's only
+    /// compressed if the requester asked for compression.
+    protocol_id: ProtocolId,
     response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
 }
 
 impl ResponseSender {
-    pub fn new(response_tx: oneshot::Sender<Result<Bytes, RpcError>>) -> Self {
-        Self { response_tx }
+    pub fn new(
+        protocol_id: ProtocolId,
+        response_tx: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> Self {
+        Self {
+            protocol_id,
+            response_tx,
+        }
     }
 
     pub fn send(self, response: Result<StorageServiceResponse>) {
         let msg = StorageServiceMessage::Response(response);
-        let result = bcs::to_bytes(&msg)
+        let result = self
+            .protocol_id
+            .to_bytes(&msg)
             .map(Bytes::from)
-            .map_err(RpcError::BcsError);
+            .map_err(RpcError::Error);
         let _ = self.response_tx.send(result);
     }
 }
@@ -74,6 +74,25 @@ async fn test_get_server_protocol_version() {
     assert_eq!(response, expected_response);
 }
 
+#[tokio::test]
+async fn test_compressed_request() {
+    let (mut mock_client, service) = MockClient::new();
+    tokio::spawn(service.start());
+
+    // Process a request over the compressed protocol
+    let request = StorageServiceRequest::GetServerProtocolVersion;
+    let response = mock_client
+        .send_request_with_protocol(ProtocolId::StorageServiceRpcCompressed, request)
+        .await
+        .unwrap();
+
+    // Verify the response is correct (and was compressed, or it wouldn't decode)
+    let expected_response = StorageServiceResponse::ServerProtocolVersion(ServerProtocolVersion {
+        protocol_version: PROTOCOL_VERSION,
+    });
+    assert_eq!(response, expected_response);
+}
+
 #[tokio::test]
 async fn test_get_account_states_chunk_with_proof() {
     let (mut mock_client, service) = MockClient::new();
@@ -341,10 +360,18 @@ impl MockClient {
     async fn send_request(
         &mut self,
         request: StorageServiceRequest,
+    ) -> Result<StorageServiceResponse, StorageServiceError> {
+        self.send_request_with_protocol(ProtocolId::StorageServiceRpc, request)
+            .await
+    }
+
+    async fn send_request_with_protocol(
+        &mut self,
+        protocol_id: ProtocolId,
+        request: StorageServiceRequest,
     ) -> Result<StorageServiceResponse, StorageServiceError> {
         // craft the inbound Rpc notification
         let peer_id = PeerId::ZERO;
-        let protocol_id = ProtocolId::StorageServiceRpc;
         let data = protocol_id
             .to_bytes(&StorageServiceMessage::Request(request))
             .unwrap();
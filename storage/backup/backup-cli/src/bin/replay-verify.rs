@@ -57,6 +57,7 @@ async fn main_impl() -> Result<()> {
         opt.db_dir,
         false, /* read_only */
         None,  /* pruner */
+        None,  /* ledger_prune_window */
         opt.rocksdb_opt.into(),
         true, /* account_count_migration */
     )?)
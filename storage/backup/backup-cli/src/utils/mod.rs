@@ -171,6 +171,7 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
                 db_dir,
                 false, /* read_only */
                 None,  /* pruner */
+                None,  /* ledger_prune_window */
                 opt.rocksdb_opt.into(),
                 opt.account_count_migration,
             )?)
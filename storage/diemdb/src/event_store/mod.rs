@@ -194,6 +194,15 @@ impl EventStore {
             if path != *event_key || ver > ledger_version {
                 break;
             }
+            // A gap before the first record found means the older part of the stream has been
+            // pruned.
+            if result.is_empty() && seq > cur_seq {
+                return Err(DiemDbError::Pruned(format!(
+                    "Event {} of seq num {} (min available seq num is {})",
+                    event_key, cur_seq, seq,
+                ))
+                .into());
+            }
             ensure!(
                 seq == cur_seq,
                 "DB corrupt: Sequence number not continuous, expected: {}, actual: {}.",
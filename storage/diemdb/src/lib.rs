@@ -49,7 +49,7 @@ use crate::{
         DIEM_STORAGE_LEDGER_VERSION, DIEM_STORAGE_NEXT_BLOCK_EPOCH,
         DIEM_STORAGE_OTHER_TIMERS_SECONDS, DIEM_STORAGE_ROCKSDB_PROPERTIES,
     },
-    pruner::Pruner,
+    pruner::{LedgerPruner, Pruner, StatePruner},
     schema::*,
     state_store::StateStore,
     system_store::SystemStore,
@@ -58,8 +58,10 @@ use crate::{
 use anyhow::{ensure, format_err, Result};
 use diem_config::config::RocksdbConfig;
 use diem_crypto::hash::{CryptoHash, HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use diem_infallible::RwLockReadGuard;
 use diem_logger::prelude::*;
 use diem_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
     account_state::AccountState,
     account_state_blob::{
@@ -89,8 +91,8 @@ use diem_types::{
 };
 use itertools::{izip, zip_eq};
 use move_core_types::{
-    language_storage::{ModuleId, StructTag},
-    resolver::{ModuleResolver, ResourceResolver},
+    language_storage::{ModuleId, StructTag, TableHandle},
+    resolver::{ModuleResolver, ResourceResolver, TableResolver},
 };
 use once_cell::sync::Lazy;
 use schemadb::{ColumnFamilyName, Options, DB, DEFAULT_CF_NAME};
@@ -229,6 +231,8 @@ pub struct DiemDB {
     rocksdb_property_reporter: RocksdbPropertyReporter,
     pruner: Option<Pruner>,
     prune_window: Option<u64>,
+    ledger_pruner: Option<Pruner>,
+    ledger_prune_window: Option<u64>,
 }
 
 impl DiemDB {
@@ -252,7 +256,12 @@ impl DiemDB {
         ]
     }
 
-    fn new_with_db(db: DB, prune_window: Option<u64>, account_count_migration: bool) -> Self {
+    fn new_with_db(
+        db: DB,
+        prune_window: Option<u64>,
+        ledger_prune_window: Option<u64>,
+        account_count_migration: bool,
+    ) -> Self {
         let db = Arc::new(db);
 
         DiemDB {
@@ -263,8 +272,11 @@ impl DiemDB {
             transaction_store: Arc::new(TransactionStore::new(Arc::clone(&db))),
             system_store: SystemStore::new(Arc::clone(&db)),
             rocksdb_property_reporter: RocksdbPropertyReporter::new(Arc::clone(&db)),
-            pruner: prune_window.map(|n| Pruner::new(Arc::clone(&db), n)),
+            pruner: prune_window.map(|n| Pruner::new(StatePruner::new(Arc::clone(&db)), n)),
             prune_window,
+            ledger_pruner: ledger_prune_window
+                .map(|n| Pruner::new(LedgerPruner::new(Arc::clone(&db)), n)),
+            ledger_prune_window,
         }
     }
 
@@ -272,6 +284,7 @@ impl DiemDB {
         db_root_path: P,
         readonly: bool,
         prune_window: Option<u64>,
+        ledger_prune_window: Option<u64>,
         rocksdb_config: RocksdbConfig,
         account_count_migration: bool, // ignored when opening readonly
     ) -> Result<Self> {
@@ -279,6 +292,10 @@ impl DiemDB {
             prune_window.is_none() || !readonly,
             "Do not set prune_window when opening readonly.",
         );
+        ensure!(
+            ledger_prune_window.is_none() || !readonly,
+            "Do not set ledger_prune_window when opening readonly.",
+        );
 
         let path = db_root_path.as_ref().join("diemdb");
         let instant = Instant::now();
@@ -309,7 +326,12 @@ impl DiemDB {
             )
         };
 
-        let ret = Self::new_with_db(db, prune_window, account_count_migration);
+        let ret = Self::new_with_db(
+            db,
+            prune_window,
+            ledger_prune_window,
+            account_count_migration,
+        );
         info!(
             path = path,
             time_ms = %instant.elapsed().as_millis(),
@@ -338,6 +360,7 @@ impl DiemDB {
                 &rocksdb_opts,
             )?,
             None, // prune_window
+            None, // ledger_prune_window
             true, // account_count_migration
         ))
     }
@@ -349,12 +372,37 @@ impl DiemDB {
             db_root_path,
             false, /* readonly */
             None,  /* pruner */
+            None,  /* ledger_prune_window */
             RocksdbConfig::default(),
             true, /* account_count_migration */
         )
         .expect("Unable to open DiemDB")
     }
 
+    /// Returns an error if ledger data (transactions, events, write sets, etc.) at `version` has
+    /// been pruned by the ledger pruner. Otherwise the returned guard keeps the ledger pruner from
+    /// pruning the data until it's dropped, so it must be held while reading the data.
+    fn error_if_ledger_pruned(
+        &self,
+        data_type: &str,
+        version: Version,
+    ) -> Result<Option<RwLockReadGuard<'_, Version>>> {
+        let (pruner, prune_window) = match (&self.ledger_pruner, self.ledger_prune_window) {
+            (Some(pruner), Some(prune_window)) => (pruner, prune_window),
+            _ => return Ok(None),
+        };
+        let min_readable_version = pruner.least_readable_version();
+        if version >= *min_readable_version {
+            Ok(Some(min_readable_version))
+        } else {
+            Err(DiemDbError::Pruned(format!(
+                "{} at version {} (min readable version is {}, ledger prune window is {})",
+                data_type, version, *min_readable_version, prune_window,
+            ))
+            .into())
+        }
+    }
+
     /// This force the db to update rocksdb properties immediately.
     pub fn update_rocksdb_properties(&self) -> Result<()> {
         update_rocksdb_properties(&self.db)
@@ -428,6 +476,7 @@ impl DiemDB {
         ledger_version: Version,
         fetch_events: bool,
     ) -> Result<TransactionWithProof> {
+        let _pruner_guard = self.error_if_ledger_pruned("Transaction", version)?;
         let proof = self
             .ledger_store
             .get_transaction_info_with_proof(version, ledger_version)?;
@@ -649,6 +698,9 @@ impl DiemDB {
         if let Some(pruner) = self.pruner.as_ref() {
             pruner.wake(latest_version)
         }
+        if let Some(ledger_pruner) = self.ledger_pruner.as_ref() {
+            ledger_pruner.wake(latest_version)
+        }
     }
 }
 
@@ -771,6 +823,7 @@ impl DbReader<DpnProto> for DiemDB {
             if start_version > ledger_version || limit == 0 {
                 return Ok(TransactionListWithProof::new_empty());
             }
+            let _pruner_guard = self.error_if_ledger_pruned("Transaction", start_version)?;
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
@@ -807,14 +860,20 @@ impl DbReader<DpnProto> for DiemDB {
         })
     }
 
-    /// Get the first version that txn starts existent.
+    /// Get the first version that txn starts existent. When the ledger pruner is on this moves
+    /// forward as old transactions are pruned.
     fn get_first_txn_version(&self) -> Result<Option<Version>> {
-        self.transaction_store.get_first_txn_version()
+        gauged_api("get_first_txn_version", || {
+            self.transaction_store.get_first_txn_version()
+        })
     }
 
-    /// Get the first version that write set starts existent.
+    /// Get the first version that write set starts existent. When the ledger pruner is on this
+    /// moves forward as old write sets are pruned.
     fn get_first_write_set_version(&self) -> Result<Option<Version>> {
-        self.transaction_store.get_first_write_set_version()
+        gauged_api("get_first_write_set_version", || {
+            self.transaction_store.get_first_write_set_version()
+        })
     }
 
     /// Gets a batch of transactions for the purpose of synchronizing state to another node.
@@ -832,6 +891,7 @@ impl DbReader<DpnProto> for DiemDB {
             if start_version > ledger_version || limit == 0 {
                 return Ok(TransactionOutputListWithProof::new_empty());
             }
+            let _pruner_guard = self.error_if_ledger_pruned("Transaction output", start_version)?;
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
@@ -995,6 +1055,8 @@ impl DbReader<DpnProto> for DiemDB {
                 );
             }
 
+            let _pruner_guard = self.error_if_ledger_pruned("Transaction info", version)?;
+
             let txn_info_with_proof = self
                 .ledger_store
                 .get_transaction_info_with_proof(version, ledger_version)?;
@@ -1062,6 +1124,7 @@ impl DbReader<DpnProto> for DiemDB {
 
     fn get_block_timestamp(&self, version: u64) -> Result<u64> {
         gauged_api("get_block_timestamp", || {
+            let _pruner_guard = self.error_if_ledger_pruned("Block timestamp", version)?;
             let ts = match self.transaction_store.get_block_metadata(version)? {
                 Some((_v, block_meta)) => block_meta.into_inner().1,
                 // genesis timestamp is 0
@@ -1246,6 +1309,24 @@ impl ResourceResolver for DiemDB {
     }
 }
 
+impl TableResolver for DiemDB {
+    type Error = anyhow::Error;
+
+    fn get_table_item(&self, handle: &TableHandle, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let access_path = AccessPath::table_item_access_path(*handle, key.to_vec());
+        let (account_state_with_proof, _) = self.get_account_state_with_proof_by_version(
+            access_path.address,
+            self.get_latest_version()?,
+        )?;
+        if let Some(account_state_blob) = account_state_with_proof {
+            let account_state = AccountState::try_from(&account_state_blob)?;
+            Ok(account_state.get(&access_path.path).cloned())
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 impl MoveDbReader<DpnProto> for DiemDB {}
 
 impl DbWriter<DpnProto> for DiemDB {
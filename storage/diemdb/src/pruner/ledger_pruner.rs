@@ -0,0 +1,152 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides `LedgerPruner` which prunes transactions, transaction infos, write sets,
+//! events and their indices older than the ledger prune window.
+//!
+//! The transaction accumulator, ledger infos and the `EventByVersionSchema` index (which is how
+//! the latest sequence number of an event stream is found) are kept, so that proofs for the
+//! remaining versions can still be constructed.
+
+use crate::{
+    metrics::{
+        DIEM_STORAGE_LEDGER_PRUNE_WINDOW, DIEM_STORAGE_OTHER_TIMERS_SECONDS,
+        DIEM_STORAGE_PRUNER_LEAST_READABLE_LEDGER_VERSION,
+    },
+    pruner::DBPruner,
+    schema::{
+        event::EventSchema, event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema, transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema, transaction_info::TransactionInfoSchema,
+        write_set::WriteSetSchema,
+    },
+};
+use anyhow::Result;
+use diem_crypto::hash::CryptoHash;
+use diem_metrics::IntGauge;
+use diem_types::{
+    proof::position::Position,
+    transaction::{Transaction, Version},
+};
+use schemadb::{ReadOptions, SchemaBatch, DB};
+use std::sync::Arc;
+
+/// Prunes ledger data (everything keyed by or indexing into transaction versions) below the
+/// target least readable version.
+#[derive(Debug)]
+pub(crate) struct LedgerPruner {
+    db: Arc<DB>,
+}
+
+impl LedgerPruner {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db }
+    }
+}
+
+impl DBPruner for LedgerPruner {
+    fn name(&self) -> &'static str {
+        "ledger"
+    }
+
+    /// The first transaction left in the DB is the least readable version, since transactions are
+    /// deleted in the same batch as the rest of the ledger data of the same version.
+    fn initialize_least_readable_version(&self) -> Result<Version> {
+        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        Ok(iter.next().transpose()?.map_or(0, |(version, _)| version))
+    }
+
+    fn prune(
+        &mut self,
+        least_readable_version: Version,
+        target_least_readable_version: Version,
+        max_versions: usize,
+    ) -> Result<Version> {
+        prune_ledger(
+            &self.db,
+            least_readable_version,
+            target_least_readable_version,
+            max_versions,
+        )
+    }
+
+    fn prune_window_gauge(&self) -> &'static IntGauge {
+        &DIEM_STORAGE_LEDGER_PRUNE_WINDOW
+    }
+
+    fn least_readable_version_gauge(&self) -> &'static IntGauge {
+        &DIEM_STORAGE_PRUNER_LEAST_READABLE_LEDGER_VERSION
+    }
+}
+
+/// Deletes ledger data for versions in `[least_readable_version, new_least_readable_version)` in a
+/// single batch, where `new_least_readable_version` is at most `max_versions` versions ahead and
+/// never beyond `target_least_readable_version`. Returns `new_least_readable_version`.
+pub fn prune_ledger(
+    db: &DB,
+    least_readable_version: Version,
+    target_least_readable_version: Version,
+    max_versions: usize,
+) -> Result<Version> {
+    if least_readable_version >= target_least_readable_version {
+        return Ok(least_readable_version);
+    }
+    let new_least_readable_version = std::cmp::min(
+        target_least_readable_version,
+        least_readable_version.saturating_add(max_versions as u64),
+    );
+
+    let _timer = DIEM_STORAGE_OTHER_TIMERS_SECONDS
+        .with_label_values(&["ledger_pruner_commit"])
+        .start_timer();
+    let mut batch = SchemaBatch::new();
+
+    // Transactions and the indices pointing to them.
+    let mut iter = db.iter::<TransactionSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for res in iter {
+        let (version, transaction) = res?;
+        if version >= new_least_readable_version {
+            break;
+        }
+        if let Transaction::UserTransaction(txn) = &transaction {
+            batch.delete::<TransactionByAccountSchema>(&(txn.sender(), txn.sequence_number()))?;
+        }
+        batch.delete::<TransactionByHashSchema>(&transaction.hash())?;
+        batch.delete::<TransactionSchema>(&version)?;
+    }
+
+    // Transaction infos and write sets.
+    for version in least_readable_version..new_least_readable_version {
+        batch.delete::<TransactionInfoSchema>(&version)?;
+        batch.delete::<WriteSetSchema>(&version)?;
+    }
+
+    // Events and the event by key index.
+    let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+    iter.seek(&least_readable_version)?;
+    for res in iter {
+        let ((version, index), event) = res?;
+        if version >= new_least_readable_version {
+            break;
+        }
+        batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+        batch.delete::<EventSchema>(&(version, index))?;
+    }
+
+    // Per transaction event accumulators.
+    let mut iter = db.iter::<EventAccumulatorSchema>(ReadOptions::default())?;
+    iter.seek(&(least_readable_version, Position::from_inorder_index(0)))?;
+    for res in iter {
+        let ((version, position), _hash) = res?;
+        if version >= new_least_readable_version {
+            break;
+        }
+        batch.delete::<EventAccumulatorSchema>(&(version, position))?;
+    }
+
+    db.write_schemas(batch)?;
+    Ok(new_least_readable_version)
+}
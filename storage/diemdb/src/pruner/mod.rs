@@ -3,33 +3,60 @@
 
 //! This module provides `Pruner` which manages a thread pruning old data in the background and is
 //! meant to be triggered by other threads as they commit new data to the DB.
+//!
+//! What is pruned is decided by the `DBPruner` the `Pruner` is constructed with, currently
+//! `StatePruner` (stale Jellyfish Merkle nodes) or `LedgerPruner` (transactions, events, write
+//! sets and their indices).
+
+mod ledger_pruner;
+mod state_pruner;
+
+pub(crate) use ledger_pruner::LedgerPruner;
+pub use state_pruner::prune_state;
+pub(crate) use state_pruner::StatePruner;
 
-use crate::{
-    metrics::{
-        DIEM_STORAGE_OTHER_TIMERS_SECONDS, DIEM_STORAGE_PRUNER_LEAST_READABLE_STATE_VERSION,
-        DIEM_STORAGE_PRUNE_WINDOW,
-    },
-    schema::{
-        jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
-    },
-};
 use anyhow::Result;
-use diem_infallible::Mutex;
-use diem_jellyfish_merkle::StaleNodeIndex;
+use diem_infallible::{Mutex, RwLock, RwLockReadGuard};
 use diem_logger::prelude::*;
+use diem_metrics::IntGauge;
 use diem_types::transaction::Version;
-use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
+#[cfg(test)]
+use std::time::Instant;
 use std::{
-    iter::Peekable,
     sync::{
-        atomic::{AtomicU64, Ordering},
         mpsc::{channel, Receiver, Sender},
         Arc,
     },
     thread::{sleep, JoinHandle},
-    time::{Duration, Instant},
+    time::Duration,
 };
 
+/// Defines what a `Pruner` prunes. Implementations are driven by the pruner worker thread.
+pub(crate) trait DBPruner: Send + 'static {
+    /// Name of the pruner, used in the worker thread name and in logs.
+    fn name(&self) -> &'static str;
+
+    /// Figures out the least readable version from what is left in the DB. Called once when the
+    /// worker thread starts.
+    fn initialize_least_readable_version(&self) -> Result<Version>;
+
+    /// Prunes data of at most `max_versions` versions starting from `least_readable_version`,
+    /// without going beyond `target_least_readable_version`. Returns the new least readable
+    /// version.
+    fn prune(
+        &mut self,
+        least_readable_version: Version,
+        target_least_readable_version: Version,
+        max_versions: usize,
+    ) -> Result<Version>;
+
+    /// The gauge reporting the prune window.
+    fn prune_window_gauge(&self) -> &'static IntGauge;
+
+    /// The gauge reporting the pruning progress.
+    fn least_readable_version_gauge(&self) -> &'static IntGauge;
+}
+
 /// The `Pruner` is meant to be part of a `DiemDB` instance and runs in the background to prune old
 /// data.
 ///
@@ -45,24 +72,26 @@ pub(crate) struct Pruner {
     worker_thread: Option<JoinHandle<()>>,
     /// The sender side of the channel talking to the worker thread.
     command_sender: Mutex<Sender<Command>>,
-    /// (For tests) A way for the worker thread to inform the `Pruner` the pruning progress. If it
-    /// sets this atomic value to `V`, all versions before `V` can no longer be accessed.
-    #[allow(dead_code)]
-    worker_progress: Arc<AtomicU64>,
+    /// A way for the worker thread to inform the `Pruner` the pruning progress. If it sets this
+    /// value to `V`, all versions before `V` can no longer be accessed. The worker holds the write
+    /// lock while pruning, so versions from `V` on are kept while a read lock is held.
+    worker_progress: Arc<RwLock<Version>>,
 }
 
 impl Pruner {
     /// Creates a worker thread that waits on a channel for pruning commands.
-    pub fn new(db: Arc<DB>, historical_versions_to_keep: u64) -> Self {
+    pub fn new<P: DBPruner>(db_pruner: P, historical_versions_to_keep: u64) -> Self {
         let (command_sender, command_receiver) = channel();
 
-        let worker_progress = Arc::new(AtomicU64::new(0));
+        let worker_progress = Arc::new(RwLock::new(0));
         let worker_progress_clone = Arc::clone(&worker_progress);
 
-        DIEM_STORAGE_PRUNE_WINDOW.set(historical_versions_to_keep as i64);
+        db_pruner
+            .prune_window_gauge()
+            .set(historical_versions_to_keep as i64);
         let worker_thread = std::thread::Builder::new()
-            .name("diemdb_pruner".into())
-            .spawn(move || Worker::new(db, command_receiver, worker_progress_clone).work())
+            .name(format!("diemdb_{}_pruner", db_pruner.name()))
+            .spawn(move || Worker::new(db_pruner, command_receiver, worker_progress_clone).work())
             .expect("Creating pruner thread should succeed.");
 
         Self {
@@ -86,6 +115,12 @@ impl Pruner {
         }
     }
 
+    /// Versions smaller than the returned one have been pruned and can no longer be read. Versions
+    /// from it on are not pruned until the returned guard is dropped.
+    pub fn least_readable_version(&self) -> RwLockReadGuard<'_, Version> {
+        self.worker_progress.read()
+    }
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
     /// an internal counter.
     #[cfg(test)]
@@ -99,7 +134,7 @@ impl Pruner {
             let end = Instant::now() + TIMEOUT;
 
             while Instant::now() < end {
-                if self.worker_progress.load(Ordering::Relaxed) >= least_readable_version {
+                if *self.worker_progress.read() >= least_readable_version {
                     return Ok(());
                 }
                 sleep(Duration::from_millis(1));
@@ -129,37 +164,33 @@ enum Command {
     Prune { least_readable_version: Version },
 }
 
-struct Worker {
-    db: Arc<DB>,
+struct Worker<P> {
+    db_pruner: P,
     command_receiver: Receiver<Command>,
     target_least_readable_version: Version,
     /// Keeps a record of the pruning progress. If this equals to version `V`, we know versions
     /// smaller than `V` are no longer readable.
-    /// This being an atomic value is to communicate the info with the Pruner thread (for tests).
-    least_readable_version: Arc<AtomicU64>,
+    /// This being shared is to communicate the info with the Pruner thread.
+    least_readable_version: Arc<RwLock<Version>>,
     /// Indicates if there's NOT any pending work to do currently, to hint
     /// `Self::receive_commands()` to `recv()` blocking-ly.
     blocking_recv: bool,
-    index_min_nonpurged_version: Version,
-    index_purged_at: Instant,
 }
 
-impl Worker {
+impl<P: DBPruner> Worker<P> {
     const MAX_VERSIONS_TO_PRUNE_PER_BATCH: usize = 100;
 
     fn new(
-        db: Arc<DB>,
+        db_pruner: P,
         command_receiver: Receiver<Command>,
-        least_readable_version: Arc<AtomicU64>,
+        least_readable_version: Arc<RwLock<Version>>,
     ) -> Self {
         Self {
-            db,
+            db_pruner,
             command_receiver,
             least_readable_version,
             target_least_readable_version: 0,
             blocking_recv: true,
-            index_min_nonpurged_version: 0,
-            index_purged_at: Instant::now(),
         }
     }
 
@@ -169,32 +200,31 @@ impl Worker {
         while self.receive_commands() {
             // Process a reasonably small batch of work before trying to receive commands again,
             // in case `Command::Quit` is received (that's when we should quit.)
-            let least_readable_version = self.least_readable_version.load(Ordering::Relaxed);
-            match prune_state(
-                Arc::clone(&self.db),
+            // Readers hold the read lock from checking a version is readable until they are done
+            // reading it, so the write lock is held while pruning.
+            let progress = Arc::clone(&self.least_readable_version);
+            let mut progress = progress.write();
+            let least_readable_version = *progress;
+            match self.db_pruner.prune(
                 least_readable_version,
                 self.target_least_readable_version,
                 Self::MAX_VERSIONS_TO_PRUNE_PER_BATCH,
             ) {
                 Ok(new_least_readable_version) => {
+                    *progress = new_least_readable_version;
+                    drop(progress);
                     self.record_progress(new_least_readable_version);
 
-                    // Make next recv() blocking if nothing left to do.
-                    self.blocking_recv = new_least_readable_version == least_readable_version // did nothing
-                        || new_least_readable_version == self.target_least_readable_version; // did all
-
-                    // Try to purge the log.
-                    if let Err(e) = self.maybe_purge_index() {
-                        warn!(
-                            error = ?e,
-                            "Failed purging state node index, ignored.",
-                        );
-                    }
+                    // Make next recv() blocking if nothing left to do: either nothing was pruned
+                    // or the target has been reached.
+                    self.blocking_recv = new_least_readable_version == least_readable_version
+                        || new_least_readable_version == self.target_least_readable_version;
                 }
                 Err(e) => {
                     error!(
                         error = ?e,
-                        "Error pruning stale state nodes.",
+                        pruner = self.db_pruner.name(),
+                        "Error pruning.",
                     );
                     // On error, stop retrying vigorously by making next recv() blocking.
                     self.blocking_recv = true;
@@ -203,26 +233,29 @@ impl Worker {
         }
     }
 
-    /// Find out the first undeleted item in the stale node index.
+    /// Find out the least readable version from the DB.
     ///
     /// Seeking from the beginning (version 0) is potentially costly, we do it once upon worker
     /// thread start, record the progress and seek from that position afterwards.
     fn initialize(&mut self) {
         loop {
-            match self.get_least_readable_version() {
+            match self.db_pruner.initialize_least_readable_version() {
                 Ok(least_readable_version) => {
                     info!(
                         least_readable_version = least_readable_version,
-                        "[state pruner worker] initialized."
+                        pruner = self.db_pruner.name(),
+                        "[pruner worker] initialized."
                     );
                     self.target_least_readable_version = least_readable_version;
+                    *self.least_readable_version.write() = least_readable_version;
                     self.record_progress(least_readable_version);
                     return;
                 }
                 Err(e) => {
                     error!(
                         error = ?e,
-                        "[state pruner worker] Error on first seek. Retrying in 1 second.",
+                        pruner = self.db_pruner.name(),
+                        "[pruner worker] Error on first seek. Retrying in 1 second.",
                     );
                     sleep(Duration::from_secs(1));
                 }
@@ -230,24 +263,11 @@ impl Worker {
         }
     }
 
-    fn get_least_readable_version(&self) -> Result<Version> {
-        let mut iter = self
-            .db
-            .iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
-        iter.seek_to_first();
-        Ok(iter.next().transpose()?.map_or(0, |(index, _)| {
-            index
-                .stale_since_version
-                .checked_sub(1)
-                .expect("Nothing is stale since version 0.")
-        }))
-    }
-
     /// Log the progress.
     fn record_progress(&mut self, least_readable_version: Version) {
-        self.least_readable_version
-            .store(least_readable_version, Ordering::Relaxed);
-        DIEM_STORAGE_PRUNER_LEAST_READABLE_STATE_VERSION.set(least_readable_version as i64);
+        self.db_pruner
+            .least_readable_version_gauge()
+            .set(least_readable_version as i64);
     }
 
     /// Tries to receive all pending commands, blocking waits for the next command if no work needs
@@ -288,124 +308,6 @@ impl Worker {
             }
         }
     }
-
-    /// Purge the stale node index so that after restart not too much already pruned stuff is dealt
-    /// with again (although no harm is done deleting those then non-existent things.)
-    ///
-    /// We issue (range) deletes on the index only periodically instead of after every pruning batch
-    /// to avoid sending too many deletions to the DB, which takes disk space and slows it down.
-    fn maybe_purge_index(&mut self) -> Result<()> {
-        const MIN_INTERVAL: Duration = Duration::from_secs(60);
-        const MIN_VERSIONS: u64 = 60000;
-
-        // A deletion is issued at most once in one minute and when the pruner has progressed by at
-        // least 60000 versions (assuming the pruner deletes as slow as 1000 versions per second,
-        // this imposes at most one minute of work in vain after restarting.)
-        let now = Instant::now();
-        if now - self.index_purged_at > MIN_INTERVAL {
-            let least_readable_version = self.least_readable_version.load(Ordering::Relaxed);
-
-            if least_readable_version - self.index_min_nonpurged_version + 1 > MIN_VERSIONS {
-                let new_min_non_purged_version = least_readable_version + 1;
-                self.db.range_delete::<StaleNodeIndexSchema, Version>(
-                    &self.index_min_nonpurged_version,
-                    &new_min_non_purged_version, // end is exclusive
-                )?;
-                self.index_min_nonpurged_version = new_min_non_purged_version;
-                self.index_purged_at = now;
-            }
-        }
-
-        Ok(())
-    }
-}
-
-struct StaleNodeIndicesByVersionIterator<'a> {
-    inner: Peekable<SchemaIterator<'a, StaleNodeIndexSchema>>,
-    target_least_readable_version: Version,
-}
-
-impl<'a> StaleNodeIndicesByVersionIterator<'a> {
-    fn new(
-        db: &'a DB,
-        least_readable_version: Version,
-        target_least_readable_version: Version,
-    ) -> Result<Self> {
-        let mut iter = db.iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
-        iter.seek(&least_readable_version)?;
-
-        Ok(Self {
-            inner: iter.peekable(),
-            target_least_readable_version,
-        })
-    }
-
-    fn next_result(&mut self) -> Result<Option<Vec<StaleNodeIndex>>> {
-        match self.inner.next().transpose()? {
-            None => Ok(None),
-            Some((index, _)) => {
-                let version = index.stale_since_version;
-                if version > self.target_least_readable_version {
-                    return Ok(None);
-                }
-
-                let mut indices = vec![index];
-                while let Some(res) = self.inner.peek() {
-                    if let Ok((index_ref, _)) = res {
-                        if index_ref.stale_since_version != version {
-                            break;
-                        }
-                    }
-
-                    let (index, _) = self.inner.next().transpose()?.expect("Should be Some.");
-                    indices.push(index);
-                }
-
-                Ok(Some(indices))
-            }
-        }
-    }
-}
-
-impl<'a> Iterator for StaleNodeIndicesByVersionIterator<'a> {
-    type Item = Result<Vec<StaleNodeIndex>>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next_result().transpose()
-    }
-}
-
-pub fn prune_state(
-    db: Arc<DB>,
-    least_readable_version: Version,
-    target_least_readable_version: Version,
-    max_versions: usize,
-) -> Result<Version> {
-    let indices = StaleNodeIndicesByVersionIterator::new(
-        &db,
-        least_readable_version,
-        target_least_readable_version,
-    )?
-    .take(max_versions) // Iterator<Item = Result<Vec<StaleNodeIndex>>>
-    .collect::<Result<Vec<_>>>()? // now Vec<Vec<StaleNodeIndex>>
-    .into_iter()
-    .flatten()
-    .collect::<Vec<_>>();
-
-    if indices.is_empty() {
-        Ok(least_readable_version)
-    } else {
-        let _timer = DIEM_STORAGE_OTHER_TIMERS_SECONDS
-            .with_label_values(&["pruner_commit"])
-            .start_timer();
-        let new_least_readable_version = indices.last().expect("Should exist.").stale_since_version;
-        let mut batch = SchemaBatch::new();
-        indices
-            .into_iter()
-            .try_for_each(|index| batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key))?;
-        db.write_schemas(batch)?;
-        Ok(new_least_readable_version)
-    }
 }
 
 #[cfg(test)]
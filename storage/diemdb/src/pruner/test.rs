@@ -2,11 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::{change_set::ChangeSet, state_store::StateStore, DiemDB};
-use diem_crypto::HashValue;
+use crate::{
+    change_set::ChangeSet, errors::DiemDbError, state_store::StateStore,
+    test_helper::arb_blocks_to_commit, DiemDB,
+};
+use diem_config::config::RocksdbConfig;
+use diem_crypto::{hash::CryptoHash, HashValue};
 use diem_temppath::TempPath;
-use diem_types::{account_address::AccountAddress, account_state_blob::AccountStateBlob};
+use diem_types::{
+    account_address::AccountAddress,
+    account_state_blob::AccountStateBlob,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionToCommit, Version},
+};
+use proptest::prelude::*;
+use schemadb::DB;
 use std::collections::HashMap;
+use storage_interface::{DbReader, Order};
 
 fn put_account_state_set(
     db: &DB,
@@ -50,7 +62,10 @@ fn test_pruner() {
     let tmp_dir = TempPath::new();
     let db = DiemDB::new_for_test(&tmp_dir).db;
     let state_store = &StateStore::new(Arc::clone(&db), true /* account_count_migration */);
-    let pruner = Pruner::new(Arc::clone(&db), 0 /* historical_versions_to_keep */);
+    let pruner = Pruner::new(
+        StatePruner::new(Arc::clone(&db)),
+        0, /* historical_versions_to_keep */
+    );
 
     let _root0 = put_account_state_set(
         &db,
@@ -134,9 +149,9 @@ fn test_worker_quit_eagerly() {
     {
         let (command_sender, command_receiver) = channel();
         let worker = Worker::new(
-            Arc::clone(&db),
+            StatePruner::new(Arc::clone(&db)),
             command_receiver,
-            Arc::new(AtomicU64::new(0)), /* progress */
+            Arc::new(RwLock::new(0)), /* progress */
         );
         command_sender
             .send(Command::Prune {
@@ -156,3 +171,92 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, address, Some(&value2), 2);
     }
 }
+
+fn is_pruned_error(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<DiemDbError>(),
+        Some(DiemDbError::Pruned(_))
+    )
+}
+
+fn test_ledger_pruner_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    const LEDGER_PRUNE_WINDOW: u64 = 5;
+
+    let tmp_dir = TempPath::new();
+    let db = DiemDB::open(
+        &tmp_dir,
+        false, /* readonly */
+        None,  /* pruner */
+        Some(LEDGER_PRUNE_WINDOW),
+        RocksdbConfig::default(),
+        true, /* account_count_migration */
+    )
+    .unwrap();
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+            .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let latest_version = cur_ver - 1;
+    db.ledger_pruner
+        .as_ref()
+        .unwrap()
+        .wake_and_wait(latest_version)
+        .unwrap();
+    let least_readable_version = latest_version.saturating_sub(LEDGER_PRUNE_WINDOW);
+
+    assert_eq!(
+        db.get_first_txn_version().unwrap(),
+        Some(least_readable_version)
+    );
+    assert_eq!(
+        db.get_first_write_set_version().unwrap(),
+        Some(least_readable_version)
+    );
+
+    let all_txns_to_commit = input.iter().flat_map(|(txns, _)| txns);
+    for (version, txn_to_commit) in all_txns_to_commit.enumerate() {
+        let version = version as Version;
+        let txn_with_proof = db.get_transaction_by_version(version, latest_version, true);
+        if version < least_readable_version {
+            assert!(is_pruned_error(&txn_with_proof.unwrap_err()));
+            assert!(is_pruned_error(
+                &db.get_transactions(version, 1, latest_version, false)
+                    .unwrap_err()
+            ));
+            assert!(is_pruned_error(
+                &db.get_transaction_outputs(version, 1, latest_version)
+                    .unwrap_err()
+            ));
+            assert!(db
+                .get_transaction_by_hash(txn_to_commit.transaction().hash(), latest_version, false)
+                .unwrap()
+                .is_none());
+            for event in txn_to_commit.events() {
+                // Either the whole stream is gone or the older part of it is reported as pruned.
+                match db.get_events(event.key(), event.sequence_number(), Order::Ascending, 1) {
+                    Ok(events) => assert!(events.is_empty()),
+                    Err(err) => assert!(is_pruned_error(&err)),
+                }
+            }
+        } else {
+            let txn_with_proof = txn_with_proof.unwrap();
+            assert_eq!(&txn_with_proof.transaction, txn_to_commit.transaction());
+            assert_eq!(
+                txn_with_proof.events.as_deref(),
+                Some(txn_to_commit.events())
+            );
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_ledger_pruner(input in arb_blocks_to_commit()) {
+        test_ledger_pruner_impl(input);
+    }
+}
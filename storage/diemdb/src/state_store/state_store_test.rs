@@ -406,6 +406,7 @@ proptest! {
                 &tgt_tmp_dir,
                 false, /* readonly */
                 None,  /* pruner */
+                None, /* ledger_prune_window */
                 RocksdbConfig::default(),
                 true, /* account_count_migration */
             ).unwrap();
@@ -496,6 +497,7 @@ proptest! {
                 &tmp_dir,
                 false, /* read_only */
                 None,
+                None, /* ledger_prune_window */
                 RocksdbConfig::default(),
                 false, /* account_count_migration */
             ).unwrap();
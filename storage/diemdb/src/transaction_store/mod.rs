@@ -266,6 +266,16 @@ impl<'a> AccountTransactionVersionIter<'a> {
                     return Ok(None);
                 }
 
+                // A gap before the first transaction found means the older transactions have
+                // been pruned.
+                if self.prev_version.is_none() && seq_num > self.expected_next_seq_num {
+                    return Err(DiemDbError::Pruned(format!(
+                        "Transaction of account {} with seq num {} (min available seq num is {})",
+                        address, self.expected_next_seq_num, seq_num,
+                    ))
+                    .into());
+                }
+
                 // Ensure seq_num_{i+1} == seq_num_{i} + 1
                 ensure!(
                     seq_num == self.expected_next_seq_num,
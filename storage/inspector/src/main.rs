@@ -171,6 +171,7 @@ fn main() {
         p,
         true, /* readonly */
         None, /* pruner */
+        None, /* ledger_prune_window */
         RocksdbConfig::default(),
         true, /* account_count_migration, ignored anyway */
     )
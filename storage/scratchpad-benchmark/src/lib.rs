@@ -36,6 +36,7 @@ pub fn run_benchmark(num_updates: usize, max_accounts: u64, blob_size: usize, db
         &db_dir,
         false, /* readonly */
         None,  /* pruner */
+        None,  /* ledger_prune_window */
         RocksdbConfig::default(),
         true, /* account_count_migration, ignored anyway */
     )
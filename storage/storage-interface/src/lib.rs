@@ -26,7 +26,7 @@ use diem_types::{
     },
 };
 use itertools::Itertools;
-use move_core_types::resolver::{ModuleResolver, ResourceResolver};
+use move_core_types::resolver::{ModuleResolver, ResourceResolver, TableResolver};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
@@ -607,7 +607,10 @@ pub trait DbWriter<PS: ProtocolSpec>: Send + Sync {
 }
 
 pub trait MoveDbReader<PS: ProtocolSpec>:
-    DbReader<PS> + ResourceResolver<Error = anyhow::Error> + ModuleResolver<Error = anyhow::Error>
+    DbReader<PS>
+    + ResourceResolver<Error = anyhow::Error>
+    + ModuleResolver<Error = anyhow::Error>
+    + TableResolver<Error = anyhow::Error>
 {
 }
 
@@ -37,12 +37,16 @@
 
 use crate::account_address::AccountAddress;
 use diem_crypto::hash::HashValue;
-use move_core_types::language_storage::{ModuleId, ResourceKey, StructTag, CODE_TAG, RESOURCE_TAG};
+use move_core_types::language_storage::{
+    ModuleId, ResourceKey, StructTag, TableHandle, CODE_TAG, RESOURCE_TAG, TABLE_ITEM_TAG,
+};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, fmt};
 
+const TABLE_ITEM_SALT: &[u8] = b"MOVE::TableItem";
+
 #[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Ord, PartialOrd)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
 pub struct AccessPath {
@@ -55,6 +59,11 @@ pub struct AccessPath {
 pub enum Path {
     Code(ModuleId),
     Resource(StructTag),
+    TableItem {
+        handle: TableHandle,
+        #[serde(with = "serde_bytes")]
+        key: Vec<u8>,
+    },
 }
 
 impl AccessPath {
@@ -86,7 +95,22 @@ impl AccessPath {
         AccessPath { address, path }
     }
 
-    /// Extract the structured resource or module `Path` from `self`
+    /// Items of tables are stored under their own address, derived from the handle of the table
+    /// and the key of the item, so that each item is a separate entry of the state tree.
+    pub fn table_item_access_path(handle: TableHandle, key: Vec<u8>) -> AccessPath {
+        let mut bytes = TABLE_ITEM_SALT.to_vec();
+        bytes.extend(handle.0.as_ref());
+        bytes.extend(&key);
+        let hash = HashValue::sha3_256_of(&bytes).to_vec();
+        let address =
+            AccountAddress::from_bytes(&hash[HashValue::LENGTH - AccountAddress::LENGTH..])
+                .expect("Unexpected address length");
+        let path = bcs::to_bytes(&Path::TableItem { handle, key })
+            .expect("Unexpected serialization error");
+        AccessPath { address, path }
+    }
+
+    /// Extract the structured resource, module or table item `Path` from `self`
     pub fn get_path(&self) -> Path {
         bcs::from_bytes::<Path>(&self.path).expect("Unexpected serialization error")
     }
@@ -96,7 +120,7 @@ impl AccessPath {
     pub fn get_struct_tag(&self) -> Option<StructTag> {
         match self.get_path() {
             Path::Resource(s) => Some(s),
-            Path::Code(_) => None,
+            Path::Code(_) | Path::TableItem { .. } => None,
         }
     }
 }
@@ -114,7 +138,18 @@ impl fmt::Debug for AccessPath {
 
 impl fmt::Display for AccessPath {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.path.len() < 1 + HashValue::LENGTH {
+        if self.path.first() == Some(&TABLE_ITEM_TAG) {
+            match self.get_path() {
+                Path::TableItem { handle, key } => write!(
+                    f,
+                    "AccessPath {{ address: {:x}, type: TableItem, handle: {}, key: {} }}",
+                    self.address,
+                    handle,
+                    hex::encode(&key)
+                ),
+                _ => write!(f, "{:?}", self),
+            }
+        } else if self.path.len() < 1 + HashValue::LENGTH {
             write!(f, "{:?}", self)
         } else {
             write!(f, "AccessPath {{ address: {:x}, ", self.address)?;
@@ -244,7 +244,7 @@ impl AccountState {
         self.0.iter().filter_map(
             |(k, v)| match Path::try_from(k).expect("Invalid access path") {
                 Path::Code(_) => Some(v),
-                Path::Resource(_) => None,
+                Path::Resource(_) | Path::TableItem { .. } => None,
             },
         )
     }
@@ -254,7 +254,7 @@ impl AccountState {
         self.0.into_iter().filter_map(|(k, v)| {
             match Path::try_from(&k).expect("Invalid access path") {
                 Path::Code(_) => Some(v),
-                Path::Resource(_) => None,
+                Path::Resource(_) | Path::TableItem { .. } => None,
             }
         })
     }
@@ -266,7 +266,7 @@ impl AccountState {
     pub fn get_resources(&self) -> impl Iterator<Item = (StructTag, &[u8])> {
         self.0.iter().filter_map(|(k, v)| match Path::try_from(k) {
             Ok(Path::Resource(struct_tag)) => Some((struct_tag, v.as_ref())),
-            Ok(Path::Code(_)) | Err(_) => None,
+            Ok(Path::Code(_)) | Ok(Path::TableItem { .. }) | Err(_) => None,
         })
     }
 
@@ -63,6 +63,25 @@ pub enum MempoolStatusCode {
     // transaction didn't pass vm_validation
     VmError = 5,
     UnknownStatus = 6,
+    // Transaction was accepted by Mempool, replacing the pending transaction with the same sender
+    // and sequence number
+    Replaced = 7,
+    // Transaction was accepted by Mempool, evicting a lower fee transaction to make room for it
+    AcceptedWithEviction = 8,
+    // Replacement of a pending transaction with a gas price not increased enough
+    ReplacementGasPriceTooLow = 9,
+}
+
+impl MempoolStatusCode {
+    /// Whether the transaction has been inserted into Mempool.
+    pub fn is_accepted(self) -> bool {
+        matches!(
+            self,
+            MempoolStatusCode::Accepted
+                | MempoolStatusCode::Replaced
+                | MempoolStatusCode::AcceptedWithEviction
+        )
+    }
 }
 
 impl TryFrom<u64> for MempoolStatusCode {
@@ -77,6 +96,9 @@ impl TryFrom<u64> for MempoolStatusCode {
             4 => Ok(MempoolStatusCode::InvalidUpdate),
             5 => Ok(MempoolStatusCode::VmError),
             6 => Ok(MempoolStatusCode::UnknownStatus),
+            7 => Ok(MempoolStatusCode::Replaced),
+            8 => Ok(MempoolStatusCode::AcceptedWithEviction),
+            9 => Ok(MempoolStatusCode::ReplacementGasPriceTooLow),
             _ => Err("invalid StatusCode"),
         }
     }
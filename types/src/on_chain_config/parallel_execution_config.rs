@@ -9,7 +9,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 /// Defines the operation status of parallel execution. If this `read_write_analysis_result` is not
-/// None VM will execute transactions in parallel.
+/// None VM will execute transactions in parallel, using the dependencies it describes between
+/// transactions.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct ParallelExecutionConfig {
     pub read_write_analysis_result: Option<ReadWriteSetAnalysis>,
@@ -33,13 +34,19 @@ impl ParallelExecutionConfigInner {
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum ReadWriteSetAnalysis {
+    /// Read and write sets of the script functions, inferred statically. Blocks with transactions
+    /// not covered by the analysis are executed sequentially.
     V1(BTreeMap<ModuleId, BTreeMap<Identifier, ReadWriteSet>>),
+    /// No static analysis: the reads and writes of transactions are discovered while executing
+    /// them, and transactions are re-executed when they conflict with lower transactions.
+    Dynamic,
 }
 
 impl ReadWriteSetAnalysis {
-    pub fn into_inner(self) -> BTreeMap<ModuleId, BTreeMap<Identifier, ReadWriteSet>> {
+    pub fn into_inner(self) -> Option<BTreeMap<ModuleId, BTreeMap<Identifier, ReadWriteSet>>> {
         match self {
-            Self::V1(inner) => inner,
+            Self::V1(inner) => Some(inner),
+            Self::Dynamic => None,
         }
     }
 }
@@ -509,6 +509,12 @@ pub struct SignedTransaction {
 pub struct SignatureCheckedTransaction(SignedTransaction);
 
 impl SignatureCheckedTransaction {
+    /// Wraps the transaction without checking its signature. Only for simulating a transaction,
+    /// whose output is never committed.
+    pub fn new_unchecked_for_simulation(txn: SignedTransaction) -> Self {
+        Self(txn)
+    }
+
     /// Returns the `SignedTransaction` within.
     pub fn into_inner(self) -> SignedTransaction {
         self.0